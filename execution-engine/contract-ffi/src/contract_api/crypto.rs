@@ -0,0 +1,203 @@
+//! Functions for hashing data and verifying cryptographic signatures.
+
+use core::convert::TryFrom;
+
+use crate::{
+    contract_api::{
+        error::{self, Error},
+        runtime,
+    },
+    ext_ffi,
+};
+
+/// Length in bytes of the digests returned by [`hash`].
+pub const HASH_LENGTH: usize = 32;
+
+/// Length in bytes of an Ed25519 public key.
+pub const ED25519_PUBLIC_KEY_LENGTH: usize = 32;
+
+/// Length in bytes of an Ed25519 signature.
+pub const ED25519_SIGNATURE_LENGTH: usize = 64;
+
+/// Length in bytes of a compressed secp256k1 public key.
+pub const SECP256K1_COMPRESSED_PUBLIC_KEY_LENGTH: usize = 33;
+
+/// Length in bytes of an uncompressed secp256k1 public key.
+pub const SECP256K1_UNCOMPRESSED_PUBLIC_KEY_LENGTH: usize = 65;
+
+/// Length in bytes of a compact secp256k1 signature.
+pub const SECP256K1_SIGNATURE_LENGTH: usize = 64;
+
+/// Hash algorithms supported by [`hash`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum HashAlgorithm {
+    /// Blake2b with a 256-bit digest.
+    Blake2b,
+    /// SHA2-256.
+    Sha256,
+    /// Keccak-256, as used by Ethereum.
+    Keccak256,
+}
+
+impl Into<u32> for HashAlgorithm {
+    fn into(self) -> u32 {
+        match self {
+            HashAlgorithm::Blake2b => 0,
+            HashAlgorithm::Sha256 => 1,
+            HashAlgorithm::Keccak256 => 2,
+        }
+    }
+}
+
+impl TryFrom<u32> for HashAlgorithm {
+    type Error = Error;
+
+    fn try_from(value: u32) -> Result<HashAlgorithm, Self::Error> {
+        match value {
+            0 => Ok(HashAlgorithm::Blake2b),
+            1 => Ok(HashAlgorithm::Sha256),
+            2 => Ok(HashAlgorithm::Keccak256),
+            _ => Err(Error::InvalidHashAlgorithm),
+        }
+    }
+}
+
+/// Signature schemes supported by [`verify_signature`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SignatureScheme {
+    /// Ed25519 signatures over the raw message.
+    Ed25519,
+    /// ECDSA signatures on the secp256k1 curve over the SHA-256 digest of the message.
+    Secp256k1,
+}
+
+impl Into<u32> for SignatureScheme {
+    fn into(self) -> u32 {
+        match self {
+            SignatureScheme::Ed25519 => 0,
+            SignatureScheme::Secp256k1 => 1,
+        }
+    }
+}
+
+impl TryFrom<u32> for SignatureScheme {
+    type Error = Error;
+
+    fn try_from(value: u32) -> Result<SignatureScheme, Self::Error> {
+        match value {
+            0 => Ok(SignatureScheme::Ed25519),
+            1 => Ok(SignatureScheme::Secp256k1),
+            _ => Err(Error::InvalidSignatureScheme),
+        }
+    }
+}
+
+/// Returns the digest of `input` computed by the host using the given `algorithm`.
+///
+/// Hashing on the host is charged per byte of `input`, which is considerably cheaper than hashing
+/// within the contract.
+pub fn hash(algorithm: HashAlgorithm, input: &[u8]) -> [u8; HASH_LENGTH] {
+    let mut digest = [0u8; HASH_LENGTH];
+    let result = unsafe {
+        ext_ffi::hash(
+            algorithm.into(),
+            input.as_ptr(),
+            input.len(),
+            digest.as_mut_ptr(),
+            digest.len(),
+        )
+    };
+    match error::result_from(result) {
+        Ok(()) => digest,
+        Err(error) => runtime::revert(error),
+    }
+}
+
+/// Returns the Blake2b-256 digest of `input`.
+pub fn blake2b(input: &[u8]) -> [u8; HASH_LENGTH] {
+    hash(HashAlgorithm::Blake2b, input)
+}
+
+/// Returns the SHA2-256 digest of `input`.
+pub fn sha256(input: &[u8]) -> [u8; HASH_LENGTH] {
+    hash(HashAlgorithm::Sha256, input)
+}
+
+/// Returns the Keccak-256 digest of `input`.
+pub fn keccak256(input: &[u8]) -> [u8; HASH_LENGTH] {
+    hash(HashAlgorithm::Keccak256, input)
+}
+
+/// Returns `true` if `signature` is a valid signature of `message` by `public_key` under the given
+/// `scheme`.
+///
+/// Malformed public keys and signatures are treated as invalid signatures.
+pub fn verify_signature(
+    scheme: SignatureScheme,
+    public_key: &[u8],
+    message: &[u8],
+    signature: &[u8],
+) -> bool {
+    let result = unsafe {
+        ext_ffi::verify_signature(
+            scheme.into(),
+            public_key.as_ptr(),
+            public_key.len(),
+            message.as_ptr(),
+            message.len(),
+            signature.as_ptr(),
+            signature.len(),
+        )
+    };
+    match error::result_from(result) {
+        Ok(()) => true,
+        Err(Error::InvalidSignature) => false,
+        Err(error) => runtime::revert(error),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hash_algorithm_round_trip() {
+        for algorithm in &[
+            HashAlgorithm::Blake2b,
+            HashAlgorithm::Sha256,
+            HashAlgorithm::Keccak256,
+        ] {
+            let index: u32 = (*algorithm).into();
+            assert_eq!(HashAlgorithm::try_from(index), Ok(*algorithm));
+        }
+    }
+
+    #[test]
+    fn create_unknown_hash_algorithm_variant() {
+        assert_eq!(HashAlgorithm::try_from(3), Err(Error::InvalidHashAlgorithm));
+        assert_eq!(
+            HashAlgorithm::try_from(u32::max_value()),
+            Err(Error::InvalidHashAlgorithm)
+        );
+    }
+
+    #[test]
+    fn signature_scheme_round_trip() {
+        for scheme in &[SignatureScheme::Ed25519, SignatureScheme::Secp256k1] {
+            let index: u32 = (*scheme).into();
+            assert_eq!(SignatureScheme::try_from(index), Ok(*scheme));
+        }
+    }
+
+    #[test]
+    fn create_unknown_signature_scheme_variant() {
+        assert_eq!(
+            SignatureScheme::try_from(2),
+            Err(Error::InvalidSignatureScheme)
+        );
+        assert_eq!(
+            SignatureScheme::try_from(u32::max_value()),
+            Err(Error::InvalidSignatureScheme)
+        );
+    }
+}
@@ -128,6 +128,12 @@ pub enum Error {
     HostBufferEmpty,
     /// Data in the host buffer is full and should be consumed first by read operation
     HostBufferFull,
+    /// The given signature scheme is not supported by the host.
+    InvalidSignatureScheme,
+    /// Signature verification failed.
+    InvalidSignature,
+    /// The given hash algorithm is not supported by the host.
+    InvalidHashAlgorithm,
     /// Error specific to Mint contract.
     Mint(u8),
     /// Error specific to Proof of Stake contract.
@@ -258,6 +264,9 @@ impl From<Error> for u32 {
             Error::BufferTooSmall => 35,
             Error::HostBufferEmpty => 36,
             Error::HostBufferFull => 37,
+            Error::InvalidSignatureScheme => 38,
+            Error::InvalidSignature => 39,
+            Error::InvalidHashAlgorithm => 40,
             Error::Mint(value) => MINT_ERROR_OFFSET + u32::from(value),
             Error::ProofOfStake(value) => POS_ERROR_OFFSET + u32::from(value),
             Error::User(value) => RESERVED_ERROR_MAX + 1 + u32::from(value),
@@ -307,6 +316,9 @@ impl Debug for Error {
             Error::BufferTooSmall => write!(f, "Error::BufferTooSmall")?,
             Error::HostBufferEmpty => write!(f, "Error::HostBufferEmpty")?,
             Error::HostBufferFull => write!(f, "Error::HostBufferFull")?,
+            Error::InvalidSignatureScheme => write!(f, "Error::InvalidSignatureScheme")?,
+            Error::InvalidSignature => write!(f, "Error::InvalidSignature")?,
+            Error::InvalidHashAlgorithm => write!(f, "Error::InvalidHashAlgorithm")?,
             Error::Mint(value) => write!(f, "Error::Mint({})", value)?,
             Error::ProofOfStake(value) => write!(f, "Error::ProofOfStake({})", value)?,
             Error::User(value) => write!(f, "Error::User({})", value)?,
@@ -362,6 +374,9 @@ pub fn result_from(value: i32) -> Result<(), Error> {
         35 => Err(Error::BufferTooSmall),
         36 => Err(Error::HostBufferEmpty),
         37 => Err(Error::HostBufferFull),
+        38 => Err(Error::InvalidSignatureScheme),
+        39 => Err(Error::InvalidSignature),
+        40 => Err(Error::InvalidHashAlgorithm),
         _ => {
             if value > RESERVED_ERROR_MAX as i32 && value <= (2 * RESERVED_ERROR_MAX + 1) as i32 {
                 Err(Error::User(value as u16))
@@ -463,6 +478,9 @@ mod tests {
         round_trip(Err(Error::BufferTooSmall));
         round_trip(Err(Error::HostBufferEmpty));
         round_trip(Err(Error::HostBufferFull));
+        round_trip(Err(Error::InvalidSignatureScheme));
+        round_trip(Err(Error::InvalidSignature));
+        round_trip(Err(Error::InvalidHashAlgorithm));
         round_trip(Err(Error::Mint(0)));
         round_trip(Err(Error::Mint(u8::MAX)));
         round_trip(Err(Error::ProofOfStake(0)));
@@ -2,6 +2,7 @@
 
 pub mod account;
 mod contract_ref;
+pub mod crypto;
 mod error;
 pub mod runtime;
 pub mod storage;
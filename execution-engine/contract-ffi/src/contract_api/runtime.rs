@@ -230,6 +230,16 @@ pub fn is_valid_uref(uref: URef) -> bool {
     result != 0
 }
 
+/// Emits an event with the given `topic` and `value`.
+///
+/// Events are returned to the node as part of the deploy's execution result, in the order they
+/// were emitted.  Events emitted by session code which fails are discarded.
+pub fn emit_event(topic: &str, value: CLValue) {
+    let (topic_ptr, topic_size, _bytes) = contract_api::to_ptr(topic);
+    let (value_ptr, value_size, _bytes2) = contract_api::to_ptr(value);
+    unsafe { ext_ffi::emit_event(topic_ptr, topic_size, value_ptr, value_size) }
+}
+
 fn read_host_buffer_into(dest: &mut [u8]) -> Result<usize, Error> {
     let mut bytes_written = MaybeUninit::uninit();
     let ret = unsafe {
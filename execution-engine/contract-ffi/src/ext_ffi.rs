@@ -100,4 +100,26 @@ extern "C" {
     ) -> i32;
     pub fn get_main_purse(dest_ptr: *mut u8);
     pub fn read_host_buffer(dest_ptr: *mut u8, dest_size: usize, bytes_written: *mut usize) -> i32;
+    pub fn emit_event(
+        topic_ptr: *const u8,
+        topic_size: usize,
+        value_ptr: *const u8,
+        value_size: usize,
+    );
+    pub fn verify_signature(
+        scheme: u32,
+        public_key_ptr: *const u8,
+        public_key_size: usize,
+        message_ptr: *const u8,
+        message_size: usize,
+        signature_ptr: *const u8,
+        signature_size: usize,
+    ) -> i32;
+    pub fn hash(
+        algorithm: u32,
+        input_ptr: *const u8,
+        input_size: usize,
+        dest_ptr: *mut u8,
+        dest_size: usize,
+    ) -> i32;
 }
@@ -13,6 +13,7 @@ use proptest::{
 use crate::{
     execution::Phase,
     key::*,
+    system_contracts::pos,
     uref::{AccessRights, URef},
     value::{
         account::{PublicKey, Weight},
@@ -161,3 +162,29 @@ pub fn cl_value_arb() -> impl Strategy<Value = CLValue> {
 pub fn result_arb() -> impl Strategy<Value = Result<u32, u32>> {
     result::maybe_ok(any::<u32>(), any::<u32>())
 }
+
+pub fn pos_config_arb() -> impl Strategy<Value = pos::Config> {
+    (
+        (any::<u64>(), any::<u64>(), any::<u64>(), any::<u64>()),
+        (u512_arb(), u512_arb(), u512_arb()),
+        (any::<u64>(), any::<u64>(), any::<u64>()),
+    )
+        .prop_map(
+            |(
+                (bond_delay, unbond_delay, max_bond_len, max_unbond_len),
+                (max_spread, max_increase, max_decrease),
+                (max_rel_increase, max_rel_decrease, delegation_commission),
+            )| pos::Config {
+                bond_delay,
+                unbond_delay,
+                max_bond_len,
+                max_unbond_len,
+                max_spread,
+                max_increase,
+                max_decrease,
+                max_rel_increase,
+                max_rel_decrease,
+                delegation_commission,
+            },
+        )
+}
@@ -0,0 +1,194 @@
+use alloc::vec::Vec;
+
+use crate::{
+    bytesrepr::{self, FromBytes, ToBytes, U512_SERIALIZED_LENGTH, U64_SERIALIZED_LENGTH},
+    value::{CLType, CLTyped, U512},
+};
+
+const CONFIG_SERIALIZED_LENGTH: usize = 7 * U64_SERIALIZED_LENGTH + 3 * U512_SERIALIZED_LENGTH;
+
+/// The parameters of the proof of stake contract.
+///
+/// They are passed to the contract's installer at genesis and stored in the contract's named
+/// keys, and can be changed by a protocol upgrade without replacing the contract.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Config {
+    /// The time from a bonding request until the bond becomes effective and part of the stake.
+    pub bond_delay: u64,
+    /// The time from an unbonding request until the stakes are paid out.
+    pub unbond_delay: u64,
+    /// The maximum number of pending bonding requests.
+    pub max_bond_len: u64,
+    /// The maximum number of pending unbonding requests.
+    pub max_unbond_len: u64,
+    /// The maximum difference between the largest and the smallest stakes.
+    pub max_spread: U512,
+    /// The maximum increase of stakes in a single bonding request.
+    pub max_increase: U512,
+    /// The maximum decrease of stakes in a single unbonding request.
+    pub max_decrease: U512,
+    /// The maximum increase of stakes in millionths of the total stakes in a single bonding
+    /// request.
+    pub max_rel_increase: u64,
+    /// The maximum decrease of stakes in millionths of the total stakes in a single unbonding
+    /// request.
+    pub max_rel_decrease: u64,
+    /// The share of the rewards earned with delegated stakes which the validator keeps as
+    /// commission, in millionths.
+    pub delegation_commission: u64,
+}
+
+impl Config {
+    /// Returns the name of the first limit which is zero or out of range, if any.
+    ///
+    /// A config which omits the limits decodes with every limit at zero, which would make every
+    /// bonding and unbonding request fail, so such configs must be rejected.
+    pub fn invalid_limit(&self) -> Option<&'static str> {
+        if self.max_bond_len == 0 {
+            return Some("max_bond_len");
+        }
+        if self.max_unbond_len == 0 {
+            return Some("max_unbond_len");
+        }
+        if self.max_spread.is_zero() {
+            return Some("max_spread");
+        }
+        if self.max_increase.is_zero() {
+            return Some("max_increase");
+        }
+        if self.max_decrease.is_zero() {
+            return Some("max_decrease");
+        }
+        if self.max_rel_increase == 0 {
+            return Some("max_rel_increase");
+        }
+        if self.max_rel_decrease == 0 || self.max_rel_decrease > 1_000_000 {
+            return Some("max_rel_decrease");
+        }
+        if self.delegation_commission > 1_000_000 {
+            return Some("delegation_commission");
+        }
+        None
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            bond_delay: 0,
+            unbond_delay: 0,
+            max_bond_len: 100,
+            max_unbond_len: 1000,
+            max_spread: U512::MAX,
+            max_increase: U512::MAX,
+            max_decrease: U512::MAX,
+            max_rel_increase: 1_000_000_000,
+            max_rel_decrease: 900_000,
+            delegation_commission: 100_000,
+        }
+    }
+}
+
+impl ToBytes for Config {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut ret: Vec<u8> = Vec::with_capacity(CONFIG_SERIALIZED_LENGTH);
+        ret.append(&mut self.bond_delay.to_bytes()?);
+        ret.append(&mut self.unbond_delay.to_bytes()?);
+        ret.append(&mut self.max_bond_len.to_bytes()?);
+        ret.append(&mut self.max_unbond_len.to_bytes()?);
+        ret.append(&mut self.max_spread.to_bytes()?);
+        ret.append(&mut self.max_increase.to_bytes()?);
+        ret.append(&mut self.max_decrease.to_bytes()?);
+        ret.append(&mut self.max_rel_increase.to_bytes()?);
+        ret.append(&mut self.max_rel_decrease.to_bytes()?);
+        ret.append(&mut self.delegation_commission.to_bytes()?);
+        Ok(ret)
+    }
+}
+
+impl FromBytes for Config {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (bond_delay, rem): (u64, &[u8]) = FromBytes::from_bytes(bytes)?;
+        let (unbond_delay, rem): (u64, &[u8]) = FromBytes::from_bytes(rem)?;
+        let (max_bond_len, rem): (u64, &[u8]) = FromBytes::from_bytes(rem)?;
+        let (max_unbond_len, rem): (u64, &[u8]) = FromBytes::from_bytes(rem)?;
+        let (max_spread, rem): (U512, &[u8]) = FromBytes::from_bytes(rem)?;
+        let (max_increase, rem): (U512, &[u8]) = FromBytes::from_bytes(rem)?;
+        let (max_decrease, rem): (U512, &[u8]) = FromBytes::from_bytes(rem)?;
+        let (max_rel_increase, rem): (u64, &[u8]) = FromBytes::from_bytes(rem)?;
+        let (max_rel_decrease, rem): (u64, &[u8]) = FromBytes::from_bytes(rem)?;
+        let (delegation_commission, rem): (u64, &[u8]) = FromBytes::from_bytes(rem)?;
+        let config = Config {
+            bond_delay,
+            unbond_delay,
+            max_bond_len,
+            max_unbond_len,
+            max_spread,
+            max_increase,
+            max_decrease,
+            max_rel_increase,
+            max_rel_decrease,
+            delegation_commission,
+        };
+        Ok((config, rem))
+    }
+}
+
+impl CLTyped for Config {
+    fn cl_type() -> CLType {
+        CLType::Any
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::Config;
+    use crate::{bytesrepr, gens, value::U512};
+
+    #[test]
+    fn should_serialize_default_config() {
+        bytesrepr::test_serialization_roundtrip(&Config::default());
+    }
+
+    #[test]
+    fn should_find_invalid_limit() {
+        assert_eq!(Config::default().invalid_limit(), None);
+        let config = Config {
+            max_bond_len: 0,
+            ..Config::default()
+        };
+        assert_eq!(config.invalid_limit(), Some("max_bond_len"));
+        let config = Config {
+            max_rel_decrease: 1_000_001,
+            ..Config::default()
+        };
+        assert_eq!(config.invalid_limit(), Some("max_rel_decrease"));
+        let config = Config {
+            delegation_commission: 1_000_001,
+            ..Config::default()
+        };
+        assert_eq!(config.invalid_limit(), Some("delegation_commission"));
+        let config = Config {
+            bond_delay: 0,
+            unbond_delay: 0,
+            max_bond_len: 0,
+            max_unbond_len: 0,
+            max_spread: U512::zero(),
+            max_increase: U512::zero(),
+            max_decrease: U512::zero(),
+            max_rel_increase: 0,
+            max_rel_decrease: 0,
+            delegation_commission: 0,
+        };
+        assert_eq!(config.invalid_limit(), Some("max_bond_len"));
+    }
+
+    proptest! {
+        #[test]
+        fn test_config_serialization_roundtrip(config in gens::pos_config_arb()) {
+            bytesrepr::test_serialization_roundtrip(&config);
+        }
+    }
+}
@@ -41,6 +41,20 @@ pub enum Error {
     FailedTransferToRewardsPurse,
     FailedTransferToAccountPurse,
     SetRefundPurseCalledOutsidePayment,
+    RewardsPurseBalanceNotFound,
+    FailedTransferToValidatorPurse,
+    SlashFractionTooLarge,
+    CannotSlashLastValidator,
+    DelegationTooSmall,
+    NotDelegated,
+    UndelegateTooLarge,
+    DelegationTransferFailed,
+    UndelegationTransferFailed,
+    DelegationsKeyDeserializationFailed,
+    DelegationsDeserializationFailed,
+    ConfigNotFound,
+    ConfigKeyUnexpectedType,
+    ConfigDeserializationFailed,
 }
 
 pub type Result<T> = result::Result<T, Error>;
@@ -1,3 +1,5 @@
+mod config;
 mod error;
 
+pub use config::Config;
 pub use error::{Error, PurseLookupError, Result};
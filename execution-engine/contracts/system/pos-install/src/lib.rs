@@ -8,7 +8,7 @@ use core::fmt::Write;
 use contract_ffi::{
     contract_api::{runtime, storage, ContractRef, Error},
     key::Key,
-    system_contracts::mint,
+    system_contracts::{mint, pos},
     unwrap_or_revert::UnwrapOrRevert,
     uref::{AccessRights, URef},
     value::{
@@ -21,12 +21,14 @@ const PLACEHOLDER_KEY: Key = Key::Hash([0u8; 32]);
 const POS_BONDING_PURSE: &str = "pos_bonding_purse";
 const POS_PAYMENT_PURSE: &str = "pos_payment_purse";
 const POS_REWARDS_PURSE: &str = "pos_rewards_purse";
+const POS_CONFIG: &str = "pos_config";
 const POS_FUNCTION_NAME: &str = "pos_ext";
 
 #[repr(u32)]
 enum Args {
     MintURef = 0,
     GenesisValidators = 1,
+    Config = 2,
 }
 
 #[no_mangle]
@@ -65,6 +67,12 @@ pub extern "C" fn call() {
         .map(|key| (key, PLACEHOLDER_KEY))
         .collect();
 
+    let config: pos::Config = runtime::get_arg(Args::Config as u32)
+        .unwrap_or_revert_with(Error::MissingArgument)
+        .unwrap_or_revert_with(Error::InvalidArgument);
+    let config_uref: URef = storage::new_turef(config).into();
+    named_keys.insert(String::from(POS_CONFIG), Key::URef(config_uref));
+
     let total_bonds: U512 = genesis_validators.values().fold(U512::zero(), |x, y| x + y);
 
     let bonding_purse = mint_purse(&mint, total_bonds);
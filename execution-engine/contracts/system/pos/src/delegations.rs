@@ -0,0 +1,304 @@
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    string::String,
+};
+use core::fmt::Write;
+
+use base16;
+
+use contract_ffi::{
+    contract_api::runtime,
+    key::Key,
+    system_contracts::pos::{Error, Result},
+    value::{account::PublicKey, U512},
+};
+
+pub trait DelegationsProvider {
+    fn read() -> Result<Delegations>;
+    fn write(delegations: &Delegations);
+}
+
+/// A `DelegationsProvider` that reads and writes the delegations to/from the contract's known
+/// urefs.
+pub struct ContractDelegations;
+
+impl ContractDelegations {
+    fn decode_key(hex_key: Option<&str>) -> Result<PublicKey> {
+        let hex_key = hex_key.ok_or(Error::DelegationsKeyDeserializationFailed)?;
+        if hex_key.len() != 64 {
+            return Err(Error::DelegationsKeyDeserializationFailed);
+        }
+        let mut key_bytes = [0u8; 32];
+        let _bytes_written = base16::decode_slice(hex_key, &mut key_bytes)
+            .map_err(|_| Error::DelegationsKeyDeserializationFailed)?;
+        debug_assert!(_bytes_written == key_bytes.len());
+        Ok(PublicKey::new(key_bytes))
+    }
+
+    fn encode_key(pub_key: &PublicKey, hex_key: &mut String) {
+        for byte in &pub_key.value()[..32] {
+            write!(hex_key, "{:02x}", byte).expect("Writing to a string cannot fail");
+        }
+    }
+}
+
+impl DelegationsProvider for ContractDelegations {
+    /// Reads the current delegations from the contract's known urefs.
+    fn read() -> Result<Delegations> {
+        let mut delegations = Delegations::default();
+        for (name, _) in runtime::list_named_keys() {
+            let mut split_name = name.split('_');
+            if Some("d") != split_name.next() {
+                continue;
+            }
+            let validator = Self::decode_key(split_name.next())?;
+            let delegator = Self::decode_key(split_name.next())?;
+            let amount = split_name
+                .next()
+                .and_then(|b| U512::from_dec_str(b).ok())
+                .ok_or(Error::DelegationsDeserializationFailed)?;
+            delegations.delegate(&validator, &delegator, amount);
+        }
+        Ok(delegations)
+    }
+
+    /// Writes the current delegations to the contract's known urefs.
+    fn write(delegations: &Delegations) {
+        // Encode the delegations as a set of uref names.
+        let mut new_urefs: BTreeSet<String> = delegations
+            .0
+            .iter()
+            .flat_map(|(validator, delegators)| {
+                delegators.iter().map(move |(delegator, amount)| {
+                    let mut uref = String::from("d_");
+                    Self::encode_key(validator, &mut uref);
+                    uref.push('_');
+                    Self::encode_key(delegator, &mut uref);
+                    uref.write_fmt(format_args!("_{}", amount))
+                        .expect("Writing to a string cannot fail");
+                    uref
+                })
+            })
+            .collect();
+        // Remove and add urefs to update the contract's known urefs accordingly.
+        for (name, _) in runtime::list_named_keys() {
+            if name.starts_with("d_") && !new_urefs.remove(&name) {
+                runtime::remove_key(&name);
+            }
+        }
+        for name in new_urefs {
+            runtime::put_key(&name, Key::Hash([0; 32]));
+        }
+    }
+}
+
+/// The delegations map, assigning to each validator the amount of motes each of its delegators
+/// has staked with it.
+///
+/// Delegated motes count towards the validator's weight, but not towards its stakes, so the
+/// validator can't unbond them. Delegating goes through the bonding queue and is subject to the
+/// bonding limits, applied to the validator's stakes together with the motes delegated to it.
+/// Undelegating goes through the unbonding queue, but only its length is limited.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Delegations(pub BTreeMap<PublicKey, BTreeMap<PublicKey, U512>>);
+
+impl Delegations {
+    /// Adds `amount` to the motes the delegator has staked with the validator.
+    pub fn delegate(&mut self, validator: &PublicKey, delegator: &PublicKey, amount: U512) {
+        self.0
+            .entry(*validator)
+            .or_default()
+            .entry(*delegator)
+            .and_modify(|x| *x += amount)
+            .or_insert(amount);
+    }
+
+    /// If `maybe_amount` is `None`, removes all of the delegator's motes staked with the
+    /// validator, otherwise subtracts the given amount.
+    ///
+    /// Returns the amount that was actually subtracted, or an error if the delegator has not
+    /// staked with the validator or has staked less than the given amount.
+    pub fn undelegate(
+        &mut self,
+        validator: &PublicKey,
+        delegator: &PublicKey,
+        maybe_amount: Option<U512>,
+    ) -> Result<U512> {
+        let delegators = self.0.get_mut(validator).ok_or(Error::NotDelegated)?;
+        let delegation = delegators.get_mut(delegator).ok_or(Error::NotDelegated)?;
+        let amount = maybe_amount.unwrap_or(*delegation);
+        if amount > *delegation {
+            return Err(Error::UndelegateTooLarge);
+        }
+        *delegation -= amount;
+        if delegation.is_zero() {
+            delegators.remove(delegator);
+            if delegators.is_empty() {
+                self.0.remove(validator);
+            }
+        }
+        Ok(amount)
+    }
+
+    /// Subtracts `fraction` millionths of every delegation to the validator, removing those
+    /// which are left empty.
+    ///
+    /// Returns the total amount that was subtracted.
+    pub fn slash(&mut self, validator: &PublicKey, fraction: u64) -> U512 {
+        let delegators = match self.0.get_mut(validator) {
+            Some(delegators) => delegators,
+            None => return U512::zero(),
+        };
+        let mut slashed = U512::zero();
+        for delegation in delegators.values_mut() {
+            let amount = *delegation * U512::from(fraction) / 1_000_000;
+            *delegation -= amount;
+            slashed += amount;
+        }
+        *delegators = delegators
+            .iter()
+            .filter(|(_, delegation)| !delegation.is_zero())
+            .map(|(delegator, delegation)| (*delegator, *delegation))
+            .collect();
+        if delegators.is_empty() {
+            self.0.remove(validator);
+        }
+        slashed
+    }
+
+    /// Returns the delegators of the validator with the amounts they staked.
+    pub fn delegators(&self, validator: &PublicKey) -> Option<&BTreeMap<PublicKey, U512>> {
+        self.0.get(validator)
+    }
+
+    /// Returns the total amount delegated to the validator.
+    pub fn delegated_to(&self, validator: &PublicKey) -> U512 {
+        self.delegators(validator)
+            .map(|delegators| {
+                delegators
+                    .values()
+                    .fold(U512::zero(), |sum, d| sum.saturating_add(*d))
+            })
+            .unwrap_or_else(U512::zero)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use contract_ffi::{
+        system_contracts::pos::Error,
+        value::{account::PublicKey, U512},
+    };
+
+    use crate::delegations::Delegations;
+
+    const KEY1: [u8; 32] = [1; 32];
+    const KEY2: [u8; 32] = [2; 32];
+    const KEY3: [u8; 32] = [3; 32];
+
+    fn new_delegations(delegations: &[([u8; 32], [u8; 32], u64)]) -> Delegations {
+        let mut result = Delegations::default();
+        for &(validator, delegator, amount) in delegations {
+            result.delegate(
+                &PublicKey::new(validator),
+                &PublicKey::new(delegator),
+                U512::from(amount),
+            );
+        }
+        result
+    }
+
+    #[test]
+    fn test_delegate() {
+        let mut delegations = new_delegations(&[(KEY1, KEY2, 100)]);
+        delegations.delegate(&PublicKey::new(KEY1), &PublicKey::new(KEY2), U512::from(5));
+        delegations.delegate(&PublicKey::new(KEY1), &PublicKey::new(KEY3), U512::from(7));
+        assert_eq!(
+            new_delegations(&[(KEY1, KEY2, 105), (KEY1, KEY3, 7)]),
+            delegations
+        );
+        assert_eq!(
+            U512::from(112),
+            delegations.delegated_to(&PublicKey::new(KEY1))
+        );
+        assert!(delegations.delegated_to(&PublicKey::new(KEY2)).is_zero());
+    }
+
+    #[test]
+    fn test_undelegate() {
+        let mut delegations = new_delegations(&[(KEY1, KEY2, 100), (KEY1, KEY3, 50)]);
+        assert_eq!(
+            Ok(U512::from(40)),
+            delegations.undelegate(
+                &PublicKey::new(KEY1),
+                &PublicKey::new(KEY2),
+                Some(U512::from(40))
+            )
+        );
+        assert_eq!(
+            Ok(U512::from(50)),
+            delegations.undelegate(&PublicKey::new(KEY1), &PublicKey::new(KEY3), None)
+        );
+        assert_eq!(new_delegations(&[(KEY1, KEY2, 60)]), delegations);
+
+        assert_eq!(
+            Ok(U512::from(60)),
+            delegations.undelegate(
+                &PublicKey::new(KEY1),
+                &PublicKey::new(KEY2),
+                Some(U512::from(60))
+            )
+        );
+        assert_eq!(Delegations::default(), delegations);
+    }
+
+    #[test]
+    fn test_undelegate_too_much() {
+        let mut delegations = new_delegations(&[(KEY1, KEY2, 100)]);
+        assert_eq!(
+            Err(Error::UndelegateTooLarge),
+            delegations.undelegate(
+                &PublicKey::new(KEY1),
+                &PublicKey::new(KEY2),
+                Some(U512::from(101))
+            )
+        );
+        assert_eq!(new_delegations(&[(KEY1, KEY2, 100)]), delegations);
+    }
+
+    #[test]
+    fn test_undelegate_not_delegated() {
+        let mut delegations = new_delegations(&[(KEY1, KEY2, 100)]);
+        assert_eq!(
+            Err(Error::NotDelegated),
+            delegations.undelegate(&PublicKey::new(KEY1), &PublicKey::new(KEY3), None)
+        );
+        assert_eq!(
+            Err(Error::NotDelegated),
+            delegations.undelegate(&PublicKey::new(KEY2), &PublicKey::new(KEY2), None)
+        );
+    }
+
+    #[test]
+    fn test_slash() {
+        let mut delegations =
+            new_delegations(&[(KEY1, KEY2, 100), (KEY1, KEY3, 50), (KEY2, KEY3, 10)]);
+        assert_eq!(
+            U512::from(75),
+            delegations.slash(&PublicKey::new(KEY1), 500_000)
+        );
+        assert_eq!(
+            new_delegations(&[(KEY1, KEY2, 50), (KEY1, KEY3, 25), (KEY2, KEY3, 10)]),
+            delegations
+        );
+
+        assert_eq!(
+            U512::from(75),
+            delegations.slash(&PublicKey::new(KEY1), 1_000_000)
+        );
+        assert_eq!(new_delegations(&[(KEY2, KEY3, 10)]), delegations);
+        assert!(delegations
+            .slash(&PublicKey::new(KEY3), 1_000_000)
+            .is_zero());
+    }
+}
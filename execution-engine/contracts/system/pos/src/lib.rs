@@ -2,17 +2,23 @@
 
 extern crate alloc;
 
+mod delegations;
 mod queue;
+mod rewards;
 mod stakes;
 
-use alloc::{string::String, vec::Vec};
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    string::String,
+    vec::Vec,
+};
 
 use contract_ffi::{
     block_time::BlockTime,
-    contract_api::{runtime, system},
+    contract_api::{runtime, storage, system, TURef},
     execution::Phase,
     key::Key,
-    system_contracts::pos::{Error, PurseLookupError, Result},
+    system_contracts::pos::{Config, Error, PurseLookupError, Result},
     unwrap_or_revert::UnwrapOrRevert,
     uref::{AccessRights, URef},
     value::{
@@ -22,11 +28,13 @@ use contract_ffi::{
 };
 
 use crate::{
+    delegations::{ContractDelegations, DelegationsProvider},
     queue::{QueueEntry, QueueLocal, QueueProvider},
     stakes::{ContractStakes, StakesProvider},
 };
 
-/// Account used to run system functions (in particular `finalize_payment`).
+/// Account used to run system functions (in particular `finalize_payment`, `step`,
+/// `distribute_rewards` and `slash`).
 const SYSTEM_ACCOUNT: [u8; 32] = [0u8; 32];
 
 /// The uref name where the PoS purse is stored. It contains all staked motes,
@@ -45,29 +53,8 @@ const REWARDS_PURSE_KEY: &str = "pos_rewards_purse";
 /// uref this name corresponds to is set by the user.
 const REFUND_PURSE_KEY: &str = "pos_refund_purse";
 
-/// The time from a bonding request until the bond becomes effective and part of
-/// the stake.
-const BOND_DELAY: u64 = 0;
-/// The time from an unbonding request until the stakes are paid out.
-const UNBOND_DELAY: u64 = 0;
-/// The maximum number of pending bonding requests.
-const MAX_BOND_LEN: usize = 100;
-/// The maximum number of pending unbonding requests.
-const MAX_UNBOND_LEN: usize = 1000;
-/// The maximum difference between the largest and the smallest stakes.
-// TODO: Should this be a percentage instead?
-// TODO: Pick a reasonable value.
-const MAX_SPREAD: U512 = U512::MAX;
-/// The maximum increase of stakes in a single bonding request.
-const MAX_INCREASE: U512 = U512::MAX;
-/// The maximum decrease of stakes in a single unbonding request.
-const MAX_DECREASE: U512 = U512::MAX;
-/// The maximum increase of stakes in millionths of the total stakes in a single
-/// bonding request.
-const MAX_REL_INCREASE: u64 = 1_000_000_000;
-/// The maximum decrease of stakes in millionths of the total stakes in a single
-/// unbonding request.
-const MAX_REL_DECREASE: u64 = 900_000;
+/// The uref name where the PoS stores its `Config`, set at genesis and changed by upgrades.
+const CONFIG_KEY: &str = "pos_config";
 
 /// Enqueues the deploy's creator for becoming a validator. The bond `amount` is
 /// paid from the purse `source`.
@@ -75,18 +62,19 @@ fn bond<Q: QueueProvider, S: StakesProvider>(
     amount: U512,
     validator: PublicKey,
     timestamp: BlockTime,
+    config: &Config,
 ) -> Result<()> {
     let mut queue = Q::read_bonding();
-    if queue.0.len() >= MAX_BOND_LEN {
+    if queue.0.len() as u64 >= config.max_bond_len {
         return Err(Error::TooManyEventsInQueue);
     }
 
     let mut stakes = S::read()?;
     // Simulate applying all earlier bonds. The modified stakes are not written.
-    for entry in &queue.0 {
+    for entry in queue.0.iter().filter(|entry| entry.delegator.is_none()) {
         stakes.bond(&entry.validator, entry.amount);
     }
-    stakes.validate_bonding(&validator, amount)?;
+    stakes.validate_bonding(&validator, amount, config)?;
 
     queue.push(validator, amount, timestamp)?;
     Q::write_bonding(queue);
@@ -101,14 +89,15 @@ fn unbond<Q: QueueProvider, S: StakesProvider>(
     maybe_amount: Option<U512>,
     validator: PublicKey,
     timestamp: BlockTime,
+    config: &Config,
 ) -> Result<()> {
     let mut queue = Q::read_unbonding();
-    if queue.0.len() >= MAX_UNBOND_LEN {
+    if queue.0.len() as u64 >= config.max_unbond_len {
         return Err(Error::TooManyEventsInQueue);
     }
 
     let mut stakes = S::read()?;
-    let payout = stakes.unbond(&validator, maybe_amount)?;
+    let payout = stakes.unbond(&validator, maybe_amount, config)?;
     S::write(&stakes);
     // TODO: Make sure the destination is valid and the amount can be paid. The
     // actual payment will be made later, after the unbonding delay.
@@ -119,12 +108,19 @@ fn unbond<Q: QueueProvider, S: StakesProvider>(
 }
 
 /// Removes all due requests from the queues and applies them.
-fn step<Q: QueueProvider, S: StakesProvider>(timestamp: BlockTime) -> Result<Vec<QueueEntry>> {
+///
+/// Returns the entries to be paid out: the due unbonding requests, and the due delegations to
+/// validators which are no longer bonded.
+fn step<Q: QueueProvider, S: StakesProvider, D: DelegationsProvider>(
+    timestamp: BlockTime,
+    config: &Config,
+) -> Result<Vec<QueueEntry>> {
     let mut bonding_queue = Q::read_bonding();
     let mut unbonding_queue = Q::read_unbonding();
 
-    let bonds = bonding_queue.pop_due(timestamp.saturating_sub(BlockTime::new(BOND_DELAY)));
-    let unbonds = unbonding_queue.pop_due(timestamp.saturating_sub(BlockTime::new(UNBOND_DELAY)));
+    let bonds = bonding_queue.pop_due(timestamp.saturating_sub(BlockTime::new(config.bond_delay)));
+    let mut unbonds =
+        unbonding_queue.pop_due(timestamp.saturating_sub(BlockTime::new(config.unbond_delay)));
 
     if !unbonds.is_empty() {
         Q::write_unbonding(unbonding_queue);
@@ -133,15 +129,129 @@ fn step<Q: QueueProvider, S: StakesProvider>(timestamp: BlockTime) -> Result<Vec
     if !bonds.is_empty() {
         Q::write_bonding(bonding_queue);
         let mut stakes = S::read()?;
+        let mut delegations = D::read()?;
         for entry in bonds {
-            stakes.bond(&entry.validator, entry.amount);
+            match entry.delegator {
+                // Delegations to validators which are no longer bonded are paid back.
+                Some(_) if !stakes.0.contains_key(&entry.validator) => unbonds.push(entry),
+                Some(delegator) => delegations.delegate(&entry.validator, &delegator, entry.amount),
+                None => stakes.bond(&entry.validator, entry.amount),
+            }
         }
         S::write(&stakes);
+        D::write(&delegations);
     }
 
     Ok(unbonds)
 }
 
+/// Enqueues `amount` to be added to the stakes `delegator` has delegated to `validator`. The
+/// delegation counts towards the validator's weight only after the bonding delay, and is subject
+/// to the same limits as the validator's own bonds, applied to the validator's stakes together
+/// with the stakes delegated to it.
+fn add_delegation<Q: QueueProvider, S: StakesProvider, D: DelegationsProvider>(
+    delegator: PublicKey,
+    validator: PublicKey,
+    amount: U512,
+    timestamp: BlockTime,
+    config: &Config,
+) -> Result<()> {
+    let mut queue = Q::read_bonding();
+    if queue.0.len() as u64 >= config.max_bond_len {
+        return Err(Error::TooManyEventsInQueue);
+    }
+
+    let mut stakes = S::read()?;
+    if !stakes.0.contains_key(&validator) {
+        return Err(Error::NotBonded);
+    }
+    // Simulate applying all delegations and earlier bonds. The modified stakes are not written.
+    for (delegated_validator, delegators) in &D::read()?.0 {
+        for delegation in delegators.values() {
+            stakes.bond(delegated_validator, *delegation);
+        }
+    }
+    for entry in &queue.0 {
+        stakes.bond(&entry.validator, entry.amount);
+    }
+    stakes.validate_bonding(&validator, amount, config)?;
+
+    queue.push_delegator(validator, delegator, amount, timestamp)?;
+    Q::write_bonding(queue);
+    Ok(())
+}
+
+/// Enqueues `delegator`'s stakes with `validator` for withdrawal. If `maybe_amount` is `None`,
+/// all of them are withdrawn. They stop counting towards the validator's weight immediately, but
+/// are only paid back to the delegator after the unbonding delay, and can be slashed until then.
+fn remove_delegation<Q: QueueProvider, D: DelegationsProvider>(
+    delegator: PublicKey,
+    validator: PublicKey,
+    maybe_amount: Option<U512>,
+    timestamp: BlockTime,
+    config: &Config,
+) -> Result<()> {
+    let mut queue = Q::read_unbonding();
+    if queue.0.len() as u64 >= config.max_unbond_len {
+        return Err(Error::TooManyEventsInQueue);
+    }
+
+    let mut delegations = D::read()?;
+    let payout = delegations.undelegate(&validator, &delegator, maybe_amount)?;
+    queue.push_delegator(validator, delegator, payout, timestamp)?;
+    D::write(&delegations);
+    Q::write_unbonding(queue);
+    Ok(())
+}
+
+/// Slashes `fraction` millionths of the stakes of each of the `validators`, of the stakes
+/// delegated to them, and of the amounts of their pending bonding and unbonding requests,
+/// including their delegators' undelegations. Requests left empty are removed. Returns the total
+/// amount slashed.
+///
+/// The stakes are never left empty: if every bonded validator is slashed in full, the one with
+/// the largest stakes stays bonded and keeps its stakes and its pending requests, but the stakes
+/// delegated to it are slashed.
+fn slash<Q: QueueProvider, S: StakesProvider, D: DelegationsProvider>(
+    validators: &[PublicKey],
+    fraction: u64,
+) -> Result<U512> {
+    if fraction > 1_000_000 {
+        return Err(Error::SlashFractionTooLarge);
+    }
+
+    let mut stakes = S::read()?;
+    let mut delegations = D::read()?;
+    let mut bonding_queue = Q::read_bonding();
+    let mut unbonding_queue = Q::read_unbonding();
+    let mut slashed = U512::zero();
+    // Validators listed more than once are only slashed once.
+    let validators: BTreeSet<&PublicKey> = validators.iter().collect();
+    let spared = if fraction == 1_000_000 && stakes.0.keys().all(|v| validators.contains(v)) {
+        stakes
+            .0
+            .iter()
+            .max_by_key(|(_, stake)| *stake)
+            .map(|(validator, _)| *validator)
+    } else {
+        None
+    };
+    for validator in validators {
+        slashed += delegations.slash(validator, fraction);
+        if spared.as_ref() == Some(validator) {
+            continue;
+        }
+        slashed += stakes.slash(validator, fraction)?;
+        slashed += bonding_queue.slash(validator, fraction);
+        slashed += unbonding_queue.slash(validator, fraction);
+    }
+    S::write(&stakes);
+    D::write(&delegations);
+    Q::write_bonding(bonding_queue);
+    Q::write_unbonding(unbonding_queue);
+    Ok(slashed)
+}
+
 /// Attempts to look up a purse from the named_keys
 fn get_purse_id(name: &str) -> core::result::Result<PurseId, PurseLookupError> {
     runtime::get_key(name)
@@ -167,6 +277,18 @@ fn get_rewards_purse() -> Result<PurseId> {
     get_purse_id(REWARDS_PURSE_KEY).map_err(PurseLookupError::rewards)
 }
 
+/// Returns the parameters the PoS was configured with at genesis or by the latest upgrade.
+fn get_config() -> Result<Config> {
+    let uref = match runtime::get_key(CONFIG_KEY).ok_or(Error::ConfigNotFound)? {
+        Key::URef(uref) => uref,
+        _ => return Err(Error::ConfigKeyUnexpectedType),
+    };
+    let turef = TURef::from_uref(uref).map_err(|_| Error::ConfigKeyUnexpectedType)?;
+    storage::read(turef)
+        .map_err(|_| Error::ConfigDeserializationFailed)?
+        .ok_or(Error::ConfigNotFound)
+}
+
 /// Sets the purse where refunds (excess funds not spent to pay for computation)
 /// will be sent. Note that if this function is never called, the default
 /// location is the main purse of the deployer's account.
@@ -236,6 +358,46 @@ fn refund_to_account(payment_purse: PurseId, account: PublicKey, amount: U512) {
         .unwrap_or_revert_with(Error::FailedTransferToAccountPurse);
 }
 
+/// Pays out the rewards purse to the bonded validators and their delegators, in proportion to the
+/// validators' stakes including delegations multiplied by the given weights, crediting each
+/// account's main purse.
+fn distribute_rewards<S: StakesProvider, D: DelegationsProvider>(
+    weights: &BTreeMap<PublicKey, u64>,
+    config: &Config,
+) -> Result<()> {
+    let rewards_purse = get_rewards_purse()?;
+    let total = system::get_balance(rewards_purse).ok_or(Error::RewardsPurseBalanceNotFound)?;
+    let stakes = S::read()?;
+    let delegations = D::read()?;
+    let shares = rewards::reward_shares(
+        total,
+        &stakes,
+        &delegations,
+        weights,
+        config.delegation_commission,
+    );
+    for (account, amount) in shares {
+        system::transfer_from_purse_to_account(rewards_purse, account, amount)
+            .map_err(|_| Error::FailedTransferToValidatorPurse)?;
+    }
+    Ok(())
+}
+
+/// Applies the due requests from the queues and pays out the due unbondings.
+///
+/// Payouts are made outside of `step` so that it can be unit tested.
+fn step_and_pay_out(pos_purse: PurseId, timestamp: BlockTime, config: &Config) {
+    let unbonds = step::<QueueLocal, ContractStakes, ContractDelegations>(timestamp, config)
+        .unwrap_or_revert();
+    for entry in unbonds {
+        // TODO: We currently ignore `TransferResult::TransferError`s here, since we
+        // can't recover from them and we shouldn't retry indefinitely.
+        // That would mean the contract just keeps the money forever,
+        // though.
+        let _ = system::transfer_from_purse_to_account(pos_purse, entry.payee(), entry.amount);
+    }
+}
+
 pub fn delegate() {
     let method_name: String = runtime::get_arg(0)
         .unwrap_or_revert_with(Error::MissingArgument)
@@ -260,20 +422,15 @@ pub fn delegate() {
             // Transfer `amount` from the `source` purse to PoS internal purse.
             // POS_PURSE is a constant, it is the PurseID of the proof-of-stake contract's
             // own purse.
+            let config = get_config().unwrap_or_revert();
             system::transfer_from_purse_to_purse(source, pos_purse, amount)
                 .unwrap_or_revert_with(Error::BondTransferFailed);
-            bond::<QueueLocal, ContractStakes>(amount, validator, timestamp).unwrap_or_revert();
-
-            // TODO: Remove this and set nonzero delays once the system calls `step` in each
-            // block.
-            let unbonds = step::<QueueLocal, ContractStakes>(timestamp).unwrap_or_revert();
-            for entry in unbonds {
-                let _ = system::transfer_from_purse_to_account(
-                    pos_purse,
-                    entry.validator,
-                    entry.amount,
-                );
-            }
+            bond::<QueueLocal, ContractStakes>(amount, validator, timestamp, &config)
+                .unwrap_or_revert();
+
+            // The node doesn't run the engine's `step` at the end of each block yet, so due
+            // requests are applied here too; otherwise they would never be.
+            step_and_pay_out(pos_purse, timestamp, &config);
         }
         // Type of this method: `fn unbond(amount: Option<U512>)`
         "unbond" => {
@@ -281,35 +438,74 @@ pub fn delegate() {
             let maybe_amount = runtime::get_arg(1)
                 .unwrap_or_revert_with(Error::MissingArgument)
                 .unwrap_or_revert_with(Error::InvalidArgument);
-            unbond::<QueueLocal, ContractStakes>(maybe_amount, validator, timestamp)
+            let config = get_config().unwrap_or_revert();
+            unbond::<QueueLocal, ContractStakes>(maybe_amount, validator, timestamp, &config)
                 .unwrap_or_revert();
 
-            // TODO: Remove this and set nonzero delays once the system calls `step` in each
-            // block.
-            let unbonds = step::<QueueLocal, ContractStakes>(timestamp).unwrap_or_revert();
-            for entry in unbonds {
-                system::transfer_from_purse_to_account(pos_purse, entry.validator, entry.amount)
-                    .unwrap_or_revert_with(Error::UnbondTransferFailed);
+            // The node doesn't run the engine's `step` at the end of each block yet, so due
+            // requests are applied here too; otherwise they would never be.
+            step_and_pay_out(pos_purse, timestamp, &config);
+        }
+        // Type of this method: `fn delegate(validator: PublicKey, amount: U512, purse: URef)`
+        "delegate" => {
+            let delegator = runtime::get_caller();
+            let validator: PublicKey = runtime::get_arg(1)
+                .unwrap_or_revert_with(Error::MissingArgument)
+                .unwrap_or_revert_with(Error::InvalidArgument);
+            let amount: U512 = runtime::get_arg(2)
+                .unwrap_or_revert_with(Error::MissingArgument)
+                .unwrap_or_revert_with(Error::InvalidArgument);
+            if amount.is_zero() {
+                runtime::revert(Error::DelegationTooSmall);
             }
+            let source_uref: URef = runtime::get_arg(3)
+                .unwrap_or_revert_with(Error::MissingArgument)
+                .unwrap_or_revert_with(Error::InvalidArgument);
+            let source = PurseId::new(source_uref);
+            let config = get_config().unwrap_or_revert();
+            // Delegated stakes are held in the bonding purse together with the validators' own.
+            system::transfer_from_purse_to_purse(source, pos_purse, amount)
+                .unwrap_or_revert_with(Error::DelegationTransferFailed);
+            add_delegation::<QueueLocal, ContractStakes, ContractDelegations>(
+                delegator, validator, amount, timestamp, &config,
+            )
+            .unwrap_or_revert();
+
+            // The node doesn't run the engine's `step` at the end of each block yet, so due
+            // requests are applied here too; otherwise they would never be.
+            step_and_pay_out(pos_purse, timestamp, &config);
+        }
+        // Type of this method: `fn undelegate(validator: PublicKey, amount: Option<U512>)`
+        "undelegate" => {
+            let delegator = runtime::get_caller();
+            let validator: PublicKey = runtime::get_arg(1)
+                .unwrap_or_revert_with(Error::MissingArgument)
+                .unwrap_or_revert_with(Error::InvalidArgument);
+            let maybe_amount = runtime::get_arg(2)
+                .unwrap_or_revert_with(Error::MissingArgument)
+                .unwrap_or_revert_with(Error::InvalidArgument);
+            let config = get_config().unwrap_or_revert();
+            remove_delegation::<QueueLocal, ContractDelegations>(
+                delegator,
+                validator,
+                maybe_amount,
+                timestamp,
+                &config,
+            )
+            .unwrap_or_revert();
+
+            // The node doesn't run the engine's `step` at the end of each block yet, so due
+            // requests are applied here too; otherwise they would never be.
+            step_and_pay_out(pos_purse, timestamp, &config);
         }
         // Type of this method: `fn step()`
         "step" => {
-            // This is called by the system in every block.
-            let unbonds = step::<QueueLocal, ContractStakes>(timestamp).unwrap_or_revert();
-
-            // Mateusz: Moved outside of `step` function so that it [step] can be unit
-            // tested.
-            for entry in unbonds {
-                // TODO: We currently ignore `TransferResult::TransferError`s here, since we
-                // can't recover from them and we shouldn't retry indefinitely.
-                // That would mean the contract just keeps the money forever,
-                // though.
-                let _ = system::transfer_from_purse_to_account(
-                    pos_purse,
-                    entry.validator,
-                    entry.amount,
-                );
+            // This is called by the system at the end of every block.
+            if runtime::get_caller().value() != SYSTEM_ACCOUNT {
+                runtime::revert(Error::SystemFunctionCalledByUserAccount);
             }
+            let config = get_config().unwrap_or_revert();
+            step_and_pay_out(pos_purse, timestamp, &config);
         }
         "get_payment_purse" => {
             let purse = get_payment_purse().unwrap_or_revert();
@@ -343,6 +539,48 @@ pub fn delegate() {
                 .unwrap_or_revert_with(Error::InvalidArgument);
             finalize_payment(amount_spent, account);
         }
+        // Type of this method: `fn slash(validators: Vec<PublicKey>, fraction: u64)`
+        "slash" => {
+            // This is called by the system when evidence of equivocation is found.
+            if runtime::get_caller().value() != SYSTEM_ACCOUNT {
+                runtime::revert(Error::SystemFunctionCalledByUserAccount);
+            }
+            let validators: Vec<PublicKey> = runtime::get_arg(1)
+                .unwrap_or_revert_with(Error::MissingArgument)
+                .unwrap_or_revert_with(Error::InvalidArgument);
+            let fraction: u64 = runtime::get_arg(2)
+                .unwrap_or_revert_with(Error::MissingArgument)
+                .unwrap_or_revert_with(Error::InvalidArgument);
+            let slashed =
+                slash::<QueueLocal, ContractStakes, ContractDelegations>(&validators, fraction)
+                    .unwrap_or_revert();
+
+            // The slashed motes are paid out to the validators with the next rewards.
+            if !slashed.is_zero() {
+                let rewards_purse = get_rewards_purse().unwrap_or_revert();
+                system::transfer_from_purse_to_purse(pos_purse, rewards_purse, slashed)
+                    .unwrap_or_revert_with(Error::FailedTransferToRewardsPurse);
+            }
+        }
+        // Type of this method: `fn distribute_rewards(weights: BTreeMap<PublicKey, u64>)`
+        "distribute_rewards" => {
+            // This is called by the system at the end of every block.
+            if runtime::get_caller().value() != SYSTEM_ACCOUNT {
+                runtime::revert(Error::SystemFunctionCalledByUserAccount);
+            }
+            let weights: BTreeMap<PublicKey, u64> = runtime::get_arg(1)
+                .unwrap_or_revert_with(Error::MissingArgument)
+                .unwrap_or_revert_with(Error::InvalidArgument);
+            let config = get_config().unwrap_or_revert();
+            distribute_rewards::<ContractStakes, ContractDelegations>(&weights, &config)
+                .unwrap_or_revert();
+        }
+        // Type of this method: `fn get_config() -> Config`
+        "get_config" => {
+            let config = get_config().unwrap_or_revert();
+            let return_value = CLValue::from_t(config).unwrap_or_revert();
+            runtime::ret(return_value);
+        }
         _ => {}
     }
 }
@@ -359,19 +597,22 @@ mod tests {
 
     use contract_ffi::{
         block_time::BlockTime,
-        system_contracts::pos::Result,
+        system_contracts::pos::{Config, Error, Result},
         value::{account::PublicKey, U512},
     };
 
     use crate::{
-        bond,
+        add_delegation, bond,
+        delegations::{Delegations, DelegationsProvider},
         queue::{Queue, QueueProvider},
+        remove_delegation, slash,
         stakes::{Stakes, StakesProvider},
-        step, unbond, BOND_DELAY, UNBOND_DELAY,
+        step, unbond,
     };
 
     const KEY1: [u8; 32] = [1; 32];
     const KEY2: [u8; 32] = [2; 32];
+    const KEY3: [u8; 32] = [3; 32];
 
     thread_local! {
         static BONDING: RefCell<Queue> = RefCell::new(Queue(Default::default()));
@@ -379,6 +620,7 @@ mod tests {
         static STAKES: RefCell<Stakes> = RefCell::new(
             Stakes(iter::once((PublicKey::new(KEY1), U512::from(1_000))).collect())
         );
+        static DELEGATIONS: RefCell<Delegations> = RefCell::new(Delegations::default());
     }
 
     struct TestQueues;
@@ -413,6 +655,18 @@ mod tests {
         }
     }
 
+    struct TestDelegations;
+
+    impl DelegationsProvider for TestDelegations {
+        fn read() -> Result<Delegations> {
+            DELEGATIONS.with(|d| Ok(d.borrow().clone()))
+        }
+
+        fn write(delegations: &Delegations) {
+            DELEGATIONS.with(|d| d.replace(delegations.clone()));
+        }
+    }
+
     fn assert_stakes(stakes: &[([u8; 32], usize)]) {
         let expected = Stakes(
             stakes
@@ -425,26 +679,445 @@ mod tests {
 
     #[test]
     fn test_bond_step_unbond() {
-        bond::<TestQueues, TestStakes>(U512::from(500), PublicKey::new(KEY2), BlockTime::new(1))
-            .expect("bond validator 2");
+        let config = Config {
+            bond_delay: 10,
+            unbond_delay: 20,
+            ..Config::default()
+        };
+        bond::<TestQueues, TestStakes>(
+            U512::from(500),
+            PublicKey::new(KEY2),
+            BlockTime::new(1),
+            &config,
+        )
+        .expect("bond validator 2");
 
         // Bonding becomes effective only after the delay.
         assert_stakes(&[(KEY1, 1_000)]);
-        step::<TestQueues, TestStakes>(BlockTime::new(BOND_DELAY)).expect("step 1");
+        step::<TestQueues, TestStakes, TestDelegations>(BlockTime::new(config.bond_delay), &config)
+            .expect("step 1");
         assert_stakes(&[(KEY1, 1_000)]);
-        step::<TestQueues, TestStakes>(BlockTime::new(1 + BOND_DELAY)).expect("step 2");
+        step::<TestQueues, TestStakes, TestDelegations>(
+            BlockTime::new(1 + config.bond_delay),
+            &config,
+        )
+        .expect("step 2");
         assert_stakes(&[(KEY1, 1_000), (KEY2, 500)]);
 
         unbond::<TestQueues, TestStakes>(
             Some(U512::from(500)),
             PublicKey::new(KEY1),
             BlockTime::new(2),
+            &config,
         )
         .expect("partly unbond validator 1");
 
-        // Unbonding becomes effective immediately.
+        // Unbonding becomes effective immediately, but is paid out only after the delay.
         assert_stakes(&[(KEY1, 500), (KEY2, 500)]);
-        step::<TestQueues, TestStakes>(BlockTime::new(2 + UNBOND_DELAY)).expect("step 3");
+        let unbonds = step::<TestQueues, TestStakes, TestDelegations>(
+            BlockTime::new(1 + config.unbond_delay),
+            &config,
+        )
+        .expect("step 3");
+        assert!(unbonds.is_empty());
+        let unbonds = step::<TestQueues, TestStakes, TestDelegations>(
+            BlockTime::new(2 + config.unbond_delay),
+            &config,
+        )
+        .expect("step 4");
+        assert_eq!(1, unbonds.len());
+        assert_eq!(U512::from(500), unbonds[0].amount);
         assert_stakes(&[(KEY1, 500), (KEY2, 500)]);
     }
+
+    #[test]
+    fn test_slash() {
+        let config = Config::default();
+        bond::<TestQueues, TestStakes>(
+            U512::from(500),
+            PublicKey::new(KEY2),
+            BlockTime::new(1),
+            &config,
+        )
+        .expect("bond validator 2");
+        step::<TestQueues, TestStakes, TestDelegations>(
+            BlockTime::new(1 + config.bond_delay),
+            &config,
+        )
+        .expect("step 1");
+        add_delegation::<TestQueues, TestStakes, TestDelegations>(
+            PublicKey::new(KEY3),
+            PublicKey::new(KEY2),
+            U512::from(100),
+            BlockTime::new(1),
+            &config,
+        )
+        .expect("delegate to validator 2");
+        step::<TestQueues, TestStakes, TestDelegations>(
+            BlockTime::new(1 + config.bond_delay),
+            &config,
+        )
+        .expect("step 2");
+        unbond::<TestQueues, TestStakes>(
+            Some(U512::from(200)),
+            PublicKey::new(KEY2),
+            BlockTime::new(2),
+            &config,
+        )
+        .expect("partly unbond validator 2");
+        bond::<TestQueues, TestStakes>(
+            U512::from(100),
+            PublicKey::new(KEY2),
+            BlockTime::new(3),
+            &config,
+        )
+        .expect("bond more for validator 2");
+        assert_stakes(&[(KEY1, 1_000), (KEY2, 300)]);
+
+        // The stakes, the delegations and the pending bonding and unbonding requests are
+        // slashed, once.
+        let validators = [PublicKey::new(KEY2), PublicKey::new(KEY2)];
+        assert_eq!(
+            Ok(U512::from(350)),
+            slash::<TestQueues, TestStakes, TestDelegations>(&validators, 500_000)
+        );
+        assert_stakes(&[(KEY1, 1_000), (KEY2, 150)]);
+        assert_eq!(
+            U512::from(50),
+            TestDelegations::read()
+                .unwrap()
+                .delegated_to(&PublicKey::new(KEY2))
+        );
+        assert_eq!(U512::from(50), TestQueues::read_bonding().0[0].amount);
+        assert_eq!(U512::from(100), TestQueues::read_unbonding().0[0].amount);
+
+        assert_eq!(
+            Ok(U512::from(350)),
+            slash::<TestQueues, TestStakes, TestDelegations>(&validators, 1_000_000)
+        );
+        assert_stakes(&[(KEY1, 1_000)]);
+        assert_eq!(Ok(Delegations::default()), TestDelegations::read());
+        assert!(TestQueues::read_bonding().0.is_empty());
+        assert!(TestQueues::read_unbonding().0.is_empty());
+
+        assert_eq!(
+            Err(Error::SlashFractionTooLarge),
+            slash::<TestQueues, TestStakes, TestDelegations>(&[PublicKey::new(KEY1)], 1_000_001)
+        );
+    }
+
+    #[test]
+    fn test_slash_all_validators() {
+        let config = Config {
+            unbond_delay: 20,
+            ..Config::default()
+        };
+        bond::<TestQueues, TestStakes>(
+            U512::from(500),
+            PublicKey::new(KEY2),
+            BlockTime::new(1),
+            &config,
+        )
+        .expect("bond validator 2");
+        step::<TestQueues, TestStakes, TestDelegations>(
+            BlockTime::new(1 + config.bond_delay),
+            &config,
+        )
+        .expect("step 1");
+        unbond::<TestQueues, TestStakes>(
+            Some(U512::from(200)),
+            PublicKey::new(KEY1),
+            BlockTime::new(2),
+            &config,
+        )
+        .expect("partly unbond validator 1");
+        add_delegation::<TestQueues, TestStakes, TestDelegations>(
+            PublicKey::new(KEY3),
+            PublicKey::new(KEY1),
+            U512::from(100),
+            BlockTime::new(2),
+            &config,
+        )
+        .expect("delegate to validator 1");
+        step::<TestQueues, TestStakes, TestDelegations>(
+            BlockTime::new(2 + config.bond_delay),
+            &config,
+        )
+        .expect("step 2");
+
+        // Validator 1 has the largest stakes, so it stays bonded and keeps its pending unbonding
+        // request. Everything else is slashed.
+        let validators = [PublicKey::new(KEY1), PublicKey::new(KEY2)];
+        assert_eq!(
+            Ok(U512::from(600)),
+            slash::<TestQueues, TestStakes, TestDelegations>(&validators, 1_000_000)
+        );
+        assert_stakes(&[(KEY1, 800)]);
+        assert_eq!(Ok(Delegations::default()), TestDelegations::read());
+        assert_eq!(U512::from(200), TestQueues::read_unbonding().0[0].amount);
+
+        assert_eq!(
+            Ok(U512::zero()),
+            slash::<TestQueues, TestStakes, TestDelegations>(&[PublicKey::new(KEY1)], 1_000_000)
+        );
+        assert_stakes(&[(KEY1, 800)]);
+    }
+
+    #[test]
+    fn test_delegate_undelegate() {
+        let config = Config {
+            unbond_delay: 20,
+            ..Config::default()
+        };
+        assert_eq!(
+            Err(Error::NotBonded),
+            add_delegation::<TestQueues, TestStakes, TestDelegations>(
+                PublicKey::new(KEY3),
+                PublicKey::new(KEY2),
+                U512::from(100),
+                BlockTime::new(1),
+                &config,
+            )
+        );
+        add_delegation::<TestQueues, TestStakes, TestDelegations>(
+            PublicKey::new(KEY3),
+            PublicKey::new(KEY1),
+            U512::from(100),
+            BlockTime::new(1),
+            &config,
+        )
+        .expect("delegate to validator 1");
+        step::<TestQueues, TestStakes, TestDelegations>(
+            BlockTime::new(1 + config.bond_delay),
+            &config,
+        )
+        .expect("step 0");
+
+        // Delegations don't change the validator's own stakes.
+        assert_stakes(&[(KEY1, 1_000)]);
+        let delegations = TestDelegations::read().expect("read delegations");
+        assert_eq!(
+            U512::from(100),
+            delegations.delegated_to(&PublicKey::new(KEY1))
+        );
+
+        assert_eq!(
+            Err(Error::UndelegateTooLarge),
+            remove_delegation::<TestQueues, TestDelegations>(
+                PublicKey::new(KEY3),
+                PublicKey::new(KEY1),
+                Some(U512::from(101)),
+                BlockTime::new(2),
+                &config,
+            )
+        );
+        remove_delegation::<TestQueues, TestDelegations>(
+            PublicKey::new(KEY3),
+            PublicKey::new(KEY1),
+            Some(U512::from(40)),
+            BlockTime::new(2),
+            &config,
+        )
+        .expect("partly undelegate from validator 1");
+        assert_eq!(
+            Err(Error::MultipleRequests),
+            remove_delegation::<TestQueues, TestDelegations>(
+                PublicKey::new(KEY3),
+                PublicKey::new(KEY1),
+                None,
+                BlockTime::new(3),
+                &config,
+            )
+        );
+        assert_eq!(
+            U512::from(60),
+            TestDelegations::read()
+                .unwrap()
+                .delegated_to(&PublicKey::new(KEY1))
+        );
+
+        // Undelegating becomes effective immediately, but is paid out to the delegator only
+        // after the delay.
+        let unbonds = step::<TestQueues, TestStakes, TestDelegations>(
+            BlockTime::new(1 + config.unbond_delay),
+            &config,
+        )
+        .expect("step 1");
+        assert!(unbonds.is_empty());
+        let unbonds = step::<TestQueues, TestStakes, TestDelegations>(
+            BlockTime::new(2 + config.unbond_delay),
+            &config,
+        )
+        .expect("step 2");
+        assert_eq!(1, unbonds.len());
+        assert_eq!(PublicKey::new(KEY3), unbonds[0].payee());
+        assert_eq!(U512::from(40), unbonds[0].amount);
+
+        remove_delegation::<TestQueues, TestDelegations>(
+            PublicKey::new(KEY3),
+            PublicKey::new(KEY1),
+            None,
+            BlockTime::new(3),
+            &config,
+        )
+        .expect("undelegate the rest from validator 1");
+        assert_eq!(Ok(Delegations::default()), TestDelegations::read());
+        assert_stakes(&[(KEY1, 1_000)]);
+    }
+
+    #[test]
+    fn test_delegation_bonding_delay_and_limits() {
+        let config = Config {
+            bond_delay: 10,
+            max_rel_increase: 100_000,
+            ..Config::default()
+        };
+
+        // The limits apply to the validator's stakes together with the stakes delegated to it.
+        assert_eq!(
+            Err(Error::BondTooLarge),
+            add_delegation::<TestQueues, TestStakes, TestDelegations>(
+                PublicKey::new(KEY3),
+                PublicKey::new(KEY1),
+                U512::from(101),
+                BlockTime::new(1),
+                &config,
+            )
+        );
+        add_delegation::<TestQueues, TestStakes, TestDelegations>(
+            PublicKey::new(KEY3),
+            PublicKey::new(KEY1),
+            U512::from(100),
+            BlockTime::new(1),
+            &config,
+        )
+        .expect("delegate to validator 1");
+        assert_eq!(
+            Err(Error::MultipleRequests),
+            add_delegation::<TestQueues, TestStakes, TestDelegations>(
+                PublicKey::new(KEY3),
+                PublicKey::new(KEY1),
+                U512::from(10),
+                BlockTime::new(2),
+                &config,
+            )
+        );
+        add_delegation::<TestQueues, TestStakes, TestDelegations>(
+            PublicKey::new(KEY2),
+            PublicKey::new(KEY1),
+            U512::from(10),
+            BlockTime::new(2),
+            &config,
+        )
+        .expect("delegate more to validator 1");
+        assert_eq!(
+            Err(Error::BondTooLarge),
+            add_delegation::<TestQueues, TestStakes, TestDelegations>(
+                PublicKey::new(KEY1),
+                PublicKey::new(KEY1),
+                U512::from(112),
+                BlockTime::new(3),
+                &config,
+            )
+        );
+
+        // Delegating becomes effective only after the delay.
+        step::<TestQueues, TestStakes, TestDelegations>(BlockTime::new(10), &config)
+            .expect("step 1");
+        assert_eq!(Ok(Delegations::default()), TestDelegations::read());
+        step::<TestQueues, TestStakes, TestDelegations>(BlockTime::new(12), &config)
+            .expect("step 2");
+        assert_eq!(
+            U512::from(110),
+            TestDelegations::read()
+                .unwrap()
+                .delegated_to(&PublicKey::new(KEY1))
+        );
+        assert_stakes(&[(KEY1, 1_000)]);
+    }
+
+    #[test]
+    fn test_delegation_to_unbonded_validator_is_paid_back() {
+        let config = Config {
+            bond_delay: 10,
+            ..Config::default()
+        };
+        bond::<TestQueues, TestStakes>(
+            U512::from(500),
+            PublicKey::new(KEY2),
+            BlockTime::new(1),
+            &config,
+        )
+        .expect("bond validator 2");
+        step::<TestQueues, TestStakes, TestDelegations>(
+            BlockTime::new(1 + config.bond_delay),
+            &config,
+        )
+        .expect("step 1");
+        add_delegation::<TestQueues, TestStakes, TestDelegations>(
+            PublicKey::new(KEY3),
+            PublicKey::new(KEY2),
+            U512::from(100),
+            BlockTime::new(2),
+            &config,
+        )
+        .expect("delegate to validator 2");
+        unbond::<TestQueues, TestStakes>(None, PublicKey::new(KEY2), BlockTime::new(3), &config)
+            .expect("unbond validator 2");
+
+        let unbonds = step::<TestQueues, TestStakes, TestDelegations>(
+            BlockTime::new(2 + config.bond_delay),
+            &config,
+        )
+        .expect("step 2");
+        assert_eq!(2, unbonds.len());
+        assert_eq!(PublicKey::new(KEY3), unbonds[1].payee());
+        assert_eq!(U512::from(100), unbonds[1].amount);
+        assert_eq!(Ok(Delegations::default()), TestDelegations::read());
+    }
+
+    #[test]
+    fn test_slash_pending_undelegation() {
+        let config = Config {
+            unbond_delay: 20,
+            ..Config::default()
+        };
+        add_delegation::<TestQueues, TestStakes, TestDelegations>(
+            PublicKey::new(KEY3),
+            PublicKey::new(KEY1),
+            U512::from(100),
+            BlockTime::new(1),
+            &config,
+        )
+        .expect("delegate to validator 1");
+        step::<TestQueues, TestStakes, TestDelegations>(
+            BlockTime::new(1 + config.bond_delay),
+            &config,
+        )
+        .expect("step 0");
+        remove_delegation::<TestQueues, TestDelegations>(
+            PublicKey::new(KEY3),
+            PublicKey::new(KEY1),
+            None,
+            BlockTime::new(2),
+            &config,
+        )
+        .expect("undelegate from validator 1");
+
+        // Half of the pending undelegation is slashed together with half of the validator's
+        // stakes, and the rest is paid out to the delegator.
+        assert_eq!(
+            Ok(U512::from(550)),
+            slash::<TestQueues, TestStakes, TestDelegations>(&[PublicKey::new(KEY1)], 500_000)
+        );
+        let unbonds = step::<TestQueues, TestStakes, TestDelegations>(
+            BlockTime::new(2 + config.unbond_delay),
+            &config,
+        )
+        .expect("step");
+        assert_eq!(1, unbonds.len());
+        assert_eq!(PublicKey::new(KEY3), unbonds[0].payee());
+        assert_eq!(U512::from(50), unbonds[0].amount);
+        assert_stakes(&[(KEY1, 500)]);
+    }
 }
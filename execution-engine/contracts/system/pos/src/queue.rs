@@ -6,17 +6,25 @@ use contract_ffi::{
     bytesrepr::{self, FromBytes, ToBytes},
     contract_api::storage,
     system_contracts::pos::{Error, Result},
+    unwrap_or_revert::UnwrapOrRevert,
     value::{account::PublicKey, CLType, CLTyped, U512},
 };
 
-const BONDING_KEY: u8 = 1;
-const UNBONDING_KEY: u8 = 2;
+/// Local keys of the queues stored before the entries had a delegator. They are only read if the
+/// queues have not been written under the current keys yet.
+const LEGACY_BONDING_KEY: u8 = 1;
+const LEGACY_UNBONDING_KEY: u8 = 2;
+
+const BONDING_KEY: u8 = 3;
+const UNBONDING_KEY: u8 = 4;
 
 /// A pending entry in the bonding or unbonding queue.
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub struct QueueEntry {
-    /// The validator who is bonding or unbonding.
+    /// The validator who is bonding or unbonding, or whom the delegator is undelegating from.
     pub validator: PublicKey,
+    /// The delegator who is undelegating, if any.
+    pub delegator: Option<PublicKey>,
     /// The amount by which to change the stakes.
     pub amount: U512,
     /// The timestamp when the request was made.
@@ -25,22 +33,36 @@ pub struct QueueEntry {
 
 impl QueueEntry {
     /// Creates a new `QueueEntry` with the current block's timestamp.
-    fn new(validator: PublicKey, amount: U512, timestamp: BlockTime) -> QueueEntry {
+    fn new(
+        validator: PublicKey,
+        delegator: Option<PublicKey>,
+        amount: U512,
+        timestamp: BlockTime,
+    ) -> QueueEntry {
         QueueEntry {
             validator,
+            delegator,
             amount,
             timestamp,
         }
     }
+
+    /// Returns the account the entry's amount is paid out to once it is due: the delegator if
+    /// there is one, otherwise the validator.
+    pub fn payee(&self) -> PublicKey {
+        self.delegator.unwrap_or(self.validator)
+    }
 }
 
 impl FromBytes for QueueEntry {
     fn from_bytes(bytes: &[u8]) -> result::Result<(Self, &[u8]), bytesrepr::Error> {
         let (validator, bytes) = PublicKey::from_bytes(bytes)?;
+        let (delegator, bytes) = Option::<PublicKey>::from_bytes(bytes)?;
         let (amount, bytes) = U512::from_bytes(bytes)?;
         let (timestamp, bytes) = BlockTime::from_bytes(bytes)?;
         let entry = QueueEntry {
             validator,
+            delegator,
             amount,
             timestamp,
         };
@@ -51,6 +73,7 @@ impl FromBytes for QueueEntry {
 impl ToBytes for QueueEntry {
     fn to_bytes(&self) -> result::Result<Vec<u8>, bytesrepr::Error> {
         Ok((self.validator.to_bytes()?.into_iter())
+            .chain(self.delegator.to_bytes()?)
             .chain(self.amount.to_bytes()?)
             .chain(self.timestamp.to_bytes()?)
             .collect())
@@ -81,19 +104,32 @@ pub trait QueueProvider {
 /// local state.
 pub struct QueueLocal;
 
+impl QueueLocal {
+    /// Reads the queue stored under `key`, or under `legacy_key` if there is none, reverting if it
+    /// can't be deserialized. A missing queue is empty.
+    fn read(key: u8, legacy_key: u8) -> Queue {
+        let maybe_queue: Option<Queue> =
+            storage::read_local(&key).unwrap_or_revert_with(Error::QueueDeserializationFailed);
+        if let Some(queue) = maybe_queue {
+            return queue;
+        }
+        let maybe_legacy_queue: Option<LegacyQueue> = storage::read_local(&legacy_key)
+            .unwrap_or_revert_with(Error::QueueDeserializationFailed);
+        maybe_legacy_queue
+            .map(|LegacyQueue(queue)| queue)
+            .unwrap_or_default()
+    }
+}
+
 impl QueueProvider for QueueLocal {
     /// Reads bonding queue from the local state of the contract.
     fn read_bonding() -> Queue {
-        storage::read_local(&BONDING_KEY)
-            .unwrap_or_default()
-            .unwrap_or_default()
+        QueueLocal::read(BONDING_KEY, LEGACY_BONDING_KEY)
     }
 
     /// Reads unbonding queue from the local state of the contract.
     fn read_unbonding() -> Queue {
-        storage::read_local(&UNBONDING_KEY)
-            .unwrap_or_default()
-            .unwrap_or_default()
+        QueueLocal::read(UNBONDING_KEY, LEGACY_UNBONDING_KEY)
     }
 
     /// Writes bonding queue to the local state of the contract.
@@ -113,22 +149,63 @@ impl QueueProvider for QueueLocal {
 pub struct Queue(pub Vec<QueueEntry>);
 
 impl Queue {
-    /// Pushes a new entry to the end of the queue.
+    /// Pushes a new entry for the validator to the end of the queue.
     ///
     /// Returns an error if the validator already has a request in the queue.
     pub fn push(&mut self, validator: PublicKey, amount: U512, timestamp: BlockTime) -> Result<()> {
-        if self.0.iter().any(|entry| entry.validator == validator) {
+        self.push_entry(QueueEntry::new(validator, None, amount, timestamp))
+    }
+
+    /// Pushes a new entry for the delegator's stakes with the validator to the end of the queue.
+    ///
+    /// Returns an error if the delegator already has a request for the validator in the queue.
+    pub fn push_delegator(
+        &mut self,
+        validator: PublicKey,
+        delegator: PublicKey,
+        amount: U512,
+        timestamp: BlockTime,
+    ) -> Result<()> {
+        self.push_entry(QueueEntry::new(
+            validator,
+            Some(delegator),
+            amount,
+            timestamp,
+        ))
+    }
+
+    fn push_entry(&mut self, new_entry: QueueEntry) -> Result<()> {
+        if self.0.iter().any(|entry| {
+            entry.validator == new_entry.validator && entry.delegator == new_entry.delegator
+        }) {
             return Err(Error::MultipleRequests);
         }
         if let Some(entry) = self.0.last() {
-            if entry.timestamp > timestamp {
+            if entry.timestamp > new_entry.timestamp {
                 return Err(Error::TimeWentBackwards);
             }
         }
-        self.0.push(QueueEntry::new(validator, amount, timestamp));
+        self.0.push(new_entry);
         Ok(())
     }
 
+    /// Subtracts `fraction` millionths of each of the validator's entries, including those of its
+    /// delegators, removing those which are left empty.
+    ///
+    /// Returns the total amount that was subtracted.
+    pub fn slash(&mut self, validator: &PublicKey, fraction: u64) -> U512 {
+        let mut slashed = U512::zero();
+        for entry in self.0.iter_mut() {
+            if entry.validator == *validator {
+                let amount = entry.amount * U512::from(fraction) / 1_000_000;
+                entry.amount -= amount;
+                slashed += amount;
+            }
+        }
+        self.0.retain(|entry| !entry.amount.is_zero());
+        slashed
+    }
+
     /// Returns all queue entries at least as old as the specified timestamp.
     pub fn pop_due(&mut self, timestamp: BlockTime) -> Vec<QueueEntry> {
         let (older_than, rest) = self
@@ -169,15 +246,41 @@ impl CLTyped for Queue {
     }
 }
 
+/// A queue stored before the entries had a delegator, deserialized into a [`Queue`] whose entries
+/// have none.
+struct LegacyQueue(Queue);
+
+impl FromBytes for LegacyQueue {
+    fn from_bytes(bytes: &[u8]) -> result::Result<(Self, &[u8]), bytesrepr::Error> {
+        let (len, mut bytes) = u64::from_bytes(bytes)?;
+        let mut queue = Vec::new();
+        for _ in 0..len {
+            let (validator, rest) = PublicKey::from_bytes(bytes)?;
+            let (amount, rest) = U512::from_bytes(rest)?;
+            let (timestamp, rest) = BlockTime::from_bytes(rest)?;
+            bytes = rest;
+            queue.push(QueueEntry::new(validator, None, amount, timestamp));
+        }
+        Ok((LegacyQueue(Queue(queue)), bytes))
+    }
+}
+
+impl CLTyped for LegacyQueue {
+    fn cl_type() -> CLType {
+        Queue::cl_type()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use contract_ffi::{
         block_time::BlockTime,
+        bytesrepr::{self, ToBytes},
         system_contracts::pos::Error,
         value::{account::PublicKey, U512},
     };
 
-    use crate::queue::{Queue, QueueEntry};
+    use crate::queue::{LegacyQueue, Queue, QueueEntry};
 
     const KEY1: [u8; 32] = [1; 32];
     const KEY2: [u8; 32] = [2; 32];
@@ -199,6 +302,18 @@ mod tests {
             Err(Error::TimeWentBackwards),
             queue.push(val3, U512::from(5), BlockTime::new(100))
         );
+        assert_eq!(
+            Ok(()),
+            queue.push_delegator(val1, val3, U512::from(5), BlockTime::new(102))
+        );
+        assert_eq!(
+            Ok(()),
+            queue.push_delegator(val2, val3, U512::from(5), BlockTime::new(102))
+        );
+        assert_eq!(
+            Err(Error::MultipleRequests),
+            queue.push_delegator(val1, val3, U512::from(5), BlockTime::new(103))
+        );
     }
 
     #[test]
@@ -212,13 +327,91 @@ mod tests {
         assert_eq!(Ok(()), queue.push(val3, U512::from(7), BlockTime::new(102)));
         assert_eq!(
             vec![
-                QueueEntry::new(val1, U512::from(5), BlockTime::new(100)),
-                QueueEntry::new(val2, U512::from(6), BlockTime::new(101)),
+                QueueEntry::new(val1, None, U512::from(5), BlockTime::new(100)),
+                QueueEntry::new(val2, None, U512::from(6), BlockTime::new(101)),
             ],
             queue.pop_due(BlockTime::new(101))
         );
         assert_eq!(
-            vec![QueueEntry::new(val3, U512::from(7), BlockTime::new(102)),],
+            vec![QueueEntry::new(
+                val3,
+                None,
+                U512::from(7),
+                BlockTime::new(102)
+            ),],
+            queue.pop_due(BlockTime::new(105))
+        );
+    }
+
+    #[test]
+    fn test_serialization() {
+        let mut queue: Queue = Default::default();
+        assert_eq!(
+            Ok(()),
+            queue.push(PublicKey::new(KEY1), U512::from(5), BlockTime::new(100))
+        );
+        assert_eq!(
+            Ok(()),
+            queue.push_delegator(
+                PublicKey::new(KEY1),
+                PublicKey::new(KEY2),
+                U512::from(6),
+                BlockTime::new(101)
+            )
+        );
+        let bytes = queue.to_bytes().expect("serialize queue");
+        let deserialized: Queue = bytesrepr::deserialize(bytes).expect("deserialize queue");
+        assert_eq!(queue.0, deserialized.0);
+    }
+
+    #[test]
+    fn test_legacy_deserialization() {
+        let val1 = PublicKey::new(KEY1);
+        let val2 = PublicKey::new(KEY2);
+        let mut bytes = 2u64.to_bytes().unwrap();
+        for (validator, amount, timestamp) in &[(val1, 5u64, 100u64), (val2, 6, 101)] {
+            bytes.append(&mut validator.to_bytes().unwrap());
+            bytes.append(&mut U512::from(*amount).to_bytes().unwrap());
+            bytes.append(&mut BlockTime::new(*timestamp).to_bytes().unwrap());
+        }
+        let LegacyQueue(queue) = bytesrepr::deserialize(bytes).expect("deserialize legacy queue");
+        assert_eq!(
+            vec![
+                QueueEntry::new(val1, None, U512::from(5), BlockTime::new(100)),
+                QueueEntry::new(val2, None, U512::from(6), BlockTime::new(101)),
+            ],
+            queue.0
+        );
+    }
+
+    #[test]
+    fn test_slash() {
+        let val1 = PublicKey::new(KEY1);
+        let val2 = PublicKey::new(KEY2);
+        let del3 = PublicKey::new(KEY3);
+        let mut queue: Queue = Default::default();
+        assert_eq!(
+            Ok(()),
+            queue.push(val1, U512::from(10), BlockTime::new(100))
+        );
+        assert_eq!(
+            Ok(()),
+            queue.push(val2, U512::from(20), BlockTime::new(101))
+        );
+        assert_eq!(
+            Ok(()),
+            queue.push_delegator(val1, del3, U512::from(30), BlockTime::new(102))
+        );
+        assert_eq!(U512::from(20), queue.slash(&val1, 500_000));
+        assert_eq!(U512::from(20), queue.slash(&val1, 1_000_000));
+        assert!(queue.slash(&val1, 1_000_000).is_zero());
+        assert_eq!(
+            vec![QueueEntry::new(
+                val2,
+                None,
+                U512::from(20),
+                BlockTime::new(101)
+            )],
             queue.pop_due(BlockTime::new(105))
         );
     }
@@ -0,0 +1,262 @@
+use alloc::{collections::BTreeMap, vec::Vec};
+
+use contract_ffi::value::{account::PublicKey, U512};
+
+use crate::{delegations::Delegations, stakes::Stakes};
+
+/// Splits `total` among the bonded validators in proportion to their stakes, including the stakes
+/// delegated to them, multiplied by their weights.  Validators without a weight, with a zero
+/// weight or with a zero stake get nothing, and neither do validators which are not bonded.
+///
+/// Each validator's share is split further between the validator and its delegators in
+/// proportion to their stakes, with the validator keeping `delegation_commission` millionths of
+/// the delegators' part, or all of it if the commission exceeds a million.  Accounts which earn
+/// several shares get their sum.
+///
+/// Shares are rounded down, so they may add up to less than `total`.  The remainder is left in
+/// the rewards purse and paid out with the next distribution.
+pub fn reward_shares(
+    total: U512,
+    stakes: &Stakes,
+    delegations: &Delegations,
+    weights: &BTreeMap<PublicKey, u64>,
+    delegation_commission: u64,
+) -> Vec<(PublicKey, U512)> {
+    // Stakes are bounded by the total supply of motes, so neither these products nor the ones
+    // below come anywhere near overflowing.
+    let weighted_stakes: Vec<(PublicKey, U512, U512)> = stakes
+        .0
+        .iter()
+        .filter_map(|(validator, stake)| {
+            let weight = weights.get(validator)?;
+            let pooled_stake = *stake + delegations.delegated_to(validator);
+            let weighted_stake = pooled_stake * U512::from(*weight);
+            if weighted_stake.is_zero() {
+                None
+            } else {
+                Some((*validator, pooled_stake, weighted_stake))
+            }
+        })
+        .collect();
+    let sum = weighted_stakes
+        .iter()
+        .fold(U512::zero(), |sum, (_, _, s)| sum.saturating_add(*s));
+    if sum.is_zero() {
+        return Vec::new();
+    }
+
+    let mut shares: BTreeMap<PublicKey, U512> = BTreeMap::new();
+    for (validator, pooled_stake, weighted_stake) in weighted_stakes {
+        let validator_share = total * weighted_stake / sum;
+        let mut validator_reward = validator_share;
+        for (delegator, delegation) in delegations.delegators(&validator).into_iter().flatten() {
+            let delegator_share = validator_share
+                * *delegation
+                * U512::from(1_000_000u64.saturating_sub(delegation_commission))
+                / (pooled_stake * U512::from(1_000_000));
+            validator_reward -= delegator_share;
+            *shares.entry(*delegator).or_default() += delegator_share;
+        }
+        *shares.entry(validator).or_default() += validator_reward;
+    }
+
+    shares
+        .into_iter()
+        .filter(|(_, share)| !share.is_zero())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use contract_ffi::value::{account::PublicKey, U512};
+
+    use crate::{delegations::Delegations, rewards::reward_shares, stakes::Stakes};
+
+    const KEY1: [u8; 32] = [1; 32];
+    const KEY2: [u8; 32] = [2; 32];
+    const KEY3: [u8; 32] = [3; 32];
+    const KEY4: [u8; 32] = [4; 32];
+    const COMMISSION: u64 = 100_000;
+
+    fn new_stakes(stakes: &[([u8; 32], u64)]) -> Stakes {
+        Stakes(
+            stakes
+                .iter()
+                .map(|&(key, amount)| (PublicKey::new(key), U512::from(amount)))
+                .collect(),
+        )
+    }
+
+    fn new_weights(weights: &[([u8; 32], u64)]) -> BTreeMap<PublicKey, u64> {
+        weights
+            .iter()
+            .map(|&(key, weight)| (PublicKey::new(key), weight))
+            .collect()
+    }
+
+    fn new_delegations(delegations: &[([u8; 32], [u8; 32], u64)]) -> Delegations {
+        let mut result = Delegations::default();
+        for &(validator, delegator, amount) in delegations {
+            result.delegate(
+                &PublicKey::new(validator),
+                &PublicKey::new(delegator),
+                U512::from(amount),
+            );
+        }
+        result
+    }
+
+    fn new_shares(shares: &[([u8; 32], u64)]) -> Vec<(PublicKey, U512)> {
+        shares
+            .iter()
+            .map(|&(key, amount)| (PublicKey::new(key), U512::from(amount)))
+            .collect()
+    }
+
+    #[test]
+    fn test_shares_proportional_to_stakes() {
+        let stakes = new_stakes(&[(KEY1, 100), (KEY2, 300)]);
+        let delegations = Delegations::default();
+        let weights = new_weights(&[(KEY1, 1), (KEY2, 1)]);
+        assert_eq!(
+            new_shares(&[(KEY1, 250), (KEY2, 750)]),
+            reward_shares(
+                U512::from(1_000),
+                &stakes,
+                &delegations,
+                &weights,
+                COMMISSION
+            )
+        );
+    }
+
+    #[test]
+    fn test_shares_proportional_to_weights() {
+        let stakes = new_stakes(&[(KEY1, 100), (KEY2, 100)]);
+        let delegations = Delegations::default();
+        let weights = new_weights(&[(KEY1, 3), (KEY2, 1)]);
+        assert_eq!(
+            new_shares(&[(KEY1, 750), (KEY2, 250)]),
+            reward_shares(
+                U512::from(1_000),
+                &stakes,
+                &delegations,
+                &weights,
+                COMMISSION
+            )
+        );
+    }
+
+    #[test]
+    fn test_shares_round_down() {
+        let stakes = new_stakes(&[(KEY1, 1), (KEY2, 1), (KEY3, 1)]);
+        let delegations = Delegations::default();
+        let weights = new_weights(&[(KEY1, 1), (KEY2, 1), (KEY3, 1)]);
+        let shares = reward_shares(U512::from(100), &stakes, &delegations, &weights, COMMISSION);
+        assert_eq!(new_shares(&[(KEY1, 33), (KEY2, 33), (KEY3, 33)]), shares);
+    }
+
+    #[test]
+    fn test_no_shares_below_one_mote() {
+        let stakes = new_stakes(&[(KEY1, 1), (KEY2, 1_000)]);
+        let delegations = Delegations::default();
+        let weights = new_weights(&[(KEY1, 1), (KEY2, 1)]);
+        assert_eq!(
+            new_shares(&[(KEY2, 9)]),
+            reward_shares(U512::from(10), &stakes, &delegations, &weights, COMMISSION)
+        );
+    }
+
+    #[test]
+    fn test_no_shares_without_weight_or_stake() {
+        let stakes = new_stakes(&[(KEY1, 100), (KEY2, 0), (KEY3, 100)]);
+        let delegations = Delegations::default();
+        // KEY1 has a zero weight, KEY2 no stake, KEY3 no weight at all and KEY4 is not bonded.
+        let weights = new_weights(&[(KEY1, 0), (KEY2, 1), (KEY4, 1)]);
+        assert!(reward_shares(
+            U512::from(1_000),
+            &stakes,
+            &delegations,
+            &weights,
+            COMMISSION
+        )
+        .is_empty());
+
+        let weights = new_weights(&[(KEY1, 1), (KEY2, 1)]);
+        assert_eq!(
+            new_shares(&[(KEY1, 1_000)]),
+            reward_shares(
+                U512::from(1_000),
+                &stakes,
+                &delegations,
+                &weights,
+                COMMISSION
+            )
+        );
+    }
+
+    #[test]
+    fn test_shares_split_with_delegators() {
+        let stakes = new_stakes(&[(KEY1, 100)]);
+        let delegations = new_delegations(&[(KEY1, KEY2, 100), (KEY1, KEY3, 200)]);
+        let weights = new_weights(&[(KEY1, 1)]);
+
+        // The delegators' stakes are 1/4 and 1/2 of the validator's pooled stake.
+        let key2_share = 250 * (1_000_000 - COMMISSION) / 1_000_000;
+        let key3_share = 500 * (1_000_000 - COMMISSION) / 1_000_000;
+        let key1_share = 1_000 - key2_share - key3_share;
+        assert_eq!(
+            new_shares(&[(KEY1, key1_share), (KEY2, key2_share), (KEY3, key3_share)]),
+            reward_shares(
+                U512::from(1_000),
+                &stakes,
+                &delegations,
+                &weights,
+                COMMISSION
+            )
+        );
+    }
+
+    #[test]
+    fn test_delegations_count_towards_weighted_stakes() {
+        let stakes = new_stakes(&[(KEY1, 100), (KEY3, 100)]);
+        let delegations = new_delegations(&[(KEY1, KEY3, 100)]);
+        let weights = new_weights(&[(KEY1, 1), (KEY3, 1)]);
+
+        // KEY1 earns 200 with its pooled stake, 100 of which go to KEY3 minus the commission.
+        let delegator_share = 100 * (1_000_000 - COMMISSION) / 1_000_000;
+        assert_eq!(
+            new_shares(&[(KEY1, 200 - delegator_share), (KEY3, 100 + delegator_share)]),
+            reward_shares(U512::from(300), &stakes, &delegations, &weights, COMMISSION)
+        );
+    }
+
+    #[test]
+    fn test_no_delegator_shares_without_weight() {
+        let stakes = new_stakes(&[(KEY1, 100), (KEY2, 100)]);
+        let delegations = new_delegations(&[(KEY2, KEY3, 100)]);
+        let weights = new_weights(&[(KEY1, 1)]);
+        assert_eq!(
+            new_shares(&[(KEY1, 1_000)]),
+            reward_shares(
+                U512::from(1_000),
+                &stakes,
+                &delegations,
+                &weights,
+                COMMISSION
+            )
+        );
+    }
+
+    #[test]
+    fn test_no_shares_of_empty_purse() {
+        let stakes = new_stakes(&[(KEY1, 100)]);
+        let delegations = Delegations::default();
+        let weights = new_weights(&[(KEY1, 1)]);
+        assert!(
+            reward_shares(U512::zero(), &stakes, &delegations, &weights, COMMISSION).is_empty()
+        );
+    }
+}
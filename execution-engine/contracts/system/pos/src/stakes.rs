@@ -9,12 +9,10 @@ use base16;
 use contract_ffi::{
     contract_api::runtime,
     key::Key,
-    system_contracts::pos::{Error, Result},
+    system_contracts::pos::{Config, Error, Result},
     value::{account::PublicKey, U512},
 };
 
-use super::{MAX_DECREASE, MAX_INCREASE, MAX_REL_DECREASE, MAX_REL_INCREASE, MAX_SPREAD};
-
 pub trait StakesProvider {
     fn read() -> Result<Stakes>;
     fn write(stakes: &Stakes);
@@ -101,12 +99,19 @@ impl Stakes {
     /// * unbonding the specified amount is not allowed,
     /// * tries to unbond last validator,
     /// * validator was not bonded.
-    pub fn unbond(&mut self, validator: &PublicKey, maybe_amount: Option<U512>) -> Result<U512> {
+    pub fn unbond(
+        &mut self,
+        validator: &PublicKey,
+        maybe_amount: Option<U512>,
+        config: &Config,
+    ) -> Result<U512> {
         let min = self
             .max_without(validator)
             .unwrap_or_else(U512::zero)
-            .saturating_sub(MAX_SPREAD);
-        let max_decrease = MAX_DECREASE.min(self.sum() * MAX_REL_DECREASE / 1_000_000);
+            .saturating_sub(config.max_spread);
+        let max_decrease = config
+            .max_decrease
+            .min(self.sum() * config.max_rel_decrease / 1_000_000);
 
         if let Some(amount) = maybe_amount {
             // The minimum stake value to not violate the maximum spread.
@@ -141,6 +146,27 @@ impl Stakes {
         Ok(stake)
     }
 
+    /// Subtracts `fraction` millionths of the validator's stakes, removing the validator if no
+    /// stakes are left.
+    ///
+    /// Returns the amount that was actually subtracted from the stakes, which is zero if the
+    /// validator was not bonded, or an error if the last validator would be removed.
+    pub fn slash(&mut self, validator: &PublicKey, fraction: u64) -> Result<U512> {
+        let stake = match self.0.get_mut(validator) {
+            Some(stake) => stake,
+            None => return Ok(U512::zero()),
+        };
+        let amount = *stake * U512::from(fraction) / 1_000_000;
+        if amount < *stake {
+            *stake -= amount;
+            return Ok(amount);
+        }
+        if self.0.len() == 1 {
+            return Err(Error::CannotSlashLastValidator);
+        }
+        Ok(self.0.remove(validator).unwrap_or_default())
+    }
+
     /// Adds `amount` to the validator's stakes.
     pub fn bond(&mut self, validator: &PublicKey, amount: U512) {
         self.0
@@ -150,20 +176,27 @@ impl Stakes {
     }
 
     /// Returns an error if bonding the specified amount is not allowed.
-    pub fn validate_bonding(&self, validator: &PublicKey, amount: U512) -> Result<()> {
+    pub fn validate_bonding(
+        &self,
+        validator: &PublicKey,
+        amount: U512,
+        config: &Config,
+    ) -> Result<()> {
         let max = self
             .min_without(validator)
             .unwrap_or(U512::MAX)
-            .saturating_add(MAX_SPREAD);
+            .saturating_add(config.max_spread);
         let min = self
             .max_without(validator)
             .unwrap_or_else(U512::zero)
-            .saturating_sub(MAX_SPREAD);
+            .saturating_sub(config.max_spread);
         let stake = self.0.get(validator).map(|s| *s + amount).unwrap_or(amount);
         if stake > max || stake < min {
             return Err(Error::SpreadTooHigh);
         }
-        let max_increase = MAX_INCREASE.min(self.sum() * MAX_REL_INCREASE / 1_000_000);
+        let max_increase = config
+            .max_increase
+            .min(self.sum() * config.max_rel_increase / 1_000_000);
         if (stake.is_zero() && amount > min.saturating_add(max_increase))
             || (!stake.is_zero() && amount > max_increase)
         {
@@ -203,7 +236,7 @@ impl Stakes {
 #[cfg(test)]
 mod tests {
     use contract_ffi::{
-        system_contracts::pos::Error,
+        system_contracts::pos::{Config, Error},
         value::{account::PublicKey, U512},
     };
 
@@ -226,7 +259,7 @@ mod tests {
         let mut stakes = new_stakes(&[(KEY2, 100)]);
         assert_eq!(
             Ok(()),
-            stakes.validate_bonding(&PublicKey::new(KEY1), U512::from(5))
+            stakes.validate_bonding(&PublicKey::new(KEY1), U512::from(5), &Config::default())
         );
         stakes.bond(&PublicKey::new(KEY1), U512::from(5));
         assert_eq!(new_stakes(&[(KEY1, 5), (KEY2, 100)]), stakes);
@@ -237,7 +270,7 @@ mod tests {
         let mut stakes = new_stakes(&[(KEY1, 50), (KEY2, 100)]);
         assert_eq!(
             Ok(()),
-            stakes.validate_bonding(&PublicKey::new(KEY1), U512::from(4))
+            stakes.validate_bonding(&PublicKey::new(KEY1), U512::from(4), &Config::default())
         );
         stakes.bond(&PublicKey::new(KEY1), U512::from(4));
         assert_eq!(new_stakes(&[(KEY1, 54), (KEY2, 100)]), stakes);
@@ -245,13 +278,15 @@ mod tests {
 
     #[test]
     fn test_bond_too_much_rel() {
+        let config = Config::default();
         let stakes = new_stakes(&[(KEY1, 1_000), (KEY2, 1_000)]);
         let total = 1_000 + 1_000;
         assert_eq!(
             Err(Error::BondTooLarge),
             stakes.validate_bonding(
                 &PublicKey::new(KEY1),
-                U512::from(crate::MAX_REL_INCREASE * total / 1_000_000 + 1)
+                U512::from(config.max_rel_increase * total / 1_000_000 + 1),
+                &config
             ),
             "Successfully bonded more than the maximum amount."
         );
@@ -259,7 +294,8 @@ mod tests {
             Ok(()),
             stakes.validate_bonding(
                 &PublicKey::new(KEY1),
-                U512::from(crate::MAX_REL_INCREASE * total / 1_000_000)
+                U512::from(config.max_rel_increase * total / 1_000_000),
+                &config
             ),
             "Failed to bond the maximum amount."
         );
@@ -270,7 +306,7 @@ mod tests {
         let mut stakes = new_stakes(&[(KEY1, 5), (KEY2, 100)]);
         assert_eq!(
             Ok(U512::from(5)),
-            stakes.unbond(&PublicKey::new(KEY1), None)
+            stakes.unbond(&PublicKey::new(KEY1), None, &Config::default())
         );
         assert_eq!(new_stakes(&[(KEY2, 100)]), stakes);
     }
@@ -280,7 +316,7 @@ mod tests {
         let mut stakes = new_stakes(&[(KEY1, 5)]);
         assert_eq!(
             Err(Error::CannotUnbondLastValidator),
-            stakes.unbond(&PublicKey::new(KEY1), None)
+            stakes.unbond(&PublicKey::new(KEY1), None, &Config::default())
         );
     }
 
@@ -289,28 +325,74 @@ mod tests {
         let mut stakes = new_stakes(&[(KEY1, 50)]);
         assert_eq!(
             Ok(U512::from(4)),
-            stakes.unbond(&PublicKey::new(KEY1), Some(U512::from(4)))
+            stakes.unbond(
+                &PublicKey::new(KEY1),
+                Some(U512::from(4)),
+                &Config::default()
+            )
         );
         assert_eq!(new_stakes(&[(KEY1, 46)]), stakes);
     }
 
+    #[test]
+    fn test_slash() {
+        let mut stakes = new_stakes(&[(KEY1, 50), (KEY2, 100)]);
+        assert_eq!(
+            Ok(U512::from(25)),
+            stakes.slash(&PublicKey::new(KEY2), 250_000)
+        );
+        assert_eq!(new_stakes(&[(KEY1, 50), (KEY2, 75)]), stakes);
+    }
+
+    #[test]
+    fn test_slash_all() {
+        let mut stakes = new_stakes(&[(KEY1, 50), (KEY2, 100)]);
+        assert_eq!(
+            Ok(U512::from(50)),
+            stakes.slash(&PublicKey::new(KEY1), 1_000_000)
+        );
+        assert_eq!(new_stakes(&[(KEY2, 100)]), stakes);
+    }
+
+    #[test]
+    fn test_slash_not_bonded() {
+        let mut stakes = new_stakes(&[(KEY2, 100)]);
+        assert_eq!(
+            Ok(U512::zero()),
+            stakes.slash(&PublicKey::new(KEY1), 1_000_000)
+        );
+        assert_eq!(new_stakes(&[(KEY2, 100)]), stakes);
+    }
+
+    #[test]
+    fn test_slash_last_validator() {
+        let mut stakes = new_stakes(&[(KEY1, 5)]);
+        assert_eq!(
+            Err(Error::CannotSlashLastValidator),
+            stakes.slash(&PublicKey::new(KEY1), 1_000_000)
+        );
+    }
+
     #[test]
     fn test_unbond_too_much_rel() {
+        let config = Config::default();
         let mut stakes = new_stakes(&[(KEY1, 999), (KEY2, 1)]);
         let total = 999 + 1;
         assert_eq!(
             Err(Error::UnbondTooLarge),
             stakes.unbond(
                 &PublicKey::new(KEY1),
-                Some(U512::from(crate::MAX_REL_DECREASE * total / 1_000_000 + 1))
+                Some(U512::from(config.max_rel_decrease * total / 1_000_000 + 1)),
+                &config
             ),
             "Successfully unbonded more than the maximum amount."
         );
         assert_eq!(
-            Ok(U512::from(crate::MAX_REL_DECREASE * total / 1_000_000)),
+            Ok(U512::from(config.max_rel_decrease * total / 1_000_000)),
             stakes.unbond(
                 &PublicKey::new(KEY1),
-                Some(U512::from(crate::MAX_REL_DECREASE * total / 1_000_000))
+                Some(U512::from(config.max_rel_decrease * total / 1_000_000)),
+                &config
             ),
             "Failed to unbond the maximum amount."
         );
@@ -17,8 +17,10 @@ extern "C" {
 }
 
 const SUBCALL_NAME: &str = "add_gas";
+const REVERTING_SUBCALL_NAME: &str = "add_gas_and_revert";
 const ADD_GAS_FROM_SESSION: &str = "add-gas-from-session";
 const ADD_GAS_VIA_SUBCALL: &str = "add-gas-via-subcall";
+const ADD_GAS_AND_REVERT_VIA_SUBCALL: &str = "add-gas-and-revert-via-subcall";
 
 enum Args {
     GasAmount = 0,
@@ -36,6 +38,12 @@ pub extern "C" fn add_gas() {
     }
 }
 
+#[no_mangle]
+pub extern "C" fn add_gas_and_revert() {
+    add_gas();
+    runtime::revert(Error::User(0))
+}
+
 #[no_mangle]
 pub extern "C" fn call() {
     let amount: i32 = runtime::get_arg(Args::GasAmount as u32)
@@ -54,6 +62,11 @@ pub extern "C" fn call() {
             let reference = storage::store_function_at_hash(SUBCALL_NAME, BTreeMap::new());
             runtime::call_contract::<_, ()>(reference, (amount,));
         }
+        ADD_GAS_AND_REVERT_VIA_SUBCALL => {
+            let reference =
+                storage::store_function_at_hash(REVERTING_SUBCALL_NAME, BTreeMap::new());
+            runtime::call_contract::<_, ()>(reference, (amount,));
+        }
         _ => runtime::revert(Error::InvalidArgument),
     }
 }
@@ -0,0 +1,33 @@
+#![no_std]
+
+use contract_ffi::{
+    contract_api::{runtime, Error},
+    unwrap_or_revert::UnwrapOrRevert,
+    value::CLValue,
+};
+
+const EVENT_TOPIC: &str = "counter";
+
+#[repr(u16)]
+enum CustomError {
+    RevertAfterEmit = 0,
+}
+
+#[no_mangle]
+pub extern "C" fn call() {
+    let event_count: u64 = runtime::get_arg(0)
+        .unwrap_or_revert_with(Error::MissingArgument)
+        .unwrap_or_revert_with(Error::InvalidArgument);
+    let should_revert: bool = runtime::get_arg(1)
+        .unwrap_or_revert_with(Error::MissingArgument)
+        .unwrap_or_revert_with(Error::InvalidArgument);
+
+    for i in 0..event_count {
+        let value = CLValue::from_t(i).unwrap_or_revert();
+        runtime::emit_event(EVENT_TOPIC, value);
+    }
+
+    if should_revert {
+        runtime::revert(Error::User(CustomError::RevertAfterEmit as u16));
+    }
+}
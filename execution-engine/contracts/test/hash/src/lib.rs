@@ -0,0 +1,33 @@
+#![no_std]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+
+use contract_ffi::{
+    contract_api::{crypto, runtime, Error},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+
+#[no_mangle]
+pub extern "C" fn call() {
+    let algorithm_index: u32 = runtime::get_arg(0)
+        .unwrap_or_revert_with(Error::MissingArgument)
+        .unwrap_or_revert_with(Error::InvalidArgument);
+    let input: Vec<u8> = runtime::get_arg(1)
+        .unwrap_or_revert_with(Error::MissingArgument)
+        .unwrap_or_revert_with(Error::InvalidArgument);
+    let expected_digest: Vec<u8> = runtime::get_arg(2)
+        .unwrap_or_revert_with(Error::MissingArgument)
+        .unwrap_or_revert_with(Error::InvalidArgument);
+
+    let algorithm = crypto::HashAlgorithm::try_from(algorithm_index).unwrap_or_revert();
+    let actual_digest = crypto::hash(algorithm, &input);
+
+    assert_eq!(
+        &actual_digest[..],
+        expected_digest.as_slice(),
+        "actual digest not expected digest"
+    );
+}
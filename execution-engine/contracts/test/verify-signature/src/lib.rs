@@ -0,0 +1,38 @@
+#![no_std]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+
+use contract_ffi::{
+    contract_api::{crypto, runtime, Error},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+
+#[repr(u16)]
+enum CustomError {
+    InvalidSignature = 0,
+}
+
+#[no_mangle]
+pub extern "C" fn call() {
+    let scheme_index: u32 = runtime::get_arg(0)
+        .unwrap_or_revert_with(Error::MissingArgument)
+        .unwrap_or_revert_with(Error::InvalidArgument);
+    let public_key: Vec<u8> = runtime::get_arg(1)
+        .unwrap_or_revert_with(Error::MissingArgument)
+        .unwrap_or_revert_with(Error::InvalidArgument);
+    let message: Vec<u8> = runtime::get_arg(2)
+        .unwrap_or_revert_with(Error::MissingArgument)
+        .unwrap_or_revert_with(Error::InvalidArgument);
+    let signature: Vec<u8> = runtime::get_arg(3)
+        .unwrap_or_revert_with(Error::MissingArgument)
+        .unwrap_or_revert_with(Error::InvalidArgument);
+
+    let scheme = crypto::SignatureScheme::try_from(scheme_index).unwrap_or_revert();
+
+    if !crypto::verify_signature(scheme, &public_key, &message, &signature) {
+        runtime::revert(Error::User(CustomError::InvalidSignature as u16));
+    }
+}
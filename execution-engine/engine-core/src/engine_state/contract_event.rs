@@ -0,0 +1,36 @@
+use contract_ffi::{key::Key, value::CLValue};
+
+/// An event emitted by a contract through the `emit_event` host function.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ContractEvent {
+    emitter: Key,
+    topic: String,
+    value: CLValue,
+}
+
+impl ContractEvent {
+    pub fn new(emitter: Key, topic: String, value: CLValue) -> Self {
+        ContractEvent {
+            emitter,
+            topic,
+            value,
+        }
+    }
+
+    /// The base key of the context which emitted this event.
+    pub fn emitter(&self) -> Key {
+        self.emitter
+    }
+
+    pub fn topic(&self) -> &str {
+        &self.topic
+    }
+
+    pub fn value(&self) -> &CLValue {
+        &self.value
+    }
+
+    pub fn destructure(self) -> (Key, String, CLValue) {
+        (self.emitter, self.topic, self.value)
+    }
+}
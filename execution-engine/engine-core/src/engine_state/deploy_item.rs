@@ -14,6 +14,8 @@ pub struct DeployItem {
     pub gas_price: GasPrice,
     pub authorization_keys: BTreeSet<PublicKey>,
     pub deploy_hash: DeployHash,
+    /// Whether to record a trace of the calls made while executing the deploy.
+    pub trace: bool,
 }
 
 impl DeployItem {
@@ -33,6 +35,12 @@ impl DeployItem {
             gas_price,
             authorization_keys,
             deploy_hash,
+            trace: false,
         }
     }
+
+    /// Enables or disables recording an execution trace for this deploy.
+    pub fn with_trace(self, trace: bool) -> Self {
+        DeployItem { trace, ..self }
+    }
 }
@@ -0,0 +1,56 @@
+use contract_ffi::key::Key;
+use engine_shared::{newtypes::Blake2bHash, stored_value::StoredValue};
+use engine_storage::trie_store::operations::Difference;
+
+pub enum DiffStateResult {
+    RootNotFound(Blake2bHash),
+    Success {
+        /// The changes found, in ascending order of serialized key.
+        differences: Vec<Difference<Key, StoredValue>>,
+        /// The serialized key at which the next page starts, if there are more changes.
+        next_start: Option<Vec<u8>>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffStateRequest {
+    old_state_hash: Blake2bHash,
+    new_state_hash: Blake2bHash,
+    start: Vec<u8>,
+    limit: usize,
+}
+
+impl DiffStateRequest {
+    pub fn new(
+        old_state_hash: Blake2bHash,
+        new_state_hash: Blake2bHash,
+        start: Vec<u8>,
+        limit: usize,
+    ) -> Self {
+        DiffStateRequest {
+            old_state_hash,
+            new_state_hash,
+            start,
+            limit,
+        }
+    }
+
+    pub fn old_state_hash(&self) -> Blake2bHash {
+        self.old_state_hash
+    }
+
+    pub fn new_state_hash(&self) -> Blake2bHash {
+        self.new_state_hash
+    }
+
+    /// Changes to keys whose serialized form is less than this are skipped.  Empty for the first
+    /// page.
+    pub fn start(&self) -> &[u8] {
+        &self.start
+    }
+
+    /// The maximum number of changes to return.
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+}
@@ -17,6 +17,13 @@ pub enum Error {
     InvalidProtocolVersion(ProtocolVersion),
     #[fail(display = "Invalid upgrade config")]
     InvalidUpgradeConfig,
+    #[fail(display = "Invalid cost table: {} cost must not be zero", _0)]
+    ZeroCost(String),
+    #[fail(
+        display = "Invalid proof of stake config: {} is zero or out of range",
+        _0
+    )]
+    InvalidProofOfStakeConfig(String),
     #[fail(display = "Wasm preprocessing error: {}", _0)]
     WasmPreprocessingError(engine_wasm_prep::PreprocessingError),
     #[fail(display = "Wasm serialization error: {:?}", _0)]
@@ -35,6 +42,11 @@ pub enum Error {
     FinalizationError,
     #[fail(display = "Missing system contract association: {}", _0)]
     MissingSystemContractError(String),
+    #[fail(
+        display = "Unexpected key type for system contract association: {}",
+        _0
+    )]
+    UnexpectedSystemContractKeyType(String),
     #[fail(display = "Serialization error: {}", _0)]
     SerializationError(bytesrepr::Error),
     #[fail(display = "Mint error: {}", _0)]
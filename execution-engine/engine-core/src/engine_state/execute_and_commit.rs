@@ -0,0 +1,107 @@
+use std::{collections::HashMap, fmt};
+
+use contract_ffi::{
+    bytesrepr,
+    key::Key,
+    value::{account::PublicKey, U512},
+};
+use engine_shared::{newtypes::Blake2bHash, transform::TypeMismatch};
+use engine_storage::global_state::CommitResult;
+
+use crate::{
+    engine_state::{execution_result::ExecutionResult, Error},
+    execution,
+    tracking_copy::AddResult,
+};
+
+/// The result of executing a sequence of deploys, each against the post-state of the ones before
+/// it, and committing their combined effects.
+pub enum ExecuteAndCommitResult {
+    RootNotFound,
+    KeyNotFound(Key),
+    TypeMismatch(TypeMismatch),
+    Serialization(bytesrepr::Error),
+    Success {
+        execution_results: Vec<ExecutionResult>,
+        post_state_hash: Blake2bHash,
+        bonded_validators: HashMap<PublicKey, U512>,
+    },
+    /// The effects of the last of `execution_results` could not be carried over to the deploys
+    /// after it, so those were not executed and nothing was committed.
+    EffectsNotApplied {
+        execution_results: Vec<ExecutionResult>,
+        error: Error,
+    },
+}
+
+impl fmt::Display for ExecuteAndCommitResult {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match self {
+            ExecuteAndCommitResult::RootNotFound => write!(f, "Root not found"),
+            ExecuteAndCommitResult::KeyNotFound(key) => write!(f, "Key not found: {}", key),
+            ExecuteAndCommitResult::TypeMismatch(type_mismatch) => {
+                write!(f, "Type mismatch: {:?}", type_mismatch)
+            }
+            ExecuteAndCommitResult::Serialization(error) => {
+                write!(f, "Serialization error: {:?}", error)
+            }
+            ExecuteAndCommitResult::Success {
+                execution_results,
+                post_state_hash,
+                bonded_validators,
+            } => write!(
+                f,
+                "Success: {} deploys, post_state_hash: {}, bonded_validators: {:?}",
+                execution_results.len(),
+                post_state_hash,
+                bonded_validators
+            ),
+            ExecuteAndCommitResult::EffectsNotApplied {
+                execution_results,
+                error,
+            } => write!(
+                f,
+                "Effects of deploy {} not applied: {}",
+                execution_results.len(),
+                error
+            ),
+        }
+    }
+}
+
+impl ExecuteAndCommitResult {
+    pub fn from_commit_result(
+        commit_result: CommitResult,
+        execution_results: Vec<ExecutionResult>,
+    ) -> Self {
+        match commit_result {
+            CommitResult::RootNotFound => ExecuteAndCommitResult::RootNotFound,
+            CommitResult::KeyNotFound(key) => ExecuteAndCommitResult::KeyNotFound(key),
+            CommitResult::TypeMismatch(type_mismatch) => {
+                ExecuteAndCommitResult::TypeMismatch(type_mismatch)
+            }
+            CommitResult::Serialization(error) => ExecuteAndCommitResult::Serialization(error),
+            CommitResult::Success {
+                state_root,
+                bonded_validators,
+            } => ExecuteAndCommitResult::Success {
+                execution_results,
+                post_state_hash: state_root,
+                bonded_validators,
+            },
+        }
+    }
+
+    /// Converts a failure to carry one deploy's effects over to the next into an error.  Returns
+    /// `None` if the effects were applied successfully.
+    pub(crate) fn add_result_error(add_result: AddResult) -> Option<Error> {
+        match add_result {
+            AddResult::Success => None,
+            AddResult::KeyNotFound(key) => Some(execution::Error::KeyNotFound(key).into()),
+            AddResult::TypeMismatch(type_mismatch) => {
+                Some(execution::Error::TypeMismatch(type_mismatch).into())
+            }
+            AddResult::Serialization(error) => Some(error.into()),
+        }
+    }
+}
@@ -1,16 +1,26 @@
 use contract_ffi::key::Key;
 use engine_shared::{additive_map::AdditiveMap, transform::Transform};
 
-use super::op::Op;
+use super::{contract_event::ContractEvent, op::Op};
 
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct ExecutionEffect {
     pub ops: AdditiveMap<Key, Op>,
     pub transforms: AdditiveMap<Key, Transform>,
+    /// Events emitted during execution, in the order they were emitted.
+    pub events: Vec<ContractEvent>,
 }
 
 impl ExecutionEffect {
     pub fn new(ops: AdditiveMap<Key, Op>, transforms: AdditiveMap<Key, Transform>) -> Self {
-        ExecutionEffect { ops, transforms }
+        ExecutionEffect {
+            ops,
+            transforms,
+            events: Vec::new(),
+        }
+    }
+
+    pub fn with_events(self, events: Vec<ContractEvent>) -> Self {
+        ExecutionEffect { events, ..self }
     }
 }
@@ -1,4 +1,6 @@
-use super::{error, execution_effect::ExecutionEffect, op::Op, CONV_RATE};
+use super::{
+    contract_event::ContractEvent, error, execution_effect::ExecutionEffect, op::Op, CONV_RATE,
+};
 use contract_ffi::{key::Key, value::CLValue};
 use engine_shared::{
     additive_map::AdditiveMap, gas::Gas, motes::Motes, newtypes::CorrelationId,
@@ -250,6 +252,7 @@ impl ExecutionResultBuilder {
         let cost = self.total_cost();
         let mut ops = AdditiveMap::new();
         let mut transforms = AdditiveMap::new();
+        let mut events = Vec::new();
 
         let mut ret: ExecutionResult = ExecutionResult::Success {
             effect: Default::default(),
@@ -261,7 +264,7 @@ impl ExecutionResultBuilder {
                 if result.is_failure() {
                     return Ok(result);
                 } else {
-                    Self::add_effects(&mut ops, &mut transforms, result.effect());
+                    Self::add_effects(&mut ops, &mut transforms, &mut events, result.effect());
                 }
             }
             None => return Err(ExecutionResultBuilderError::MissingPaymentExecutionResult),
@@ -274,7 +277,7 @@ impl ExecutionResultBuilder {
                 if result.is_failure() {
                     ret = result.with_cost(cost);
                 } else {
-                    Self::add_effects(&mut ops, &mut transforms, result.effect());
+                    Self::add_effects(&mut ops, &mut transforms, &mut events, result.effect());
                 }
             }
             None => return Err(ExecutionResultBuilderError::MissingSessionExecutionResult),
@@ -288,14 +291,15 @@ impl ExecutionResultBuilder {
                         error::Error::FinalizationError,
                     ));
                 } else {
-                    Self::add_effects(&mut ops, &mut transforms, result.effect());
+                    Self::add_effects(&mut ops, &mut transforms, &mut events, result.effect());
                 }
             }
             None => return Err(ExecutionResultBuilderError::MissingFinalizeExecutionResult),
         }
 
         // Remove redundant writes to allow more opportunity to commute
-        let reduced_effect = Self::reduce_identity_writes(ops, transforms, reader, correlation_id);
+        let reduced_effect = Self::reduce_identity_writes(ops, transforms, reader, correlation_id)
+            .with_events(events);
 
         Ok(ret.with_effect(reduced_effect))
     }
@@ -303,6 +307,7 @@ impl ExecutionResultBuilder {
     fn add_effects(
         ops: &mut AdditiveMap<Key, Op>,
         transforms: &mut AdditiveMap<Key, Transform>,
+        events: &mut Vec<ContractEvent>,
         effect: &ExecutionEffect,
     ) {
         for (k, op) in effect.ops.iter() {
@@ -311,6 +316,7 @@ impl ExecutionResultBuilder {
         for (k, t) in effect.transforms.iter() {
             transforms.insert_add(*k, t.clone())
         }
+        events.extend(effect.events.iter().cloned());
     }
 
     /// In the case we are writing the same value as was there originally,
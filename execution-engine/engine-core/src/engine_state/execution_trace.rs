@@ -0,0 +1,170 @@
+use contract_ffi::{execution::Phase, key::Key, value::CLValue};
+use engine_shared::gas::Gas;
+
+/// How a call recorded in an [`ExecutionTrace`] finished.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CallOutcome {
+    /// The call finished, returning the value passed to `ret` if it called it.
+    Returned(Option<CLValue>),
+    /// The call failed with the given error.
+    Failed(String),
+}
+
+/// A single invocation of a contract, including the host functions and other contracts it called.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CallFrame {
+    key: Key,
+    args: Vec<CLValue>,
+    host_calls: Vec<String>,
+    calls: Vec<CallFrame>,
+    gas_used: Gas,
+    outcome: CallOutcome,
+}
+
+impl CallFrame {
+    pub fn new(
+        key: Key,
+        args: Vec<CLValue>,
+        host_calls: Vec<String>,
+        calls: Vec<CallFrame>,
+        gas_used: Gas,
+        outcome: CallOutcome,
+    ) -> Self {
+        CallFrame {
+            key,
+            args,
+            host_calls,
+            calls,
+            gas_used,
+            outcome,
+        }
+    }
+
+    /// Creates a frame for a call to the contract at `key` which has not made any calls yet.
+    pub(crate) fn start(key: Key, args: Vec<CLValue>) -> Self {
+        CallFrame::new(
+            key,
+            args,
+            Vec::new(),
+            Vec::new(),
+            Gas::default(),
+            CallOutcome::Returned(None),
+        )
+    }
+
+    pub(crate) fn record_host_call(&mut self, name: &str) {
+        self.host_calls.push(name.to_string());
+    }
+
+    pub(crate) fn record_call(&mut self, call_frame: CallFrame) {
+        self.calls.push(call_frame);
+    }
+
+    pub(crate) fn finish(self, gas_used: Gas, outcome: CallOutcome) -> Self {
+        CallFrame {
+            gas_used,
+            outcome,
+            ..self
+        }
+    }
+
+    /// The key of the contract, or of the account for session and payment code, which was called.
+    pub fn key(&self) -> Key {
+        self.key
+    }
+
+    pub fn args(&self) -> &[CLValue] {
+        &self.args
+    }
+
+    /// Names of the host functions called by this contract, in the order they were called.
+    pub fn host_calls(&self) -> &[String] {
+        &self.host_calls
+    }
+
+    /// Frames of the contracts called by this contract, in the order they were called.
+    pub fn calls(&self) -> &[CallFrame] {
+        &self.calls
+    }
+
+    /// The gas used by this call, including the gas used by the contracts it called.
+    pub fn gas_used(&self) -> Gas {
+        self.gas_used
+    }
+
+    pub fn outcome(&self) -> &CallOutcome {
+        &self.outcome
+    }
+
+    #[allow(clippy::type_complexity)]
+    pub fn destructure(
+        self,
+    ) -> (
+        Key,
+        Vec<CLValue>,
+        Vec<String>,
+        Vec<CallFrame>,
+        Gas,
+        CallOutcome,
+    ) {
+        (
+            self.key,
+            self.args,
+            self.host_calls,
+            self.calls,
+            self.gas_used,
+            self.outcome,
+        )
+    }
+}
+
+/// The tree of calls made while executing each phase of a deploy.
+///
+/// A phase which was not executed, e.g. the session of a deploy whose payment failed, has no frame.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ExecutionTrace {
+    payment: Option<CallFrame>,
+    session: Option<CallFrame>,
+    finalize: Option<CallFrame>,
+}
+
+impl ExecutionTrace {
+    pub fn new(
+        payment: Option<CallFrame>,
+        session: Option<CallFrame>,
+        finalize: Option<CallFrame>,
+    ) -> Self {
+        ExecutionTrace {
+            payment,
+            session,
+            finalize,
+        }
+    }
+
+    /// Records `call_frame` as the root of the call tree of `phase`.  Calls made in the system
+    /// phase are not part of a deploy and are not recorded.
+    pub(crate) fn record(&mut self, phase: Phase, call_frame: CallFrame) {
+        match phase {
+            Phase::Payment => self.payment = Some(call_frame),
+            Phase::Session => self.session = Some(call_frame),
+            Phase::FinalizePayment => self.finalize = Some(call_frame),
+            Phase::System => {}
+        }
+    }
+
+    pub fn payment(&self) -> Option<&CallFrame> {
+        self.payment.as_ref()
+    }
+
+    pub fn session(&self) -> Option<&CallFrame> {
+        self.session.as_ref()
+    }
+
+    pub fn finalize(&self) -> Option<&CallFrame> {
+        self.finalize.as_ref()
+    }
+
+    pub fn destructure(self) -> (Option<CallFrame>, Option<CallFrame>, Option<CallFrame>) {
+        (self.payment, self.session, self.finalize)
+    }
+}
@@ -9,17 +9,22 @@ use rand::{
 use contract_ffi::{
     bytesrepr,
     key::Key,
+    system_contracts::pos,
     value::{account::PublicKey, ProtocolVersion, U512},
 };
 use engine_shared::{motes::Motes, newtypes::Blake2bHash, transform::TypeMismatch};
 use engine_storage::global_state::CommitResult;
-use engine_wasm_prep::wasm_costs::WasmCosts;
+use engine_wasm_prep::{
+    host_function_costs::{HostFunctionCosts, HOST_FUNCTION_COSTS_SERIALIZED_LENGTH},
+    wasm_costs::WasmCosts,
+};
 
 use crate::engine_state::execution_effect::ExecutionEffect;
 
 pub const POS_BONDING_PURSE: &str = "pos_bonding_purse";
 pub const POS_PAYMENT_PURSE: &str = "pos_payment_purse";
 pub const POS_REWARDS_PURSE: &str = "pos_rewards_purse";
+pub const POS_CONFIG: &str = "pos_config";
 
 pub enum GenesisResult {
     RootNotFound,
@@ -121,6 +126,8 @@ pub struct GenesisConfig {
     proof_of_stake_installer_bytes: Vec<u8>,
     accounts: Vec<GenesisAccount>,
     wasm_costs: WasmCosts,
+    host_function_costs: HostFunctionCosts,
+    proof_of_stake_config: pos::Config,
 }
 
 impl GenesisConfig {
@@ -132,6 +139,8 @@ impl GenesisConfig {
         proof_of_stake_installer_bytes: Vec<u8>,
         accounts: Vec<GenesisAccount>,
         wasm_costs: WasmCosts,
+        host_function_costs: HostFunctionCosts,
+        proof_of_stake_config: pos::Config,
     ) -> Self {
         GenesisConfig {
             name,
@@ -141,6 +150,8 @@ impl GenesisConfig {
             proof_of_stake_installer_bytes,
             accounts,
             wasm_costs,
+            host_function_costs,
+            proof_of_stake_config,
         }
     }
 
@@ -168,6 +179,14 @@ impl GenesisConfig {
         self.wasm_costs
     }
 
+    pub fn host_function_costs(&self) -> HostFunctionCosts {
+        self.host_function_costs
+    }
+
+    pub fn proof_of_stake_config(&self) -> pos::Config {
+        self.proof_of_stake_config
+    }
+
     pub fn get_bonded_validators(&self) -> impl Iterator<Item = (PublicKey, Motes)> + '_ {
         let zero = Motes::zero();
         self.accounts.iter().filter_map(move |genesis_account| {
@@ -220,6 +239,32 @@ impl Distribution<GenesisConfig> for Standard {
             max_stack_height: rng.gen(),
             opcodes_mul: rng.gen(),
             opcodes_div: rng.gen(),
+            verify_ed25519: rng.gen(),
+            verify_secp256k1: rng.gen(),
+            hash_blake2b: rng.gen(),
+            hash_sha256: rng.gen(),
+            hash_keccak256: rng.gen(),
+        };
+
+        let host_function_costs = {
+            let bytes: Vec<u8> = iter::repeat(())
+                .map(|_| rng.gen())
+                .take(HOST_FUNCTION_COSTS_SERIALIZED_LENGTH)
+                .collect();
+            bytesrepr::deserialize(bytes).expect("should deserialize host function costs")
+        };
+
+        let proof_of_stake_config = pos::Config {
+            bond_delay: rng.gen(),
+            unbond_delay: rng.gen(),
+            max_bond_len: rng.gen(),
+            max_unbond_len: rng.gen(),
+            max_spread: U512::from(rng.gen::<u64>()),
+            max_increase: U512::from(rng.gen::<u64>()),
+            max_decrease: U512::from(rng.gen::<u64>()),
+            max_rel_increase: rng.gen(),
+            max_rel_decrease: rng.gen(),
+            delegation_commission: rng.gen(),
         };
 
         GenesisConfig {
@@ -230,6 +275,8 @@ impl Distribution<GenesisConfig> for Standard {
             proof_of_stake_installer_bytes,
             accounts,
             wasm_costs,
+            host_function_costs,
+            proof_of_stake_config,
         }
     }
 }
@@ -0,0 +1,51 @@
+use contract_ffi::key::Key;
+use engine_shared::{newtypes::Blake2bHash, stored_value::StoredValue};
+
+pub enum ListKeysResult {
+    RootNotFound,
+    Success {
+        /// The key-value pairs found, in ascending order of serialized key.
+        entries: Vec<(Key, StoredValue)>,
+        /// The serialized key at which the next page starts, if there are more pairs with the
+        /// requested prefix.
+        next_start: Option<Vec<u8>>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ListKeysRequest {
+    state_hash: Blake2bHash,
+    prefix: Vec<u8>,
+    start: Vec<u8>,
+    limit: usize,
+}
+
+impl ListKeysRequest {
+    pub fn new(state_hash: Blake2bHash, prefix: Vec<u8>, start: Vec<u8>, limit: usize) -> Self {
+        ListKeysRequest {
+            state_hash,
+            prefix,
+            start,
+            limit,
+        }
+    }
+
+    pub fn state_hash(&self) -> Blake2bHash {
+        self.state_hash
+    }
+
+    /// Only keys whose serialized form starts with this prefix are listed.
+    pub fn prefix(&self) -> &[u8] {
+        &self.prefix
+    }
+
+    /// Keys whose serialized form is less than this are skipped.  Empty for the first page.
+    pub fn start(&self) -> &[u8] {
+        &self.start
+    }
+
+    /// The maximum number of key-value pairs to list.
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+}
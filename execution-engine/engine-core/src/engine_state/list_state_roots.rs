@@ -0,0 +1,31 @@
+use engine_shared::newtypes::Blake2bHash;
+use engine_storage::state_root_metadata::StateRootMetadata;
+
+pub struct ListStateRootsResult {
+    /// The state roots found, with how each was produced, in ascending order of state root.
+    pub state_roots: Vec<(Blake2bHash, StateRootMetadata)>,
+    /// The state root at which the next page starts, if there are more state roots.
+    pub next_start: Option<Blake2bHash>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ListStateRootsRequest {
+    start: Vec<u8>,
+    limit: usize,
+}
+
+impl ListStateRootsRequest {
+    pub fn new(start: Vec<u8>, limit: usize) -> Self {
+        ListStateRootsRequest { start, limit }
+    }
+
+    /// State roots whose serialized form is less than this are skipped.  Empty for the first page.
+    pub fn start(&self) -> &[u8] {
+        &self.start
+    }
+
+    /// The maximum number of state roots to return.
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+}
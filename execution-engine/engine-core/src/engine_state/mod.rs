@@ -1,12 +1,19 @@
+pub mod contract_event;
 pub mod deploy_item;
+pub mod diff_state;
 pub mod engine_config;
 mod error;
 pub mod executable_deploy_item;
+pub mod execute_and_commit;
 pub mod execution_effect;
 pub mod execution_result;
+pub mod execution_trace;
 pub mod genesis;
+pub mod list_keys;
+pub mod list_state_roots;
 pub mod op;
 pub mod query;
+pub mod step;
 pub mod system_contract_cache;
 pub mod upgrade;
 pub mod utils;
@@ -30,7 +37,7 @@ use contract_ffi::{
     uref::{AccessRights, URef, UREF_ADDR_LENGTH},
     value::{
         account::{PublicKey, PurseId},
-        ProtocolVersion, U512,
+        CLValue, ProtocolVersion, U512,
     },
 };
 use engine_shared::{
@@ -43,16 +50,28 @@ use engine_shared::{
     transform::Transform,
 };
 use engine_storage::{
-    global_state::{CommitResult, StateProvider, StateReader},
+    global_state::{CommitResult, StatePrefixReader, StateProvider, StateReader},
     protocol_data::ProtocolData,
+    trie::Trie,
+    trie_store::operations::{DiffResult, MissingTrieKeys},
+};
+use engine_wasm_prep::{
+    host_function_costs::{HostFunctionCosts, DEFAULT_HOST_FUNCTION_COST},
+    wasm_costs::WasmCosts,
+    Preprocessor,
 };
-use engine_wasm_prep::{wasm_costs::WasmCosts, Preprocessor};
 
 use self::{
     deploy_item::DeployItem,
     executable_deploy_item::ExecutableDeployItem,
+    execute_and_commit::ExecuteAndCommitResult,
+    execution_effect::ExecutionEffect,
     execution_result::{ExecutionResult, ForcedTransferResult},
-    genesis::{GenesisAccount, GenesisConfig, GenesisResult, POS_PAYMENT_PURSE, POS_REWARDS_PURSE},
+    execution_trace::ExecutionTrace,
+    genesis::{
+        GenesisAccount, GenesisConfig, GenesisResult, POS_CONFIG, POS_PAYMENT_PURSE,
+        POS_REWARDS_PURSE,
+    },
     system_contract_cache::SystemContractCache,
 };
 pub use self::{
@@ -61,8 +80,12 @@ pub use self::{
 };
 use crate::{
     engine_state::{
+        diff_state::{DiffStateRequest, DiffStateResult},
         error::Error::MissingSystemContractError,
+        list_keys::{ListKeysRequest, ListKeysResult},
+        list_state_roots::{ListStateRootsRequest, ListStateRootsResult},
         query::{QueryRequest, QueryResult},
+        step::StepResult,
         upgrade::{UpgradeConfig, UpgradeResult},
     },
     execution::{self, AddressGenerator, Executor, MINT_NAME, POS_NAME},
@@ -79,6 +102,9 @@ pub const SYSTEM_ACCOUNT_ADDR: [u8; 32] = [0u8; 32];
 
 const GENESIS_INITIAL_BLOCKTIME: u64 = 0;
 const MINT_METHOD_NAME: &str = "mint";
+const STEP_METHOD_NAME: &str = "step";
+const DISTRIBUTE_REWARDS_METHOD_NAME: &str = "distribute_rewards";
+const SLASH_METHOD_NAME: &str = "slash";
 
 #[derive(Debug)]
 pub struct EngineState<S> {
@@ -105,6 +131,10 @@ where
         &self.config
     }
 
+    pub fn state(&self) -> &S {
+        &self.state
+    }
+
     pub fn wasm_costs(
         &self,
         protocol_version: ProtocolVersion,
@@ -115,6 +145,16 @@ where
         }
     }
 
+    pub fn host_function_costs(
+        &self,
+        protocol_version: ProtocolVersion,
+    ) -> Result<Option<HostFunctionCosts>, Error> {
+        match self.get_protocol_data(protocol_version)? {
+            Some(protocol_data) => Ok(Some(*protocol_data.host_function_costs())),
+            None => Ok(None),
+        }
+    }
+
     pub fn get_protocol_data(
         &self,
         protocol_version: ProtocolVersion,
@@ -141,6 +181,16 @@ where
         let initial_root_hash = self.state.empty_root();
         let protocol_version = genesis_config.protocol_version();
         let wasm_costs = genesis_config.wasm_costs();
+        let host_function_costs = genesis_config.host_function_costs();
+        if let Some(name) = wasm_costs.zero_crypto_cost() {
+            return Err(Error::ZeroCost(name.to_string()));
+        }
+        if let Some(name) = host_function_costs.zero_cost() {
+            return Err(Error::ZeroCost(format!("{} host function", name)));
+        }
+        if let Some(name) = genesis_config.proof_of_stake_config().invalid_limit() {
+            return Err(Error::InvalidProofOfStakeConfig(name.to_string()));
+        }
         let preprocessor = Preprocessor::new(wasm_costs);
 
         // Spec #3: Create "virtual system account" object.
@@ -177,11 +227,13 @@ where
             let name: &[u8] = genesis_config.name().as_bytes();
             let timestamp: &[u8] = &genesis_config.timestamp().to_le_bytes();
             let wasm_costs_bytes: &[u8] = &wasm_costs.into_bytes()?;
+            let host_function_costs_bytes: &[u8] = &host_function_costs.into_bytes()?;
             let bytes: Vec<u8> = {
                 let mut ret = Vec::new();
                 ret.extend_from_slice(name);
                 ret.extend_from_slice(timestamp);
                 ret.extend_from_slice(wasm_costs_bytes);
+                ret.extend_from_slice(host_function_costs_bytes);
                 ret
             };
             Blake2bHash::new(&bytes)
@@ -226,8 +278,8 @@ where
             )?
         };
 
-        // Spec #7: Execute pos installer wasm code, passing the initially bonded validators as an
-        // argument
+        // Spec #7: Execute pos installer wasm code, passing the initially bonded validators and the
+        // proof of stake config as arguments
         let proof_of_stake_reference: URef = {
             let proof_of_stake_installer_module = {
                 let bytes = genesis_config.proof_of_stake_installer_bytes();
@@ -240,7 +292,8 @@ where
                     .get_bonded_validators()
                     .map(|(k, v)| (k, v.value()))
                     .collect();
-                let args = (mint_reference, bonded_validators);
+                let proof_of_stake_config = genesis_config.proof_of_stake_config();
+                let args = (mint_reference, bonded_validators, proof_of_stake_config);
                 ArgsParser::parse(args)
                     .expect("args should convert to `Vec<CLValue>`")
                     .into_bytes()
@@ -256,6 +309,7 @@ where
             // Constructs a partial protocol data with already known urefs to pass the validation
             // step
             let partial_protocol_data = ProtocolData::new(
+                Default::default(),
                 Default::default(),
                 mint_reference,
                 // This is used as unknown key
@@ -283,7 +337,12 @@ where
         };
 
         // Spec #2: Associate given CostTable with given ProtocolVersion.
-        let protocol_data = ProtocolData::new(wasm_costs, mint_reference, proof_of_stake_reference);
+        let protocol_data = ProtocolData::new(
+            wasm_costs,
+            host_function_costs,
+            mint_reference,
+            proof_of_stake_reference,
+        );
 
         self.state
             .put_protocol_data(protocol_version, &protocol_data)
@@ -414,10 +473,12 @@ where
 
         let commit_result = self
             .state
-            .commit(
+            .commit_and_register(
                 correlation_id,
                 initial_root_hash,
                 effects.transforms.to_owned(),
+                protocol_version,
+                blocktime,
             )
             .map_err(Into::into)?;
 
@@ -471,10 +532,39 @@ where
             Some(new_wasm_costs) => new_wasm_costs,
             None => *current_protocol_data.wasm_costs(),
         };
+        if let Some(name) = new_wasm_costs.zero_crypto_cost() {
+            return Err(Error::ZeroCost(name.to_string()));
+        }
+
+        // resolve host function costs for new protocol version
+        let new_host_function_costs = match upgrade_config.host_function_costs() {
+            Some(new_host_function_costs) => new_host_function_costs,
+            // Host functions are free in protocol data stored before they were charged, and are
+            // charged the default cost from the first upgrade which leaves their costs unchanged.
+            None if current_protocol_data
+                .host_function_costs()
+                .zero_cost()
+                .is_some() =>
+            {
+                HostFunctionCosts::uniform(DEFAULT_HOST_FUNCTION_COST)
+            }
+            None => *current_protocol_data.host_function_costs(),
+        };
+        if let Some(name) = new_host_function_costs.zero_cost() {
+            return Err(Error::ZeroCost(format!("{} host function", name)));
+        }
+
+        if let Some(name) = upgrade_config
+            .proof_of_stake_config()
+            .and_then(|config| config.invalid_limit())
+        {
+            return Err(Error::InvalidProofOfStakeConfig(name.to_string()));
+        }
 
         // 3.1.2.2 persist wasm CostTable
         let new_protocol_data = ProtocolData::new(
             new_wasm_costs,
+            new_host_function_costs,
             current_protocol_data.mint(),
             current_protocol_data.proof_of_stake(),
         );
@@ -527,7 +617,7 @@ where
                     ret
                 };
 
-                let blocktime = BlockTime::default();
+                let blocktime = upgrade_config.block_time();
 
                 let deploy_hash = {
                     // seeds address generator w/ protocol version
@@ -570,15 +660,59 @@ where
             }
         }
 
+        // overwrite the proof of stake config stored under the contract's named keys, leaving the
+        // contract itself in place; contracts installed before the config existed get a new key
+        if let Some(proof_of_stake_config) = upgrade_config.proof_of_stake_config() {
+            let proof_of_stake_reference = new_protocol_data.proof_of_stake();
+            let mut proof_of_stake_contract = tracking_copy
+                .borrow_mut()
+                .get_contract(correlation_id, Key::from(proof_of_stake_reference))?;
+            let config_key = match proof_of_stake_contract.named_keys().get(POS_CONFIG) {
+                Some(key @ Key::URef(_)) => key.normalize(),
+                Some(_) => {
+                    return Err(Error::UnexpectedSystemContractKeyType(
+                        POS_CONFIG.to_string(),
+                    ))
+                }
+                None => {
+                    // seeded separately from the upgrade installer's address generator so the
+                    // new uref can't collide with one the installer created
+                    let address = {
+                        let mut bytes: Vec<u8> = new_protocol_version.value().into_bytes()?;
+                        bytes.extend_from_slice(POS_CONFIG.as_bytes());
+                        let mut generator =
+                            AddressGenerator::new(Blake2bHash::new(&bytes).into(), Phase::System);
+                        generator.create_address()
+                    };
+                    let config_uref = URef::new(address, AccessRights::READ_ADD_WRITE);
+                    proof_of_stake_contract
+                        .named_keys_mut()
+                        .insert(POS_CONFIG.to_string(), Key::URef(config_uref));
+                    tracking_copy.borrow_mut().write(
+                        Key::from(proof_of_stake_reference).normalize(),
+                        StoredValue::Contract(proof_of_stake_contract),
+                    );
+                    Key::URef(config_uref).normalize()
+                }
+            };
+            let config_value = CLValue::from_t(proof_of_stake_config)
+                .map_err(|error| Error::ExecError(error.into()))?;
+            tracking_copy
+                .borrow_mut()
+                .write(config_key, StoredValue::CLValue(config_value));
+        }
+
         let effects = tracking_copy.borrow().effect();
 
         // commit
         let commit_result = self
             .state
-            .commit(
+            .commit_and_register(
                 correlation_id,
                 pre_state_hash,
                 effects.transforms.to_owned(),
+                new_protocol_version,
+                upgrade_config.block_time(),
             )
             .map_err(Into::into)?;
 
@@ -586,6 +720,212 @@ where
         Ok(UpgradeResult::from_commit_result(commit_result, effects))
     }
 
+    /// Runs the proof of stake contract's `step` as the system account at `blocktime`, followed
+    /// by its `distribute_rewards` unless `reward_weights` is empty, then commits their effects on
+    /// top of `prestate_hash`.
+    ///
+    /// This is meant to be called once at the end of every block, so that the bonding and
+    /// unbonding requests which became due are processed without waiting for a validator to bond
+    /// or unbond, and the fees paid in the block are passed on to the validators.
+    pub fn run_step(
+        &self,
+        correlation_id: CorrelationId,
+        protocol_version: ProtocolVersion,
+        prestate_hash: Blake2bHash,
+        blocktime: BlockTime,
+        reward_weights: BTreeMap<PublicKey, u64>,
+    ) -> Result<StepResult, Error>
+    where
+        Error: From<S::Error>,
+    {
+        let mut proof_of_stake_calls = vec![(
+            STEP_METHOD_NAME,
+            ArgsParser::parse((STEP_METHOD_NAME,))
+                .expect("args should convert to `Vec<CLValue>`")
+                .into_bytes()?,
+        )];
+        if !reward_weights.is_empty() {
+            proof_of_stake_calls.push((
+                DISTRIBUTE_REWARDS_METHOD_NAME,
+                ArgsParser::parse((DISTRIBUTE_REWARDS_METHOD_NAME, reward_weights))
+                    .expect("args should convert to `Vec<CLValue>`")
+                    .into_bytes()?,
+            ));
+        }
+
+        let effect = match self.exec_proof_of_stake_system_calls(
+            correlation_id,
+            protocol_version,
+            prestate_hash,
+            blocktime,
+            proof_of_stake_calls,
+        )? {
+            Some(effect) => effect,
+            None => return Ok(StepResult::RootNotFound),
+        };
+
+        let commit_result = self.apply_effect(
+            correlation_id,
+            protocol_version,
+            prestate_hash,
+            blocktime,
+            effect.transforms.clone(),
+        )?;
+
+        Ok(StepResult::from_commit_result(commit_result, effect))
+    }
+
+    /// Runs the proof of stake contract's `slash` as the system account at `blocktime`, then
+    /// commits its effects on top of `prestate_hash`.
+    ///
+    /// Each of the `validators` loses `fraction` millionths of its stakes and of the stakes
+    /// delegated to it, and all of its pending bonding and unbonding requests.  The slashed motes
+    /// are moved to the rewards purse, so the bonded validators of a successful commit no longer
+    /// include those which lost all their stakes, unless that would leave none bonded.
+    pub fn run_slash(
+        &self,
+        correlation_id: CorrelationId,
+        protocol_version: ProtocolVersion,
+        prestate_hash: Blake2bHash,
+        blocktime: BlockTime,
+        validators: Vec<PublicKey>,
+        fraction: u64,
+    ) -> Result<CommitResult, Error>
+    where
+        Error: From<S::Error>,
+    {
+        let proof_of_stake_calls = vec![(
+            SLASH_METHOD_NAME,
+            ArgsParser::parse((SLASH_METHOD_NAME, validators, fraction))
+                .expect("args should convert to `Vec<CLValue>`")
+                .into_bytes()?,
+        )];
+
+        let effect = match self.exec_proof_of_stake_system_calls(
+            correlation_id,
+            protocol_version,
+            prestate_hash,
+            blocktime,
+            proof_of_stake_calls,
+        )? {
+            Some(effect) => effect,
+            None => return Ok(CommitResult::RootNotFound),
+        };
+
+        self.apply_effect(
+            correlation_id,
+            protocol_version,
+            prestate_hash,
+            blocktime,
+            effect.transforms,
+        )
+    }
+
+    /// Runs the given methods of the proof of stake contract one after another as the system
+    /// account at `blocktime`, on top of `prestate_hash`.  Each call is given as the method name
+    /// and the serialized args.
+    ///
+    /// Returns the combined effects of the calls, or `None` if `prestate_hash` is not found.
+    fn exec_proof_of_stake_system_calls(
+        &self,
+        correlation_id: CorrelationId,
+        protocol_version: ProtocolVersion,
+        prestate_hash: Blake2bHash,
+        blocktime: BlockTime,
+        proof_of_stake_calls: Vec<(&str, Vec<u8>)>,
+    ) -> Result<Option<ExecutionEffect>, Error>
+    where
+        Error: From<S::Error>,
+    {
+        let tracking_copy = match self.tracking_copy(prestate_hash)? {
+            Some(tracking_copy) => Rc::new(RefCell::new(tracking_copy)),
+            None => return Ok(None),
+        };
+
+        let protocol_data = match self.state.get_protocol_data(protocol_version)? {
+            Some(protocol_data) => protocol_data,
+            None => return Err(Error::InvalidProtocolVersion(protocol_version)),
+        };
+
+        let proof_of_stake_reference = protocol_data.proof_of_stake();
+        let proof_of_stake_module = match self.system_contract_cache.get(&proof_of_stake_reference)
+        {
+            Some(module) => module,
+            None => {
+                let proof_of_stake_contract = tracking_copy
+                    .borrow_mut()
+                    .get_contract(correlation_id, Key::URef(proof_of_stake_reference))?;
+                let module = engine_wasm_prep::deserialize(proof_of_stake_contract.bytes())?;
+                self.system_contract_cache
+                    .insert(proof_of_stake_reference, module.clone());
+                module
+            }
+        };
+
+        // execute as system account
+        let system_account = {
+            let key = Key::Account(SYSTEM_ACCOUNT_ADDR);
+            match tracking_copy.borrow_mut().read(correlation_id, &key) {
+                Ok(Some(StoredValue::Account(account))) => account,
+                Ok(_) => panic!("system account must exist"),
+                Err(error) => return Err(Error::ExecError(error.into())),
+            }
+        };
+
+        let authorization_keys = {
+            let mut ret = BTreeSet::new();
+            ret.insert(PublicKey::new(SYSTEM_ACCOUNT_ADDR));
+            ret
+        };
+
+        let base_key = Key::from(proof_of_stake_reference);
+        // system calls have no gas limit; approximating with MAX
+        let gas_limit = Gas::new(U512::from(std::u64::MAX));
+
+        for (method_name, proof_of_stake_args) in proof_of_stake_calls {
+            // The PoS keys may have changed during the previous call, so we need to look them up
+            // again from the tracking copy
+            let proof_of_stake_contract = tracking_copy
+                .borrow_mut()
+                .get_contract(correlation_id, Key::URef(proof_of_stake_reference))?;
+            let mut proof_of_stake_keys = proof_of_stake_contract.named_keys().to_owned();
+
+            // seeds address generator w/ pre state hash and method name
+            let deploy_hash = {
+                let mut bytes = prestate_hash.to_vec();
+                bytes.extend_from_slice(method_name.as_bytes());
+                Blake2bHash::new(&bytes).into()
+            };
+            let system_contract_cache = SystemContractCache::clone(&self.system_contract_cache);
+
+            let execution_result = Executor.exec_direct(
+                proof_of_stake_module.clone(),
+                proof_of_stake_args,
+                &mut proof_of_stake_keys,
+                base_key,
+                &system_account,
+                authorization_keys.clone(),
+                blocktime,
+                deploy_hash,
+                gas_limit,
+                protocol_version,
+                correlation_id,
+                Rc::clone(&tracking_copy),
+                Phase::System,
+                protocol_data,
+                system_contract_cache,
+                None,
+            );
+
+            if let ExecutionResult::Failure { error, .. } = execution_result {
+                return Err(error);
+            }
+        }
+
+        let effect = tracking_copy.borrow().effect();
+        Ok(Some(effect))
+    }
+
     pub fn tracking_copy(
         &self,
         hash: Blake2bHash,
@@ -608,21 +948,181 @@ where
 
         let mut mut_tracking_copy = tracking_copy.borrow_mut();
 
-        Ok(mut_tracking_copy
+        let query_result: QueryResult = mut_tracking_copy
             .query(correlation_id, query_request.key(), query_request.path())
             .map_err(|err| Error::ExecError(err.into()))?
-            .into())
+            .into();
+
+        if !query_request.include_proofs() {
+            return Ok(query_result);
+        }
+
+        let proofs = mut_tracking_copy
+            .query_proofs(correlation_id, query_request.key(), query_request.path())
+            .map_err(|err| Error::ExecError(err.into()))?;
+
+        Ok(query_result.with_proofs(proofs))
+    }
+
+    /// Lists up to `list_keys_request.limit()` of the key-value pairs whose serialized keys start
+    /// with the requested prefix, beginning at the requested start.
+    pub fn list_keys(
+        &self,
+        correlation_id: CorrelationId,
+        list_keys_request: ListKeysRequest,
+    ) -> Result<ListKeysResult, Error>
+    where
+        Error: From<S::Error>,
+    {
+        let reader = match self.state.checkout(list_keys_request.state_hash())? {
+            Some(reader) => reader,
+            None => return Ok(ListKeysResult::RootNotFound),
+        };
+
+        let mut iter = reader.iter_prefix(
+            correlation_id,
+            list_keys_request.prefix(),
+            list_keys_request.start(),
+        )?;
+
+        let mut entries = Vec::new();
+        for result in iter.by_ref().take(list_keys_request.limit()) {
+            entries.push(result?);
+        }
+
+        let next_start = match iter.next() {
+            Some(result) => {
+                let (key, _value) = result?;
+                Some(key.to_bytes()?)
+            }
+            None => None,
+        };
+
+        Ok(ListKeysResult::Success {
+            entries,
+            next_start,
+        })
+    }
+
+    /// Returns up to `diff_state_request.limit()` of the keys whose values differ between the
+    /// requested old and new post states, with their old and new values, beginning at the
+    /// requested start.
+    pub fn diff_state(
+        &self,
+        correlation_id: CorrelationId,
+        diff_state_request: DiffStateRequest,
+    ) -> Result<DiffStateResult, Error>
+    where
+        Error: From<S::Error>,
+    {
+        // One more difference than the limit is requested to find where the next page starts.
+        let diff_result = self.state.diff(
+            correlation_id,
+            diff_state_request.old_state_hash(),
+            diff_state_request.new_state_hash(),
+            diff_state_request.start(),
+            diff_state_request.limit().saturating_add(1),
+        )?;
+
+        let mut differences = match diff_result {
+            DiffResult::Success(differences) => differences,
+            DiffResult::RootNotFound(state_hash) => {
+                return Ok(DiffStateResult::RootNotFound(state_hash))
+            }
+        };
+
+        let next_start = if differences.len() > diff_state_request.limit() {
+            match differences.pop() {
+                Some(difference) => Some(difference.key().to_bytes()?),
+                None => None,
+            }
+        } else {
+            None
+        };
+
+        Ok(DiffStateResult::Success {
+            differences,
+            next_start,
+        })
+    }
+
+    /// Returns the trie element stored under `trie_key`, if any.
+    pub fn get_trie(&self, trie_key: &Blake2bHash) -> Result<Option<Trie<Key, StoredValue>>, Error>
+    where
+        Error: From<S::Error>,
+    {
+        let maybe_trie = self.state.get_trie(trie_key)?;
+        Ok(maybe_trie)
+    }
+
+    /// Stores `tries` in global state in a single transaction and returns their hashes.
+    pub fn put_tries(&self, tries: &[Trie<Key, StoredValue>]) -> Result<Vec<Blake2bHash>, Error>
+    where
+        Error: From<S::Error>,
+    {
+        let trie_hashes = self.state.put_tries(tries)?;
+        Ok(trie_hashes)
     }
 
-    pub fn get_module(
+    /// Returns the hashes of up to `limit` trie elements reachable from `trie_keys` which are not
+    /// yet in global state, along with the hashes left unexplored once the limit was reached.  A
+    /// state root is complete once this returns nothing for it.
+    pub fn missing_trie_keys(
         &self,
-        tracking_copy: Rc<RefCell<TrackingCopy<<S as StateProvider>::Reader>>>,
+        correlation_id: CorrelationId,
+        trie_keys: &[Blake2bHash],
+        limit: usize,
+    ) -> Result<MissingTrieKeys, Error>
+    where
+        Error: From<S::Error>,
+    {
+        let missing = self
+            .state
+            .missing_trie_keys(correlation_id, trie_keys, limit)?;
+        Ok(missing)
+    }
+
+    /// Returns up to `list_state_roots_request.limit()` of the state roots produced by commits,
+    /// genesis and upgrades, with how each was produced, in ascending order of state root
+    /// beginning at the requested start.
+    pub fn list_state_roots(
+        &self,
+        list_state_roots_request: ListStateRootsRequest,
+    ) -> Result<ListStateRootsResult, Error>
+    where
+        Error: From<S::Error>,
+    {
+        // One more state root than the limit is requested to find where the next page starts.
+        let mut state_roots = self.state.list_state_roots(
+            list_state_roots_request.start(),
+            list_state_roots_request.limit().saturating_add(1),
+        )?;
+
+        let next_start = if state_roots.len() > list_state_roots_request.limit() {
+            state_roots.pop().map(|(state_root, _)| state_root)
+        } else {
+            None
+        };
+
+        Ok(ListStateRootsResult {
+            state_roots,
+            next_start,
+        })
+    }
+
+    pub fn get_module<R>(
+        &self,
+        tracking_copy: Rc<RefCell<TrackingCopy<R>>>,
         deploy_item: &ExecutableDeployItem,
         account: &Account,
         correlation_id: CorrelationId,
         preprocessor: &Preprocessor,
         protocol_version: &ProtocolVersion,
-    ) -> Result<Module, error::Error> {
+    ) -> Result<Module, error::Error>
+    where
+        R: StateReader<Key, StoredValue>,
+        R::Error: Into<execution::Error>,
+    {
         let stored_contract_key = match deploy_item {
             ExecutableDeployItem::ModuleBytes { module_bytes, .. } => {
                 let module = preprocessor.preprocess(&module_bytes)?;
@@ -714,6 +1214,10 @@ where
         Ok(module)
     }
 
+    /// Executes `deploy_item` on top of `prestate_hash`.
+    ///
+    /// If tracing is enabled on `deploy_item`, an [`ExecutionTrace`] of the calls made in each
+    /// phase of the deploy is returned alongside its result.
     #[allow(clippy::too_many_arguments)]
     pub fn deploy(
         &self,
@@ -724,34 +1228,206 @@ where
         prestate_hash: Blake2bHash,
         blocktime: BlockTime,
         deploy_item: DeployItem,
-    ) -> Result<ExecutionResult, RootNotFound> {
-        // spec: https://casperlabs.atlassian.net/wiki/spaces/EN/pages/123404576/Payment+code+execution+specification
-
-        let session = deploy_item.session;
-        let payment = deploy_item.payment;
-        let address = Key::Account(deploy_item.address.value());
-        let authorization_keys = deploy_item.authorization_keys;
-        let deploy_hash = deploy_item.deploy_hash;
+    ) -> Result<(ExecutionResult, Option<ExecutionTrace>), RootNotFound> {
+        let mut trace = if deploy_item.trace {
+            Some(ExecutionTrace::default())
+        } else {
+            None
+        };
 
         // Create tracking copy (which functions as a deploy context)
         // validation_spec_2: prestate_hash check
+        let tracking_copy = match self.tracking_copy(prestate_hash) {
+            Err(error) => return Ok((ExecutionResult::precondition_failure(error), trace)),
+            Ok(None) => return Err(RootNotFound(prestate_hash)),
+            Ok(Some(tracking_copy)) => Rc::new(RefCell::new(tracking_copy)),
+        };
+
+        let execution_result = self.deploy_with_tracking_copy(
+            correlation_id,
+            executor,
+            preprocessor,
+            protocol_version,
+            blocktime,
+            deploy_item,
+            tracking_copy,
+            trace.as_mut(),
+        );
+
+        Ok((execution_result, trace))
+    }
+
+    /// Executes `deploy_items` in order, each against the state produced by the ones before it,
+    /// then commits their combined effects on top of `prestate_hash`.
+    ///
+    /// Deploys which could not be parsed are given as errors and result in precondition
+    /// failures, as they would in [`EngineState::deploy`].
+    #[allow(clippy::too_many_arguments)]
+    pub fn run_execute_and_commit(
+        &self,
+        correlation_id: CorrelationId,
+        executor: &Executor,
+        preprocessor: &Preprocessor,
+        protocol_version: ProtocolVersion,
+        prestate_hash: Blake2bHash,
+        blocktime: BlockTime,
+        deploy_items: Vec<Result<DeployItem, Error>>,
+    ) -> Result<ExecuteAndCommitResult, Error>
+    where
+        Error: From<S::Error>,
+    {
+        let mut tracking_copy = match self.tracking_copy(prestate_hash)? {
+            Some(tracking_copy) => tracking_copy,
+            None => return Ok(ExecuteAndCommitResult::RootNotFound),
+        };
+
+        let mut execution_results = Vec::with_capacity(deploy_items.len());
+
+        for maybe_deploy_item in deploy_items {
+            let execution_result = match maybe_deploy_item {
+                Ok(deploy_item) => {
+                    let deploy_tracking_copy = Rc::new(RefCell::new(tracking_copy.fork()));
+                    self.deploy_with_tracking_copy(
+                        correlation_id,
+                        executor,
+                        preprocessor,
+                        protocol_version,
+                        blocktime,
+                        deploy_item,
+                        deploy_tracking_copy,
+                        None,
+                    )
+                }
+                Err(error) => ExecutionResult::precondition_failure(error),
+            };
+
+            let transforms = execution_result.effect().transforms.clone();
+            execution_results.push(execution_result);
+
+            let maybe_error = match tracking_copy.apply_effects(correlation_id, transforms) {
+                Ok(add_result) => ExecuteAndCommitResult::add_result_error(add_result),
+                Err(error) => Some(error.into()),
+            };
+            if let Some(error) = maybe_error {
+                return Ok(ExecuteAndCommitResult::EffectsNotApplied {
+                    execution_results,
+                    error,
+                });
+            }
+        }
+
+        let effects = tracking_copy.effect().transforms;
+        let commit_result = self.apply_effect(
+            correlation_id,
+            protocol_version,
+            prestate_hash,
+            blocktime,
+            effects,
+        )?;
+
+        Ok(ExecuteAndCommitResult::from_commit_result(
+            commit_result,
+            execution_results,
+        ))
+    }
+
+    /// Executes only the session code of `deploy_item` on top of `prestate_hash`, with at most
+    /// `gas_limit` available to it, to estimate the gas needed to run the deploy.
+    ///
+    /// The payment code and the proof of stake contract's `finalize_payment` are not run, so the
+    /// account is not required to have any balance and no payment or rewards purses are touched.
+    /// The effects of the session code are returned but are never committed.
+    #[allow(clippy::too_many_arguments)]
+    pub fn estimate_gas(
+        &self,
+        correlation_id: CorrelationId,
+        executor: &Executor,
+        preprocessor: &Preprocessor,
+        protocol_version: ProtocolVersion,
+        prestate_hash: Blake2bHash,
+        blocktime: BlockTime,
+        deploy_item: DeployItem,
+        gas_limit: Gas,
+    ) -> Result<ExecutionResult, RootNotFound> {
         let tracking_copy = match self.tracking_copy(prestate_hash) {
             Err(error) => return Ok(ExecutionResult::precondition_failure(error)),
             Ok(None) => return Err(RootNotFound(prestate_hash)),
             Ok(Some(tracking_copy)) => Rc::new(RefCell::new(tracking_copy)),
         };
 
-        // Get addr bytes from `address` (which is actually a Key)
-        // validation_spec_3: account validity
-        let account_addr = match address.as_account() {
-            Some(account_addr) => account_addr,
-            None => {
-                return Ok(ExecutionResult::precondition_failure(
-                    error::Error::AuthorizationError,
-                ))
+        let mut session = deploy_item.session;
+        let account_addr = deploy_item.address.value();
+        let authorization_keys = deploy_item.authorization_keys;
+
+        let account = match self.get_authorized_account(
+            correlation_id,
+            account_addr,
+            &authorization_keys,
+            &tracking_copy,
+        ) {
+            Ok(account) => account,
+            Err(error) => return Ok(ExecutionResult::precondition_failure(error)),
+        };
+
+        let session_module = match self.get_module(
+            Rc::clone(&tracking_copy),
+            &session,
+            &account,
+            correlation_id,
+            preprocessor,
+            &protocol_version,
+        ) {
+            Ok(module) => module,
+            Err(error) => return Ok(ExecutionResult::precondition_failure(error)),
+        };
+
+        let protocol_data = match self.state.get_protocol_data(protocol_version) {
+            Ok(Some(protocol_data)) => protocol_data,
+            Ok(None) => {
+                let error = Error::InvalidProtocolVersion(protocol_version);
+                return Ok(ExecutionResult::precondition_failure(error));
+            }
+            Err(error) => {
+                return Ok(ExecutionResult::precondition_failure(Error::ExecError(
+                    error.into(),
+                )));
             }
         };
 
+        let system_contract_cache = SystemContractCache::clone(&self.system_contract_cache);
+
+        Ok(executor.exec(
+            session_module,
+            session.take_args(),
+            Key::Account(account_addr),
+            &account,
+            authorization_keys,
+            blocktime,
+            deploy_item.deploy_hash,
+            gas_limit,
+            protocol_version,
+            correlation_id,
+            tracking_copy,
+            Phase::Session,
+            protocol_data,
+            system_contract_cache,
+            None,
+        ))
+    }
+
+    /// Returns the account at `account_addr` if `authorization_keys` authorize it and carry
+    /// enough weight to deploy from it.
+    fn get_authorized_account<R>(
+        &self,
+        correlation_id: CorrelationId,
+        account_addr: [u8; 32],
+        authorization_keys: &BTreeSet<PublicKey>,
+        tracking_copy: &Rc<RefCell<TrackingCopy<R>>>,
+    ) -> Result<Account, Error>
+    where
+        R: StateReader<Key, StoredValue>,
+        R::Error: Into<execution::Error>,
+    {
         // Get account from tracking copy
         // validation_spec_3: account validity
         let account: Account = match tracking_copy
@@ -759,30 +1435,68 @@ where
             .get_account(correlation_id, account_addr)
         {
             Ok(account) => account,
-            Err(_) => {
-                return Ok(ExecutionResult::precondition_failure(
-                    error::Error::AuthorizationError,
-                ));
-            }
+            Err(_) => return Err(Error::AuthorizationError),
         };
 
         // Authorize using provided authorization keys
         // validation_spec_3: account validity
-        if !account.can_authorize(&authorization_keys) {
-            return Ok(ExecutionResult::precondition_failure(
-                crate::engine_state::error::Error::AuthorizationError,
-            ));
+        if !account.can_authorize(authorization_keys) {
+            return Err(Error::AuthorizationError);
         }
 
         // Check total key weight against deploy threshold
         // validation_spec_4: deploy validity
-        if !account.can_deploy_with(&authorization_keys) {
-            return Ok(ExecutionResult::precondition_failure(
-                // TODO?:this doesn't happen in execution any longer, should error variant be moved
-                execution::Error::DeploymentAuthorizationFailure.into(),
-            ));
+        if !account.can_deploy_with(authorization_keys) {
+            // TODO?:this doesn't happen in execution any longer, should error variant be moved
+            return Err(execution::Error::DeploymentAuthorizationFailure.into());
         }
 
+        Ok(account)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn deploy_with_tracking_copy<R>(
+        &self,
+        correlation_id: CorrelationId,
+        executor: &Executor,
+        preprocessor: &Preprocessor,
+        protocol_version: ProtocolVersion,
+        blocktime: BlockTime,
+        deploy_item: DeployItem,
+        tracking_copy: Rc<RefCell<TrackingCopy<R>>>,
+        mut trace: Option<&mut ExecutionTrace>,
+    ) -> ExecutionResult
+    where
+        R: StateReader<Key, StoredValue>,
+        R::Error: Into<execution::Error>,
+    {
+        // spec: https://casperlabs.atlassian.net/wiki/spaces/EN/pages/123404576/Payment+code+execution+specification
+
+        let session = deploy_item.session;
+        let payment = deploy_item.payment;
+        let address = Key::Account(deploy_item.address.value());
+        let authorization_keys = deploy_item.authorization_keys;
+        let deploy_hash = deploy_item.deploy_hash;
+
+        // Get addr bytes from `address` (which is actually a Key)
+        // validation_spec_3: account validity
+        let account_addr = match address.as_account() {
+            Some(account_addr) => account_addr,
+            None => return ExecutionResult::precondition_failure(error::Error::AuthorizationError),
+        };
+
+        // validation_spec_3: account validity
+        // validation_spec_4: deploy validity
+        let account = match self.get_authorized_account(
+            correlation_id,
+            account_addr,
+            &authorization_keys,
+            &tracking_copy,
+        ) {
+            Ok(account) => account,
+            Err(error) => return ExecutionResult::precondition_failure(error),
+        };
+
         // Create session code `A` from provided session bytes
         // validation_spec_1: valid wasm bytes
         let session_module = match self.get_module(
@@ -795,7 +1509,7 @@ where
         ) {
             Ok(module) => module,
             Err(error) => {
-                return Ok(ExecutionResult::precondition_failure(error));
+                return ExecutionResult::precondition_failure(error);
             }
         };
 
@@ -804,12 +1518,10 @@ where
             Ok(Some(protocol_data)) => protocol_data,
             Ok(None) => {
                 let error = Error::InvalidProtocolVersion(protocol_version);
-                return Ok(ExecutionResult::precondition_failure(error));
+                return ExecutionResult::precondition_failure(error);
             }
             Err(error) => {
-                return Ok(ExecutionResult::precondition_failure(Error::ExecError(
-                    error.into(),
-                )));
+                return ExecutionResult::precondition_failure(Error::ExecError(error.into()));
             }
         };
 
@@ -828,13 +1540,13 @@ where
                 .get_contract(correlation_id, Key::URef(mint_reference))
             {
                 Ok(contract) => contract,
-                Err(error) => return Ok(ExecutionResult::precondition_failure(error.into())),
+                Err(error) => return ExecutionResult::precondition_failure(error.into()),
             };
 
             if !self.system_contract_cache.has(&mint_reference) {
                 let module = match engine_wasm_prep::deserialize(mint_contract.bytes()) {
                     Ok(module) => module,
-                    Err(error) => return Ok(ExecutionResult::precondition_failure(error.into())),
+                    Err(error) => return ExecutionResult::precondition_failure(error.into()),
                 };
                 self.system_contract_cache.insert(mint_reference, module);
             }
@@ -855,7 +1567,7 @@ where
         {
             Ok(contract) => contract,
             Err(error) => {
-                return Ok(ExecutionResult::precondition_failure(error.into()));
+                return ExecutionResult::precondition_failure(error.into());
             }
         };
 
@@ -868,7 +1580,7 @@ where
                 match proof_of_stake_contract.named_keys().get(POS_REWARDS_PURSE) {
                     Some(key) => *key,
                     None => {
-                        return Ok(ExecutionResult::precondition_failure(Error::DeployError));
+                        return ExecutionResult::precondition_failure(Error::DeployError);
                     }
                 };
 
@@ -879,7 +1591,7 @@ where
             ) {
                 Ok(key) => key,
                 Err(error) => {
-                    return Ok(ExecutionResult::precondition_failure(error.into()));
+                    return ExecutionResult::precondition_failure(error.into());
                 }
             }
         };
@@ -895,7 +1607,7 @@ where
             ) {
                 Ok(key) => key,
                 Err(error) => {
-                    return Ok(ExecutionResult::precondition_failure(error.into()));
+                    return ExecutionResult::precondition_failure(error.into());
                 }
             }
         };
@@ -907,15 +1619,13 @@ where
             .get_purse_balance(correlation_id, account_main_purse_balance_key)
         {
             Ok(balance) => balance,
-            Err(error) => return Ok(ExecutionResult::precondition_failure(error.into())),
+            Err(error) => return ExecutionResult::precondition_failure(error.into()),
         };
 
         // Enforce minimum main purse balance validation
         // validation_spec_5: account main purse minimum balance
         if account_main_purse_balance < max_payment_cost {
-            return Ok(ExecutionResult::precondition_failure(
-                Error::InsufficientPaymentError,
-            ));
+            return ExecutionResult::precondition_failure(Error::InsufficientPaymentError);
         }
 
         // Finalization is executed by system account (currently genesis account)
@@ -949,7 +1659,7 @@ where
             ) {
                 Ok(module) => module,
                 Err(error) => {
-                    return Ok(ExecutionResult::precondition_failure(error));
+                    return ExecutionResult::precondition_failure(error);
                 }
             };
             let system_contract_cache = SystemContractCache::clone(&self.system_contract_cache);
@@ -970,6 +1680,7 @@ where
                 Phase::Payment,
                 protocol_data,
                 system_contract_cache,
+                trace.as_mut().map(|trace| &mut **trace),
             )
         };
 
@@ -983,7 +1694,7 @@ where
             let payment_purse: Key =
                 match proof_of_stake_contract.named_keys().get(POS_PAYMENT_PURSE) {
                     Some(key) => *key,
-                    None => return Ok(ExecutionResult::precondition_failure(Error::DeployError)),
+                    None => return ExecutionResult::precondition_failure(Error::DeployError),
                 };
 
             let purse_balance_key = match tracking_copy.borrow_mut().get_purse_balance_key(
@@ -993,7 +1704,7 @@ where
             ) {
                 Ok(key) => key,
                 Err(error) => {
-                    return Ok(ExecutionResult::precondition_failure(error.into()));
+                    return ExecutionResult::precondition_failure(error.into());
                 }
             };
 
@@ -1003,7 +1714,7 @@ where
             {
                 Ok(balance) => balance,
                 Err(error) => {
-                    return Ok(ExecutionResult::precondition_failure(error.into()));
+                    return ExecutionResult::precondition_failure(error.into());
                 }
             }
         };
@@ -1013,13 +1724,13 @@ where
                 ForcedTransferResult::InsufficientPayment => Error::InsufficientPaymentError,
                 ForcedTransferResult::PaymentFailure => payment_result.take_error().unwrap(),
             };
-            return Ok(ExecutionResult::new_payment_code_error(
+            return ExecutionResult::new_payment_code_error(
                 error,
                 max_payment_cost,
                 account_main_purse_balance,
                 account_main_purse_balance_key,
                 rewards_purse_balance_key,
-            ));
+            );
         }
 
         execution_result_builder.set_payment_execution_result(payment_result);
@@ -1053,6 +1764,7 @@ where
                 Phase::Session,
                 protocol_data,
                 system_contract_cache,
+                trace.as_mut().map(|trace| &mut **trace),
             )
         };
 
@@ -1082,7 +1794,7 @@ where
                             match engine_wasm_prep::deserialize(proof_of_stake_contract.bytes()) {
                                 Ok(module) => module,
                                 Err(error) => {
-                                    return Ok(ExecutionResult::precondition_failure(error.into()))
+                                    return ExecutionResult::precondition_failure(error.into())
                                 }
                             };
                         self.system_contract_cache
@@ -1108,7 +1820,7 @@ where
                 .get_contract(correlation_id, Key::URef(proof_of_stake_reference))
             {
                 Ok(info) => info,
-                Err(error) => return Ok(ExecutionResult::precondition_failure(error.into())),
+                Err(error) => return ExecutionResult::precondition_failure(error.into()),
             };
 
             let mut proof_of_stake_keys = proof_of_stake_contract.named_keys().to_owned();
@@ -1133,6 +1845,7 @@ where
                 Phase::FinalizePayment,
                 protocol_data,
                 system_contract_cache,
+                trace,
             )
         };
 
@@ -1146,20 +1859,30 @@ where
         // NOTE: payment_code_spec_5_a is enforced in execution_result_builder.build()
         // payment_code_spec_6: return properly combined set of transforms and
         // appropriate error
-        Ok(ret)
+        ret
     }
 
+    /// Commits `effects` on top of `pre_state_hash` and records how the resulting state root was
+    /// produced.
     pub fn apply_effect(
         &self,
         correlation_id: CorrelationId,
         protocol_version: ProtocolVersion,
         pre_state_hash: Blake2bHash,
+        blocktime: BlockTime,
         effects: AdditiveMap<Key, Transform>,
     ) -> Result<CommitResult, Error>
     where
         Error: From<S::Error>,
     {
-        match self.state.commit(correlation_id, pre_state_hash, effects)? {
+        let commit_result = self.state.commit_and_register(
+            correlation_id,
+            pre_state_hash,
+            effects,
+            protocol_version,
+            blocktime,
+        )?;
+        match commit_result {
             CommitResult::Success { state_root, .. } => {
                 let bonded_validators =
                     self.get_bonded_validators(correlation_id, protocol_version, state_root)?;
@@ -1172,7 +1895,8 @@ where
         }
     }
 
-    /// Calculates bonded validators at `root_hash` state.
+    /// Calculates bonded validators at `root_hash` state, with their stakes including the stakes
+    /// delegated to them.
     ///
     /// Should only be called with a valid root hash after a successful call to
     /// [`StateProvider::commit`]. Will panic if called with an invalid root hash.
@@ -1205,12 +1929,23 @@ where
             _ => return Err(MissingSystemContractError("proof of stake".to_string())),
         };
 
-        let bonded_validators = contract
+        let mut bonded_validators = contract
             .named_keys()
             .keys()
             .filter_map(|entry| utils::pos_validator_key_name_to_tuple(entry))
             .collect::<HashMap<PublicKey, U512>>();
 
+        // Stakes delegated to a bonded validator count towards its weight.
+        let delegations = contract
+            .named_keys()
+            .keys()
+            .filter_map(|entry| utils::pos_delegation_key_name_to_tuple(entry));
+        for (validator, _delegator, amount) in delegations {
+            if let Some(weight) = bonded_validators.get_mut(&validator) {
+                *weight += amount;
+            }
+        }
+
         Ok(bonded_validators)
     }
 }
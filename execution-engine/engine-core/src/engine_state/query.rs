@@ -1,12 +1,32 @@
 use contract_ffi::key::Key;
 use engine_shared::{newtypes::Blake2bHash, stored_value::StoredValue};
+use engine_storage::trie::merkle_proof::TrieMerkleProof;
 
 use crate::tracking_copy::TrackingCopyQueryResult;
 
 pub enum QueryResult {
     RootNotFound,
-    ValueNotFound(String),
-    Success(StoredValue),
+    ValueNotFound {
+        full_path: String,
+        proofs: Vec<TrieMerkleProof<Key, StoredValue>>,
+    },
+    Success {
+        value: StoredValue,
+        proofs: Vec<TrieMerkleProof<Key, StoredValue>>,
+    },
+}
+
+impl QueryResult {
+    /// Attaches `proofs` to a successful or a not-found result.
+    pub fn with_proofs(self, proofs: Vec<TrieMerkleProof<Key, StoredValue>>) -> Self {
+        match self {
+            QueryResult::RootNotFound => QueryResult::RootNotFound,
+            QueryResult::ValueNotFound { full_path, .. } => {
+                QueryResult::ValueNotFound { full_path, proofs }
+            }
+            QueryResult::Success { value, .. } => QueryResult::Success { value, proofs },
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -14,14 +34,16 @@ pub struct QueryRequest {
     state_hash: Blake2bHash,
     key: Key,
     path: Vec<String>,
+    include_proofs: bool,
 }
 
 impl QueryRequest {
-    pub fn new(state_hash: Blake2bHash, key: Key, path: Vec<String>) -> Self {
+    pub fn new(state_hash: Blake2bHash, key: Key, path: Vec<String>, include_proofs: bool) -> Self {
         QueryRequest {
             state_hash,
             key,
             path,
+            include_proofs,
         }
     }
 
@@ -36,15 +58,24 @@ impl QueryRequest {
     pub fn path(&self) -> &[String] {
         &self.path
     }
+
+    /// Whether Merkle proofs of the values read by the query should be returned.
+    pub fn include_proofs(&self) -> bool {
+        self.include_proofs
+    }
 }
 
 impl From<TrackingCopyQueryResult> for QueryResult {
     fn from(tracking_copy_query_result: TrackingCopyQueryResult) -> Self {
         match tracking_copy_query_result {
-            TrackingCopyQueryResult::ValueNotFound(full_path) => {
-                QueryResult::ValueNotFound(full_path)
-            }
-            TrackingCopyQueryResult::Success(value) => QueryResult::Success(value),
+            TrackingCopyQueryResult::ValueNotFound(full_path) => QueryResult::ValueNotFound {
+                full_path,
+                proofs: Vec::new(),
+            },
+            TrackingCopyQueryResult::Success(value) => QueryResult::Success {
+                value,
+                proofs: Vec::new(),
+            },
         }
     }
 }
@@ -0,0 +1,66 @@
+use std::{collections::HashMap, fmt};
+
+use contract_ffi::{
+    bytesrepr,
+    key::Key,
+    value::{account::PublicKey, U512},
+};
+use engine_shared::{newtypes::Blake2bHash, transform::TypeMismatch};
+use engine_storage::global_state::CommitResult;
+
+use crate::engine_state::execution_effect::ExecutionEffect;
+
+/// The result of running the proof of stake contract's `step` and `distribute_rewards` at the end
+/// of a block and committing their effects.
+pub enum StepResult {
+    RootNotFound,
+    KeyNotFound(Key),
+    TypeMismatch(TypeMismatch),
+    Serialization(bytesrepr::Error),
+    Success {
+        post_state_hash: Blake2bHash,
+        effect: ExecutionEffect,
+        bonded_validators: HashMap<PublicKey, U512>,
+    },
+}
+
+impl fmt::Display for StepResult {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match self {
+            StepResult::RootNotFound => write!(f, "Root not found"),
+            StepResult::KeyNotFound(key) => write!(f, "Key not found: {}", key),
+            StepResult::TypeMismatch(type_mismatch) => {
+                write!(f, "Type mismatch: {:?}", type_mismatch)
+            }
+            StepResult::Serialization(error) => write!(f, "Serialization error: {:?}", error),
+            StepResult::Success {
+                post_state_hash,
+                effect,
+                bonded_validators,
+            } => write!(
+                f,
+                "Success: {} {:?}, bonded_validators: {:?}",
+                post_state_hash, effect, bonded_validators
+            ),
+        }
+    }
+}
+
+impl StepResult {
+    pub fn from_commit_result(commit_result: CommitResult, effect: ExecutionEffect) -> Self {
+        match commit_result {
+            CommitResult::RootNotFound => StepResult::RootNotFound,
+            CommitResult::KeyNotFound(key) => StepResult::KeyNotFound(key),
+            CommitResult::TypeMismatch(type_mismatch) => StepResult::TypeMismatch(type_mismatch),
+            CommitResult::Serialization(error) => StepResult::Serialization(error),
+            CommitResult::Success {
+                state_root,
+                bonded_validators,
+            } => StepResult::Success {
+                post_state_hash: state_root,
+                effect,
+                bonded_validators,
+            },
+        }
+    }
+}
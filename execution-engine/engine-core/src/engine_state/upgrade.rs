@@ -1,9 +1,11 @@
 use std::fmt;
 
-use contract_ffi::{bytesrepr, key::Key, value::ProtocolVersion};
+use contract_ffi::{
+    block_time::BlockTime, bytesrepr, key::Key, system_contracts::pos, value::ProtocolVersion,
+};
 use engine_shared::{newtypes::Blake2bHash, transform::TypeMismatch};
 use engine_storage::global_state::CommitResult;
-use engine_wasm_prep::wasm_costs::WasmCosts;
+use engine_wasm_prep::{host_function_costs::HostFunctionCosts, wasm_costs::WasmCosts};
 
 use crate::engine_state::execution_effect::ExecutionEffect;
 
@@ -60,7 +62,10 @@ pub struct UpgradeConfig {
     upgrade_installer_args: Option<Vec<u8>>,
     upgrade_installer_bytes: Option<Vec<u8>>,
     wasm_costs: Option<WasmCosts>,
+    host_function_costs: Option<HostFunctionCosts>,
     activation_point: Option<ActivationPoint>,
+    block_time: BlockTime,
+    proof_of_stake_config: Option<pos::Config>,
 }
 
 impl UpgradeConfig {
@@ -71,7 +76,10 @@ impl UpgradeConfig {
         upgrade_installer_args: Option<Vec<u8>>,
         upgrade_installer_bytes: Option<Vec<u8>>,
         wasm_costs: Option<WasmCosts>,
+        host_function_costs: Option<HostFunctionCosts>,
         activation_point: Option<ActivationPoint>,
+        block_time: BlockTime,
+        proof_of_stake_config: Option<pos::Config>,
     ) -> Self {
         UpgradeConfig {
             pre_state_hash,
@@ -80,7 +88,10 @@ impl UpgradeConfig {
             upgrade_installer_args,
             upgrade_installer_bytes,
             wasm_costs,
+            host_function_costs,
             activation_point,
+            block_time,
+            proof_of_stake_config,
         }
     }
 
@@ -110,7 +121,20 @@ impl UpgradeConfig {
         self.wasm_costs
     }
 
+    pub fn host_function_costs(&self) -> Option<HostFunctionCosts> {
+        self.host_function_costs
+    }
+
     pub fn activation_point(&self) -> Option<u64> {
         self.activation_point
     }
+
+    /// The time of the block at which the upgrade activates.
+    pub fn block_time(&self) -> BlockTime {
+        self.block_time
+    }
+
+    pub fn proof_of_stake_config(&self) -> Option<pos::Config> {
+        self.proof_of_stake_config
+    }
 }
@@ -8,32 +8,56 @@ pub fn pos_validator_key_name_to_tuple(pos_key_name: &str) -> Option<(PublicKey,
     if Some("v") != split_bond.next() {
         None
     } else {
-        let hex_key: &str = split_bond.next()?;
-        if hex_key.len() != 64 {
-            return None;
-        }
-        let mut key_bytes = [0u8; 32];
-        let _bytes_written = base16::decode_slice(hex_key, &mut key_bytes).ok()?;
-        debug_assert!(_bytes_written == key_bytes.len());
-        let pub_key = PublicKey::new(key_bytes);
-        let balance = split_bond.next().and_then(|b| {
-            if b.is_empty() {
-                None
-            } else {
-                U512::from_dec_str(b).ok()
-            }
-        })?;
+        let pub_key = parse_public_key(split_bond.next()?)?;
+        let balance = parse_amount(split_bond.next()?)?;
         Some((pub_key, balance))
     }
 }
 
+/// In PoS, the delegations are stored under named keys with names formatted as
+/// "d_<hex-formatted-validator-PublicKey>_<hex-formatted-delegator-PublicKey>_<amount>".  This
+/// function attempts to parse such a string back into the validator's and the delegator's
+/// `PublicKey`s and the delegated amount.
+pub fn pos_delegation_key_name_to_tuple(
+    pos_key_name: &str,
+) -> Option<(PublicKey, PublicKey, U512)> {
+    // expected format is "d_{validator}_{delegator}_{amount}".
+    let mut split_delegation = pos_key_name.split('_');
+    if Some("d") != split_delegation.next() {
+        None
+    } else {
+        let validator = parse_public_key(split_delegation.next()?)?;
+        let delegator = parse_public_key(split_delegation.next()?)?;
+        let amount = parse_amount(split_delegation.next()?)?;
+        Some((validator, delegator, amount))
+    }
+}
+
+fn parse_public_key(hex_key: &str) -> Option<PublicKey> {
+    if hex_key.len() != 64 {
+        return None;
+    }
+    let mut key_bytes = [0u8; 32];
+    let _bytes_written = base16::decode_slice(hex_key, &mut key_bytes).ok()?;
+    debug_assert!(_bytes_written == key_bytes.len());
+    Some(PublicKey::new(key_bytes))
+}
+
+fn parse_amount(amount: &str) -> Option<U512> {
+    if amount.is_empty() {
+        None
+    } else {
+        U512::from_dec_str(amount).ok()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use hex_fmt::HexFmt;
 
     use contract_ffi::value::{account::PublicKey, U512};
 
-    use super::pos_validator_key_name_to_tuple;
+    use super::{pos_delegation_key_name_to_tuple, pos_validator_key_name_to_tuple};
 
     #[test]
     fn should_parse_string_to_validator_tuple() {
@@ -83,4 +107,49 @@ mod tests {
         let no_stake = format!("v_{}", HexFmt(&public_key.value()));
         assert!(pos_validator_key_name_to_tuple(&no_stake).is_none());
     }
+
+    #[test]
+    fn should_parse_string_to_delegation_tuple() {
+        let validator = PublicKey::new([1u8; 32]);
+        let delegator = PublicKey::new([2u8; 32]);
+        let amount = U512::from(100);
+        let named_key_name = format!(
+            "d_{}_{}_{}",
+            HexFmt(&validator.value()),
+            HexFmt(&delegator.value()),
+            amount
+        );
+
+        let parsed = pos_delegation_key_name_to_tuple(&named_key_name);
+        assert_eq!(parsed, Some((validator, delegator, amount)));
+        assert!(pos_validator_key_name_to_tuple(&named_key_name).is_none());
+    }
+
+    #[test]
+    fn should_not_parse_string_to_delegation_tuple() {
+        let validator = PublicKey::new([1u8; 32]);
+        let delegator = PublicKey::new([2u8; 32]);
+        let amount = U512::from(100);
+
+        let validator_key = format!("v_{}_{}", HexFmt(&validator.value()), amount);
+        assert!(pos_delegation_key_name_to_tuple(&validator_key).is_none());
+
+        let no_delegator = format!("d_{}_{}", HexFmt(&validator.value()), amount);
+        assert!(pos_delegation_key_name_to_tuple(&no_delegator).is_none());
+
+        let short_delegator = format!(
+            "d_{}_{}_{}",
+            HexFmt(&validator.value()),
+            HexFmt(&[2u8; 31]),
+            amount
+        );
+        assert!(pos_delegation_key_name_to_tuple(&short_delegator).is_none());
+
+        let no_amount = format!(
+            "d_{}_{}_",
+            HexFmt(&validator.value()),
+            HexFmt(&delegator.value())
+        );
+        assert!(pos_delegation_key_name_to_tuple(&no_amount).is_none());
+    }
 }
@@ -20,7 +20,10 @@ use engine_storage::{global_state::StateReader, protocol_data::ProtocolData};
 
 use super::{extract_access_rights_from_keys, instance_and_memory, Error, Runtime};
 use crate::{
-    engine_state::{execution_result::ExecutionResult, system_contract_cache::SystemContractCache},
+    engine_state::{
+        execution_result::ExecutionResult, execution_trace::ExecutionTrace,
+        system_contract_cache::SystemContractCache,
+    },
     execution::{address_generator::AddressGenerator, FN_STORE_ID_INITIAL},
     runtime_context::{self, RuntimeContext},
     tracking_copy::TrackingCopy,
@@ -84,6 +87,7 @@ impl Executor {
         phase: Phase,
         protocol_data: ProtocolData,
         system_contract_cache: SystemContractCache,
+        trace: Option<&mut ExecutionTrace>,
     ) -> ExecutionResult
     where
         R: StateReader<Key, StoredValue>,
@@ -145,11 +149,17 @@ impl Executor {
         );
 
         let mut runtime = Runtime::new(system_contract_cache, memory, parity_module, context);
-        on_fail_charge!(
-            instance.invoke_export("call", &[], &mut runtime),
-            runtime.context().gas_counter(),
-            effects_snapshot
-        );
+        if trace.is_some() {
+            runtime.start_call_frame();
+        }
+
+        let result = instance.invoke_export("call", &[], &mut runtime);
+
+        if let (Some(trace), Some(call_frame)) = (trace, runtime.take_call_frame(&result)) {
+            trace.record(phase, call_frame);
+        }
+
+        on_fail_charge!(result, runtime.context().gas_counter(), effects_snapshot);
 
         ExecutionResult::Success {
             effect: runtime.context().effect(),
@@ -174,6 +184,7 @@ impl Executor {
         phase: Phase,
         protocol_data: ProtocolData,
         system_contract_cache: SystemContractCache,
+        trace: Option<&mut ExecutionTrace>,
     ) -> ExecutionResult
     where
         R: StateReader<Key, StoredValue>,
@@ -230,8 +241,17 @@ impl Executor {
             on_fail_charge!(instance_and_memory(parity_module.clone(), protocol_version));
 
         let mut runtime = Runtime::new(system_contract_cache, memory, parity_module, context);
+        if trace.is_some() {
+            runtime.start_call_frame();
+        }
+
+        let result = instance.invoke_export("call", &[], &mut runtime);
+
+        if let (Some(trace), Some(call_frame)) = (trace, runtime.take_call_frame(&result)) {
+            trace.record(phase, call_frame);
+        }
 
-        match instance.invoke_export("call", &[], &mut runtime) {
+        match result {
             Ok(_) => ExecutionResult::Success {
                 effect: runtime.context().effect(),
                 cost: runtime.context().gas_counter(),
@@ -0,0 +1,174 @@
+use sha2::{Digest, Sha256};
+
+use contract_ffi::contract_api::crypto::{HashAlgorithm, SignatureScheme, HASH_LENGTH};
+use engine_shared::newtypes::Blake2bHash;
+
+/// Returns the digest of `input` computed with `algorithm`.
+pub(super) fn hash(algorithm: HashAlgorithm, input: &[u8]) -> [u8; HASH_LENGTH] {
+    match algorithm {
+        HashAlgorithm::Blake2b => Blake2bHash::new(input).into(),
+        HashAlgorithm::Sha256 => sha256(input),
+        HashAlgorithm::Keccak256 => tiny_keccak::keccak256(input),
+    }
+}
+
+fn sha256(input: &[u8]) -> [u8; HASH_LENGTH] {
+    let mut ret = [0u8; HASH_LENGTH];
+    ret.copy_from_slice(&Sha256::digest(input));
+    ret
+}
+
+/// Returns `true` if `signature` is a valid signature of `message` by `public_key` under `scheme`.
+///
+/// Public keys and signatures which cannot be parsed are treated as invalid signatures.
+pub(super) fn verify_signature(
+    scheme: SignatureScheme,
+    public_key: &[u8],
+    message: &[u8],
+    signature: &[u8],
+) -> bool {
+    match scheme {
+        SignatureScheme::Ed25519 => verify_ed25519(public_key, message, signature),
+        SignatureScheme::Secp256k1 => verify_secp256k1(public_key, message, signature),
+    }
+}
+
+fn verify_ed25519(public_key: &[u8], message: &[u8], signature: &[u8]) -> bool {
+    let public_key = match ed25519_dalek::PublicKey::from_bytes(public_key) {
+        Ok(public_key) => public_key,
+        Err(_) => return false,
+    };
+    let signature = match ed25519_dalek::Signature::from_bytes(signature) {
+        Ok(signature) => signature,
+        Err(_) => return false,
+    };
+    public_key.verify(message, &signature).is_ok()
+}
+
+/// Signatures are checked against the SHA-256 digest of `message`.  Both compressed and
+/// uncompressed public keys are accepted.
+fn verify_secp256k1(public_key: &[u8], message: &[u8], signature: &[u8]) -> bool {
+    let public_key = match secp256k1::PublicKey::parse_slice(public_key, None) {
+        Ok(public_key) => public_key,
+        Err(_) => return false,
+    };
+    let signature = match secp256k1::Signature::parse_slice(signature) {
+        Ok(signature) => signature,
+        Err(_) => return false,
+    };
+    let digest = secp256k1::Message::parse(&sha256(message));
+    secp256k1::verify(&digest, &signature, &public_key)
+}
+
+#[cfg(test)]
+mod tests {
+    use contract_ffi::contract_api::crypto::{HashAlgorithm, SignatureScheme};
+
+    use super::{hash, sha256, verify_signature};
+
+    const MESSAGE: &[u8] = b"voucher #1";
+    const SECRET_KEY_BYTES: [u8; 32] = [7u8; 32];
+
+    fn ed25519_sign(message: &[u8]) -> (Vec<u8>, Vec<u8>) {
+        let secret_key = ed25519_dalek::SecretKey::from_bytes(&SECRET_KEY_BYTES).unwrap();
+        let public_key: ed25519_dalek::PublicKey = (&secret_key).into();
+        let expanded_secret_key: ed25519_dalek::ExpandedSecretKey = (&secret_key).into();
+        let signature = expanded_secret_key.sign(message, &public_key);
+        (
+            public_key.to_bytes().to_vec(),
+            signature.to_bytes().to_vec(),
+        )
+    }
+
+    fn secp256k1_sign(message: &[u8]) -> (Vec<u8>, Vec<u8>) {
+        let secret_key = secp256k1::SecretKey::parse(&SECRET_KEY_BYTES).unwrap();
+        let public_key = secp256k1::PublicKey::from_secret_key(&secret_key);
+        let digest = secp256k1::Message::parse(&sha256(message));
+        let (signature, _recovery_id) = secp256k1::sign(&digest, &secret_key);
+        (
+            public_key.serialize_compressed().to_vec(),
+            signature.serialize().to_vec(),
+        )
+    }
+
+    #[test]
+    fn should_hash_empty_input() {
+        assert_eq!(
+            base16::encode_lower(&hash(HashAlgorithm::Blake2b, &[])),
+            "0e5751c026e543b2e8ab2eb06099daa1d1e5df47778f7787faab45cdf12fe3a8"
+        );
+        assert_eq!(
+            base16::encode_lower(&hash(HashAlgorithm::Sha256, &[])),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+        assert_eq!(
+            base16::encode_lower(&hash(HashAlgorithm::Keccak256, &[])),
+            "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"
+        );
+    }
+
+    #[test]
+    fn should_verify_valid_signatures() {
+        let (public_key, signature) = ed25519_sign(MESSAGE);
+        assert!(verify_signature(
+            SignatureScheme::Ed25519,
+            &public_key,
+            MESSAGE,
+            &signature
+        ));
+
+        let (public_key, signature) = secp256k1_sign(MESSAGE);
+        assert!(verify_signature(
+            SignatureScheme::Secp256k1,
+            &public_key,
+            MESSAGE,
+            &signature
+        ));
+    }
+
+    #[test]
+    fn should_reject_signature_of_other_message() {
+        let other_message = b"voucher #2";
+
+        let (public_key, signature) = ed25519_sign(other_message);
+        assert!(!verify_signature(
+            SignatureScheme::Ed25519,
+            &public_key,
+            MESSAGE,
+            &signature
+        ));
+
+        let (public_key, signature) = secp256k1_sign(other_message);
+        assert!(!verify_signature(
+            SignatureScheme::Secp256k1,
+            &public_key,
+            MESSAGE,
+            &signature
+        ));
+    }
+
+    #[test]
+    fn should_reject_malformed_inputs() {
+        let (public_key, signature) = ed25519_sign(MESSAGE);
+        assert!(!verify_signature(
+            SignatureScheme::Ed25519,
+            &public_key[1..],
+            MESSAGE,
+            &signature
+        ));
+        assert!(!verify_signature(
+            SignatureScheme::Ed25519,
+            &public_key,
+            MESSAGE,
+            &signature[1..]
+        ));
+
+        // An ed25519 signature is not accepted under the secp256k1 scheme.
+        assert!(!verify_signature(
+            SignatureScheme::Secp256k1,
+            &public_key,
+            MESSAGE,
+            &signature
+        ));
+    }
+}
@@ -26,12 +26,20 @@ where
         args: RuntimeArgs,
     ) -> Result<Option<RuntimeValue>, Trap> {
         let func = FunctionIndex::try_from(index).expect("unknown function index");
+        let host_function_costs = *self.context.protocol_data().host_function_costs();
+        if let Some(call_frame) = self.call_frame.as_mut() {
+            // Calls to `gas` are injected by the preprocessor rather than made by the contract.
+            if func != FunctionIndex::GasFuncIndex {
+                call_frame.record_host_call(func.name());
+            }
+        }
         match func {
             FunctionIndex::ReadFuncIndex => {
                 // args(0) = pointer to key in Wasm memory
                 // args(1) = size of key in Wasm memory
                 // args(2) = pointer to output size (output param)
                 let (key_ptr, key_size, output_size_ptr) = Args::parse(args)?;
+                self.charge_host_function_call(&host_function_costs.read_value, &[key_size])?;
                 let ret = self.read(key_ptr, key_size, output_size_ptr)?;
                 Ok(Some(RuntimeValue::I32(contract_api::i32_from(ret))))
             }
@@ -41,6 +49,7 @@ where
                 // args(1) = size of key in Wasm memory
                 // args(2) = pointer to output size (output param)
                 let (key_ptr, key_size, output_size_ptr) = Args::parse(args)?;
+                self.charge_host_function_call(&host_function_costs.read_value_local, &[key_size])?;
                 let ret = self.read_local(key_ptr, key_size, output_size_ptr)?;
                 Ok(Some(RuntimeValue::I32(contract_api::i32_from(ret))))
             }
@@ -49,6 +58,7 @@ where
                 // args(0) = pointer to amount of keys (output)
                 // args(1) = pointer to amount of serialized bytes (output)
                 let (total_keys_ptr, result_size_ptr) = Args::parse(args)?;
+                self.charge_host_function_call(&host_function_costs.load_named_keys, &[])?;
                 let ret = self.load_named_keys(total_keys_ptr, result_size_ptr)?;
                 Ok(Some(RuntimeValue::I32(contract_api::i32_from(ret))))
             }
@@ -59,6 +69,10 @@ where
                 // args(2) = pointer to value
                 // args(3) = size of value
                 let (key_ptr, key_size, value_ptr, value_size) = Args::parse(args)?;
+                self.charge_host_function_call(
+                    &host_function_costs.write,
+                    &[key_size, value_size],
+                )?;
                 self.write(key_ptr, key_size, value_ptr, value_size)?;
                 Ok(None)
             }
@@ -69,6 +83,10 @@ where
                 // args(2) = pointer to value
                 // args(3) = size of value
                 let (key_bytes_ptr, key_bytes_size, value_ptr, value_size) = Args::parse(args)?;
+                self.charge_host_function_call(
+                    &host_function_costs.write_local,
+                    &[key_bytes_size, value_size],
+                )?;
                 self.write_local(key_bytes_ptr, key_bytes_size, value_ptr, value_size)?;
                 Ok(None)
             }
@@ -79,6 +97,7 @@ where
                 // args(2) = pointer to value
                 // args(3) = size of value
                 let (key_ptr, key_size, value_ptr, value_size) = Args::parse(args)?;
+                self.charge_host_function_call(&host_function_costs.add, &[key_size, value_size])?;
                 self.add(key_ptr, key_size, value_ptr, value_size)?;
                 Ok(None)
             }
@@ -89,6 +108,10 @@ where
                 // args(2) = pointer to value
                 // args(3) = size of value
                 let (key_bytes_ptr, key_bytes_size, value_ptr, value_size) = Args::parse(args)?;
+                self.charge_host_function_call(
+                    &host_function_costs.add_local,
+                    &[key_bytes_size, value_size],
+                )?;
                 self.add_local(key_bytes_ptr, key_bytes_size, value_ptr, value_size)?;
                 Ok(None)
             }
@@ -98,6 +121,7 @@ where
                 // args(1) = pointer to initial value
                 // args(2) = size of initial value
                 let (key_ptr, value_ptr, value_size) = Args::parse(args)?;
+                self.charge_host_function_call(&host_function_costs.new_uref, &[value_size])?;
                 self.new_uref(key_ptr, value_ptr, value_size)?;
                 Ok(None)
             }
@@ -106,6 +130,7 @@ where
                 // args(0) = index of host runtime arg to load
                 // args(1) = pointer to a argument size (output)
                 let (index, size_ptr): (u32, u32) = Args::parse(args)?;
+                self.charge_host_function_call(&host_function_costs.get_arg_size, &[])?;
                 let ret = self.get_arg_size(index as usize, size_ptr)?;
                 Ok(Some(RuntimeValue::I32(contract_api::i32_from(ret))))
             }
@@ -115,6 +140,7 @@ where
                 // args(1) = pointer to destination in Wasm memory
                 // args(2) = size of destination pointer memory
                 let (index, dest_ptr, dest_size): (u32, _, u32) = Args::parse(args)?;
+                self.charge_host_function_call(&host_function_costs.get_arg, &[dest_size])?;
                 let ret = self.get_arg(index as usize, dest_ptr, dest_size as usize)?;
                 Ok(Some(RuntimeValue::I32(contract_api::i32_from(ret))))
             }
@@ -123,6 +149,7 @@ where
                 // args(0) = pointer to value
                 // args(1) = size of value
                 let (value_ptr, value_size): (_, u32) = Args::parse(args)?;
+                self.charge_host_function_call(&host_function_costs.ret, &[value_size])?;
 
                 Err(self.ret(value_ptr, value_size as usize))
             }
@@ -135,6 +162,10 @@ where
                 // args(4) = pointer to result size (output)
                 let (key_ptr, key_size, args_ptr, args_size, result_size_ptr): (_, _, _, u32, _) =
                     Args::parse(args)?;
+                self.charge_host_function_call(
+                    &host_function_costs.call_contract,
+                    &[key_size, args_size],
+                )?;
 
                 let key_contract: Key = self.key_from_mem(key_ptr, key_size)?;
                 let args_bytes: Vec<u8> = self.bytes_from_mem(args_ptr, args_size as usize)?;
@@ -156,6 +187,7 @@ where
                     u32,
                     u32,
                 ) = Args::parse(args)?;
+                self.charge_host_function_call(&host_function_costs.get_key, &[name_size])?;
                 let ret = self.load_key(
                     name_ptr,
                     name_size,
@@ -170,6 +202,7 @@ where
                 // args(0) = pointer to key name in Wasm memory
                 // args(1) = size of key name
                 let (name_ptr, name_size) = Args::parse(args)?;
+                self.charge_host_function_call(&host_function_costs.has_key, &[name_size])?;
                 let result = self.has_key(name_ptr, name_size)?;
                 Ok(Some(RuntimeValue::I32(result)))
             }
@@ -180,6 +213,10 @@ where
                 // args(2) = pointer to key in Wasm memory
                 // args(3) = size of key
                 let (name_ptr, name_size, key_ptr, key_size) = Args::parse(args)?;
+                self.charge_host_function_call(
+                    &host_function_costs.put_key,
+                    &[name_size, key_size],
+                )?;
                 self.put_key(name_ptr, name_size, key_ptr, key_size)?;
                 Ok(None)
             }
@@ -188,6 +225,7 @@ where
                 // args(0) = pointer to key name in Wasm memory
                 // args(1) = size of key name
                 let (name_ptr, name_size) = Args::parse(args)?;
+                self.charge_host_function_call(&host_function_costs.remove_key, &[name_size])?;
                 self.remove_key(name_ptr, name_size)?;
                 Ok(None)
             }
@@ -195,6 +233,7 @@ where
             FunctionIndex::GetCallerIndex => {
                 // args(0) = pointer to Wasm memory where to write.
                 let dest_ptr = Args::parse(args)?;
+                self.charge_host_function_call(&host_function_costs.get_caller, &[])?;
                 self.get_caller(dest_ptr)?;
                 Ok(None)
             }
@@ -202,6 +241,7 @@ where
             FunctionIndex::GetBlocktimeIndex => {
                 // args(0) = pointer to Wasm memory where to write.
                 let dest_ptr = Args::parse(args)?;
+                self.charge_host_function_call(&host_function_costs.get_blocktime, &[])?;
                 self.get_blocktime(dest_ptr)?;
                 Ok(None)
             }
@@ -221,6 +261,10 @@ where
                 // args(4) = pointer to a Wasm memory where we will save
                 //           uref address of the new function
                 let (name_ptr, name_size, urefs_ptr, urefs_size, hash_ptr) = Args::parse(args)?;
+                self.charge_host_function_call(
+                    &host_function_costs.store_function,
+                    &[name_size, urefs_size],
+                )?;
                 let _uref_type: u32 = urefs_size;
                 let fn_bytes = self.get_function_by_name(name_ptr, name_size)?;
                 let uref_bytes = self
@@ -242,6 +286,10 @@ where
                 // args(4) = pointer to a Wasm memory where we will save
                 //           hash of the new function
                 let (name_ptr, name_size, urefs_ptr, urefs_size, hash_ptr) = Args::parse(args)?;
+                self.charge_host_function_call(
+                    &host_function_costs.store_function_at_hash,
+                    &[name_size, urefs_size],
+                )?;
                 let _uref_type: u32 = urefs_size;
                 let fn_bytes = self.get_function_by_name(name_ptr, name_size)?;
                 let uref_bytes = self
@@ -258,6 +306,7 @@ where
                 // args(0) = pointer to value to validate
                 // args(1) = size of value
                 let (uref_ptr, uref_size) = Args::parse(args)?;
+                self.charge_host_function_call(&host_function_costs.is_valid_uref, &[uref_size])?;
 
                 Ok(Some(RuntimeValue::I32(i32::from(
                     self.is_valid_uref(uref_ptr, uref_size)?,
@@ -267,6 +316,7 @@ where
             FunctionIndex::RevertFuncIndex => {
                 // args(0) = status u32
                 let status = Args::parse(args)?;
+                self.charge_host_function_call(&host_function_costs.revert, &[])?;
 
                 Err(self.revert(status))
             }
@@ -275,6 +325,7 @@ where
                 // args(0) = pointer to array of bytes of a public key
                 // args(1) = weight of the key
                 let (public_key_ptr, weight_value): (u32, u8) = Args::parse(args)?;
+                self.charge_host_function_call(&host_function_costs.add_associated_key, &[])?;
                 let value = self.add_associated_key(public_key_ptr, weight_value)?;
                 Ok(Some(RuntimeValue::I32(value)))
             }
@@ -282,6 +333,7 @@ where
             FunctionIndex::RemoveAssociatedKeyFuncIndex => {
                 // args(0) = pointer to array of bytes of a public key
                 let public_key_ptr: u32 = Args::parse(args)?;
+                self.charge_host_function_call(&host_function_costs.remove_associated_key, &[])?;
                 let value = self.remove_associated_key(public_key_ptr)?;
                 Ok(Some(RuntimeValue::I32(value)))
             }
@@ -290,6 +342,7 @@ where
                 // args(0) = pointer to array of bytes of a public key
                 // args(1) = weight of the key
                 let (public_key_ptr, weight_value): (u32, u8) = Args::parse(args)?;
+                self.charge_host_function_call(&host_function_costs.update_associated_key, &[])?;
                 let value = self.update_associated_key(public_key_ptr, weight_value)?;
                 Ok(Some(RuntimeValue::I32(value)))
             }
@@ -298,6 +351,7 @@ where
                 // args(0) = action type
                 // args(1) = new threshold
                 let (action_type_value, threshold_value): (u32, u8) = Args::parse(args)?;
+                self.charge_host_function_call(&host_function_costs.set_action_threshold, &[])?;
                 let value = self.set_action_threshold(action_type_value, threshold_value)?;
                 Ok(Some(RuntimeValue::I32(value)))
             }
@@ -306,6 +360,7 @@ where
                 // args(0) = pointer to array for return value
                 // args(1) = length of array for return value
                 let (dest_ptr, dest_size): (u32, u32) = Args::parse(args)?;
+                self.charge_host_function_call(&host_function_costs.create_purse, &[])?;
                 let purse_id = self.create_purse()?;
                 let purse_id_bytes = purse_id.into_bytes().map_err(Error::BytesRepr)?;
                 assert_eq!(dest_size, purse_id_bytes.len() as u32);
@@ -322,6 +377,10 @@ where
                 // args(3) = length of array of bytes of an amount
                 let (key_ptr, key_size, amount_ptr, amount_size): (u32, u32, u32, u32) =
                     Args::parse(args)?;
+                self.charge_host_function_call(
+                    &host_function_costs.transfer_to_account,
+                    &[key_size, amount_size],
+                )?;
                 let public_key: PublicKey = {
                     let bytes = self.bytes_from_mem(key_ptr, key_size as usize)?;
                     bytesrepr::deserialize(bytes).map_err(Error::BytesRepr)?
@@ -349,6 +408,10 @@ where
                     u32,
                     u32,
                 ) = Args::parse(args)?;
+                self.charge_host_function_call(
+                    &host_function_costs.transfer_from_purse_to_account,
+                    &[source_size, key_size, amount_size],
+                )?;
 
                 let source_purse = {
                     let bytes = self.bytes_from_mem(source_ptr, source_size as usize)?;
@@ -375,6 +438,10 @@ where
                 // args(5) = length of array of bytes in Wasm memory of an amount
                 let (source_ptr, source_size, target_ptr, target_size, amount_ptr, amount_size) =
                     Args::parse(args)?;
+                self.charge_host_function_call(
+                    &host_function_costs.transfer_from_purse_to_purse,
+                    &[source_size, target_size, amount_size],
+                )?;
                 let ret = self.transfer_from_purse_to_purse(
                     source_ptr,
                     source_size,
@@ -391,6 +458,7 @@ where
                 // args(1) = length of purse_id
                 // args(2) = pointer to output size (output)
                 let (ptr, ptr_size, output_size_ptr): (_, u32, _) = Args::parse(args)?;
+                self.charge_host_function_call(&host_function_costs.get_balance, &[ptr_size])?;
                 let ret = self.get_balance_host_buf(ptr, ptr_size as usize, output_size_ptr)?;
                 Ok(Some(RuntimeValue::I32(contract_api::i32_from(ret))))
             }
@@ -398,6 +466,7 @@ where
             FunctionIndex::GetPhaseIndex => {
                 // args(0) = pointer to Wasm memory where to write.
                 let dest_ptr = Args::parse(args)?;
+                self.charge_host_function_call(&host_function_costs.get_phase, &[])?;
                 self.get_phase(dest_ptr)?;
                 Ok(None)
             }
@@ -408,6 +477,10 @@ where
                 // args(2) = pointer to key in Wasm memory
                 // args(3) = size of key
                 let (name_ptr, name_size, key_ptr, key_size) = Args::parse(args)?;
+                self.charge_host_function_call(
+                    &host_function_costs.upgrade_contract_at_uref,
+                    &[name_size, key_size],
+                )?;
                 let ret = self.upgrade_contract_at_uref(name_ptr, name_size, key_ptr, key_size)?;
                 Ok(Some(RuntimeValue::I32(contract_api::i32_from(ret))))
             }
@@ -417,6 +490,7 @@ where
                 // args(1) = dest pointer for storing serialized result
                 // args(2) = dest pointer size
                 let (system_contract_index, dest_ptr, dest_size) = Args::parse(args)?;
+                self.charge_host_function_call(&host_function_costs.get_system_contract, &[])?;
                 let ret = self.get_system_contract(system_contract_index, dest_ptr, dest_size)?;
                 Ok(Some(RuntimeValue::I32(contract_api::i32_from(ret))))
             }
@@ -424,6 +498,7 @@ where
             FunctionIndex::GetMainPurseIndex => {
                 // args(0) = pointer to Wasm memory where to write.
                 let dest_ptr = Args::parse(args)?;
+                self.charge_host_function_call(&host_function_costs.get_main_purse, &[])?;
                 self.get_main_purse(dest_ptr)?;
                 Ok(None)
             }
@@ -431,9 +506,66 @@ where
             FunctionIndex::ReadHostBufferIndex => {
                 // args(0) = pointer to Wasm memory where to write size.
                 let (dest_ptr, dest_size, bytes_written_ptr): (_, u32, _) = Args::parse(args)?;
+                self.charge_host_function_call(
+                    &host_function_costs.read_host_buffer,
+                    &[dest_size],
+                )?;
                 let ret = self.read_host_buffer(dest_ptr, dest_size as usize, bytes_written_ptr)?;
                 Ok(Some(RuntimeValue::I32(contract_api::i32_from(ret))))
             }
+
+            FunctionIndex::EmitEventIndex => {
+                // args(0) = pointer to the serialized topic in Wasm memory
+                // args(1) = size of the serialized topic
+                // args(2) = pointer to the serialized event value
+                // args(3) = size of the serialized event value
+                let (topic_ptr, topic_size, value_ptr, value_size) = Args::parse(args)?;
+                self.charge_host_function_call(&host_function_costs.emit_event, &[])?;
+                self.emit_event(topic_ptr, topic_size, value_ptr, value_size)?;
+                Ok(None)
+            }
+
+            FunctionIndex::VerifySignatureIndex => {
+                // args(0) = signature scheme index
+                // args(1) = pointer to the public key in Wasm memory
+                // args(2) = size of the public key
+                // args(3) = pointer to the signed message
+                // args(4) = size of the signed message
+                // args(5) = pointer to the signature
+                // args(6) = size of the signature
+                let (
+                    scheme,
+                    public_key_ptr,
+                    public_key_size,
+                    message_ptr,
+                    message_size,
+                    signature_ptr,
+                    signature_size,
+                ) = Args::parse(args)?;
+                self.charge_host_function_call(&host_function_costs.verify_signature, &[])?;
+                let ret = self.verify_signature(
+                    scheme,
+                    public_key_ptr,
+                    public_key_size,
+                    message_ptr,
+                    message_size,
+                    signature_ptr,
+                    signature_size,
+                )?;
+                Ok(Some(RuntimeValue::I32(contract_api::i32_from(ret))))
+            }
+
+            FunctionIndex::HashIndex => {
+                // args(0) = hash algorithm index
+                // args(1) = pointer to the input in Wasm memory
+                // args(2) = size of the input
+                // args(3) = pointer to the output buffer for the digest
+                // args(4) = size of the output buffer
+                let (algorithm, input_ptr, input_size, dest_ptr, dest_size) = Args::parse(args)?;
+                self.charge_host_function_call(&host_function_costs.hash, &[])?;
+                let ret = self.hash(algorithm, input_ptr, input_size, dest_ptr, dest_size)?;
+                Ok(Some(RuntimeValue::I32(contract_api::i32_from(ret))))
+            }
         }
     }
 }
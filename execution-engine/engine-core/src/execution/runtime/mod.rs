@@ -1,4 +1,5 @@
 mod args;
+mod crypto;
 mod externals;
 
 use std::{
@@ -10,12 +11,13 @@ use std::{
 
 use itertools::Itertools;
 use parity_wasm::elements::Module;
-use wasmi::{ImportsBuilder, MemoryRef, ModuleInstance, ModuleRef, Trap, TrapKind};
+use wasmi::{ImportsBuilder, MemoryRef, ModuleInstance, ModuleRef, RuntimeValue, Trap, TrapKind};
 
 use contract_ffi::{
     args_parser::ArgsParser,
     bytesrepr::{self, ToBytes},
     contract_api::{
+        crypto::{HashAlgorithm, SignatureScheme, HASH_LENGTH},
         system::{TransferResult, TransferredTo},
         Error as ApiError,
     },
@@ -29,10 +31,14 @@ use contract_ffi::{
 };
 use engine_shared::{account::Account, contract::Contract, gas::Gas, stored_value::StoredValue};
 use engine_storage::global_state::StateReader;
+use engine_wasm_prep::host_function_costs::HostFunctionCost;
 
 use super::{Error, MINT_NAME, POS_NAME};
 use crate::{
-    engine_state::system_contract_cache::SystemContractCache,
+    engine_state::{
+        execution_trace::{CallFrame, CallOutcome},
+        system_contract_cache::SystemContractCache,
+    },
     resolvers::{create_module_resolver, memory_resolver::MemoryResolver},
     runtime_context::RuntimeContext,
     Address,
@@ -44,6 +50,7 @@ pub struct Runtime<'a, R> {
     module: Module,
     host_buf: Option<CLValue>,
     context: RuntimeContext<'a, R>,
+    call_frame: Option<CallFrame>,
 }
 
 /// Rename function called `name` in the `module` to `call`.
@@ -1366,6 +1373,10 @@ where
 
     let system_contract_cache = SystemContractCache::clone(&current_runtime.system_contract_cache);
 
+    // The callee may use at most the gas remaining to the caller, and starts counting from zero so
+    // that its usage can be charged back to the caller once it finishes.
+    let gas_limit = current_runtime.context.gas_limit() - current_runtime.context.gas_counter();
+
     let mut runtime = Runtime {
         system_contract_cache,
         memory,
@@ -1381,8 +1392,8 @@ where
             key,
             current_runtime.context.get_blocktime(),
             current_runtime.context.get_deployhash(),
-            current_runtime.context.gas_limit(),
-            current_runtime.context.gas_counter(),
+            gas_limit,
+            Gas::default(),
             current_runtime.context.fn_store_id(),
             current_runtime.context.address_generator(),
             protocol_version,
@@ -1390,14 +1401,25 @@ where
             current_runtime.context.phase(),
             current_runtime.context.protocol_data(),
         ),
+        call_frame: None,
     };
 
+    if current_runtime.call_frame.is_some() {
+        runtime.start_call_frame();
+    }
+
     let result = instance.invoke_export("call", &[], &mut runtime);
 
-    // TODO: To account for the gas used in a subcall, we should uncomment the following lines
-    // if !current_runtime.charge_gas(runtime.context.gas_counter()) {
-    //     return Err(Error::GasLimit);
-    // }
+    if let Some(call_frame) = runtime.take_call_frame(&result) {
+        if let Some(current_call_frame) = current_runtime.call_frame.as_mut() {
+            current_call_frame.record_call(call_frame);
+        }
+    }
+
+    // Charge the caller for the gas used by the callee, whether or not the call succeeded.
+    if !current_runtime.charge_gas(runtime.context.gas_counter()) {
+        return Err(Error::GasLimit);
+    }
 
     match result {
         // If `Ok` and the `host_buf` is `None`, the contract's execution succeeded but did not
@@ -1428,6 +1450,11 @@ where
                         // TODO: https://casperlabs.atlassian.net/browse/EE-771
                         return Err(Error::InvalidContext);
                     }
+                    Error::GasLimit => {
+                        // Propagate running out of gas in the callee as running out of gas in the
+                        // caller.
+                        return Err(Error::GasLimit);
+                    }
                     _ => {}
                 }
             }
@@ -1453,6 +1480,7 @@ where
             module,
             host_buf: None,
             context,
+            call_frame: None,
         }
     }
 
@@ -1460,6 +1488,34 @@ where
         &self.context
     }
 
+    /// Starts recording the host functions and contracts called by this runtime.
+    pub fn start_call_frame(&mut self) {
+        let key = self.context.base_key();
+        let args = self.context.args().clone();
+        self.call_frame = Some(CallFrame::start(key, args));
+    }
+
+    /// Finishes and returns the call frame started by [`Runtime::start_call_frame`], if any,
+    /// given the `result` of invoking this runtime's module.
+    pub fn take_call_frame(
+        &mut self,
+        result: &Result<Option<RuntimeValue>, wasmi::Error>,
+    ) -> Option<CallFrame> {
+        let call_frame = self.call_frame.take()?;
+        let outcome = match result {
+            Ok(_) => CallOutcome::Returned(None),
+            Err(error) => match error
+                .as_host_error()
+                .and_then(|host_error| host_error.downcast_ref::<Error>())
+            {
+                Some(Error::Ret(_)) => CallOutcome::Returned(self.host_buf.clone()),
+                Some(host_error) => CallOutcome::Failed(host_error.to_string()),
+                None => CallOutcome::Failed(error.to_string()),
+            },
+        };
+        Some(call_frame.finish(self.context.gas_counter(), outcome))
+    }
+
     /// Charge specified amount of gas
     ///
     /// Returns false if gas limit exceeded and true if not.
@@ -1486,6 +1542,26 @@ where
         }
     }
 
+    /// Charges for a call to a host function: the `base` cost of `cost`, plus its `per_byte` cost
+    /// for each byte passed to the host as described by `byte_counts`.
+    fn charge_host_function_call(
+        &mut self,
+        cost: &HostFunctionCost,
+        byte_counts: &[u32],
+    ) -> Result<(), Trap> {
+        let bytes_count = byte_counts
+            .iter()
+            .fold(U512::zero(), |total, count| total + U512::from(*count));
+        let amount = U512::from(cost.base) + U512::from(cost.per_byte) * bytes_count;
+        self.gas(Gas::new(amount))
+    }
+
+    /// Charges the `per_byte` cost of `cost` for each of the `size` bytes of a value read into the
+    /// host buffer.
+    fn charge_host_buffer_bytes(&mut self, cost: &HostFunctionCost, size: u32) -> Result<(), Trap> {
+        self.gas(Gas::new(U512::from(cost.per_byte) * U512::from(size)))
+    }
+
     fn bytes_from_mem(&self, ptr: u32, size: usize) -> Result<Vec<u8>, Error> {
         self.memory.get(ptr, size).map_err(Into::into)
     }
@@ -1697,6 +1773,102 @@ where
             .map_err(|e| Error::Interpreter(e).into())
     }
 
+    /// Reads a topic and a `CLValue` from Wasm memory and records them as an event of the current
+    /// deploy.  The event is charged per byte of its serialized topic and value.
+    fn emit_event(
+        &mut self,
+        topic_ptr: u32,
+        topic_size: u32,
+        value_ptr: u32,
+        value_size: u32,
+    ) -> Result<(), Trap> {
+        let bytes_count = U512::from(topic_size) + U512::from(value_size);
+        let memcpy_cost = U512::from(self.context.protocol_data().wasm_costs().memcpy);
+        self.gas(Gas::new(memcpy_cost * bytes_count))?;
+
+        let topic = self.string_from_mem(topic_ptr, topic_size)?;
+        let value = self.cl_value_from_mem(value_ptr, value_size)?;
+        self.context.emit_event(topic, value);
+        Ok(())
+    }
+
+    /// Verifies a signature of a message, reading the public key, message and signature from Wasm
+    /// memory.  The verification is charged the cost of the given scheme plus the memory copy cost
+    /// of its inputs.
+    ///
+    /// Returns `ApiError::InvalidSignature` if the signature is invalid or any of the inputs is
+    /// malformed.
+    #[allow(clippy::too_many_arguments)]
+    fn verify_signature(
+        &mut self,
+        scheme_index: u32,
+        public_key_ptr: u32,
+        public_key_size: u32,
+        message_ptr: u32,
+        message_size: u32,
+        signature_ptr: u32,
+        signature_size: u32,
+    ) -> Result<Result<(), ApiError>, Trap> {
+        let scheme = match SignatureScheme::try_from(scheme_index) {
+            Ok(scheme) => scheme,
+            Err(error) => return Ok(Err(error)),
+        };
+
+        let wasm_costs = *self.context.protocol_data().wasm_costs();
+        let scheme_cost = match scheme {
+            SignatureScheme::Ed25519 => U512::from(wasm_costs.verify_ed25519),
+            SignatureScheme::Secp256k1 => U512::from(wasm_costs.verify_secp256k1),
+        };
+        let bytes_count =
+            U512::from(public_key_size) + U512::from(message_size) + U512::from(signature_size);
+        let memcpy_cost = U512::from(wasm_costs.memcpy);
+        self.gas(Gas::new(scheme_cost + memcpy_cost * bytes_count))?;
+
+        let public_key = self.bytes_from_mem(public_key_ptr, public_key_size as usize)?;
+        let message = self.bytes_from_mem(message_ptr, message_size as usize)?;
+        let signature = self.bytes_from_mem(signature_ptr, signature_size as usize)?;
+
+        if crypto::verify_signature(scheme, &public_key, &message, &signature) {
+            Ok(Ok(()))
+        } else {
+            Ok(Err(ApiError::InvalidSignature))
+        }
+    }
+
+    /// Hashes the input read from Wasm memory with the given algorithm and writes the digest to
+    /// `dest_ptr`.  Hashing is charged the per byte cost of the algorithm.
+    fn hash(
+        &mut self,
+        algorithm_index: u32,
+        input_ptr: u32,
+        input_size: u32,
+        dest_ptr: u32,
+        dest_size: u32,
+    ) -> Result<Result<(), ApiError>, Trap> {
+        let algorithm = match HashAlgorithm::try_from(algorithm_index) {
+            Ok(algorithm) => algorithm,
+            Err(error) => return Ok(Err(error)),
+        };
+        if (dest_size as usize) < HASH_LENGTH {
+            return Ok(Err(ApiError::BufferTooSmall));
+        }
+
+        let wasm_costs = *self.context.protocol_data().wasm_costs();
+        let per_byte_cost = match algorithm {
+            HashAlgorithm::Blake2b => U512::from(wasm_costs.hash_blake2b),
+            HashAlgorithm::Sha256 => U512::from(wasm_costs.hash_sha256),
+            HashAlgorithm::Keccak256 => U512::from(wasm_costs.hash_keccak256),
+        };
+        self.gas(Gas::new(per_byte_cost * U512::from(input_size)))?;
+
+        let input = self.bytes_from_mem(input_ptr, input_size as usize)?;
+        let digest = crypto::hash(algorithm, &input);
+        self.memory
+            .set(dest_ptr, &digest)
+            .map_err(|e| Error::Interpreter(e).into())
+            .map(Ok)
+    }
+
     /// Return some bytes from the memory and terminate the current `sub_call`. Note that the return
     /// type is `Trap`, indicating that this function will always kill the current Wasm instance.
     fn ret(&mut self, value_ptr: u32, value_size: usize) -> Trap {
@@ -1979,6 +2151,12 @@ where
         };
 
         let value_size = cl_value.inner_bytes().len() as u32;
+        let cost = self
+            .context
+            .protocol_data()
+            .host_function_costs()
+            .read_value;
+        self.charge_host_buffer_bytes(&cost, value_size)?;
         if let Err(error) = self.write_host_buf(cl_value) {
             return Ok(Err(error));
         }
@@ -2012,6 +2190,12 @@ where
         };
 
         let value_size = cl_value.inner_bytes().len() as u32;
+        let cost = self
+            .context
+            .protocol_data()
+            .host_function_costs()
+            .read_value_local;
+        self.charge_host_buffer_bytes(&cost, value_size)?;
         if let Err(error) = self.write_host_buf(cl_value) {
             return Ok(Err(error));
         }
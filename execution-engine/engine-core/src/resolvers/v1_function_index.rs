@@ -43,6 +43,57 @@ pub enum FunctionIndex {
     GetMainPurseIndex,
     GetArgSizeFuncIndex,
     ReadHostBufferIndex,
+    EmitEventIndex,
+    VerifySignatureIndex,
+    HashIndex,
+}
+
+impl FunctionIndex {
+    /// The name under which the host function is imported by contracts.
+    pub fn name(&self) -> &'static str {
+        match self {
+            FunctionIndex::WriteFuncIndex => "write",
+            FunctionIndex::WriteLocalFuncIndex => "write_local",
+            FunctionIndex::ReadFuncIndex => "read_value",
+            FunctionIndex::ReadLocalFuncIndex => "read_value_local",
+            FunctionIndex::AddFuncIndex => "add",
+            FunctionIndex::AddLocalFuncIndex => "add_local",
+            FunctionIndex::NewFuncIndex => "new_uref",
+            FunctionIndex::RetFuncIndex => "ret",
+            FunctionIndex::CallContractFuncIndex => "call_contract",
+            FunctionIndex::GetArgFuncIndex => "get_arg",
+            FunctionIndex::GetKeyFuncIndex => "get_key",
+            FunctionIndex::GasFuncIndex => "gas",
+            FunctionIndex::HasKeyFuncIndex => "has_key",
+            FunctionIndex::PutKeyFuncIndex => "put_key",
+            FunctionIndex::StoreFnIndex => "store_function",
+            FunctionIndex::StoreFnAtHashIndex => "store_function_at_hash",
+            FunctionIndex::IsValidURefFnIndex => "is_valid_uref",
+            FunctionIndex::RevertFuncIndex => "revert",
+            FunctionIndex::AddAssociatedKeyFuncIndex => "add_associated_key",
+            FunctionIndex::RemoveAssociatedKeyFuncIndex => "remove_associated_key",
+            FunctionIndex::UpdateAssociatedKeyFuncIndex => "update_associated_key",
+            FunctionIndex::SetActionThresholdFuncIndex => "set_action_threshold",
+            FunctionIndex::LoadNamedKeysFuncIndex => "load_named_keys",
+            FunctionIndex::RemoveKeyFuncIndex => "remove_key",
+            FunctionIndex::GetCallerIndex => "get_caller",
+            FunctionIndex::GetBlocktimeIndex => "get_blocktime",
+            FunctionIndex::CreatePurseIndex => "create_purse",
+            FunctionIndex::TransferToAccountIndex => "transfer_to_account",
+            FunctionIndex::TransferFromPurseToAccountIndex => "transfer_from_purse_to_account",
+            FunctionIndex::TransferFromPurseToPurseIndex => "transfer_from_purse_to_purse",
+            FunctionIndex::GetBalanceIndex => "get_balance",
+            FunctionIndex::GetPhaseIndex => "get_phase",
+            FunctionIndex::UpgradeContractAtURefIndex => "upgrade_contract_at_uref",
+            FunctionIndex::GetSystemContractIndex => "get_system_contract",
+            FunctionIndex::GetMainPurseIndex => "get_main_purse",
+            FunctionIndex::GetArgSizeFuncIndex => "get_arg_size",
+            FunctionIndex::ReadHostBufferIndex => "read_host_buffer",
+            FunctionIndex::EmitEventIndex => "emit_event",
+            FunctionIndex::VerifySignatureIndex => "verify_signature",
+            FunctionIndex::HashIndex => "hash",
+        }
+    }
 }
 
 impl Into<usize> for FunctionIndex {
@@ -33,7 +33,9 @@ use engine_shared::{
 use engine_storage::{global_state::StateReader, protocol_data::ProtocolData};
 
 use crate::{
-    engine_state::{execution_effect::ExecutionEffect, SYSTEM_ACCOUNT_ADDR},
+    engine_state::{
+        contract_event::ContractEvent, execution_effect::ExecutionEffect, SYSTEM_ACCOUNT_ADDR,
+    },
     execution::{AddressGenerator, Error},
     tracking_copy::{AddResult, TrackingCopy},
     Address,
@@ -469,6 +471,12 @@ where
         self.state.borrow_mut().effect()
     }
 
+    /// Records an event under `topic`, attributed to the current context's base key.
+    pub fn emit_event(&mut self, topic: String, value: CLValue) {
+        let event = ContractEvent::new(self.base_key, topic, value);
+        self.state.borrow_mut().emit_event(event);
+    }
+
     /// Validates whether keys used in the `value` are not forged.
     fn validate_value(&self, value: &StoredValue) -> Result<(), Error> {
         match value {
@@ -19,9 +19,14 @@ use engine_shared::{
     stored_value::StoredValue,
     transform::{self, Transform, TypeMismatch},
 };
-use engine_storage::global_state::StateReader;
+use engine_storage::{
+    global_state::{StateProofReader, StateReader},
+    trie::merkle_proof::TrieMerkleProof,
+};
 
-use crate::engine_state::{execution_effect::ExecutionEffect, op::Op};
+use crate::engine_state::{
+    contract_event::ContractEvent, execution_effect::ExecutionEffect, op::Op,
+};
 
 pub use self::ext::TrackingCopyExt;
 use self::meter::{heap_meter::HeapSize, Meter};
@@ -94,6 +99,7 @@ pub struct TrackingCopy<R> {
     cache: TrackingCopyCache<HeapSize>,
     ops: AdditiveMap<Key, Op>,
     fns: AdditiveMap<Key, Transform>,
+    events: Vec<ContractEvent>,
 }
 
 #[derive(Debug)]
@@ -126,6 +132,7 @@ impl<R: StateReader<Key, StoredValue>> TrackingCopy<R> {
                                                                  * limit? */
             ops: AdditiveMap::new(),
             fns: AdditiveMap::new(),
+            events: Vec::new(),
         }
     }
 
@@ -264,8 +271,52 @@ impl<R: StateReader<Key, StoredValue>> TrackingCopy<R> {
         }
     }
 
+    /// Applies `transforms` to this tracking copy as though they had been produced by operations
+    /// on it.  This is used to carry the effects of one execution over to the next.
+    ///
+    /// Stops at the first transform which cannot be applied, leaving any earlier ones applied.
+    pub fn apply_effects(
+        &mut self,
+        correlation_id: CorrelationId,
+        transforms: AdditiveMap<Key, Transform>,
+    ) -> Result<AddResult, R::Error> {
+        for (key, transform) in transforms {
+            let normalized_key = key.normalize();
+            match transform {
+                Transform::Identity => (),
+                Transform::Write(value) => self.write(normalized_key, value),
+                transform => {
+                    let current_value = match self.get(correlation_id, &normalized_key)? {
+                        None => return Ok(AddResult::KeyNotFound(normalized_key)),
+                        Some(current_value) => current_value,
+                    };
+                    match transform.clone().apply(current_value) {
+                        Ok(new_value) => {
+                            self.cache.insert_write(normalized_key, new_value);
+                            self.ops.insert_add(normalized_key, Op::Add);
+                            self.fns.insert_add(normalized_key, transform);
+                        }
+                        Err(transform::Error::TypeMismatch(type_mismatch)) => {
+                            return Ok(AddResult::TypeMismatch(type_mismatch))
+                        }
+                        Err(transform::Error::Serialization(error)) => {
+                            return Ok(AddResult::Serialization(error))
+                        }
+                    }
+                }
+            }
+        }
+        Ok(AddResult::Success)
+    }
+
+    /// Records an event emitted by a contract.  Events are kept in emission order and are
+    /// returned as part of [`TrackingCopy::effect`].
+    pub fn emit_event(&mut self, event: ContractEvent) {
+        self.events.push(event);
+    }
+
     pub fn effect(&self) -> ExecutionEffect {
-        ExecutionEffect::new(self.ops.clone(), self.fns.clone())
+        ExecutionEffect::new(self.ops.clone(), self.fns.clone()).with_events(self.events.clone())
     }
 
     pub fn query(
@@ -358,6 +409,41 @@ impl<R: StateReader<Key, StoredValue>> TrackingCopy<R> {
     }
 }
 
+impl<R: StateProofReader<Key, StoredValue>> TrackingCopy<R> {
+    /// Returns Merkle proofs for the values read while resolving `path` from `base_key`, in the
+    /// order they were read.  The proofs are against the state root of the underlying reader, so
+    /// mutations held by this `TrackingCopy` are not reflected in them.  If resolution stops at a
+    /// missing key, the last proof is a proof of that key's absence.
+    pub fn query_proofs(
+        &self,
+        correlation_id: CorrelationId,
+        base_key: Key,
+        path: &[String],
+    ) -> Result<Vec<TrieMerkleProof<Key, StoredValue>>, R::Error> {
+        let mut proofs = Vec::new();
+        let mut current_key = base_key.normalize();
+        let mut names = path.iter();
+
+        loop {
+            let proof = self.reader.read_with_proof(correlation_id, &current_key)?;
+            let next_key = match (proof.value(), names.next()) {
+                (Some(StoredValue::Account(account)), Some(name)) => {
+                    account.named_keys().get(name).cloned()
+                }
+                (Some(StoredValue::Contract(contract)), Some(name)) => {
+                    contract.named_keys().get(name).cloned()
+                }
+                _ => None,
+            };
+            proofs.push(proof);
+            match next_key {
+                Some(key) => current_key = key.normalize(),
+                None => return Ok(proofs),
+            }
+        }
+    }
+}
+
 /// The purpose of this implementation is to allow a "snapshot" mechanism for
 /// TrackingCopy. The state of a TrackingCopy (including the effects of
 /// any transforms it has accumulated) can be read using an immutable
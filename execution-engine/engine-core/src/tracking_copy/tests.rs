@@ -14,6 +14,7 @@ use contract_ffi::{
 };
 use engine_shared::{
     account::{Account, AssociatedKeys},
+    additive_map::AdditiveMap,
     contract::Contract,
     newtypes::CorrelationId,
     stored_value::{gens::stored_value_arb, StoredValue},
@@ -289,6 +290,60 @@ fn tracking_copy_aw() {
     assert_eq!(tc.ops.get(&k), Some(&Op::Write));
 }
 
+#[test]
+fn tracking_copy_apply_effects() {
+    let correlation_id = CorrelationId::new();
+    let counter = Rc::new(Cell::new(0));
+    let db = CountingDb::new(counter);
+    let mut tc = TrackingCopy::new(db);
+    let k1 = Key::Hash([1u8; 32]);
+    let k2 = Key::Hash([2u8; 32]);
+
+    let written = StoredValue::CLValue(CLValue::from_t(7_i32).unwrap());
+    let effects = {
+        let mut tmp = AdditiveMap::new();
+        tmp.insert(k1, Transform::AddInt32(3));
+        tmp.insert(k2, Transform::Write(written.clone()));
+        tmp
+    };
+
+    let result = tc.apply_effects(correlation_id, effects);
+    assert_matches!(result, Ok(AddResult::Success));
+
+    // applied effects are recorded as though the operations happened on this tracking copy
+    assert_eq!(tc.fns.len(), 2);
+    assert_eq!(tc.fns.get(&k1), Some(&Transform::AddInt32(3)));
+    assert_eq!(tc.fns.get(&k2), Some(&Transform::Write(written.clone())));
+    assert_eq!(tc.ops.get(&k1), Some(&Op::Add));
+    assert_eq!(tc.ops.get(&k2), Some(&Op::Write));
+
+    // and are visible to later reads, including through forks
+    let expected = StoredValue::CLValue(CLValue::from_t(3_i32).unwrap());
+    assert_eq!(tc.get(correlation_id, &k1).unwrap(), Some(expected.clone()));
+    let mut fork = tc.fork();
+    assert_eq!(fork.get(correlation_id, &k1).unwrap(), Some(expected));
+    assert_eq!(fork.get(correlation_id, &k2).unwrap(), Some(written));
+}
+
+#[test]
+fn tracking_copy_apply_effects_type_mismatch() {
+    let correlation_id = CorrelationId::new();
+    let counter = Rc::new(Cell::new(0));
+    let db = CountingDb::new(counter);
+    let mut tc = TrackingCopy::new(db);
+    let k = Key::Hash([0u8; 32]);
+
+    let effects = {
+        let mut tmp = AdditiveMap::new();
+        tmp.insert(k, Transform::AddUInt64(3));
+        tmp
+    };
+
+    let result = tc.apply_effects(correlation_id, effects);
+    assert_matches!(result, Ok(AddResult::TypeMismatch(_)));
+    assert!(tc.fns.is_empty());
+}
+
 proptest! {
     #[test]
     fn query_empty_path(k in key_arb(), missing_key in key_arb(), v in stored_value_arb()) {
@@ -0,0 +1,47 @@
+use std::convert::{TryFrom, TryInto};
+
+use engine_core::engine_state::contract_event::ContractEvent;
+
+use crate::engine_server::{ipc, mappings::ParsingError};
+
+impl From<ContractEvent> for ipc::ContractEvent {
+    fn from(contract_event: ContractEvent) -> Self {
+        let (emitter, topic, value) = contract_event.destructure();
+
+        let mut pb_contract_event = ipc::ContractEvent::new();
+        pb_contract_event.set_emitter(emitter.into());
+        pb_contract_event.set_topic(topic);
+        pb_contract_event.set_value(value.into());
+
+        pb_contract_event
+    }
+}
+
+impl TryFrom<ipc::ContractEvent> for ContractEvent {
+    type Error = ParsingError;
+
+    fn try_from(mut pb_contract_event: ipc::ContractEvent) -> Result<Self, Self::Error> {
+        let emitter = pb_contract_event.take_emitter().try_into()?;
+        let topic = pb_contract_event.take_topic();
+        let value = pb_contract_event.take_value().try_into()?;
+        Ok(ContractEvent::new(emitter, topic, value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::proptest;
+
+    use contract_ffi::gens;
+
+    use super::*;
+    use crate::engine_server::mappings::test_utils;
+
+    proptest! {
+        #[test]
+        fn round_trip(key in gens::key_arb(), topic in "\\PC*", cl_value in gens::cl_value_arb()) {
+            let contract_event = ContractEvent::new(key, topic, cl_value);
+            test_utils::protobuf_round_trip::<ContractEvent, ipc::ContractEvent>(contract_event);
+        }
+    }
+}
@@ -45,6 +45,8 @@ impl TryFrom<ipc::DeployItem> for DeployItem {
             MappingError::invalid_deploy_hash_length(pb_deploy_item.deploy_hash.len())
         })?;
 
+        let trace = pb_deploy_item.get_trace();
+
         Ok(DeployItem::new(
             address,
             session,
@@ -52,6 +54,7 @@ impl TryFrom<ipc::DeployItem> for DeployItem {
             gas_price,
             authorization_keys,
             deploy_hash,
-        ))
+        )
+        .with_trace(trace))
     }
 }
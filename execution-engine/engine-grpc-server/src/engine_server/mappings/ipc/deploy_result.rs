@@ -7,7 +7,7 @@ use engine_core::{
 };
 use engine_shared::gas::Gas;
 
-use crate::engine_server::ipc::{DeployError_OutOfGasError, DeployResult};
+use crate::engine_server::ipc::{ContractEvent, DeployError_OutOfGasError, DeployResult};
 
 impl From<ExecutionResult> for DeployResult {
     fn from(execution_result: ExecutionResult) -> DeployResult {
@@ -44,6 +44,9 @@ impl From<(EngineStateError, ExecutionEffect, Gas)> for DeployResult {
             EngineStateError::MissingSystemContractError(msg) => {
                 detail::execution_error(msg, effect, cost)
             }
+            error @ EngineStateError::UnexpectedSystemContractKeyType(_) => {
+                detail::execution_error(error, effect, cost)
+            }
             error @ EngineStateError::InsufficientPaymentError
             | error @ EngineStateError::DeployError
             | error @ EngineStateError::FinalizationError
@@ -95,7 +98,7 @@ impl From<(ExecutionError, ExecutionEffect, Gas)> for DeployResult {
 }
 
 mod detail {
-    use super::{DeployError_OutOfGasError, DeployResult, ExecutionEffect, Gas};
+    use super::{ContractEvent, DeployError_OutOfGasError, DeployResult, ExecutionEffect, Gas};
 
     /// Constructs an instance of `DeployResult` with no error set, i.e. a successful
     /// result.
@@ -154,6 +157,8 @@ mod detail {
                 .mut_exec_error()
                 .set_message(msg),
         }
+        let pb_events: Vec<ContractEvent> = effect.events.iter().cloned().map(Into::into).collect();
+        pb_execution_result.set_events(pb_events.into());
         pb_execution_result.set_effects(effect.into());
         pb_execution_result.set_cost(cost.value().into());
 
@@ -169,8 +174,9 @@ mod tests {
         bytesrepr::Error as BytesReprError,
         key::Key,
         uref::{AccessRights, URef},
-        value::U512,
+        value::{CLValue, U512},
     };
+    use engine_core::engine_state::contract_event::ContractEvent;
     use engine_shared::{additive_map::AdditiveMap, transform::Transform};
 
     use super::*;
@@ -210,6 +216,33 @@ mod tests {
         assert_eq!(input_transforms, ipc_transforms);
     }
 
+    #[test]
+    fn deploy_result_to_ipc_preserves_event_order() {
+        let events: Vec<ContractEvent> = (0..3u64)
+            .map(|i| {
+                ContractEvent::new(
+                    Key::Hash([i as u8; 32]),
+                    format!("topic-{}", i),
+                    CLValue::from_t(i).unwrap(),
+                )
+            })
+            .collect();
+        let execution_effect = ExecutionEffect::default().with_events(events.clone());
+        let execution_result = ExecutionResult::Success {
+            effect: execution_effect,
+            cost: Gas::default(),
+        };
+        let mut ipc_deploy_result: DeployResult = execution_result.into();
+        let ipc_events: Vec<ContractEvent> = ipc_deploy_result
+            .take_execution_result()
+            .take_events()
+            .into_iter()
+            .map(TryInto::try_into)
+            .collect::<Result<Vec<ContractEvent>, _>>()
+            .expect("should parse events");
+        assert_eq!(events, ipc_events);
+    }
+
     fn test_cost<E: Into<EngineStateError>>(expected_cost: Gas, error: E) -> Gas {
         let execution_failure = ExecutionResult::Failure {
             error: error.into(),
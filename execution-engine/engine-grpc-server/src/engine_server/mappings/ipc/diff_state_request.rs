@@ -0,0 +1,91 @@
+use std::{
+    cmp,
+    convert::{TryFrom, TryInto},
+};
+
+use engine_core::engine_state::diff_state::DiffStateRequest;
+use engine_shared::newtypes::{Blake2bHash, BLAKE2B_DIGEST_LENGTH};
+
+use crate::engine_server::{ipc, mappings::MappingError};
+
+/// The number of entries listed when a request does not give a limit.
+const DEFAULT_DIFF_STATE_LIMIT: u32 = 100;
+
+/// The most entries listed in a single response, whatever the requested limit.
+const MAX_DIFF_STATE_LIMIT: u32 = 1_000;
+
+fn state_hash_from(bytes: &[u8]) -> Result<Blake2bHash, MappingError> {
+    let length = bytes.len();
+    if length != BLAKE2B_DIGEST_LENGTH {
+        return Err(MappingError::InvalidStateHashLength {
+            expected: BLAKE2B_DIGEST_LENGTH,
+            actual: length,
+        });
+    }
+    bytes
+        .try_into()
+        .map_err(|_| MappingError::TryFromSliceError)
+}
+
+impl TryFrom<ipc::DiffStateRequest> for DiffStateRequest {
+    type Error = MappingError;
+
+    fn try_from(mut diff_state_request: ipc::DiffStateRequest) -> Result<Self, Self::Error> {
+        let old_state_hash = state_hash_from(diff_state_request.get_old_state_hash())?;
+
+        let new_state_hash = state_hash_from(diff_state_request.get_new_state_hash())?;
+
+        let start = diff_state_request.take_start();
+
+        let limit = match diff_state_request.get_limit() {
+            0 => DEFAULT_DIFF_STATE_LIMIT,
+            limit => cmp::min(limit, MAX_DIFF_STATE_LIMIT),
+        };
+
+        Ok(DiffStateRequest::new(
+            old_state_hash,
+            new_state_hash,
+            start,
+            limit as usize,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn diff_state_request(limit: u32) -> ipc::DiffStateRequest {
+        let mut pb_request = ipc::DiffStateRequest::new();
+        pb_request.set_old_state_hash(vec![1; BLAKE2B_DIGEST_LENGTH]);
+        pb_request.set_new_state_hash(vec![2; BLAKE2B_DIGEST_LENGTH]);
+        pb_request.set_start(vec![0, 2]);
+        pb_request.set_limit(limit);
+        pb_request
+    }
+
+    #[test]
+    fn should_map_request() {
+        let request = DiffStateRequest::try_from(diff_state_request(10)).unwrap();
+        assert_eq!(request.old_state_hash(), [1; BLAKE2B_DIGEST_LENGTH].into());
+        assert_eq!(request.new_state_hash(), [2; BLAKE2B_DIGEST_LENGTH].into());
+        assert_eq!(request.start(), &[0, 2]);
+        assert_eq!(request.limit(), 10);
+    }
+
+    #[test]
+    fn should_default_and_cap_limit() {
+        let request = DiffStateRequest::try_from(diff_state_request(0)).unwrap();
+        assert_eq!(request.limit(), DEFAULT_DIFF_STATE_LIMIT as usize);
+
+        let request = DiffStateRequest::try_from(diff_state_request(u32::max_value())).unwrap();
+        assert_eq!(request.limit(), MAX_DIFF_STATE_LIMIT as usize);
+    }
+
+    #[test]
+    fn should_reject_invalid_state_hash() {
+        let mut pb_request = diff_state_request(10);
+        pb_request.set_new_state_hash(vec![2; BLAKE2B_DIGEST_LENGTH - 1]);
+        assert!(DiffStateRequest::try_from(pb_request).is_err());
+    }
+}
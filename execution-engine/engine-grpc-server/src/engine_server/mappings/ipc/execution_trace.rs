@@ -0,0 +1,156 @@
+use std::convert::{TryFrom, TryInto};
+
+use contract_ffi::value::{CLValue, U512};
+use engine_core::engine_state::execution_trace::{CallFrame, CallOutcome, ExecutionTrace};
+use engine_shared::gas::Gas;
+
+use crate::engine_server::{
+    ipc::{self, ExecutionTrace_CallFrame, ExecutionTrace_CallFrame_oneof_outcome},
+    mappings::ParsingError,
+    state,
+};
+
+impl From<CallFrame> for ExecutionTrace_CallFrame {
+    fn from(call_frame: CallFrame) -> Self {
+        let (key, args, host_calls, calls, gas_used, outcome) = call_frame.destructure();
+
+        let mut pb_call_frame = ExecutionTrace_CallFrame::new();
+        pb_call_frame.set_key(key.into());
+        let pb_args: Vec<state::CLValue> = args.into_iter().map(Into::into).collect();
+        pb_call_frame.set_args(pb_args.into());
+        pb_call_frame.set_host_calls(host_calls.into());
+        let pb_calls: Vec<ExecutionTrace_CallFrame> = calls.into_iter().map(Into::into).collect();
+        pb_call_frame.set_calls(pb_calls.into());
+        pb_call_frame.set_gas_used(gas_used.value().into());
+        match outcome {
+            CallOutcome::Returned(maybe_value) => {
+                let pb_return_value = pb_call_frame.mut_returned();
+                if let Some(value) = maybe_value {
+                    pb_return_value.set_value(value.into());
+                }
+            }
+            CallOutcome::Failed(error) => pb_call_frame.set_error(error),
+        }
+
+        pb_call_frame
+    }
+}
+
+impl TryFrom<ExecutionTrace_CallFrame> for CallFrame {
+    type Error = ParsingError;
+
+    fn try_from(mut pb_call_frame: ExecutionTrace_CallFrame) -> Result<Self, Self::Error> {
+        let key = pb_call_frame.take_key().try_into()?;
+        let args = pb_call_frame
+            .take_args()
+            .into_iter()
+            .map(TryInto::try_into)
+            .collect::<Result<Vec<CLValue>, ParsingError>>()?;
+        let host_calls = pb_call_frame.take_host_calls().into_vec();
+        let calls = pb_call_frame
+            .take_calls()
+            .into_iter()
+            .map(TryInto::try_into)
+            .collect::<Result<Vec<CallFrame>, ParsingError>>()?;
+        let gas_used = Gas::new(U512::try_from(pb_call_frame.take_gas_used())?);
+        let outcome = match pb_call_frame.outcome {
+            Some(ExecutionTrace_CallFrame_oneof_outcome::returned(mut pb_return_value)) => {
+                let maybe_value = if pb_return_value.has_value() {
+                    Some(pb_return_value.take_value().try_into()?)
+                } else {
+                    None
+                };
+                CallOutcome::Returned(maybe_value)
+            }
+            Some(ExecutionTrace_CallFrame_oneof_outcome::error(error)) => {
+                CallOutcome::Failed(error)
+            }
+            None => return Err(ParsingError::from("Unable to parse Protobuf CallFrame")),
+        };
+
+        Ok(CallFrame::new(
+            key, args, host_calls, calls, gas_used, outcome,
+        ))
+    }
+}
+
+impl From<ExecutionTrace> for ipc::ExecutionTrace {
+    fn from(execution_trace: ExecutionTrace) -> Self {
+        let (payment, session, finalize) = execution_trace.destructure();
+
+        let mut pb_execution_trace = ipc::ExecutionTrace::new();
+        if let Some(payment) = payment {
+            pb_execution_trace.set_payment(payment.into());
+        }
+        if let Some(session) = session {
+            pb_execution_trace.set_session(session.into());
+        }
+        if let Some(finalize) = finalize {
+            pb_execution_trace.set_finalize(finalize.into());
+        }
+
+        pb_execution_trace
+    }
+}
+
+impl TryFrom<ipc::ExecutionTrace> for ExecutionTrace {
+    type Error = ParsingError;
+
+    fn try_from(mut pb_execution_trace: ipc::ExecutionTrace) -> Result<Self, Self::Error> {
+        let payment = if pb_execution_trace.has_payment() {
+            Some(pb_execution_trace.take_payment().try_into()?)
+        } else {
+            None
+        };
+        let session = if pb_execution_trace.has_session() {
+            Some(pb_execution_trace.take_session().try_into()?)
+        } else {
+            None
+        };
+        let finalize = if pb_execution_trace.has_finalize() {
+            Some(pb_execution_trace.take_finalize().try_into()?)
+        } else {
+            None
+        };
+        Ok(ExecutionTrace::new(payment, session, finalize))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::proptest;
+
+    use contract_ffi::{gens, key::Key};
+
+    use super::*;
+    use crate::engine_server::mappings::test_utils;
+
+    fn call_frame(key: Key, value: CLValue, outcome: CallOutcome) -> CallFrame {
+        CallFrame::new(
+            key,
+            vec![value],
+            vec!["get_arg".to_string(), "call_contract".to_string()],
+            Vec::new(),
+            Gas::new(U512::from(1_000)),
+            outcome,
+        )
+    }
+
+    proptest! {
+        #[test]
+        fn round_trip(key in gens::key_arb(), value in gens::cl_value_arb(), error in "\\PC+") {
+            let returned = call_frame(key, value.clone(), CallOutcome::Returned(Some(value.clone())));
+            let failed = call_frame(key, value.clone(), CallOutcome::Failed(error));
+            let session = CallFrame::new(
+                key,
+                Vec::new(),
+                vec!["call_contract".to_string()],
+                vec![returned.clone(), failed],
+                Gas::new(U512::from(3_000)),
+                CallOutcome::Returned(None),
+            );
+            let execution_trace = ExecutionTrace::new(Some(returned), Some(session), None);
+            test_utils::protobuf_round_trip::<ExecutionTrace, ipc::ExecutionTrace>(execution_trace);
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use std::convert::{TryFrom, TryInto};
 
+use contract_ffi::system_contracts::pos;
 use engine_core::engine_state::genesis::{GenesisAccount, GenesisConfig};
 
 use crate::engine_server::{
@@ -30,6 +31,10 @@ impl From<GenesisConfig> for ChainSpec_GenesisConfig {
             .mut_costs()
             .set_wasm(genesis_config.wasm_costs().into());
         pb_genesis_config
+            .mut_costs()
+            .set_host(genesis_config.host_function_costs().into());
+        pb_genesis_config.set_pos_config(genesis_config.proof_of_stake_config().into());
+        pb_genesis_config
     }
 }
 
@@ -45,7 +50,15 @@ impl TryFrom<ChainSpec_GenesisConfig> for GenesisConfig {
             .into_iter()
             .map(TryInto::try_into)
             .collect::<Result<Vec<GenesisAccount>, Self::Error>>()?;
-        let wasm_costs = pb_genesis_config.take_costs().take_wasm().into();
+        let (wasm_costs, host_function_costs) = {
+            let mut costs = pb_genesis_config.take_costs();
+            (costs.take_wasm().into(), costs.take_host().into())
+        };
+        let proof_of_stake_config = if pb_genesis_config.has_pos_config() {
+            pb_genesis_config.take_pos_config().try_into()?
+        } else {
+            pos::Config::default()
+        };
         let mint_initializer_bytes = pb_genesis_config.mint_installer;
         let proof_of_stake_initializer_bytes = pb_genesis_config.pos_installer;
         Ok(GenesisConfig::new(
@@ -56,6 +69,8 @@ impl TryFrom<ChainSpec_GenesisConfig> for GenesisConfig {
             proof_of_stake_initializer_bytes,
             accounts,
             wasm_costs,
+            host_function_costs,
+            proof_of_stake_config,
         ))
     }
 }
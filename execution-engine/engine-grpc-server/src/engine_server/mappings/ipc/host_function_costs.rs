@@ -0,0 +1,184 @@
+use engine_wasm_prep::host_function_costs::{
+    HostFunctionCost, HostFunctionCosts, DEFAULT_HOST_FUNCTION_COST,
+};
+
+use crate::engine_server::ipc::{
+    ChainSpec_CostTable_HostFunctionCost, ChainSpec_CostTable_HostFunctionCosts,
+};
+
+impl From<HostFunctionCost> for ChainSpec_CostTable_HostFunctionCost {
+    fn from(host_function_cost: HostFunctionCost) -> Self {
+        ChainSpec_CostTable_HostFunctionCost {
+            base: host_function_cost.base,
+            per_byte: host_function_cost.per_byte,
+            ..Default::default()
+        }
+    }
+}
+
+impl From<ChainSpec_CostTable_HostFunctionCost> for HostFunctionCost {
+    fn from(pb_host_function_cost: ChainSpec_CostTable_HostFunctionCost) -> Self {
+        HostFunctionCost {
+            base: pb_host_function_cost.base,
+            per_byte: pb_host_function_cost.per_byte,
+        }
+    }
+}
+
+impl From<HostFunctionCosts> for ChainSpec_CostTable_HostFunctionCosts {
+    fn from(host_function_costs: HostFunctionCosts) -> Self {
+        let mut pb_host_function_costs = ChainSpec_CostTable_HostFunctionCosts::new();
+        pb_host_function_costs.set_read_value(host_function_costs.read_value.into());
+        pb_host_function_costs.set_read_value_local(host_function_costs.read_value_local.into());
+        pb_host_function_costs.set_load_named_keys(host_function_costs.load_named_keys.into());
+        pb_host_function_costs.set_write(host_function_costs.write.into());
+        pb_host_function_costs.set_write_local(host_function_costs.write_local.into());
+        pb_host_function_costs.set_add(host_function_costs.add.into());
+        pb_host_function_costs.set_add_local(host_function_costs.add_local.into());
+        pb_host_function_costs.set_new_uref(host_function_costs.new_uref.into());
+        pb_host_function_costs.set_get_arg_size(host_function_costs.get_arg_size.into());
+        pb_host_function_costs.set_get_arg(host_function_costs.get_arg.into());
+        pb_host_function_costs.set_ret(host_function_costs.ret.into());
+        pb_host_function_costs.set_call_contract(host_function_costs.call_contract.into());
+        pb_host_function_costs.set_get_key(host_function_costs.get_key.into());
+        pb_host_function_costs.set_has_key(host_function_costs.has_key.into());
+        pb_host_function_costs.set_put_key(host_function_costs.put_key.into());
+        pb_host_function_costs.set_store_function(host_function_costs.store_function.into());
+        pb_host_function_costs
+            .set_store_function_at_hash(host_function_costs.store_function_at_hash.into());
+        pb_host_function_costs.set_is_valid_uref(host_function_costs.is_valid_uref.into());
+        pb_host_function_costs.set_revert(host_function_costs.revert.into());
+        pb_host_function_costs
+            .set_add_associated_key(host_function_costs.add_associated_key.into());
+        pb_host_function_costs
+            .set_remove_associated_key(host_function_costs.remove_associated_key.into());
+        pb_host_function_costs
+            .set_update_associated_key(host_function_costs.update_associated_key.into());
+        pb_host_function_costs
+            .set_set_action_threshold(host_function_costs.set_action_threshold.into());
+        pb_host_function_costs.set_remove_key(host_function_costs.remove_key.into());
+        pb_host_function_costs.set_get_caller(host_function_costs.get_caller.into());
+        pb_host_function_costs.set_get_blocktime(host_function_costs.get_blocktime.into());
+        pb_host_function_costs.set_create_purse(host_function_costs.create_purse.into());
+        pb_host_function_costs
+            .set_transfer_to_account(host_function_costs.transfer_to_account.into());
+        pb_host_function_costs.set_transfer_from_purse_to_account(
+            host_function_costs.transfer_from_purse_to_account.into(),
+        );
+        pb_host_function_costs.set_transfer_from_purse_to_purse(
+            host_function_costs.transfer_from_purse_to_purse.into(),
+        );
+        pb_host_function_costs.set_get_balance(host_function_costs.get_balance.into());
+        pb_host_function_costs.set_get_phase(host_function_costs.get_phase.into());
+        pb_host_function_costs
+            .set_upgrade_contract_at_uref(host_function_costs.upgrade_contract_at_uref.into());
+        pb_host_function_costs
+            .set_get_system_contract(host_function_costs.get_system_contract.into());
+        pb_host_function_costs.set_get_main_purse(host_function_costs.get_main_purse.into());
+        pb_host_function_costs.set_read_host_buffer(host_function_costs.read_host_buffer.into());
+        pb_host_function_costs.set_emit_event(host_function_costs.emit_event.into());
+        pb_host_function_costs.set_verify_signature(host_function_costs.verify_signature.into());
+        pb_host_function_costs.set_hash(host_function_costs.hash.into());
+        pb_host_function_costs
+    }
+}
+
+/// Returns the cost of a host function, or the default cost if the node leaves it unset.
+fn or_default(maybe_pb_cost: Option<ChainSpec_CostTable_HostFunctionCost>) -> HostFunctionCost {
+    maybe_pb_cost
+        .map(Into::into)
+        .unwrap_or(DEFAULT_HOST_FUNCTION_COST)
+}
+
+/// The costs of the host functions the node leaves unset are set to the default cost, so an
+/// absent table charges [`DEFAULT_HOST_FUNCTION_COST`] for every host function.
+impl From<ChainSpec_CostTable_HostFunctionCosts> for HostFunctionCosts {
+    fn from(mut pb_host_function_costs: ChainSpec_CostTable_HostFunctionCosts) -> Self {
+        HostFunctionCosts {
+            read_value: or_default(pb_host_function_costs.read_value.take()),
+            read_value_local: or_default(pb_host_function_costs.read_value_local.take()),
+            load_named_keys: or_default(pb_host_function_costs.load_named_keys.take()),
+            write: or_default(pb_host_function_costs.write.take()),
+            write_local: or_default(pb_host_function_costs.write_local.take()),
+            add: or_default(pb_host_function_costs.add.take()),
+            add_local: or_default(pb_host_function_costs.add_local.take()),
+            new_uref: or_default(pb_host_function_costs.new_uref.take()),
+            get_arg_size: or_default(pb_host_function_costs.get_arg_size.take()),
+            get_arg: or_default(pb_host_function_costs.get_arg.take()),
+            ret: or_default(pb_host_function_costs.ret.take()),
+            call_contract: or_default(pb_host_function_costs.call_contract.take()),
+            get_key: or_default(pb_host_function_costs.get_key.take()),
+            has_key: or_default(pb_host_function_costs.has_key.take()),
+            put_key: or_default(pb_host_function_costs.put_key.take()),
+            store_function: or_default(pb_host_function_costs.store_function.take()),
+            store_function_at_hash: or_default(
+                pb_host_function_costs.store_function_at_hash.take(),
+            ),
+            is_valid_uref: or_default(pb_host_function_costs.is_valid_uref.take()),
+            revert: or_default(pb_host_function_costs.revert.take()),
+            add_associated_key: or_default(pb_host_function_costs.add_associated_key.take()),
+            remove_associated_key: or_default(pb_host_function_costs.remove_associated_key.take()),
+            update_associated_key: or_default(pb_host_function_costs.update_associated_key.take()),
+            set_action_threshold: or_default(pb_host_function_costs.set_action_threshold.take()),
+            remove_key: or_default(pb_host_function_costs.remove_key.take()),
+            get_caller: or_default(pb_host_function_costs.get_caller.take()),
+            get_blocktime: or_default(pb_host_function_costs.get_blocktime.take()),
+            create_purse: or_default(pb_host_function_costs.create_purse.take()),
+            transfer_to_account: or_default(pb_host_function_costs.transfer_to_account.take()),
+            transfer_from_purse_to_account: or_default(
+                pb_host_function_costs.transfer_from_purse_to_account.take(),
+            ),
+            transfer_from_purse_to_purse: or_default(
+                pb_host_function_costs.transfer_from_purse_to_purse.take(),
+            ),
+            get_balance: or_default(pb_host_function_costs.get_balance.take()),
+            get_phase: or_default(pb_host_function_costs.get_phase.take()),
+            upgrade_contract_at_uref: or_default(
+                pb_host_function_costs.upgrade_contract_at_uref.take(),
+            ),
+            get_system_contract: or_default(pb_host_function_costs.get_system_contract.take()),
+            get_main_purse: or_default(pb_host_function_costs.get_main_purse.take()),
+            read_host_buffer: or_default(pb_host_function_costs.read_host_buffer.take()),
+            emit_event: or_default(pb_host_function_costs.emit_event.take()),
+            verify_signature: or_default(pb_host_function_costs.verify_signature.take()),
+            hash: or_default(pb_host_function_costs.hash.take()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::proptest;
+
+    use engine_wasm_prep::host_function_costs::gens;
+
+    use super::*;
+    use crate::engine_server::mappings::test_utils;
+
+    proptest! {
+        #[test]
+        fn round_trip(host_function_costs in gens::host_function_costs_arb()) {
+            test_utils::protobuf_round_trip::<
+                HostFunctionCosts,
+                ChainSpec_CostTable_HostFunctionCosts,
+            >(host_function_costs);
+        }
+    }
+
+    #[test]
+    fn unset_costs_are_set_to_the_default() {
+        let mut pb_host_function_costs = ChainSpec_CostTable_HostFunctionCosts::new();
+        pb_host_function_costs.set_write(HostFunctionCost::new(7, 3).into());
+        let host_function_costs: HostFunctionCosts = pb_host_function_costs.into();
+        assert_eq!(host_function_costs.write, HostFunctionCost::new(7, 3));
+        assert_eq!(host_function_costs.read_value, DEFAULT_HOST_FUNCTION_COST);
+        assert_eq!(host_function_costs.hash, DEFAULT_HOST_FUNCTION_COST);
+
+        let host_function_costs: HostFunctionCosts =
+            ChainSpec_CostTable_HostFunctionCosts::new().into();
+        assert_eq!(
+            host_function_costs,
+            HostFunctionCosts::uniform(DEFAULT_HOST_FUNCTION_COST)
+        );
+    }
+}
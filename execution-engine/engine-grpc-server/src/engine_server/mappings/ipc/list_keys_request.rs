@@ -0,0 +1,83 @@
+use std::{
+    cmp,
+    convert::{TryFrom, TryInto},
+};
+
+use engine_core::engine_state::list_keys::ListKeysRequest;
+use engine_shared::newtypes::BLAKE2B_DIGEST_LENGTH;
+
+use crate::engine_server::{ipc, mappings::MappingError};
+
+/// The number of entries listed when a request does not give a limit.
+const DEFAULT_LIST_KEYS_LIMIT: u32 = 100;
+
+/// The most entries listed in a single response, whatever the requested limit.
+const MAX_LIST_KEYS_LIMIT: u32 = 1_000;
+
+impl TryFrom<ipc::ListKeysRequest> for ListKeysRequest {
+    type Error = MappingError;
+
+    fn try_from(mut list_keys_request: ipc::ListKeysRequest) -> Result<Self, Self::Error> {
+        let state_hash = {
+            let state_hash = list_keys_request.get_state_hash();
+            let length = state_hash.len();
+            if length != BLAKE2B_DIGEST_LENGTH {
+                return Err(MappingError::InvalidStateHashLength {
+                    expected: BLAKE2B_DIGEST_LENGTH,
+                    actual: length,
+                });
+            }
+            state_hash
+                .try_into()
+                .map_err(|_| MappingError::TryFromSliceError)?
+        };
+
+        let prefix = list_keys_request.take_prefix();
+
+        let start = list_keys_request.take_start();
+
+        let limit = match list_keys_request.get_limit() {
+            0 => DEFAULT_LIST_KEYS_LIMIT,
+            limit => cmp::min(limit, MAX_LIST_KEYS_LIMIT),
+        };
+
+        Ok(ListKeysRequest::new(
+            state_hash,
+            prefix,
+            start,
+            limit as usize,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn list_keys_request(limit: u32) -> ipc::ListKeysRequest {
+        let mut pb_request = ipc::ListKeysRequest::new();
+        pb_request.set_state_hash(vec![1; BLAKE2B_DIGEST_LENGTH]);
+        pb_request.set_prefix(vec![0]);
+        pb_request.set_start(vec![0, 2]);
+        pb_request.set_limit(limit);
+        pb_request
+    }
+
+    #[test]
+    fn should_map_request() {
+        let request = ListKeysRequest::try_from(list_keys_request(10)).unwrap();
+        assert_eq!(request.state_hash(), [1; BLAKE2B_DIGEST_LENGTH].into());
+        assert_eq!(request.prefix(), &[0]);
+        assert_eq!(request.start(), &[0, 2]);
+        assert_eq!(request.limit(), 10);
+    }
+
+    #[test]
+    fn should_default_and_cap_limit() {
+        let request = ListKeysRequest::try_from(list_keys_request(0)).unwrap();
+        assert_eq!(request.limit(), DEFAULT_LIST_KEYS_LIMIT as usize);
+
+        let request = ListKeysRequest::try_from(list_keys_request(u32::max_value())).unwrap();
+        assert_eq!(request.limit(), MAX_LIST_KEYS_LIMIT as usize);
+    }
+}
@@ -0,0 +1,52 @@
+use std::cmp;
+
+use engine_core::engine_state::list_state_roots::ListStateRootsRequest;
+
+use crate::engine_server::ipc;
+
+/// The number of state roots listed when a request does not give a limit.
+const DEFAULT_LIST_STATE_ROOTS_LIMIT: u32 = 100;
+
+/// The most state roots listed in a single response, whatever the requested limit.
+const MAX_LIST_STATE_ROOTS_LIMIT: u32 = 1_000;
+
+impl From<ipc::ListStateRootsRequest> for ListStateRootsRequest {
+    fn from(mut list_state_roots_request: ipc::ListStateRootsRequest) -> Self {
+        let start = list_state_roots_request.take_start();
+
+        let limit = match list_state_roots_request.get_limit() {
+            0 => DEFAULT_LIST_STATE_ROOTS_LIMIT,
+            limit => cmp::min(limit, MAX_LIST_STATE_ROOTS_LIMIT),
+        };
+
+        ListStateRootsRequest::new(start, limit as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn list_state_roots_request(limit: u32) -> ipc::ListStateRootsRequest {
+        let mut pb_request = ipc::ListStateRootsRequest::new();
+        pb_request.set_start(vec![0, 2]);
+        pb_request.set_limit(limit);
+        pb_request
+    }
+
+    #[test]
+    fn should_map_request() {
+        let request = ListStateRootsRequest::from(list_state_roots_request(10));
+        assert_eq!(request.start(), &[0, 2]);
+        assert_eq!(request.limit(), 10);
+    }
+
+    #[test]
+    fn should_default_and_cap_limit() {
+        let request = ListStateRootsRequest::from(list_state_roots_request(0));
+        assert_eq!(request.limit(), DEFAULT_LIST_STATE_ROOTS_LIMIT as usize);
+
+        let request = ListStateRootsRequest::from(list_state_roots_request(u32::max_value()));
+        assert_eq!(request.limit(), MAX_LIST_STATE_ROOTS_LIMIT as usize);
+    }
+}
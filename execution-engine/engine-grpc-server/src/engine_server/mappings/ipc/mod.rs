@@ -2,12 +2,22 @@
 //! defined in protobuf/io/casperlabs/ipc/ipc.proto
 
 mod bond;
+mod contract_event;
 mod deploy_item;
 mod deploy_result;
+mod diff_state_request;
 mod executable_deploy_item;
 mod execution_effect;
+mod execution_trace;
 mod genesis_account;
 mod genesis_config;
+mod host_function_costs;
+mod list_keys_request;
+mod list_state_roots_request;
+mod proof_of_stake_config;
 mod query_request;
+mod reward_weight;
+mod state_diff;
+mod trie_merkle_proof;
 mod upgrade_request;
 mod wasm_costs;
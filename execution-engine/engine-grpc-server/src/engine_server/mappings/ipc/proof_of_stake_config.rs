@@ -0,0 +1,60 @@
+use std::convert::{TryFrom, TryInto};
+
+use contract_ffi::system_contracts::pos::Config;
+
+use crate::engine_server::{ipc::ChainSpec_ProofOfStakeConfig, mappings::MappingError};
+
+impl From<Config> for ChainSpec_ProofOfStakeConfig {
+    fn from(config: Config) -> Self {
+        let mut pb_config = ChainSpec_ProofOfStakeConfig::new();
+
+        pb_config.set_bond_delay(config.bond_delay);
+        pb_config.set_unbond_delay(config.unbond_delay);
+        pb_config.set_max_bond_len(config.max_bond_len);
+        pb_config.set_max_unbond_len(config.max_unbond_len);
+        pb_config.set_max_spread(config.max_spread.into());
+        pb_config.set_max_increase(config.max_increase.into());
+        pb_config.set_max_decrease(config.max_decrease.into());
+        pb_config.set_max_rel_increase(config.max_rel_increase);
+        pb_config.set_max_rel_decrease(config.max_rel_decrease);
+        pb_config.set_delegation_commission(config.delegation_commission);
+
+        pb_config
+    }
+}
+
+impl TryFrom<ChainSpec_ProofOfStakeConfig> for Config {
+    type Error = MappingError;
+
+    fn try_from(mut pb_config: ChainSpec_ProofOfStakeConfig) -> Result<Self, Self::Error> {
+        Ok(Config {
+            bond_delay: pb_config.get_bond_delay(),
+            unbond_delay: pb_config.get_unbond_delay(),
+            max_bond_len: pb_config.get_max_bond_len(),
+            max_unbond_len: pb_config.get_max_unbond_len(),
+            max_spread: pb_config.take_max_spread().try_into()?,
+            max_increase: pb_config.take_max_increase().try_into()?,
+            max_decrease: pb_config.take_max_decrease().try_into()?,
+            max_rel_increase: pb_config.get_max_rel_increase(),
+            max_rel_decrease: pb_config.get_max_rel_decrease(),
+            delegation_commission: pb_config.get_delegation_commission(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::proptest;
+
+    use contract_ffi::gens;
+
+    use super::*;
+    use crate::engine_server::mappings::test_utils;
+
+    proptest! {
+        #[test]
+        fn round_trip(config in gens::pos_config_arb()) {
+            test_utils::protobuf_round_trip::<Config, ChainSpec_ProofOfStakeConfig>(config);
+        }
+    }
+}
@@ -0,0 +1,46 @@
+use std::convert::{TryFrom, TryInto};
+
+use contract_ffi::value::account::PublicKey;
+
+use crate::engine_server::{ipc::RewardWeight, mappings::MappingError};
+
+impl From<(PublicKey, u64)> for RewardWeight {
+    fn from((key, weight): (PublicKey, u64)) -> Self {
+        let mut pb_reward_weight = RewardWeight::new();
+        pb_reward_weight.set_validator_public_key(key.to_vec());
+        pb_reward_weight.set_weight(weight);
+        pb_reward_weight
+    }
+}
+
+impl TryFrom<RewardWeight> for (PublicKey, u64) {
+    type Error = MappingError;
+
+    fn try_from(pb_reward_weight: RewardWeight) -> Result<Self, Self::Error> {
+        let public_key = pb_reward_weight
+            .get_validator_public_key()
+            .try_into()
+            .map_err(|_| {
+                MappingError::invalid_public_key_length(pb_reward_weight.validator_public_key.len())
+            })?;
+
+        Ok((public_key, pb_reward_weight.get_weight()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::{num, proptest};
+
+    use contract_ffi::gens;
+
+    use super::*;
+    use crate::engine_server::mappings::test_utils;
+
+    proptest! {
+        #[test]
+        fn round_trip(public_key in gens::public_key_arb(), weight in num::u64::ANY) {
+            test_utils::protobuf_round_trip::<(PublicKey, u64), RewardWeight>((public_key, weight));
+        }
+    }
+}
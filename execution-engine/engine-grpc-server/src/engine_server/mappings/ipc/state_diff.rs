@@ -0,0 +1,109 @@
+use std::convert::{TryFrom, TryInto};
+
+use contract_ffi::key::Key;
+use engine_shared::stored_value::StoredValue;
+use engine_storage::trie_store::operations::Difference;
+
+use crate::engine_server::{ipc::StateDiff_Entry, mappings::ParsingError};
+
+impl TryFrom<Difference<Key, StoredValue>> for StateDiff_Entry {
+    type Error = ParsingError;
+
+    fn try_from(difference: Difference<Key, StoredValue>) -> Result<Self, Self::Error> {
+        let mut pb_entry = StateDiff_Entry::new();
+        match difference {
+            Difference::Added { key, value } => {
+                pb_entry.set_key(key.into());
+                pb_entry.set_new_value(value.try_into()?);
+            }
+            Difference::Removed { key, value } => {
+                pb_entry.set_key(key.into());
+                pb_entry.set_old_value(value.try_into()?);
+            }
+            Difference::Modified {
+                key,
+                old_value,
+                new_value,
+            } => {
+                pb_entry.set_key(key.into());
+                pb_entry.set_old_value(old_value.try_into()?);
+                pb_entry.set_new_value(new_value.try_into()?);
+            }
+        }
+        Ok(pb_entry)
+    }
+}
+
+impl TryFrom<StateDiff_Entry> for Difference<Key, StoredValue> {
+    type Error = ParsingError;
+
+    fn try_from(mut pb_entry: StateDiff_Entry) -> Result<Self, Self::Error> {
+        let key = pb_entry.take_key().try_into()?;
+        let maybe_old_value = if pb_entry.has_old_value() {
+            Some(pb_entry.take_old_value().try_into()?)
+        } else {
+            None
+        };
+        let maybe_new_value = if pb_entry.has_new_value() {
+            Some(pb_entry.take_new_value().try_into()?)
+        } else {
+            None
+        };
+        match (maybe_old_value, maybe_new_value) {
+            (None, Some(value)) => Ok(Difference::Added { key, value }),
+            (Some(value), None) => Ok(Difference::Removed { key, value }),
+            (Some(old_value), Some(new_value)) => Ok(Difference::Modified {
+                key,
+                old_value,
+                new_value,
+            }),
+            (None, None) => Err(ParsingError::from(
+                "Unable to parse Protobuf StateDiff entry with no values",
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::proptest;
+
+    use contract_ffi::gens;
+    use engine_shared::stored_value::gens::stored_value_arb;
+
+    use super::*;
+
+    fn round_trip(difference: Difference<Key, StoredValue>) {
+        let pb_entry: StateDiff_Entry = difference.clone().try_into().unwrap();
+        let parsed_difference: Difference<Key, StoredValue> = pb_entry.try_into().unwrap();
+        assert_eq!(difference, parsed_difference);
+    }
+
+    proptest! {
+        #[test]
+        fn added_round_trip(key in gens::key_arb(), value in stored_value_arb()) {
+            round_trip(Difference::Added { key, value });
+        }
+
+        #[test]
+        fn removed_round_trip(key in gens::key_arb(), value in stored_value_arb()) {
+            round_trip(Difference::Removed { key, value });
+        }
+
+        #[test]
+        fn modified_round_trip(
+            key in gens::key_arb(),
+            old_value in stored_value_arb(),
+            new_value in stored_value_arb()
+        ) {
+            round_trip(Difference::Modified { key, old_value, new_value });
+        }
+    }
+
+    #[test]
+    fn should_fail_to_parse_entry_without_values() {
+        let mut pb_entry = StateDiff_Entry::new();
+        pb_entry.set_key(Key::Hash([1; 32]).into());
+        assert!(Difference::<Key, StoredValue>::try_from(pb_entry).is_err());
+    }
+}
@@ -0,0 +1,64 @@
+use std::convert::{TryFrom, TryInto};
+
+use contract_ffi::{
+    bytesrepr::{self, ToBytes},
+    key::Key,
+};
+use engine_shared::stored_value::StoredValue;
+use engine_storage::trie::{merkle_proof::TrieMerkleProof, Trie};
+
+use crate::engine_server::{ipc, mappings::ParsingError};
+
+impl TryFrom<TrieMerkleProof<Key, StoredValue>> for ipc::TrieMerkleProof {
+    type Error = ParsingError;
+
+    fn try_from(proof: TrieMerkleProof<Key, StoredValue>) -> Result<Self, Self::Error> {
+        let (key, steps) = proof.destructure();
+
+        let trie_elements = steps
+            .iter()
+            .map(ToBytes::to_bytes)
+            .collect::<Result<Vec<Vec<u8>>, bytesrepr::Error>>()?;
+
+        let mut pb_proof = ipc::TrieMerkleProof::new();
+        pb_proof.set_key(key.into());
+        pb_proof.set_trie_elements(trie_elements.into());
+        Ok(pb_proof)
+    }
+}
+
+impl TryFrom<ipc::TrieMerkleProof> for TrieMerkleProof<Key, StoredValue> {
+    type Error = ParsingError;
+
+    fn try_from(mut pb_proof: ipc::TrieMerkleProof) -> Result<Self, Self::Error> {
+        let key = pb_proof.take_key().try_into()?;
+        let steps = pb_proof
+            .take_trie_elements()
+            .into_iter()
+            .map(bytesrepr::deserialize::<Trie<Key, StoredValue>>)
+            .collect::<Result<Vec<_>, bytesrepr::Error>>()?;
+        Ok(TrieMerkleProof::new(key, steps))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::proptest;
+
+    use contract_ffi::gens;
+    use engine_shared::stored_value::gens::stored_value_arb;
+
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn round_trip(key in gens::key_arb(), value in stored_value_arb()) {
+            let leaf = Trie::Leaf { key, value };
+            let proof = TrieMerkleProof::new(key, vec![leaf]);
+
+            let pb_proof: ipc::TrieMerkleProof = proof.clone().try_into().unwrap();
+            let parsed_proof: TrieMerkleProof<Key, StoredValue> = pb_proof.try_into().unwrap();
+            assert_eq!(proof, parsed_proof);
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use std::convert::{TryFrom, TryInto};
 
-use contract_ffi::value::ProtocolVersion;
+use contract_ffi::{block_time::BlockTime, value::ProtocolVersion};
 use engine_core::engine_state::upgrade::UpgradeConfig;
 
 use crate::engine_server::{ipc::UpgradeRequest, mappings::MappingError};
@@ -16,6 +16,8 @@ impl TryFrom<UpgradeRequest> for UpgradeConfig {
 
         let current_protocol_version = pb_upgrade_request.take_protocol_version().into();
 
+        let block_time = BlockTime::new(pb_upgrade_request.get_block_time());
+
         let upgrade_point = pb_upgrade_request.mut_upgrade_point();
         let new_protocol_version: ProtocolVersion = upgrade_point.take_protocol_version().into();
         let (upgrade_installer_bytes, upgrade_installer_args) =
@@ -30,16 +32,26 @@ impl TryFrom<UpgradeRequest> for UpgradeConfig {
                 (bytes, args)
             };
 
-        let wasm_costs = if !upgrade_point.has_new_costs() {
+        let wasm_costs = if !upgrade_point.get_new_costs().has_wasm() {
             None
         } else {
             Some(upgrade_point.mut_new_costs().take_wasm().into())
         };
+        let host_function_costs = if !upgrade_point.get_new_costs().has_host() {
+            None
+        } else {
+            Some(upgrade_point.mut_new_costs().take_host().into())
+        };
         let activation_point = if !upgrade_point.has_activation_point() {
             None
         } else {
             Some(upgrade_point.get_activation_point().rank)
         };
+        let proof_of_stake_config = if !upgrade_point.has_new_pos_config() {
+            None
+        } else {
+            Some(upgrade_point.take_new_pos_config().try_into()?)
+        };
 
         Ok(UpgradeConfig::new(
             pre_state_hash,
@@ -48,7 +60,10 @@ impl TryFrom<UpgradeRequest> for UpgradeConfig {
             upgrade_installer_args,
             upgrade_installer_bytes,
             wasm_costs,
+            host_function_costs,
             activation_point,
+            block_time,
+            proof_of_stake_config,
         ))
     }
 }
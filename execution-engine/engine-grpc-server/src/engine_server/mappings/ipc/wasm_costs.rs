@@ -1,4 +1,7 @@
-use engine_wasm_prep::wasm_costs::WasmCosts;
+use engine_wasm_prep::wasm_costs::{
+    WasmCosts, DEFAULT_HASH_BLAKE2B_COST, DEFAULT_HASH_KECCAK256_COST, DEFAULT_HASH_SHA256_COST,
+    DEFAULT_VERIFY_ED25519_COST, DEFAULT_VERIFY_SECP256K1_COST,
+};
 
 use crate::engine_server::ipc::ChainSpec_CostTable_WasmCosts;
 
@@ -15,11 +18,27 @@ impl From<WasmCosts> for ChainSpec_CostTable_WasmCosts {
             max_stack_height: wasm_costs.max_stack_height,
             opcodes_mul: wasm_costs.opcodes_mul,
             opcodes_div: wasm_costs.opcodes_div,
+            verify_ed25519: wasm_costs.verify_ed25519,
+            verify_secp256k1: wasm_costs.verify_secp256k1,
+            hash_blake2b: wasm_costs.hash_blake2b,
+            hash_sha256: wasm_costs.hash_sha256,
+            hash_keccak256: wasm_costs.hash_keccak256,
             ..Default::default()
         }
     }
 }
 
+/// Returns `cost`, or `default` if the cost is unset.
+fn or_default(cost: u32, default: u32) -> u32 {
+    if cost == 0 {
+        default
+    } else {
+        cost
+    }
+}
+
+/// The costs of the signature verification and hash host functions are set to their defaults if
+/// the node leaves them unset.
 impl From<ChainSpec_CostTable_WasmCosts> for WasmCosts {
     fn from(pb_wasm_costs: ChainSpec_CostTable_WasmCosts) -> Self {
         WasmCosts {
@@ -33,6 +52,14 @@ impl From<ChainSpec_CostTable_WasmCosts> for WasmCosts {
             max_stack_height: pb_wasm_costs.max_stack_height,
             opcodes_mul: pb_wasm_costs.opcodes_mul,
             opcodes_div: pb_wasm_costs.opcodes_div,
+            verify_ed25519: or_default(pb_wasm_costs.verify_ed25519, DEFAULT_VERIFY_ED25519_COST),
+            verify_secp256k1: or_default(
+                pb_wasm_costs.verify_secp256k1,
+                DEFAULT_VERIFY_SECP256K1_COST,
+            ),
+            hash_blake2b: or_default(pb_wasm_costs.hash_blake2b, DEFAULT_HASH_BLAKE2B_COST),
+            hash_sha256: or_default(pb_wasm_costs.hash_sha256, DEFAULT_HASH_SHA256_COST),
+            hash_keccak256: or_default(pb_wasm_costs.hash_keccak256, DEFAULT_HASH_KECCAK256_COST),
         }
     }
 }
@@ -52,4 +79,19 @@ mod tests {
             test_utils::protobuf_round_trip::<WasmCosts, ChainSpec_CostTable_WasmCosts>(wasm_costs);
         }
     }
+
+    #[test]
+    fn unset_crypto_costs_are_set_to_defaults() {
+        let pb_wasm_costs = ChainSpec_CostTable_WasmCosts {
+            regular: 1,
+            ..Default::default()
+        };
+        let wasm_costs: WasmCosts = pb_wasm_costs.into();
+        assert_eq!(wasm_costs.regular, 1);
+        assert_eq!(wasm_costs.verify_ed25519, DEFAULT_VERIFY_ED25519_COST);
+        assert_eq!(wasm_costs.verify_secp256k1, DEFAULT_VERIFY_SECP256K1_COST);
+        assert_eq!(wasm_costs.hash_blake2b, DEFAULT_HASH_BLAKE2B_COST);
+        assert_eq!(wasm_costs.hash_sha256, DEFAULT_HASH_SHA256_COST);
+        assert_eq!(wasm_costs.hash_keccak256, DEFAULT_HASH_KECCAK256_COST);
+    }
 }
@@ -5,6 +5,7 @@ pub mod state;
 pub mod transforms;
 
 use std::{
+    cmp,
     collections::BTreeMap,
     convert::{TryFrom, TryInto},
     fmt::Debug,
@@ -16,29 +17,50 @@ use std::{
 
 use grpc::{RequestOptions, ServerBuilder, SingleResponse};
 
-use contract_ffi::{block_time::BlockTime, value::ProtocolVersion};
+use contract_ffi::{
+    block_time::BlockTime,
+    bytesrepr::{self, ToBytes},
+    key::Key,
+    value::{account::PublicKey, ProtocolVersion, U512},
+};
 use engine_core::{
     engine_state::{
         deploy_item::DeployItem,
+        diff_state::{DiffStateRequest, DiffStateResult},
+        execute_and_commit::ExecuteAndCommitResult,
         execution_result::ExecutionResult,
         genesis::{GenesisConfig, GenesisResult},
+        list_keys::{ListKeysRequest, ListKeysResult},
+        list_state_roots::{ListStateRootsRequest, ListStateRootsResult},
         query::{QueryRequest, QueryResult},
+        step::StepResult,
         upgrade::{UpgradeConfig, UpgradeResult},
-        EngineState, Error as EngineError,
+        EngineState, Error as EngineError, CONV_RATE, MAX_PAYMENT,
     },
     execution::Executor,
 };
 use engine_shared::{
+    gas::Gas,
     logging::{self, log_duration, log_info, log_level::LogLevel},
+    motes::Motes,
     newtypes::{Blake2bHash, CorrelationId, BLAKE2B_DIGEST_LENGTH},
+    stored_value::StoredValue,
+};
+use engine_storage::{
+    global_state::{CommitResult, StateProvider},
+    trie::{merkle_proof::TrieMerkleProof, Trie},
+    trie_store::operations::MissingTrieKeys,
 };
-use engine_storage::global_state::{CommitResult, StateProvider};
 use engine_wasm_prep::Preprocessor;
 
 use self::{
     ipc::{
-        ChainSpec_GenesisConfig, CommitRequest, CommitResponse, ExecuteRequest, ExecuteResponse,
-        GenesisResponse, QueryResponse, UpgradeRequest, UpgradeResponse,
+        ChainSpec_GenesisConfig, CommitRequest, CommitResponse, DeployResult, DiffStateResponse,
+        EstimateGasRequest, EstimateGasResponse, ExecuteAndCommitRequest, ExecuteAndCommitResponse,
+        ExecuteRequest, ExecuteResponse, GenesisResponse, GetTrieRequest, GetTrieResponse,
+        ListKeysResponse, ListStateRootsResponse, MissingTrieKeysRequest, MissingTrieKeysResponse,
+        PutTrieRequest, PutTrieResponse, QueryResponse, SlashRequest, SlashResponse,
+        StateDiff_Entry, StateRoot, StepRequest, StepResponse, UpgradeRequest, UpgradeResponse,
     },
     ipc_grpc::{ExecutionEngineService, ExecutionEngineServiceServer},
     mappings::{MappingError, ParsingError, TransformMap},
@@ -46,18 +68,44 @@ use self::{
 
 const METRIC_DURATION_COMMIT: &str = "commit_duration";
 const METRIC_DURATION_EXEC: &str = "exec_duration";
+const METRIC_DURATION_EXEC_AND_COMMIT: &str = "exec_and_commit_duration";
+const METRIC_DURATION_ESTIMATE_GAS: &str = "estimate_gas_duration";
+const METRIC_DURATION_STEP: &str = "step_duration";
+const METRIC_DURATION_SLASH: &str = "slash_duration";
 const METRIC_DURATION_QUERY: &str = "query_duration";
+const METRIC_DURATION_LIST_KEYS: &str = "list_keys_duration";
+const METRIC_DURATION_DIFF_STATE: &str = "diff_state_duration";
+const METRIC_DURATION_GET_TRIE: &str = "get_trie_duration";
+const METRIC_DURATION_PUT_TRIE: &str = "put_trie_duration";
+const METRIC_DURATION_MISSING_TRIE_KEYS: &str = "missing_trie_keys_duration";
+const METRIC_DURATION_LIST_STATE_ROOTS: &str = "list_state_roots_duration";
 const METRIC_DURATION_GENESIS: &str = "genesis_duration";
 const METRIC_DURATION_UPGRADE: &str = "upgrade_duration";
 
 const TAG_RESPONSE_COMMIT: &str = "commit_response";
 const TAG_RESPONSE_EXEC: &str = "exec_response";
+const TAG_RESPONSE_EXEC_AND_COMMIT: &str = "exec_and_commit_response";
+const TAG_RESPONSE_ESTIMATE_GAS: &str = "estimate_gas_response";
+const TAG_RESPONSE_STEP: &str = "step_response";
+const TAG_RESPONSE_SLASH: &str = "slash_response";
 const TAG_RESPONSE_QUERY: &str = "query_response";
+const TAG_RESPONSE_LIST_KEYS: &str = "list_keys_response";
+const TAG_RESPONSE_DIFF_STATE: &str = "diff_state_response";
+const TAG_RESPONSE_GET_TRIE: &str = "get_trie_response";
+const TAG_RESPONSE_PUT_TRIE: &str = "put_trie_response";
+const TAG_RESPONSE_MISSING_TRIE_KEYS: &str = "missing_trie_keys_response";
+const TAG_RESPONSE_LIST_STATE_ROOTS: &str = "list_state_roots_response";
 const TAG_RESPONSE_GENESIS: &str = "genesis_response";
 const TAG_RESPONSE_UPGRADE: &str = "upgrade_response";
 
 const DEFAULT_PROTOCOL_VERSION: ProtocolVersion = ProtocolVersion::V1_0_0;
 
+/// The number of missing trie keys listed when a request does not give a limit.
+const DEFAULT_MISSING_TRIE_KEYS_LIMIT: u32 = 1_000;
+
+/// The most missing trie keys listed in a single response, whatever the requested limit.
+const MAX_MISSING_TRIE_KEYS_LIMIT: u32 = 10_000;
+
 // Idea is that Engine will represent the core of the execution engine project.
 // It will act as an entry point for execution of Wasm binaries.
 // Proto definitions should be translated into domain objects when Engine's API
@@ -97,8 +145,9 @@ where
         let result = self.run_query(correlation_id, request);
 
         let response = match result {
-            Ok(QueryResult::Success(value)) => {
+            Ok(QueryResult::Success { value, proofs }) => {
                 let mut result = ipc::QueryResponse::new();
+                set_query_proofs(&mut result, proofs);
                 match value.try_into() {
                     Ok(pb_value) => {
                         let log_message =
@@ -115,10 +164,11 @@ where
                 }
                 result
             }
-            Ok(QueryResult::ValueNotFound(full_path)) => {
+            Ok(QueryResult::ValueNotFound { full_path, proofs }) => {
                 let log_message = format!("Value not found: {:?}", full_path);
                 logging::log_warning(&log_message);
                 let mut result = ipc::QueryResponse::new();
+                set_query_proofs(&mut result, proofs);
                 result.set_failure(log_message);
                 result
             }
@@ -148,6 +198,381 @@ where
         SingleResponse::completed(response)
     }
 
+    fn list_keys(
+        &self,
+        _request_options: RequestOptions,
+        list_keys_request: ipc::ListKeysRequest,
+    ) -> SingleResponse<ListKeysResponse> {
+        let start = Instant::now();
+        let correlation_id = CorrelationId::new();
+
+        let mut response = ListKeysResponse::new();
+
+        let request: ListKeysRequest = match list_keys_request.try_into() {
+            Ok(request) => request,
+            Err(error) => {
+                let log_message = format!("{:?}", error);
+                logging::log_error(&log_message);
+                response.set_failure(log_message);
+                log_duration(
+                    correlation_id,
+                    METRIC_DURATION_LIST_KEYS,
+                    TAG_RESPONSE_LIST_KEYS,
+                    start.elapsed(),
+                );
+                return SingleResponse::completed(response);
+            }
+        };
+        let state_hash = request.state_hash();
+
+        match self.list_keys(correlation_id, request) {
+            Ok(ListKeysResult::Success {
+                entries,
+                next_start,
+            }) => {
+                let maybe_pb_entries = entries
+                    .into_iter()
+                    .map(|(key, value)| {
+                        let mut pb_entry = ipc::ListKeysResult_Entry::new();
+                        pb_entry.set_key(key.into());
+                        pb_entry.set_value(value.try_into()?);
+                        Ok(pb_entry)
+                    })
+                    .collect::<Result<Vec<ipc::ListKeysResult_Entry>, ParsingError>>();
+                match maybe_pb_entries {
+                    Ok(pb_entries) => {
+                        let result = response.mut_success();
+                        result.set_entries(pb_entries.into());
+                        result.set_next_start(next_start.unwrap_or_default());
+                    }
+                    Err(ParsingError(error_msg)) => {
+                        let log_message =
+                            format!("Failed to convert StoredValue to Value: {}", error_msg);
+                        logging::log_error(&log_message);
+                        response.set_failure(log_message);
+                    }
+                }
+            }
+            Ok(ListKeysResult::RootNotFound) => {
+                logging::log_warning("RootNotFound");
+                response.mut_missing_parent().set_hash(state_hash.to_vec());
+            }
+            Err(error) => {
+                let log_message = format!("{:?}", error);
+                logging::log_error(&log_message);
+                response.set_failure(log_message);
+            }
+        }
+
+        log_duration(
+            correlation_id,
+            METRIC_DURATION_LIST_KEYS,
+            TAG_RESPONSE_LIST_KEYS,
+            start.elapsed(),
+        );
+
+        SingleResponse::completed(response)
+    }
+
+    fn diff_state(
+        &self,
+        _request_options: RequestOptions,
+        diff_state_request: ipc::DiffStateRequest,
+    ) -> SingleResponse<DiffStateResponse> {
+        let start = Instant::now();
+        let correlation_id = CorrelationId::new();
+
+        let mut response = DiffStateResponse::new();
+
+        let request: DiffStateRequest = match diff_state_request.try_into() {
+            Ok(request) => request,
+            Err(error) => {
+                let log_message = format!("{:?}", error);
+                logging::log_error(&log_message);
+                response.set_failure(log_message);
+                log_duration(
+                    correlation_id,
+                    METRIC_DURATION_DIFF_STATE,
+                    TAG_RESPONSE_DIFF_STATE,
+                    start.elapsed(),
+                );
+                return SingleResponse::completed(response);
+            }
+        };
+
+        match self.diff_state(correlation_id, request) {
+            Ok(DiffStateResult::Success {
+                differences,
+                next_start,
+            }) => {
+                let maybe_pb_entries = differences
+                    .into_iter()
+                    .map(TryInto::try_into)
+                    .collect::<Result<Vec<StateDiff_Entry>, ParsingError>>();
+                match maybe_pb_entries {
+                    Ok(pb_entries) => {
+                        let result = response.mut_success();
+                        result.set_entries(pb_entries.into());
+                        result.set_next_start(next_start.unwrap_or_default());
+                    }
+                    Err(ParsingError(error_msg)) => {
+                        let log_message =
+                            format!("Failed to convert StoredValue to Value: {}", error_msg);
+                        logging::log_error(&log_message);
+                        response.set_failure(log_message);
+                    }
+                }
+            }
+            Ok(DiffStateResult::RootNotFound(state_hash)) => {
+                logging::log_warning("RootNotFound");
+                response.mut_missing_parent().set_hash(state_hash.to_vec());
+            }
+            Err(error) => {
+                let log_message = format!("{:?}", error);
+                logging::log_error(&log_message);
+                response.set_failure(log_message);
+            }
+        }
+
+        log_duration(
+            correlation_id,
+            METRIC_DURATION_DIFF_STATE,
+            TAG_RESPONSE_DIFF_STATE,
+            start.elapsed(),
+        );
+
+        SingleResponse::completed(response)
+    }
+
+    fn get_trie(
+        &self,
+        _request_options: RequestOptions,
+        get_trie_request: GetTrieRequest,
+    ) -> SingleResponse<GetTrieResponse> {
+        let start = Instant::now();
+        let correlation_id = CorrelationId::new();
+
+        let mut response = GetTrieResponse::new();
+
+        let trie_key = match Blake2bHash::try_from(get_trie_request.get_trie_key()) {
+            Ok(trie_key) => trie_key,
+            Err(_) => {
+                let log_message = "Could not parse trie key".to_string();
+                logging::log_error(&log_message);
+                response.set_failure(log_message);
+                log_duration(
+                    correlation_id,
+                    METRIC_DURATION_GET_TRIE,
+                    TAG_RESPONSE_GET_TRIE,
+                    start.elapsed(),
+                );
+                return SingleResponse::completed(response);
+            }
+        };
+
+        match self.get_trie(&trie_key) {
+            Ok(Some(trie)) => match trie.to_bytes() {
+                Ok(trie_bytes) => response.set_trie(trie_bytes),
+                Err(error) => {
+                    let log_message = format!("Failed to serialize trie element: {:?}", error);
+                    logging::log_error(&log_message);
+                    response.set_failure(log_message);
+                }
+            },
+            Ok(None) => {
+                logging::log_warning("TrieNotFound");
+                response.mut_not_found().set_trie_key(trie_key.to_vec());
+            }
+            Err(error) => {
+                let log_message = format!("{:?}", error);
+                logging::log_error(&log_message);
+                response.set_failure(log_message);
+            }
+        }
+
+        log_duration(
+            correlation_id,
+            METRIC_DURATION_GET_TRIE,
+            TAG_RESPONSE_GET_TRIE,
+            start.elapsed(),
+        );
+
+        SingleResponse::completed(response)
+    }
+
+    fn put_trie(
+        &self,
+        _request_options: RequestOptions,
+        mut put_trie_request: PutTrieRequest,
+    ) -> SingleResponse<PutTrieResponse> {
+        let start = Instant::now();
+        let correlation_id = CorrelationId::new();
+
+        let mut response = PutTrieResponse::new();
+
+        let maybe_tries = put_trie_request
+            .take_entries()
+            .into_iter()
+            .map(|mut entry| {
+                let trie_key = Blake2bHash::try_from(entry.get_trie_key())
+                    .map_err(|_| "Could not parse trie key".to_string())?;
+                let trie_bytes = entry.take_trie();
+                let actual_trie_key = Blake2bHash::new(&trie_bytes);
+                if actual_trie_key != trie_key {
+                    return Err(format!(
+                        "Trie element hashes to {} rather than {}",
+                        actual_trie_key, trie_key
+                    ));
+                }
+                bytesrepr::deserialize::<Trie<Key, StoredValue>>(trie_bytes)
+                    .map_err(|error| format!("Could not parse trie element: {:?}", error))
+            })
+            .collect::<Result<Vec<Trie<Key, StoredValue>>, String>>();
+
+        match maybe_tries {
+            Ok(tries) => match self.put_tries(&tries) {
+                Ok(trie_keys) => {
+                    let pb_trie_keys: Vec<Vec<u8>> =
+                        trie_keys.iter().map(Blake2bHash::to_vec).collect();
+                    response.mut_success().set_trie_keys(pb_trie_keys.into());
+                }
+                Err(error) => {
+                    let log_message = format!("{:?}", error);
+                    logging::log_error(&log_message);
+                    response.set_failure(log_message);
+                }
+            },
+            Err(log_message) => {
+                logging::log_error(&log_message);
+                response.set_failure(log_message);
+            }
+        }
+
+        log_duration(
+            correlation_id,
+            METRIC_DURATION_PUT_TRIE,
+            TAG_RESPONSE_PUT_TRIE,
+            start.elapsed(),
+        );
+
+        SingleResponse::completed(response)
+    }
+
+    fn missing_trie_keys(
+        &self,
+        _request_options: RequestOptions,
+        missing_trie_keys_request: MissingTrieKeysRequest,
+    ) -> SingleResponse<MissingTrieKeysResponse> {
+        let start = Instant::now();
+        let correlation_id = CorrelationId::new();
+
+        let mut response = MissingTrieKeysResponse::new();
+
+        let maybe_trie_keys = missing_trie_keys_request
+            .get_trie_keys()
+            .iter()
+            .map(|trie_key| Blake2bHash::try_from(trie_key.as_slice()))
+            .collect::<Result<Vec<Blake2bHash>, _>>();
+
+        let limit = match missing_trie_keys_request.get_limit() {
+            0 => DEFAULT_MISSING_TRIE_KEYS_LIMIT,
+            limit => cmp::min(limit, MAX_MISSING_TRIE_KEYS_LIMIT),
+        };
+
+        match maybe_trie_keys {
+            Ok(trie_keys) => {
+                match self.missing_trie_keys(correlation_id, &trie_keys, limit as usize) {
+                    Ok(MissingTrieKeys {
+                        missing,
+                        unexplored,
+                    }) => {
+                        let pb_trie_keys: Vec<Vec<u8>> =
+                            missing.iter().map(Blake2bHash::to_vec).collect();
+                        let pb_unexplored_trie_keys: Vec<Vec<u8>> =
+                            unexplored.iter().map(Blake2bHash::to_vec).collect();
+                        let result = response.mut_success();
+                        result.set_trie_keys(pb_trie_keys.into());
+                        result.set_unexplored_trie_keys(pb_unexplored_trie_keys.into());
+                    }
+                    Err(error) => {
+                        let log_message = format!("{:?}", error);
+                        logging::log_error(&log_message);
+                        response.set_failure(log_message);
+                    }
+                }
+            }
+            Err(_) => {
+                let log_message = "Could not parse trie keys".to_string();
+                logging::log_error(&log_message);
+                response.set_failure(log_message);
+            }
+        }
+
+        log_duration(
+            correlation_id,
+            METRIC_DURATION_MISSING_TRIE_KEYS,
+            TAG_RESPONSE_MISSING_TRIE_KEYS,
+            start.elapsed(),
+        );
+
+        SingleResponse::completed(response)
+    }
+
+    fn list_state_roots(
+        &self,
+        _request_options: RequestOptions,
+        list_state_roots_request: ipc::ListStateRootsRequest,
+    ) -> SingleResponse<ListStateRootsResponse> {
+        let start = Instant::now();
+        let correlation_id = CorrelationId::new();
+
+        let mut response = ListStateRootsResponse::new();
+
+        let request = ListStateRootsRequest::from(list_state_roots_request);
+
+        match self.list_state_roots(request) {
+            Ok(ListStateRootsResult {
+                state_roots,
+                next_start,
+            }) => {
+                let pb_state_roots: Vec<StateRoot> = state_roots
+                    .into_iter()
+                    .map(|(state_root, state_root_metadata)| {
+                        let mut pb_state_root = StateRoot::new();
+                        pb_state_root.set_state_hash(state_root.to_vec());
+                        pb_state_root.set_parent_state_hash(state_root_metadata.parent().to_vec());
+                        pb_state_root
+                            .set_protocol_version(state_root_metadata.protocol_version().into());
+                        pb_state_root.set_block_time(state_root_metadata.block_time().into());
+                        pb_state_root.set_effects_count(state_root_metadata.effects_count());
+                        pb_state_root
+                    })
+                    .collect();
+                let result = response.mut_success();
+                result.set_state_roots(pb_state_roots.into());
+                result.set_next_start(
+                    next_start
+                        .map(|state_root| state_root.to_vec())
+                        .unwrap_or_default(),
+                );
+            }
+            Err(error) => {
+                let log_message = format!("{:?}", error);
+                logging::log_error(&log_message);
+                response.set_failure(log_message);
+            }
+        }
+
+        log_duration(
+            correlation_id,
+            METRIC_DURATION_LIST_STATE_ROOTS,
+            TAG_RESPONSE_LIST_STATE_ROOTS,
+            start.elapsed(),
+        );
+
+        SingleResponse::completed(response)
+    }
+
     fn execute(
         &self,
         _request_options: RequestOptions,
@@ -178,7 +603,7 @@ where
         let preprocessor = Preprocessor::new(wasm_costs);
 
         let mut exec_response = ExecuteResponse::new();
-        let mut results: Vec<ExecutionResult> = Vec::new();
+        let mut results: Vec<DeployResult> = Vec::new();
 
         for result in exec_request
             .take_deploys()
@@ -197,7 +622,15 @@ where
                         deploy_item,
                     );
                     match result {
-                        Ok(result) => results.push(result),
+                        Ok((result, maybe_trace)) => {
+                            let mut deploy_result: DeployResult = result.into();
+                            if let Some(trace) = maybe_trace {
+                                if deploy_result.has_execution_result() {
+                                    deploy_result.mut_execution_result().set_trace(trace.into());
+                                }
+                            }
+                            results.push(deploy_result)
+                        }
                         Err(error) => {
                             logging::log_error("deploy results error: RootNotFound");
                             exec_response
@@ -214,15 +647,14 @@ where
                     };
                 }
                 Err(mapping_error) => {
-                    results.push(ExecutionResult::precondition_failure(mapping_error.into()))
+                    results.push(ExecutionResult::precondition_failure(mapping_error.into()).into())
                 }
             }
         }
 
-        let protobuf_results_iter = results.into_iter().map(Into::into);
         exec_response
             .mut_success()
-            .set_deploy_results(FromIterator::from_iter(protobuf_results_iter));
+            .set_deploy_results(results.into());
         log_duration(
             correlation_id,
             METRIC_DURATION_EXEC,
@@ -232,6 +664,449 @@ where
         SingleResponse::completed(exec_response)
     }
 
+    fn execute_and_commit(
+        &self,
+        _request_options: RequestOptions,
+        mut exec_request: ExecuteAndCommitRequest,
+    ) -> SingleResponse<ExecuteAndCommitResponse> {
+        let start = Instant::now();
+        let correlation_id = CorrelationId::new();
+
+        let mut response = ExecuteAndCommitResponse::new();
+
+        let parent_state_hash: Blake2bHash = match exec_request.get_parent_state_hash().try_into() {
+            Ok(hash) => hash,
+            Err(_) => {
+                let error_message = "Could not parse parent state hash".to_string();
+                logging::log_error(&error_message);
+                response.mut_failed_transform().set_message(error_message);
+                return SingleResponse::completed(response);
+            }
+        };
+        let block_time = BlockTime::new(exec_request.get_block_time());
+        let protocol_version = exec_request.take_protocol_version().into();
+        let wasm_costs = match self.wasm_costs(protocol_version) {
+            Ok(Some(wasm_costs)) => wasm_costs,
+            Ok(None) => {
+                let error_message = format!("Invalid protocol version: {}", protocol_version);
+                logging::log_error(&error_message);
+                response.mut_failed_transform().set_message(error_message);
+                return SingleResponse::completed(response);
+            }
+            Err(error) => {
+                let error_message = format!("{:?}", error);
+                logging::log_error(&error_message);
+                response.mut_failed_transform().set_message(error_message);
+                return SingleResponse::completed(response);
+            }
+        };
+        let executor = Executor;
+        let preprocessor = Preprocessor::new(wasm_costs);
+
+        let deploy_items = exec_request
+            .take_deploys()
+            .into_iter()
+            .map(|deploy_item| {
+                DeployItem::try_from(deploy_item).map_err(|error: MappingError| error.into())
+            })
+            .collect();
+
+        match self.run_execute_and_commit(
+            correlation_id,
+            &executor,
+            &preprocessor,
+            protocol_version,
+            parent_state_hash,
+            block_time,
+            deploy_items,
+        ) {
+            Ok(ExecuteAndCommitResult::Success {
+                execution_results,
+                post_state_hash,
+                bonded_validators,
+            }) => {
+                let properties = {
+                    let mut tmp = BTreeMap::new();
+                    tmp.insert(
+                        "post-state-hash".to_string(),
+                        format!("{:?}", post_state_hash),
+                    );
+                    tmp.insert("success".to_string(), true.to_string());
+                    tmp
+                };
+                logging::log_details(
+                    LogLevel::Info,
+                    "deploys executed and committed; new state hash is: {post-state-hash}"
+                        .to_owned(),
+                    properties,
+                );
+
+                let deploy_results = execution_results.into_iter().map(Into::into);
+                let bonds = bonded_validators.into_iter().map(Into::into).collect();
+                let result = response.mut_success();
+                result.set_deploy_results(FromIterator::from_iter(deploy_results));
+                result.set_poststate_hash(post_state_hash.to_vec());
+                result.set_bonded_validators(bonds);
+            }
+            Ok(ExecuteAndCommitResult::RootNotFound) => {
+                logging::log_warning("RootNotFound");
+                response
+                    .mut_missing_parent()
+                    .set_hash(parent_state_hash.to_vec());
+            }
+            Ok(ExecuteAndCommitResult::KeyNotFound(key)) => {
+                logging::log_warning("KeyNotFound");
+                response.set_key_not_found(key.into());
+            }
+            Ok(ExecuteAndCommitResult::TypeMismatch(type_mismatch)) => {
+                logging::log_warning("TypeMismatch");
+                response.set_type_mismatch(type_mismatch.into());
+            }
+            Ok(ExecuteAndCommitResult::Serialization(error)) => {
+                logging::log_warning("Serialization");
+                response
+                    .mut_failed_transform()
+                    .set_message(format!("{:?}", error));
+            }
+            Ok(ExecuteAndCommitResult::EffectsNotApplied {
+                execution_results,
+                error,
+            }) => {
+                let log_message = format!(
+                    "Effects of deploy {} not applied: {:?}",
+                    execution_results.len(),
+                    error
+                );
+                logging::log_error(&log_message);
+                let deploy_results = execution_results.into_iter().map(Into::into);
+                let failure = response.mut_effects_not_applied();
+                failure.set_deploy_results(FromIterator::from_iter(deploy_results));
+                failure.set_message(format!("{:?}", error));
+            }
+            Err(error) => {
+                let log_message = format!("Error {:?} when executing and committing", error);
+                logging::log_error(&log_message);
+                response
+                    .mut_failed_transform()
+                    .set_message(format!("{:?}", error));
+            }
+        }
+
+        log_duration(
+            correlation_id,
+            METRIC_DURATION_EXEC_AND_COMMIT,
+            TAG_RESPONSE_EXEC_AND_COMMIT,
+            start.elapsed(),
+        );
+
+        SingleResponse::completed(response)
+    }
+
+    fn estimate_gas(
+        &self,
+        _request_options: RequestOptions,
+        mut estimate_request: EstimateGasRequest,
+    ) -> SingleResponse<EstimateGasResponse> {
+        let start = Instant::now();
+        let correlation_id = CorrelationId::new();
+
+        let mut response = EstimateGasResponse::new();
+
+        let parent_state_hash: Blake2bHash =
+            match estimate_request.get_parent_state_hash().try_into() {
+                Ok(hash) => hash,
+                Err(_) => {
+                    let error_message = "Could not parse parent state hash".to_string();
+                    logging::log_error(&error_message);
+                    response.set_failure(error_message);
+                    return SingleResponse::completed(response);
+                }
+            };
+        let block_time = BlockTime::new(estimate_request.get_block_time());
+        let protocol_version = estimate_request.take_protocol_version().into();
+        let max_gas_limit =
+            Gas::from_motes(Motes::new(U512::from(MAX_PAYMENT)), CONV_RATE).unwrap_or_default();
+        let gas_limit = if estimate_request.has_gas_limit() {
+            match U512::try_from(estimate_request.take_gas_limit()) {
+                Ok(gas_limit) => cmp::min(Gas::new(gas_limit), max_gas_limit),
+                Err(ParsingError(error_message)) => {
+                    logging::log_error(&error_message);
+                    response.set_failure(error_message);
+                    return SingleResponse::completed(response);
+                }
+            }
+        } else {
+            max_gas_limit
+        };
+        let wasm_costs = match self.wasm_costs(protocol_version) {
+            Ok(Some(wasm_costs)) => wasm_costs,
+            Ok(None) => {
+                let error_message = format!("Invalid protocol version: {}", protocol_version);
+                logging::log_error(&error_message);
+                response.set_failure(error_message);
+                return SingleResponse::completed(response);
+            }
+            Err(error) => {
+                let error_message = format!("{:?}", error);
+                logging::log_error(&error_message);
+                response.set_failure(error_message);
+                return SingleResponse::completed(response);
+            }
+        };
+        let executor = Executor;
+        let preprocessor = Preprocessor::new(wasm_costs);
+
+        let result = match DeployItem::try_from(estimate_request.take_deploy()) {
+            Ok(deploy_item) => self.estimate_gas(
+                correlation_id,
+                &executor,
+                &preprocessor,
+                protocol_version,
+                parent_state_hash,
+                block_time,
+                deploy_item,
+                gas_limit,
+            ),
+            Err(mapping_error) => Ok(ExecutionResult::precondition_failure(mapping_error.into())),
+        };
+
+        match result {
+            Ok(execution_result) => response.set_success(execution_result.into()),
+            Err(error) => {
+                logging::log_warning("RootNotFound");
+                response.mut_missing_parent().set_hash(error.0.to_vec());
+            }
+        }
+
+        log_duration(
+            correlation_id,
+            METRIC_DURATION_ESTIMATE_GAS,
+            TAG_RESPONSE_ESTIMATE_GAS,
+            start.elapsed(),
+        );
+
+        SingleResponse::completed(response)
+    }
+
+    fn step(
+        &self,
+        _request_options: RequestOptions,
+        mut step_request: StepRequest,
+    ) -> SingleResponse<StepResponse> {
+        let start = Instant::now();
+        let correlation_id = CorrelationId::new();
+
+        let mut response = StepResponse::new();
+
+        let parent_state_hash: Blake2bHash = match step_request.get_parent_state_hash().try_into() {
+            Ok(hash) => hash,
+            Err(_) => {
+                let error_message = "Could not parse parent state hash".to_string();
+                logging::log_error(&error_message);
+                response.mut_failed_step().set_message(error_message);
+                return SingleResponse::completed(response);
+            }
+        };
+        let block_time = BlockTime::new(step_request.get_block_time());
+        let protocol_version = step_request.take_protocol_version().into();
+        let reward_weights = match step_request
+            .take_reward_weights()
+            .into_iter()
+            .map(TryInto::try_into)
+            .collect::<Result<BTreeMap<PublicKey, u64>, MappingError>>()
+        {
+            Ok(reward_weights) => reward_weights,
+            Err(error) => {
+                let error_message = format!("{:?}", error);
+                logging::log_error(&error_message);
+                response.mut_failed_step().set_message(error_message);
+                return SingleResponse::completed(response);
+            }
+        };
+
+        match self.run_step(
+            correlation_id,
+            protocol_version,
+            parent_state_hash,
+            block_time,
+            reward_weights,
+        ) {
+            Ok(StepResult::Success {
+                post_state_hash,
+                effect,
+                bonded_validators,
+            }) => {
+                let properties = {
+                    let mut tmp = BTreeMap::new();
+                    tmp.insert(
+                        "post-state-hash".to_string(),
+                        format!("{:?}", post_state_hash),
+                    );
+                    tmp.insert("success".to_string(), true.to_string());
+                    tmp
+                };
+                logging::log_details(
+                    LogLevel::Info,
+                    "step committed; new state hash is: {post-state-hash}".to_owned(),
+                    properties,
+                );
+
+                let bonds = bonded_validators.into_iter().map(Into::into).collect();
+                let result = response.mut_success();
+                result.set_poststate_hash(post_state_hash.to_vec());
+                result.set_effect(effect.into());
+                result.set_bonded_validators(bonds);
+            }
+            Ok(StepResult::RootNotFound) => {
+                logging::log_warning("RootNotFound");
+                response
+                    .mut_missing_parent()
+                    .set_hash(parent_state_hash.to_vec());
+            }
+            Ok(StepResult::KeyNotFound(key)) => {
+                logging::log_warning("KeyNotFound");
+                response.set_key_not_found(key.into());
+            }
+            Ok(StepResult::TypeMismatch(type_mismatch)) => {
+                logging::log_warning("TypeMismatch");
+                response.set_type_mismatch(type_mismatch.into());
+            }
+            Ok(StepResult::Serialization(error)) => {
+                logging::log_warning("Serialization");
+                response
+                    .mut_failed_transform()
+                    .set_message(format!("{:?}", error));
+            }
+            Err(error) => {
+                let log_message = format!("Error {:?} when running step", error);
+                logging::log_error(&log_message);
+                response
+                    .mut_failed_step()
+                    .set_message(format!("{:?}", error));
+            }
+        }
+
+        log_duration(
+            correlation_id,
+            METRIC_DURATION_STEP,
+            TAG_RESPONSE_STEP,
+            start.elapsed(),
+        );
+
+        SingleResponse::completed(response)
+    }
+
+    fn slash(
+        &self,
+        _request_options: RequestOptions,
+        mut slash_request: SlashRequest,
+    ) -> SingleResponse<SlashResponse> {
+        let start = Instant::now();
+        let correlation_id = CorrelationId::new();
+
+        let mut response = SlashResponse::new();
+
+        let parent_state_hash: Blake2bHash = match slash_request.get_parent_state_hash().try_into()
+        {
+            Ok(hash) => hash,
+            Err(_) => {
+                let error_message = "Could not parse parent state hash".to_string();
+                logging::log_error(&error_message);
+                response.mut_failed_slash().set_message(error_message);
+                return SingleResponse::completed(response);
+            }
+        };
+        let block_time = BlockTime::new(slash_request.get_block_time());
+        let protocol_version = slash_request.take_protocol_version().into();
+        let validators = match slash_request
+            .get_validator_public_keys()
+            .iter()
+            .map(|bytes| {
+                bytes
+                    .as_slice()
+                    .try_into()
+                    .map_err(|_| MappingError::invalid_public_key_length(bytes.len()))
+            })
+            .collect::<Result<Vec<PublicKey>, MappingError>>()
+        {
+            Ok(validators) => validators,
+            Err(error) => {
+                let error_message = format!("{:?}", error);
+                logging::log_error(&error_message);
+                response.mut_failed_slash().set_message(error_message);
+                return SingleResponse::completed(response);
+            }
+        };
+        let fraction = slash_request.get_fraction();
+
+        match self.run_slash(
+            correlation_id,
+            protocol_version,
+            parent_state_hash,
+            block_time,
+            validators,
+            fraction,
+        ) {
+            Ok(CommitResult::Success {
+                state_root,
+                bonded_validators,
+            }) => {
+                let properties = {
+                    let mut tmp = BTreeMap::new();
+                    tmp.insert("post-state-hash".to_string(), format!("{:?}", state_root));
+                    tmp.insert("success".to_string(), true.to_string());
+                    tmp
+                };
+                logging::log_details(
+                    LogLevel::Info,
+                    "slash committed; new state hash is: {post-state-hash}".to_owned(),
+                    properties,
+                );
+
+                let bonds = bonded_validators.into_iter().map(Into::into).collect();
+                let result = response.mut_success();
+                result.set_poststate_hash(state_root.to_vec());
+                result.set_bonded_validators(bonds);
+            }
+            Ok(CommitResult::RootNotFound) => {
+                logging::log_warning("RootNotFound");
+                response
+                    .mut_missing_parent()
+                    .set_hash(parent_state_hash.to_vec());
+            }
+            Ok(CommitResult::KeyNotFound(key)) => {
+                logging::log_warning("KeyNotFound");
+                response.set_key_not_found(key.into());
+            }
+            Ok(CommitResult::TypeMismatch(type_mismatch)) => {
+                logging::log_warning("TypeMismatch");
+                response.set_type_mismatch(type_mismatch.into());
+            }
+            Ok(CommitResult::Serialization(error)) => {
+                logging::log_warning("Serialization");
+                response
+                    .mut_failed_transform()
+                    .set_message(format!("{:?}", error));
+            }
+            Err(error) => {
+                let log_message = format!("Error {:?} when running slash", error);
+                logging::log_error(&log_message);
+                response
+                    .mut_failed_slash()
+                    .set_message(format!("{:?}", error));
+            }
+        }
+
+        log_duration(
+            correlation_id,
+            METRIC_DURATION_SLASH,
+            TAG_RESPONSE_SLASH,
+            start.elapsed(),
+        );
+
+        SingleResponse::completed(response)
+    }
+
     fn commit(
         &self,
         _request_options: RequestOptions,
@@ -264,6 +1139,8 @@ where
             Ok(hash) => hash,
         };
 
+        let block_time = BlockTime::new(commit_request.get_block_time());
+
         // Acquire commit transforms
         let transforms = match TransformMap::try_from(commit_request.take_effects().into_vec()) {
             Err(ParsingError(error_message)) => {
@@ -281,7 +1158,13 @@ where
         let commit_response = {
             let mut ret = CommitResponse::new();
 
-            match self.apply_effect(correlation_id, protocol_version, pre_state_hash, transforms) {
+            match self.apply_effect(
+                correlation_id,
+                protocol_version,
+                pre_state_hash,
+                block_time,
+                transforms,
+            ) {
                 Ok(CommitResult::Success {
                     state_root,
                     bonded_validators,
@@ -479,6 +1362,20 @@ where
     }
 }
 
+/// Sets the proofs of `response`, logging an error and leaving them unset if any of them cannot
+/// be converted.
+fn set_query_proofs(response: &mut QueryResponse, proofs: Vec<TrieMerkleProof<Key, StoredValue>>) {
+    let maybe_pb_proofs: Result<Vec<ipc::TrieMerkleProof>, ParsingError> =
+        proofs.into_iter().map(TryInto::try_into).collect();
+    match maybe_pb_proofs {
+        Ok(pb_proofs) => response.set_proofs(pb_proofs.into()),
+        Err(ParsingError(error_msg)) => {
+            let log_message = format!("Failed to convert TrieMerkleProof: {}", error_msg);
+            logging::log_error(&log_message);
+        }
+    }
+}
+
 // Helper method which returns single DeployResult that is set to be a
 // WasmError.
 pub fn new<E: ExecutionEngineService + Sync + Send + 'static>(
@@ -1,6 +1,8 @@
 use std::{
     collections::BTreeMap,
-    fs,
+    convert::TryFrom,
+    fs::{self, File},
+    io::{BufReader, BufWriter},
     path::PathBuf,
     str::FromStr,
     sync::{
@@ -10,7 +12,7 @@ use std::{
     time::Duration,
 };
 
-use clap::{App, Arg, ArgMatches};
+use clap::{App, AppSettings, Arg, ArgMatches, SubCommand};
 use dirs::home_dir;
 use engine_core::engine_state::{EngineConfig, EngineState};
 use lazy_static::lazy_static;
@@ -21,16 +23,27 @@ use engine_shared::{
         self, log_level,
         log_settings::{self, LogLevelFilter, LogSettings},
     },
+    newtypes::{Blake2bHash, CorrelationId},
     os::get_page_size,
     socket,
 };
 use engine_storage::{
-    global_state::lmdb::LmdbGlobalState, transaction_source::lmdb::LmdbEnvironment,
-    trie_store::lmdb::LmdbTrieStore,
+    error,
+    global_state::{lmdb::LmdbGlobalState, snapshot::SnapshotError, PruneResult},
+    protocol_data_store::lmdb::LmdbProtocolDataStore,
+    state_root_store::lmdb::LmdbStateRootStore,
+    transaction_source::lmdb::LmdbEnvironment,
+    trie_store::{lmdb::LmdbTrieStore, operations::TrieVerification},
+};
+#[cfg(feature = "use_rocksdb")]
+use engine_storage::{
+    global_state::rocksdb::RocksDbGlobalState,
+    protocol_data_store::rocksdb::RocksDbProtocolDataStore,
+    state_root_store::rocksdb::RocksDbStateRootStore,
+    transaction_source::rocksdb::RocksDbEnvironment, trie_store::rocksdb::RocksDbTrieStore,
 };
 
 use casperlabs_engine_grpc_server::engine_server;
-use engine_storage::protocol_data_store::lmdb::LmdbProtocolDataStore;
 
 // exe / proc
 const PROC_NAME: &str = "casperlabs-engine-grpc-server";
@@ -52,8 +65,29 @@ const CREATE_DATA_DIR_EXPECT: &str = "Could not create directory";
 const LMDB_ENVIRONMENT_EXPECT: &str = "Could not create LmdbEnvironment";
 const LMDB_TRIE_STORE_EXPECT: &str = "Could not create LmdbTrieStore";
 const LMDB_PROTOCOL_DATA_STORE_EXPECT: &str = "Could not create LmdbProtocolDataStore";
+const LMDB_STATE_ROOT_STORE_EXPECT: &str = "Could not create LmdbStateRootStore";
 const LMDB_GLOBAL_STATE_EXPECT: &str = "Could not create LmdbGlobalState";
 
+// storage
+const ARG_STORAGE: &str = "storage";
+const ARG_STORAGE_VALUE: &str = "BACKEND";
+const ARG_STORAGE_HELP: &str = "Sets the storage backend of the global state.  Each backend keeps \
+                                its own files in the data directory.";
+const STORAGE_LMDB: &str = "lmdb";
+#[cfg(feature = "use_rocksdb")]
+const STORAGE_ROCKSDB: &str = "rocksdb";
+#[cfg(not(feature = "use_rocksdb"))]
+const STORAGE_BACKENDS: &[&str] = &[STORAGE_LMDB];
+#[cfg(feature = "use_rocksdb")]
+const STORAGE_BACKENDS: &[&str] = &[STORAGE_LMDB, STORAGE_ROCKSDB];
+const ARG_STORAGE_EXPECT: &str = "expected valid storage backend";
+#[cfg(feature = "use_rocksdb")]
+const ROCKSDB_DIR: &str = "rocksdb";
+#[cfg(feature = "use_rocksdb")]
+const ROCKSDB_ENVIRONMENT_EXPECT: &str = "Could not create RocksDbEnvironment";
+#[cfg(feature = "use_rocksdb")]
+const ROCKSDB_GLOBAL_STATE_EXPECT: &str = "Could not create RocksDbGlobalState";
+
 // pages / lmdb
 const ARG_PAGES: &str = "pages";
 const ARG_PAGES_SHORT: &str = "p";
@@ -65,6 +99,29 @@ const GET_PAGES_EXPECT: &str = "Could not parse pages argument";
 // 805306368000 / 4096 = 196608000
 const DEFAULT_PAGES: usize = 196_608_000;
 
+// trie cache
+const ARG_TRIE_CACHE_SIZE: &str = "trie-cache-size";
+const ARG_TRIE_CACHE_SIZE_VALUE: &str = "BYTES";
+const ARG_TRIE_CACHE_SIZE_HELP: &str =
+    "Sets the max size in bytes of the cache of decoded global state trie nodes.  0 disables it.";
+const GET_TRIE_CACHE_SIZE_EXPECT: &str = "Could not parse trie-cache-size argument";
+// 128 MiB
+const DEFAULT_TRIE_CACHE_SIZE: usize = 134_217_728;
+// the prune and snapshot subcommands read each trie node at most once
+const NO_TRIE_CACHE: usize = 0;
+
+// verify state
+const ARG_VERIFY_STATE: &str = "verify-state";
+const ARG_VERIFY_STATE_HELP: &str =
+    "Verifies the integrity of the global state before starting, and exits if it is corrupt";
+const ARG_REPAIR_STATE: &str = "repair-state";
+const ARG_REPAIR_STATE_HELP: &str =
+    "Deletes the damaged global state elements found by verify-state, so they can be fetched \
+     again, rather than exiting";
+const VERIFY_STATE_EXPECT: &str = "failed to verify global state";
+const REPAIR_STATE_EXPECT: &str = "failed to repair global state";
+const VERIFY_STATE_START_MESSAGE: &str = "verifying global state";
+
 // socket
 const ARG_SOCKET: &str = "socket";
 const ARG_SOCKET_HELP: &str =
@@ -86,6 +143,44 @@ const ARG_THREAD_COUNT_VALUE: &str = "NUM";
 const ARG_THREAD_COUNT_HELP: &str = "Worker thread count";
 const ARG_THREAD_COUNT_EXPECT: &str = "expected valid thread count";
 
+// prune
+const SUBCMD_PRUNE: &str = "prune";
+const SUBCMD_PRUNE_ABOUT: &str =
+    "Deletes all global state which is not reachable from the given state roots, then exits.  \
+     The server must not be running against the same data directory.";
+const ARG_PRUNE_ROOTS: &str = "roots";
+const ARG_PRUNE_ROOTS_VALUE: &str = "STATE_ROOT";
+const ARG_PRUNE_ROOTS_HELP: &str = "Hex-encoded state root hash to retain";
+const ARG_PRUNE_ROOTS_EXPECT: &str = "expected at least one state root";
+const PRUNE_ROOT_EXPECT: &str = "expected valid hex-encoded state root hash";
+const PRUNE_EXPECT: &str = "failed to prune global state";
+const PRUNE_START_MESSAGE: &str = "pruning global state";
+
+// snapshots
+const SUBCMD_EXPORT_SNAPSHOT: &str = "export-snapshot";
+const SUBCMD_EXPORT_SNAPSHOT_ABOUT: &str =
+    "Writes a snapshot of the global state at the given state root, along with all protocol \
+     data, to a file, then exits.";
+const SUBCMD_IMPORT_SNAPSHOT: &str = "import-snapshot";
+const SUBCMD_IMPORT_SNAPSHOT_ABOUT: &str =
+    "Reads a snapshot written by export-snapshot into the global state, then exits.  The server \
+     must not be running against the same data directory.";
+const ARG_SNAPSHOT_ROOT: &str = "root";
+const ARG_SNAPSHOT_ROOT_VALUE: &str = "STATE_ROOT";
+const ARG_SNAPSHOT_ROOT_HELP: &str = "Hex-encoded state root hash to export";
+const ARG_SNAPSHOT_ROOT_EXPECT: &str = "expected valid hex-encoded state root hash";
+const ARG_SNAPSHOT_FILE: &str = "file";
+const ARG_SNAPSHOT_FILE_VALUE: &str = "FILE";
+const ARG_SNAPSHOT_FILE_HELP: &str = "Path to the snapshot file";
+const ARG_SNAPSHOT_FILE_EXPECT: &str = "expected snapshot file";
+const ARG_OVERWRITE_PROTOCOL_DATA: &str = "overwrite-protocol-data";
+const ARG_OVERWRITE_PROTOCOL_DATA_HELP: &str =
+    "Replaces stored protocol data which differs from the snapshot's instead of failing";
+const SNAPSHOT_FILE_CREATE_EXPECT: &str = "Could not create snapshot file";
+const SNAPSHOT_FILE_OPEN_EXPECT: &str = "Could not open snapshot file";
+const EXPORT_SNAPSHOT_START_MESSAGE: &str = "exporting global state snapshot";
+const IMPORT_SNAPSHOT_START_MESSAGE: &str = "importing global state snapshot";
+
 // runnable
 const SIGINT_HANDLE_EXPECT: &str = "Error setting Ctrl-C handler";
 const RUNNABLE_CHECK_INTERVAL_SECONDS: u64 = 3;
@@ -109,6 +204,25 @@ fn main() {
 
     let matches: &clap::ArgMatches = &*ARG_MATCHES;
 
+    if let Some(prune_matches) = matches.subcommand_matches(SUBCMD_PRUNE) {
+        prune(matches, prune_matches);
+        return;
+    }
+
+    if let Some(export_matches) = matches.subcommand_matches(SUBCMD_EXPORT_SNAPSHOT) {
+        export_snapshot(matches, export_matches);
+        return;
+    }
+
+    if let Some(import_matches) = matches.subcommand_matches(SUBCMD_IMPORT_SNAPSHOT) {
+        import_snapshot(matches, import_matches);
+        return;
+    }
+
+    if matches.is_present(ARG_VERIFY_STATE) {
+        verify_state(matches);
+    }
+
     let socket = get_socket(matches);
 
     match socket.remove_file() {
@@ -118,13 +232,25 @@ fn main() {
 
     let data_dir = get_data_dir(matches);
 
+    let storage = get_storage(matches);
+
     let map_size = get_map_size(matches);
 
+    let trie_cache_size = get_trie_cache_size(matches);
+
     let thread_count = get_thread_count(matches);
 
     let engine_config: EngineConfig = get_engine_config(matches);
 
-    let _server = get_grpc_server(&socket, data_dir, map_size, thread_count, engine_config);
+    let _server = get_grpc_server(
+        &socket,
+        data_dir,
+        storage,
+        map_size,
+        trie_cache_size,
+        thread_count,
+        engine_config,
+    );
 
     log_listening_message(&socket);
 
@@ -187,6 +313,22 @@ fn get_args() -> ArgMatches<'static> {
                 .help(ARG_PAGES_HELP)
                 .takes_value(true),
         )
+        .arg(
+            Arg::with_name(ARG_STORAGE)
+                .long(ARG_STORAGE)
+                .value_name(ARG_STORAGE_VALUE)
+                .help(ARG_STORAGE_HELP)
+                .takes_value(true)
+                .possible_values(STORAGE_BACKENDS)
+                .default_value(STORAGE_LMDB),
+        )
+        .arg(
+            Arg::with_name(ARG_TRIE_CACHE_SIZE)
+                .long(ARG_TRIE_CACHE_SIZE)
+                .value_name(ARG_TRIE_CACHE_SIZE_VALUE)
+                .help(ARG_TRIE_CACHE_SIZE_HELP)
+                .takes_value(true),
+        )
         .arg(
             Arg::with_name(ARG_THREAD_COUNT)
                 .short(ARG_THREAD_COUNT_SHORT)
@@ -196,12 +338,70 @@ fn get_args() -> ArgMatches<'static> {
                 .value_name(ARG_THREAD_COUNT_VALUE)
                 .help(ARG_THREAD_COUNT_HELP),
         )
+        .arg(
+            Arg::with_name(ARG_VERIFY_STATE)
+                .long(ARG_VERIFY_STATE)
+                .help(ARG_VERIFY_STATE_HELP),
+        )
+        .arg(
+            Arg::with_name(ARG_REPAIR_STATE)
+                .long(ARG_REPAIR_STATE)
+                .requires(ARG_VERIFY_STATE)
+                .help(ARG_REPAIR_STATE_HELP),
+        )
         .arg(
             Arg::with_name(ARG_SOCKET)
                 .required(true)
                 .help(ARG_SOCKET_HELP)
                 .index(1),
         )
+        .setting(AppSettings::SubcommandsNegateReqs)
+        .subcommand(
+            SubCommand::with_name(SUBCMD_PRUNE)
+                .about(SUBCMD_PRUNE_ABOUT)
+                .arg(
+                    Arg::with_name(ARG_PRUNE_ROOTS)
+                        .required(true)
+                        .multiple(true)
+                        .value_name(ARG_PRUNE_ROOTS_VALUE)
+                        .help(ARG_PRUNE_ROOTS_HELP)
+                        .index(1),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name(SUBCMD_EXPORT_SNAPSHOT)
+                .about(SUBCMD_EXPORT_SNAPSHOT_ABOUT)
+                .arg(
+                    Arg::with_name(ARG_SNAPSHOT_ROOT)
+                        .required(true)
+                        .value_name(ARG_SNAPSHOT_ROOT_VALUE)
+                        .help(ARG_SNAPSHOT_ROOT_HELP)
+                        .index(1),
+                )
+                .arg(
+                    Arg::with_name(ARG_SNAPSHOT_FILE)
+                        .required(true)
+                        .value_name(ARG_SNAPSHOT_FILE_VALUE)
+                        .help(ARG_SNAPSHOT_FILE_HELP)
+                        .index(2),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name(SUBCMD_IMPORT_SNAPSHOT)
+                .about(SUBCMD_IMPORT_SNAPSHOT_ABOUT)
+                .arg(
+                    Arg::with_name(ARG_SNAPSHOT_FILE)
+                        .required(true)
+                        .value_name(ARG_SNAPSHOT_FILE_VALUE)
+                        .help(ARG_SNAPSHOT_FILE_HELP)
+                        .index(1),
+                )
+                .arg(
+                    Arg::with_name(ARG_OVERWRITE_PROTOCOL_DATA)
+                        .long(ARG_OVERWRITE_PROTOCOL_DATA)
+                        .help(ARG_OVERWRITE_PROTOCOL_DATA_HELP),
+                ),
+        )
         .get_matches()
 }
 
@@ -238,6 +438,11 @@ fn get_data_dir(matches: &ArgMatches) -> PathBuf {
     buf
 }
 
+/// Parses storage argument and returns the name of the storage backend
+fn get_storage(matches: &ArgMatches) -> &str {
+    matches.value_of(ARG_STORAGE).expect(ARG_STORAGE_EXPECT)
+}
+
 ///  Parses pages argument and returns map size
 fn get_map_size(matches: &ArgMatches) -> usize {
     let page_size = get_page_size().unwrap();
@@ -248,6 +453,14 @@ fn get_map_size(matches: &ArgMatches) -> usize {
     page_size * pages
 }
 
+/// Parses trie-cache-size argument and returns the max size of the trie cache in bytes
+fn get_trie_cache_size(matches: &ArgMatches) -> usize {
+    matches
+        .value_of(ARG_TRIE_CACHE_SIZE)
+        .map_or(Ok(DEFAULT_TRIE_CACHE_SIZE), usize::from_str)
+        .expect(GET_TRIE_CACHE_SIZE_EXPECT)
+}
+
 fn get_thread_count(matches: &ArgMatches) -> usize {
     matches
         .value_of(ARG_THREAD_COUNT)
@@ -266,23 +479,41 @@ fn get_engine_config(_matches: &ArgMatches) -> EngineConfig {
 fn get_grpc_server(
     socket: &socket::Socket,
     data_dir: PathBuf,
+    storage: &str,
     map_size: usize,
+    trie_cache_size: usize,
     thread_count: usize,
     engine_config: EngineConfig,
 ) -> grpc::Server {
-    let engine_state = get_engine_state(data_dir, map_size, engine_config);
+    let server_builder = match storage {
+        #[cfg(feature = "use_rocksdb")]
+        STORAGE_ROCKSDB => {
+            let engine_state = get_rocksdb_engine_state(data_dir, engine_config);
+            engine_server::new(socket.as_str(), thread_count, engine_state)
+        }
+        _ => {
+            let engine_state = get_engine_state(data_dir, map_size, trie_cache_size, engine_config);
+            engine_server::new(socket.as_str(), thread_count, engine_state)
+        }
+    };
 
-    engine_server::new(socket.as_str(), thread_count, engine_state)
-        .build()
-        .expect(SERVER_START_EXPECT)
+    server_builder.build().expect(SERVER_START_EXPECT)
 }
 
 /// Builds and returns engine global state
 fn get_engine_state(
     data_dir: PathBuf,
     map_size: usize,
+    trie_cache_size: usize,
     engine_config: EngineConfig,
 ) -> EngineState<LmdbGlobalState> {
+    let global_state = get_global_state(data_dir, map_size, trie_cache_size);
+
+    EngineState::new(global_state, engine_config)
+}
+
+/// Builds and returns LMDB-backed global state
+fn get_global_state(data_dir: PathBuf, map_size: usize, trie_cache_size: usize) -> LmdbGlobalState {
     let environment = {
         let ret = LmdbEnvironment::new(&data_dir, map_size).expect(LMDB_ENVIRONMENT_EXPECT);
         Arc::new(ret)
@@ -290,7 +521,8 @@ fn get_engine_state(
 
     let trie_store = {
         let ret = LmdbTrieStore::new(&environment, None, DatabaseFlags::empty())
-            .expect(LMDB_TRIE_STORE_EXPECT);
+            .expect(LMDB_TRIE_STORE_EXPECT)
+            .with_cache(trie_cache_size);
         Arc::new(ret)
     };
 
@@ -300,12 +532,236 @@ fn get_engine_state(
         Arc::new(ret)
     };
 
-    let global_state = LmdbGlobalState::empty(environment, trie_store, protocol_data_store)
-        .expect(LMDB_GLOBAL_STATE_EXPECT);
+    let state_root_store = {
+        let ret = LmdbStateRootStore::new(&environment, None, DatabaseFlags::empty())
+            .expect(LMDB_STATE_ROOT_STORE_EXPECT);
+        Arc::new(ret)
+    };
+
+    LmdbGlobalState::empty(
+        environment,
+        trie_store,
+        protocol_data_store,
+        state_root_store,
+    )
+    .expect(LMDB_GLOBAL_STATE_EXPECT)
+}
+
+/// Builds and returns engine global state backed by RocksDB
+#[cfg(feature = "use_rocksdb")]
+fn get_rocksdb_engine_state(
+    data_dir: PathBuf,
+    engine_config: EngineConfig,
+) -> EngineState<RocksDbGlobalState> {
+    let global_state = get_rocksdb_global_state(data_dir);
 
     EngineState::new(global_state, engine_config)
 }
 
+/// Builds and returns RocksDB-backed global state, kept in its own directory within `data_dir`
+#[cfg(feature = "use_rocksdb")]
+fn get_rocksdb_global_state(mut data_dir: PathBuf) -> RocksDbGlobalState {
+    data_dir.push(ROCKSDB_DIR);
+    fs::create_dir_all(&data_dir).expect(CREATE_DATA_DIR_EXPECT);
+
+    let environment = {
+        let ret = RocksDbEnvironment::new(&data_dir).expect(ROCKSDB_ENVIRONMENT_EXPECT);
+        Arc::new(ret)
+    };
+
+    let trie_store = Arc::new(RocksDbTrieStore::new(&environment, None));
+
+    let protocol_data_store = Arc::new(RocksDbProtocolDataStore::new(&environment, None));
+
+    let state_root_store = Arc::new(RocksDbStateRootStore::new(&environment, None));
+
+    RocksDbGlobalState::empty(
+        environment,
+        trie_store,
+        protocol_data_store,
+        state_root_store,
+    )
+    .expect(ROCKSDB_GLOBAL_STATE_EXPECT)
+}
+
+/// Parses the values of the roots argument of the prune subcommand
+fn get_prune_roots(prune_matches: &ArgMatches) -> Vec<Blake2bHash> {
+    prune_matches
+        .values_of(ARG_PRUNE_ROOTS)
+        .expect(ARG_PRUNE_ROOTS_EXPECT)
+        .map(|root| {
+            let bytes = base16::decode(root).expect(PRUNE_ROOT_EXPECT);
+            Blake2bHash::try_from(bytes.as_slice()).expect(PRUNE_ROOT_EXPECT)
+        })
+        .collect()
+}
+
+/// Prunes the global state in the data directory, retaining only the given roots
+fn prune(matches: &ArgMatches, prune_matches: &ArgMatches) {
+    let roots = get_prune_roots(prune_matches);
+
+    let data_dir = get_data_dir(matches);
+
+    let correlation_id = CorrelationId::new();
+    let prune_result = match get_storage(matches) {
+        #[cfg(feature = "use_rocksdb")]
+        STORAGE_ROCKSDB => {
+            let global_state = get_rocksdb_global_state(data_dir);
+            logging::log_info(PRUNE_START_MESSAGE);
+            global_state.prune(correlation_id, &roots)
+        }
+        _ => {
+            let map_size = get_map_size(matches);
+            let global_state = get_global_state(data_dir, map_size, NO_TRIE_CACHE);
+            logging::log_info(PRUNE_START_MESSAGE);
+            global_state.prune(correlation_id, &roots)
+        }
+    }
+    .expect(PRUNE_EXPECT);
+    match prune_result {
+        result @ PruneResult::Success { .. } => logging::log_info(&result.to_string()),
+        result @ PruneResult::RootNotFound(_) => {
+            logging::log_error(&result.to_string());
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Verifies the integrity of the global state in the data directory, exiting with an error code if
+/// it is corrupt unless the repair-state argument is given
+fn verify_state(matches: &ArgMatches) {
+    let data_dir = get_data_dir(matches);
+
+    match get_storage(matches) {
+        #[cfg(feature = "use_rocksdb")]
+        STORAGE_ROCKSDB => {
+            let global_state = get_rocksdb_global_state(data_dir);
+            verify_and_repair_state(
+                matches,
+                |correlation_id| global_state.verify_all(correlation_id),
+                |verification| global_state.repair(verification),
+            )
+        }
+        _ => {
+            let map_size = get_map_size(matches);
+            let global_state = get_global_state(data_dir, map_size, NO_TRIE_CACHE);
+            verify_and_repair_state(
+                matches,
+                |correlation_id| global_state.verify_all(correlation_id),
+                |verification| global_state.repair(verification),
+            )
+        }
+    }
+}
+
+/// Verifies the integrity of a global state with `verify_all`, and repairs it with `repair` if it
+/// is corrupt and the repair-state argument is given
+fn verify_and_repair_state<V, R>(matches: &ArgMatches, verify_all: V, repair: R)
+where
+    V: FnOnce(CorrelationId) -> Result<TrieVerification, error::Error>,
+    R: FnOnce(&TrieVerification) -> Result<usize, error::Error>,
+{
+    logging::log_info(VERIFY_STATE_START_MESSAGE);
+
+    let correlation_id = CorrelationId::new();
+    let verification = verify_all(correlation_id).expect(VERIFY_STATE_EXPECT);
+    for corruption in &verification.corruptions {
+        logging::log_error(&corruption.to_string());
+    }
+    logging::log_info(&format!(
+        "verified {} global state elements, found {} corrupt",
+        verification.checked,
+        verification.corruptions.len()
+    ));
+
+    if verification.is_intact() {
+        return;
+    }
+    if !matches.is_present(ARG_REPAIR_STATE) {
+        std::process::exit(1);
+    }
+    let deleted = repair(&verification).expect(REPAIR_STATE_EXPECT);
+    logging::log_info(&format!(
+        "deleted {} damaged global state elements",
+        deleted
+    ));
+}
+
+/// Parses the value of the file argument of the snapshot subcommands
+fn get_snapshot_file(snapshot_matches: &ArgMatches) -> PathBuf {
+    snapshot_matches
+        .value_of(ARG_SNAPSHOT_FILE)
+        .map(PathBuf::from)
+        .expect(ARG_SNAPSHOT_FILE_EXPECT)
+}
+
+/// Logs the result of a snapshot subcommand, exiting with an error code if it failed
+fn log_snapshot_result(result: Result<impl ToString, SnapshotError>) {
+    match result {
+        Ok(summary) => logging::log_info(&summary.to_string()),
+        Err(error) => {
+            logging::log_error(&error.to_string());
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Exports a snapshot of the global state in the data directory at the given root to a file
+fn export_snapshot(matches: &ArgMatches, export_matches: &ArgMatches) {
+    let state_root = export_matches
+        .value_of(ARG_SNAPSHOT_ROOT)
+        .and_then(|root| base16::decode(root).ok())
+        .and_then(|bytes| Blake2bHash::try_from(bytes.as_slice()).ok())
+        .expect(ARG_SNAPSHOT_ROOT_EXPECT);
+
+    let file = File::create(get_snapshot_file(export_matches)).expect(SNAPSHOT_FILE_CREATE_EXPECT);
+
+    let data_dir = get_data_dir(matches);
+
+    let result = match get_storage(matches) {
+        #[cfg(feature = "use_rocksdb")]
+        STORAGE_ROCKSDB => {
+            let global_state = get_rocksdb_global_state(data_dir);
+            logging::log_info(EXPORT_SNAPSHOT_START_MESSAGE);
+            global_state.export_snapshot(state_root, BufWriter::new(file))
+        }
+        _ => {
+            let map_size = get_map_size(matches);
+            let global_state = get_global_state(data_dir, map_size, NO_TRIE_CACHE);
+            logging::log_info(EXPORT_SNAPSHOT_START_MESSAGE);
+            global_state.export_snapshot(state_root, BufWriter::new(file))
+        }
+    };
+
+    log_snapshot_result(result);
+}
+
+/// Imports a snapshot from a file into the global state in the data directory
+fn import_snapshot(matches: &ArgMatches, import_matches: &ArgMatches) {
+    let file = File::open(get_snapshot_file(import_matches)).expect(SNAPSHOT_FILE_OPEN_EXPECT);
+
+    let overwrite_protocol_data = import_matches.is_present(ARG_OVERWRITE_PROTOCOL_DATA);
+
+    let data_dir = get_data_dir(matches);
+
+    let result = match get_storage(matches) {
+        #[cfg(feature = "use_rocksdb")]
+        STORAGE_ROCKSDB => {
+            let global_state = get_rocksdb_global_state(data_dir);
+            logging::log_info(IMPORT_SNAPSHOT_START_MESSAGE);
+            global_state.import_snapshot(BufReader::new(file), overwrite_protocol_data)
+        }
+        _ => {
+            let map_size = get_map_size(matches);
+            let global_state = get_global_state(data_dir, map_size, NO_TRIE_CACHE);
+            logging::log_info(IMPORT_SNAPSHOT_START_MESSAGE);
+            global_state.import_snapshot(BufReader::new(file), overwrite_protocol_data)
+        }
+    };
+
+    log_snapshot_result(result);
+}
+
 /// Builds and returns log_settings
 fn get_log_settings() -> log_settings::LogSettings {
     let matches: &clap::ArgMatches = &*ARG_MATCHES;
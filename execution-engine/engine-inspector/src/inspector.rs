@@ -0,0 +1,459 @@
+use std::{path::PathBuf, sync::Arc};
+
+use failure::Fail;
+
+use contract_ffi::{
+    bytesrepr::{self, FromBytes, ToBytes},
+    key::Key,
+    uref::URef,
+    value::{CLTyped, CLValueError, ProtocolVersion, U512},
+};
+use engine_core::engine_state::{
+    self,
+    query::{QueryRequest, QueryResult},
+    EngineConfig, EngineState,
+};
+use engine_shared::{
+    account::Account,
+    contract::Contract,
+    newtypes::{Blake2bHash, CorrelationId},
+    stored_value::StoredValue,
+};
+use engine_storage::{
+    error,
+    global_state::{lmdb::LmdbGlobalState, StateProvider},
+    protocol_data::ProtocolData,
+    protocol_data_store::lmdb::LmdbProtocolDataStore,
+    state_root_store::lmdb::LmdbStateRootStore,
+    transaction_source::lmdb::LmdbEnvironment,
+    trie_store::{lmdb::LmdbTrieStore, operations::TrieStats},
+};
+
+#[derive(Fail, Debug)]
+pub enum Error {
+    #[fail(display = "Storage error: {}", _0)]
+    StorageError(error::Error),
+    #[fail(display = "Engine error: {}", _0)]
+    EngineError(engine_state::Error),
+    #[fail(display = "State root not found: {}", _0)]
+    RootNotFound(Blake2bHash),
+    #[fail(display = "Value not found: {}", _0)]
+    ValueNotFound(String),
+    #[fail(display = "Type mismatch: expected {}, found {}", expected, found)]
+    TypeMismatch { expected: String, found: String },
+    #[fail(display = "No protocol data found for version {}", _0)]
+    NoProtocolData(ProtocolVersion),
+    #[fail(display = "No metadata recorded for state root: {}", _0)]
+    NoStateRootMetadata(Blake2bHash),
+    #[fail(display = "CLValue error: {:?}", _0)]
+    CLValueError(CLValueError),
+    #[fail(display = "Serialization error: {}", _0)]
+    SerializationError(bytesrepr::Error),
+}
+
+impl From<error::Error> for Error {
+    fn from(error: error::Error) -> Self {
+        Error::StorageError(error)
+    }
+}
+
+impl From<engine_state::Error> for Error {
+    fn from(error: engine_state::Error) -> Self {
+        Error::EngineError(error)
+    }
+}
+
+impl From<CLValueError> for Error {
+    fn from(error: CLValueError) -> Self {
+        Error::CLValueError(error)
+    }
+}
+
+impl From<bytesrepr::Error> for Error {
+    fn from(error: bytesrepr::Error) -> Self {
+        Error::SerializationError(error)
+    }
+}
+
+/// Reads the global state of a data directory without modifying it.
+pub struct Inspector {
+    engine_state: EngineState<LmdbGlobalState>,
+}
+
+impl Inspector {
+    /// Opens the LMDB environment in `global_state_dir` for reading only.
+    ///
+    /// Since LMDB readers do not block its writer, this is safe to run against the data directory
+    /// of a live server.
+    pub fn open(global_state_dir: &PathBuf) -> Result<Self, Error> {
+        let environment = Arc::new(LmdbEnvironment::open_read_only(global_state_dir)?);
+        let trie_store = Arc::new(LmdbTrieStore::open(&environment, None)?);
+        let protocol_data_store = Arc::new(LmdbProtocolDataStore::open(&environment, None)?);
+        let state_root_store = Arc::new(LmdbStateRootStore::open(&environment, None)?);
+        let global_state = LmdbGlobalState::open(
+            environment,
+            trie_store,
+            protocol_data_store,
+            state_root_store,
+        )?;
+        let engine_state = EngineState::new(global_state, EngineConfig::new());
+        Ok(Inspector { engine_state })
+    }
+
+    /// Returns all the protocol data, in ascending order of protocol version.
+    pub fn protocol_data(&self) -> Result<Vec<(ProtocolVersion, ProtocolData)>, Error> {
+        let mut all_protocol_data = self.engine_state.state().get_all_protocol_data()?;
+        all_protocol_data.sort_by_key(|(protocol_version, _)| *protocol_version);
+        Ok(all_protocol_data)
+    }
+
+    /// Returns the value found by following `path` through the named keys of the account or
+    /// contract stored under `key`.
+    pub fn query(
+        &self,
+        state_root: Blake2bHash,
+        key: Key,
+        path: &[String],
+    ) -> Result<StoredValue, Error> {
+        let correlation_id = CorrelationId::new();
+        let query_request = QueryRequest::new(state_root, key, path.to_vec(), false);
+        match self.engine_state.run_query(correlation_id, query_request)? {
+            QueryResult::Success { value, .. } => Ok(value),
+            QueryResult::ValueNotFound { full_path, .. } => Err(Error::ValueNotFound(full_path)),
+            QueryResult::RootNotFound => Err(Error::RootNotFound(state_root)),
+        }
+    }
+
+    pub fn account(&self, state_root: Blake2bHash, public_key: [u8; 32]) -> Result<Account, Error> {
+        match self.query(state_root, Key::Account(public_key), &[])? {
+            StoredValue::Account(account) => Ok(account),
+            other => Err(type_mismatch("Account", &other)),
+        }
+    }
+
+    pub fn contract(
+        &self,
+        state_root: Blake2bHash,
+        key: Key,
+        path: &[String],
+    ) -> Result<Contract, Error> {
+        match self.query(state_root, key, path)? {
+            StoredValue::Contract(contract) => Ok(contract),
+            other => Err(type_mismatch("Contract", &other)),
+        }
+    }
+
+    /// Returns the balance of `purse`, as recorded by the mint of the protocol version that
+    /// `state_root` was committed under.
+    pub fn balance(&self, state_root: Blake2bHash, purse: URef) -> Result<U512, Error> {
+        let state = self.engine_state.state();
+        let protocol_version = state
+            .get_state_root_metadata(state_root)?
+            .ok_or_else(|| Error::NoStateRootMetadata(state_root))?
+            .protocol_version();
+        let mint = state
+            .get_protocol_data(protocol_version)?
+            .ok_or_else(|| Error::NoProtocolData(protocol_version))?
+            .mint();
+        let local_key_bytes = purse.addr().to_bytes()?;
+        let balance_mapping_key = Key::local(mint.addr(), &local_key_bytes);
+        let balance_key: Key = self.query_cl_value(state_root, balance_mapping_key)?;
+        self.query_cl_value(state_root, balance_key)
+    }
+
+    /// Returns the counts and sizes of the trie elements reachable from `state_root`.
+    pub fn trie_stats(&self, state_root: Blake2bHash) -> Result<TrieStats, Error> {
+        let correlation_id = CorrelationId::new();
+        self.engine_state
+            .state()
+            .trie_stats(correlation_id, state_root)?
+            .ok_or_else(|| Error::RootNotFound(state_root))
+    }
+
+    fn query_cl_value<T>(&self, state_root: Blake2bHash, key: Key) -> Result<T, Error>
+    where
+        T: CLTyped + FromBytes,
+    {
+        match self.query(state_root, key, &[])? {
+            StoredValue::CLValue(cl_value) => Ok(cl_value.into_t()?),
+            other => Err(type_mismatch("CLValue", &other)),
+        }
+    }
+}
+
+fn type_mismatch(expected: &str, found: &StoredValue) -> Error {
+    Error::TypeMismatch {
+        expected: expected.to_string(),
+        found: found.type_name(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use lmdb::DatabaseFlags;
+    use tempfile::{tempdir, TempDir};
+
+    use contract_ffi::{
+        block_time::BlockTime,
+        bytesrepr::ToBytes,
+        key::Key,
+        uref::{AccessRights, URef},
+        value::{CLValue, ProtocolVersion, U512},
+    };
+    use engine_shared::{
+        additive_map::AdditiveMap,
+        newtypes::{Blake2bHash, CorrelationId},
+        os,
+        stored_value::StoredValue,
+        transform::Transform,
+    };
+    use engine_storage::{
+        global_state::{lmdb::LmdbGlobalState, CommitResult, StateProvider},
+        protocol_data::ProtocolData,
+        protocol_data_store::lmdb::LmdbProtocolDataStore,
+        state_root_metadata::StateRootMetadata,
+        state_root_store::lmdb::LmdbStateRootStore,
+        transaction_source::lmdb::LmdbEnvironment,
+        trie_store::lmdb::LmdbTrieStore,
+    };
+    use engine_wasm_prep::{host_function_costs::HostFunctionCosts, wasm_costs::WasmCosts};
+
+    use super::{Error, Inspector};
+
+    fn purse() -> URef {
+        URef::new([1; 32], AccessRights::READ_ADD_WRITE)
+    }
+
+    fn old_mint() -> URef {
+        URef::new([2; 32], AccessRights::READ)
+    }
+
+    fn new_mint() -> URef {
+        URef::new([3; 32], AccessRights::READ)
+    }
+
+    fn proof_of_stake() -> URef {
+        URef::new([4; 32], AccessRights::READ)
+    }
+
+    fn old_balance() -> URef {
+        URef::new([5; 32], AccessRights::READ_ADD_WRITE)
+    }
+
+    fn new_balance() -> URef {
+        URef::new([6; 32], AccessRights::READ_ADD_WRITE)
+    }
+
+    /// The state roots of a data directory where the mint was replaced by an upgrade.
+    struct TestData {
+        temp_dir: TempDir,
+        old_root: Blake2bHash,
+        new_root: Blake2bHash,
+        unregistered_root: Blake2bHash,
+    }
+
+    fn protocol_data(mint: URef) -> ProtocolData {
+        ProtocolData::new(
+            WasmCosts::default(),
+            HostFunctionCosts::default(),
+            mint,
+            proof_of_stake(),
+        )
+    }
+
+    /// Records `amount` as the balance of the purse in the mint `mint`, under the key `balance`.
+    fn balance_effects(mint: URef, balance: URef, amount: u64) -> AdditiveMap<Key, Transform> {
+        let local_key_bytes = purse().addr().to_bytes().unwrap();
+        let balance_mapping_key = Key::local(mint.addr(), &local_key_bytes);
+        let mut effects = AdditiveMap::new();
+        effects.insert(
+            balance_mapping_key,
+            Transform::Write(StoredValue::CLValue(
+                CLValue::from_t(Key::URef(balance)).unwrap(),
+            )),
+        );
+        effects.insert(
+            Key::URef(balance),
+            Transform::Write(StoredValue::CLValue(
+                CLValue::from_t(U512::from(amount)).unwrap(),
+            )),
+        );
+        effects
+    }
+
+    fn commit(
+        global_state: &LmdbGlobalState,
+        prestate_hash: Blake2bHash,
+        effects: AdditiveMap<Key, Transform>,
+    ) -> Blake2bHash {
+        let correlation_id = CorrelationId::new();
+        match global_state
+            .commit(correlation_id, prestate_hash, effects)
+            .unwrap()
+        {
+            CommitResult::Success { state_root, .. } => state_root,
+            other => panic!("commit failed: {:?}", other),
+        }
+    }
+
+    fn register(
+        global_state: &LmdbGlobalState,
+        state_root: Blake2bHash,
+        parent: Blake2bHash,
+        protocol_version: ProtocolVersion,
+    ) {
+        let state_root_metadata =
+            StateRootMetadata::new(parent, protocol_version, BlockTime::new(0), 2);
+        global_state
+            .put_state_root_metadata(state_root, &state_root_metadata)
+            .unwrap();
+    }
+
+    /// Writes a data directory in which the balance of the purse is 1 under the mint of version
+    /// 1.0.0, and 2 under the mint of version 2.0.0 which replaced it.
+    fn create_test_data() -> TestData {
+        let temp_dir = tempdir().unwrap();
+        let map_size = os::get_page_size().unwrap() * 2560;
+        let environment =
+            Arc::new(LmdbEnvironment::new(&temp_dir.path().to_path_buf(), map_size).unwrap());
+        let trie_store =
+            Arc::new(LmdbTrieStore::new(&environment, None, DatabaseFlags::empty()).unwrap());
+        let protocol_data_store = Arc::new(
+            LmdbProtocolDataStore::new(&environment, None, DatabaseFlags::empty()).unwrap(),
+        );
+        let state_root_store =
+            Arc::new(LmdbStateRootStore::new(&environment, None, DatabaseFlags::empty()).unwrap());
+        let global_state = LmdbGlobalState::empty(
+            environment,
+            trie_store,
+            protocol_data_store,
+            state_root_store,
+        )
+        .unwrap();
+
+        let old_version = ProtocolVersion::from_parts(1, 0, 0);
+        let new_version = ProtocolVersion::from_parts(2, 0, 0);
+        global_state
+            .put_protocol_data(new_version, &protocol_data(new_mint()))
+            .unwrap();
+        global_state
+            .put_protocol_data(old_version, &protocol_data(old_mint()))
+            .unwrap();
+
+        let empty_root = global_state.empty_root();
+        let old_root = commit(
+            &global_state,
+            empty_root,
+            balance_effects(old_mint(), old_balance(), 1),
+        );
+        register(&global_state, old_root, empty_root, old_version);
+
+        let new_root = commit(
+            &global_state,
+            old_root,
+            balance_effects(new_mint(), new_balance(), 2),
+        );
+        register(&global_state, new_root, old_root, new_version);
+
+        let mut effects = AdditiveMap::new();
+        effects.insert(
+            Key::Hash([7; 32]),
+            Transform::Write(StoredValue::CLValue(CLValue::from_t(()).unwrap())),
+        );
+        let unregistered_root = commit(&global_state, new_root, effects);
+
+        TestData {
+            temp_dir,
+            old_root,
+            new_root,
+            unregistered_root,
+        }
+    }
+
+    fn open(test_data: &TestData) -> Inspector {
+        Inspector::open(&test_data.temp_dir.path().to_path_buf()).unwrap()
+    }
+
+    #[test]
+    fn should_list_protocol_data_in_ascending_version_order() {
+        let test_data = create_test_data();
+        let inspector = open(&test_data);
+
+        let all_protocol_data = inspector.protocol_data().unwrap();
+
+        assert_eq!(
+            all_protocol_data,
+            vec![
+                (
+                    ProtocolVersion::from_parts(1, 0, 0),
+                    protocol_data(old_mint())
+                ),
+                (
+                    ProtocolVersion::from_parts(2, 0, 0),
+                    protocol_data(new_mint())
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn should_read_balance_from_mint_in_force_at_state_root() {
+        let test_data = create_test_data();
+        let inspector = open(&test_data);
+
+        assert_eq!(
+            inspector.balance(test_data.old_root, purse()).unwrap(),
+            U512::from(1)
+        );
+        assert_eq!(
+            inspector.balance(test_data.new_root, purse()).unwrap(),
+            U512::from(2)
+        );
+    }
+
+    #[test]
+    fn should_not_read_balance_at_state_root_without_metadata() {
+        let test_data = create_test_data();
+        let inspector = open(&test_data);
+
+        match inspector.balance(test_data.unregistered_root, purse()) {
+            Err(Error::NoStateRootMetadata(state_root)) => {
+                assert_eq!(state_root, test_data.unregistered_root)
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn should_query_value_under_state_root() {
+        let test_data = create_test_data();
+        let inspector = open(&test_data);
+
+        let value = inspector
+            .query(test_data.new_root, Key::URef(new_balance()), &[])
+            .unwrap();
+        assert_eq!(
+            value,
+            StoredValue::CLValue(CLValue::from_t(U512::from(2)).unwrap())
+        );
+
+        match inspector.query(test_data.old_root, Key::URef(new_balance()), &[]) {
+            Err(Error::ValueNotFound(_)) => (),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn should_not_query_missing_state_root() {
+        let test_data = create_test_data();
+        let inspector = open(&test_data);
+        let missing_root = Blake2bHash::new(&[8; 32]);
+
+        match inspector.query(missing_root, Key::URef(new_balance()), &[]) {
+            Err(Error::RootNotFound(state_root)) => assert_eq!(state_root, missing_root),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+}
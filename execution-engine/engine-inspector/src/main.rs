@@ -0,0 +1,328 @@
+//! Inspects the global state of an execution engine data directory, without modifying it.
+//!
+//! The LMDB environment is opened read-only, so this is safe to run while a server is using the
+//! same data directory.
+
+mod inspector;
+mod parse;
+
+use std::{collections::BTreeMap, path::PathBuf, process};
+
+use clap::{App, AppSettings, Arg, ArgMatches, SubCommand};
+use dirs::home_dir;
+
+use contract_ffi::key::Key;
+use engine_shared::{newtypes::Blake2bHash, stored_value::StoredValue};
+
+use inspector::{Error, Inspector};
+
+// exe / proc
+const APP_NAME: &str = "CasperLabs Execution Engine Inspector";
+
+// data-dir
+const ARG_DATA_DIR: &str = "data-dir";
+const ARG_DATA_DIR_SHORT: &str = "d";
+const ARG_DATA_DIR_VALUE: &str = "DIR";
+const ARG_DATA_DIR_HELP: &str = "Sets the data directory of the execution engine server";
+const DEFAULT_DATA_DIR_RELATIVE: &str = ".casperlabs";
+const GLOBAL_STATE_DIR: &str = "global_state";
+const GET_HOME_DIR_EXPECT: &str = "Could not get home directory";
+
+// state root
+const ARG_STATE_ROOT: &str = "state-root";
+const ARG_STATE_ROOT_HELP: &str = "Hex-encoded state root hash";
+const ARG_STATE_ROOT_EXPECT: &str = "expected state root";
+const PARSE_STATE_ROOT_MESSAGE: &str = "expected valid hex-encoded state root hash";
+
+// key
+const ARG_KEY: &str = "key";
+const ARG_KEY_HELP: &str =
+    "Key formatted as account-<hex>, hash-<hex>, uref-<hex>[-<octal access rights>] or local-<hex>";
+const ARG_KEY_EXPECT: &str = "expected key";
+const PARSE_KEY_MESSAGE: &str = "expected valid formatted key";
+
+// path
+const ARG_PATH: &str = "path";
+const ARG_PATH_SHORT: &str = "p";
+const ARG_PATH_VALUE: &str = "PATH";
+const ARG_PATH_HELP: &str = "Slash-separated path of named keys to follow from the key";
+
+// subcommands
+const SUBCMD_PROTOCOL_VERSIONS: &str = "protocol-versions";
+const SUBCMD_PROTOCOL_VERSIONS_ABOUT: &str =
+    "Lists the protocol versions along with their system contracts";
+const SUBCMD_QUERY: &str = "query";
+const SUBCMD_QUERY_ABOUT: &str = "Prints the value under a key, or under a path from a key";
+const SUBCMD_ACCOUNT: &str = "account";
+const SUBCMD_ACCOUNT_ABOUT: &str =
+    "Prints an account along with its associated keys and action thresholds";
+const SUBCMD_BALANCE: &str = "balance";
+const SUBCMD_BALANCE_ABOUT: &str =
+    "Prints the balance of a purse, or of the main purse of an account";
+const SUBCMD_NAMED_KEYS: &str = "named-keys";
+const SUBCMD_NAMED_KEYS_ABOUT: &str =
+    "Prints the named keys of a contract under a key, or under a path from a key";
+const SUBCMD_TRIE_STATS: &str = "trie-stats";
+const SUBCMD_TRIE_STATS_ABOUT: &str =
+    "Prints the counts, sizes and depth of the trie elements reachable from a state root";
+const BALANCE_KEY_MESSAGE: &str = "expected a uref or an account key";
+
+fn main() {
+    let matches = get_args();
+
+    let data_dir = get_data_dir(&matches);
+
+    let inspector = Inspector::open(&data_dir).unwrap_or_else(|error| exit_with_error(error));
+
+    let result = match matches.subcommand() {
+        (SUBCMD_PROTOCOL_VERSIONS, Some(_)) => print_protocol_versions(&inspector),
+        (SUBCMD_QUERY, Some(query_matches)) => print_query(&inspector, query_matches),
+        (SUBCMD_ACCOUNT, Some(account_matches)) => print_account(&inspector, account_matches),
+        (SUBCMD_BALANCE, Some(balance_matches)) => print_balance(&inspector, balance_matches),
+        (SUBCMD_NAMED_KEYS, Some(named_keys_matches)) => {
+            print_named_keys(&inspector, named_keys_matches)
+        }
+        (SUBCMD_TRIE_STATS, Some(trie_stats_matches)) => {
+            print_trie_stats(&inspector, trie_stats_matches)
+        }
+        _ => unreachable!(),
+    };
+
+    if let Err(error) = result {
+        exit_with_error(error);
+    }
+}
+
+/// Parses command line arguments and returns ArgMatches
+fn get_args() -> ArgMatches<'static> {
+    App::new(APP_NAME)
+        .version(env!("CARGO_PKG_VERSION"))
+        .setting(AppSettings::SubcommandRequiredElseHelp)
+        .arg(
+            Arg::with_name(ARG_DATA_DIR)
+                .short(ARG_DATA_DIR_SHORT)
+                .long(ARG_DATA_DIR)
+                .value_name(ARG_DATA_DIR_VALUE)
+                .help(ARG_DATA_DIR_HELP)
+                .takes_value(true),
+        )
+        .subcommand(
+            SubCommand::with_name(SUBCMD_PROTOCOL_VERSIONS).about(SUBCMD_PROTOCOL_VERSIONS_ABOUT),
+        )
+        .subcommand(
+            SubCommand::with_name(SUBCMD_QUERY)
+                .about(SUBCMD_QUERY_ABOUT)
+                .arg(state_root_arg())
+                .arg(key_arg())
+                .arg(path_arg()),
+        )
+        .subcommand(
+            SubCommand::with_name(SUBCMD_ACCOUNT)
+                .about(SUBCMD_ACCOUNT_ABOUT)
+                .arg(state_root_arg())
+                .arg(key_arg()),
+        )
+        .subcommand(
+            SubCommand::with_name(SUBCMD_BALANCE)
+                .about(SUBCMD_BALANCE_ABOUT)
+                .arg(state_root_arg())
+                .arg(key_arg()),
+        )
+        .subcommand(
+            SubCommand::with_name(SUBCMD_NAMED_KEYS)
+                .about(SUBCMD_NAMED_KEYS_ABOUT)
+                .arg(state_root_arg())
+                .arg(key_arg())
+                .arg(path_arg()),
+        )
+        .subcommand(
+            SubCommand::with_name(SUBCMD_TRIE_STATS)
+                .about(SUBCMD_TRIE_STATS_ABOUT)
+                .arg(state_root_arg()),
+        )
+        .get_matches()
+}
+
+fn state_root_arg() -> Arg<'static, 'static> {
+    Arg::with_name(ARG_STATE_ROOT)
+        .help(ARG_STATE_ROOT_HELP)
+        .required(true)
+        .index(1)
+}
+
+fn key_arg() -> Arg<'static, 'static> {
+    Arg::with_name(ARG_KEY)
+        .help(ARG_KEY_HELP)
+        .required(true)
+        .index(2)
+}
+
+fn path_arg() -> Arg<'static, 'static> {
+    Arg::with_name(ARG_PATH)
+        .short(ARG_PATH_SHORT)
+        .long(ARG_PATH)
+        .value_name(ARG_PATH_VALUE)
+        .help(ARG_PATH_HELP)
+        .takes_value(true)
+}
+
+/// Parses data directory argument and returns the global state directory within it
+fn get_data_dir(matches: &ArgMatches) -> PathBuf {
+    let mut buf = matches.value_of(ARG_DATA_DIR).map_or(
+        {
+            let mut dir = home_dir().expect(GET_HOME_DIR_EXPECT);
+            dir.push(DEFAULT_DATA_DIR_RELATIVE);
+            dir
+        },
+        PathBuf::from,
+    );
+    buf.push(GLOBAL_STATE_DIR);
+    buf
+}
+
+fn get_state_root(matches: &ArgMatches) -> Blake2bHash {
+    let input = matches
+        .value_of(ARG_STATE_ROOT)
+        .expect(ARG_STATE_ROOT_EXPECT);
+    parse::parse_hash(input).unwrap_or_else(|| exit_with_message(PARSE_STATE_ROOT_MESSAGE))
+}
+
+fn get_key(matches: &ArgMatches) -> Key {
+    let input = matches.value_of(ARG_KEY).expect(ARG_KEY_EXPECT);
+    parse::parse_key(input).unwrap_or_else(|| exit_with_message(PARSE_KEY_MESSAGE))
+}
+
+fn get_path(matches: &ArgMatches) -> Vec<String> {
+    matches
+        .value_of(ARG_PATH)
+        .map(parse::parse_path)
+        .unwrap_or_default()
+}
+
+fn exit_with_error(error: Error) -> ! {
+    exit_with_message(&error.to_string())
+}
+
+fn exit_with_message(message: &str) -> ! {
+    eprintln!("error: {}", message);
+    process::exit(1)
+}
+
+fn print_protocol_versions(inspector: &Inspector) -> Result<(), Error> {
+    for (protocol_version, protocol_data) in inspector.protocol_data()? {
+        println!("{}", protocol_version);
+        println!("  mint: {}", Key::from(protocol_data.mint()).as_string());
+        println!(
+            "  proof of stake: {}",
+            Key::from(protocol_data.proof_of_stake()).as_string()
+        );
+    }
+    Ok(())
+}
+
+fn print_query(inspector: &Inspector, matches: &ArgMatches) -> Result<(), Error> {
+    let value = inspector.query(
+        get_state_root(matches),
+        get_key(matches),
+        &get_path(matches),
+    )?;
+    match value {
+        StoredValue::CLValue(cl_value) => {
+            println!("type: {:?}", cl_value.cl_type());
+            println!("bytes: {}", base16::encode_lower(cl_value.inner_bytes()));
+        }
+        StoredValue::Account(account) => {
+            println!("account: {}", base16::encode_lower(&account.pub_key()));
+            println!(
+                "main purse: {}",
+                Key::from(account.purse_id().value()).as_string()
+            );
+            print_named_keys_map(account.named_keys());
+        }
+        StoredValue::Contract(contract) => {
+            println!("protocol version: {}", contract.protocol_version());
+            println!("wasm size: {}", contract.bytes().len());
+            print_named_keys_map(contract.named_keys());
+        }
+    }
+    Ok(())
+}
+
+fn print_account(inspector: &Inspector, matches: &ArgMatches) -> Result<(), Error> {
+    let public_key = get_key(matches)
+        .as_account()
+        .unwrap_or_else(|| exit_with_message(PARSE_KEY_MESSAGE));
+    let account = inspector.account(get_state_root(matches), public_key)?;
+    println!("account: {}", base16::encode_lower(&account.pub_key()));
+    println!(
+        "main purse: {}",
+        Key::from(account.purse_id().value()).as_string()
+    );
+    println!("associated keys:");
+    for (associated_key, weight) in account.get_associated_keys() {
+        println!(
+            "  {}: {}",
+            base16::encode_lower(&associated_key.value()),
+            weight.value()
+        );
+    }
+    println!("action thresholds:");
+    let action_thresholds = account.action_thresholds();
+    println!("  deployment: {}", action_thresholds.deployment().value());
+    println!(
+        "  key management: {}",
+        action_thresholds.key_management().value()
+    );
+    print_named_keys_map(account.named_keys());
+    Ok(())
+}
+
+fn print_balance(inspector: &Inspector, matches: &ArgMatches) -> Result<(), Error> {
+    let state_root = get_state_root(matches);
+    let purse = match get_key(matches) {
+        Key::URef(uref) => uref,
+        Key::Account(public_key) => inspector
+            .account(state_root, public_key)?
+            .purse_id()
+            .value(),
+        _ => exit_with_message(BALANCE_KEY_MESSAGE),
+    };
+    let balance = inspector.balance(state_root, purse)?;
+    println!("{}", balance);
+    Ok(())
+}
+
+fn print_named_keys(inspector: &Inspector, matches: &ArgMatches) -> Result<(), Error> {
+    let contract = inspector.contract(
+        get_state_root(matches),
+        get_key(matches),
+        &get_path(matches),
+    )?;
+    print_named_keys_map(contract.named_keys());
+    Ok(())
+}
+
+fn print_named_keys_map(named_keys: &BTreeMap<String, Key>) {
+    println!("named keys:");
+    for (name, key) in named_keys {
+        println!("  {}: {}", name, key.as_string());
+    }
+}
+
+fn print_trie_stats(inspector: &Inspector, matches: &ArgMatches) -> Result<(), Error> {
+    let stats = inspector.trie_stats(get_state_root(matches))?;
+    println!("{:<12}{:>12}{:>16}", "element", "count", "bytes");
+    for (name, count, bytes) in &[
+        ("leaf", stats.leaf_count, stats.leaf_bytes),
+        ("node", stats.node_count, stats.node_bytes),
+        ("extension", stats.extension_count, stats.extension_bytes),
+        ("total", stats.element_count(), stats.total_bytes()),
+    ] {
+        println!("{:<12}{:>12}{:>16}", name, count, bytes);
+    }
+    println!("max depth: {}", stats.max_depth);
+    if stats.missing_count > 0 {
+        println!("missing elements: {}", stats.missing_count);
+    }
+    Ok(())
+}
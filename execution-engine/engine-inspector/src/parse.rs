@@ -0,0 +1,114 @@
+use std::convert::TryFrom;
+
+use contract_ffi::{
+    key::Key,
+    uref::{AccessRights, URef},
+};
+use engine_shared::newtypes::Blake2bHash;
+
+const ACCOUNT_PREFIX: &str = "account-";
+const HASH_PREFIX: &str = "hash-";
+const UREF_PREFIX: &str = "uref-";
+const LOCAL_PREFIX: &str = "local-";
+const ADDR_LENGTH: usize = 32;
+
+/// Parses a hex-encoded state root hash.
+pub fn parse_hash(input: &str) -> Option<Blake2bHash> {
+    let bytes = base16::decode(input).ok()?;
+    Blake2bHash::try_from(bytes.as_slice()).ok()
+}
+
+/// Parses a key in the format of [`Key::as_string`], e.g. `account-<hex>`.
+///
+/// The access rights of a URef, given in octal after its address, are optional.
+pub fn parse_key(input: &str) -> Option<Key> {
+    if input.starts_with(ACCOUNT_PREFIX) {
+        parse_addr(&input[ACCOUNT_PREFIX.len()..]).map(Key::Account)
+    } else if input.starts_with(HASH_PREFIX) {
+        parse_addr(&input[HASH_PREFIX.len()..]).map(Key::Hash)
+    } else if input.starts_with(UREF_PREFIX) {
+        parse_uref(&input[UREF_PREFIX.len()..]).map(Key::URef)
+    } else if input.starts_with(LOCAL_PREFIX) {
+        parse_addr(&input[LOCAL_PREFIX.len()..]).map(Key::Local)
+    } else {
+        None
+    }
+}
+
+/// Parses a path of named keys separated by slashes.  An empty input is an empty path.
+pub fn parse_path(input: &str) -> Vec<String> {
+    input
+        .split('/')
+        .filter(|name| !name.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+fn parse_addr(input: &str) -> Option<[u8; ADDR_LENGTH]> {
+    if input.len() != 2 * ADDR_LENGTH {
+        return None;
+    }
+    let mut ret = [0u8; ADDR_LENGTH];
+    base16::decode_slice(input, &mut ret).ok()?;
+    Some(ret)
+}
+
+fn parse_uref(input: &str) -> Option<URef> {
+    let mut parts = input.splitn(2, '-');
+    let addr = parse_addr(parts.next()?)?;
+    match parts.next() {
+        Some(access_rights) => {
+            let bits = u8::from_str_radix(access_rights, 8).ok()?;
+            let access_rights = AccessRights::from_bits(bits)?;
+            Some(URef::new(addr, access_rights))
+        }
+        None => Some(URef::new(addr, AccessRights::READ)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use contract_ffi::{
+        key::Key,
+        uref::{AccessRights, URef},
+    };
+
+    use super::{parse_key, parse_path};
+
+    #[test]
+    fn should_parse_formatted_keys() {
+        let keys = [
+            Key::Account([1; 32]),
+            Key::Hash([2; 32]),
+            Key::URef(URef::new([3; 32], AccessRights::READ_ADD_WRITE)),
+            Key::Local([4; 32]),
+        ];
+        for key in keys.iter() {
+            assert_eq!(parse_key(&key.as_string()), Some(*key));
+        }
+    }
+
+    #[test]
+    fn should_parse_uref_without_access_rights() {
+        let input = format!("uref-{}", base16::encode_lower(&[3; 32]));
+        assert_eq!(
+            parse_key(&input),
+            Some(Key::URef(URef::new([3; 32], AccessRights::READ)))
+        );
+    }
+
+    #[test]
+    fn should_not_parse_malformed_keys() {
+        let addr = base16::encode_lower(&[1; 32]);
+        assert_eq!(parse_key(&addr), None);
+        assert_eq!(parse_key(&format!("account-{}", &addr[2..])), None);
+        assert_eq!(parse_key(&format!("contract-{}", addr)), None);
+        assert_eq!(parse_key(&format!("uref-{}-9", addr)), None);
+    }
+
+    #[test]
+    fn should_parse_path() {
+        assert_eq!(parse_path("a/b"), vec!["a".to_string(), "b".to_string()]);
+        assert!(parse_path("").is_empty());
+    }
+}
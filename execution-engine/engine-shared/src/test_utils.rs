@@ -11,7 +11,10 @@ use contract_ffi::{
     uref::{AccessRights, URef},
     value::account::PurseId,
 };
-use engine_wasm_prep::wasm_costs::WasmCosts;
+use engine_wasm_prep::{
+    host_function_costs::{HostFunctionCost, HostFunctionCosts},
+    wasm_costs::WasmCosts,
+};
 
 use crate::{account::Account, stored_value::StoredValue};
 
@@ -44,6 +47,11 @@ pub fn wasm_costs_mock() -> WasmCosts {
         max_stack_height: 64 * 1024,
         opcodes_mul: 3,
         opcodes_div: 8,
+        verify_ed25519: 10_000,
+        verify_secp256k1: 15_000,
+        hash_blake2b: 2,
+        hash_sha256: 2,
+        hash_keccak256: 3,
     }
 }
 
@@ -59,5 +67,18 @@ pub fn wasm_costs_free() -> WasmCosts {
         max_stack_height: 64 * 1024,
         opcodes_mul: 1,
         opcodes_div: 1,
+        verify_ed25519: 0,
+        verify_secp256k1: 0,
+        hash_blake2b: 0,
+        hash_sha256: 0,
+        hash_keccak256: 0,
     }
 }
+
+pub fn host_function_costs_mock() -> HostFunctionCosts {
+    HostFunctionCosts::uniform(HostFunctionCost::new(10, 1))
+}
+
+pub fn host_function_costs_free() -> HostFunctionCosts {
+    HostFunctionCosts::default()
+}
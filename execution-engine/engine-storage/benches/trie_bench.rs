@@ -4,13 +4,21 @@ extern crate test;
 
 use test::{black_box, Bencher};
 
-use casperlabs_engine_storage::trie::{Pointer, PointerBlock, Trie};
+use casperlabs_engine_storage::{
+    global_state::{in_memory::InMemoryGlobalState, CommitResult, StateProvider},
+    trie::{Pointer, PointerBlock, Trie},
+};
 use contract_ffi::{
     bytesrepr::{FromBytes, ToBytes},
     key::Key,
     value::CLValue,
 };
-use engine_shared::{newtypes::Blake2bHash, stored_value::StoredValue};
+use engine_shared::{
+    additive_map::AdditiveMap,
+    newtypes::{Blake2bHash, CorrelationId},
+    stored_value::StoredValue,
+    transform::Transform,
+};
 
 #[bench]
 fn serialize_trie_leaf(b: &mut Bencher) {
@@ -69,3 +77,72 @@ fn deserialize_trie_node_pointer(b: &mut Bencher) {
 
     b.iter(|| u8::from_bytes(black_box(&node_bytes)))
 }
+
+/// Returns `count` account keys spread evenly over the trie, each paired with a value derived from
+/// `seed`.
+fn account_pairs(count: u32, seed: i32) -> Vec<(Key, StoredValue)> {
+    (0..count)
+        .map(|index| {
+            let account: [u8; 32] = Blake2bHash::new(&index.to_le_bytes()).into();
+            let value = StoredValue::CLValue(CLValue::from_t(seed + index as i32).unwrap());
+            (Key::Account(account), value)
+        })
+        .collect()
+}
+
+fn write_effects(pairs: Vec<(Key, StoredValue)>) -> AdditiveMap<Key, Transform> {
+    pairs
+        .into_iter()
+        .map(|(key, value)| (key, Transform::Write(value)))
+        .collect()
+}
+
+/// Writes `count` new keys one at a time, as `commit` did before writes were batched.
+fn bench_sequential_writes(b: &mut Bencher, count: u32) {
+    let correlation_id = CorrelationId::new();
+    let pairs = account_pairs(count, 0);
+    b.iter(|| InMemoryGlobalState::from_pairs(correlation_id, black_box(&pairs)).unwrap());
+}
+
+/// Commits `count` writes to new keys if `existing` is zero, or updates to `existing` keys
+/// otherwise.
+fn bench_commit(b: &mut Bencher, count: u32, existing: u32) {
+    let correlation_id = CorrelationId::new();
+    let (global_state, root_hash) =
+        InMemoryGlobalState::from_pairs(correlation_id, &account_pairs(existing, 0)).unwrap();
+    let effects = write_effects(account_pairs(count, 1));
+    b.iter(|| {
+        match global_state
+            .commit(correlation_id, root_hash, black_box(effects.clone()))
+            .unwrap()
+        {
+            CommitResult::Success { state_root, .. } => state_root,
+            _ => panic!("commit failed"),
+        }
+    })
+}
+
+#[bench]
+fn sequential_write_100_keys(b: &mut Bencher) {
+    bench_sequential_writes(b, 100)
+}
+
+#[bench]
+fn sequential_write_1000_keys(b: &mut Bencher) {
+    bench_sequential_writes(b, 1000)
+}
+
+#[bench]
+fn commit_100_new_keys(b: &mut Bencher) {
+    bench_commit(b, 100, 0)
+}
+
+#[bench]
+fn commit_1000_new_keys(b: &mut Bencher) {
+    bench_commit(b, 1000, 0)
+}
+
+#[bench]
+fn commit_1000_updated_keys(b: &mut Bencher) {
+    bench_commit(b, 1000, 1000)
+}
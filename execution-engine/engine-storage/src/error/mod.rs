@@ -1,4 +1,55 @@
 pub mod in_memory;
 pub mod lmdb;
+#[cfg(feature = "use_rocksdb")]
+pub mod rocksdb;
 
-pub use self::lmdb::Error;
+use std::sync;
+
+use failure::Fail;
+use ::lmdb as lmdb_external;
+use wasmi;
+
+use contract_ffi::bytesrepr;
+
+/// An error of the global state and its stores, whichever their backend.
+///
+/// Errors of the backends are converted in the backends' own modules.
+#[derive(Debug, Clone, Fail, PartialEq, Eq)]
+pub enum Error {
+    #[fail(display = "{}", _0)]
+    Lmdb(#[fail(cause)] lmdb_external::Error),
+
+    /// An error of the RocksDB-backed stores, which are only built with the `use_rocksdb`
+    /// feature.
+    #[fail(display = "{}", _0)]
+    RocksDb(String),
+
+    #[fail(display = "{}", _0)]
+    BytesRepr(#[fail(cause)] bytesrepr::Error),
+
+    #[fail(display = "Another thread panicked while holding a lock")]
+    PoisonError,
+}
+
+impl wasmi::HostError for Error {}
+
+impl From<bytesrepr::Error> for Error {
+    fn from(error: bytesrepr::Error) -> Self {
+        Error::BytesRepr(error)
+    }
+}
+
+impl<T> From<sync::PoisonError<T>> for Error {
+    fn from(_error: sync::PoisonError<T>) -> Self {
+        Error::PoisonError
+    }
+}
+
+impl From<in_memory::Error> for Error {
+    fn from(error: in_memory::Error) -> Self {
+        match error {
+            in_memory::Error::BytesRepr(error) => Error::BytesRepr(error),
+            in_memory::Error::PoisonError => Error::PoisonError,
+        }
+    }
+}
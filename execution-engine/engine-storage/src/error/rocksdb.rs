@@ -0,0 +1,9 @@
+use rocksdb as rocksdb_external;
+
+use super::Error;
+
+impl From<rocksdb_external::Error> for Error {
+    fn from(error: rocksdb_external::Error) -> Self {
+        Error::RocksDb(error.into_string())
+    }
+}
@@ -1,6 +1,6 @@
 use std::{ops::Deref, sync::Arc};
 
-use contract_ffi::{key::Key, value::ProtocolVersion};
+use contract_ffi::{block_time::BlockTime, bytesrepr::ToBytes, key::Key, value::ProtocolVersion};
 use engine_shared::{
     additive_map::AdditiveMap,
     newtypes::{Blake2bHash, CorrelationId},
@@ -10,18 +10,26 @@ use engine_shared::{
 
 use crate::{
     error::{self, in_memory},
-    global_state::{commit, CommitResult, StateProvider, StateReader},
+    global_state::{
+        commit, commit_and_then, register_state_root, CommitResult, StateIterator,
+        StatePrefixReader, StateProofReader, StateProvider, StateReader,
+    },
     protocol_data::ProtocolData,
     protocol_data_store::in_memory::InMemoryProtocolDataStore,
+    state_root_metadata::StateRootMetadata,
+    state_root_store::in_memory::InMemoryStateRootStore,
     store::Store,
     transaction_source::{
         in_memory::{InMemoryEnvironment, InMemoryReadTransaction},
         Transaction, TransactionSource,
     },
-    trie::{operations::create_hashed_empty_trie, Trie},
+    trie::{merkle_proof::TrieMerkleProof, operations::create_hashed_empty_trie, Trie},
     trie_store::{
         in_memory::InMemoryTrieStore,
-        operations::{self, read, ReadResult, WriteResult},
+        operations::{
+            self, diff, iter_prefix, missing_trie_keys, read, read_with_proof, DiffResult,
+            MissingTrieKeys, ReadResult, WriteResult,
+        },
     },
 };
 
@@ -29,6 +37,7 @@ pub struct InMemoryGlobalState {
     pub environment: Arc<InMemoryEnvironment>,
     pub trie_store: Arc<InMemoryTrieStore>,
     pub protocol_data_store: Arc<InMemoryProtocolDataStore>,
+    pub state_root_store: Arc<InMemoryStateRootStore>,
     pub empty_root_hash: Blake2bHash,
 }
 
@@ -45,6 +54,7 @@ impl InMemoryGlobalState {
         let environment = Arc::new(InMemoryEnvironment::new());
         let trie_store = Arc::new(InMemoryTrieStore::new(&environment, None));
         let protocol_data_store = Arc::new(InMemoryProtocolDataStore::new(&environment, None));
+        let state_root_store = Arc::new(InMemoryStateRootStore::new(&environment, None));
         let root_hash: Blake2bHash = {
             let (root_hash, root) = create_hashed_empty_trie::<Key, StoredValue>()?;
             let mut txn = environment.create_read_write_txn()?;
@@ -56,6 +66,7 @@ impl InMemoryGlobalState {
             environment,
             trie_store,
             protocol_data_store,
+            state_root_store,
             root_hash,
         ))
     }
@@ -66,12 +77,14 @@ impl InMemoryGlobalState {
         environment: Arc<InMemoryEnvironment>,
         trie_store: Arc<InMemoryTrieStore>,
         protocol_data_store: Arc<InMemoryProtocolDataStore>,
+        state_root_store: Arc<InMemoryStateRootStore>,
         empty_root_hash: Blake2bHash,
     ) -> Self {
         InMemoryGlobalState {
             environment,
             trie_store,
             protocol_data_store,
+            state_root_store,
             empty_root_hash,
         }
     }
@@ -139,6 +152,60 @@ impl StateReader<Key, StoredValue> for InMemoryGlobalStateView {
     }
 }
 
+impl StateProofReader<Key, StoredValue> for InMemoryGlobalStateView {
+    fn read_with_proof(
+        &self,
+        correlation_id: CorrelationId,
+        key: &Key,
+    ) -> Result<TrieMerkleProof<Key, StoredValue>, Self::Error> {
+        let txn = self.environment.create_read_txn()?;
+        let ret = match read_with_proof::<
+            Key,
+            StoredValue,
+            InMemoryReadTransaction,
+            InMemoryTrieStore,
+            Self::Error,
+        >(
+            correlation_id,
+            &txn,
+            self.store.deref(),
+            &self.root_hash,
+            key,
+        )? {
+            Some(proof) => proof,
+            None => panic!("InMemoryGlobalState has invalid root"),
+        };
+        txn.commit()?;
+        Ok(ret)
+    }
+}
+
+impl StatePrefixReader<Key, StoredValue> for InMemoryGlobalStateView {
+    fn iter_prefix<'a>(
+        &'a self,
+        correlation_id: CorrelationId,
+        prefix: &[u8],
+        start: &[u8],
+    ) -> Result<StateIterator<'a, Key, StoredValue, Self::Error>, Self::Error> {
+        let txn = self.environment.create_read_txn()?;
+        let iter = iter_prefix::<
+            Key,
+            StoredValue,
+            InMemoryReadTransaction,
+            InMemoryTrieStore,
+            Self::Error,
+        >(
+            correlation_id,
+            txn,
+            self.store.deref(),
+            &self.root_hash,
+            prefix,
+            start,
+        )?;
+        Ok(Box::new(iter))
+    }
+}
+
 impl StateProvider for InMemoryGlobalState {
     type Error = error::Error;
 
@@ -173,6 +240,39 @@ impl StateProvider for InMemoryGlobalState {
         Ok(commit_result)
     }
 
+    fn commit_and_register(
+        &self,
+        correlation_id: CorrelationId,
+        prestate_hash: Blake2bHash,
+        effects: AdditiveMap<Key, Transform>,
+        protocol_version: ProtocolVersion,
+        block_time: BlockTime,
+    ) -> Result<CommitResult, Self::Error> {
+        let state_root_metadata = StateRootMetadata::new(
+            prestate_hash,
+            protocol_version,
+            block_time,
+            effects.len() as u64,
+        );
+        let commit_result =
+            commit_and_then::<InMemoryEnvironment, InMemoryTrieStore, _, Self::Error, _>(
+                &self.environment,
+                &self.trie_store,
+                correlation_id,
+                prestate_hash,
+                effects,
+                |txn, state_root| {
+                    register_state_root(
+                        txn,
+                        self.state_root_store.deref(),
+                        state_root,
+                        &state_root_metadata,
+                    )
+                },
+            )?;
+        Ok(commit_result)
+    }
+
     fn put_protocol_data(
         &self,
         protocol_version: ProtocolVersion,
@@ -194,15 +294,118 @@ impl StateProvider for InMemoryGlobalState {
         Ok(result)
     }
 
+    fn put_state_root_metadata(
+        &self,
+        state_root: Blake2bHash,
+        state_root_metadata: &StateRootMetadata,
+    ) -> Result<(), Self::Error> {
+        let mut txn = self.environment.create_read_write_txn()?;
+        self.state_root_store
+            .put(&mut txn, &state_root, state_root_metadata)?;
+        txn.commit().map_err(Into::into)
+    }
+
+    fn get_state_root_metadata(
+        &self,
+        state_root: Blake2bHash,
+    ) -> Result<Option<StateRootMetadata>, Self::Error> {
+        let txn = self.environment.create_read_txn()?;
+        let result = self.state_root_store.get(&txn, &state_root)?;
+        txn.commit()?;
+        Ok(result)
+    }
+
+    fn list_state_roots(
+        &self,
+        start: &[u8],
+        limit: usize,
+    ) -> Result<Vec<(Blake2bHash, StateRootMetadata)>, Self::Error> {
+        let state_roots = self
+            .state_root_store
+            .get_range(&self.environment, start, limit)?;
+        Ok(state_roots)
+    }
+
     fn empty_root(&self) -> Blake2bHash {
         self.empty_root_hash
     }
+
+    fn diff(
+        &self,
+        correlation_id: CorrelationId,
+        old_state_hash: Blake2bHash,
+        new_state_hash: Blake2bHash,
+        start: &[u8],
+        limit: usize,
+    ) -> Result<DiffResult<Key, StoredValue>, Self::Error> {
+        let txn = self.environment.create_read_txn()?;
+        let diff_result =
+            diff::<Key, StoredValue, InMemoryReadTransaction, InMemoryTrieStore, Self::Error>(
+                correlation_id,
+                &txn,
+                self.trie_store.deref(),
+                &old_state_hash,
+                &new_state_hash,
+                start,
+                limit,
+            )?;
+        txn.commit()?;
+        Ok(diff_result)
+    }
+
+    fn get_trie(
+        &self,
+        trie_key: &Blake2bHash,
+    ) -> Result<Option<Trie<Key, StoredValue>>, Self::Error> {
+        let txn = self.environment.create_read_txn()?;
+        let maybe_trie: Option<Trie<Key, StoredValue>> = self.trie_store.get(&txn, trie_key)?;
+        txn.commit()?;
+        Ok(maybe_trie)
+    }
+
+    fn put_tries(&self, tries: &[Trie<Key, StoredValue>]) -> Result<Vec<Blake2bHash>, Self::Error> {
+        let mut txn = self.environment.create_read_write_txn()?;
+        let mut trie_hashes = Vec::with_capacity(tries.len());
+        for trie in tries {
+            let trie_hash = Blake2bHash::new(&trie.to_bytes()?);
+            self.trie_store.put(&mut txn, &trie_hash, trie)?;
+            trie_hashes.push(trie_hash);
+        }
+        txn.commit()?;
+        Ok(trie_hashes)
+    }
+
+    fn missing_trie_keys(
+        &self,
+        correlation_id: CorrelationId,
+        trie_keys: &[Blake2bHash],
+        limit: usize,
+    ) -> Result<MissingTrieKeys, Self::Error> {
+        let txn = self.environment.create_read_txn()?;
+        let missing = missing_trie_keys::<
+            Key,
+            StoredValue,
+            InMemoryReadTransaction,
+            InMemoryTrieStore,
+            Self::Error,
+        >(
+            correlation_id,
+            &txn,
+            self.trie_store.deref(),
+            trie_keys,
+            limit,
+        )?;
+        txn.commit()?;
+        Ok(missing)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use contract_ffi::value::CLValue;
 
+    use crate::trie_store::operations::Difference;
+
     use super::*;
 
     #[derive(Debug, Clone)]
@@ -263,6 +466,171 @@ mod tests {
         }
     }
 
+    #[test]
+    fn proofs_from_a_checkout_verify_against_its_root() {
+        let correlation_id = CorrelationId::new();
+        let (state, root_hash) = create_test_state();
+        let checkout = state.checkout(root_hash).unwrap().unwrap();
+        for TestPair { key, value } in create_test_pairs().iter().cloned() {
+            let proof = checkout.read_with_proof(correlation_id, &key).unwrap();
+            assert_eq!(Ok(Some(&value)), proof.verify(&root_hash));
+        }
+        let missing_key = Key::Account([3u8; 32]);
+        let proof = checkout
+            .read_with_proof(correlation_id, &missing_key)
+            .unwrap();
+        assert_eq!(Ok(None), proof.verify(&root_hash));
+    }
+
+    #[test]
+    fn iter_prefix_of_a_checkout_returns_matching_pairs_in_order() {
+        let correlation_id = CorrelationId::new();
+        let (state, root_hash) = create_test_state();
+        let checkout = state.checkout(root_hash).unwrap().unwrap();
+        let test_pairs = create_test_pairs();
+
+        let account_prefix = [0u8];
+        let all_accounts = checkout
+            .iter_prefix(correlation_id, &account_prefix, &[])
+            .unwrap()
+            .collect::<Result<Vec<(Key, StoredValue)>, _>>()
+            .unwrap();
+        let expected = test_pairs
+            .iter()
+            .cloned()
+            .map(|TestPair { key, value }| (key, value))
+            .collect::<Vec<(Key, StoredValue)>>();
+        assert_eq!(all_accounts, expected);
+
+        let start = test_pairs[1].key.to_bytes().unwrap();
+        let from_second_account = checkout
+            .iter_prefix(correlation_id, &account_prefix, &start)
+            .unwrap()
+            .collect::<Result<Vec<(Key, StoredValue)>, _>>()
+            .unwrap();
+        assert_eq!(from_second_account, expected[1..].to_vec());
+
+        let hash_prefix = [1u8];
+        let mut no_hashes = checkout
+            .iter_prefix(correlation_id, &hash_prefix, &[])
+            .unwrap();
+        assert!(no_hashes.next().is_none());
+    }
+
+    #[test]
+    fn diff_between_commits_has_modified_and_added_keys() {
+        let correlation_id = CorrelationId::new();
+        let test_pairs = create_test_pairs();
+        let test_pairs_updated = create_test_pairs_updated();
+
+        let (state, root_hash) = create_test_state();
+
+        let effects: AdditiveMap<Key, Transform> = test_pairs_updated
+            .iter()
+            .cloned()
+            .map(|TestPair { key, value }| (key, Transform::Write(value)))
+            .collect();
+
+        let updated_hash = match state.commit(correlation_id, root_hash, effects).unwrap() {
+            CommitResult::Success { state_root, .. } => state_root,
+            _ => panic!("commit failed"),
+        };
+
+        let modified = |old: &TestPair, new: &TestPair| Difference::Modified {
+            key: new.key,
+            old_value: old.value.clone(),
+            new_value: new.value.clone(),
+        };
+
+        let expected = vec![
+            modified(&test_pairs[0], &test_pairs_updated[0]),
+            modified(&test_pairs[1], &test_pairs_updated[1]),
+            Difference::Added {
+                key: test_pairs_updated[2].key,
+                value: test_pairs_updated[2].value.clone(),
+            },
+        ];
+        let diff_result = state
+            .diff(
+                correlation_id,
+                root_hash,
+                updated_hash,
+                &[],
+                usize::max_value(),
+            )
+            .unwrap();
+        assert_eq!(diff_result, DiffResult::Success(expected));
+
+        let expected = vec![
+            modified(&test_pairs_updated[0], &test_pairs[0]),
+            modified(&test_pairs_updated[1], &test_pairs[1]),
+            Difference::Removed {
+                key: test_pairs_updated[2].key,
+                value: test_pairs_updated[2].value.clone(),
+            },
+        ];
+        let diff_result = state
+            .diff(
+                correlation_id,
+                updated_hash,
+                root_hash,
+                &[],
+                usize::max_value(),
+            )
+            .unwrap();
+        assert_eq!(diff_result, DiffResult::Success(expected));
+
+        let diff_result = state
+            .diff(
+                correlation_id,
+                root_hash,
+                root_hash,
+                &[],
+                usize::max_value(),
+            )
+            .unwrap();
+        assert_eq!(diff_result, DiffResult::Success(vec![]));
+    }
+
+    #[test]
+    fn missing_trie_keys_guides_sync_to_empty_state() {
+        let correlation_id = CorrelationId::new();
+        let (source, root_hash) = create_test_state();
+        let target = InMemoryGlobalState::empty().unwrap();
+
+        // Each round only walks the elements stored in the previous round, plus whatever was left
+        // unexplored when the limit was reached.
+        let mut frontier = vec![root_hash];
+        while !frontier.is_empty() {
+            let MissingTrieKeys {
+                missing,
+                unexplored,
+            } = target
+                .missing_trie_keys(correlation_id, &frontier, 2)
+                .unwrap();
+            assert!(missing.len() <= 2);
+            let tries: Vec<Trie<Key, StoredValue>> = missing
+                .iter()
+                .map(|trie_key| source.get_trie(trie_key).unwrap().unwrap())
+                .collect();
+            assert_eq!(target.put_tries(&tries).unwrap(), missing);
+            frontier = missing;
+            frontier.extend(unexplored);
+        }
+
+        assert_eq!(
+            target
+                .missing_trie_keys(correlation_id, &[root_hash], usize::max_value())
+                .unwrap(),
+            MissingTrieKeys::default()
+        );
+
+        let checkout = target.checkout(root_hash).unwrap().unwrap();
+        for TestPair { key, value } in create_test_pairs().iter().cloned() {
+            assert_eq!(Some(value), checkout.read(correlation_id, &key).unwrap());
+        }
+    }
+
     #[test]
     fn checkout_fails_if_unknown_hash_is_given() {
         let (state, _) = create_test_state();
@@ -353,4 +721,34 @@ mod tests {
         let (_, root_hash) = InMemoryGlobalState::from_pairs(correlation_id, &[]).unwrap();
         assert_eq!(expected_bytes, root_hash.to_vec())
     }
+
+    #[test]
+    fn list_state_roots_pages_in_ascending_order_of_state_root() {
+        let (state, _) = create_test_state();
+        let state_roots: Vec<(Blake2bHash, StateRootMetadata)> = [3u8, 1, 2]
+            .iter()
+            .map(|byte| {
+                let state_root_metadata = StateRootMetadata::new(
+                    state.empty_root_hash,
+                    ProtocolVersion::V1_0_0,
+                    BlockTime::new(u64::from(*byte)),
+                    1,
+                );
+                (Blake2bHash::from([*byte; 32]), state_root_metadata)
+            })
+            .collect();
+        for (state_root, state_root_metadata) in &state_roots {
+            state
+                .put_state_root_metadata(*state_root, state_root_metadata)
+                .unwrap();
+        }
+
+        let first_page = state.list_state_roots(&[], 2).unwrap();
+        assert_eq!(first_page, vec![state_roots[1], state_roots[2]]);
+
+        let second_page = state.list_state_roots(&[3; 32], 2).unwrap();
+        assert_eq!(second_page, vec![state_roots[0]]);
+
+        assert!(state.list_state_roots(&[4], 2).unwrap().is_empty());
+    }
 }
@@ -1,10 +1,15 @@
-use std::{ops::Deref, sync::Arc};
+use std::{
+    io::{Read, Write},
+    ops::Deref,
+    sync::Arc,
+};
 
 use lmdb;
 
-use contract_ffi::{key::Key, value::ProtocolVersion};
+use contract_ffi::{block_time::BlockTime, bytesrepr::ToBytes, key::Key, value::ProtocolVersion};
 use engine_shared::{
     additive_map::AdditiveMap,
+    logging::{log_metric, GAUGE},
     newtypes::{Blake2bHash, CorrelationId},
     stored_value::StoredValue,
     transform::Transform,
@@ -12,22 +17,40 @@ use engine_shared::{
 
 use crate::{
     error,
-    global_state::{commit, CommitResult, StateProvider, StateReader},
+    global_state::{
+        commit, commit_and_then, register_state_root,
+        snapshot::{self, SnapshotError, SnapshotSummary},
+        CommitResult, PruneResult, StateIterator, StatePrefixReader, StateProofReader,
+        StateProvider, StateReader, PRUNE_BATCH_SIZE,
+    },
     protocol_data::ProtocolData,
     protocol_data_store::lmdb::LmdbProtocolDataStore,
+    state_root_metadata::StateRootMetadata,
+    state_root_store::lmdb::LmdbStateRootStore,
     store::Store,
     transaction_source::{lmdb::LmdbEnvironment, Transaction, TransactionSource},
-    trie::{operations::create_hashed_empty_trie, Trie},
+    trie::{merkle_proof::TrieMerkleProof, operations::create_hashed_empty_trie, Trie},
     trie_store::{
         lmdb::LmdbTrieStore,
-        operations::{read, ReadResult},
+        operations::{
+            diff, iter_prefix, missing_trie_keys, reachable_hashes, read, read_with_proof,
+            trie_stats, verify, DiffResult, MissingTrieKeys, ReadResult, TrieStats,
+            TrieVerification,
+        },
     },
 };
 
+const TRIE_CACHE_HITS: &str = "trie_cache_hits";
+const TRIE_CACHE_MISSES: &str = "trie_cache_misses";
+const TRIE_CACHE_ENTRIES: &str = "trie_cache_entries";
+const TRIE_CACHE_SIZE: &str = "trie_cache_size";
+const TRIE_CACHE: &str = "trie_cache";
+
 pub struct LmdbGlobalState {
     pub environment: Arc<LmdbEnvironment>,
     pub trie_store: Arc<LmdbTrieStore>,
     pub protocol_data_store: Arc<LmdbProtocolDataStore>,
+    pub state_root_store: Arc<LmdbStateRootStore>,
     pub empty_root_hash: Blake2bHash,
 }
 
@@ -44,6 +67,7 @@ impl LmdbGlobalState {
         environment: Arc<LmdbEnvironment>,
         trie_store: Arc<LmdbTrieStore>,
         protocol_data_store: Arc<LmdbProtocolDataStore>,
+        state_root_store: Arc<LmdbStateRootStore>,
     ) -> Result<Self, error::Error> {
         let root_hash: Blake2bHash = {
             let (root_hash, root) = create_hashed_empty_trie::<Key, StoredValue>()?;
@@ -56,25 +80,299 @@ impl LmdbGlobalState {
             environment,
             trie_store,
             protocol_data_store,
+            state_root_store,
             root_hash,
         ))
     }
 
+    /// Creates a state from an existing environment and trie_store, without writing to them.
+    ///
+    /// Unlike [`LmdbGlobalState::empty`], this can be used with an environment opened with
+    /// [`LmdbEnvironment::open_read_only`].
+    pub fn open(
+        environment: Arc<LmdbEnvironment>,
+        trie_store: Arc<LmdbTrieStore>,
+        protocol_data_store: Arc<LmdbProtocolDataStore>,
+        state_root_store: Arc<LmdbStateRootStore>,
+    ) -> Result<Self, error::Error> {
+        let (empty_root_hash, _) = create_hashed_empty_trie::<Key, StoredValue>()?;
+        Ok(LmdbGlobalState::new(
+            environment,
+            trie_store,
+            protocol_data_store,
+            state_root_store,
+            empty_root_hash,
+        ))
+    }
+
     /// Creates a state from an existing environment, store, and root_hash.
     /// Intended to be used for testing.
     pub(crate) fn new(
         environment: Arc<LmdbEnvironment>,
         trie_store: Arc<LmdbTrieStore>,
         protocol_data_store: Arc<LmdbProtocolDataStore>,
+        state_root_store: Arc<LmdbStateRootStore>,
         empty_root_hash: Blake2bHash,
     ) -> Self {
         LmdbGlobalState {
             environment,
             trie_store,
             protocol_data_store,
+            state_root_store,
             empty_root_hash,
         }
     }
+
+    /// Deletes all trie elements which are not reachable from `roots_to_retain` or the empty root.
+    ///
+    /// The reachable elements are marked under a read-only transaction, then the store is swept in
+    /// batches of [`PRUNE_BATCH_SIZE`] elements, each in its own read-write transaction, so that
+    /// commits can interleave with the sweep.  Marking waits for any write transaction in flight to
+    /// commit, elements written after that are never deleted, and readers holding an older
+    /// transaction keep seeing the deleted elements until they finish.  Any root which is later
+    /// used as a prestate hash must be retained.
+    ///
+    /// Once the sweep is done, the records of the state roots it deleted are removed from the
+    /// state root registry.
+    ///
+    /// If any of `roots_to_retain` is not found, nothing is deleted.
+    pub fn prune(
+        &self,
+        correlation_id: CorrelationId,
+        roots_to_retain: &[Blake2bHash],
+    ) -> Result<PruneResult, error::Error> {
+        self.prune_in_batches(correlation_id, roots_to_retain, PRUNE_BATCH_SIZE)
+    }
+
+    pub(crate) fn prune_in_batches(
+        &self,
+        correlation_id: CorrelationId,
+        roots_to_retain: &[Blake2bHash],
+        batch_size: usize,
+    ) -> Result<PruneResult, error::Error> {
+        // Recording starts under a read-write transaction, which waits for the write in flight (if
+        // any) to finish.  Every write is then either committed before marking or recorded in full,
+        // so no element kept by the sweep can point to one it deletes.
+        {
+            let txn = self.environment.create_read_write_txn()?;
+            self.trie_store.start_recording_writes()?;
+            txn.commit()?;
+        }
+        let result = self.prune_recording_writes(correlation_id, roots_to_retain, batch_size);
+        self.trie_store.stop_recording_writes()?;
+        result
+    }
+
+    fn prune_recording_writes(
+        &self,
+        correlation_id: CorrelationId,
+        roots_to_retain: &[Blake2bHash],
+        batch_size: usize,
+    ) -> Result<PruneResult, error::Error> {
+        let mut roots = roots_to_retain.to_vec();
+        roots.push(self.empty_root_hash);
+
+        let reachable = {
+            let txn = self.environment.create_read_txn()?;
+            for root in &roots {
+                let maybe_root: Option<Trie<Key, StoredValue>> = self.trie_store.get(&txn, root)?;
+                if maybe_root.is_none() {
+                    return Ok(PruneResult::RootNotFound(*root));
+                }
+            }
+            let reachable = reachable_hashes::<Key, StoredValue, _, _, error::Error>(
+                correlation_id,
+                &txn,
+                self.trie_store.deref(),
+                &roots,
+            )?;
+            txn.commit()?;
+            reachable
+        };
+
+        let mut removed = 0;
+        let mut maybe_start: Option<Vec<u8>> = None;
+        loop {
+            let mut txn = self.environment.create_read_write_txn()?;
+            let (batch_removed, maybe_next_start) = self.trie_store.prune_batch(
+                &mut txn,
+                &reachable,
+                maybe_start.as_ref().map(Vec::as_slice),
+                batch_size,
+            )?;
+            txn.commit()?;
+            removed += batch_removed;
+            match maybe_next_start {
+                Some(next_start) => maybe_start = Some(next_start),
+                None => break,
+            }
+        }
+
+        let unregistered = self.unregister_deleted_state_roots()?;
+
+        Ok(PruneResult::Success {
+            retained: reachable.len(),
+            removed,
+            unregistered,
+        })
+    }
+
+    /// Removes the records of the state roots which are no longer stored from the state root
+    /// registry, and returns how many were removed.
+    fn unregister_deleted_state_roots(&self) -> Result<usize, error::Error> {
+        let mut txn = self.environment.create_read_write_txn()?;
+        let state_roots = self.state_root_store.get_all(&txn)?;
+        let mut unregistered = 0;
+        for (state_root, _) in state_roots {
+            let maybe_root: Option<Trie<Key, StoredValue>> =
+                self.trie_store.get(&txn, &state_root)?;
+            if maybe_root.is_none() {
+                self.state_root_store.delete(&mut txn, &state_root)?;
+                unregistered += 1;
+            }
+        }
+        txn.commit()?;
+        Ok(unregistered)
+    }
+
+    /// Returns the counts and sizes of the trie elements reachable from `state_root`, or `None` if
+    /// it is not found.
+    pub fn trie_stats(
+        &self,
+        correlation_id: CorrelationId,
+        state_root: Blake2bHash,
+    ) -> Result<Option<TrieStats>, error::Error> {
+        let txn = self.environment.create_read_txn()?;
+        let maybe_stats = trie_stats::<Key, StoredValue, _, _, error::Error>(
+            correlation_id,
+            &txn,
+            self.trie_store.deref(),
+            &state_root,
+        )?;
+        txn.commit()?;
+        Ok(maybe_stats)
+    }
+
+    /// Verifies the integrity of the trie elements reachable from `roots`.
+    ///
+    /// The trie is read in a single read transaction, so this is safe to run against a live
+    /// global state.
+    pub fn verify(
+        &self,
+        correlation_id: CorrelationId,
+        roots: &[Blake2bHash],
+    ) -> Result<TrieVerification, error::Error> {
+        let txn = self.environment.create_read_txn()?;
+        let verification = verify::<Key, StoredValue, _, _, error::Error>(
+            correlation_id,
+            &txn,
+            self.trie_store.deref(),
+            roots,
+        )?;
+        txn.commit()?;
+        Ok(verification)
+    }
+
+    /// Verifies the integrity of every trie element in the store, walking from each element which
+    /// is not referenced by another.
+    pub fn verify_all(
+        &self,
+        correlation_id: CorrelationId,
+    ) -> Result<TrieVerification, error::Error> {
+        let txn = self.environment.create_read_txn()?;
+        let roots = self
+            .trie_store
+            .unreferenced_hashes::<Key, StoredValue, _>(&txn)?;
+        let verification = verify::<Key, StoredValue, _, _, error::Error>(
+            correlation_id,
+            &txn,
+            self.trie_store.deref(),
+            &roots,
+        )?;
+        txn.commit()?;
+        Ok(verification)
+    }
+
+    /// Deletes the damaged elements found by a verification, returning the number of elements
+    /// deleted.
+    ///
+    /// Once deleted, the elements are reported by [`StateProvider::missing_trie_keys`], so they can
+    /// be fetched again from peers.  Missing and misplaced elements are not repaired.
+    pub fn repair(&self, verification: &TrieVerification) -> Result<usize, error::Error> {
+        let damaged: Vec<Blake2bHash> = verification
+            .corruptions
+            .iter()
+            .filter(|corruption| corruption.is_damaged())
+            .map(|corruption| corruption.hash())
+            .collect();
+        let mut txn = self.environment.create_read_write_txn()?;
+        let deleted = self.trie_store.delete(&mut txn, &damaged)?;
+        txn.commit()?;
+        Ok(deleted)
+    }
+
+    /// Returns all the protocol data in the store, in ascending order of serialized protocol
+    /// version.
+    pub fn get_all_protocol_data(
+        &self,
+    ) -> Result<Vec<(ProtocolVersion, ProtocolData)>, error::Error> {
+        let txn = self.environment.create_read_txn()?;
+        let all_protocol_data = self.protocol_data_store.get_all(&txn)?;
+        txn.commit()?;
+        Ok(all_protocol_data)
+    }
+
+    /// Logs the hit and miss counters of the trie store's cache, along with its current size.
+    fn log_trie_cache_stats(&self, correlation_id: CorrelationId) -> Result<(), error::Error> {
+        let stats = match self.trie_store.cache_stats()? {
+            Some(stats) => stats,
+            None => return Ok(()),
+        };
+        for (metric, value) in &[
+            (TRIE_CACHE_HITS, stats.hits as f64),
+            (TRIE_CACHE_MISSES, stats.misses as f64),
+            (TRIE_CACHE_ENTRIES, stats.entries as f64),
+            (TRIE_CACHE_SIZE, stats.size as f64),
+        ] {
+            log_metric(correlation_id, metric, TRIE_CACHE, GAUGE, *value);
+        }
+        Ok(())
+    }
+
+    /// Writes a snapshot of the trie elements reachable from `state_root`, along with all the
+    /// protocol data, to `writer`.
+    ///
+    /// See [`snapshot::export`].
+    pub fn export_snapshot<W: Write>(
+        &self,
+        state_root: Blake2bHash,
+        writer: W,
+    ) -> Result<SnapshotSummary, SnapshotError> {
+        snapshot::export(
+            self.environment.deref(),
+            self.trie_store.deref(),
+            |txn| self.protocol_data_store.get_all(txn),
+            state_root,
+            writer,
+        )
+    }
+
+    /// Reads a snapshot from `reader` into this global state.
+    ///
+    /// See [`snapshot::import`].
+    pub fn import_snapshot<R: Read>(
+        &self,
+        reader: R,
+        overwrite_protocol_data: bool,
+    ) -> Result<SnapshotSummary, SnapshotError> {
+        snapshot::import(
+            self.environment.deref(),
+            self.trie_store.deref(),
+            self.protocol_data_store.deref(),
+            reader,
+            overwrite_protocol_data,
+        )
+    }
 }
 
 impl StateReader<Key, StoredValue> for LmdbGlobalStateView {
@@ -102,6 +400,54 @@ impl StateReader<Key, StoredValue> for LmdbGlobalStateView {
     }
 }
 
+impl StateProofReader<Key, StoredValue> for LmdbGlobalStateView {
+    fn read_with_proof(
+        &self,
+        correlation_id: CorrelationId,
+        key: &Key,
+    ) -> Result<TrieMerkleProof<Key, StoredValue>, Self::Error> {
+        let txn = self.environment.create_read_txn()?;
+        let ret = match read_with_proof::<
+            Key,
+            StoredValue,
+            lmdb::RoTransaction,
+            LmdbTrieStore,
+            Self::Error,
+        >(
+            correlation_id,
+            &txn,
+            self.store.deref(),
+            &self.root_hash,
+            key,
+        )? {
+            Some(proof) => proof,
+            None => panic!("LmdbGlobalState has invalid root"),
+        };
+        txn.commit()?;
+        Ok(ret)
+    }
+}
+
+impl StatePrefixReader<Key, StoredValue> for LmdbGlobalStateView {
+    fn iter_prefix<'a>(
+        &'a self,
+        correlation_id: CorrelationId,
+        prefix: &[u8],
+        start: &[u8],
+    ) -> Result<StateIterator<'a, Key, StoredValue, Self::Error>, Self::Error> {
+        let txn = self.environment.create_read_txn()?;
+        let iter = iter_prefix::<Key, StoredValue, lmdb::RoTransaction, LmdbTrieStore, Self::Error>(
+            correlation_id,
+            txn,
+            self.store.deref(),
+            &self.root_hash,
+            prefix,
+            start,
+        )?;
+        Ok(Box::new(iter))
+    }
+}
+
 impl StateProvider for LmdbGlobalState {
     type Error = error::Error;
 
@@ -132,6 +478,40 @@ impl StateProvider for LmdbGlobalState {
             prestate_hash,
             effects,
         )?;
+        self.log_trie_cache_stats(correlation_id)?;
+        Ok(commit_result)
+    }
+
+    fn commit_and_register(
+        &self,
+        correlation_id: CorrelationId,
+        prestate_hash: Blake2bHash,
+        effects: AdditiveMap<Key, Transform>,
+        protocol_version: ProtocolVersion,
+        block_time: BlockTime,
+    ) -> Result<CommitResult, Self::Error> {
+        let state_root_metadata = StateRootMetadata::new(
+            prestate_hash,
+            protocol_version,
+            block_time,
+            effects.len() as u64,
+        );
+        let commit_result = commit_and_then::<LmdbEnvironment, LmdbTrieStore, _, Self::Error, _>(
+            &self.environment,
+            &self.trie_store,
+            correlation_id,
+            prestate_hash,
+            effects,
+            |txn, state_root| {
+                register_state_root(
+                    txn,
+                    self.state_root_store.deref(),
+                    state_root,
+                    &state_root_metadata,
+                )
+            },
+        )?;
+        self.log_trie_cache_stats(correlation_id)?;
         Ok(commit_result)
     }
 
@@ -156,20 +536,124 @@ impl StateProvider for LmdbGlobalState {
         Ok(result)
     }
 
+    fn put_state_root_metadata(
+        &self,
+        state_root: Blake2bHash,
+        state_root_metadata: &StateRootMetadata,
+    ) -> Result<(), Self::Error> {
+        let mut txn = self.environment.create_read_write_txn()?;
+        self.state_root_store
+            .put(&mut txn, &state_root, state_root_metadata)?;
+        txn.commit().map_err(Into::into)
+    }
+
+    fn get_state_root_metadata(
+        &self,
+        state_root: Blake2bHash,
+    ) -> Result<Option<StateRootMetadata>, Self::Error> {
+        let txn = self.environment.create_read_txn()?;
+        let result = self.state_root_store.get(&txn, &state_root)?;
+        txn.commit()?;
+        Ok(result)
+    }
+
+    fn list_state_roots(
+        &self,
+        start: &[u8],
+        limit: usize,
+    ) -> Result<Vec<(Blake2bHash, StateRootMetadata)>, Self::Error> {
+        let txn = self.environment.create_read_txn()?;
+        let state_roots = self.state_root_store.get_range(&txn, start, limit)?;
+        txn.commit()?;
+        Ok(state_roots)
+    }
+
     fn empty_root(&self) -> Blake2bHash {
         self.empty_root_hash
     }
+
+    fn diff(
+        &self,
+        correlation_id: CorrelationId,
+        old_state_hash: Blake2bHash,
+        new_state_hash: Blake2bHash,
+        start: &[u8],
+        limit: usize,
+    ) -> Result<DiffResult<Key, StoredValue>, Self::Error> {
+        let txn = self.environment.create_read_txn()?;
+        let diff_result = diff::<Key, StoredValue, lmdb::RoTransaction, LmdbTrieStore, Self::Error>(
+            correlation_id,
+            &txn,
+            self.trie_store.deref(),
+            &old_state_hash,
+            &new_state_hash,
+            start,
+            limit,
+        )?;
+        txn.commit()?;
+        Ok(diff_result)
+    }
+
+    fn get_trie(
+        &self,
+        trie_key: &Blake2bHash,
+    ) -> Result<Option<Trie<Key, StoredValue>>, Self::Error> {
+        let txn = self.environment.create_read_txn()?;
+        let maybe_trie: Option<Trie<Key, StoredValue>> = self.trie_store.get(&txn, trie_key)?;
+        txn.commit()?;
+        Ok(maybe_trie)
+    }
+
+    fn put_tries(&self, tries: &[Trie<Key, StoredValue>]) -> Result<Vec<Blake2bHash>, Self::Error> {
+        let mut txn = self.environment.create_read_write_txn()?;
+        let mut trie_hashes = Vec::with_capacity(tries.len());
+        for trie in tries {
+            let trie_hash = Blake2bHash::new(&trie.to_bytes()?);
+            self.trie_store.put(&mut txn, &trie_hash, trie)?;
+            trie_hashes.push(trie_hash);
+        }
+        txn.commit()?;
+        Ok(trie_hashes)
+    }
+
+    fn missing_trie_keys(
+        &self,
+        correlation_id: CorrelationId,
+        trie_keys: &[Blake2bHash],
+        limit: usize,
+    ) -> Result<MissingTrieKeys, Self::Error> {
+        let txn = self.environment.create_read_txn()?;
+        let missing =
+            missing_trie_keys::<Key, StoredValue, lmdb::RoTransaction, LmdbTrieStore, Self::Error>(
+                correlation_id,
+                &txn,
+                self.trie_store.deref(),
+                trie_keys,
+                limit,
+            )?;
+        txn.commit()?;
+        Ok(missing)
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::{thread, time::Duration};
+
     use lmdb::DatabaseFlags;
     use tempfile::tempdir;
 
-    use contract_ffi::value::CLValue;
+    use contract_ffi::{
+        block_time::BlockTime,
+        bytesrepr::ToBytes,
+        uref::{AccessRights, URef},
+        value::CLValue,
+    };
+    use engine_wasm_prep::{host_function_costs::HostFunctionCosts, wasm_costs::WasmCosts};
 
     use crate::{
-        trie_store::operations::{write, WriteResult},
+        transaction_source::Writable,
+        trie_store::operations::{write, Difference, TrieCorruption, WriteResult},
         TEST_MAP_SIZE,
     };
 
@@ -211,18 +695,41 @@ mod tests {
         ]
     }
 
-    fn create_test_state() -> (LmdbGlobalState, Blake2bHash) {
-        let correlation_id = CorrelationId::new();
+    fn create_empty_state() -> LmdbGlobalState {
+        create_empty_state_with_trie_cache(0)
+    }
+
+    fn create_empty_state_with_trie_cache(max_cache_size: usize) -> LmdbGlobalState {
         let _temp_dir = tempdir().unwrap();
         let environment = Arc::new(
             LmdbEnvironment::new(&_temp_dir.path().to_path_buf(), *TEST_MAP_SIZE).unwrap(),
         );
-        let trie_store =
-            Arc::new(LmdbTrieStore::new(&environment, None, DatabaseFlags::empty()).unwrap());
+        let trie_store = Arc::new(
+            LmdbTrieStore::new(&environment, None, DatabaseFlags::empty())
+                .unwrap()
+                .with_cache(max_cache_size),
+        );
         let protocol_data_store = Arc::new(
             LmdbProtocolDataStore::new(&environment, None, DatabaseFlags::empty()).unwrap(),
         );
-        let ret = LmdbGlobalState::empty(environment, trie_store, protocol_data_store).unwrap();
+        let state_root_store =
+            Arc::new(LmdbStateRootStore::new(&environment, None, DatabaseFlags::empty()).unwrap());
+        LmdbGlobalState::empty(
+            environment,
+            trie_store,
+            protocol_data_store,
+            state_root_store,
+        )
+        .unwrap()
+    }
+
+    fn create_test_state() -> (LmdbGlobalState, Blake2bHash) {
+        create_test_state_with_trie_cache(0)
+    }
+
+    fn create_test_state_with_trie_cache(max_cache_size: usize) -> (LmdbGlobalState, Blake2bHash) {
+        let correlation_id = CorrelationId::new();
+        let ret = create_empty_state_with_trie_cache(max_cache_size);
         let mut current_root = ret.empty_root_hash;
         {
             let mut txn = ret.environment.create_read_write_txn().unwrap();
@@ -261,6 +768,116 @@ mod tests {
         }
     }
 
+    #[test]
+    fn iter_prefix_of_a_checkout_returns_matching_pairs_in_order() {
+        let correlation_id = CorrelationId::new();
+        let (state, root_hash) = create_test_state();
+        let checkout = state.checkout(root_hash).unwrap().unwrap();
+        let test_pairs = create_test_pairs();
+
+        let account_prefix = [0u8];
+        let all_accounts = checkout
+            .iter_prefix(correlation_id, &account_prefix, &[])
+            .unwrap()
+            .collect::<Result<Vec<(Key, StoredValue)>, _>>()
+            .unwrap();
+        let expected = test_pairs
+            .iter()
+            .cloned()
+            .map(|TestPair { key, value }| (key, value))
+            .collect::<Vec<(Key, StoredValue)>>();
+        assert_eq!(all_accounts, expected);
+
+        let start = test_pairs[1].key.to_bytes().unwrap();
+        let from_second_account = checkout
+            .iter_prefix(correlation_id, &account_prefix, &start)
+            .unwrap()
+            .collect::<Result<Vec<(Key, StoredValue)>, _>>()
+            .unwrap();
+        assert_eq!(from_second_account, expected[1..].to_vec());
+
+        let hash_prefix = [1u8];
+        let mut no_hashes = checkout
+            .iter_prefix(correlation_id, &hash_prefix, &[])
+            .unwrap();
+        assert!(no_hashes.next().is_none());
+    }
+
+    #[test]
+    fn diff_between_commits_has_modified_and_added_keys() {
+        let correlation_id = CorrelationId::new();
+        let test_pairs = create_test_pairs();
+        let test_pairs_updated = create_test_pairs_updated();
+
+        let (state, root_hash) = create_test_state();
+
+        let effects: AdditiveMap<Key, Transform> = test_pairs_updated
+            .iter()
+            .cloned()
+            .map(|TestPair { key, value }| (key, Transform::Write(value)))
+            .collect();
+
+        let updated_hash = match state.commit(correlation_id, root_hash, effects).unwrap() {
+            CommitResult::Success { state_root, .. } => state_root,
+            _ => panic!("commit failed"),
+        };
+
+        let modified = |old: &TestPair, new: &TestPair| Difference::Modified {
+            key: new.key,
+            old_value: old.value.clone(),
+            new_value: new.value.clone(),
+        };
+
+        let expected = vec![
+            modified(&test_pairs[0], &test_pairs_updated[0]),
+            modified(&test_pairs[1], &test_pairs_updated[1]),
+            Difference::Added {
+                key: test_pairs_updated[2].key,
+                value: test_pairs_updated[2].value.clone(),
+            },
+        ];
+        let diff_result = state
+            .diff(
+                correlation_id,
+                root_hash,
+                updated_hash,
+                &[],
+                usize::max_value(),
+            )
+            .unwrap();
+        assert_eq!(diff_result, DiffResult::Success(expected));
+
+        let expected = vec![
+            modified(&test_pairs_updated[0], &test_pairs[0]),
+            modified(&test_pairs_updated[1], &test_pairs[1]),
+            Difference::Removed {
+                key: test_pairs_updated[2].key,
+                value: test_pairs_updated[2].value.clone(),
+            },
+        ];
+        let diff_result = state
+            .diff(
+                correlation_id,
+                updated_hash,
+                root_hash,
+                &[],
+                usize::max_value(),
+            )
+            .unwrap();
+        assert_eq!(diff_result, DiffResult::Success(expected));
+
+        let diff_result = state
+            .diff(
+                correlation_id,
+                root_hash,
+                root_hash,
+                &[],
+                usize::max_value(),
+            )
+            .unwrap();
+        assert_eq!(diff_result, DiffResult::Success(vec![]));
+    }
+
     #[test]
     fn checkout_fails_if_unknown_hash_is_given() {
         let (state, _) = create_test_state();
@@ -341,4 +958,636 @@ mod tests {
                 .unwrap()
         );
     }
+
+    #[test]
+    fn prune_unregisters_deleted_state_roots() {
+        let correlation_id = CorrelationId::new();
+        let (state, root_hash) = create_test_state();
+        let state_root_metadata = StateRootMetadata::new(
+            state.empty_root(),
+            ProtocolVersion::V1_0_0,
+            BlockTime::new(1),
+            2,
+        );
+        state
+            .put_state_root_metadata(root_hash, &state_root_metadata)
+            .unwrap();
+
+        let effects: AdditiveMap<Key, Transform> = create_test_pairs_updated()
+            .iter()
+            .cloned()
+            .map(|TestPair { key, value }| (key, Transform::Write(value)))
+            .collect();
+        let updated_hash = match state
+            .commit_and_register(
+                correlation_id,
+                root_hash,
+                effects,
+                ProtocolVersion::V1_0_0,
+                BlockTime::new(2),
+            )
+            .unwrap()
+        {
+            CommitResult::Success { state_root, .. } => state_root,
+            _ => panic!("commit failed"),
+        };
+
+        match state.prune(correlation_id, &[updated_hash]).unwrap() {
+            PruneResult::Success { unregistered, .. } => assert_eq!(unregistered, 1),
+            PruneResult::RootNotFound(root) => panic!("root not found: {}", root),
+        }
+        assert!(state.get_state_root_metadata(root_hash).unwrap().is_none());
+        assert!(state
+            .get_state_root_metadata(updated_hash)
+            .unwrap()
+            .is_some());
+
+        match state.prune(correlation_id, &[updated_hash]).unwrap() {
+            PruneResult::Success { unregistered, .. } => assert_eq!(unregistered, 0),
+            PruneResult::RootNotFound(root) => panic!("root not found: {}", root),
+        }
+    }
+
+    #[test]
+    fn prune_removes_unretained_roots_and_keeps_retained_ones() {
+        let correlation_id = CorrelationId::new();
+        let test_pairs_updated = create_test_pairs_updated();
+
+        let (state, root_hash) = create_test_state();
+
+        let effects: AdditiveMap<Key, Transform> = {
+            let mut tmp = AdditiveMap::new();
+            for TestPair { key, value } in &test_pairs_updated {
+                tmp.insert(*key, Transform::Write(value.to_owned()));
+            }
+            tmp
+        };
+
+        let updated_hash = match state.commit(correlation_id, root_hash, effects).unwrap() {
+            CommitResult::Success { state_root, .. } => state_root,
+            _ => panic!("commit failed"),
+        };
+
+        match state.prune(correlation_id, &[updated_hash]).unwrap() {
+            PruneResult::Success { removed, .. } => assert!(removed > 0),
+            PruneResult::RootNotFound(root) => panic!("root not found: {}", root),
+        }
+
+        assert!(state.checkout(root_hash).unwrap().is_none());
+        assert!(state.checkout(state.empty_root()).unwrap().is_some());
+
+        let updated_checkout = state.checkout(updated_hash).unwrap().unwrap();
+        for TestPair { key, value } in test_pairs_updated.iter().cloned() {
+            assert_eq!(
+                Some(value),
+                updated_checkout.read(correlation_id, &key).unwrap()
+            );
+        }
+
+        match state.prune(correlation_id, &[updated_hash]).unwrap() {
+            PruneResult::Success { removed, .. } => assert_eq!(removed, 0),
+            PruneResult::RootNotFound(root) => panic!("root not found: {}", root),
+        }
+    }
+
+    #[test]
+    fn reads_from_a_checkout_are_served_from_trie_cache() {
+        let correlation_id = CorrelationId::new();
+        let (state, root_hash) = create_test_state_with_trie_cache(1024 * 1024);
+        let checkout = state.checkout(root_hash).unwrap().unwrap();
+
+        for TestPair { key, value } in create_test_pairs().iter().cloned() {
+            assert_eq!(Some(value), checkout.read(correlation_id, &key).unwrap());
+        }
+        let first_stats = state.trie_store.cache_stats().unwrap().unwrap();
+        assert!(first_stats.entries > 0);
+        assert!(first_stats.size > 0);
+
+        for TestPair { key, value } in create_test_pairs().iter().cloned() {
+            assert_eq!(Some(value), checkout.read(correlation_id, &key).unwrap());
+        }
+        let second_stats = state.trie_store.cache_stats().unwrap().unwrap();
+        assert_eq!(second_stats.misses, first_stats.misses);
+        assert_eq!(second_stats.entries, first_stats.entries);
+        assert!(second_stats.hits > first_stats.hits);
+    }
+
+    #[test]
+    fn prune_clears_trie_cache() {
+        let correlation_id = CorrelationId::new();
+        let (state, root_hash) = create_test_state_with_trie_cache(1024 * 1024);
+
+        let checkout = state.checkout(root_hash).unwrap().unwrap();
+        for TestPair { key, value } in create_test_pairs().iter().cloned() {
+            assert_eq!(Some(value), checkout.read(correlation_id, &key).unwrap());
+        }
+        assert!(state.trie_store.cache_stats().unwrap().unwrap().entries > 0);
+
+        let empty_root = state.empty_root();
+        match state.prune(correlation_id, &[empty_root]).unwrap() {
+            PruneResult::Success { removed, .. } => assert!(removed > 0),
+            PruneResult::RootNotFound(root) => panic!("root not found: {}", root),
+        }
+
+        assert_eq!(state.trie_store.cache_stats().unwrap().unwrap().entries, 0);
+        assert!(state.checkout(root_hash).unwrap().is_none());
+    }
+
+    #[test]
+    fn prune_does_nothing_if_unknown_root_is_given() {
+        let correlation_id = CorrelationId::new();
+        let (state, root_hash) = create_test_state();
+        let fake_hash: Blake2bHash = [1u8; 32].into();
+
+        let result = state.prune(correlation_id, &[fake_hash]).unwrap();
+        assert_eq!(result, PruneResult::RootNotFound(fake_hash));
+
+        let checkout = state.checkout(root_hash).unwrap().unwrap();
+        for TestPair { key, value } in create_test_pairs().iter().cloned() {
+            assert_eq!(Some(value), checkout.read(correlation_id, &key).unwrap());
+        }
+    }
+
+    #[test]
+    fn prune_in_small_batches_removes_the_same_elements() {
+        let correlation_id = CorrelationId::new();
+        let (state, root_hash) = create_test_state();
+        let (other_state, other_root_hash) = create_test_state();
+        assert_eq!(root_hash, other_root_hash);
+
+        let empty_root = state.empty_root();
+        let removed_at_once = match state.prune(correlation_id, &[empty_root]).unwrap() {
+            PruneResult::Success { removed, .. } => removed,
+            PruneResult::RootNotFound(root) => panic!("root not found: {}", root),
+        };
+        let removed_in_batches = match other_state
+            .prune_in_batches(correlation_id, &[empty_root], 1)
+            .unwrap()
+        {
+            PruneResult::Success { removed, .. } => removed,
+            PruneResult::RootNotFound(root) => panic!("root not found: {}", root),
+        };
+
+        assert!(removed_at_once > 0);
+        assert_eq!(removed_in_batches, removed_at_once);
+        assert!(other_state.checkout(root_hash).unwrap().is_none());
+        assert!(other_state.checkout(empty_root).unwrap().is_some());
+    }
+
+    #[test]
+    fn prune_keeps_elements_written_after_marking() {
+        let correlation_id = CorrelationId::new();
+        let (state, root_hash) = create_test_state();
+        let reachable = {
+            let txn = state.environment.create_read_txn().unwrap();
+            let reachable = reachable_hashes::<Key, StoredValue, _, _, error::Error>(
+                correlation_id,
+                &txn,
+                state.trie_store.deref(),
+                &[state.empty_root()],
+            )
+            .unwrap();
+            txn.commit().unwrap();
+            reachable
+        };
+
+        state.trie_store.start_recording_writes().unwrap();
+        let effects: AdditiveMap<Key, Transform> = {
+            let mut tmp = AdditiveMap::new();
+            for TestPair { key, value } in &create_test_pairs_updated() {
+                tmp.insert(*key, Transform::Write(value.to_owned()));
+            }
+            tmp
+        };
+        let updated_hash = match state.commit(correlation_id, root_hash, effects).unwrap() {
+            CommitResult::Success { state_root, .. } => state_root,
+            _ => panic!("commit failed"),
+        };
+
+        let mut txn = state.environment.create_read_write_txn().unwrap();
+        let (removed, maybe_next_start) = state
+            .trie_store
+            .prune_batch(&mut txn, &reachable, None, PRUNE_BATCH_SIZE)
+            .unwrap();
+        txn.commit().unwrap();
+        state.trie_store.stop_recording_writes().unwrap();
+
+        assert!(removed > 0);
+        assert!(maybe_next_start.is_none());
+        assert!(state.checkout(root_hash).unwrap().is_none());
+        let updated_checkout = state.checkout(updated_hash).unwrap().unwrap();
+        for TestPair { key, value } in create_test_pairs_updated().iter().cloned() {
+            assert_eq!(
+                Some(value),
+                updated_checkout.read(correlation_id, &key).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn prune_waits_for_the_write_in_flight_when_marking() {
+        let correlation_id = CorrelationId::new();
+        let (state, root_hash) = create_test_state();
+        let state = Arc::new(state);
+        let pairs = create_test_pairs_updated();
+
+        let mut txn = state.environment.create_read_write_txn().unwrap();
+        let first_root = match write::<_, _, _, LmdbTrieStore, error::Error>(
+            correlation_id,
+            &mut txn,
+            &state.trie_store,
+            &root_hash,
+            &pairs[0].key,
+            &pairs[0].value,
+        )
+        .unwrap()
+        {
+            WriteResult::Written(first_root) => first_root,
+            _ => panic!("write failed"),
+        };
+
+        let pruning_state = Arc::clone(&state);
+        let pruning = thread::spawn(move || {
+            pruning_state
+                .prune(CorrelationId::new(), &[root_hash])
+                .unwrap()
+        });
+        thread::sleep(Duration::from_millis(100));
+
+        let second_root = match write::<_, _, _, LmdbTrieStore, error::Error>(
+            correlation_id,
+            &mut txn,
+            &state.trie_store,
+            &first_root,
+            &pairs[1].key,
+            &pairs[1].value,
+        )
+        .unwrap()
+        {
+            WriteResult::Written(second_root) => second_root,
+            _ => panic!("write failed"),
+        };
+        txn.commit().unwrap();
+
+        match pruning.join().unwrap() {
+            PruneResult::Success { removed, .. } => assert!(removed > 0),
+            _ => panic!("prune failed"),
+        }
+        let verification = state.verify_all(correlation_id).unwrap();
+        assert!(verification.is_intact(), "{:?}", verification);
+        assert!(state.checkout(first_root).unwrap().is_none());
+        assert!(state.checkout(second_root).unwrap().is_none());
+        assert!(state.checkout(root_hash).unwrap().is_some());
+    }
+
+    #[test]
+    fn import_of_exported_snapshot_copies_state_and_protocol_data() {
+        let correlation_id = CorrelationId::new();
+        let (state, root_hash) = create_test_state();
+        let protocol_data = ProtocolData::default();
+        state
+            .put_protocol_data(ProtocolVersion::V1_0_0, &protocol_data)
+            .unwrap();
+
+        let mut snapshot = Vec::new();
+        let exported = state.export_snapshot(root_hash, &mut snapshot).unwrap();
+        assert_eq!(exported.state_root, root_hash);
+        assert_eq!(exported.protocol_data, 1);
+
+        let other_state = create_empty_state();
+        assert!(other_state.checkout(root_hash).unwrap().is_none());
+
+        let imported = other_state
+            .import_snapshot(snapshot.as_slice(), false)
+            .unwrap();
+        assert_eq!(imported, exported);
+
+        let checkout = other_state.checkout(root_hash).unwrap().unwrap();
+        for TestPair { key, value } in create_test_pairs().iter().cloned() {
+            assert_eq!(Some(value), checkout.read(correlation_id, &key).unwrap());
+        }
+        assert_eq!(
+            other_state
+                .get_protocol_data(ProtocolVersion::V1_0_0)
+                .unwrap(),
+            Some(protocol_data)
+        );
+    }
+
+    #[test]
+    fn import_of_incomplete_snapshot_changes_nothing() {
+        let (state, root_hash) = create_test_state();
+
+        // A snapshot holding only the root element.
+        let mut snapshot = Vec::new();
+        {
+            let txn = state.environment.create_read_txn().unwrap();
+            let root: Trie<Key, StoredValue> =
+                state.trie_store.get(&txn, &root_hash).unwrap().unwrap();
+            txn.commit().unwrap();
+
+            let mut writer = SnapshotWriter::new(&mut snapshot, root_hash).unwrap();
+            writer.write_trie_element(root.to_bytes().unwrap()).unwrap();
+            writer.finish().unwrap();
+        }
+
+        let other_state = create_empty_state();
+        match other_state.import_snapshot(snapshot.as_slice(), false) {
+            Err(SnapshotError::MissingTrieElement(_)) => {}
+            result => panic!("unexpected result: {:?}", result),
+        }
+        assert!(other_state.checkout(root_hash).unwrap().is_none());
+    }
+
+    #[test]
+    fn import_of_conflicting_protocol_data_requires_overwrite() {
+        let (state, root_hash) = create_test_state();
+        let protocol_data = ProtocolData::default();
+        state
+            .put_protocol_data(ProtocolVersion::V1_0_0, &protocol_data)
+            .unwrap();
+
+        let mut snapshot = Vec::new();
+        state.export_snapshot(root_hash, &mut snapshot).unwrap();
+
+        // Importing the same protocol data again is not a conflict.
+        state.import_snapshot(snapshot.as_slice(), false).unwrap();
+
+        let other_state = create_empty_state();
+        let other_protocol_data = ProtocolData::new(
+            WasmCosts::default(),
+            HostFunctionCosts::default(),
+            URef::new([1; 32], AccessRights::READ),
+            URef::new([2; 32], AccessRights::READ),
+        );
+        other_state
+            .put_protocol_data(ProtocolVersion::V1_0_0, &other_protocol_data)
+            .unwrap();
+
+        match other_state.import_snapshot(snapshot.as_slice(), false) {
+            Err(SnapshotError::ConflictingProtocolData(version)) => {
+                assert_eq!(version, ProtocolVersion::V1_0_0)
+            }
+            result => panic!("unexpected result: {:?}", result),
+        }
+        assert!(other_state.checkout(root_hash).unwrap().is_none());
+        assert_eq!(
+            other_state
+                .get_protocol_data(ProtocolVersion::V1_0_0)
+                .unwrap(),
+            Some(other_protocol_data)
+        );
+
+        other_state
+            .import_snapshot(snapshot.as_slice(), true)
+            .unwrap();
+        assert!(other_state.checkout(root_hash).unwrap().is_some());
+        assert_eq!(
+            other_state
+                .get_protocol_data(ProtocolVersion::V1_0_0)
+                .unwrap(),
+            Some(protocol_data)
+        );
+    }
+
+    #[test]
+    fn export_fails_if_unknown_root_is_given() {
+        let (state, _) = create_test_state();
+        let fake_hash: Blake2bHash = [1u8; 32].into();
+
+        match state.export_snapshot(fake_hash, Vec::new()) {
+            Err(SnapshotError::RootNotFound(hash)) => assert_eq!(hash, fake_hash),
+            result => panic!("unexpected result: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn trie_stats_of_a_root_count_its_leaves() {
+        let correlation_id = CorrelationId::new();
+        let (state, root_hash) = create_test_state();
+
+        let stats = state
+            .trie_stats(correlation_id, root_hash)
+            .unwrap()
+            .unwrap();
+        assert_eq!(stats.leaf_count, create_test_pairs().len());
+        assert_eq!(stats.missing_count, 0);
+
+        let fake_hash: Blake2bHash = [1u8; 32].into();
+        assert!(state
+            .trie_stats(correlation_id, fake_hash)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn list_state_roots_pages_in_ascending_order_of_state_root() {
+        let (state, _) = create_test_state();
+        let state_roots: Vec<(Blake2bHash, StateRootMetadata)> = [3u8, 1, 2]
+            .iter()
+            .map(|byte| {
+                let state_root_metadata = StateRootMetadata::new(
+                    state.empty_root_hash,
+                    ProtocolVersion::V1_0_0,
+                    BlockTime::new(u64::from(*byte)),
+                    1,
+                );
+                (Blake2bHash::from([*byte; 32]), state_root_metadata)
+            })
+            .collect();
+        for (state_root, state_root_metadata) in &state_roots {
+            state
+                .put_state_root_metadata(*state_root, state_root_metadata)
+                .unwrap();
+        }
+
+        let first_page = state.list_state_roots(&[], 2).unwrap();
+        assert_eq!(first_page, vec![state_roots[1], state_roots[2]]);
+
+        let second_page = state.list_state_roots(&[3; 32], 2).unwrap();
+        assert_eq!(second_page, vec![state_roots[0]]);
+
+        assert!(state.list_state_roots(&[4], 2).unwrap().is_empty());
+    }
+
+    #[test]
+    fn list_state_roots_returns_recorded_metadata() {
+        let (state, root_hash) = create_test_state();
+        assert!(state.list_state_roots(&[], 10).unwrap().is_empty());
+
+        let state_root_metadata = StateRootMetadata::new(
+            state.empty_root_hash,
+            ProtocolVersion::V1_0_0,
+            BlockTime::new(42),
+            create_test_pairs().len() as u64,
+        );
+        state
+            .put_state_root_metadata(root_hash, &state_root_metadata)
+            .unwrap();
+
+        assert_eq!(
+            state.get_state_root_metadata(root_hash).unwrap(),
+            Some(state_root_metadata)
+        );
+        assert_eq!(
+            state.list_state_roots(&[], 10).unwrap(),
+            vec![(root_hash, state_root_metadata)]
+        );
+        assert!(state
+            .get_state_root_metadata(state.empty_root_hash)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn commit_and_register_records_metadata_unless_already_recorded() {
+        let correlation_id = CorrelationId::new();
+        let (state, root_hash) = create_test_state();
+
+        let effects: AdditiveMap<Key, Transform> = {
+            let mut tmp = AdditiveMap::new();
+            for TestPair { key, value } in &create_test_pairs_updated() {
+                tmp.insert(*key, Transform::Write(value.to_owned()));
+            }
+            tmp
+        };
+
+        let updated_hash = match state
+            .commit_and_register(
+                correlation_id,
+                root_hash,
+                effects.clone(),
+                ProtocolVersion::V1_0_0,
+                BlockTime::new(1),
+            )
+            .unwrap()
+        {
+            CommitResult::Success { state_root, .. } => state_root,
+            _ => panic!("commit failed"),
+        };
+        let state_root_metadata = StateRootMetadata::new(
+            root_hash,
+            ProtocolVersion::V1_0_0,
+            BlockTime::new(1),
+            effects.len() as u64,
+        );
+        assert_eq!(
+            state.get_state_root_metadata(updated_hash).unwrap(),
+            Some(state_root_metadata)
+        );
+
+        // committing the same effects again reproduces the state root, which keeps its record
+        match state
+            .commit_and_register(
+                correlation_id,
+                root_hash,
+                effects,
+                ProtocolVersion::V1_0_0,
+                BlockTime::new(2),
+            )
+            .unwrap()
+        {
+            CommitResult::Success { state_root, .. } => assert_eq!(state_root, updated_hash),
+            _ => panic!("commit failed"),
+        };
+        assert_eq!(
+            state.get_state_root_metadata(updated_hash).unwrap(),
+            Some(state_root_metadata)
+        );
+    }
+
+    #[test]
+    fn read_only_state_reads_committed_values() {
+        let correlation_id = CorrelationId::new();
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().to_path_buf();
+
+        let (empty_root_hash, root_hash) = {
+            let environment = Arc::new(LmdbEnvironment::new(&path, *TEST_MAP_SIZE).unwrap());
+            let trie_store =
+                Arc::new(LmdbTrieStore::new(&environment, None, DatabaseFlags::empty()).unwrap());
+            let protocol_data_store = Arc::new(
+                LmdbProtocolDataStore::new(&environment, None, DatabaseFlags::empty()).unwrap(),
+            );
+            let state_root_store = Arc::new(
+                LmdbStateRootStore::new(&environment, None, DatabaseFlags::empty()).unwrap(),
+            );
+            let state = LmdbGlobalState::empty(
+                environment,
+                trie_store,
+                protocol_data_store,
+                state_root_store,
+            )
+            .unwrap();
+            let effects: AdditiveMap<Key, Transform> = {
+                let mut tmp = AdditiveMap::new();
+                for TestPair { key, value } in &create_test_pairs() {
+                    tmp.insert(*key, Transform::Write(value.to_owned()));
+                }
+                tmp
+            };
+            match state
+                .commit(correlation_id, state.empty_root_hash, effects)
+                .unwrap()
+            {
+                CommitResult::Success { state_root, .. } => (state.empty_root_hash, state_root),
+                _ => panic!("commit failed"),
+            }
+        };
+
+        let environment = Arc::new(LmdbEnvironment::open_read_only(&path).unwrap());
+        let trie_store = Arc::new(LmdbTrieStore::open(&environment, None).unwrap());
+        let protocol_data_store =
+            Arc::new(LmdbProtocolDataStore::open(&environment, None).unwrap());
+        let state_root_store = Arc::new(LmdbStateRootStore::open(&environment, None).unwrap());
+        let state = LmdbGlobalState::open(
+            environment,
+            trie_store,
+            protocol_data_store,
+            state_root_store,
+        )
+        .unwrap();
+        assert_eq!(state.empty_root_hash, empty_root_hash);
+
+        let checkout = state.checkout(root_hash).unwrap().unwrap();
+        for TestPair { key, value } in create_test_pairs().iter().cloned() {
+            assert_eq!(Some(value), checkout.read(correlation_id, &key).unwrap());
+        }
+        assert!(state.environment.create_read_write_txn().is_err());
+    }
+
+    #[test]
+    fn repair_deletes_damaged_elements_so_they_can_be_fetched_again() {
+        let correlation_id = CorrelationId::new();
+        let (state, root_hash) = create_test_state();
+
+        let verification = state.verify_all(correlation_id).unwrap();
+        assert!(verification.is_intact(), "{:?}", verification);
+        assert!(verification.checked > create_test_pairs().len());
+
+        {
+            let mut txn = state.environment.create_read_write_txn().unwrap();
+            let handle = <LmdbTrieStore as Store<Blake2bHash, Trie<Key, StoredValue>>>::handle(
+                &state.trie_store,
+            );
+            txn.write(handle, &root_hash.to_bytes().unwrap(), &[255u8; 3])
+                .unwrap();
+            txn.commit().unwrap();
+        }
+
+        let verification = state.verify(correlation_id, &[root_hash]).unwrap();
+        assert_eq!(
+            verification.corruptions,
+            vec![TrieCorruption::Undecodable { hash: root_hash }]
+        );
+
+        assert_eq!(state.repair(&verification).unwrap(), 1);
+        assert_eq!(
+            state
+                .missing_trie_keys(correlation_id, &[root_hash], usize::max_value())
+                .unwrap()
+                .missing,
+            vec![root_hash]
+        );
+    }
 }
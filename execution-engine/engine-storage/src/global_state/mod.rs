@@ -1,9 +1,13 @@
 pub mod in_memory;
 pub mod lmdb;
+#[cfg(feature = "use_rocksdb")]
+pub mod rocksdb;
+pub mod snapshot;
 
 use std::{collections::HashMap, fmt, hash::BuildHasher, time::Instant};
 
 use contract_ffi::{
+    block_time::BlockTime,
     bytesrepr,
     key::Key,
     value::{account::PublicKey, ProtocolVersion, U512},
@@ -18,10 +22,13 @@ use engine_shared::{
 
 use crate::{
     protocol_data::ProtocolData,
-    transaction_source::{Transaction, TransactionSource},
-    trie::Trie,
+    state_root_metadata::StateRootMetadata,
+    state_root_store::StateRootStore,
+    store::Store,
+    transaction_source::{Readable, Transaction, TransactionSource, Writable},
+    trie::{merkle_proof::TrieMerkleProof, Trie},
     trie_store::{
-        operations::{read, write, ReadResult, WriteResult},
+        operations::{read, write_batch, DiffResult, MissingTrieKeys, ReadResult, WriteResult},
         TrieStore,
     },
 };
@@ -33,6 +40,9 @@ const GLOBAL_STATE_COMMIT_READ_DURATION: &str = "global_state_commit_read_durati
 const GLOBAL_STATE_COMMIT_WRITE_DURATION: &str = "global_state_commit_write_duration";
 const COMMIT: &str = "commit";
 
+/// The number of trie elements scanned by each read-write transaction of a prune.
+pub const PRUNE_BATCH_SIZE: usize = 10_000;
+
 /// A reader of state
 pub trait StateReader<K, V> {
     /// An error which occurs when reading state
@@ -42,6 +52,35 @@ pub trait StateReader<K, V> {
     fn read(&self, correlation_id: CorrelationId, key: &K) -> Result<Option<V>, Self::Error>;
 }
 
+/// A reader of state which can prove the values it reads against its state root
+pub trait StateProofReader<K, V>: StateReader<K, V> {
+    /// Returns a Merkle proof of the presence or absence of the value under the corresponding key
+    fn read_with_proof(
+        &self,
+        correlation_id: CorrelationId,
+        key: &K,
+    ) -> Result<TrieMerkleProof<K, V>, Self::Error>;
+}
+
+/// An iterator over key-value pairs read from state
+pub type StateIterator<'a, K, V, E> = Box<dyn Iterator<Item = Result<(K, V), E>> + 'a>;
+
+/// A reader of state which can enumerate the values stored under keys with a given prefix
+pub trait StatePrefixReader<K, V>: StateReader<K, V> {
+    /// Returns an iterator over the key-value pairs whose serialized keys start with `prefix`,
+    /// skipping those whose serialized keys are less than `start`, in ascending order of
+    /// serialized key.
+    ///
+    /// Values are read lazily as the iterator advances, from a read transaction which is held
+    /// open until the iterator is dropped.
+    fn iter_prefix<'a>(
+        &'a self,
+        correlation_id: CorrelationId,
+        prefix: &[u8],
+        start: &[u8],
+    ) -> Result<StateIterator<'a, K, V, Self::Error>, Self::Error>;
+}
+
 #[derive(Debug)]
 pub enum CommitResult {
     RootNotFound,
@@ -86,9 +125,42 @@ impl From<transform::Error> for CommitResult {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PruneResult {
+    /// One of the roots to retain was not found; nothing was deleted.
+    RootNotFound(Blake2bHash),
+    Success {
+        /// The number of trie elements reachable from the retained roots.
+        retained: usize,
+        /// The number of unreachable trie elements deleted.
+        removed: usize,
+        /// The number of deleted state roots whose records were removed from the registry.
+        unregistered: usize,
+    },
+}
+
+impl fmt::Display for PruneResult {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match self {
+            PruneResult::RootNotFound(root) => write!(f, "Root not found: {}", root),
+            PruneResult::Success {
+                retained,
+                removed,
+                unregistered,
+            } => write!(
+                f,
+                "Success: retained: {}, removed: {}, unregistered: {}",
+                retained, removed, unregistered
+            ),
+        }
+    }
+}
+
 pub trait StateProvider {
     type Error;
-    type Reader: StateReader<Key, StoredValue, Error = Self::Error>;
+    type Reader: StateReader<Key, StoredValue, Error = Self::Error>
+        + StateProofReader<Key, StoredValue>
+        + StatePrefixReader<Key, StoredValue>;
 
     /// Checkouts to the post state of a specific block.
     fn checkout(&self, state_hash: Blake2bHash) -> Result<Option<Self::Reader>, Self::Error>;
@@ -102,6 +174,17 @@ pub trait StateProvider {
         effects: AdditiveMap<Key, Transform>,
     ) -> Result<CommitResult, Self::Error>;
 
+    /// Applies changes on top of `state_hash` like [`StateProvider::commit`], and records how the
+    /// resulting state root was produced, unless it already is, in the same transaction.
+    fn commit_and_register(
+        &self,
+        correlation_id: CorrelationId,
+        state_hash: Blake2bHash,
+        effects: AdditiveMap<Key, Transform>,
+        protocol_version: ProtocolVersion,
+        block_time: BlockTime,
+    ) -> Result<CommitResult, Self::Error>;
+
     fn put_protocol_data(
         &self,
         protocol_version: ProtocolVersion,
@@ -113,7 +196,62 @@ pub trait StateProvider {
         protocol_version: ProtocolVersion,
     ) -> Result<Option<ProtocolData>, Self::Error>;
 
+    /// Records how `state_root` was produced, replacing any earlier record of it.
+    fn put_state_root_metadata(
+        &self,
+        state_root: Blake2bHash,
+        state_root_metadata: &StateRootMetadata,
+    ) -> Result<(), Self::Error>;
+
+    /// Returns the record of how `state_root` was produced, if any.
+    fn get_state_root_metadata(
+        &self,
+        state_root: Blake2bHash,
+    ) -> Result<Option<StateRootMetadata>, Self::Error>;
+
+    /// Returns up to `limit` of the recorded state roots with their metadata, in ascending order
+    /// of state root, skipping those whose serialized form is less than `start`.
+    fn list_state_roots(
+        &self,
+        start: &[u8],
+        limit: usize,
+    ) -> Result<Vec<(Blake2bHash, StateRootMetadata)>, Self::Error>;
+
     fn empty_root(&self) -> Blake2bHash;
+
+    /// Returns up to `limit` of the keys whose values differ between two post states, with their
+    /// old and new values, skipping those whose serialized keys are less than `start`.
+    fn diff(
+        &self,
+        correlation_id: CorrelationId,
+        old_state_hash: Blake2bHash,
+        new_state_hash: Blake2bHash,
+        start: &[u8],
+        limit: usize,
+    ) -> Result<DiffResult<Key, StoredValue>, Self::Error>;
+
+    /// Returns the trie element stored under `trie_key`, if any.
+    fn get_trie(
+        &self,
+        trie_key: &Blake2bHash,
+    ) -> Result<Option<Trie<Key, StoredValue>>, Self::Error>;
+
+    /// Stores each of `tries` under the hash of its serialized form, in a single transaction, and
+    /// returns those hashes in the same order.
+    ///
+    /// As the hashes are computed here rather than supplied by the caller, an element received
+    /// from a peer can only ever be stored under its own hash.
+    fn put_tries(&self, tries: &[Trie<Key, StoredValue>]) -> Result<Vec<Blake2bHash>, Self::Error>;
+
+    /// Returns the hashes of up to `limit` trie elements reachable from `trie_keys`, including
+    /// `trie_keys` themselves, which are not stored, along with the hashes left unexplored once
+    /// the limit was reached.
+    fn missing_trie_keys(
+        &self,
+        correlation_id: CorrelationId,
+        trie_keys: &[Blake2bHash],
+        limit: usize,
+    ) -> Result<MissingTrieKeys, Self::Error>;
 }
 
 pub fn commit<'a, R, S, H, E>(
@@ -129,6 +267,35 @@ where
     S::Error: From<R::Error>,
     E: From<R::Error> + From<S::Error> + From<contract_ffi::bytesrepr::Error>,
     H: BuildHasher,
+{
+    commit_and_then(
+        environment,
+        store,
+        correlation_id,
+        prestate_hash,
+        effects,
+        |_, _| Ok(()),
+    )
+}
+
+/// Applies `effects` on top of `prestate_hash` like [`commit`], then calls `on_success` with the
+/// read-write transaction and the resulting state root, so that whatever it writes is committed
+/// along with the effects or not at all.
+pub fn commit_and_then<'a, R, S, H, E, F>(
+    environment: &'a R,
+    store: &S,
+    correlation_id: CorrelationId,
+    prestate_hash: Blake2bHash,
+    effects: AdditiveMap<Key, Transform, H>,
+    on_success: F,
+) -> Result<CommitResult, E>
+where
+    R: TransactionSource<'a, Handle = S::Handle>,
+    S: TrieStore<Key, StoredValue>,
+    S::Error: From<R::Error>,
+    E: From<R::Error> + From<S::Error> + From<contract_ffi::bytesrepr::Error>,
+    H: BuildHasher,
+    F: FnOnce(&mut R::ReadWriteTransaction, Blake2bHash) -> Result<(), E>,
 {
     let mut txn = environment.create_read_write_txn()?;
     let mut state_root = prestate_hash;
@@ -141,18 +308,12 @@ where
 
     let start = Instant::now();
     let mut reads: i32 = 0;
-    let mut writes: i32 = 0;
+
+    let mut pairs: Vec<(Key, StoredValue)> = Vec::with_capacity(effects.len());
 
     for (key, transform) in effects.into_iter() {
         let read_result = read::<_, _, _, _, E>(correlation_id, &txn, store, &state_root, &key)?;
 
-        log_duration(
-            correlation_id,
-            GLOBAL_STATE_COMMIT_READ_DURATION,
-            COMMIT,
-            start.elapsed(),
-        );
-
         reads += 1;
 
         let value = match (read_result, transform) {
@@ -167,26 +328,38 @@ where
             _x @ (ReadResult::RootNotFound, _) => panic!(stringify!(_x._1)),
         };
 
-        let write_result =
-            write::<_, _, _, _, E>(correlation_id, &mut txn, store, &state_root, &key, &value)?;
+        pairs.push((key, value));
+    }
 
-        log_duration(
-            correlation_id,
-            GLOBAL_STATE_COMMIT_WRITE_DURATION,
-            COMMIT,
-            start.elapsed(),
-        );
+    log_duration(
+        correlation_id,
+        GLOBAL_STATE_COMMIT_READ_DURATION,
+        COMMIT,
+        start.elapsed(),
+    );
 
-        match write_result {
-            WriteResult::Written(root_hash) => {
-                state_root = root_hash;
-                writes += 1;
-            }
-            WriteResult::AlreadyExists => (),
-            _x @ WriteResult::RootNotFound => panic!(stringify!(_x)),
-        }
+    let writes = pairs.len() as i32;
+
+    // All the effects are written in one batch, so that trie elements shared by several of the
+    // keys are only rewritten once.
+    let write_result =
+        write_batch::<_, _, _, _, E>(correlation_id, &mut txn, store, &state_root, pairs)?;
+
+    log_duration(
+        correlation_id,
+        GLOBAL_STATE_COMMIT_WRITE_DURATION,
+        COMMIT,
+        start.elapsed(),
+    );
+
+    match write_result {
+        WriteResult::Written(root_hash) => state_root = root_hash,
+        WriteResult::AlreadyExists => (),
+        _x @ WriteResult::RootNotFound => panic!(stringify!(_x)),
     }
 
+    on_success(&mut txn, state_root)?;
+
     txn.commit()?;
 
     log_duration(
@@ -219,3 +392,23 @@ where
         bonded_validators,
     })
 }
+
+/// Records `state_root_metadata` at `state_root` in `txn`, unless `state_root` is already
+/// recorded: committing no effects, or effects which were committed before, reproduces an existing
+/// state root.
+pub fn register_state_root<T, S>(
+    txn: &mut T,
+    state_root_store: &S,
+    state_root: Blake2bHash,
+    state_root_metadata: &StateRootMetadata,
+) -> Result<(), S::Error>
+where
+    T: Readable<Handle = S::Handle> + Writable<Handle = S::Handle>,
+    S: StateRootStore,
+    S::Error: From<T::Error>,
+{
+    if state_root_store.get(&*txn, &state_root)?.is_none() {
+        state_root_store.put(txn, &state_root, state_root_metadata)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,957 @@
+use std::{
+    io::{Read, Write},
+    ops::Deref,
+    sync::Arc,
+};
+
+use contract_ffi::{block_time::BlockTime, bytesrepr::ToBytes, key::Key, value::ProtocolVersion};
+use engine_shared::{
+    additive_map::AdditiveMap,
+    newtypes::{Blake2bHash, CorrelationId},
+    stored_value::StoredValue,
+    transform::Transform,
+};
+
+use crate::{
+    error,
+    global_state::{
+        commit, commit_and_then, register_state_root,
+        snapshot::{self, SnapshotError, SnapshotSummary},
+        CommitResult, PruneResult, StateIterator, StatePrefixReader, StateProofReader,
+        StateProvider, StateReader, PRUNE_BATCH_SIZE,
+    },
+    protocol_data::ProtocolData,
+    protocol_data_store::rocksdb::RocksDbProtocolDataStore,
+    state_root_metadata::StateRootMetadata,
+    state_root_store::rocksdb::RocksDbStateRootStore,
+    store::Store,
+    transaction_source::{
+        rocksdb::{RocksDbEnvironment, RocksDbReadTransaction},
+        Transaction, TransactionSource,
+    },
+    trie::{merkle_proof::TrieMerkleProof, operations::create_hashed_empty_trie, Trie},
+    trie_store::{
+        operations::{
+            diff, iter_prefix, missing_trie_keys, reachable_hashes, read, read_with_proof,
+            trie_stats, verify, DiffResult, MissingTrieKeys, ReadResult, TrieStats,
+            TrieVerification,
+        },
+        rocksdb::RocksDbTrieStore,
+    },
+};
+
+pub struct RocksDbGlobalState {
+    pub environment: Arc<RocksDbEnvironment>,
+    pub trie_store: Arc<RocksDbTrieStore>,
+    pub protocol_data_store: Arc<RocksDbProtocolDataStore>,
+    pub state_root_store: Arc<RocksDbStateRootStore>,
+    pub empty_root_hash: Blake2bHash,
+}
+
+/// Represents a "view" of global state at a particular root hash.
+pub struct RocksDbGlobalStateView {
+    pub environment: Arc<RocksDbEnvironment>,
+    pub store: Arc<RocksDbTrieStore>,
+    pub root_hash: Blake2bHash,
+}
+
+impl RocksDbGlobalState {
+    /// Creates an empty state from an existing environment and trie_store.
+    pub fn empty(
+        environment: Arc<RocksDbEnvironment>,
+        trie_store: Arc<RocksDbTrieStore>,
+        protocol_data_store: Arc<RocksDbProtocolDataStore>,
+        state_root_store: Arc<RocksDbStateRootStore>,
+    ) -> Result<Self, error::Error> {
+        let root_hash: Blake2bHash = {
+            let (root_hash, root) = create_hashed_empty_trie::<Key, StoredValue>()?;
+            let mut txn = environment.create_read_write_txn()?;
+            trie_store.put(&mut txn, &root_hash, &root)?;
+            txn.commit()?;
+            root_hash
+        };
+        Ok(RocksDbGlobalState::new(
+            environment,
+            trie_store,
+            protocol_data_store,
+            state_root_store,
+            root_hash,
+        ))
+    }
+
+    /// Creates a state from an existing environment, store, and root_hash.
+    /// Intended to be used for testing.
+    pub(crate) fn new(
+        environment: Arc<RocksDbEnvironment>,
+        trie_store: Arc<RocksDbTrieStore>,
+        protocol_data_store: Arc<RocksDbProtocolDataStore>,
+        state_root_store: Arc<RocksDbStateRootStore>,
+        empty_root_hash: Blake2bHash,
+    ) -> Self {
+        RocksDbGlobalState {
+            environment,
+            trie_store,
+            protocol_data_store,
+            state_root_store,
+            empty_root_hash,
+        }
+    }
+
+    /// Deletes all trie elements which are not reachable from `roots_to_retain` or the empty root.
+    ///
+    /// Works like [`LmdbGlobalState::prune`](super::lmdb::LmdbGlobalState::prune).
+    pub fn prune(
+        &self,
+        correlation_id: CorrelationId,
+        roots_to_retain: &[Blake2bHash],
+    ) -> Result<PruneResult, error::Error> {
+        self.prune_in_batches(correlation_id, roots_to_retain, PRUNE_BATCH_SIZE)
+    }
+
+    pub(crate) fn prune_in_batches(
+        &self,
+        correlation_id: CorrelationId,
+        roots_to_retain: &[Blake2bHash],
+        batch_size: usize,
+    ) -> Result<PruneResult, error::Error> {
+        self.trie_store.start_recording_writes()?;
+        let result = self.prune_recording_writes(correlation_id, roots_to_retain, batch_size);
+        self.trie_store.stop_recording_writes()?;
+        result
+    }
+
+    fn prune_recording_writes(
+        &self,
+        correlation_id: CorrelationId,
+        roots_to_retain: &[Blake2bHash],
+        batch_size: usize,
+    ) -> Result<PruneResult, error::Error> {
+        let mut roots = roots_to_retain.to_vec();
+        roots.push(self.empty_root_hash);
+
+        let reachable = {
+            let txn = self.environment.create_read_txn()?;
+            for root in &roots {
+                let maybe_root: Option<Trie<Key, StoredValue>> = self.trie_store.get(&txn, root)?;
+                if maybe_root.is_none() {
+                    return Ok(PruneResult::RootNotFound(*root));
+                }
+            }
+            let reachable = reachable_hashes::<Key, StoredValue, _, _, error::Error>(
+                correlation_id,
+                &txn,
+                self.trie_store.deref(),
+                &roots,
+            )?;
+            txn.commit()?;
+            reachable
+        };
+
+        let mut removed = 0;
+        let mut maybe_start: Option<Vec<u8>> = None;
+        loop {
+            let mut txn = self.environment.create_read_write_txn()?;
+            let (batch_removed, maybe_next_start) = self.trie_store.prune_batch(
+                &mut txn,
+                &reachable,
+                maybe_start.as_ref().map(Vec::as_slice),
+                batch_size,
+            )?;
+            txn.commit()?;
+            removed += batch_removed;
+            match maybe_next_start {
+                Some(next_start) => maybe_start = Some(next_start),
+                None => break,
+            }
+        }
+
+        let unregistered = self.unregister_deleted_state_roots()?;
+
+        Ok(PruneResult::Success {
+            retained: reachable.len(),
+            removed,
+            unregistered,
+        })
+    }
+
+    /// Removes the records of the state roots which are no longer stored from the state root
+    /// registry, and returns how many were removed.
+    fn unregister_deleted_state_roots(&self) -> Result<usize, error::Error> {
+        let state_roots = {
+            let txn = self.environment.create_read_txn()?;
+            let state_roots = self.state_root_store.get_all(&txn)?;
+            txn.commit()?;
+            state_roots
+        };
+        let mut txn = self.environment.create_read_write_txn()?;
+        let mut unregistered = 0;
+        for (state_root, _) in state_roots {
+            let maybe_root: Option<Trie<Key, StoredValue>> =
+                self.trie_store.get(&txn, &state_root)?;
+            if maybe_root.is_none() {
+                self.state_root_store.delete(&mut txn, &state_root)?;
+                unregistered += 1;
+            }
+        }
+        txn.commit()?;
+        Ok(unregistered)
+    }
+
+    /// Returns the counts and sizes of the trie elements reachable from `state_root`, or `None` if
+    /// it is not found.
+    pub fn trie_stats(
+        &self,
+        correlation_id: CorrelationId,
+        state_root: Blake2bHash,
+    ) -> Result<Option<TrieStats>, error::Error> {
+        let txn = self.environment.create_read_txn()?;
+        let maybe_stats = trie_stats::<Key, StoredValue, _, _, error::Error>(
+            correlation_id,
+            &txn,
+            self.trie_store.deref(),
+            &state_root,
+        )?;
+        txn.commit()?;
+        Ok(maybe_stats)
+    }
+
+    /// Verifies the integrity of the trie elements reachable from `roots`, reading them from a
+    /// single snapshot.
+    pub fn verify(
+        &self,
+        correlation_id: CorrelationId,
+        roots: &[Blake2bHash],
+    ) -> Result<TrieVerification, error::Error> {
+        let txn = self.environment.create_read_txn()?;
+        let verification = verify::<Key, StoredValue, _, _, error::Error>(
+            correlation_id,
+            &txn,
+            self.trie_store.deref(),
+            roots,
+        )?;
+        txn.commit()?;
+        Ok(verification)
+    }
+
+    /// Verifies the integrity of every trie element in the store, walking from each element which
+    /// is not referenced by another.
+    pub fn verify_all(
+        &self,
+        correlation_id: CorrelationId,
+    ) -> Result<TrieVerification, error::Error> {
+        let txn = self.environment.create_read_txn()?;
+        let roots = self
+            .trie_store
+            .unreferenced_hashes::<Key, StoredValue>(&txn)?;
+        let verification = verify::<Key, StoredValue, _, _, error::Error>(
+            correlation_id,
+            &txn,
+            self.trie_store.deref(),
+            &roots,
+        )?;
+        txn.commit()?;
+        Ok(verification)
+    }
+
+    /// Deletes the damaged elements found by a verification, returning the number of elements
+    /// deleted.
+    pub fn repair(&self, verification: &TrieVerification) -> Result<usize, error::Error> {
+        let damaged: Vec<Blake2bHash> = verification
+            .corruptions
+            .iter()
+            .filter(|corruption| corruption.is_damaged())
+            .map(|corruption| corruption.hash())
+            .collect();
+        let mut txn = self.environment.create_read_write_txn()?;
+        let deleted = self.trie_store.delete(&mut txn, &damaged)?;
+        txn.commit()?;
+        Ok(deleted)
+    }
+
+    /// Returns all the protocol data in the store, in ascending order of serialized protocol
+    /// version.
+    pub fn get_all_protocol_data(
+        &self,
+    ) -> Result<Vec<(ProtocolVersion, ProtocolData)>, error::Error> {
+        let txn = self.environment.create_read_txn()?;
+        let all_protocol_data = self.protocol_data_store.get_all(&txn)?;
+        txn.commit()?;
+        Ok(all_protocol_data)
+    }
+
+    /// Writes a snapshot of the trie elements reachable from `state_root`, along with all the
+    /// protocol data, to `writer`.
+    ///
+    /// See [`snapshot::export`].
+    pub fn export_snapshot<W: Write>(
+        &self,
+        state_root: Blake2bHash,
+        writer: W,
+    ) -> Result<SnapshotSummary, SnapshotError> {
+        snapshot::export(
+            self.environment.deref(),
+            self.trie_store.deref(),
+            |txn| self.protocol_data_store.get_all(txn),
+            state_root,
+            writer,
+        )
+    }
+
+    /// Reads a snapshot from `reader` into this global state.
+    ///
+    /// See [`snapshot::import`].
+    pub fn import_snapshot<R: Read>(
+        &self,
+        reader: R,
+        overwrite_protocol_data: bool,
+    ) -> Result<SnapshotSummary, SnapshotError> {
+        snapshot::import(
+            self.environment.deref(),
+            self.trie_store.deref(),
+            self.protocol_data_store.deref(),
+            reader,
+            overwrite_protocol_data,
+        )
+    }
+}
+
+impl StateReader<Key, StoredValue> for RocksDbGlobalStateView {
+    type Error = error::Error;
+
+    fn read(
+        &self,
+        correlation_id: CorrelationId,
+        key: &Key,
+    ) -> Result<Option<StoredValue>, Self::Error> {
+        let txn = self.environment.create_read_txn()?;
+        let ret =
+            match read::<Key, StoredValue, RocksDbReadTransaction, RocksDbTrieStore, Self::Error>(
+                correlation_id,
+                &txn,
+                self.store.deref(),
+                &self.root_hash,
+                key,
+            )? {
+                ReadResult::Found(value) => Some(value),
+                ReadResult::NotFound => None,
+                ReadResult::RootNotFound => panic!("RocksDbGlobalState has invalid root"),
+            };
+        txn.commit()?;
+        Ok(ret)
+    }
+}
+
+impl StateProofReader<Key, StoredValue> for RocksDbGlobalStateView {
+    fn read_with_proof(
+        &self,
+        correlation_id: CorrelationId,
+        key: &Key,
+    ) -> Result<TrieMerkleProof<Key, StoredValue>, Self::Error> {
+        let txn = self.environment.create_read_txn()?;
+        let ret = match read_with_proof::<
+            Key,
+            StoredValue,
+            RocksDbReadTransaction,
+            RocksDbTrieStore,
+            Self::Error,
+        >(
+            correlation_id,
+            &txn,
+            self.store.deref(),
+            &self.root_hash,
+            key,
+        )? {
+            Some(proof) => proof,
+            None => panic!("RocksDbGlobalState has invalid root"),
+        };
+        txn.commit()?;
+        Ok(ret)
+    }
+}
+
+impl StatePrefixReader<Key, StoredValue> for RocksDbGlobalStateView {
+    fn iter_prefix<'a>(
+        &'a self,
+        correlation_id: CorrelationId,
+        prefix: &[u8],
+        start: &[u8],
+    ) -> Result<StateIterator<'a, Key, StoredValue, Self::Error>, Self::Error> {
+        let txn = self.environment.create_read_txn()?;
+        let iter =
+            iter_prefix::<Key, StoredValue, RocksDbReadTransaction, RocksDbTrieStore, Self::Error>(
+                correlation_id,
+                txn,
+                self.store.deref(),
+                &self.root_hash,
+                prefix,
+                start,
+            )?;
+        Ok(Box::new(iter))
+    }
+}
+
+impl StateProvider for RocksDbGlobalState {
+    type Error = error::Error;
+
+    type Reader = RocksDbGlobalStateView;
+
+    fn checkout(&self, prestate_hash: Blake2bHash) -> Result<Option<Self::Reader>, Self::Error> {
+        let txn = self.environment.create_read_txn()?;
+        let maybe_root: Option<Trie<Key, StoredValue>> =
+            self.trie_store.get(&txn, &prestate_hash)?;
+        let maybe_state = maybe_root.map(|_| RocksDbGlobalStateView {
+            environment: Arc::clone(&self.environment),
+            store: Arc::clone(&self.trie_store),
+            root_hash: prestate_hash,
+        });
+        txn.commit()?;
+        Ok(maybe_state)
+    }
+
+    fn commit(
+        &self,
+        correlation_id: CorrelationId,
+        prestate_hash: Blake2bHash,
+        effects: AdditiveMap<Key, Transform>,
+    ) -> Result<CommitResult, Self::Error> {
+        let commit_result = commit::<RocksDbEnvironment, RocksDbTrieStore, _, Self::Error>(
+            &self.environment,
+            &self.trie_store,
+            correlation_id,
+            prestate_hash,
+            effects,
+        )?;
+        Ok(commit_result)
+    }
+
+    fn commit_and_register(
+        &self,
+        correlation_id: CorrelationId,
+        prestate_hash: Blake2bHash,
+        effects: AdditiveMap<Key, Transform>,
+        protocol_version: ProtocolVersion,
+        block_time: BlockTime,
+    ) -> Result<CommitResult, Self::Error> {
+        let state_root_metadata = StateRootMetadata::new(
+            prestate_hash,
+            protocol_version,
+            block_time,
+            effects.len() as u64,
+        );
+        let commit_result =
+            commit_and_then::<RocksDbEnvironment, RocksDbTrieStore, _, Self::Error, _>(
+                &self.environment,
+                &self.trie_store,
+                correlation_id,
+                prestate_hash,
+                effects,
+                |txn, state_root| {
+                    register_state_root(
+                        txn,
+                        self.state_root_store.deref(),
+                        state_root,
+                        &state_root_metadata,
+                    )
+                },
+            )?;
+        Ok(commit_result)
+    }
+
+    fn put_protocol_data(
+        &self,
+        protocol_version: ProtocolVersion,
+        protocol_data: &ProtocolData,
+    ) -> Result<(), Self::Error> {
+        let mut txn = self.environment.create_read_write_txn()?;
+        self.protocol_data_store
+            .put(&mut txn, &protocol_version, protocol_data)?;
+        txn.commit().map_err(Into::into)
+    }
+
+    fn get_protocol_data(
+        &self,
+        protocol_version: ProtocolVersion,
+    ) -> Result<Option<ProtocolData>, Self::Error> {
+        let txn = self.environment.create_read_txn()?;
+        let result = self.protocol_data_store.get(&txn, &protocol_version)?;
+        txn.commit()?;
+        Ok(result)
+    }
+
+    fn put_state_root_metadata(
+        &self,
+        state_root: Blake2bHash,
+        state_root_metadata: &StateRootMetadata,
+    ) -> Result<(), Self::Error> {
+        let mut txn = self.environment.create_read_write_txn()?;
+        self.state_root_store
+            .put(&mut txn, &state_root, state_root_metadata)?;
+        txn.commit().map_err(Into::into)
+    }
+
+    fn get_state_root_metadata(
+        &self,
+        state_root: Blake2bHash,
+    ) -> Result<Option<StateRootMetadata>, Self::Error> {
+        let txn = self.environment.create_read_txn()?;
+        let result = self.state_root_store.get(&txn, &state_root)?;
+        txn.commit()?;
+        Ok(result)
+    }
+
+    fn list_state_roots(
+        &self,
+        start: &[u8],
+        limit: usize,
+    ) -> Result<Vec<(Blake2bHash, StateRootMetadata)>, Self::Error> {
+        let txn = self.environment.create_read_txn()?;
+        let state_roots = self.state_root_store.get_range(&txn, start, limit)?;
+        txn.commit()?;
+        Ok(state_roots)
+    }
+
+    fn empty_root(&self) -> Blake2bHash {
+        self.empty_root_hash
+    }
+
+    fn diff(
+        &self,
+        correlation_id: CorrelationId,
+        old_state_hash: Blake2bHash,
+        new_state_hash: Blake2bHash,
+        start: &[u8],
+        limit: usize,
+    ) -> Result<DiffResult<Key, StoredValue>, Self::Error> {
+        let txn = self.environment.create_read_txn()?;
+        let diff_result =
+            diff::<Key, StoredValue, RocksDbReadTransaction, RocksDbTrieStore, Self::Error>(
+                correlation_id,
+                &txn,
+                self.trie_store.deref(),
+                &old_state_hash,
+                &new_state_hash,
+                start,
+                limit,
+            )?;
+        txn.commit()?;
+        Ok(diff_result)
+    }
+
+    fn get_trie(
+        &self,
+        trie_key: &Blake2bHash,
+    ) -> Result<Option<Trie<Key, StoredValue>>, Self::Error> {
+        let txn = self.environment.create_read_txn()?;
+        let maybe_trie: Option<Trie<Key, StoredValue>> = self.trie_store.get(&txn, trie_key)?;
+        txn.commit()?;
+        Ok(maybe_trie)
+    }
+
+    fn put_tries(&self, tries: &[Trie<Key, StoredValue>]) -> Result<Vec<Blake2bHash>, Self::Error> {
+        let mut txn = self.environment.create_read_write_txn()?;
+        let mut trie_hashes = Vec::with_capacity(tries.len());
+        for trie in tries {
+            let trie_hash = Blake2bHash::new(&trie.to_bytes()?);
+            self.trie_store.put(&mut txn, &trie_hash, trie)?;
+            trie_hashes.push(trie_hash);
+        }
+        txn.commit()?;
+        Ok(trie_hashes)
+    }
+
+    fn missing_trie_keys(
+        &self,
+        correlation_id: CorrelationId,
+        trie_keys: &[Blake2bHash],
+        limit: usize,
+    ) -> Result<MissingTrieKeys, Self::Error> {
+        let txn = self.environment.create_read_txn()?;
+        let missing = missing_trie_keys::<
+            Key,
+            StoredValue,
+            RocksDbReadTransaction,
+            RocksDbTrieStore,
+            Self::Error,
+        >(
+            correlation_id,
+            &txn,
+            self.trie_store.deref(),
+            trie_keys,
+            limit,
+        )?;
+        txn.commit()?;
+        Ok(missing)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::{tempdir, TempDir};
+
+    use contract_ffi::value::CLValue;
+
+    use crate::{
+        transaction_source::Writable,
+        trie_store::operations::{write, Difference, TrieCorruption, WriteResult},
+    };
+
+    use super::*;
+
+    #[derive(Debug, Clone)]
+    struct TestPair {
+        key: Key,
+        value: StoredValue,
+    }
+
+    fn create_test_pairs() -> [TestPair; 2] {
+        [
+            TestPair {
+                key: Key::Account([1_u8; 32]),
+                value: StoredValue::CLValue(CLValue::from_t(1_i32).unwrap()),
+            },
+            TestPair {
+                key: Key::Account([2_u8; 32]),
+                value: StoredValue::CLValue(CLValue::from_t(2_i32).unwrap()),
+            },
+        ]
+    }
+
+    fn create_test_pairs_updated() -> [TestPair; 3] {
+        [
+            TestPair {
+                key: Key::Account([1u8; 32]),
+                value: StoredValue::CLValue(CLValue::from_t("one".to_string()).unwrap()),
+            },
+            TestPair {
+                key: Key::Account([2u8; 32]),
+                value: StoredValue::CLValue(CLValue::from_t("two".to_string()).unwrap()),
+            },
+            TestPair {
+                key: Key::Account([3u8; 32]),
+                value: StoredValue::CLValue(CLValue::from_t(3_i32).unwrap()),
+            },
+        ]
+    }
+
+    fn open_state(temp_dir: &TempDir) -> RocksDbGlobalState {
+        let environment =
+            Arc::new(RocksDbEnvironment::new(&temp_dir.path().to_path_buf()).unwrap());
+        let trie_store = Arc::new(RocksDbTrieStore::new(&environment, None));
+        let protocol_data_store = Arc::new(RocksDbProtocolDataStore::new(&environment, None));
+        let state_root_store = Arc::new(RocksDbStateRootStore::new(&environment, None));
+        RocksDbGlobalState::empty(
+            environment,
+            trie_store,
+            protocol_data_store,
+            state_root_store,
+        )
+        .unwrap()
+    }
+
+    // The temporary directory is returned so that it outlives the database.
+    fn create_test_state() -> (TempDir, RocksDbGlobalState, Blake2bHash) {
+        let correlation_id = CorrelationId::new();
+        let temp_dir = tempdir().unwrap();
+        let state = open_state(&temp_dir);
+        let mut current_root = state.empty_root_hash;
+        {
+            let mut txn = state.environment.create_read_write_txn().unwrap();
+
+            for TestPair { key, value } in &create_test_pairs() {
+                match write::<_, _, _, RocksDbTrieStore, error::Error>(
+                    correlation_id,
+                    &mut txn,
+                    &state.trie_store,
+                    &current_root,
+                    key,
+                    value,
+                )
+                .unwrap()
+                {
+                    WriteResult::Written(root_hash) => {
+                        current_root = root_hash;
+                    }
+                    WriteResult::AlreadyExists => (),
+                    WriteResult::RootNotFound => panic!("RocksDbGlobalState has invalid root"),
+                }
+            }
+
+            txn.commit().unwrap();
+        }
+        (temp_dir, state, current_root)
+    }
+
+    #[test]
+    fn reads_from_a_checkout_return_expected_values() {
+        let correlation_id = CorrelationId::new();
+        let (_temp_dir, state, root_hash) = create_test_state();
+        let checkout = state.checkout(root_hash).unwrap().unwrap();
+        for TestPair { key, value } in create_test_pairs().iter().cloned() {
+            assert_eq!(Some(value), checkout.read(correlation_id, &key).unwrap());
+        }
+    }
+
+    #[test]
+    fn iter_prefix_of_a_checkout_returns_matching_pairs_in_order() {
+        let correlation_id = CorrelationId::new();
+        let (_temp_dir, state, root_hash) = create_test_state();
+        let checkout = state.checkout(root_hash).unwrap().unwrap();
+
+        let all_accounts = checkout
+            .iter_prefix(correlation_id, &[0u8], &[])
+            .unwrap()
+            .collect::<Result<Vec<(Key, StoredValue)>, _>>()
+            .unwrap();
+        let expected = create_test_pairs()
+            .iter()
+            .cloned()
+            .map(|TestPair { key, value }| (key, value))
+            .collect::<Vec<(Key, StoredValue)>>();
+        assert_eq!(all_accounts, expected);
+    }
+
+    #[test]
+    fn checkout_fails_if_unknown_hash_is_given() {
+        let (_temp_dir, state, _) = create_test_state();
+        let fake_hash: Blake2bHash = [1u8; 32].into();
+        let result = state.checkout(fake_hash).unwrap();
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn commit_updates_state_and_original_state_stays_intact() {
+        let correlation_id = CorrelationId::new();
+        let test_pairs_updated = create_test_pairs_updated();
+
+        let (_temp_dir, state, root_hash) = create_test_state();
+
+        let effects: AdditiveMap<Key, Transform> = test_pairs_updated
+            .iter()
+            .cloned()
+            .map(|TestPair { key, value }| (key, Transform::Write(value)))
+            .collect();
+
+        let updated_hash = match state.commit(correlation_id, root_hash, effects).unwrap() {
+            CommitResult::Success { state_root, .. } => state_root,
+            _ => panic!("commit failed"),
+        };
+
+        let updated_checkout = state.checkout(updated_hash).unwrap().unwrap();
+        for TestPair { key, value } in test_pairs_updated.iter().cloned() {
+            assert_eq!(
+                Some(value),
+                updated_checkout.read(correlation_id, &key).unwrap()
+            );
+        }
+
+        let original_checkout = state.checkout(root_hash).unwrap().unwrap();
+        for TestPair { key, value } in create_test_pairs().iter().cloned() {
+            assert_eq!(
+                Some(value),
+                original_checkout.read(correlation_id, &key).unwrap()
+            );
+        }
+        assert_eq!(
+            None,
+            original_checkout
+                .read(correlation_id, &test_pairs_updated[2].key)
+                .unwrap()
+        );
+
+        let diff = state
+            .diff(
+                correlation_id,
+                root_hash,
+                updated_hash,
+                &[],
+                usize::max_value(),
+            )
+            .unwrap();
+        match diff {
+            DiffResult::Success(differences) => assert_eq!(
+                differences
+                    .iter()
+                    .filter(|difference| match difference {
+                        Difference::Added { .. } => true,
+                        _ => false,
+                    })
+                    .count(),
+                1
+            ),
+            other => panic!("unexpected diff result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn reopened_state_keeps_committed_state() {
+        let correlation_id = CorrelationId::new();
+        let (temp_dir, state, root_hash) = create_test_state();
+        drop(state);
+
+        let reopened = open_state(&temp_dir);
+        let checkout = reopened.checkout(root_hash).unwrap().unwrap();
+        for TestPair { key, value } in create_test_pairs().iter().cloned() {
+            assert_eq!(Some(value), checkout.read(correlation_id, &key).unwrap());
+        }
+    }
+
+    #[test]
+    fn prune_unregisters_deleted_state_roots() {
+        let correlation_id = CorrelationId::new();
+        let (_temp_dir, state, root_hash) = create_test_state();
+        let state_root_metadata = StateRootMetadata::new(
+            state.empty_root(),
+            ProtocolVersion::V1_0_0,
+            BlockTime::new(1),
+            2,
+        );
+        state
+            .put_state_root_metadata(root_hash, &state_root_metadata)
+            .unwrap();
+
+        let effects: AdditiveMap<Key, Transform> = create_test_pairs_updated()
+            .iter()
+            .cloned()
+            .map(|TestPair { key, value }| (key, Transform::Write(value)))
+            .collect();
+        let updated_hash = match state
+            .commit_and_register(
+                correlation_id,
+                root_hash,
+                effects,
+                ProtocolVersion::V1_0_0,
+                BlockTime::new(2),
+            )
+            .unwrap()
+        {
+            CommitResult::Success { state_root, .. } => state_root,
+            _ => panic!("commit failed"),
+        };
+
+        match state.prune(correlation_id, &[updated_hash]).unwrap() {
+            PruneResult::Success { unregistered, .. } => assert_eq!(unregistered, 1),
+            PruneResult::RootNotFound(root) => panic!("root not found: {}", root),
+        }
+        assert!(state.get_state_root_metadata(root_hash).unwrap().is_none());
+        assert!(state
+            .get_state_root_metadata(updated_hash)
+            .unwrap()
+            .is_some());
+
+        match state.prune(correlation_id, &[updated_hash]).unwrap() {
+            PruneResult::Success { unregistered, .. } => assert_eq!(unregistered, 0),
+            PruneResult::RootNotFound(root) => panic!("root not found: {}", root),
+        }
+    }
+
+    #[test]
+    fn prune_removes_unretained_roots_and_keeps_retained_ones() {
+        let correlation_id = CorrelationId::new();
+        let test_pairs_updated = create_test_pairs_updated();
+
+        let (_temp_dir, state, root_hash) = create_test_state();
+
+        let effects: AdditiveMap<Key, Transform> = test_pairs_updated
+            .iter()
+            .cloned()
+            .map(|TestPair { key, value }| (key, Transform::Write(value)))
+            .collect();
+
+        let updated_hash = match state.commit(correlation_id, root_hash, effects).unwrap() {
+            CommitResult::Success { state_root, .. } => state_root,
+            _ => panic!("commit failed"),
+        };
+
+        match state
+            .prune_in_batches(correlation_id, &[updated_hash], 2)
+            .unwrap()
+        {
+            PruneResult::Success { removed, .. } => assert!(removed > 0),
+            PruneResult::RootNotFound(root) => panic!("root not found: {}", root),
+        }
+
+        assert!(state.checkout(root_hash).unwrap().is_none());
+        assert!(state.checkout(state.empty_root()).unwrap().is_some());
+
+        let updated_checkout = state.checkout(updated_hash).unwrap().unwrap();
+        for TestPair { key, value } in test_pairs_updated.iter().cloned() {
+            assert_eq!(
+                Some(value),
+                updated_checkout.read(correlation_id, &key).unwrap()
+            );
+        }
+
+        match state.prune(correlation_id, &[updated_hash]).unwrap() {
+            PruneResult::Success { removed, .. } => assert_eq!(removed, 0),
+            PruneResult::RootNotFound(root) => panic!("root not found: {}", root),
+        }
+    }
+
+    #[test]
+    fn import_of_exported_snapshot_copies_state_and_protocol_data() {
+        let correlation_id = CorrelationId::new();
+        let (_temp_dir, state, root_hash) = create_test_state();
+        let protocol_data = ProtocolData::default();
+        state
+            .put_protocol_data(ProtocolVersion::V1_0_0, &protocol_data)
+            .unwrap();
+
+        let mut snapshot = Vec::new();
+        let exported = state.export_snapshot(root_hash, &mut snapshot).unwrap();
+        assert_eq!(exported.state_root, root_hash);
+        assert_eq!(exported.protocol_data, 1);
+
+        let other_temp_dir = tempdir().unwrap();
+        let other_state = open_state(&other_temp_dir);
+        assert!(other_state.checkout(root_hash).unwrap().is_none());
+
+        let imported = other_state
+            .import_snapshot(snapshot.as_slice(), false)
+            .unwrap();
+        assert_eq!(imported, exported);
+
+        let checkout = other_state.checkout(root_hash).unwrap().unwrap();
+        for TestPair { key, value } in create_test_pairs().iter().cloned() {
+            assert_eq!(Some(value), checkout.read(correlation_id, &key).unwrap());
+        }
+        assert_eq!(
+            other_state.get_all_protocol_data().unwrap(),
+            vec![(ProtocolVersion::V1_0_0, protocol_data)]
+        );
+    }
+
+    #[test]
+    fn repair_deletes_damaged_elements_so_they_can_be_fetched_again() {
+        let correlation_id = CorrelationId::new();
+        let (_temp_dir, state, root_hash) = create_test_state();
+
+        let verification = state.verify_all(correlation_id).unwrap();
+        assert!(verification.is_intact(), "{:?}", verification);
+        assert!(verification.checked > create_test_pairs().len());
+
+        {
+            let mut txn = state.environment.create_read_write_txn().unwrap();
+            let handle = <RocksDbTrieStore as Store<Blake2bHash, Trie<Key, StoredValue>>>::handle(
+                &state.trie_store,
+            );
+            txn.write(handle, &root_hash.to_bytes().unwrap(), &[255u8; 3])
+                .unwrap();
+            txn.commit().unwrap();
+        }
+
+        let verification = state.verify(correlation_id, &[root_hash]).unwrap();
+        assert_eq!(
+            verification.corruptions,
+            vec![TrieCorruption::Undecodable { hash: root_hash }]
+        );
+
+        assert_eq!(state.repair(&verification).unwrap(), 1);
+        assert_eq!(
+            state
+                .missing_trie_keys(correlation_id, &[root_hash], usize::max_value())
+                .unwrap()
+                .missing,
+            vec![root_hash]
+        );
+    }
+}
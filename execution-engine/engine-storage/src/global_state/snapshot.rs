@@ -0,0 +1,615 @@
+//! A portable format for moving a state root, and the protocol data it depends on, between
+//! engines.
+//!
+//! A snapshot starts with a header holding [`MAGIC`], the format version as a little-endian `u32`
+//! and the state root hash.  The header is followed by a stream of chunks, each made up of:
+//!
+//! * a one-byte kind
+//! * the length of the payload as a little-endian `u32`
+//! * the payload
+//! * the Blake2b hash of the previous chunk's hash, the kind and the payload.  The first chunk uses
+//!   the state root in place of the previous chunk's hash.
+//!
+//! The payloads of trie element and protocol data chunks are sequences of length-prefixed
+//! entries.  The last chunk is an end chunk holding the number of entries of each kind.  Chaining
+//! the hashes lets a reader detect corrupted, reordered or missing chunks as it streams, and the
+//! end chunk lets it detect truncation.
+
+use std::{
+    collections::HashSet,
+    fmt,
+    io::{self, Read, Write},
+    mem,
+};
+
+use failure::Fail;
+
+use contract_ffi::{
+    bytesrepr::{self, FromBytes, ToBytes},
+    key::Key,
+    value::ProtocolVersion,
+};
+use engine_shared::{
+    newtypes::{Blake2bHash, BLAKE2B_DIGEST_LENGTH},
+    stored_value::StoredValue,
+};
+
+use crate::{
+    error,
+    protocol_data::ProtocolData,
+    protocol_data_store::ProtocolDataStore,
+    transaction_source::{Transaction, TransactionSource},
+    trie::Trie,
+    trie_store::TrieStore,
+};
+
+/// The bytes every snapshot starts with.
+pub const MAGIC: [u8; 8] = *b"CLSNAPSH";
+
+/// The version of the snapshot format written by [`SnapshotWriter`].
+pub const FORMAT_VERSION: u32 = 1;
+
+/// Chunks are written once their payload reaches this size.
+const CHUNK_SIZE: usize = 1024 * 1024;
+
+const TRIE_ELEMENTS_CHUNK: u8 = 0;
+const PROTOCOL_DATA_CHUNK: u8 = 1;
+const END_CHUNK: u8 = 2;
+
+#[derive(Debug, Fail)]
+pub enum SnapshotError {
+    #[fail(display = "{}", _0)]
+    Io(#[fail(cause)] io::Error),
+
+    #[fail(display = "{}", _0)]
+    Storage(#[fail(cause)] error::Error),
+
+    #[fail(display = "{}", _0)]
+    BytesRepr(#[fail(cause)] bytesrepr::Error),
+
+    #[fail(display = "Root not found: {}", _0)]
+    RootNotFound(Blake2bHash),
+
+    #[fail(display = "Not a global state snapshot")]
+    InvalidMagic,
+
+    #[fail(display = "Unsupported snapshot format version {}", _0)]
+    UnsupportedVersion(u32),
+
+    #[fail(display = "Unknown chunk kind {}", _0)]
+    InvalidChunkKind(u8),
+
+    #[fail(display = "Hash of chunk {} does not match the expected hash", _0)]
+    HashMismatch(u64),
+
+    #[fail(display = "Snapshot ends before its end chunk")]
+    Truncated,
+
+    #[fail(display = "Number of entries read does not match the end chunk")]
+    CountMismatch,
+
+    #[fail(display = "Trie element missing: {}", _0)]
+    MissingTrieElement(Blake2bHash),
+
+    #[fail(
+        display = "Protocol data for version {} differs from the stored protocol data",
+        _0
+    )]
+    ConflictingProtocolData(ProtocolVersion),
+}
+
+impl From<io::Error> for SnapshotError {
+    fn from(error: io::Error) -> Self {
+        SnapshotError::Io(error)
+    }
+}
+
+impl From<error::Error> for SnapshotError {
+    fn from(error: error::Error) -> Self {
+        SnapshotError::Storage(error)
+    }
+}
+
+impl From<lmdb::Error> for SnapshotError {
+    fn from(error: lmdb::Error) -> Self {
+        SnapshotError::Storage(error.into())
+    }
+}
+
+impl From<bytesrepr::Error> for SnapshotError {
+    fn from(error: bytesrepr::Error) -> Self {
+        SnapshotError::BytesRepr(error)
+    }
+}
+
+/// The state root of a snapshot and the number of entries it holds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SnapshotSummary {
+    pub state_root: Blake2bHash,
+    pub trie_elements: u64,
+    pub protocol_data: u64,
+}
+
+impl SnapshotSummary {
+    fn new(state_root: Blake2bHash) -> Self {
+        SnapshotSummary {
+            state_root,
+            trie_elements: 0,
+            protocol_data: 0,
+        }
+    }
+}
+
+impl fmt::Display for SnapshotSummary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(
+            f,
+            "state_root: {}, trie_elements: {}, protocol_data: {}",
+            self.state_root, self.trie_elements, self.protocol_data
+        )
+    }
+}
+
+/// Returns the hash of a chunk which follows the chunk with `previous_hash`.
+fn chunk_hash(previous_hash: &Blake2bHash, kind: u8, payload: &[u8]) -> Blake2bHash {
+    let mut data = Vec::with_capacity(BLAKE2B_DIGEST_LENGTH + 1 + payload.len());
+    data.extend_from_slice(&previous_hash.to_vec());
+    data.push(kind);
+    data.extend_from_slice(payload);
+    Blake2bHash::new(&data)
+}
+
+/// Writes a snapshot to an underlying writer, one chunk at a time.
+pub struct SnapshotWriter<W> {
+    writer: W,
+    previous_hash: Blake2bHash,
+    kind: u8,
+    payload: Vec<u8>,
+    summary: SnapshotSummary,
+}
+
+impl<W: Write> SnapshotWriter<W> {
+    /// Writes the header of a snapshot of `state_root`.
+    pub fn new(mut writer: W, state_root: Blake2bHash) -> Result<Self, SnapshotError> {
+        writer.write_all(&MAGIC)?;
+        writer.write_all(&FORMAT_VERSION.to_bytes()?)?;
+        writer.write_all(&state_root.to_vec())?;
+        Ok(SnapshotWriter {
+            writer,
+            previous_hash: state_root,
+            kind: TRIE_ELEMENTS_CHUNK,
+            payload: Vec::new(),
+            summary: SnapshotSummary::new(state_root),
+        })
+    }
+
+    /// Appends a serialized trie element.
+    pub fn write_trie_element(&mut self, trie_bytes: Vec<u8>) -> Result<(), SnapshotError> {
+        self.append(TRIE_ELEMENTS_CHUNK, trie_bytes)?;
+        self.summary.trie_elements += 1;
+        Ok(())
+    }
+
+    /// Appends the protocol data of `protocol_version`.
+    pub fn write_protocol_data(
+        &mut self,
+        protocol_version: ProtocolVersion,
+        protocol_data: &ProtocolData,
+    ) -> Result<(), SnapshotError> {
+        let mut entry = protocol_version.to_bytes()?;
+        entry.append(&mut protocol_data.to_bytes()?);
+        self.append(PROTOCOL_DATA_CHUNK, entry)?;
+        self.summary.protocol_data += 1;
+        Ok(())
+    }
+
+    /// Writes any buffered entries followed by the end chunk, then flushes the underlying writer.
+    pub fn finish(mut self) -> Result<SnapshotSummary, SnapshotError> {
+        self.write_buffered_chunk()?;
+        let mut payload = self.summary.trie_elements.to_bytes()?;
+        payload.append(&mut self.summary.protocol_data.to_bytes()?);
+        self.write_chunk(END_CHUNK, &payload)?;
+        self.writer.flush()?;
+        Ok(self.summary)
+    }
+
+    fn append(&mut self, kind: u8, entry: Vec<u8>) -> Result<(), SnapshotError> {
+        if kind != self.kind {
+            self.write_buffered_chunk()?;
+            self.kind = kind;
+        }
+        self.payload.append(&mut entry.to_bytes()?);
+        if self.payload.len() >= CHUNK_SIZE {
+            self.write_buffered_chunk()?;
+        }
+        Ok(())
+    }
+
+    fn write_buffered_chunk(&mut self) -> Result<(), SnapshotError> {
+        if self.payload.is_empty() {
+            return Ok(());
+        }
+        let payload = mem::replace(&mut self.payload, Vec::new());
+        self.write_chunk(self.kind, &payload)
+    }
+
+    fn write_chunk(&mut self, kind: u8, payload: &[u8]) -> Result<(), SnapshotError> {
+        let hash = chunk_hash(&self.previous_hash, kind, payload);
+        self.writer.write_all(&[kind])?;
+        self.writer.write_all(&(payload.len() as u32).to_bytes()?)?;
+        self.writer.write_all(payload)?;
+        self.writer.write_all(&hash.to_vec())?;
+        self.previous_hash = hash;
+        Ok(())
+    }
+}
+
+/// The entries of a chunk read from a snapshot.
+#[derive(Debug)]
+pub enum SnapshotChunk {
+    /// Serialized trie elements.
+    TrieElements(Vec<Vec<u8>>),
+    ProtocolData(Vec<(ProtocolVersion, ProtocolData)>),
+}
+
+/// Reads a snapshot from an underlying reader, one chunk at a time, verifying each chunk as it is
+/// read.
+pub struct SnapshotReader<R> {
+    reader: R,
+    previous_hash: Blake2bHash,
+    chunk_index: u64,
+    summary: SnapshotSummary,
+    finished: bool,
+}
+
+impl<R: Read> SnapshotReader<R> {
+    /// Reads and checks the header of a snapshot.
+    pub fn new(mut reader: R) -> Result<Self, SnapshotError> {
+        let mut magic = [0u8; 8];
+        read_exact(&mut reader, &mut magic)?;
+        if magic != MAGIC {
+            return Err(SnapshotError::InvalidMagic);
+        }
+
+        let mut version_bytes = [0u8; 4];
+        read_exact(&mut reader, &mut version_bytes)?;
+        let version: u32 = bytesrepr::deserialize(version_bytes.to_vec())?;
+        if version != FORMAT_VERSION {
+            return Err(SnapshotError::UnsupportedVersion(version));
+        }
+
+        let mut state_root_bytes = [0u8; BLAKE2B_DIGEST_LENGTH];
+        read_exact(&mut reader, &mut state_root_bytes)?;
+        let state_root = Blake2bHash::from(state_root_bytes);
+
+        Ok(SnapshotReader {
+            reader,
+            previous_hash: state_root,
+            chunk_index: 0,
+            summary: SnapshotSummary::new(state_root),
+            finished: false,
+        })
+    }
+
+    pub fn state_root(&self) -> Blake2bHash {
+        self.summary.state_root
+    }
+
+    /// The state root and the number of entries read so far.
+    pub fn summary(&self) -> SnapshotSummary {
+        self.summary
+    }
+
+    /// Reads the next chunk, or returns `None` once the end chunk has been read and checked.
+    pub fn next_chunk(&mut self) -> Result<Option<SnapshotChunk>, SnapshotError> {
+        if self.finished {
+            return Ok(None);
+        }
+
+        let mut kind = [0u8; 1];
+        read_exact(&mut self.reader, &mut kind)?;
+        let kind = kind[0];
+
+        let mut length_bytes = [0u8; 4];
+        read_exact(&mut self.reader, &mut length_bytes)?;
+        let length: u32 = bytesrepr::deserialize(length_bytes.to_vec())?;
+
+        // Read through `take` so that a corrupted length cannot cause a huge allocation up front.
+        let mut payload = Vec::new();
+        (&mut self.reader)
+            .take(u64::from(length))
+            .read_to_end(&mut payload)?;
+        if payload.len() != length as usize {
+            return Err(SnapshotError::Truncated);
+        }
+
+        let mut hash_bytes = [0u8; BLAKE2B_DIGEST_LENGTH];
+        read_exact(&mut self.reader, &mut hash_bytes)?;
+        let hash = chunk_hash(&self.previous_hash, kind, &payload);
+        if Blake2bHash::from(hash_bytes) != hash {
+            return Err(SnapshotError::HashMismatch(self.chunk_index));
+        }
+        self.previous_hash = hash;
+        self.chunk_index += 1;
+
+        match kind {
+            TRIE_ELEMENTS_CHUNK => {
+                let entries = parse_entries(&payload)?;
+                self.summary.trie_elements += entries.len() as u64;
+                Ok(Some(SnapshotChunk::TrieElements(entries)))
+            }
+            PROTOCOL_DATA_CHUNK => {
+                let mut entries = Vec::new();
+                for entry in parse_entries(&payload)? {
+                    let (protocol_version, rem) = ProtocolVersion::from_bytes(&entry)?;
+                    let protocol_data = bytesrepr::deserialize(rem.to_vec())?;
+                    entries.push((protocol_version, protocol_data));
+                }
+                self.summary.protocol_data += entries.len() as u64;
+                Ok(Some(SnapshotChunk::ProtocolData(entries)))
+            }
+            END_CHUNK => {
+                let (trie_elements, rem) = u64::from_bytes(&payload)?;
+                let protocol_data: u64 = bytesrepr::deserialize(rem.to_vec())?;
+                if trie_elements != self.summary.trie_elements
+                    || protocol_data != self.summary.protocol_data
+                {
+                    return Err(SnapshotError::CountMismatch);
+                }
+                self.finished = true;
+                Ok(None)
+            }
+            kind => Err(SnapshotError::InvalidChunkKind(kind)),
+        }
+    }
+}
+
+fn read_exact<R: Read>(reader: &mut R, buf: &mut [u8]) -> Result<(), SnapshotError> {
+    reader.read_exact(buf).map_err(|error| match error.kind() {
+        io::ErrorKind::UnexpectedEof => SnapshotError::Truncated,
+        _ => SnapshotError::Io(error),
+    })
+}
+
+fn parse_entries(payload: &[u8]) -> Result<Vec<Vec<u8>>, bytesrepr::Error> {
+    let mut entries = Vec::new();
+    let mut remainder = payload;
+    while !remainder.is_empty() {
+        let (entry, rem): (Vec<u8>, &[u8]) = FromBytes::from_bytes(remainder)?;
+        entries.push(entry);
+        remainder = rem;
+    }
+    Ok(entries)
+}
+
+/// Writes a snapshot of the trie elements of `trie_store` reachable from `state_root`, along with
+/// the protocol data returned by `get_all_protocol_data`, to `writer`.
+///
+/// The trie and the protocol data are read in a single read transaction, so this is safe to run
+/// against a live global state.
+pub fn export<'a, R, S, F, W>(
+    environment: &'a R,
+    trie_store: &S,
+    get_all_protocol_data: F,
+    state_root: Blake2bHash,
+    writer: W,
+) -> Result<SnapshotSummary, SnapshotError>
+where
+    R: TransactionSource<'a, Handle = S::Handle>,
+    S: TrieStore<Key, StoredValue, Error = error::Error>,
+    error::Error: From<R::Error>,
+    SnapshotError: From<R::Error>,
+    F: FnOnce(&R::ReadTransaction) -> Result<Vec<(ProtocolVersion, ProtocolData)>, error::Error>,
+    W: Write,
+{
+    let txn = environment.create_read_txn()?;
+
+    let maybe_root: Option<Trie<Key, StoredValue>> = trie_store.get(&txn, &state_root)?;
+    if maybe_root.is_none() {
+        return Err(SnapshotError::RootNotFound(state_root));
+    }
+
+    let mut snapshot_writer = SnapshotWriter::new(writer, state_root)?;
+
+    let mut visited: HashSet<Blake2bHash> = HashSet::new();
+    let mut to_visit: Vec<Blake2bHash> = vec![state_root];
+    while let Some(hash) = to_visit.pop() {
+        if !visited.insert(hash) {
+            continue;
+        }
+        let trie: Trie<Key, StoredValue> = match trie_store.get(&txn, &hash)? {
+            Some(trie) => trie,
+            None => return Err(SnapshotError::MissingTrieElement(hash)),
+        };
+        to_visit.extend(trie.child_hashes());
+        snapshot_writer.write_trie_element(trie.to_bytes()?)?;
+    }
+
+    for (protocol_version, protocol_data) in get_all_protocol_data(&txn)? {
+        snapshot_writer.write_protocol_data(protocol_version, &protocol_data)?;
+    }
+
+    txn.commit()?;
+    snapshot_writer.finish()
+}
+
+/// Reads a snapshot from `reader` into `trie_store` and `protocol_data_store`.
+///
+/// Each chunk of trie elements is verified and committed in its own read-write transaction,
+/// except for the state root element, which is held back along with the protocol data until
+/// every trie element reachable from the state root is present.  A failed import may leave some
+/// of the snapshot's trie elements in the store, but never the state root, so they are
+/// unreachable and can be pruned.
+///
+/// Protocol data for a version which is already stored with different contents is rejected with
+/// [`SnapshotError::ConflictingProtocolData`], unless `overwrite_protocol_data` is set.
+pub fn import<'a, R, S, P, Rd>(
+    environment: &'a R,
+    trie_store: &S,
+    protocol_data_store: &P,
+    reader: Rd,
+    overwrite_protocol_data: bool,
+) -> Result<SnapshotSummary, SnapshotError>
+where
+    R: TransactionSource<'a, Handle = S::Handle>,
+    S: TrieStore<Key, StoredValue, Error = error::Error>,
+    P: ProtocolDataStore<Handle = S::Handle, Error = error::Error>,
+    error::Error: From<R::Error>,
+    SnapshotError: From<R::Error>,
+    Rd: Read,
+{
+    let mut snapshot_reader = SnapshotReader::new(reader)?;
+    let state_root = snapshot_reader.state_root();
+
+    let mut maybe_root: Option<Trie<Key, StoredValue>> = None;
+    let mut protocol_data_entries: Vec<(ProtocolVersion, ProtocolData)> = Vec::new();
+
+    while let Some(chunk) = snapshot_reader.next_chunk()? {
+        match chunk {
+            SnapshotChunk::TrieElements(elements) => {
+                let mut txn = environment.create_read_write_txn()?;
+                for trie_bytes in elements {
+                    let hash = Blake2bHash::new(&trie_bytes);
+                    let trie: Trie<Key, StoredValue> = bytesrepr::deserialize(trie_bytes)?;
+                    if hash == state_root {
+                        maybe_root = Some(trie);
+                    } else {
+                        trie_store.put(&mut txn, &hash, &trie)?;
+                    }
+                }
+                txn.commit()?;
+            }
+            SnapshotChunk::ProtocolData(entries) => {
+                let txn = environment.create_read_txn()?;
+                for (protocol_version, protocol_data) in entries {
+                    match protocol_data_store.get(&txn, &protocol_version)? {
+                        Some(ref stored) if *stored == protocol_data => continue,
+                        Some(_) if !overwrite_protocol_data => {
+                            return Err(SnapshotError::ConflictingProtocolData(protocol_version))
+                        }
+                        _ => protocol_data_entries.push((protocol_version, protocol_data)),
+                    }
+                }
+                txn.commit()?;
+            }
+        }
+    }
+
+    // The elements of a trie are never shared between its sub-tries, so the walk needs no record
+    // of the elements it has visited.  Elements which are not in the snapshot may already be in
+    // the store.
+    let txn = environment.create_read_txn()?;
+    let mut to_visit: Vec<Blake2bHash> = match maybe_root {
+        Some(ref root) => root.child_hashes(),
+        None => vec![state_root],
+    };
+    while let Some(hash) = to_visit.pop() {
+        let maybe_trie: Option<Trie<Key, StoredValue>> = trie_store.get(&txn, &hash)?;
+        match maybe_trie {
+            Some(trie) => to_visit.extend(trie.child_hashes()),
+            None => return Err(SnapshotError::MissingTrieElement(hash)),
+        }
+    }
+    txn.commit()?;
+
+    let mut txn = environment.create_read_write_txn()?;
+    if let Some(root) = maybe_root {
+        trie_store.put(&mut txn, &state_root, &root)?;
+    }
+    for (protocol_version, protocol_data) in protocol_data_entries {
+        protocol_data_store.put(&mut txn, &protocol_version, &protocol_data)?;
+    }
+    txn.commit()?;
+
+    Ok(snapshot_reader.summary())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state_root() -> Blake2bHash {
+        Blake2bHash::new(b"state root")
+    }
+
+    fn write_test_snapshot(trie_elements: &[Vec<u8>]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        let mut writer = SnapshotWriter::new(&mut bytes, state_root()).unwrap();
+        for trie_bytes in trie_elements {
+            writer.write_trie_element(trie_bytes.clone()).unwrap();
+        }
+        writer
+            .write_protocol_data(ProtocolVersion::V1_0_0, &ProtocolData::default())
+            .unwrap();
+        let summary = writer.finish().unwrap();
+        assert_eq!(summary.trie_elements, trie_elements.len() as u64);
+        assert_eq!(summary.protocol_data, 1);
+        bytes
+    }
+
+    fn read_all(bytes: &[u8]) -> Result<(Vec<SnapshotChunk>, SnapshotSummary), SnapshotError> {
+        let mut reader = SnapshotReader::new(bytes)?;
+        let mut chunks = Vec::new();
+        while let Some(chunk) = reader.next_chunk()? {
+            chunks.push(chunk);
+        }
+        Ok((chunks, reader.summary()))
+    }
+
+    #[test]
+    fn should_read_what_was_written() {
+        // Enough entries to span several chunks.
+        let trie_elements: Vec<Vec<u8>> = (0..3_000u32).map(|i| vec![i as u8; 1_000]).collect();
+        let bytes = write_test_snapshot(&trie_elements);
+
+        let (chunks, summary) = read_all(&bytes).unwrap();
+        assert_eq!(summary.state_root, state_root());
+        assert_eq!(summary.trie_elements, trie_elements.len() as u64);
+        assert_eq!(summary.protocol_data, 1);
+        assert!(chunks.len() > 2);
+
+        let mut read_trie_elements = Vec::new();
+        let mut read_protocol_data = Vec::new();
+        for chunk in chunks {
+            match chunk {
+                SnapshotChunk::TrieElements(entries) => read_trie_elements.extend(entries),
+                SnapshotChunk::ProtocolData(entries) => read_protocol_data.extend(entries),
+            }
+        }
+        assert_eq!(read_trie_elements, trie_elements);
+        assert_eq!(
+            read_protocol_data,
+            vec![(ProtocolVersion::V1_0_0, ProtocolData::default())]
+        );
+    }
+
+    #[test]
+    fn should_fail_to_read_corrupted_snapshot() {
+        let mut bytes = write_test_snapshot(&[vec![1, 2, 3]]);
+        // Flip a byte of the first trie element.
+        let index = MAGIC.len() + 4 + BLAKE2B_DIGEST_LENGTH + 1 + 4 + 4;
+        bytes[index] ^= 0xff;
+        match read_all(&bytes) {
+            Err(SnapshotError::HashMismatch(0)) => {}
+            result => panic!("unexpected result: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn should_fail_to_read_truncated_snapshot() {
+        let bytes = write_test_snapshot(&[vec![1, 2, 3], vec![4, 5, 6]]);
+        for length in 0..bytes.len() {
+            assert!(read_all(&bytes[..length]).is_err(), "length {}", length);
+        }
+    }
+
+    #[test]
+    fn should_fail_to_read_other_data() {
+        let mut bytes = write_test_snapshot(&[]);
+        bytes[0] = b'X';
+        match read_all(&bytes) {
+            Err(SnapshotError::InvalidMagic) => {}
+            result => panic!("unexpected result: {:?}", result),
+        }
+    }
+}
@@ -1,10 +1,37 @@
 #![feature(never_type, result_map_or_else)]
 
+/// Defines `$item`s in an `lmdb` module and, with the `use_rocksdb` feature, again in a `rocksdb`
+/// module, where `$context` names the `LmdbTestContext` or the `RocksDbTestContext` in scope at the
+/// invocation.
+#[cfg(test)]
+macro_rules! persistent_backend_tests {
+    ($context:ident; $($item:item)*) => {
+        mod lmdb {
+            use super::*;
+
+            type $context = LmdbTestContext;
+
+            $($item)*
+        }
+
+        #[cfg(feature = "use_rocksdb")]
+        mod rocksdb {
+            use super::*;
+
+            type $context = RocksDbTestContext;
+
+            $($item)*
+        }
+    };
+}
+
 // modules
 pub mod error;
 pub mod global_state;
 pub mod protocol_data;
 pub mod protocol_data_store;
+pub mod state_root_metadata;
+pub mod state_root_store;
 pub mod store;
 pub mod transaction_source;
 pub mod trie;
@@ -13,7 +40,7 @@ pub mod trie_store;
 #[cfg(test)]
 use lazy_static::lazy_static;
 
-const MAX_DBS: u32 = 2;
+const MAX_DBS: u32 = 3;
 
 #[cfg(test)]
 lazy_static! {
@@ -1,16 +1,37 @@
 use contract_ffi::{
-    bytesrepr::{self, FromBytes, ToBytes},
+    bytesrepr::{self, FromBytes, ToBytes, U32_SERIALIZED_LENGTH, U8_SERIALIZED_LENGTH},
     uref::{AccessRights, URef, UREF_SERIALIZED_LENGTH},
 };
-use engine_wasm_prep::wasm_costs::{WasmCosts, WASM_COSTS_SERIALIZED_LENGTH};
+use engine_wasm_prep::{
+    host_function_costs::{HostFunctionCosts, HOST_FUNCTION_COSTS_SERIALIZED_LENGTH},
+    wasm_costs::{WasmCosts, LEGACY_WASM_COSTS_SERIALIZED_LENGTH, WASM_COSTS_SERIALIZED_LENGTH},
+};
+
+/// Starts the versioned serialization of [`ProtocolData`].
+const VERSIONED_LAYOUT_MARKER: u32 = u32::max_value();
+
+/// The version of the current serialization of [`ProtocolData`].
+const PROTOCOL_DATA_VERSION: u8 = 1;
 
-const PROTOCOL_DATA_SERIALIZED_LENGTH: usize =
-    WASM_COSTS_SERIALIZED_LENGTH + UREF_SERIALIZED_LENGTH + UREF_SERIALIZED_LENGTH;
+/// The greatest length of protocol data stored before the serialization was versioned.
+///
+/// That layout has no marker, and its first field (the regular opcode cost) may hold any value, so
+/// it is told apart by its length instead: the versioned layout is always longer.
+const LEGACY_PROTOCOL_DATA_MAX_SERIALIZED_LENGTH: usize =
+    LEGACY_WASM_COSTS_SERIALIZED_LENGTH + UREF_SERIALIZED_LENGTH + UREF_SERIALIZED_LENGTH;
+
+const PROTOCOL_DATA_SERIALIZED_LENGTH: usize = U32_SERIALIZED_LENGTH
+    + U8_SERIALIZED_LENGTH
+    + WASM_COSTS_SERIALIZED_LENGTH
+    + HOST_FUNCTION_COSTS_SERIALIZED_LENGTH
+    + UREF_SERIALIZED_LENGTH
+    + UREF_SERIALIZED_LENGTH;
 
 /// Represents a protocol's data. Intended to be associated with a given protocol version.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct ProtocolData {
     wasm_costs: WasmCosts,
+    host_function_costs: HostFunctionCosts,
     mint: URef,
     proof_of_stake: URef,
 }
@@ -23,6 +44,7 @@ impl Default for ProtocolData {
     fn default() -> ProtocolData {
         ProtocolData {
             wasm_costs: WasmCosts::default(),
+            host_function_costs: HostFunctionCosts::default(),
             mint: URef::new([0; 32], AccessRights::READ),
             proof_of_stake: URef::new([0; 32], AccessRights::READ),
         }
@@ -30,10 +52,17 @@ impl Default for ProtocolData {
 }
 
 impl ProtocolData {
-    /// Creates a new [`ProtocolData`] value from a given [`WasmCosts`] value.
-    pub fn new(wasm_costs: WasmCosts, mint: URef, proof_of_stake: URef) -> Self {
+    /// Creates a new [`ProtocolData`] value from given [`WasmCosts`] and [`HostFunctionCosts`]
+    /// values.
+    pub fn new(
+        wasm_costs: WasmCosts,
+        host_function_costs: HostFunctionCosts,
+        mint: URef,
+        proof_of_stake: URef,
+    ) -> Self {
         ProtocolData {
             wasm_costs,
+            host_function_costs,
             mint,
             proof_of_stake,
         }
@@ -44,6 +73,11 @@ impl ProtocolData {
         &self.wasm_costs
     }
 
+    /// Gets the [`HostFunctionCosts`] value from a given [`ProtocolData`] value.
+    pub fn host_function_costs(&self) -> &HostFunctionCosts {
+        &self.host_function_costs
+    }
+
     pub fn mint(&self) -> URef {
         self.mint
     }
@@ -68,21 +102,57 @@ impl ProtocolData {
 impl ToBytes for ProtocolData {
     fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
         let mut ret: Vec<u8> = Vec::with_capacity(PROTOCOL_DATA_SERIALIZED_LENGTH);
+        ret.append(&mut VERSIONED_LAYOUT_MARKER.to_bytes()?);
+        ret.append(&mut PROTOCOL_DATA_VERSION.to_bytes()?);
         ret.append(&mut self.wasm_costs.to_bytes()?);
+        ret.append(&mut self.host_function_costs.to_bytes()?);
         ret.append(&mut self.mint.to_bytes()?);
         ret.append(&mut self.proof_of_stake.to_bytes()?);
         Ok(ret)
     }
 }
 
+impl ProtocolData {
+    /// Deserializes protocol data stored before the serialization was versioned, which consists
+    /// of the legacy wasm costs and the system contract urefs.  Host functions were not charged
+    /// then, so they are kept free to replay the blocks of those protocol versions as they ran.
+    fn from_legacy_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (wasm_costs, rem) = WasmCosts::from_legacy_bytes(bytes)?;
+        let (mint_reference, rem): (URef, &[u8]) = FromBytes::from_bytes(rem)?;
+        let (proof_of_stake_reference, rem): (URef, &[u8]) = FromBytes::from_bytes(rem)?;
+        Ok((
+            ProtocolData {
+                wasm_costs,
+                host_function_costs: HostFunctionCosts::default(),
+                mint: mint_reference,
+                proof_of_stake: proof_of_stake_reference,
+            },
+            rem,
+        ))
+    }
+}
+
 impl FromBytes for ProtocolData {
     fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
-        let (wasm_costs, rem): (WasmCosts, &[u8]) = FromBytes::from_bytes(bytes)?;
+        if bytes.len() <= LEGACY_PROTOCOL_DATA_MAX_SERIALIZED_LENGTH {
+            return ProtocolData::from_legacy_bytes(bytes);
+        }
+        let (marker, rem): (u32, &[u8]) = FromBytes::from_bytes(bytes)?;
+        if marker != VERSIONED_LAYOUT_MARKER {
+            return Err(bytesrepr::Error::FormattingError);
+        }
+        let (version, rem): (u8, &[u8]) = FromBytes::from_bytes(rem)?;
+        if version != PROTOCOL_DATA_VERSION {
+            return Err(bytesrepr::Error::FormattingError);
+        }
+        let (wasm_costs, rem): (WasmCosts, &[u8]) = FromBytes::from_bytes(rem)?;
+        let (host_function_costs, rem): (HostFunctionCosts, &[u8]) = FromBytes::from_bytes(rem)?;
         let (mint_reference, rem): (URef, &[u8]) = FromBytes::from_bytes(rem)?;
         let (proof_of_stake_reference, rem): (URef, &[u8]) = FromBytes::from_bytes(rem)?;
         Ok((
             ProtocolData {
                 wasm_costs,
+                host_function_costs,
                 mint: mint_reference,
                 proof_of_stake: proof_of_stake_reference,
             },
@@ -96,18 +166,22 @@ pub(crate) mod gens {
     use proptest::prop_compose;
 
     use contract_ffi::gens;
-    use engine_wasm_prep::wasm_costs::gens as wasm_costs_gens;
+    use engine_wasm_prep::{
+        host_function_costs::gens as host_function_costs_gens, wasm_costs::gens as wasm_costs_gens,
+    };
 
     use super::ProtocolData;
 
     prop_compose! {
         pub fn protocol_data_arb()(
             wasm_costs in wasm_costs_gens::wasm_costs_arb(),
+            host_function_costs in host_function_costs_gens::host_function_costs_arb(),
             mint in gens::uref_arb(),
             proof_of_stake in gens::uref_arb(),
         ) -> ProtocolData {
             ProtocolData {
                 wasm_costs,
+                host_function_costs,
                 mint,
                 proof_of_stake,
             }
@@ -120,10 +194,14 @@ mod tests {
     use proptest::proptest;
 
     use contract_ffi::{
-        bytesrepr,
+        bytesrepr::{self, ToBytes},
         uref::{AccessRights, URef},
     };
     use engine_shared::test_utils;
+    use engine_wasm_prep::{
+        host_function_costs::HostFunctionCosts,
+        wasm_costs::{WasmCosts, LEGACY_WASM_COSTS_SERIALIZED_LENGTH},
+    };
 
     use super::{gens, ProtocolData};
 
@@ -131,27 +209,109 @@ mod tests {
     fn should_serialize_and_deserialize() {
         let mock = {
             let costs = test_utils::wasm_costs_mock();
+            let host_function_costs = test_utils::host_function_costs_mock();
             let mint_reference = URef::new([0u8; 32], AccessRights::READ_ADD_WRITE);
             let proof_of_stake_reference = URef::new([1u8; 32], AccessRights::READ_ADD_WRITE);
-            ProtocolData::new(costs, mint_reference, proof_of_stake_reference)
+            ProtocolData::new(
+                costs,
+                host_function_costs,
+                mint_reference,
+                proof_of_stake_reference,
+            )
         };
         let free = {
             let costs = test_utils::wasm_costs_free();
+            let host_function_costs = test_utils::host_function_costs_free();
             let mint_reference = URef::new([0u8; 32], AccessRights::READ_ADD_WRITE);
             let proof_of_stake_reference = URef::new([1u8; 32], AccessRights::READ_ADD_WRITE);
-            ProtocolData::new(costs, mint_reference, proof_of_stake_reference)
+            ProtocolData::new(
+                costs,
+                host_function_costs,
+                mint_reference,
+                proof_of_stake_reference,
+            )
         };
         bytesrepr::test_serialization_roundtrip(&mock);
         bytesrepr::test_serialization_roundtrip(&free);
     }
 
+    fn legacy_bytes(wasm_costs: &WasmCosts, mint: URef, proof_of_stake: URef) -> Vec<u8> {
+        let mut ret = wasm_costs.to_bytes().unwrap();
+        ret.truncate(LEGACY_WASM_COSTS_SERIALIZED_LENGTH);
+        ret.append(&mut mint.to_bytes().unwrap());
+        ret.append(&mut proof_of_stake.to_bytes().unwrap());
+        ret
+    }
+
+    #[test]
+    fn should_deserialize_legacy_layout() {
+        let wasm_costs = test_utils::wasm_costs_mock();
+        let mint_reference = URef::new([2u8; 32], AccessRights::READ_ADD_WRITE);
+        let proof_of_stake_reference = URef::new([3u8; 32], AccessRights::READ_ADD_WRITE);
+        let legacy_bytes = legacy_bytes(&wasm_costs, mint_reference, proof_of_stake_reference);
+
+        let protocol_data: ProtocolData = bytesrepr::deserialize(legacy_bytes).unwrap();
+
+        assert_eq!(protocol_data.wasm_costs().regular, wasm_costs.regular);
+        assert_eq!(protocol_data.wasm_costs().zero_crypto_cost(), None);
+        assert_eq!(
+            *protocol_data.host_function_costs(),
+            HostFunctionCosts::default()
+        );
+        assert_eq!(protocol_data.mint(), mint_reference);
+        assert_eq!(protocol_data.proof_of_stake(), proof_of_stake_reference);
+    }
+
+    #[test]
+    fn should_deserialize_legacy_layout_starting_with_the_marker() {
+        let wasm_costs = WasmCosts {
+            regular: u32::max_value(),
+            ..test_utils::wasm_costs_mock()
+        };
+        let mint_reference = URef::new([2u8; 32], AccessRights::READ);
+        let proof_of_stake_reference = URef::new([3u8; 32], AccessRights::READ);
+        let legacy_bytes = legacy_bytes(&wasm_costs, mint_reference, proof_of_stake_reference);
+
+        let protocol_data: ProtocolData = bytesrepr::deserialize(legacy_bytes).unwrap();
+
+        assert_eq!(protocol_data.wasm_costs().regular, u32::max_value());
+        assert_eq!(protocol_data.wasm_costs().div, wasm_costs.div);
+        assert_eq!(
+            *protocol_data.host_function_costs(),
+            HostFunctionCosts::default()
+        );
+        assert_eq!(protocol_data.mint(), mint_reference);
+        assert_eq!(protocol_data.proof_of_stake(), proof_of_stake_reference);
+    }
+
+    #[test]
+    fn should_reject_unknown_version() {
+        let protocol_data = ProtocolData::new(
+            test_utils::wasm_costs_mock(),
+            test_utils::host_function_costs_mock(),
+            URef::new([2u8; 32], AccessRights::READ_ADD_WRITE),
+            URef::new([3u8; 32], AccessRights::READ_ADD_WRITE),
+        );
+        let mut bytes = protocol_data.to_bytes().unwrap();
+        bytes[4] += 1;
+
+        let result: Result<ProtocolData, _> = bytesrepr::deserialize(bytes);
+        assert_eq!(result, Err(bytesrepr::Error::FormattingError));
+    }
+
     #[test]
     fn should_return_all_system_contracts() {
         let mint_reference = URef::new([197u8; 32], AccessRights::READ_ADD_WRITE);
         let proof_of_stake_reference = URef::new([198u8; 32], AccessRights::READ_ADD_WRITE);
         let protocol_data = {
             let costs = test_utils::wasm_costs_mock();
-            ProtocolData::new(costs, mint_reference, proof_of_stake_reference)
+            let host_function_costs = test_utils::host_function_costs_mock();
+            ProtocolData::new(
+                costs,
+                host_function_costs,
+                mint_reference,
+                proof_of_stake_reference,
+            )
         };
 
         let actual = {
@@ -173,7 +333,13 @@ mod tests {
         let proof_of_stake_reference = URef::new([0u8; 32], AccessRights::READ);
         let protocol_data = {
             let costs = test_utils::wasm_costs_mock();
-            ProtocolData::new(costs, mint_reference, proof_of_stake_reference)
+            let host_function_costs = test_utils::host_function_costs_mock();
+            ProtocolData::new(
+                costs,
+                host_function_costs,
+                mint_reference,
+                proof_of_stake_reference,
+            )
         };
 
         let actual = {
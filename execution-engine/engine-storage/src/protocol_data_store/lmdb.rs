@@ -1,5 +1,6 @@
-use contract_ffi::value::ProtocolVersion;
-use lmdb::{Database, DatabaseFlags};
+use lmdb::{Cursor, Database, DatabaseFlags};
+
+use contract_ffi::{bytesrepr, value::ProtocolVersion};
 
 use crate::{
     error,
@@ -34,6 +35,22 @@ impl LmdbProtocolDataStore {
         Ok(LmdbProtocolDataStore { db })
     }
 
+    /// Returns all the protocol data in the store, in ascending order of serialized protocol
+    /// version.
+    pub fn get_all<T: lmdb::Transaction>(
+        &self,
+        txn: &T,
+    ) -> Result<Vec<(ProtocolVersion, ProtocolData)>, error::Error> {
+        let mut cursor = txn.open_ro_cursor(self.db)?;
+        let mut ret = Vec::new();
+        for (key_bytes, value_bytes) in cursor.iter_start() {
+            let protocol_version = bytesrepr::deserialize(key_bytes.to_vec())?;
+            let protocol_data = bytesrepr::deserialize(value_bytes.to_vec())?;
+            ret.push((protocol_version, protocol_data));
+        }
+        Ok(ret)
+    }
+
     fn name(maybe_name: Option<&str>) -> String {
         maybe_name
             .map(|name| format!("{}-{}", protocol_data_store::NAME, name))
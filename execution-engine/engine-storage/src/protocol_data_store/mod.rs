@@ -4,6 +4,8 @@ use contract_ffi::value::ProtocolVersion;
 
 pub mod in_memory;
 pub mod lmdb;
+#[cfg(feature = "use_rocksdb")]
+pub mod rocksdb;
 #[cfg(test)]
 mod tests;
 
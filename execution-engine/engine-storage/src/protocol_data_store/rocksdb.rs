@@ -0,0 +1,62 @@
+use rocksdb::{Direction, IteratorMode};
+
+use contract_ffi::{bytesrepr, value::ProtocolVersion};
+
+use crate::{
+    error,
+    protocol_data::ProtocolData,
+    protocol_data_store::{self, ProtocolDataStore},
+    store::Store,
+    transaction_source::rocksdb::{RocksDbEnvironment, RocksDbNamespace, RocksDbReadTransaction},
+};
+
+/// A RocksDB-backed protocol data store.
+///
+/// Keeps its entries under its own [`RocksDbNamespace`] of the environment's database.
+#[derive(Debug, Clone)]
+pub struct RocksDbProtocolDataStore {
+    namespace: RocksDbNamespace,
+}
+
+impl RocksDbProtocolDataStore {
+    pub fn new(_env: &RocksDbEnvironment, maybe_name: Option<&str>) -> Self {
+        let name = maybe_name
+            .map(|name| format!("{}-{}", protocol_data_store::NAME, name))
+            .unwrap_or_else(|| String::from(protocol_data_store::NAME));
+        RocksDbProtocolDataStore {
+            namespace: RocksDbNamespace::new(&name),
+        }
+    }
+
+    /// Returns all the protocol data in the store, in ascending order of serialized protocol
+    /// version.
+    pub fn get_all(
+        &self,
+        txn: &RocksDbReadTransaction,
+    ) -> Result<Vec<(ProtocolVersion, ProtocolData)>, error::Error> {
+        let prefix = self.namespace.prefix();
+        let mode = IteratorMode::From(prefix, Direction::Forward);
+        let mut ret = Vec::new();
+        for (key_bytes, value_bytes) in txn.snapshot().iterator(mode) {
+            if !key_bytes.starts_with(prefix) {
+                break;
+            }
+            let protocol_version = bytesrepr::deserialize(key_bytes[prefix.len()..].to_vec())?;
+            let protocol_data = bytesrepr::deserialize(value_bytes.to_vec())?;
+            ret.push((protocol_version, protocol_data));
+        }
+        Ok(ret)
+    }
+}
+
+impl Store<ProtocolVersion, ProtocolData> for RocksDbProtocolDataStore {
+    type Error = error::Error;
+
+    type Handle = RocksDbNamespace;
+
+    fn handle(&self) -> Self::Handle {
+        self.namespace.clone()
+    }
+}
+
+impl ProtocolDataStore for RocksDbProtocolDataStore {}
@@ -43,6 +43,20 @@ fn lmdb_roundtrip_succeeds(inputs: BTreeMap<ProtocolVersion, ProtocolData>) -> b
     ret
 }
 
+#[cfg(feature = "use_rocksdb")]
+fn rocksdb_roundtrip_succeeds(inputs: BTreeMap<ProtocolVersion, ProtocolData>) -> bool {
+    use crate::{
+        protocol_data_store::rocksdb::RocksDbProtocolDataStore,
+        transaction_source::rocksdb::RocksDbEnvironment,
+    };
+
+    let tmp_dir = tempfile::tempdir().unwrap();
+    let env = RocksDbEnvironment::new(&tmp_dir.path().to_path_buf()).unwrap();
+    let store = RocksDbProtocolDataStore::new(&env, None);
+
+    store_tests::roundtrip_succeeds(&env, &store, inputs).unwrap()
+}
+
 proptest! {
     #[test]
     fn prop_in_memory_roundtrip_succeeds(
@@ -57,4 +71,12 @@ proptest! {
     ) {
         assert!(lmdb_roundtrip_succeeds(m))
     }
+
+    #[cfg(feature = "use_rocksdb")]
+    #[test]
+    fn prop_rocksdb_roundtrip_succeeds(
+        m in collection::btree_map(gens_ext::protocol_version_arb(), gens::protocol_data_arb(), get_range())
+    ) {
+        assert!(rocksdb_roundtrip_succeeds(m))
+    }
 }
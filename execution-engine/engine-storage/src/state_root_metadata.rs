@@ -0,0 +1,131 @@
+use contract_ffi::{
+    block_time::{BlockTime, BLOCKTIME_SERIALIZED_LENGTH},
+    bytesrepr::{self, FromBytes, ToBytes, SEM_VER_SERIALIZED_LENGTH, U64_SERIALIZED_LENGTH},
+    value::ProtocolVersion,
+};
+use engine_shared::newtypes::{Blake2bHash, BLAKE2B_DIGEST_LENGTH};
+
+const STATE_ROOT_METADATA_SERIALIZED_LENGTH: usize = BLAKE2B_DIGEST_LENGTH
+    + SEM_VER_SERIALIZED_LENGTH
+    + BLOCKTIME_SERIALIZED_LENGTH
+    + U64_SERIALIZED_LENGTH;
+
+/// A record of how a state root was produced by a commit.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct StateRootMetadata {
+    parent: Blake2bHash,
+    protocol_version: ProtocolVersion,
+    block_time: BlockTime,
+    effects_count: u64,
+}
+
+impl StateRootMetadata {
+    pub fn new(
+        parent: Blake2bHash,
+        protocol_version: ProtocolVersion,
+        block_time: BlockTime,
+        effects_count: u64,
+    ) -> Self {
+        StateRootMetadata {
+            parent,
+            protocol_version,
+            block_time,
+            effects_count,
+        }
+    }
+
+    /// The state root the effects were committed on top of.
+    pub fn parent(&self) -> Blake2bHash {
+        self.parent
+    }
+
+    pub fn protocol_version(&self) -> ProtocolVersion {
+        self.protocol_version
+    }
+
+    pub fn block_time(&self) -> BlockTime {
+        self.block_time
+    }
+
+    /// The number of transforms in the committed effects.
+    pub fn effects_count(&self) -> u64 {
+        self.effects_count
+    }
+}
+
+impl ToBytes for StateRootMetadata {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut ret: Vec<u8> = Vec::with_capacity(STATE_ROOT_METADATA_SERIALIZED_LENGTH);
+        ret.append(&mut self.parent.to_bytes()?);
+        ret.append(&mut self.protocol_version.to_bytes()?);
+        ret.append(&mut self.block_time.to_bytes()?);
+        ret.append(&mut self.effects_count.to_bytes()?);
+        Ok(ret)
+    }
+}
+
+impl FromBytes for StateRootMetadata {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (parent, rem): (Blake2bHash, &[u8]) = FromBytes::from_bytes(bytes)?;
+        let (protocol_version, rem): (ProtocolVersion, &[u8]) = FromBytes::from_bytes(rem)?;
+        let (block_time, rem): (BlockTime, &[u8]) = FromBytes::from_bytes(rem)?;
+        let (effects_count, rem): (u64, &[u8]) = FromBytes::from_bytes(rem)?;
+        Ok((
+            StateRootMetadata {
+                parent,
+                protocol_version,
+                block_time,
+                effects_count,
+            },
+            rem,
+        ))
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod gens {
+    use proptest::{num, prelude::*};
+
+    use contract_ffi::{block_time::BlockTime, gens};
+    use engine_shared::newtypes::Blake2bHash;
+
+    use super::StateRootMetadata;
+
+    pub fn blake2b_hash_arb() -> impl Strategy<Value = Blake2bHash> {
+        gens::u8_slice_32().prop_map(Into::into)
+    }
+
+    prop_compose! {
+        pub fn state_root_metadata_arb()(
+            parent in blake2b_hash_arb(),
+            protocol_version in gens::protocol_version_arb(),
+            block_time in num::u64::ANY,
+            effects_count in num::u64::ANY,
+        ) -> StateRootMetadata {
+            StateRootMetadata {
+                parent,
+                protocol_version,
+                block_time: BlockTime::new(block_time),
+                effects_count,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::proptest;
+
+    use contract_ffi::bytesrepr;
+
+    use super::gens;
+
+    proptest! {
+        #[test]
+        fn should_serialize_and_deserialize_with_arbitrary_values(
+            state_root_metadata in gens::state_root_metadata_arb()
+        ) {
+            bytesrepr::test_serialization_roundtrip(&state_root_metadata);
+        }
+    }
+}
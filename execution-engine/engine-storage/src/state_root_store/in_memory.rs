@@ -0,0 +1,84 @@
+use std::cmp;
+
+use contract_ffi::bytesrepr;
+use engine_shared::newtypes::Blake2bHash;
+
+use crate::{
+    error::in_memory::Error,
+    state_root_metadata::StateRootMetadata,
+    state_root_store::{self, StateRootStore},
+    store::Store,
+    transaction_source::in_memory::InMemoryEnvironment,
+};
+
+/// An in-memory state root store
+pub struct InMemoryStateRootStore {
+    maybe_name: Option<String>,
+}
+
+impl InMemoryStateRootStore {
+    pub fn new(_env: &InMemoryEnvironment, maybe_name: Option<&str>) -> Self {
+        let name = maybe_name
+            .map(|name| format!("{}-{}", state_root_store::NAME, name))
+            .unwrap_or_else(|| String::from(state_root_store::NAME));
+        InMemoryStateRootStore {
+            maybe_name: Some(name),
+        }
+    }
+
+    /// Returns all the state roots committed to `env` with their metadata, in no particular
+    /// order.
+    pub fn get_all(
+        &self,
+        env: &InMemoryEnvironment,
+    ) -> Result<Vec<(Blake2bHash, StateRootMetadata)>, Error> {
+        let data = match env.data(self.maybe_name.as_ref().map(String::as_str))? {
+            Some(data) => data,
+            None => return Ok(Vec::new()),
+        };
+        let mut ret = Vec::with_capacity(data.len());
+        for (key_bytes, value_bytes) in data {
+            let state_root = bytesrepr::deserialize(key_bytes)?;
+            let state_root_metadata = bytesrepr::deserialize(value_bytes)?;
+            ret.push((state_root, state_root_metadata));
+        }
+        Ok(ret)
+    }
+
+    /// Returns up to `limit` records in ascending order of state root, skipping those whose
+    /// serialized form is less than `start`.
+    pub fn get_range(
+        &self,
+        env: &InMemoryEnvironment,
+        start: &[u8],
+        limit: usize,
+    ) -> Result<Vec<(Blake2bHash, StateRootMetadata)>, Error> {
+        let data = match env.data(self.maybe_name.as_ref().map(String::as_str))? {
+            Some(data) => data,
+            None => return Ok(Vec::new()),
+        };
+        let mut entries: Vec<(Vec<u8>, Vec<u8>)> = data
+            .into_iter()
+            .filter(|(key_bytes, _)| key_bytes.as_slice() >= start)
+            .collect();
+        entries.sort();
+        let mut ret = Vec::with_capacity(cmp::min(entries.len(), limit));
+        for (key_bytes, value_bytes) in entries.into_iter().take(limit) {
+            let state_root = bytesrepr::deserialize(key_bytes)?;
+            let state_root_metadata = bytesrepr::deserialize(value_bytes)?;
+            ret.push((state_root, state_root_metadata));
+        }
+        Ok(ret)
+    }
+}
+
+impl Store<Blake2bHash, StateRootMetadata> for InMemoryStateRootStore {
+    type Error = Error;
+    type Handle = Option<String>;
+
+    fn handle(&self) -> Self::Handle {
+        self.maybe_name.to_owned()
+    }
+}
+
+impl StateRootStore for InMemoryStateRootStore {}
@@ -0,0 +1,108 @@
+use lmdb::{Cursor, Database, DatabaseFlags, RwTransaction};
+
+use contract_ffi::bytesrepr::{self, ToBytes};
+use engine_shared::newtypes::Blake2bHash;
+
+use crate::{
+    error,
+    state_root_metadata::StateRootMetadata,
+    state_root_store::{self, StateRootStore},
+    store::Store,
+    transaction_source::lmdb::LmdbEnvironment,
+};
+
+/// An LMDB-backed state root store.
+///
+/// Wraps [`lmdb::Database`].
+#[derive(Debug, Clone)]
+pub struct LmdbStateRootStore {
+    db: Database,
+}
+
+impl LmdbStateRootStore {
+    pub fn new(
+        env: &LmdbEnvironment,
+        maybe_name: Option<&str>,
+        flags: DatabaseFlags,
+    ) -> Result<Self, error::Error> {
+        let name = Self::name(maybe_name);
+        let db = env.env().create_db(Some(&name), flags)?;
+        Ok(LmdbStateRootStore { db })
+    }
+
+    pub fn open(env: &LmdbEnvironment, maybe_name: Option<&str>) -> Result<Self, error::Error> {
+        let name = Self::name(maybe_name);
+        let db = env.env().open_db(Some(&name))?;
+        Ok(LmdbStateRootStore { db })
+    }
+
+    /// Returns all the state roots in the store with their metadata, in ascending order of state
+    /// root.
+    pub fn get_all<T: lmdb::Transaction>(
+        &self,
+        txn: &T,
+    ) -> Result<Vec<(Blake2bHash, StateRootMetadata)>, error::Error> {
+        let mut cursor = txn.open_ro_cursor(self.db)?;
+        let mut ret = Vec::new();
+        for (key_bytes, value_bytes) in cursor.iter_start() {
+            let state_root = bytesrepr::deserialize(key_bytes.to_vec())?;
+            let state_root_metadata = bytesrepr::deserialize(value_bytes.to_vec())?;
+            ret.push((state_root, state_root_metadata));
+        }
+        Ok(ret)
+    }
+
+    /// Returns up to `limit` records in ascending order of state root, skipping those whose
+    /// serialized form is less than `start`.
+    pub fn get_range<T: lmdb::Transaction>(
+        &self,
+        txn: &T,
+        start: &[u8],
+        limit: usize,
+    ) -> Result<Vec<(Blake2bHash, StateRootMetadata)>, error::Error> {
+        let mut cursor = txn.open_ro_cursor(self.db)?;
+        // LMDB does not position a cursor at an empty key
+        let iter = if start.is_empty() {
+            cursor.iter_start()
+        } else {
+            cursor.iter_from(start)
+        };
+        let mut ret = Vec::new();
+        for (key_bytes, value_bytes) in iter.take(limit) {
+            let state_root = bytesrepr::deserialize(key_bytes.to_vec())?;
+            let state_root_metadata = bytesrepr::deserialize(value_bytes.to_vec())?;
+            ret.push((state_root, state_root_metadata));
+        }
+        Ok(ret)
+    }
+
+    /// Deletes the record of `state_root`, if any.
+    pub fn delete(
+        &self,
+        txn: &mut RwTransaction,
+        state_root: &Blake2bHash,
+    ) -> Result<(), error::Error> {
+        match txn.del(self.db, &state_root.to_bytes()?, None) {
+            Ok(()) | Err(lmdb::Error::NotFound) => Ok(()),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    fn name(maybe_name: Option<&str>) -> String {
+        maybe_name
+            .map(|name| format!("{}-{}", state_root_store::NAME, name))
+            .unwrap_or_else(|| String::from(state_root_store::NAME))
+    }
+}
+
+impl Store<Blake2bHash, StateRootMetadata> for LmdbStateRootStore {
+    type Error = error::Error;
+
+    type Handle = Database;
+
+    fn handle(&self) -> Self::Handle {
+        self.db
+    }
+}
+
+impl StateRootStore for LmdbStateRootStore {}
@@ -0,0 +1,16 @@
+//! A store for persisting [`StateRootMetadata`] values at the state roots they describe.
+use engine_shared::newtypes::Blake2bHash;
+
+pub mod in_memory;
+pub mod lmdb;
+#[cfg(feature = "use_rocksdb")]
+pub mod rocksdb;
+#[cfg(test)]
+mod tests;
+
+use crate::{state_root_metadata::StateRootMetadata, store::Store};
+
+const NAME: &str = "STATE_ROOT_STORE";
+
+/// An entity which persists [`StateRootMetadata`] values at the state roots they describe.
+pub trait StateRootStore: Store<Blake2bHash, StateRootMetadata> {}
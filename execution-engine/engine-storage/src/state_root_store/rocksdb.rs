@@ -0,0 +1,98 @@
+use rocksdb::{Direction, IteratorMode};
+
+use contract_ffi::bytesrepr::{self, ToBytes};
+use engine_shared::newtypes::Blake2bHash;
+
+use crate::{
+    error,
+    state_root_metadata::StateRootMetadata,
+    state_root_store::{self, StateRootStore},
+    store::Store,
+    transaction_source::rocksdb::{
+        RocksDbEnvironment, RocksDbNamespace, RocksDbReadTransaction, RocksDbReadWriteTransaction,
+    },
+};
+
+/// A RocksDB-backed state root store.
+///
+/// Keeps its entries under its own [`RocksDbNamespace`] of the environment's database.
+#[derive(Debug, Clone)]
+pub struct RocksDbStateRootStore {
+    namespace: RocksDbNamespace,
+}
+
+impl RocksDbStateRootStore {
+    pub fn new(_env: &RocksDbEnvironment, maybe_name: Option<&str>) -> Self {
+        let name = maybe_name
+            .map(|name| format!("{}-{}", state_root_store::NAME, name))
+            .unwrap_or_else(|| String::from(state_root_store::NAME));
+        RocksDbStateRootStore {
+            namespace: RocksDbNamespace::new(&name),
+        }
+    }
+
+    /// Returns all the state roots in the store with their metadata, in ascending order of state
+    /// root.
+    pub fn get_all(
+        &self,
+        txn: &RocksDbReadTransaction,
+    ) -> Result<Vec<(Blake2bHash, StateRootMetadata)>, error::Error> {
+        let prefix = self.namespace.prefix();
+        let mode = IteratorMode::From(prefix, Direction::Forward);
+        let mut ret = Vec::new();
+        for (key_bytes, value_bytes) in txn.snapshot().iterator(mode) {
+            if !key_bytes.starts_with(prefix) {
+                break;
+            }
+            let state_root = bytesrepr::deserialize(key_bytes[prefix.len()..].to_vec())?;
+            let state_root_metadata = bytesrepr::deserialize(value_bytes.to_vec())?;
+            ret.push((state_root, state_root_metadata));
+        }
+        Ok(ret)
+    }
+
+    /// Returns up to `limit` records in ascending order of state root, skipping those whose
+    /// serialized form is less than `start`.
+    pub fn get_range(
+        &self,
+        txn: &RocksDbReadTransaction,
+        start: &[u8],
+        limit: usize,
+    ) -> Result<Vec<(Blake2bHash, StateRootMetadata)>, error::Error> {
+        let prefix = self.namespace.prefix();
+        let from = self.namespace.key(start);
+        let mode = IteratorMode::From(&from, Direction::Forward);
+        let mut ret = Vec::new();
+        for (key_bytes, value_bytes) in txn.snapshot().iterator(mode).take(limit) {
+            if !key_bytes.starts_with(prefix) {
+                break;
+            }
+            let state_root = bytesrepr::deserialize(key_bytes[prefix.len()..].to_vec())?;
+            let state_root_metadata = bytesrepr::deserialize(value_bytes.to_vec())?;
+            ret.push((state_root, state_root_metadata));
+        }
+        Ok(ret)
+    }
+
+    /// Deletes the record of `state_root`, if any, when `txn` is committed.
+    pub fn delete(
+        &self,
+        txn: &mut RocksDbReadWriteTransaction,
+        state_root: &Blake2bHash,
+    ) -> Result<(), error::Error> {
+        txn.delete(self.namespace.clone(), &state_root.to_bytes()?);
+        Ok(())
+    }
+}
+
+impl Store<Blake2bHash, StateRootMetadata> for RocksDbStateRootStore {
+    type Error = error::Error;
+
+    type Handle = RocksDbNamespace;
+
+    fn handle(&self) -> Self::Handle {
+        self.namespace.clone()
+    }
+}
+
+impl StateRootStore for RocksDbStateRootStore {}
@@ -0,0 +1,83 @@
+use std::{collections::BTreeMap, ops::RangeInclusive};
+
+use lmdb::DatabaseFlags;
+use proptest::{collection, prelude::proptest};
+use tempfile;
+
+use engine_shared::newtypes::Blake2bHash;
+
+use crate::{
+    state_root_metadata::{gens, StateRootMetadata},
+    state_root_store::{in_memory::InMemoryStateRootStore, lmdb::LmdbStateRootStore},
+    store::tests as store_tests,
+    transaction_source::{in_memory::InMemoryEnvironment, lmdb::LmdbEnvironment},
+    TEST_MAP_SIZE,
+};
+
+const DEFAULT_MIN_LENGTH: usize = 1;
+const DEFAULT_MAX_LENGTH: usize = 16;
+
+fn get_range() -> RangeInclusive<usize> {
+    let start = option_env!("CL_STATE_ROOT_STORE_TEST_MAP_MIN_LENGTH")
+        .and_then(|s| str::parse::<usize>(s).ok())
+        .unwrap_or(DEFAULT_MIN_LENGTH);
+    let end = option_env!("CL_STATE_ROOT_STORE_TEST_MAP_MAX_LENGTH")
+        .and_then(|s| str::parse::<usize>(s).ok())
+        .unwrap_or(DEFAULT_MAX_LENGTH);
+    RangeInclusive::new(start, end)
+}
+
+fn in_memory_roundtrip_succeeds(inputs: BTreeMap<Blake2bHash, StateRootMetadata>) -> bool {
+    let env = InMemoryEnvironment::new();
+    let store = InMemoryStateRootStore::new(&env, None);
+
+    store_tests::roundtrip_succeeds(&env, &store, inputs).unwrap()
+}
+
+fn lmdb_roundtrip_succeeds(inputs: BTreeMap<Blake2bHash, StateRootMetadata>) -> bool {
+    let tmp_dir = tempfile::tempdir().unwrap();
+    let env = LmdbEnvironment::new(&tmp_dir.path().to_path_buf(), *TEST_MAP_SIZE).unwrap();
+    let store = LmdbStateRootStore::new(&env, None, DatabaseFlags::empty()).unwrap();
+
+    let ret = store_tests::roundtrip_succeeds(&env, &store, inputs).unwrap();
+    tmp_dir.close().unwrap();
+    ret
+}
+
+#[cfg(feature = "use_rocksdb")]
+fn rocksdb_roundtrip_succeeds(inputs: BTreeMap<Blake2bHash, StateRootMetadata>) -> bool {
+    use crate::{
+        state_root_store::rocksdb::RocksDbStateRootStore,
+        transaction_source::rocksdb::RocksDbEnvironment,
+    };
+
+    let tmp_dir = tempfile::tempdir().unwrap();
+    let env = RocksDbEnvironment::new(&tmp_dir.path().to_path_buf()).unwrap();
+    let store = RocksDbStateRootStore::new(&env, None);
+
+    store_tests::roundtrip_succeeds(&env, &store, inputs).unwrap()
+}
+
+proptest! {
+    #[test]
+    fn prop_in_memory_roundtrip_succeeds(
+        m in collection::btree_map(gens::blake2b_hash_arb(), gens::state_root_metadata_arb(), get_range())
+    ) {
+        assert!(in_memory_roundtrip_succeeds(m))
+    }
+
+    #[test]
+    fn prop_lmdb_roundtrip_succeeds(
+        m in collection::btree_map(gens::blake2b_hash_arb(), gens::state_root_metadata_arb(), get_range())
+    ) {
+        assert!(lmdb_roundtrip_succeeds(m))
+    }
+
+    #[cfg(feature = "use_rocksdb")]
+    #[test]
+    fn prop_rocksdb_roundtrip_succeeds(
+        m in collection::btree_map(gens::blake2b_hash_arb(), gens::state_root_metadata_arb(), get_range())
+    ) {
+        assert!(rocksdb_roundtrip_succeeds(m))
+    }
+}
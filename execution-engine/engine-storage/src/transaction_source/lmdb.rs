@@ -1,6 +1,8 @@
 use std::path::PathBuf;
 
-use lmdb::{self, Database, Environment, RoTransaction, RwTransaction, WriteFlags};
+use lmdb::{
+    self, Database, Environment, EnvironmentFlags, RoTransaction, RwTransaction, WriteFlags,
+};
 
 use crate::{
     error,
@@ -8,6 +10,13 @@ use crate::{
     MAX_DBS,
 };
 
+/// Returns the id of `txn`: the id of the last committed transaction for a read-only transaction,
+/// or the id the transaction will be committed with for a read-write one.
+pub fn txn_id<T: lmdb::Transaction>(txn: &T) -> u64 {
+    // Safe as `txn` holds a live transaction handle for as long as it is borrowed
+    unsafe { lmdb_sys::mdb_txn_id(txn.txn()) as u64 }
+}
+
 impl<'a> Transaction for RoTransaction<'a> {
     type Error = lmdb::Error;
 
@@ -26,6 +35,10 @@ impl<'a> Readable for RoTransaction<'a> {
             Err(e) => Err(e),
         }
     }
+
+    fn read_generation(&self) -> Option<u64> {
+        Some(txn_id(self))
+    }
 }
 
 impl<'a> Transaction for RwTransaction<'a> {
@@ -74,6 +87,19 @@ impl LmdbEnvironment {
         Ok(LmdbEnvironment { path, env })
     }
 
+    /// Opens an existing environment for reading only.
+    ///
+    /// The map size recorded in the environment is used, and no databases can be created, so the
+    /// stores must be opened rather than created.
+    pub fn open_read_only(path: &PathBuf) -> Result<Self, error::Error> {
+        let env = Environment::new()
+            .set_flags(EnvironmentFlags::READ_ONLY)
+            .set_max_dbs(MAX_DBS)
+            .open(path)?;
+        let path = path.to_owned();
+        Ok(LmdbEnvironment { path, env })
+    }
+
     pub fn path(&self) -> &PathBuf {
         &self.path
     }
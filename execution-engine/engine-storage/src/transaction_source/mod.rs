@@ -1,5 +1,7 @@
 pub mod in_memory;
 pub mod lmdb;
+#[cfg(feature = "use_rocksdb")]
+pub mod rocksdb;
 
 /// A transaction which can be committed or aborted.
 pub trait Transaction: Sized {
@@ -25,6 +27,36 @@ pub trait Transaction: Sized {
 pub trait Readable: Transaction {
     /// Returns the value from the corresponding key from a given [`Transaction::Handle`].
     fn read(&self, handle: Self::Handle, key: &[u8]) -> Result<Option<Vec<u8>>, Self::Error>;
+
+    /// Returns the generation of the committed snapshot read by the transaction, which grows with
+    /// every commit, or `None` if the transaction can write, i.e. it may read elements which have
+    /// not been committed, or if the source does not number its snapshots.
+    fn read_generation(&self) -> Option<u64> {
+        None
+    }
+}
+
+/// A shared reference to a transaction can be used to read from it, e.g. by an iterator which
+/// does not own the transaction.  Committing the reference does nothing; the owner of the
+/// transaction remains responsible for committing it.
+impl<'a, T: Readable> Transaction for &'a T {
+    type Error = T::Error;
+
+    type Handle = T::Handle;
+
+    fn commit(self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+impl<'a, T: Readable> Readable for &'a T {
+    fn read(&self, handle: Self::Handle, key: &[u8]) -> Result<Option<Vec<u8>>, Self::Error> {
+        (**self).read(handle, key)
+    }
+
+    fn read_generation(&self) -> Option<u64> {
+        (**self).read_generation()
+    }
 }
 
 /// A transaction with the capability to write to a given [`Handle`](Transaction::Handle).
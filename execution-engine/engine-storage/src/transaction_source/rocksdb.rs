@@ -0,0 +1,190 @@
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::{Mutex, MutexGuard},
+};
+
+use rocksdb::{Options, Snapshot, WriteBatch, DB};
+
+use crate::{
+    error,
+    transaction_source::{Readable, Transaction, TransactionSource, Writable},
+};
+
+/// A marker for use in a mutex which represents the capability to perform a
+/// write transaction.
+struct WriteCapability;
+
+/// The prefix under which a store keeps its entries in the shared RocksDB key space.
+///
+/// The store's name is followed by a zero byte, so that no namespace is a prefix of another.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RocksDbNamespace(Vec<u8>);
+
+impl RocksDbNamespace {
+    pub fn new(name: &str) -> Self {
+        let mut prefix = name.as_bytes().to_vec();
+        prefix.push(0);
+        RocksDbNamespace(prefix)
+    }
+
+    /// Returns the prefix of all the keys in this namespace.
+    pub fn prefix(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Returns `key` qualified by this namespace.
+    pub fn key(&self, key: &[u8]) -> Vec<u8> {
+        let mut ret = Vec::with_capacity(self.0.len() + key.len());
+        ret.extend_from_slice(&self.0);
+        ret.extend_from_slice(key);
+        ret
+    }
+}
+
+/// A read transaction for the RocksDB-backed stores.
+///
+/// Reads from a snapshot of the database taken when the transaction was created.
+pub struct RocksDbReadTransaction<'a> {
+    snapshot: Snapshot<'a>,
+}
+
+impl<'a> RocksDbReadTransaction<'a> {
+    pub fn snapshot(&self) -> &Snapshot<'a> {
+        &self.snapshot
+    }
+}
+
+impl<'a> Transaction for RocksDbReadTransaction<'a> {
+    type Error = error::Error;
+
+    type Handle = RocksDbNamespace;
+
+    fn commit(self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+impl<'a> Readable for RocksDbReadTransaction<'a> {
+    fn read(&self, handle: Self::Handle, key: &[u8]) -> Result<Option<Vec<u8>>, Self::Error> {
+        let maybe_value = self.snapshot.get(handle.key(key))?;
+        Ok(maybe_value.map(|value| value.to_vec()))
+    }
+}
+
+/// A read-write transaction for the RocksDB-backed stores.
+///
+/// Writes and deletions are buffered until the transaction is committed, when they are applied as
+/// a single atomic batch.  Only one read-write transaction can be open at a time, so reads of keys
+/// which have not been written by the transaction see the latest committed state.
+pub struct RocksDbReadWriteTransaction<'a> {
+    db: &'a DB,
+    /// The pending value of each key written by the transaction, or `None` if it was deleted.
+    pending: HashMap<Vec<u8>, Option<Vec<u8>>>,
+    _write_lock: MutexGuard<'a, WriteCapability>,
+}
+
+impl<'a> RocksDbReadWriteTransaction<'a> {
+    /// Returns the database as of the latest commit, without the writes pending in this
+    /// transaction.
+    pub fn db(&self) -> &'a DB {
+        self.db
+    }
+
+    /// Deletes the value at `key` in `handle`'s namespace when the transaction is committed.
+    pub fn delete(&mut self, handle: RocksDbNamespace, key: &[u8]) {
+        self.pending.insert(handle.key(key), None);
+    }
+}
+
+impl<'a> Transaction for RocksDbReadWriteTransaction<'a> {
+    type Error = error::Error;
+
+    type Handle = RocksDbNamespace;
+
+    fn commit(self) -> Result<(), Self::Error> {
+        let mut batch = WriteBatch::default();
+        for (key, maybe_value) in self.pending {
+            match maybe_value {
+                Some(value) => batch.put(key, value)?,
+                None => batch.delete(key)?,
+            }
+        }
+        self.db.write(batch).map_err(Into::into)
+    }
+}
+
+impl<'a> Readable for RocksDbReadWriteTransaction<'a> {
+    fn read(&self, handle: Self::Handle, key: &[u8]) -> Result<Option<Vec<u8>>, Self::Error> {
+        let key = handle.key(key);
+        if let Some(maybe_value) = self.pending.get(&key) {
+            return Ok(maybe_value.to_owned());
+        }
+        let maybe_value = self.db.get(key)?;
+        Ok(maybe_value.map(|value| value.to_vec()))
+    }
+}
+
+impl<'a> Writable for RocksDbReadWriteTransaction<'a> {
+    fn write(&mut self, handle: Self::Handle, key: &[u8], value: &[u8]) -> Result<(), Self::Error> {
+        self.pending.insert(handle.key(key), Some(value.to_vec()));
+        Ok(())
+    }
+}
+
+/// The environment for the RocksDB-backed stores.
+///
+/// Wraps [`rocksdb::DB`].  Unlike LMDB, the database grows as needed, so there is no map size to
+/// configure up front.
+pub struct RocksDbEnvironment {
+    path: PathBuf,
+    db: DB,
+    write_mutex: Mutex<WriteCapability>,
+}
+
+impl RocksDbEnvironment {
+    pub fn new(path: &PathBuf) -> Result<Self, error::Error> {
+        let mut options = Options::default();
+        options.create_if_missing(true);
+        let db = DB::open(&options, path)?;
+        let path = path.to_owned();
+        let write_mutex = Mutex::new(WriteCapability);
+        Ok(RocksDbEnvironment {
+            path,
+            db,
+            write_mutex,
+        })
+    }
+
+    pub fn path(&self) -> &PathBuf {
+        &self.path
+    }
+
+    pub fn db(&self) -> &DB {
+        &self.db
+    }
+}
+
+impl<'a> TransactionSource<'a> for RocksDbEnvironment {
+    type Error = error::Error;
+
+    type Handle = RocksDbNamespace;
+
+    type ReadTransaction = RocksDbReadTransaction<'a>;
+
+    type ReadWriteTransaction = RocksDbReadWriteTransaction<'a>;
+
+    fn create_read_txn(&'a self) -> Result<RocksDbReadTransaction<'a>, Self::Error> {
+        let snapshot = self.db.snapshot();
+        Ok(RocksDbReadTransaction { snapshot })
+    }
+
+    fn create_read_write_txn(&'a self) -> Result<RocksDbReadWriteTransaction<'a>, Self::Error> {
+        let _write_lock = self.write_mutex.lock()?;
+        Ok(RocksDbReadWriteTransaction {
+            db: &self.db,
+            pending: HashMap::new(),
+            _write_lock,
+        })
+    }
+}
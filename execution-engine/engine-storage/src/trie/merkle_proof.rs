@@ -0,0 +1,141 @@
+//! Merkle proofs of inclusion and exclusion of keys in a Merkle Trie
+
+use failure::Fail;
+
+use contract_ffi::bytesrepr::{self, ToBytes};
+use engine_shared::newtypes::Blake2bHash;
+
+use crate::trie::{Pointer, Trie};
+
+#[derive(Debug, Fail, PartialEq, Eq)]
+pub enum TrieMerkleProofError {
+    #[fail(display = "Proof contains no steps")]
+    EmptyProof,
+
+    #[fail(display = "Hash of step {} does not match the expected hash", _0)]
+    HashMismatch(usize),
+
+    #[fail(display = "Proof ends before reaching the key")]
+    IncompleteProof,
+
+    #[fail(display = "Proof contains steps past the end of the path to the key")]
+    TrailingSteps,
+
+    #[fail(display = "{}", _0)]
+    BytesRepr(#[fail(cause)] bytesrepr::Error),
+}
+
+impl From<bytesrepr::Error> for TrieMerkleProofError {
+    fn from(error: bytesrepr::Error) -> Self {
+        TrieMerkleProofError::BytesRepr(error)
+    }
+}
+
+/// A proof that a given key is either present or absent in a trie with a given root hash.
+///
+/// The proof consists of the trie elements visited while looking up the key, ordered from the
+/// root to the deepest element found on the key's path.  If that element is a leaf holding the
+/// key, the proof is a proof of inclusion; otherwise it is a proof of exclusion.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrieMerkleProof<K, V> {
+    key: K,
+    steps: Vec<Trie<K, V>>,
+}
+
+impl<K, V> TrieMerkleProof<K, V> {
+    pub fn new(key: K, steps: Vec<Trie<K, V>>) -> Self {
+        TrieMerkleProof { key, steps }
+    }
+
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    pub fn steps(&self) -> &[Trie<K, V>] {
+        &self.steps
+    }
+
+    pub fn destructure(self) -> (K, Vec<Trie<K, V>>) {
+        (self.key, self.steps)
+    }
+}
+
+impl<K: Eq, V> TrieMerkleProof<K, V> {
+    /// Returns the value proven to be stored under the key, or `None` if this is a proof of
+    /// exclusion.  Note that this does not verify the proof.
+    pub fn value(&self) -> Option<&V> {
+        match self.steps.last() {
+            Some(Trie::Leaf { key, value }) if *key == self.key => Some(value),
+            _ => None,
+        }
+    }
+}
+
+impl<K, V> TrieMerkleProof<K, V>
+where
+    K: ToBytes + Eq,
+    V: ToBytes,
+{
+    /// Checks this proof against `root_hash`.
+    ///
+    /// Returns the value stored under the key if the proof is a valid proof of inclusion, or
+    /// `None` if it is a valid proof of exclusion.
+    pub fn verify(&self, root_hash: &Blake2bHash) -> Result<Option<&V>, TrieMerkleProofError> {
+        let path: Vec<u8> = self.key.to_bytes()?;
+        let last_index = match self.steps.len() {
+            0 => return Err(TrieMerkleProofError::EmptyProof),
+            len => len - 1,
+        };
+
+        let mut expected_hash = *root_hash;
+        let mut depth: usize = 0;
+
+        for (index, step) in self.steps.iter().enumerate() {
+            let step_hash = {
+                let step_bytes = step.to_bytes()?;
+                Blake2bHash::new(&step_bytes)
+            };
+            if step_hash != expected_hash {
+                return Err(TrieMerkleProofError::HashMismatch(index));
+            }
+
+            let maybe_pointer: Option<Pointer> = match step {
+                Trie::Leaf { key, value } => {
+                    if index != last_index {
+                        return Err(TrieMerkleProofError::TrailingSteps);
+                    }
+                    let maybe_value = if *key == self.key { Some(value) } else { None };
+                    return Ok(maybe_value);
+                }
+                Trie::Node { pointer_block } => {
+                    let path_index: usize = match path.get(depth) {
+                        Some(byte) => (*byte).into(),
+                        None => return Err(TrieMerkleProofError::TrailingSteps),
+                    };
+                    depth += 1;
+                    pointer_block[path_index]
+                }
+                Trie::Extension { affix, pointer } => {
+                    let sub_path = path.get(depth..depth + affix.len());
+                    if sub_path == Some(affix.as_slice()) {
+                        depth += affix.len();
+                        Some(*pointer)
+                    } else {
+                        None
+                    }
+                }
+            };
+
+            match maybe_pointer {
+                Some(_) if index == last_index => {
+                    return Err(TrieMerkleProofError::IncompleteProof)
+                }
+                Some(pointer) => expected_hash = *pointer.hash(),
+                None if index == last_index => return Ok(None),
+                None => return Err(TrieMerkleProofError::TrailingSteps),
+            }
+        }
+
+        unreachable!("the last step always returns")
+    }
+}
@@ -7,6 +7,7 @@ use engine_shared::newtypes::Blake2bHash;
 
 #[cfg(test)]
 pub mod gens;
+pub mod merkle_proof;
 
 #[cfg(test)]
 mod tests;
@@ -237,6 +238,19 @@ impl<K, V> Trie<K, V> {
             _ => None,
         }
     }
+
+    /// Returns the hashes of the trie elements this element points to.
+    pub fn child_hashes(&self) -> Vec<Blake2bHash> {
+        match self {
+            Trie::Leaf { .. } => Vec::new(),
+            Trie::Node { pointer_block } => pointer_block[..]
+                .iter()
+                .flatten()
+                .map(|pointer| *pointer.hash())
+                .collect(),
+            Trie::Extension { pointer, .. } => vec![*pointer.hash()],
+        }
+    }
 }
 
 impl<K, V> ToBytes for Trie<K, V>
@@ -0,0 +1,355 @@
+//! A bounded cache of decoded trie elements, shared by all the transactions of a trie store.
+//!
+//! Trie elements are stored at their hashes and never modified, so a cached element stays valid
+//! for as long as it remains in the store.  Deleting elements from the store, e.g. by pruning,
+//! must invalidate the cache with the generation of the deleting transaction, so that readers of
+//! older snapshots, which may still see the deleted elements, cannot cache them again.
+use std::{
+    any::Any,
+    fmt, mem,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+};
+
+use linked_hash_map::LinkedHashMap;
+
+use engine_shared::newtypes::{Blake2bHash, BLAKE2B_DIGEST_LENGTH};
+
+use crate::{
+    error,
+    trie::{PointerBlock, Trie},
+};
+
+/// The number of independently locked shards a [`TrieCache`] is split into by default.
+pub const DEFAULT_SHARD_COUNT: usize = 16;
+
+/// Returns the approximate number of bytes taken in memory by `trie`, given the length of its
+/// serialized form.
+pub fn trie_byte_size<K, V>(trie: &Trie<K, V>, serialized_len: usize) -> usize {
+    mem::size_of::<Trie<K, V>>()
+        + match trie {
+            Trie::Node { .. } => mem::size_of::<PointerBlock>(),
+            Trie::Extension { affix, .. } => affix.len(),
+            Trie::Leaf { .. } => serialized_len,
+        }
+}
+
+/// Hit and miss counters of a [`TrieCache`], along with its current size.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct TrieCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub entries: usize,
+    pub size: usize,
+}
+
+/// Keeps recently read trie elements, evicting the least-recently-used ones once the total size
+/// of the cached elements goes above `max_cache_size` bytes.
+///
+/// The elements are spread over several shards by their hashes, each with its own lock and an
+/// equal share of `max_cache_size`, so that concurrent readers rarely wait on each other.
+///
+/// Elements are kept type-erased, as a trie store is not tied to the key and value types of the
+/// tries it holds.  Looking an element up as a different type than it was inserted with counts as
+/// a miss.
+pub struct TrieCache {
+    max_cache_size: usize,
+    shards: Vec<Mutex<Shard>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+struct Shard {
+    max_size: usize,
+    current_size: usize,
+    min_generation: u64,
+    entries: LinkedHashMap<Blake2bHash, (Box<dyn Any + Send + Sync>, usize)>,
+}
+
+impl Shard {
+    fn clear(&mut self) {
+        self.entries.clear();
+        self.current_size = 0;
+    }
+}
+
+impl TrieCache {
+    pub fn new(max_cache_size: usize) -> Self {
+        Self::with_shards(max_cache_size, DEFAULT_SHARD_COUNT)
+    }
+
+    /// Creates a cache split into `shard_count` shards, each holding up to
+    /// `max_cache_size / shard_count` bytes.
+    pub fn with_shards(max_cache_size: usize, shard_count: usize) -> Self {
+        let shard_count = shard_count.max(1);
+        let shards = (0..shard_count)
+            .map(|_| {
+                Mutex::new(Shard {
+                    max_size: max_cache_size / shard_count,
+                    current_size: 0,
+                    min_generation: 0,
+                    entries: LinkedHashMap::new(),
+                })
+            })
+            .collect();
+        TrieCache {
+            max_cache_size,
+            shards,
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    fn shard(&self, hash: &Blake2bHash) -> &Mutex<Shard> {
+        let bytes: [u8; BLAKE2B_DIGEST_LENGTH] = (*hash).into();
+        &self.shards[bytes[0] as usize % self.shards.len()]
+    }
+
+    /// Returns a copy of the element cached at `hash`, marking it as the most recently used.
+    pub fn get<K, V>(&self, hash: &Blake2bHash) -> Result<Option<Trie<K, V>>, error::Error>
+    where
+        K: Clone + 'static,
+        V: Clone + 'static,
+    {
+        let maybe_trie = self
+            .shard(hash)
+            .lock()?
+            .entries
+            .get_refresh(hash)
+            .and_then(|(trie, _)| trie.downcast_ref::<Trie<K, V>>())
+            .cloned();
+        if maybe_trie.is_some() {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.misses.fetch_add(1, Ordering::Relaxed);
+        }
+        Ok(maybe_trie)
+    }
+
+    /// Caches `trie` at `hash`, counting it as `size` bytes towards `max_cache_size`.
+    ///
+    /// `generation` is the generation of the snapshot `trie` was read from.  Elements read from a
+    /// snapshot older than the last [`TrieCache::invalidate`] are not cached, nor are elements
+    /// larger than their shard.
+    pub fn insert<K, V>(
+        &self,
+        generation: u64,
+        hash: Blake2bHash,
+        trie: Trie<K, V>,
+        size: usize,
+    ) -> Result<(), error::Error>
+    where
+        K: Send + Sync + 'static,
+        V: Send + Sync + 'static,
+    {
+        let mut shard = self.shard(&hash).lock()?;
+        if generation < shard.min_generation || size > shard.max_size {
+            return Ok(());
+        }
+        if let Some((_, old_size)) = shard.entries.insert(hash, (Box::new(trie), size)) {
+            shard.current_size -= old_size;
+        }
+        shard.current_size += size;
+        while shard.current_size > shard.max_size {
+            match shard.entries.pop_front() {
+                Some((_, (_, size))) => shard.current_size -= size,
+                None => break,
+            }
+        }
+        Ok(())
+    }
+
+    /// Removes all the cached elements, and stops caching elements read from snapshots older than
+    /// `generation`.  The hit and miss counters are kept.
+    ///
+    /// Must be called with the generation of a transaction deleting elements, before it commits.
+    pub fn invalidate(&self, generation: u64) -> Result<(), error::Error> {
+        for shard in &self.shards {
+            let mut shard = shard.lock()?;
+            shard.min_generation = shard.min_generation.max(generation);
+            shard.clear();
+        }
+        Ok(())
+    }
+
+    /// Removes all the cached elements.  The hit and miss counters are kept.
+    pub fn clear(&self) -> Result<(), error::Error> {
+        for shard in &self.shards {
+            shard.lock()?.clear();
+        }
+        Ok(())
+    }
+
+    pub fn stats(&self) -> Result<TrieCacheStats, error::Error> {
+        let mut stats = TrieCacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            ..Default::default()
+        };
+        for shard in &self.shards {
+            let shard = shard.lock()?;
+            stats.entries += shard.entries.len();
+            stats.size += shard.current_size;
+        }
+        Ok(stats)
+    }
+}
+
+impl fmt::Debug for TrieCache {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("TrieCache")
+            .field("max_cache_size", &self.max_cache_size)
+            .field("shards", &self.shards.len())
+            .field("hits", &self.hits)
+            .field("misses", &self.misses)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use engine_shared::newtypes::Blake2bHash;
+
+    use super::TrieCache;
+    use crate::trie::Trie;
+
+    type TestTrie = Trie<Vec<u8>, Vec<u8>>;
+
+    fn leaf(n: u8) -> (Blake2bHash, TestTrie) {
+        let trie = Trie::Leaf {
+            key: vec![n],
+            value: vec![n; 4],
+        };
+        (Blake2bHash::new(&[n]), trie)
+    }
+
+    fn get(cache: &TrieCache, hash: &Blake2bHash) -> Option<TestTrie> {
+        cache.get(hash).unwrap()
+    }
+
+    #[test]
+    fn should_count_hits_and_misses() {
+        let cache = TrieCache::new(1600);
+        let (hash, trie) = leaf(0);
+
+        assert_eq!(get(&cache, &hash), None);
+        cache.insert(0, hash, trie.clone(), 10).unwrap();
+        assert_eq!(get(&cache, &hash), Some(trie));
+
+        let stats = cache.stats().unwrap();
+        assert_eq!(stats.hits, 1);
+        assert_eq!(stats.misses, 1);
+        assert_eq!(stats.entries, 1);
+        assert_eq!(stats.size, 10);
+    }
+
+    #[test]
+    fn should_miss_on_other_trie_type() {
+        let cache = TrieCache::new(1600);
+        let (hash, trie) = leaf(0);
+        cache.insert(0, hash, trie, 10).unwrap();
+
+        assert_eq!(cache.get::<Vec<u8>, String>(&hash).unwrap(), None);
+        assert_eq!(cache.stats().unwrap().misses, 1);
+    }
+
+    #[test]
+    fn should_evict_least_recently_used() {
+        let cache = TrieCache::with_shards(30, 1);
+        let (hash_0, trie_0) = leaf(0);
+        let (hash_1, trie_1) = leaf(1);
+        let (hash_2, trie_2) = leaf(2);
+        let (hash_3, trie_3) = leaf(3);
+
+        cache.insert(0, hash_0, trie_0.clone(), 10).unwrap();
+        cache.insert(0, hash_1, trie_1, 10).unwrap();
+        cache.insert(0, hash_2, trie_2.clone(), 10).unwrap();
+        // Refresh the first element, so the second is the least recently used
+        assert!(get(&cache, &hash_0).is_some());
+        cache.insert(0, hash_3, trie_3.clone(), 10).unwrap();
+
+        assert_eq!(cache.stats().unwrap().size, 30);
+        assert_eq!(get(&cache, &hash_1), None);
+        assert_eq!(get(&cache, &hash_0), Some(trie_0));
+        assert_eq!(get(&cache, &hash_2), Some(trie_2));
+        assert_eq!(get(&cache, &hash_3), Some(trie_3));
+    }
+
+    #[test]
+    fn should_not_cache_element_larger_than_shard() {
+        let cache = TrieCache::with_shards(60, 2);
+        let (hash_0, trie_0) = leaf(0);
+        let (hash_1, trie_1) = leaf(1);
+
+        cache.insert(0, hash_0, trie_0.clone(), 10).unwrap();
+        cache.insert(0, hash_1, trie_1, 31).unwrap();
+
+        assert_eq!(cache.stats().unwrap().entries, 1);
+        assert_eq!(get(&cache, &hash_0), Some(trie_0));
+    }
+
+    #[test]
+    fn should_bound_each_shard_separately() {
+        let cache = TrieCache::with_shards(20, 2);
+        let first_byte = |hash: &Blake2bHash| {
+            let bytes: [u8; 32] = (*hash).into();
+            bytes[0]
+        };
+        let (even, odd): (Vec<_>, Vec<_>) = (0..=u8::max_value())
+            .map(leaf)
+            .partition(|(hash, _)| first_byte(hash) % 2 == 0);
+
+        // Both elements fall in the same shard, which only has room for one of them
+        cache.insert(0, even[0].0, even[0].1.clone(), 10).unwrap();
+        cache.insert(0, even[1].0, even[1].1.clone(), 10).unwrap();
+        assert_eq!(cache.stats().unwrap().entries, 1);
+        assert_eq!(get(&cache, &even[0].0), None);
+
+        cache.insert(0, odd[0].0, odd[0].1.clone(), 10).unwrap();
+        let stats = cache.stats().unwrap();
+        assert_eq!(stats.entries, 2);
+        assert_eq!(stats.size, 20);
+        assert_eq!(get(&cache, &even[1].0), Some(even[1].1.clone()));
+        assert_eq!(get(&cache, &odd[0].0), Some(odd[0].1.clone()));
+    }
+
+    #[test]
+    fn should_clear() {
+        let cache = TrieCache::new(1600);
+        let (hash, trie) = leaf(0);
+        cache.insert(0, hash, trie, 10).unwrap();
+        cache.clear().unwrap();
+
+        let stats = cache.stats().unwrap();
+        assert_eq!(stats.entries, 0);
+        assert_eq!(stats.size, 0);
+        assert_eq!(get(&cache, &hash), None);
+    }
+
+    #[test]
+    fn should_not_cache_elements_read_before_invalidation() {
+        let cache = TrieCache::new(1600);
+        let (hash_0, trie_0) = leaf(0);
+        let (hash_1, trie_1) = leaf(1);
+        cache.insert(4, hash_0, trie_0.clone(), 10).unwrap();
+
+        cache.invalidate(5).unwrap();
+        assert_eq!(get(&cache, &hash_0), None);
+
+        // A reader of an older snapshot may still see elements deleted by generation 5
+        cache.insert(4, hash_0, trie_0.clone(), 10).unwrap();
+        assert_eq!(get(&cache, &hash_0), None);
+
+        cache.insert(5, hash_0, trie_0.clone(), 10).unwrap();
+        cache.insert(6, hash_1, trie_1.clone(), 10).unwrap();
+        assert_eq!(get(&cache, &hash_0), Some(trie_0));
+        assert_eq!(get(&cache, &hash_1), Some(trie_1));
+
+        // Invalidation never moves back to an older generation
+        cache.invalidate(3).unwrap();
+        cache.insert(4, hash_0, leaf(0).1, 10).unwrap();
+        assert_eq!(cache.stats().unwrap().entries, 0);
+    }
+}
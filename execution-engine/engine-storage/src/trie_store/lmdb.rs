@@ -104,24 +104,45 @@
 //! tmp_dir.close().unwrap();
 //! ```
 
-use lmdb::{Database, DatabaseFlags};
+use std::{
+    collections::HashSet,
+    sync::{Arc, Mutex},
+};
+
+use lmdb::{Cursor, Database, DatabaseFlags, RwTransaction};
 
+use contract_ffi::bytesrepr::{self, FromBytes, ToBytes};
 use engine_shared::newtypes::Blake2bHash;
 
 use crate::{
     error,
     store::Store,
-    transaction_source::lmdb::LmdbEnvironment,
+    transaction_source::{
+        lmdb::{self as lmdb_source, LmdbEnvironment},
+        Readable, Writable,
+    },
     trie::Trie,
-    trie_store::{self, TrieStore},
+    trie_store::{
+        self,
+        cache::{self, TrieCache, TrieCacheStats},
+        TrieStore,
+    },
 };
 
 /// An LMDB-backed trie store.
 ///
-/// Wraps [`lmdb::Database`].
+/// Wraps [`lmdb::Database`], optionally along with a [`TrieCache`] of the decoded trie elements
+/// read by read-only transactions.  Clones of the store share the same cache, and the hashes of
+/// the elements written while a prune is in progress.
+///
+/// Deleting elements invalidates the cache with the id of the deleting transaction, so that the
+/// read-only transactions begun before the deletion is committed cannot cache the deleted elements
+/// again.
 #[derive(Debug, Clone)]
 pub struct LmdbTrieStore {
     db: Database,
+    maybe_cache: Option<Arc<TrieCache>>,
+    written_while_pruning: Arc<Mutex<Option<HashSet<Blake2bHash>>>>,
 }
 
 impl LmdbTrieStore {
@@ -132,13 +153,159 @@ impl LmdbTrieStore {
     ) -> Result<Self, error::Error> {
         let name = Self::name(maybe_name);
         let db = env.env().create_db(Some(&name), flags)?;
-        Ok(LmdbTrieStore { db })
+        Ok(LmdbTrieStore {
+            db,
+            maybe_cache: None,
+            written_while_pruning: Default::default(),
+        })
     }
 
     pub fn open(env: &LmdbEnvironment, maybe_name: Option<&str>) -> Result<Self, error::Error> {
         let name = Self::name(maybe_name);
         let db = env.env().open_db(Some(&name))?;
-        Ok(LmdbTrieStore { db })
+        Ok(LmdbTrieStore {
+            db,
+            maybe_cache: None,
+            written_while_pruning: Default::default(),
+        })
+    }
+
+    /// Caches up to `max_cache_size` bytes of decoded trie elements.  A size of zero disables the
+    /// cache.
+    pub fn with_cache(self, max_cache_size: usize) -> Self {
+        let maybe_cache = if max_cache_size == 0 {
+            None
+        } else {
+            Some(Arc::new(TrieCache::new(max_cache_size)))
+        };
+        LmdbTrieStore {
+            maybe_cache,
+            ..self
+        }
+    }
+
+    /// Returns the counters of the cache, or `None` if the store has no cache.
+    pub fn cache_stats(&self) -> Result<Option<TrieCacheStats>, error::Error> {
+        match self.maybe_cache.as_ref() {
+            Some(cache) => Ok(Some(cache.stats()?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Invalidates the cache, if any, before `txn` deletes elements from the store.
+    fn invalidate_cache(&self, txn: &RwTransaction) -> Result<(), error::Error> {
+        match self.maybe_cache.as_ref() {
+            Some(cache) => cache.invalidate(lmdb_source::txn_id(txn)),
+            None => Ok(()),
+        }
+    }
+
+    /// Starts recording the hashes of the elements written to the store, until
+    /// [`LmdbTrieStore::stop_recording_writes`] is called.
+    ///
+    /// A prune marks the reachable elements before sweeping the store in several transactions, so
+    /// elements committed in the meantime must not be swept even though they were not marked.
+    pub fn start_recording_writes(&self) -> Result<(), error::Error> {
+        *self.written_while_pruning.lock()? = Some(HashSet::new());
+        Ok(())
+    }
+
+    /// Stops recording the hashes of the elements written to the store, and forgets them.
+    pub fn stop_recording_writes(&self) -> Result<(), error::Error> {
+        *self.written_while_pruning.lock()? = None;
+        Ok(())
+    }
+
+    /// Scans up to `batch_size` elements, starting at the serialized hash `start` or at the first
+    /// element if it is `None`, and deletes those whose hash is neither in `retain` nor recorded
+    /// as written since [`LmdbTrieStore::start_recording_writes`].
+    ///
+    /// Returns the number of elements deleted, along with the serialized hash to resume the scan
+    /// at, or `None` once the whole store has been scanned.
+    pub fn prune_batch(
+        &self,
+        txn: &mut RwTransaction,
+        retain: &HashSet<Blake2bHash>,
+        start: Option<&[u8]>,
+        batch_size: usize,
+    ) -> Result<(usize, Option<Vec<u8>>), error::Error> {
+        let written_while_pruning = self.written_while_pruning.lock()?;
+        let is_retained = |hash: &Blake2bHash| {
+            retain.contains(hash)
+                || written_while_pruning
+                    .as_ref()
+                    .map_or(false, |written| written.contains(hash))
+        };
+        let (to_delete, next_start) = {
+            let mut cursor = lmdb::Transaction::open_ro_cursor(txn, self.db)?;
+            let iter = match start {
+                Some(start) => cursor.iter_from(start),
+                None => cursor.iter_start(),
+            };
+            let mut to_delete: Vec<Vec<u8>> = Vec::new();
+            let mut next_start = None;
+            for (scanned, (key_bytes, _)) in iter.enumerate() {
+                if scanned == batch_size {
+                    next_start = Some(key_bytes.to_vec());
+                    break;
+                }
+                let hash: Blake2bHash = bytesrepr::deserialize(key_bytes.to_vec())?;
+                if !is_retained(&hash) {
+                    to_delete.push(key_bytes.to_vec());
+                }
+            }
+            (to_delete, next_start)
+        };
+        if !to_delete.is_empty() {
+            self.invalidate_cache(txn)?;
+        }
+        for key_bytes in &to_delete {
+            txn.del(self.db, key_bytes, None)?;
+        }
+        Ok((to_delete.len(), next_start))
+    }
+
+    /// Returns the hashes of the stored elements which no decodable stored element points to.
+    ///
+    /// These are the state roots, along with any elements orphaned by corruption of their parents,
+    /// so every stored element is reachable from them.
+    pub fn unreferenced_hashes<K, V, T>(&self, txn: &T) -> Result<Vec<Blake2bHash>, error::Error>
+    where
+        K: FromBytes,
+        V: FromBytes,
+        T: lmdb::Transaction,
+    {
+        let mut stored: Vec<Blake2bHash> = Vec::new();
+        let mut referenced: HashSet<Blake2bHash> = HashSet::new();
+        let mut cursor = txn.open_ro_cursor(self.db)?;
+        for (key_bytes, value_bytes) in cursor.iter_start() {
+            stored.push(bytesrepr::deserialize(key_bytes.to_vec())?);
+            if let Ok(trie) = bytesrepr::deserialize::<Trie<K, V>>(value_bytes.to_vec()) {
+                referenced.extend(trie.child_hashes());
+            }
+        }
+        stored.retain(|hash| !referenced.contains(hash));
+        Ok(stored)
+    }
+
+    /// Deletes the elements stored under `hashes`, returning the number of elements deleted.
+    pub fn delete(
+        &self,
+        txn: &mut RwTransaction,
+        hashes: &[Blake2bHash],
+    ) -> Result<usize, error::Error> {
+        if !hashes.is_empty() {
+            self.invalidate_cache(txn)?;
+        }
+        let mut deleted = 0;
+        for hash in hashes {
+            match txn.del(self.db, &hash.to_bytes()?, None) {
+                Ok(()) => deleted += 1,
+                Err(lmdb::Error::NotFound) => {}
+                Err(error) => return Err(error.into()),
+            }
+        }
+        Ok(deleted)
     }
 
     fn name(maybe_name: Option<&str>) -> String {
@@ -148,7 +315,11 @@ impl LmdbTrieStore {
     }
 }
 
-impl<K, V> Store<Blake2bHash, Trie<K, V>> for LmdbTrieStore {
+impl<K, V> Store<Blake2bHash, Trie<K, V>> for LmdbTrieStore
+where
+    K: Clone + Send + Sync + 'static,
+    V: Clone + Send + Sync + 'static,
+{
     type Error = error::Error;
 
     type Handle = Database;
@@ -156,6 +327,65 @@ impl<K, V> Store<Blake2bHash, Trie<K, V>> for LmdbTrieStore {
     fn handle(&self) -> Self::Handle {
         self.db
     }
+
+    /// Writes `value` at `key`, recording `key` if a prune is in progress.
+    fn put<T>(&self, txn: &mut T, key: &Blake2bHash, value: &Trie<K, V>) -> Result<(), Self::Error>
+    where
+        T: Writable<Handle = Self::Handle>,
+        Blake2bHash: ToBytes,
+        Trie<K, V>: ToBytes,
+        Self::Error: From<T::Error>,
+    {
+        if let Some(written) = self.written_while_pruning.lock()?.as_mut() {
+            written.insert(*key);
+        }
+        txn.write(self.db, &key.to_bytes()?, &value.to_bytes()?)
+            .map_err(Into::into)
+    }
+
+    /// Reads the element at `key`, from the cache if it is there.
+    ///
+    /// Only elements read by read-only transactions are added to the cache, as a read-write
+    /// transaction may read elements it has written itself and which could never be committed.
+    /// Elements are cached along with the generation of the snapshot they were read from.
+    fn get<T>(&self, txn: &T, key: &Blake2bHash) -> Result<Option<Trie<K, V>>, Self::Error>
+    where
+        T: Readable<Handle = Self::Handle>,
+        Blake2bHash: ToBytes,
+        Trie<K, V>: FromBytes,
+        Self::Error: From<T::Error>,
+    {
+        let cache = match self.maybe_cache.as_ref() {
+            Some(cache) => cache,
+            None => {
+                return match txn.read(self.db, &key.to_bytes()?)? {
+                    None => Ok(None),
+                    Some(value_bytes) => Ok(Some(bytesrepr::deserialize(value_bytes)?)),
+                };
+            }
+        };
+
+        if let Some(trie) = cache.get(key)? {
+            return Ok(Some(trie));
+        }
+
+        let value_bytes = match txn.read(self.db, &key.to_bytes()?)? {
+            None => return Ok(None),
+            Some(value_bytes) => value_bytes,
+        };
+        let serialized_len = value_bytes.len();
+        let trie: Trie<K, V> = bytesrepr::deserialize(value_bytes)?;
+        if let Some(generation) = txn.read_generation() {
+            let size = cache::trie_byte_size(&trie, serialized_len);
+            cache.insert(generation, *key, trie.clone(), size)?;
+        }
+        Ok(Some(trie))
+    }
 }
 
-impl<K, V> TrieStore<K, V> for LmdbTrieStore {}
+impl<K, V> TrieStore<K, V> for LmdbTrieStore
+where
+    K: Clone + Send + Sync + 'static,
+    V: Clone + Send + Sync + 'static,
+{
+}
@@ -1,10 +1,13 @@
 //! A store for persisting [`Trie`](crate::trie::Trie) values at their hashes.
 //!
-//! See the [in_memory](in_memory/index.html#usage) and
-//! [lmdb](lmdb/index.html#usage) modules for usage examples.
+//! See the [in_memory](in_memory/index.html#usage), [lmdb](lmdb/index.html#usage) and
+//! [rocksdb](rocksdb/index.html#usage) modules for usage examples.
+pub mod cache;
 pub mod in_memory;
 pub mod lmdb;
-pub(crate) mod operations;
+#[cfg(feature = "use_rocksdb")]
+pub mod rocksdb;
+pub mod operations;
 #[cfg(test)]
 mod tests;
 
@@ -1,7 +1,13 @@
 #[cfg(test)]
 mod tests;
 
-use std::time::Instant;
+use std::{
+    cmp,
+    collections::{BTreeMap, HashSet},
+    fmt,
+    marker::PhantomData,
+    time::Instant,
+};
 
 use contract_ffi::bytesrepr::{self, FromBytes, ToBytes};
 use engine_shared::{
@@ -10,21 +16,43 @@ use engine_shared::{
 };
 
 use crate::{
+    store::Store,
     transaction_source::{Readable, Writable},
-    trie::{self, Parents, Pointer, Trie, RADIX},
+    trie::{self, merkle_proof::TrieMerkleProof, Parents, Pointer, PointerBlock, Trie, RADIX},
     trie_store::TrieStore,
 };
 
+const TRIE_STORE_DIFF_DURATION: &str = "trie_store_diff_duration";
+const TRIE_STORE_DIFF_GETS: &str = "trie_store_diff_gets";
+const TRIE_STORE_MISSING_DURATION: &str = "trie_store_missing_duration";
+const TRIE_STORE_MISSING_GETS: &str = "trie_store_missing_gets";
 const TRIE_STORE_READ_DURATION: &str = "trie_store_read_duration";
 const TRIE_STORE_READ_GETS: &str = "trie_store_read_gets";
+const TRIE_STORE_READ_WITH_PROOF_DURATION: &str = "trie_store_read_with_proof_duration";
+const TRIE_STORE_REACHABLE_DURATION: &str = "trie_store_reachable_duration";
+const TRIE_STORE_REACHABLE_GETS: &str = "trie_store_reachable_gets";
+const TRIE_STORE_STATS_DURATION: &str = "trie_store_stats_duration";
+const TRIE_STORE_STATS_GETS: &str = "trie_store_stats_gets";
+const TRIE_STORE_VERIFY_DURATION: &str = "trie_store_verify_duration";
+const TRIE_STORE_VERIFY_READS: &str = "trie_store_verify_reads";
 const TRIE_STORE_SCAN_DURATION: &str = "trie_store_scan_duration";
 const TRIE_STORE_SCAN_GETS: &str = "trie_store_scan_gets";
 const TRIE_STORE_WRITE_DURATION: &str = "trie_store_write_duration";
 const TRIE_STORE_WRITE_PUTS: &str = "trie_store_write_puts";
+const TRIE_STORE_WRITE_BATCH_DURATION: &str = "trie_store_write_batch_duration";
+const TRIE_STORE_WRITE_BATCH_GETS: &str = "trie_store_write_batch_gets";
+const TRIE_STORE_WRITE_BATCH_PUTS: &str = "trie_store_write_batch_puts";
+const DIFF: &str = "diff";
+const MISSING: &str = "missing";
 const READ: &str = "read";
+const REACHABLE: &str = "reachable";
 const GET: &str = "get";
 const SCAN: &str = "scan";
+const STATS: &str = "stats";
+const VERIFY: &str = "verify";
+const READ_RAW: &str = "read_raw";
 const WRITE: &str = "write";
+const WRITE_BATCH: &str = "write_batch";
 const PUT: &str = "put";
 
 #[derive(Debug, PartialEq, Eq)]
@@ -198,6 +226,80 @@ where
     }
 }
 
+/// Returns a Merkle proof of the presence or absence of the given key at a given root in a given
+/// store, or `None` if the root is not found.
+pub fn read_with_proof<K, V, T, S, E>(
+    correlation_id: CorrelationId,
+    txn: &T,
+    store: &S,
+    root: &Blake2bHash,
+    key: &K,
+) -> Result<Option<TrieMerkleProof<K, V>>, E>
+where
+    K: ToBytes + FromBytes + Clone + std::fmt::Debug,
+    V: ToBytes + FromBytes,
+    T: Readable<Handle = S::Handle>,
+    S: TrieStore<K, V>,
+    S::Error: From<T::Error>,
+    E: From<S::Error> + From<contract_ffi::bytesrepr::Error>,
+{
+    let path: Vec<u8> = key.to_bytes()?;
+
+    let mut depth: usize = 0;
+    let mut current: Trie<K, V> = match store.get(txn, root)? {
+        Some(root) => root,
+        None => return Ok(None),
+    };
+    let mut steps: Vec<Trie<K, V>> = Vec::new();
+
+    let start = Instant::now();
+
+    loop {
+        let maybe_pointer: Option<Pointer> = match &current {
+            Trie::Leaf { .. } => None,
+            Trie::Node { pointer_block } => {
+                let index: usize = {
+                    assert!(depth < path.len(), "depth must be < {}", path.len());
+                    path[depth].into()
+                };
+                depth += 1;
+                pointer_block[index]
+            }
+            Trie::Extension { affix, pointer } => {
+                let sub_path = &path[depth..depth + affix.len()];
+                if sub_path == affix.as_slice() {
+                    depth += affix.len();
+                    Some(*pointer)
+                } else {
+                    None
+                }
+            }
+        };
+        steps.push(current);
+
+        let pointer = match maybe_pointer {
+            Some(pointer) => pointer,
+            None => break,
+        };
+        current = match store.get(txn, pointer.hash())? {
+            Some(next) => next,
+            None => panic!(
+                "No trie value at key: {:?} (reading from key: {:?})",
+                pointer.hash(),
+                key
+            ),
+        };
+    }
+
+    log_duration(
+        correlation_id,
+        TRIE_STORE_READ_WITH_PROOF_DURATION,
+        READ,
+        start.elapsed(),
+    );
+    Ok(Some(TrieMerkleProof::new(key.to_owned(), steps)))
+}
+
 struct TrieScan<K, V> {
     tip: Trie<K, V>,
     parents: Parents<K, V>,
@@ -730,78 +832,1265 @@ where
     }
 }
 
-/// Returns the keys at a given root hash.
+/// Writes all of the given key-value pairs to the trie at `root`, returning the new root.
 ///
-/// Notes:
-/// * This should be rewritten as an Iterator in the future.
-/// * The root doesn't necessarily need to be the apex of the trie. It can be the "root" of a
-///   sub-trie.
-#[allow(dead_code)]
+/// The pairs are sorted by serialized key and grouped by shared prefix, so each affected trie
+/// element is rewritten once per batch rather than once per pair as with repeated calls to
+/// [`write`].  The resulting trie is identical to the one produced by writing the pairs one at a
+/// time.  If a key occurs more than once, its last value is written.
+pub fn write_batch<K, V, T, S, E>(
+    correlation_id: CorrelationId,
+    txn: &mut T,
+    store: &S,
+    root: &Blake2bHash,
+    pairs: Vec<(K, V)>,
+) -> Result<WriteResult, E>
+where
+    K: ToBytes + FromBytes + Clone,
+    V: ToBytes + FromBytes + Clone,
+    T: Readable<Handle = S::Handle> + Writable<Handle = S::Handle>,
+    S: TrieStore<K, V>,
+    S::Error: From<T::Error>,
+    E: From<S::Error> + From<contract_ffi::bytesrepr::Error>,
+{
+    let start = Instant::now();
+
+    let current_root: Trie<K, V> = match store.get(txn, root)? {
+        Some(current_root) => current_root,
+        None => return Ok(WriteResult::RootNotFound),
+    };
+
+    let mut leaves: BTreeMap<Vec<u8>, Trie<K, V>> = BTreeMap::new();
+    for (key, value) in pairs {
+        let path = key.to_bytes()?;
+        leaves.insert(path, Trie::Leaf { key, value });
+    }
+    let leaves: Vec<(Vec<u8>, Trie<K, V>)> = leaves.into_iter().collect();
+
+    let (new_root, new_elements, get_counter) = {
+        let mut batch_writer = BatchWriter::new(&*txn, store);
+        let new_root = if leaves.is_empty() {
+            current_root
+        } else {
+            batch_writer.merge(0, current_root, &leaves)?
+        };
+        (
+            new_root,
+            batch_writer.new_elements,
+            batch_writer.get_counter,
+        )
+    };
+
+    let new_root_hash = Blake2bHash::new(&new_root.to_bytes()?);
+    if new_root_hash == *root {
+        log_duration(
+            correlation_id,
+            TRIE_STORE_WRITE_BATCH_DURATION,
+            WRITE_BATCH,
+            start.elapsed(),
+        );
+        return Ok(WriteResult::AlreadyExists);
+    }
+
+    let mut new_elements = new_elements;
+    new_elements.push((new_root_hash, new_root));
+
+    let mut put_counter: i32 = 0;
+    for (hash, element) in new_elements.iter() {
+        put_counter += 1;
+        store.put(txn, hash, element)?;
+    }
+
+    log_metric(
+        correlation_id,
+        TRIE_STORE_WRITE_BATCH_GETS,
+        GET,
+        GAUGE,
+        f64::from(get_counter),
+    );
+    log_metric(
+        correlation_id,
+        TRIE_STORE_WRITE_BATCH_PUTS,
+        PUT,
+        GAUGE,
+        f64::from(put_counter),
+    );
+    log_duration(
+        correlation_id,
+        TRIE_STORE_WRITE_BATCH_DURATION,
+        WRITE_BATCH,
+        start.elapsed(),
+    );
+
+    Ok(WriteResult::Written(new_root_hash))
+}
+
+/// Leaves to be written by [`write_batch`], paired with their serialized keys and sorted by them.
+type BatchLeaves<K, V> = [(Vec<u8>, Trie<K, V>)];
+
+/// Rebuilds the parts of a trie affected by a batch of leaves.
+///
+/// A sub-trie at a given depth holds the leaves whose paths share their first `depth` bytes.  In
+/// the trie built by [`write`], a sub-trie holding a single leaf is that leaf, and one holding
+/// several leaves is a node at the depth where their paths first diverge, preceded by an extension
+/// if that is deeper than the sub-trie itself.  The root is always a node.
+struct BatchWriter<'a, K, V, T, S, E> {
+    txn: &'a T,
+    store: &'a S,
+    new_elements: Vec<(Blake2bHash, Trie<K, V>)>,
+    get_counter: i32,
+    _error: PhantomData<E>,
+}
+
+impl<'a, K, V, T, S, E> BatchWriter<'a, K, V, T, S, E>
+where
+    K: ToBytes + FromBytes + Clone,
+    V: ToBytes + FromBytes + Clone,
+    T: Readable<Handle = S::Handle>,
+    S: TrieStore<K, V>,
+    S::Error: From<T::Error>,
+    E: From<S::Error> + From<contract_ffi::bytesrepr::Error>,
+{
+    fn new(txn: &'a T, store: &'a S) -> Self {
+        BatchWriter {
+            txn,
+            store,
+            new_elements: Vec::new(),
+            get_counter: 0,
+            _error: PhantomData,
+        }
+    }
+
+    fn load(&mut self, pointer: &Pointer) -> Result<Trie<K, V>, E> {
+        self.get_counter += 1;
+        match self.store.get(self.txn, pointer.hash())? {
+            Some(trie) => Ok(trie),
+            None => panic!("No trie value at key: {:?}", pointer.hash()),
+        }
+    }
+
+    /// Hashes `trie`, records it as a new element, and returns a pointer to it.
+    fn put(&mut self, trie: Trie<K, V>) -> Result<Pointer, E> {
+        let hash = Blake2bHash::new(&trie.to_bytes()?);
+        let pointer = match trie {
+            Trie::Leaf { .. } => Pointer::LeafPointer(hash),
+            Trie::Node { .. } | Trie::Extension { .. } => Pointer::NodePointer(hash),
+        };
+        self.new_elements.push((hash, trie));
+        Ok(pointer)
+    }
+
+    /// Splits `leaves` into runs sharing the same byte at `depth`, paired with that byte.
+    fn group_by_index(depth: usize, leaves: &BatchLeaves<K, V>) -> Vec<(u8, &BatchLeaves<K, V>)> {
+        let mut groups = Vec::new();
+        let mut rest = leaves;
+        while let Some((path, _)) = rest.first() {
+            assert!(depth < path.len(), "depth must be < {}", path.len());
+            let index = path[depth];
+            let length = rest
+                .iter()
+                .take_while(|(path, _)| path[depth] == index)
+                .count();
+            let (group, remainder) = rest.split_at(length);
+            groups.push((index, group));
+            rest = remainder;
+        }
+        groups
+    }
+
+    /// Returns `node`, which is at `depth + affix.len()`, preceded by an extension from `depth` if
+    /// `affix` is not empty.
+    fn with_extension(&mut self, affix: &[u8], node: Trie<K, V>) -> Result<Trie<K, V>, E> {
+        if affix.is_empty() {
+            return Ok(node);
+        }
+        let pointer = self.put(node)?;
+        Ok(Trie::extension(affix.to_vec(), pointer))
+    }
+
+    /// Returns a new sub-trie at `depth` holding exactly `leaves`, which must not be empty.
+    fn build(&mut self, depth: usize, leaves: &BatchLeaves<K, V>) -> Result<Trie<K, V>, E> {
+        if let [(_, leaf)] = leaves {
+            return Ok(leaf.clone());
+        }
+        // As the leaves are sorted, the prefix shared by all of them is the one shared by the first
+        // and the last.
+        let first_path = &leaves[0].0;
+        let last_path = &leaves[leaves.len() - 1].0;
+        let shared = common_prefix(&first_path[depth..], &last_path[depth..]);
+        let node = self.build_node(depth + shared.len(), leaves)?;
+        self.with_extension(&shared, node)
+    }
+
+    fn build_node(&mut self, depth: usize, leaves: &BatchLeaves<K, V>) -> Result<Trie<K, V>, E> {
+        let mut pointer_block = PointerBlock::new();
+        for (index, group) in Self::group_by_index(depth, leaves) {
+            let child = self.build(depth + 1, group)?;
+            pointer_block[usize::from(index)] = Some(self.put(child)?);
+        }
+        Ok(Trie::Node {
+            pointer_block: Box::new(pointer_block),
+        })
+    }
+
+    /// Returns the sub-trie at `depth` holding the leaves of `existing` and `leaves`, which must
+    /// not be empty.  Where both hold a leaf with the same key, the one in `leaves` is kept.
+    fn merge(
+        &mut self,
+        depth: usize,
+        existing: Trie<K, V>,
+        leaves: &BatchLeaves<K, V>,
+    ) -> Result<Trie<K, V>, E> {
+        match existing {
+            Trie::Leaf { key, value } => {
+                let existing_path = key.to_bytes()?;
+                let mut merged = leaves.to_vec();
+                if let Err(position) = merged.binary_search_by(|(path, _)| path.cmp(&existing_path))
+                {
+                    merged.insert(position, (existing_path, Trie::Leaf { key, value }));
+                }
+                self.build(depth, &merged)
+            }
+            Trie::Node { mut pointer_block } => {
+                for (index, group) in Self::group_by_index(depth, leaves) {
+                    let index: usize = index.into();
+                    let child = match pointer_block[index] {
+                        Some(pointer) => {
+                            let existing_child = self.load(&pointer)?;
+                            self.merge(depth + 1, existing_child, group)?
+                        }
+                        None => self.build(depth + 1, group)?,
+                    };
+                    pointer_block[index] = Some(self.put(child)?);
+                }
+                Ok(Trie::Node { pointer_block })
+            }
+            Trie::Extension { affix, pointer } => {
+                // The length of the part of the affix which is shared by all of the leaves.
+                let shared_length = leaves
+                    .iter()
+                    .map(|(path, _)| common_prefix(&path[depth..], &affix[..]).len())
+                    .min()
+                    .unwrap_or(affix.len());
+
+                if shared_length == affix.len() {
+                    let existing_child = self.load(&pointer)?;
+                    let child = self.merge(depth + affix.len(), existing_child, leaves)?;
+                    let pointer = self.put(child)?;
+                    return Ok(Trie::Extension { affix, pointer });
+                }
+
+                // Some leaves diverge from the extension, so it is split by a new node where they
+                // do.  Below the node, the extension continues with what remains of its affix.
+                let node_depth = depth + shared_length;
+                let existing_index = affix[shared_length];
+                let remaining_affix = &affix[shared_length + 1..];
+                let mut pointer_block = PointerBlock::new();
+                for (index, group) in Self::group_by_index(node_depth, leaves) {
+                    let child = if index == existing_index {
+                        let remainder = if remaining_affix.is_empty() {
+                            self.load(&pointer)?
+                        } else {
+                            Trie::extension(remaining_affix.to_vec(), pointer)
+                        };
+                        self.merge(node_depth + 1, remainder, group)?
+                    } else {
+                        self.build(node_depth + 1, group)?
+                    };
+                    pointer_block[usize::from(index)] = Some(self.put(child)?);
+                }
+                if pointer_block[usize::from(existing_index)].is_none() {
+                    let remainder_pointer = if remaining_affix.is_empty() {
+                        pointer
+                    } else {
+                        self.put(Trie::extension(remaining_affix.to_vec(), pointer))?
+                    };
+                    pointer_block[usize::from(existing_index)] = Some(remainder_pointer);
+                }
+                let node = Trie::Node {
+                    pointer_block: Box::new(pointer_block),
+                };
+                self.with_extension(&affix[..shared_length], node)
+            }
+        }
+    }
+}
+
+/// Returns the keys at a given root hash, in ascending order of their serialized form.
+///
+/// The root doesn't necessarily need to be the apex of the trie. It can be the "root" of a
+/// sub-trie.
 pub fn keys<K, V, T, S, E>(
-    _correlation_id: CorrelationId,
+    correlation_id: CorrelationId,
     txn: &T,
     store: &S,
     root: &Blake2bHash,
 ) -> Result<Vec<K>, E>
 where
-    K: ToBytes + FromBytes + Clone + Eq + std::fmt::Debug,
-    V: ToBytes + FromBytes + Clone + Eq + std::fmt::Debug,
+    K: ToBytes + FromBytes,
+    V: ToBytes + FromBytes,
     T: Readable<Handle = S::Handle>,
     S: TrieStore<K, V>,
     S::Error: From<T::Error>,
     E: From<S::Error> + From<contract_ffi::bytesrepr::Error>,
 {
-    let mut ret = Vec::new();
+    iter_prefix::<K, V, &T, S, E>(correlation_id, txn, store, root, &[], &[])?
+        .map(|result| result.map(|(key, _value)| key))
+        .collect()
+}
 
+/// Returns an iterator over the leaves under `root` whose serialized keys start with `prefix`,
+/// in ascending order of serialized key.
+///
+/// Leaves whose serialized keys are less than `start` are skipped, which allows resuming an
+/// earlier iteration.  Pass an empty `start` to begin at the first leaf with the prefix.  Only
+/// the parts of the trie which can hold such leaves are read, and they are read lazily as the
+/// iterator advances.  If `root` is not found, the iterator is empty.
+pub fn iter_prefix<'a, K, V, T, S, E>(
+    _correlation_id: CorrelationId,
+    txn: T,
+    store: &'a S,
+    root: &Blake2bHash,
+    prefix: &[u8],
+    start: &[u8],
+) -> Result<PrefixIterator<'a, K, V, T, S, E>, E>
+where
+    K: ToBytes + FromBytes,
+    V: ToBytes + FromBytes,
+    T: Readable<Handle = S::Handle>,
+    S: TrieStore<K, V>,
+    S::Error: From<T::Error>,
+    E: From<S::Error> + From<contract_ffi::bytesrepr::Error>,
+{
+    let visited = match store.get(&txn, root)? {
+        Some(root) => vec![(root, None, Vec::new())],
+        None => Vec::new(),
+    };
+    Ok(PrefixIterator {
+        txn,
+        store,
+        prefix: prefix.to_vec(),
+        start: start.to_vec(),
+        visited,
+        _error: PhantomData,
+    })
+}
+
+/// An iterator over the leaves of a trie whose serialized keys start with a given prefix.
+///
+/// Created by [`iter_prefix`].  Yields at most one error, after which it is exhausted.
+pub struct PrefixIterator<'a, K, V, T, S, E> {
+    txn: T,
+    store: &'a S,
+    prefix: Vec<u8>,
+    start: Vec<u8>,
+    /// Trie elements still to be visited, along with the index of the next pointer to visit
+    /// for nodes and the path to each element.
     #[allow(clippy::type_complexity)]
-    let mut visited: Vec<(Trie<K, V>, Option<usize>, Vec<u8>)> = {
-        let root = match store.get(txn, root)? {
-            None => return Ok(ret),
-            Some(current_root) => current_root,
+    visited: Vec<(Trie<K, V>, Option<usize>, Vec<u8>)>,
+    _error: PhantomData<E>,
+}
+
+impl<'a, K, V, T, S, E> PrefixIterator<'a, K, V, T, S, E> {
+    /// Whether the sub-trie at `path` can hold leaves with the prefix which are not before
+    /// `start`.
+    fn may_contain(&self, path: &[u8]) -> bool {
+        let prefix_len = cmp::min(path.len(), self.prefix.len());
+        let start_len = cmp::min(path.len(), self.start.len());
+        path[..prefix_len] == self.prefix[..prefix_len] && path >= &self.start[..start_len]
+    }
+
+    fn contains(&self, key_bytes: &[u8]) -> bool {
+        key_bytes.starts_with(&self.prefix) && key_bytes >= self.start.as_slice()
+    }
+}
+
+impl<'a, K, V, T, S, E> PrefixIterator<'a, K, V, T, S, E>
+where
+    K: ToBytes + FromBytes,
+    V: ToBytes + FromBytes,
+    T: Readable<Handle = S::Handle>,
+    S: TrieStore<K, V>,
+    S::Error: From<T::Error>,
+    E: From<S::Error> + From<contract_ffi::bytesrepr::Error>,
+{
+    fn next_leaf(&mut self) -> Result<Option<(K, V)>, E> {
+        while let Some((trie, maybe_index, path)) = self.visited.pop() {
+            match trie {
+                Trie::Leaf { key, value } => {
+                    if self.contains(&key.to_bytes()?) {
+                        return Ok(Some((key, value)));
+                    }
+                }
+                Trie::Node { pointer_block } => {
+                    let mut index: usize = maybe_index.unwrap_or_default();
+                    while index < RADIX {
+                        if let Some(pointer) = pointer_block[index] {
+                            let mut child_path = path.clone();
+                            child_path.push(index as u8);
+                            if self.may_contain(&child_path) {
+                                let maybe_child = self.store.get(&self.txn, pointer.hash())?;
+                                debug_assert!(maybe_child.is_some());
+                                self.visited.push((
+                                    Trie::Node { pointer_block },
+                                    Some(index + 1),
+                                    path,
+                                ));
+                                if let Some(child) = maybe_child {
+                                    self.visited.push((child, None, child_path));
+                                }
+                                break;
+                            }
+                        }
+                        index += 1;
+                    }
+                }
+                Trie::Extension { affix, pointer } => {
+                    let mut child_path = path;
+                    child_path.extend(affix);
+                    if self.may_contain(&child_path) {
+                        let maybe_child = self.store.get(&self.txn, pointer.hash())?;
+                        debug_assert!(maybe_child.is_some());
+                        if let Some(child) = maybe_child {
+                            self.visited.push((child, None, child_path));
+                        }
+                    }
+                }
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl<'a, K, V, T, S, E> Iterator for PrefixIterator<'a, K, V, T, S, E>
+where
+    K: ToBytes + FromBytes,
+    V: ToBytes + FromBytes,
+    T: Readable<Handle = S::Handle>,
+    S: TrieStore<K, V>,
+    S::Error: From<T::Error>,
+    E: From<S::Error> + From<contract_ffi::bytesrepr::Error>,
+{
+    type Item = Result<(K, V), E>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.next_leaf() {
+            Ok(maybe_leaf) => maybe_leaf.map(Ok),
+            Err(error) => {
+                self.visited.clear();
+                Some(Err(error))
+            }
+        }
+    }
+}
+
+/// Returns the hashes of all trie elements reachable from any of the given roots, including the
+/// roots themselves.
+///
+/// Roots and descendants which are missing from the store are not included in the result.
+pub fn reachable_hashes<K, V, T, S, E>(
+    correlation_id: CorrelationId,
+    txn: &T,
+    store: &S,
+    roots: &[Blake2bHash],
+) -> Result<HashSet<Blake2bHash>, E>
+where
+    K: ToBytes + FromBytes,
+    V: ToBytes + FromBytes,
+    T: Readable<Handle = S::Handle>,
+    S: TrieStore<K, V>,
+    S::Error: From<T::Error>,
+    E: From<S::Error> + From<contract_ffi::bytesrepr::Error>,
+{
+    let start = Instant::now();
+    let mut get_counter: i32 = 0;
+
+    let mut reachable: HashSet<Blake2bHash> = HashSet::new();
+    let mut to_visit: Vec<Blake2bHash> = roots.to_vec();
+
+    while let Some(hash) = to_visit.pop() {
+        if reachable.contains(&hash) {
+            continue;
+        }
+        get_counter += 1;
+        let trie: Trie<K, V> = match store.get(txn, &hash)? {
+            Some(trie) => trie,
+            None => continue,
         };
-        vec![(root, None, vec![])]
-    };
+        reachable.insert(hash);
+        to_visit.extend(trie.child_hashes());
+    }
+
+    log_metric(
+        correlation_id,
+        TRIE_STORE_REACHABLE_GETS,
+        GET,
+        GAUGE,
+        f64::from(get_counter),
+    );
+    log_duration(
+        correlation_id,
+        TRIE_STORE_REACHABLE_DURATION,
+        REACHABLE,
+        start.elapsed(),
+    );
+
+    Ok(reachable)
+}
+
+/// The trie elements found missing by [`missing_trie_keys`].
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct MissingTrieKeys {
+    /// The hashes of missing trie elements.
+    pub missing: Vec<Blake2bHash>,
+    /// The hashes which were not checked because the limit of missing elements was reached.
+    pub unexplored: Vec<Blake2bHash>,
+}
+
+/// Returns the hashes of up to `limit` trie elements which are reachable from any of the given
+/// trie keys but are missing from the store, including any of the trie keys themselves.
+///
+/// A node syncing a state root from its peers can call this with the state root, fetch and store
+/// the elements it returns, then call it again with just those elements and any unexplored
+/// hashes, repeating until nothing is missing or unexplored.  Each call then only walks the
+/// parts of the trie which were stored since the previous call, rather than everything already
+/// synced.  Since only missing elements are returned, a sync which was interrupted can resume by
+/// starting again from the state root.
+pub fn missing_trie_keys<K, V, T, S, E>(
+    correlation_id: CorrelationId,
+    txn: &T,
+    store: &S,
+    trie_keys: &[Blake2bHash],
+    limit: usize,
+) -> Result<MissingTrieKeys, E>
+where
+    K: ToBytes + FromBytes,
+    V: ToBytes + FromBytes,
+    T: Readable<Handle = S::Handle>,
+    S: TrieStore<K, V>,
+    S::Error: From<T::Error>,
+    E: From<S::Error> + From<contract_ffi::bytesrepr::Error>,
+{
+    let start = Instant::now();
+    let mut get_counter: i32 = 0;
+
+    let mut visited: HashSet<Blake2bHash> = HashSet::new();
+    let mut missing: Vec<Blake2bHash> = Vec::new();
+    let mut to_visit: Vec<Blake2bHash> = trie_keys.to_vec();
+
+    while missing.len() < limit {
+        let hash = match to_visit.pop() {
+            Some(hash) => hash,
+            None => break,
+        };
+        if !visited.insert(hash) {
+            continue;
+        }
+        get_counter += 1;
+        let maybe_trie: Option<Trie<K, V>> = store.get(txn, &hash)?;
+        match maybe_trie {
+            Some(trie) => to_visit.extend(trie.child_hashes()),
+            None => missing.push(hash),
+        }
+    }
 
-    while let Some((trie, maybe_index, mut path)) = visited.pop() {
-        let mut maybe_next_trie: Option<Trie<K, V>> = None;
+    let mut unexplored: Vec<Blake2bHash> = Vec::new();
+    for hash in to_visit {
+        if visited.insert(hash) {
+            unexplored.push(hash);
+        }
+    }
+
+    log_metric(
+        correlation_id,
+        TRIE_STORE_MISSING_GETS,
+        GET,
+        GAUGE,
+        f64::from(get_counter),
+    );
+    log_duration(
+        correlation_id,
+        TRIE_STORE_MISSING_DURATION,
+        MISSING,
+        start.elapsed(),
+    );
+
+    Ok(MissingTrieKeys {
+        missing,
+        unexplored,
+    })
+}
+
+/// Counts and serialized sizes of the trie elements reachable from a root.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct TrieStats {
+    pub leaf_count: usize,
+    pub node_count: usize,
+    pub extension_count: usize,
+    /// The number of referenced elements which are not in the store.
+    pub missing_count: usize,
+    /// The number of elements on the longest path from the root, including the root.
+    pub max_depth: usize,
+    pub leaf_bytes: usize,
+    pub node_bytes: usize,
+    pub extension_bytes: usize,
+}
+
+impl TrieStats {
+    pub fn element_count(&self) -> usize {
+        self.leaf_count + self.node_count + self.extension_count
+    }
+
+    pub fn total_bytes(&self) -> usize {
+        self.leaf_bytes + self.node_bytes + self.extension_bytes
+    }
+}
+
+/// Walks the trie elements reachable from `root`, returning their counts and sizes, or `None` if
+/// `root` is not in the store.
+///
+/// Elements missing from the store are counted rather than treated as an error, so that a
+/// partially synced trie can be inspected.
+pub fn trie_stats<K, V, T, S, E>(
+    correlation_id: CorrelationId,
+    txn: &T,
+    store: &S,
+    root: &Blake2bHash,
+) -> Result<Option<TrieStats>, E>
+where
+    K: ToBytes + FromBytes,
+    V: ToBytes + FromBytes,
+    T: Readable<Handle = S::Handle>,
+    S: TrieStore<K, V>,
+    S::Error: From<T::Error>,
+    E: From<S::Error> + From<contract_ffi::bytesrepr::Error>,
+{
+    let start = Instant::now();
+    let mut get_counter: i32 = 0;
+
+    let mut stats = TrieStats::default();
+    let mut visited: HashSet<Blake2bHash> = HashSet::new();
+    let mut to_visit: Vec<(Blake2bHash, usize)> = vec![(*root, 1)];
+
+    while let Some((hash, depth)) = to_visit.pop() {
+        if !visited.insert(hash) {
+            continue;
+        }
+        get_counter += 1;
+        let trie: Trie<K, V> = match store.get(txn, &hash)? {
+            Some(trie) => trie,
+            None if hash == *root => return Ok(None),
+            None => {
+                stats.missing_count += 1;
+                continue;
+            }
+        };
+        let trie_bytes = trie.to_bytes()?.len();
+        match trie {
+            Trie::Leaf { .. } => {
+                stats.leaf_count += 1;
+                stats.leaf_bytes += trie_bytes;
+            }
+            Trie::Node { .. } => {
+                stats.node_count += 1;
+                stats.node_bytes += trie_bytes;
+            }
+            Trie::Extension { .. } => {
+                stats.extension_count += 1;
+                stats.extension_bytes += trie_bytes;
+            }
+        }
+        stats.max_depth = cmp::max(stats.max_depth, depth);
+        to_visit.extend(
+            trie.child_hashes()
+                .into_iter()
+                .map(|child_hash| (child_hash, depth + 1)),
+        );
+    }
+
+    log_metric(
+        correlation_id,
+        TRIE_STORE_STATS_GETS,
+        GET,
+        GAUGE,
+        f64::from(get_counter),
+    );
+    log_duration(
+        correlation_id,
+        TRIE_STORE_STATS_DURATION,
+        STATS,
+        start.elapsed(),
+    );
+
+    Ok(Some(stats))
+}
+
+/// A problem with a trie element found by [`verify`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TrieCorruption {
+    /// The element is referenced by `parent`, or is a given root if there is no parent, but is not
+    /// in the store.
+    Missing {
+        hash: Blake2bHash,
+        parent: Option<Blake2bHash>,
+    },
+    /// The stored bytes do not hash to the key they are stored under.
+    HashMismatch {
+        hash: Blake2bHash,
+        actual: Blake2bHash,
+    },
+    /// The stored bytes do not deserialize to a trie element.
+    Undecodable { hash: Blake2bHash },
+    /// `parent` points to the element with a leaf pointer although it is not a leaf, or with a
+    /// node pointer although it is a leaf.
+    PointerMismatch {
+        hash: Blake2bHash,
+        parent: Blake2bHash,
+    },
+    /// The extension has an empty affix, or does not point to a node.
+    InvalidExtension { hash: Blake2bHash },
+    /// The leaf's key does not start with the path leading to it.
+    MisplacedLeaf { hash: Blake2bHash },
+}
+
+impl TrieCorruption {
+    /// Returns the hash of the element the problem was found in.
+    pub fn hash(&self) -> Blake2bHash {
+        match self {
+            TrieCorruption::Missing { hash, .. }
+            | TrieCorruption::HashMismatch { hash, .. }
+            | TrieCorruption::Undecodable { hash }
+            | TrieCorruption::PointerMismatch { hash, .. }
+            | TrieCorruption::InvalidExtension { hash }
+            | TrieCorruption::MisplacedLeaf { hash } => *hash,
+        }
+    }
+
+    /// Whether the stored bytes of the element are damaged, as opposed to the element being
+    /// missing or misplaced.  Damaged elements can be deleted and fetched again.
+    pub fn is_damaged(&self) -> bool {
+        match self {
+            TrieCorruption::HashMismatch { .. } | TrieCorruption::Undecodable { .. } => true,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for TrieCorruption {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TrieCorruption::Missing {
+                hash,
+                parent: Some(parent),
+            } => write!(f, "missing trie element {} referenced by {}", hash, parent),
+            TrieCorruption::Missing { hash, parent: None } => {
+                write!(f, "missing root trie element {}", hash)
+            }
+            TrieCorruption::HashMismatch { hash, actual } => {
+                write!(f, "trie element stored under {} hashes to {}", hash, actual)
+            }
+            TrieCorruption::Undecodable { hash } => {
+                write!(f, "trie element {} cannot be deserialized", hash)
+            }
+            TrieCorruption::PointerMismatch { hash, parent } => write!(
+                f,
+                "trie element {} does not match the kind of pointer to it in {}",
+                hash, parent
+            ),
+            TrieCorruption::InvalidExtension { hash } => write!(
+                f,
+                "extension {} has an empty affix or does not point to a node",
+                hash
+            ),
+            TrieCorruption::MisplacedLeaf { hash } => write!(
+                f,
+                "leaf {} has a key which does not start with its path",
+                hash
+            ),
+        }
+    }
+}
+
+/// The outcome of [`verify`].
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct TrieVerification {
+    /// The number of elements read from the store.
+    pub checked: usize,
+    pub corruptions: Vec<TrieCorruption>,
+}
+
+impl TrieVerification {
+    pub fn is_intact(&self) -> bool {
+        self.corruptions.is_empty()
+    }
+}
+
+/// An element to be verified, along with the path leading to it and the pointer to it.
+struct Unverified {
+    hash: Blake2bHash,
+    path: Vec<u8>,
+    maybe_parent: Option<(Blake2bHash, Pointer)>,
+    parent_is_extension: bool,
+}
+
+/// Walks the trie elements reachable from `roots`, checking that each element is present, hashes
+/// to the key it is stored under, deserializes, matches the pointer to it and, for a leaf, has a
+/// key starting with the path leading to it.  Extensions are also checked to have a non-empty
+/// affix and to point to a node.
+///
+/// Elements are read bypassing any cache of the store.  The children of an element which fails
+/// to hash or to deserialize are not walked, as its pointers cannot be trusted.
+pub fn verify<K, V, T, S, E>(
+    correlation_id: CorrelationId,
+    txn: &T,
+    store: &S,
+    roots: &[Blake2bHash],
+) -> Result<TrieVerification, E>
+where
+    K: ToBytes + FromBytes,
+    V: ToBytes + FromBytes,
+    T: Readable<Handle = S::Handle>,
+    S: TrieStore<K, V>,
+    S::Error: From<T::Error>,
+    E: From<S::Error> + From<contract_ffi::bytesrepr::Error>,
+{
+    let start = Instant::now();
+    let mut read_counter: i32 = 0;
+
+    let mut verification = TrieVerification::default();
+    let mut visited: HashSet<Blake2bHash> = HashSet::new();
+    let mut to_visit: Vec<Unverified> = roots
+        .iter()
+        .map(|root| Unverified {
+            hash: *root,
+            path: Vec::new(),
+            maybe_parent: None,
+            parent_is_extension: false,
+        })
+        .collect();
+
+    while let Some(Unverified {
+        hash,
+        path,
+        maybe_parent,
+        parent_is_extension,
+    }) = to_visit.pop()
+    {
+        if !visited.insert(hash) {
+            continue;
+        }
+        read_counter += 1;
+        let handle = <S as Store<Blake2bHash, Trie<K, V>>>::handle(store);
+        let trie_bytes = match txn
+            .read(handle, &hash.to_bytes()?)
+            .map_err(S::Error::from)?
+        {
+            Some(trie_bytes) => trie_bytes,
+            None => {
+                let parent = maybe_parent.map(|(parent, _)| parent);
+                verification
+                    .corruptions
+                    .push(TrieCorruption::Missing { hash, parent });
+                continue;
+            }
+        };
+        verification.checked += 1;
+
+        let actual = Blake2bHash::new(&trie_bytes);
+        if actual != hash {
+            verification
+                .corruptions
+                .push(TrieCorruption::HashMismatch { hash, actual });
+            continue;
+        }
+        let trie: Trie<K, V> = match bytesrepr::deserialize(trie_bytes) {
+            Ok(trie) => trie,
+            Err(_) => {
+                verification
+                    .corruptions
+                    .push(TrieCorruption::Undecodable { hash });
+                continue;
+            }
+        };
+
+        if let Some((parent, pointer)) = maybe_parent {
+            let pointer_matches = match (pointer, &trie) {
+                (Pointer::LeafPointer(_), Trie::Leaf { .. }) => true,
+                (Pointer::NodePointer(_), Trie::Leaf { .. }) => false,
+                (Pointer::LeafPointer(_), _) => false,
+                (Pointer::NodePointer(_), _) => true,
+            };
+            if !pointer_matches {
+                verification
+                    .corruptions
+                    .push(TrieCorruption::PointerMismatch { hash, parent });
+            }
+            if parent_is_extension {
+                if let Trie::Extension { .. } = trie {
+                    verification
+                        .corruptions
+                        .push(TrieCorruption::InvalidExtension { hash: parent });
+                }
+            }
+        }
 
         match trie {
             Trie::Leaf { key, .. } => {
-                debug_assert!({
-                    let key_bytes = key.to_bytes()?;
-                    key_bytes.starts_with(&path)
-                });
-                ret.push(key);
-            }
-            Trie::Node { ref pointer_block } => {
-                let mut index: usize = maybe_index.unwrap_or_default();
-                while index < RADIX {
-                    if let Some(ref pointer) = pointer_block[index] {
-                        maybe_next_trie = store.get(txn, pointer.hash())?;
-                        debug_assert!(maybe_next_trie.is_some());
-                        visited.push((trie, Some(index + 1), path.clone()));
-                        path.push(index as u8);
-                        break;
+                if !key.to_bytes()?.starts_with(&path) {
+                    verification
+                        .corruptions
+                        .push(TrieCorruption::MisplacedLeaf { hash });
+                }
+            }
+            Trie::Node { pointer_block } => {
+                for (index, maybe_pointer) in pointer_block[..].iter().enumerate() {
+                    if let Some(pointer) = maybe_pointer {
+                        let mut child_path = path.clone();
+                        child_path.push(index as u8);
+                        to_visit.push(Unverified {
+                            hash: *pointer.hash(),
+                            path: child_path,
+                            maybe_parent: Some((hash, *pointer)),
+                            parent_is_extension: false,
+                        });
                     }
-                    index += 1;
                 }
             }
             Trie::Extension { affix, pointer } => {
-                maybe_next_trie = store.get(txn, pointer.hash())?;
-                debug_assert!({
-                    match &maybe_next_trie {
-                        Some(Trie::Node { .. }) => true,
-                        _ => false,
-                    }
+                if affix.is_empty() {
+                    verification
+                        .corruptions
+                        .push(TrieCorruption::InvalidExtension { hash });
+                }
+                if let Pointer::LeafPointer(_) = pointer {
+                    verification
+                        .corruptions
+                        .push(TrieCorruption::InvalidExtension { hash });
+                }
+                let mut child_path = path;
+                child_path.extend(affix);
+                to_visit.push(Unverified {
+                    hash: *pointer.hash(),
+                    path: child_path,
+                    maybe_parent: Some((hash, pointer)),
+                    parent_is_extension: true,
                 });
-                path.extend(affix);
             }
         }
+    }
+
+    log_metric(
+        correlation_id,
+        TRIE_STORE_VERIFY_READS,
+        READ_RAW,
+        GAUGE,
+        f64::from(read_counter),
+    );
+    log_duration(
+        correlation_id,
+        TRIE_STORE_VERIFY_DURATION,
+        VERIFY,
+        start.elapsed(),
+    );
+
+    Ok(verification)
+}
 
-        if let Some(next_trie) = maybe_next_trie {
-            visited.push((next_trie, None, path));
+/// A change to the value stored under a key between two tries.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Difference<K, V> {
+    Added { key: K, value: V },
+    Removed { key: K, value: V },
+    Modified { key: K, old_value: V, new_value: V },
+}
+
+impl<K, V> Difference<K, V> {
+    pub fn key(&self) -> &K {
+        match self {
+            Difference::Added { key, .. } => key,
+            Difference::Removed { key, .. } => key,
+            Difference::Modified { key, .. } => key,
         }
     }
+}
 
-    Ok(ret)
+#[derive(Debug, PartialEq, Eq)]
+pub enum DiffResult<K, V> {
+    /// The given root was not found.
+    RootNotFound(Blake2bHash),
+    /// The changes between the two roots, in ascending order of serialized key.
+    Success(Vec<Difference<K, V>>),
+}
+
+/// Returns up to `limit` of the changes to the values stored under each key from the trie at
+/// `old_root` to the trie at `new_root`.
+///
+/// Both tries are walked in parallel, and sub-tries which are identical in both are skipped
+/// without being read, so the cost is proportional to the size of the difference rather than
+/// the size of the tries.  Changes to keys whose serialized form is less than `start` are
+/// skipped, as are the sub-tries which can only hold such keys, which allows resuming an earlier
+/// diff.  Pass an empty `start` to begin at the first change.
+pub fn diff<K, V, T, S, E>(
+    correlation_id: CorrelationId,
+    txn: &T,
+    store: &S,
+    old_root: &Blake2bHash,
+    new_root: &Blake2bHash,
+    start: &[u8],
+    limit: usize,
+) -> Result<DiffResult<K, V>, E>
+where
+    K: ToBytes + FromBytes,
+    V: ToBytes + FromBytes + Eq,
+    T: Readable<Handle = S::Handle>,
+    S: TrieStore<K, V>,
+    S::Error: From<T::Error>,
+    E: From<S::Error> + From<contract_ffi::bytesrepr::Error>,
+{
+    let start = Instant::now();
+
+    for root in &[old_root, new_root] {
+        let maybe_root: Option<Trie<K, V>> = store.get(txn, root)?;
+        if maybe_root.is_none() {
+            return Ok(DiffResult::RootNotFound(**root));
+        }
+    }
+
+    let mut differ = Differ {
+        txn,
+        store,
+        start,
+        limit,
+        get_counter: 0,
+        differences: Vec::new(),
+        _error: PhantomData,
+    };
+    differ.diff_sub_tries(
+        Vec::new(),
+        Some(DiffChild::Pointer(Pointer::NodePointer(*old_root))),
+        Some(DiffChild::Pointer(Pointer::NodePointer(*new_root))),
+    )?;
+
+    log_metric(
+        correlation_id,
+        TRIE_STORE_DIFF_GETS,
+        GET,
+        GAUGE,
+        f64::from(differ.get_counter),
+    );
+    log_duration(
+        correlation_id,
+        TRIE_STORE_DIFF_DURATION,
+        DIFF,
+        start.elapsed(),
+    );
+
+    Ok(DiffResult::Success(differ.differences))
+}
+
+/// A sub-trie compared by [`diff`], relative to the path walked so far.
+#[derive(PartialEq, Eq)]
+enum DiffChild {
+    /// A trie element in the store.
+    Pointer(Pointer),
+    /// The remainder of an extension whose affix has been partly walked.
+    Extension { affix: Vec<u8>, pointer: Pointer },
+}
+
+struct Differ<'a, K, V, T, S, E> {
+    txn: &'a T,
+    store: &'a S,
+    start: &'a [u8],
+    limit: usize,
+    get_counter: i32,
+    differences: Vec<Difference<K, V>>,
+    _error: PhantomData<E>,
+}
+
+impl<'a, K, V, T, S, E> Differ<'a, K, V, T, S, E>
+where
+    K: ToBytes + FromBytes,
+    V: ToBytes + FromBytes + Eq,
+    T: Readable<Handle = S::Handle>,
+    S: TrieStore<K, V>,
+    S::Error: From<T::Error>,
+    E: From<S::Error> + From<contract_ffi::bytesrepr::Error>,
+{
+    fn load(&mut self, maybe_child: Option<DiffChild>) -> Result<Option<Trie<K, V>>, E> {
+        match maybe_child {
+            None => Ok(None),
+            Some(DiffChild::Pointer(pointer)) => {
+                self.get_counter += 1;
+                let maybe_trie = self.store.get(self.txn, pointer.hash())?;
+                debug_assert!(maybe_trie.is_some());
+                Ok(maybe_trie)
+            }
+            Some(DiffChild::Extension { affix, pointer }) => {
+                Ok(Some(Trie::Extension { affix, pointer }))
+            }
+        }
+    }
+
+    fn is_branch(trie: &Trie<K, V>) -> bool {
+        match trie {
+            Trie::Leaf { .. } => false,
+            Trie::Node { .. } | Trie::Extension { .. } => true,
+        }
+    }
+
+    /// Returns the children of a node or extension, indexed by the next byte of their path.
+    fn children(trie: Trie<K, V>) -> Vec<(u8, DiffChild)> {
+        match trie {
+            Trie::Leaf { .. } => Vec::new(),
+            Trie::Node { pointer_block } => pointer_block[..]
+                .iter()
+                .enumerate()
+                .filter_map(|(index, maybe_pointer)| {
+                    maybe_pointer.map(|pointer| (index as u8, DiffChild::Pointer(pointer)))
+                })
+                .collect(),
+            Trie::Extension { affix, pointer } => match affix.split_first() {
+                None => Vec::new(),
+                Some((first, [])) => vec![(*first, DiffChild::Pointer(pointer))],
+                Some((first, rest)) => vec![(
+                    *first,
+                    DiffChild::Extension {
+                        affix: rest.to_vec(),
+                        pointer,
+                    },
+                )],
+            },
+        }
+    }
+
+    /// Returns the number of differences still to be found before reaching `limit`.
+    fn remaining(&self) -> usize {
+        self.limit.saturating_sub(self.differences.len())
+    }
+
+    /// Returns `true` if every key under `path` is less than `start`.
+    fn is_before_start(&self, path: &[u8]) -> bool {
+        let compared = cmp::min(path.len(), self.start.len());
+        path < &self.start[..compared]
+    }
+
+    fn diff_sub_tries(
+        &mut self,
+        path: Vec<u8>,
+        maybe_old: Option<DiffChild>,
+        maybe_new: Option<DiffChild>,
+    ) -> Result<(), E> {
+        if maybe_old == maybe_new || self.remaining() == 0 || self.is_before_start(&path) {
+            return Ok(());
+        }
+
+        match (self.load(maybe_old)?, self.load(maybe_new)?) {
+            (Some(old), Some(new)) if Self::is_branch(&old) && Self::is_branch(&new) => {
+                let mut children: BTreeMap<u8, (Option<DiffChild>, Option<DiffChild>)> =
+                    BTreeMap::new();
+                for (index, child) in Self::children(old) {
+                    children.entry(index).or_default().0 = Some(child);
+                }
+                for (index, child) in Self::children(new) {
+                    children.entry(index).or_default().1 = Some(child);
+                }
+                for (index, (maybe_old_child, maybe_new_child)) in children {
+                    let mut child_path = path.clone();
+                    child_path.push(index);
+                    self.diff_sub_tries(child_path, maybe_old_child, maybe_new_child)?;
+                }
+            }
+            (maybe_old, maybe_new) => {
+                // At least one side is a single leaf or empty, so it is cheapest to compare the
+                // leaves of both sides directly.  As at most one leaf can be unchanged, the first
+                // `remaining + 1` leaves of each side are enough to find the next `remaining`
+                // differences.
+                let max_leaves = self.remaining().saturating_add(1);
+                let old_leaves = self.leaves(&path, maybe_old, max_leaves)?;
+                let new_leaves = self.leaves(&path, maybe_new, max_leaves)?;
+                self.diff_leaves(old_leaves, new_leaves);
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns up to `max_leaves` of the leaves of `maybe_trie` at `path` whose serialized keys
+    /// are not less than `start`, with their serialized keys, in ascending order of serialized
+    /// key.
+    #[allow(clippy::type_complexity)]
+    fn leaves(
+        &mut self,
+        path: &[u8],
+        maybe_trie: Option<Trie<K, V>>,
+        max_leaves: usize,
+    ) -> Result<Vec<(Vec<u8>, K, V)>, E> {
+        let mut ret = Vec::new();
+        let mut to_visit: Vec<(Vec<u8>, Trie<K, V>)> = maybe_trie
+            .into_iter()
+            .map(|trie| (path.to_vec(), trie))
+            .collect();
+        while let Some((trie_path, trie)) = to_visit.pop() {
+            if ret.len() >= max_leaves {
+                break;
+            }
+            match trie {
+                Trie::Leaf { key, value } => {
+                    let key_bytes = key.to_bytes()?;
+                    if key_bytes.as_slice() >= self.start {
+                        ret.push((key_bytes, key, value));
+                    }
+                }
+                trie => {
+                    // Children are pushed in reverse so that they are visited in ascending order.
+                    for (index, child) in Self::children(trie).into_iter().rev() {
+                        let mut child_path = trie_path.clone();
+                        child_path.push(index);
+                        if self.is_before_start(&child_path) {
+                            continue;
+                        }
+                        if let Some(child_trie) = self.load(Some(child))? {
+                            to_visit.push((child_path, child_trie));
+                        }
+                    }
+                }
+            }
+        }
+        Ok(ret)
+    }
+
+    fn diff_leaves(&mut self, old_leaves: Vec<(Vec<u8>, K, V)>, new_leaves: Vec<(Vec<u8>, K, V)>) {
+        let mut old_leaves = old_leaves.into_iter().peekable();
+        let mut new_leaves = new_leaves.into_iter().peekable();
+        while self.remaining() > 0 {
+            let ordering = match (old_leaves.peek(), new_leaves.peek()) {
+                (None, None) => break,
+                (Some(_), None) => cmp::Ordering::Less,
+                (None, Some(_)) => cmp::Ordering::Greater,
+                (Some((old_bytes, _, _)), Some((new_bytes, _, _))) => old_bytes.cmp(new_bytes),
+            };
+            let difference = match ordering {
+                cmp::Ordering::Less => {
+                    let (_, key, value) = old_leaves.next().unwrap();
+                    Difference::Removed { key, value }
+                }
+                cmp::Ordering::Greater => {
+                    let (_, key, value) = new_leaves.next().unwrap();
+                    Difference::Added { key, value }
+                }
+                cmp::Ordering::Equal => {
+                    let (_, _, old_value) = old_leaves.next().unwrap();
+                    let (_, key, new_value) = new_leaves.next().unwrap();
+                    if old_value == new_value {
+                        continue;
+                    }
+                    Difference::Modified {
+                        key,
+                        old_value,
+                        new_value,
+                    }
+                }
+            };
+            self.differences.push(difference);
+        }
+    }
 }
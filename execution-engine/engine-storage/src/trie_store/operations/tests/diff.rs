@@ -0,0 +1,280 @@
+use std::collections::BTreeMap;
+
+use super::*;
+use crate::{
+    error::{self, in_memory},
+    trie_store::operations::{diff, DiffResult, Difference},
+};
+
+/// Returns the pairs held by `leaves`, where later leaves replace earlier ones with the same key.
+fn pairs_of(leaves: &[TestTrie]) -> BTreeMap<TestKey, TestValue> {
+    leaves
+        .iter()
+        .map(|leaf| match leaf {
+            Trie::Leaf { key, value } => (*key, *value),
+            _ => panic!("leaves should contain only leaves"),
+        })
+        .collect()
+}
+
+fn expected_differences(
+    old_leaves: &[TestTrie],
+    new_leaves: &[TestTrie],
+) -> Vec<Difference<TestKey, TestValue>> {
+    let old_pairs = pairs_of(old_leaves);
+    let new_pairs = pairs_of(new_leaves);
+    let mut keys: Vec<TestKey> = old_pairs.keys().chain(new_pairs.keys()).cloned().collect();
+    keys.sort();
+    keys.dedup();
+    keys.into_iter()
+        .filter_map(|key| match (old_pairs.get(&key), new_pairs.get(&key)) {
+            (Some(value), None) => Some(Difference::Removed { key, value: *value }),
+            (None, Some(value)) => Some(Difference::Added { key, value: *value }),
+            (Some(old_value), Some(new_value)) if old_value != new_value => {
+                Some(Difference::Modified {
+                    key,
+                    old_value: *old_value,
+                    new_value: *new_value,
+                })
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+fn check_diff<'a, R, S, E>(
+    correlation_id: CorrelationId,
+    environment: &'a R,
+    store: &S,
+    old_root: &Blake2bHash,
+    new_root: &Blake2bHash,
+    expected: DiffResult<TestKey, TestValue>,
+) -> Result<(), E>
+where
+    R: TransactionSource<'a, Handle = S::Handle>,
+    S: TrieStore<TestKey, TestValue>,
+    S::Error: From<R::Error>,
+    E: From<R::Error> + From<S::Error> + From<contract_ffi::bytesrepr::Error>,
+{
+    let txn: R::ReadTransaction = environment.create_read_txn()?;
+    let actual = diff::<TestKey, TestValue, _, _, E>(
+        correlation_id,
+        &txn,
+        store,
+        old_root,
+        new_root,
+        &[],
+        usize::max_value(),
+    )?;
+    txn.commit()?;
+    assert_eq!(actual, expected);
+    Ok(())
+}
+
+/// Diffs `old_root` against `new_root` in pages of `page_size`, each starting at the key of the
+/// first difference left out of the previous page, and returns all the differences found.
+fn diff_in_pages<'a, R, S, E>(
+    correlation_id: CorrelationId,
+    environment: &'a R,
+    store: &S,
+    old_root: &Blake2bHash,
+    new_root: &Blake2bHash,
+    page_size: usize,
+) -> Result<Vec<Difference<TestKey, TestValue>>, E>
+where
+    R: TransactionSource<'a, Handle = S::Handle>,
+    S: TrieStore<TestKey, TestValue>,
+    S::Error: From<R::Error>,
+    E: From<R::Error> + From<S::Error> + From<contract_ffi::bytesrepr::Error>,
+{
+    let mut ret = Vec::new();
+    let mut start = Vec::new();
+    loop {
+        let txn: R::ReadTransaction = environment.create_read_txn()?;
+        let mut page = match diff::<TestKey, TestValue, _, _, E>(
+            correlation_id,
+            &txn,
+            store,
+            old_root,
+            new_root,
+            &start,
+            page_size + 1,
+        )? {
+            DiffResult::Success(differences) => differences,
+            DiffResult::RootNotFound(_) => panic!("roots should be found"),
+        };
+        txn.commit()?;
+        assert!(page.len() <= page_size + 1);
+        let next = if page.len() > page_size {
+            page.pop()
+        } else {
+            None
+        };
+        ret.append(&mut page);
+        match next {
+            Some(difference) => start = difference.key().to_bytes()?,
+            None => return Ok(ret),
+        }
+    }
+}
+
+/// Returns the roots of all the test tries along with all of their elements.
+fn all_test_tries() -> (Vec<Blake2bHash>, Vec<HashedTestTrie>) {
+    let mut roots = Vec::new();
+    let mut tries = Vec::new();
+    for generator in &TEST_TRIE_GENERATORS {
+        let (root_hash, mut generated) = generator().unwrap();
+        roots.push(root_hash);
+        tries.append(&mut generated);
+    }
+    (roots, tries)
+}
+
+persistent_backend_tests! {
+    TestContext;
+
+    #[test]
+    fn diff_between_n_leaf_tries_has_expected_differences() {
+        let correlation_id = CorrelationId::new();
+        let (roots, tries) = all_test_tries();
+        let context = TestContext::new(&tries).unwrap();
+        let test_leaves = TEST_LEAVES;
+
+        for (old_len, old_root) in roots.iter().enumerate() {
+            for (new_len, new_root) in roots.iter().enumerate() {
+                let expected =
+                    expected_differences(&test_leaves[..old_len], &test_leaves[..new_len]);
+                check_diff::<_, _, error::Error>(
+                    correlation_id,
+                    &context.environment,
+                    &context.store,
+                    old_root,
+                    new_root,
+                    DiffResult::Success(expected),
+                )
+                .unwrap();
+            }
+        }
+    }
+}
+
+#[test]
+fn in_memory_diff_between_n_leaf_tries_has_expected_differences() {
+    let correlation_id = CorrelationId::new();
+    let (roots, tries) = all_test_tries();
+    let context = InMemoryTestContext::new(&tries).unwrap();
+    let test_leaves = TEST_LEAVES;
+
+    for (old_len, old_root) in roots.iter().enumerate() {
+        for (new_len, new_root) in roots.iter().enumerate() {
+            let expected = expected_differences(&test_leaves[..old_len], &test_leaves[..new_len]);
+            check_diff::<_, _, in_memory::Error>(
+                correlation_id,
+                &context.environment,
+                &context.store,
+                old_root,
+                new_root,
+                DiffResult::Success(expected),
+            )
+            .unwrap();
+        }
+    }
+}
+
+#[test]
+fn in_memory_diff_in_pages_has_expected_differences() {
+    let correlation_id = CorrelationId::new();
+    let (roots, tries) = all_test_tries();
+    let context = InMemoryTestContext::new(&tries).unwrap();
+    let test_leaves = TEST_LEAVES;
+
+    for page_size in 1..=3 {
+        for (old_len, old_root) in roots.iter().enumerate() {
+            for (new_len, new_root) in roots.iter().enumerate() {
+                let expected =
+                    expected_differences(&test_leaves[..old_len], &test_leaves[..new_len]);
+                let actual = diff_in_pages::<_, _, in_memory::Error>(
+                    correlation_id,
+                    &context.environment,
+                    &context.store,
+                    old_root,
+                    new_root,
+                    page_size,
+                )
+                .unwrap();
+                assert_eq!(actual, expected);
+            }
+        }
+    }
+}
+
+#[test]
+fn in_memory_diff_reports_modified_values() {
+    let correlation_id = CorrelationId::new();
+    let (old_root, tries) = TEST_TRIE_GENERATORS[TEST_LEAVES_LENGTH]().unwrap();
+    let context = InMemoryTestContext::new(&tries).unwrap();
+    let test_leaves = TEST_LEAVES;
+    let updated_leaves = TEST_LEAVES_UPDATED;
+
+    // Update every other leaf, then add a leaf which is not in the old trie.
+    let to_write: Vec<TestTrie> = updated_leaves
+        .iter()
+        .step_by(2)
+        .cloned()
+        .chain(TEST_LEAVES_ADJACENTS.iter().take(1).cloned())
+        .collect();
+    let new_root = write_leaves::<_, _, _, _, in_memory::Error>(
+        correlation_id,
+        &context.environment,
+        &context.store,
+        &old_root,
+        &to_write,
+    )
+    .unwrap()
+    .into_iter()
+    .filter_map(|write_result| match write_result {
+        WriteResult::Written(root_hash) => Some(root_hash),
+        _ => None,
+    })
+    .last()
+    .unwrap();
+
+    let new_leaves: Vec<TestTrie> = test_leaves.iter().chain(&to_write).cloned().collect();
+
+    check_diff::<_, _, in_memory::Error>(
+        correlation_id,
+        &context.environment,
+        &context.store,
+        &old_root,
+        &new_root,
+        DiffResult::Success(expected_differences(&test_leaves, &new_leaves)),
+    )
+    .unwrap();
+    check_diff::<_, _, in_memory::Error>(
+        correlation_id,
+        &context.environment,
+        &context.store,
+        &new_root,
+        &old_root,
+        DiffResult::Success(expected_differences(&new_leaves, &test_leaves)),
+    )
+    .unwrap();
+}
+
+#[test]
+fn in_memory_diff_with_missing_root_is_root_not_found() {
+    let correlation_id = CorrelationId::new();
+    let (root_hash, tries) = create_6_leaf_trie().unwrap();
+    let context = InMemoryTestContext::new(&tries).unwrap();
+    let missing_root = Blake2bHash::new(b"missing root");
+
+    check_diff::<_, _, in_memory::Error>(
+        correlation_id,
+        &context.environment,
+        &context.store,
+        &root_hash,
+        &missing_root,
+        DiffResult::RootNotFound(missing_root),
+    )
+    .unwrap();
+}
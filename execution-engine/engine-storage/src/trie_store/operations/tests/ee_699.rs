@@ -338,39 +338,14 @@ mod proptests {
     }
 
     use super::*;
+    #[cfg(feature = "use_rocksdb")]
+    use crate::trie_store::operations::tests::RocksDbTestContext;
     use crate::{
         error::{self, in_memory},
         trie_store::operations::tests::{self, InMemoryTestContext, LmdbTestContext},
     };
     use std::ops::RangeInclusive;
 
-    fn lmdb_roundtrip_succeeds(pairs: &[(TestKey, TestValue)]) -> bool {
-        let correlation_id = CorrelationId::new();
-        let (root_hash, tries) = create_0_leaf_trie().unwrap();
-        let context = LmdbTestContext::new(&tries).unwrap();
-        let mut states_to_check = vec![];
-
-        let root_hashes = tests::write_pairs::<_, _, _, _, error::Error>(
-            correlation_id,
-            &context.environment,
-            &context.store,
-            &root_hash,
-            pairs,
-        )
-        .unwrap();
-
-        states_to_check.extend(root_hashes);
-
-        tests::check_pairs::<_, _, _, _, error::Error>(
-            correlation_id,
-            &context.environment,
-            &context.store,
-            &states_to_check,
-            &pairs,
-        )
-        .unwrap()
-    }
-
     fn in_memory_roundtrip_succeeds(pairs: &[(TestKey, TestValue)]) -> bool {
         let correlation_id = CorrelationId::new();
         let (root_hash, tries) = create_0_leaf_trie().unwrap();
@@ -407,10 +382,43 @@ mod proptests {
         fn prop_in_memory_roundtrip_succeeds(inputs in vec((test_key_arb(), test_value_arb()), get_range())) {
             assert!(in_memory_roundtrip_succeeds(&inputs));
         }
+    }
 
-        #[test]
-        fn prop_lmdb_roundtrip_succeeds(inputs in vec((test_key_arb(), test_value_arb()), get_range())) {
-            assert!(lmdb_roundtrip_succeeds(&inputs));
+    persistent_backend_tests! {
+        TestContext;
+
+        fn roundtrip_succeeds(pairs: &[(TestKey, TestValue)]) -> bool {
+            let correlation_id = CorrelationId::new();
+            let (root_hash, tries) = create_0_leaf_trie().unwrap();
+            let context = TestContext::new(&tries).unwrap();
+            let mut states_to_check = vec![];
+
+            let root_hashes = tests::write_pairs::<_, _, _, _, error::Error>(
+                correlation_id,
+                &context.environment,
+                &context.store,
+                &root_hash,
+                pairs,
+            )
+            .unwrap();
+
+            states_to_check.extend(root_hashes);
+
+            tests::check_pairs::<_, _, _, _, error::Error>(
+                correlation_id,
+                &context.environment,
+                &context.store,
+                &states_to_check,
+                &pairs,
+            )
+            .unwrap()
+        }
+
+        proptest! {
+            #[test]
+            fn prop_roundtrip_succeeds(inputs in vec((test_key_arb(), test_value_arb()), get_range())) {
+                assert!(roundtrip_succeeds(&inputs));
+            }
         }
     }
 }
@@ -0,0 +1,128 @@
+use super::*;
+use crate::{
+    error::{self, in_memory},
+    trie_store::operations::iter_prefix,
+};
+
+const PREFIXES: &[&[u8]] = &[&[], &[0], &[0, 0], &[0, 0, 0], &[0, 0, 0, 0, 0, 0, 1], &[1]];
+
+const STARTS: &[&[u8]] = &[&[], &[0, 0, 0, 0, 0, 0, 1], &[0, 0, 1], &[0, 1], &[255]];
+
+fn check_iter_prefix<'a, R, S, E>(
+    correlation_id: CorrelationId,
+    environment: &'a R,
+    store: &S,
+    root_hash: &Blake2bHash,
+    leaves: &[TestTrie],
+    prefix: &[u8],
+    start: &[u8],
+) -> Result<(), E>
+where
+    R: TransactionSource<'a, Handle = S::Handle>,
+    S: TrieStore<TestKey, TestValue>,
+    S::Error: From<R::Error>,
+    E: From<R::Error> + From<S::Error> + From<contract_ffi::bytesrepr::Error> + std::fmt::Debug,
+{
+    let expected = {
+        let mut tmp = leaves
+            .iter()
+            .filter_map(|leaf| match leaf {
+                Trie::Leaf { key, value } => Some((*key, *value)),
+                _ => None,
+            })
+            .filter(|(key, _)| key.0.starts_with(prefix) && key.0[..] >= *start)
+            .collect::<Vec<(TestKey, TestValue)>>();
+        tmp.sort_by_key(|(key, _)| *key);
+        tmp
+    };
+
+    let txn: R::ReadTransaction = environment.create_read_txn()?;
+    let actual = iter_prefix::<TestKey, TestValue, _, _, E>(
+        correlation_id,
+        &txn,
+        store,
+        root_hash,
+        prefix,
+        start,
+    )?
+    .collect::<Result<Vec<(TestKey, TestValue)>, E>>()?;
+    txn.commit()?;
+
+    assert_eq!(actual, expected, "prefix: {:?}, start: {:?}", prefix, start);
+    Ok(())
+}
+
+persistent_backend_tests! {
+    TestContext;
+
+    #[test]
+    fn iter_prefix_of_n_leaf_trie_yields_matching_leaves_in_order() {
+        for (num_leaves, generator) in TEST_TRIE_GENERATORS.iter().enumerate() {
+            let correlation_id = CorrelationId::new();
+            let (root_hash, tries) = generator().unwrap();
+            let context = TestContext::new(&tries).unwrap();
+            let test_leaves = TEST_LEAVES;
+            let (used, _) = test_leaves.split_at(num_leaves);
+
+            for prefix in PREFIXES {
+                for start in STARTS {
+                    check_iter_prefix::<_, _, error::Error>(
+                        correlation_id,
+                        &context.environment,
+                        &context.store,
+                        &root_hash,
+                        used,
+                        prefix,
+                        start,
+                    )
+                    .unwrap();
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn in_memory_iter_prefix_of_n_leaf_trie_yields_matching_leaves_in_order() {
+    for (num_leaves, generator) in TEST_TRIE_GENERATORS.iter().enumerate() {
+        let correlation_id = CorrelationId::new();
+        let (root_hash, tries) = generator().unwrap();
+        let context = InMemoryTestContext::new(&tries).unwrap();
+        let test_leaves = TEST_LEAVES;
+        let (used, _) = test_leaves.split_at(num_leaves);
+
+        for prefix in PREFIXES {
+            for start in STARTS {
+                check_iter_prefix::<_, _, in_memory::Error>(
+                    correlation_id,
+                    &context.environment,
+                    &context.store,
+                    &root_hash,
+                    used,
+                    prefix,
+                    start,
+                )
+                .unwrap();
+            }
+        }
+    }
+}
+
+#[test]
+fn in_memory_iter_prefix_of_missing_root_is_empty() {
+    let correlation_id = CorrelationId::new();
+    let context = InMemoryTestContext::new(EMPTY_HASHED_TEST_TRIES).unwrap();
+    let missing_root = Blake2bHash::new(b"missing root");
+
+    let txn = context.environment.create_read_txn().unwrap();
+    let mut iter = iter_prefix::<TestKey, TestValue, _, _, in_memory::Error>(
+        correlation_id,
+        &txn,
+        &context.store,
+        &missing_root,
+        &[],
+        &[],
+    )
+    .unwrap();
+    assert!(iter.next().is_none());
+}
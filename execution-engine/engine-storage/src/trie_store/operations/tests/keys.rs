@@ -1,6 +1,8 @@
 mod partial_tries {
     use engine_shared::newtypes::CorrelationId;
 
+    #[cfg(feature = "use_rocksdb")]
+    use crate::trie_store::operations::tests::RocksDbTestContext;
     use crate::{
         error::{self, in_memory},
         transaction_source::{Transaction, TransactionSource},
@@ -14,38 +16,42 @@ mod partial_tries {
         },
     };
 
-    #[test]
-    fn lmdb_keys_from_n_leaf_partial_trie_had_expected_results() {
-        for (num_leaves, generator) in TEST_TRIE_GENERATORS.iter().enumerate() {
-            let correlation_id = CorrelationId::new();
-            let (root_hash, tries) = generator().unwrap();
-            let context = LmdbTestContext::new(&tries).unwrap();
-            let test_leaves = TEST_LEAVES;
-            let (used, _) = test_leaves.split_at(num_leaves);
+    persistent_backend_tests! {
+        TestContext;
 
-            let expected = {
-                let mut tmp = used
-                    .iter()
-                    .filter_map(Trie::key)
-                    .cloned()
-                    .collect::<Vec<TestKey>>();
-                tmp.sort();
-                tmp
-            };
-            let actual = {
-                let txn = context.environment.create_read_txn().unwrap();
-                let mut tmp = operations::keys::<TestKey, TestValue, _, _, error::Error>(
-                    correlation_id,
-                    &txn,
-                    &context.store,
-                    &root_hash,
-                )
-                .unwrap();
-                txn.commit().unwrap();
-                tmp.sort();
-                tmp
-            };
-            assert_eq!(actual, expected);
+        #[test]
+        fn keys_from_n_leaf_partial_trie_had_expected_results() {
+            for (num_leaves, generator) in TEST_TRIE_GENERATORS.iter().enumerate() {
+                let correlation_id = CorrelationId::new();
+                let (root_hash, tries) = generator().unwrap();
+                let context = TestContext::new(&tries).unwrap();
+                let test_leaves = TEST_LEAVES;
+                let (used, _) = test_leaves.split_at(num_leaves);
+
+                let expected = {
+                    let mut tmp = used
+                        .iter()
+                        .filter_map(Trie::key)
+                        .cloned()
+                        .collect::<Vec<TestKey>>();
+                    tmp.sort();
+                    tmp
+                };
+                let actual = {
+                    let txn = context.environment.create_read_txn().unwrap();
+                    let mut tmp = operations::keys::<TestKey, TestValue, _, _, error::Error>(
+                        correlation_id,
+                        &txn,
+                        &context.store,
+                        &root_hash,
+                    )
+                    .unwrap();
+                    txn.commit().unwrap();
+                    tmp.sort();
+                    tmp
+                };
+                assert_eq!(actual, expected);
+            }
         }
     }
 
@@ -0,0 +1,208 @@
+use super::*;
+use crate::{
+    error::{self, in_memory},
+    trie_store::operations::{missing_trie_keys, MissingTrieKeys},
+};
+
+fn check_missing<'a, R, S, E>(
+    correlation_id: CorrelationId,
+    environment: &'a R,
+    store: &S,
+    trie_keys: &[Blake2bHash],
+    expected: &[Blake2bHash],
+) -> Result<(), E>
+where
+    R: TransactionSource<'a, Handle = S::Handle>,
+    S: TrieStore<TestKey, TestValue>,
+    S::Error: From<R::Error>,
+    E: From<R::Error> + From<S::Error> + From<contract_ffi::bytesrepr::Error>,
+{
+    let txn: R::ReadTransaction = environment.create_read_txn()?;
+    let MissingTrieKeys {
+        missing: mut actual,
+        unexplored,
+    } = missing_trie_keys::<TestKey, TestValue, _, _, E>(
+        correlation_id,
+        &txn,
+        store,
+        trie_keys,
+        usize::max_value(),
+    )?;
+    txn.commit()?;
+    assert!(unexplored.is_empty());
+    actual.sort();
+    let mut expected = expected.to_vec();
+    expected.sort();
+    assert_eq!(actual, expected);
+    Ok(())
+}
+
+persistent_backend_tests! {
+    TestContext;
+
+    #[test]
+    fn n_leaf_trie_has_no_missing_trie_keys() {
+        for generator in &TEST_TRIE_GENERATORS {
+            let correlation_id = CorrelationId::new();
+            let (root_hash, tries) = generator().unwrap();
+            let context = TestContext::new(&tries).unwrap();
+
+            check_missing::<_, _, error::Error>(
+                correlation_id,
+                &context.environment,
+                &context.store,
+                &[root_hash],
+                &[],
+            )
+            .unwrap();
+        }
+    }
+
+    #[test]
+    fn missing_trie_keys_syncs_trie_from_other_store() {
+        let correlation_id = CorrelationId::new();
+        let (root_hash, tries) = create_6_leaf_trie().unwrap();
+        let source = TestContext::new(&tries).unwrap();
+        let target = TestContext::new(EMPTY_HASHED_TEST_TRIES).unwrap();
+
+        // Each round only walks the elements fetched in the previous one, plus whatever was left
+        // unexplored when the limit was reached.
+        let mut rounds = 0;
+        let mut frontier = vec![root_hash];
+        while !frontier.is_empty() {
+            let MissingTrieKeys {
+                missing,
+                unexplored,
+            } = {
+                let txn = target.environment.create_read_txn().unwrap();
+                let missing = missing_trie_keys::<TestKey, TestValue, _, _, error::Error>(
+                    correlation_id,
+                    &txn,
+                    &target.store,
+                    &frontier,
+                    2,
+                )
+                .unwrap();
+                txn.commit().unwrap();
+                missing
+            };
+            assert!(missing.len() <= 2);
+            rounds += 1;
+
+            let fetched: Vec<HashedTestTrie> = {
+                let txn = source.environment.create_read_txn().unwrap();
+                let fetched = missing
+                    .iter()
+                    .map(|hash| {
+                        let trie: TestTrie = source.store.get(&txn, hash).unwrap().unwrap();
+                        HashedTrie::new(trie).unwrap()
+                    })
+                    .collect();
+                txn.commit().unwrap();
+                fetched
+            };
+            target.update(&fetched).unwrap();
+
+            frontier = missing;
+            frontier.extend(unexplored);
+        }
+
+        assert!(rounds > 1);
+        let txn = target.environment.create_read_txn().unwrap();
+        for HashedTrie { hash, trie } in &tries {
+            let stored: Option<TestTrie> = target.store.get(&txn, hash).unwrap();
+            assert_eq!(stored.as_ref(), Some(trie));
+        }
+        txn.commit().unwrap();
+    }
+}
+
+#[test]
+fn in_memory_n_leaf_trie_has_no_missing_trie_keys() {
+    for generator in &TEST_TRIE_GENERATORS {
+        let correlation_id = CorrelationId::new();
+        let (root_hash, tries) = generator().unwrap();
+        let context = InMemoryTestContext::new(&tries).unwrap();
+
+        check_missing::<_, _, in_memory::Error>(
+            correlation_id,
+            &context.environment,
+            &context.store,
+            &[root_hash],
+            &[],
+        )
+        .unwrap();
+    }
+}
+
+#[test]
+fn in_memory_missing_trie_keys_has_each_omitted_element() {
+    let correlation_id = CorrelationId::new();
+    let (root_hash, tries) = create_6_leaf_trie().unwrap();
+
+    for omitted in &tries {
+        let remaining: Vec<HashedTestTrie> = tries
+            .iter()
+            .filter(|hashed_trie| hashed_trie.hash != omitted.hash)
+            .cloned()
+            .collect();
+        let context = InMemoryTestContext::new(&remaining).unwrap();
+
+        // The descendants of the omitted element are still in the store, so only the omitted
+        // element itself is missing.
+        check_missing::<_, _, in_memory::Error>(
+            correlation_id,
+            &context.environment,
+            &context.store,
+            &[root_hash],
+            &[omitted.hash],
+        )
+        .unwrap();
+    }
+}
+
+#[test]
+fn in_memory_missing_trie_keys_stops_at_limit() {
+    let correlation_id = CorrelationId::new();
+    let (root_hash, tries) = create_6_leaf_trie().unwrap();
+    // Store only the root and its child, so that the grandchildren are missing.
+    let mut stored_hashes = vec![root_hash];
+    for hashed_trie in &tries {
+        if hashed_trie.hash == root_hash {
+            stored_hashes.extend(hashed_trie.trie.child_hashes());
+        }
+    }
+    let stored: Vec<HashedTestTrie> = tries
+        .iter()
+        .filter(|hashed_trie| stored_hashes.contains(&hashed_trie.hash))
+        .cloned()
+        .collect();
+    let context = InMemoryTestContext::new(&stored).unwrap();
+
+    let txn = context.environment.create_read_txn().unwrap();
+    let all = missing_trie_keys::<TestKey, TestValue, _, _, in_memory::Error>(
+        correlation_id,
+        &txn,
+        &context.store,
+        &[root_hash],
+        usize::max_value(),
+    )
+    .unwrap();
+    assert!(all.missing.len() > 1);
+    assert!(all.unexplored.is_empty());
+
+    let limited = missing_trie_keys::<TestKey, TestValue, _, _, in_memory::Error>(
+        correlation_id,
+        &txn,
+        &context.store,
+        &[root_hash],
+        1,
+    )
+    .unwrap();
+    txn.commit().unwrap();
+    assert_eq!(limited.missing.len(), 1);
+    assert_eq!(
+        limited.missing.len() + limited.unexplored.len(),
+        all.missing.len()
+    );
+}
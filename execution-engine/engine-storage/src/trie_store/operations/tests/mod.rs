@@ -1,9 +1,17 @@
+mod diff;
 mod ee_699;
+mod iter_prefix;
 mod keys;
+mod missing_trie_keys;
 mod proptests;
+mod reachable;
 mod read;
+mod read_with_proof;
 mod scan;
+mod trie_stats;
+mod verify;
 mod write;
+mod write_batch;
 
 use std::{collections::HashMap, convert};
 
@@ -25,11 +33,15 @@ use crate::{
         self,
         in_memory::InMemoryTrieStore,
         lmdb::LmdbTrieStore,
-        operations::{self, read, write, ReadResult, WriteResult},
+        operations::{self, read, write, write_batch, ReadResult, WriteResult},
         TrieStore,
     },
     TEST_MAP_SIZE,
 };
+#[cfg(feature = "use_rocksdb")]
+use crate::{
+    transaction_source::rocksdb::RocksDbEnvironment, trie_store::rocksdb::RocksDbTrieStore,
+};
 
 const TEST_KEY_LENGTH: usize = 7;
 
@@ -506,8 +518,8 @@ struct LmdbTestContext {
 impl LmdbTestContext {
     fn new<K, V>(tries: &[HashedTrie<K, V>]) -> Result<Self, failure::Error>
     where
-        K: FromBytes + ToBytes,
-        V: FromBytes + ToBytes,
+        K: FromBytes + ToBytes + Clone + Send + Sync + 'static,
+        V: FromBytes + ToBytes + Clone + Send + Sync + 'static,
     {
         let _temp_dir = tempdir()?;
         let environment = LmdbEnvironment::new(&_temp_dir.path().to_path_buf(), *TEST_MAP_SIZE)?;
@@ -520,6 +532,42 @@ impl LmdbTestContext {
         })
     }
 
+    fn update<K, V>(&self, tries: &[HashedTrie<K, V>]) -> Result<(), failure::Error>
+    where
+        K: ToBytes + Clone + Send + Sync + 'static,
+        V: ToBytes + Clone + Send + Sync + 'static,
+    {
+        put_tries::<_, _, _, _, error::Error>(&self.environment, &self.store, tries)?;
+        Ok(())
+    }
+}
+
+// A context for holding rocksdb-based test resources
+#[cfg(feature = "use_rocksdb")]
+struct RocksDbTestContext {
+    _temp_dir: TempDir,
+    environment: RocksDbEnvironment,
+    store: RocksDbTrieStore,
+}
+
+#[cfg(feature = "use_rocksdb")]
+impl RocksDbTestContext {
+    fn new<K, V>(tries: &[HashedTrie<K, V>]) -> Result<Self, failure::Error>
+    where
+        K: ToBytes,
+        V: ToBytes,
+    {
+        let _temp_dir = tempdir()?;
+        let environment = RocksDbEnvironment::new(&_temp_dir.path().to_path_buf())?;
+        let store = RocksDbTrieStore::new(&environment, None);
+        put_tries::<_, _, _, _, error::Error>(&environment, &store, tries)?;
+        Ok(RocksDbTestContext {
+            _temp_dir,
+            environment,
+            store,
+        })
+    }
+
     fn update<K, V>(&self, tries: &[HashedTrie<K, V>]) -> Result<(), failure::Error>
     where
         K: ToBytes,
@@ -784,6 +832,28 @@ where
     Ok(results)
 }
 
+fn write_pairs_in_batch<'a, K, V, R, S, E>(
+    correlation_id: CorrelationId,
+    environment: &'a R,
+    store: &S,
+    root_hash: &Blake2bHash,
+    pairs: &[(K, V)],
+) -> Result<WriteResult, E>
+where
+    K: ToBytes + FromBytes + Clone,
+    V: ToBytes + FromBytes + Clone,
+    R: TransactionSource<'a, Handle = S::Handle>,
+    S: TrieStore<K, V>,
+    S::Error: From<R::Error>,
+    E: From<R::Error> + From<S::Error> + From<contract_ffi::bytesrepr::Error>,
+{
+    let mut txn = environment.create_read_write_txn()?;
+    let write_result =
+        write_batch::<_, _, _, _, E>(correlation_id, &mut txn, store, root_hash, pairs.to_vec())?;
+    txn.commit()?;
+    Ok(write_result)
+}
+
 fn writes_to_n_leaf_empty_trie_had_expected_results<'a, K, V, R, S, E>(
     correlation_id: CorrelationId,
     environment: &'a R,
@@ -22,13 +22,13 @@ fn get_range() -> RangeInclusive<usize> {
     RangeInclusive::new(start, end)
 }
 
-fn lmdb_roundtrip_succeeds(pairs: &[(TestKey, TestValue)]) -> bool {
+fn in_memory_roundtrip_succeeds(pairs: &[(TestKey, TestValue)]) -> bool {
     let correlation_id = CorrelationId::new();
     let (root_hash, tries) = TEST_TRIE_GENERATORS[0]().unwrap();
-    let context = LmdbTestContext::new(&tries).unwrap();
+    let context = InMemoryTestContext::new(&tries).unwrap();
     let mut states_to_check = vec![];
 
-    let root_hashes = write_pairs::<_, _, _, _, error::Error>(
+    let root_hashes = write_pairs::<_, _, _, _, in_memory::Error>(
         correlation_id,
         &context.environment,
         &context.store,
@@ -39,7 +39,7 @@ fn lmdb_roundtrip_succeeds(pairs: &[(TestKey, TestValue)]) -> bool {
 
     states_to_check.extend(root_hashes);
 
-    check_pairs::<_, _, _, _, error::Error>(
+    check_pairs::<_, _, _, _, in_memory::Error>(
         correlation_id,
         &context.environment,
         &context.store,
@@ -49,37 +49,64 @@ fn lmdb_roundtrip_succeeds(pairs: &[(TestKey, TestValue)]) -> bool {
     .unwrap()
 }
 
-fn in_memory_roundtrip_succeeds(pairs: &[(TestKey, TestValue)]) -> bool {
+fn in_memory_batch_write_matches_sequential_writes(
+    existing_pairs: &[(TestKey, TestValue)],
+    pairs: &[(TestKey, TestValue)],
+) -> bool {
     let correlation_id = CorrelationId::new();
-    let (root_hash, tries) = TEST_TRIE_GENERATORS[0]().unwrap();
+    let (empty_root_hash, tries) = TEST_TRIE_GENERATORS[0]().unwrap();
     let context = InMemoryTestContext::new(&tries).unwrap();
-    let mut states_to_check = vec![];
 
-    let root_hashes = write_pairs::<_, _, _, _, in_memory::Error>(
+    let root_hash = write_pairs::<_, _, _, _, in_memory::Error>(
+        correlation_id,
+        &context.environment,
+        &context.store,
+        &empty_root_hash,
+        existing_pairs,
+    )
+    .unwrap()
+    .last()
+    .cloned()
+    .unwrap_or(empty_root_hash);
+
+    let sequential_root_hash = write_pairs::<_, _, _, _, in_memory::Error>(
         correlation_id,
         &context.environment,
         &context.store,
         &root_hash,
         pairs,
     )
-    .unwrap();
-
-    states_to_check.extend(root_hashes);
+    .unwrap()
+    .last()
+    .cloned()
+    .unwrap_or(root_hash);
 
-    check_pairs::<_, _, _, _, in_memory::Error>(
+    let batch_root_hash = match write_pairs_in_batch::<_, _, _, _, in_memory::Error>(
         correlation_id,
         &context.environment,
         &context.store,
-        &states_to_check,
-        &pairs,
+        &root_hash,
+        pairs,
     )
     .unwrap()
+    {
+        WriteResult::Written(batch_root_hash) => batch_root_hash,
+        WriteResult::AlreadyExists => root_hash,
+        WriteResult::RootNotFound => panic!("write_pairs_in_batch given an invalid root"),
+    };
+
+    batch_root_hash == sequential_root_hash
 }
 
 fn test_key_arb() -> impl Strategy<Value = TestKey> {
     array::uniform7(any::<u8>()).prop_map(TestKey)
 }
 
+/// Keys drawn from a small alphabet, so that their paths share long prefixes.
+fn colliding_test_key_arb() -> impl Strategy<Value = TestKey> {
+    array::uniform7(0u8..3).prop_map(TestKey)
+}
+
 fn test_value_arb() -> impl Strategy<Value = TestValue> {
     array::uniform6(any::<u8>()).prop_map(TestValue)
 }
@@ -91,7 +118,48 @@ proptest! {
     }
 
     #[test]
-    fn prop_lmdb_roundtrip_succeeds(inputs in vec((test_key_arb(), test_value_arb()), get_range())) {
-        assert!(lmdb_roundtrip_succeeds(&inputs));
+    fn prop_in_memory_batch_write_matches_sequential_writes(
+        existing in vec((colliding_test_key_arb(), test_value_arb()), get_range()),
+        inputs in vec((colliding_test_key_arb(), test_value_arb()), get_range()),
+    ) {
+        assert!(in_memory_batch_write_matches_sequential_writes(&existing, &inputs));
+    }
+}
+
+persistent_backend_tests! {
+    TestContext;
+
+    fn roundtrip_succeeds(pairs: &[(TestKey, TestValue)]) -> bool {
+        let correlation_id = CorrelationId::new();
+        let (root_hash, tries) = TEST_TRIE_GENERATORS[0]().unwrap();
+        let context = TestContext::new(&tries).unwrap();
+        let mut states_to_check = vec![];
+
+        let root_hashes = write_pairs::<_, _, _, _, error::Error>(
+            correlation_id,
+            &context.environment,
+            &context.store,
+            &root_hash,
+            pairs,
+        )
+        .unwrap();
+
+        states_to_check.extend(root_hashes);
+
+        check_pairs::<_, _, _, _, error::Error>(
+            correlation_id,
+            &context.environment,
+            &context.store,
+            &states_to_check,
+            &pairs,
+        )
+        .unwrap()
+    }
+
+    proptest! {
+        #[test]
+        fn prop_roundtrip_succeeds(inputs in vec((test_key_arb(), test_value_arb()), get_range())) {
+            assert!(roundtrip_succeeds(&inputs));
+        }
     }
 }
@@ -0,0 +1,120 @@
+use std::collections::HashSet;
+
+use super::*;
+use crate::{
+    error::{self, in_memory},
+    trie_store::operations::reachable_hashes,
+};
+
+fn check_reachable<'a, K, V, R, S, E>(
+    correlation_id: CorrelationId,
+    environment: &'a R,
+    store: &S,
+    roots: &[Blake2bHash],
+    expected: &HashSet<Blake2bHash>,
+) -> Result<(), E>
+where
+    K: ToBytes + FromBytes,
+    V: ToBytes + FromBytes,
+    R: TransactionSource<'a, Handle = S::Handle>,
+    S: TrieStore<K, V>,
+    S::Error: From<R::Error>,
+    E: From<R::Error> + From<S::Error> + From<contract_ffi::bytesrepr::Error>,
+{
+    let txn: R::ReadTransaction = environment.create_read_txn()?;
+    let actual = reachable_hashes::<K, V, _, _, E>(correlation_id, &txn, store, roots)?;
+    assert_eq!(actual, *expected);
+    txn.commit()?;
+    Ok(())
+}
+
+fn hashes_of(tries: &[HashedTestTrie]) -> HashSet<Blake2bHash> {
+    tries.iter().map(|hashed_trie| hashed_trie.hash).collect()
+}
+
+persistent_backend_tests! {
+    TestContext;
+
+    #[test]
+    fn reachable_from_n_leaf_trie_is_whole_trie() {
+        for generator in &TEST_TRIE_GENERATORS {
+            let correlation_id = CorrelationId::new();
+            let (root_hash, tries) = generator().unwrap();
+            let context = TestContext::new(&tries).unwrap();
+
+            check_reachable::<TestKey, TestValue, _, _, error::Error>(
+                correlation_id,
+                &context.environment,
+                &context.store,
+                &[root_hash],
+                &hashes_of(&tries),
+            )
+            .unwrap();
+        }
+    }
+
+    #[test]
+    fn reachable_excludes_tries_of_other_roots() {
+        let correlation_id = CorrelationId::new();
+        let (small_root_hash, small_tries) = create_2_leaf_trie().unwrap();
+        let (large_root_hash, large_tries) = create_6_leaf_trie().unwrap();
+        let context = TestContext::new(&small_tries).unwrap();
+        context.update(&large_tries).unwrap();
+
+        check_reachable::<TestKey, TestValue, _, _, error::Error>(
+            correlation_id,
+            &context.environment,
+            &context.store,
+            &[small_root_hash],
+            &hashes_of(&small_tries),
+        )
+        .unwrap();
+
+        let both: HashSet<Blake2bHash> = hashes_of(&small_tries)
+            .union(&hashes_of(&large_tries))
+            .cloned()
+            .collect();
+        check_reachable::<TestKey, TestValue, _, _, error::Error>(
+            correlation_id,
+            &context.environment,
+            &context.store,
+            &[small_root_hash, large_root_hash],
+            &both,
+        )
+        .unwrap();
+    }
+}
+
+#[test]
+fn in_memory_reachable_from_n_leaf_trie_is_whole_trie() {
+    for generator in &TEST_TRIE_GENERATORS {
+        let correlation_id = CorrelationId::new();
+        let (root_hash, tries) = generator().unwrap();
+        let context = InMemoryTestContext::new(&tries).unwrap();
+
+        check_reachable::<TestKey, TestValue, _, _, in_memory::Error>(
+            correlation_id,
+            &context.environment,
+            &context.store,
+            &[root_hash],
+            &hashes_of(&tries),
+        )
+        .unwrap();
+    }
+}
+
+#[test]
+fn in_memory_reachable_from_unknown_root_is_empty() {
+    let correlation_id = CorrelationId::new();
+    let (_, tries) = create_6_leaf_trie().unwrap();
+    let context = InMemoryTestContext::new(&tries).unwrap();
+
+    check_reachable::<TestKey, TestValue, _, _, in_memory::Error>(
+        correlation_id,
+        &context.environment,
+        &context.store,
+        &[Blake2bHash::new(b"unknown")],
+        &HashSet::new(),
+    )
+    .unwrap();
+}
@@ -0,0 +1,193 @@
+//! This module contains tests for [`read_with_proof`] and the verification of the proofs it
+//! produces.
+
+use super::*;
+use crate::{
+    error::{self, in_memory},
+    trie::merkle_proof::{TrieMerkleProof, TrieMerkleProofError},
+    trie_store::operations::read_with_proof,
+};
+
+fn check_proofs<'a, K, V, R, S, E>(
+    correlation_id: CorrelationId,
+    environment: &'a R,
+    store: &S,
+    root: &Blake2bHash,
+    present: &[Trie<K, V>],
+    absent: &[Trie<K, V>],
+) -> Result<(), E>
+where
+    K: ToBytes + FromBytes + Eq + std::fmt::Debug + Clone,
+    V: ToBytes + FromBytes + Eq + std::fmt::Debug,
+    R: TransactionSource<'a, Handle = S::Handle>,
+    S: TrieStore<K, V>,
+    S::Error: From<R::Error>,
+    E: From<R::Error> + From<S::Error> + From<contract_ffi::bytesrepr::Error>,
+{
+    let txn: R::ReadTransaction = environment.create_read_txn()?;
+
+    for leaf in present {
+        if let Trie::Leaf { key, value } = leaf {
+            let proof = read_with_proof::<_, _, _, _, E>(correlation_id, &txn, store, root, key)?
+                .expect("root should exist");
+            assert_eq!(proof.value(), Some(value));
+            assert_eq!(proof.verify(root), Ok(Some(value)));
+        } else {
+            panic!("leaves should only contain leaves")
+        }
+    }
+
+    for leaf in absent {
+        if let Trie::Leaf { key, .. } = leaf {
+            let proof = read_with_proof::<_, _, _, _, E>(correlation_id, &txn, store, root, key)?
+                .expect("root should exist");
+            assert_eq!(proof.value(), None);
+            assert_eq!(proof.verify(root), Ok(None));
+        } else {
+            panic!("leaves should only contain leaves")
+        }
+    }
+
+    txn.commit()?;
+    Ok(())
+}
+
+persistent_backend_tests! {
+    TestContext;
+
+    #[test]
+    fn proofs_from_n_leaf_partial_trie_verify() {
+        for (num_leaves, generator) in TEST_TRIE_GENERATORS.iter().enumerate() {
+            let correlation_id = CorrelationId::new();
+            let (root_hash, tries) = generator().unwrap();
+            let context = TestContext::new(&tries).unwrap();
+            let test_leaves = TEST_LEAVES;
+            let (used, unused) = test_leaves.split_at(num_leaves);
+
+            check_proofs::<_, _, _, _, error::Error>(
+                correlation_id,
+                &context.environment,
+                &context.store,
+                &root_hash,
+                used,
+                unused,
+            )
+            .unwrap();
+        }
+    }
+}
+
+#[test]
+fn in_memory_proofs_from_n_leaf_partial_trie_verify() {
+    for (num_leaves, generator) in TEST_TRIE_GENERATORS.iter().enumerate() {
+        let correlation_id = CorrelationId::new();
+        let (root_hash, tries) = generator().unwrap();
+        let context = InMemoryTestContext::new(&tries).unwrap();
+        let test_leaves = TEST_LEAVES;
+        let (used, unused) = test_leaves.split_at(num_leaves);
+
+        check_proofs::<_, _, _, _, in_memory::Error>(
+            correlation_id,
+            &context.environment,
+            &context.store,
+            &root_hash,
+            used,
+            unused,
+        )
+        .unwrap();
+    }
+}
+
+#[test]
+fn read_with_proof_returns_none_for_unknown_root() {
+    let correlation_id = CorrelationId::new();
+    let context = InMemoryTestContext::new(EMPTY_HASHED_TEST_TRIES).unwrap();
+    let txn = context.environment.create_read_txn().unwrap();
+    let unknown_root = Blake2bHash::new(b"unknown root");
+    let test_leaves = TEST_LEAVES;
+    let key = test_leaves[0].key().unwrap();
+
+    let result = read_with_proof::<TestKey, TestValue, _, _, in_memory::Error>(
+        correlation_id,
+        &txn,
+        &context.store,
+        &unknown_root,
+        key,
+    )
+    .unwrap();
+    assert!(result.is_none());
+    txn.commit().unwrap();
+}
+
+#[test]
+fn proof_fails_to_verify_against_other_root() {
+    let correlation_id = CorrelationId::new();
+    let (root_hash, tries) = create_6_leaf_trie().unwrap();
+    let (other_root_hash, _) = create_5_leaf_trie().unwrap();
+    let context = InMemoryTestContext::new(&tries).unwrap();
+    let txn = context.environment.create_read_txn().unwrap();
+    let test_leaves = TEST_LEAVES;
+    let key = test_leaves[0].key().unwrap();
+
+    let proof = read_with_proof::<TestKey, TestValue, _, _, in_memory::Error>(
+        correlation_id,
+        &txn,
+        &context.store,
+        &root_hash,
+        key,
+    )
+    .unwrap()
+    .unwrap();
+    txn.commit().unwrap();
+
+    assert_eq!(
+        proof.verify(&other_root_hash),
+        Err(TrieMerkleProofError::HashMismatch(0))
+    );
+}
+
+#[test]
+fn tampered_proof_fails_to_verify() {
+    let correlation_id = CorrelationId::new();
+    let (root_hash, tries) = create_6_leaf_trie().unwrap();
+    let context = InMemoryTestContext::new(&tries).unwrap();
+    let txn = context.environment.create_read_txn().unwrap();
+    let test_leaves = TEST_LEAVES;
+    let key = test_leaves[0].key().unwrap();
+
+    let proof = read_with_proof::<TestKey, TestValue, _, _, in_memory::Error>(
+        correlation_id,
+        &txn,
+        &context.store,
+        &root_hash,
+        key,
+    )
+    .unwrap()
+    .unwrap();
+    txn.commit().unwrap();
+
+    let (key, mut steps) = proof.destructure();
+
+    // Replacing the value in the leaf changes its hash
+    let last = steps.len() - 1;
+    steps[last] = Trie::leaf(key, TestValue(*b"tamper"));
+    let tampered = TrieMerkleProof::new(key, steps.clone());
+    assert_eq!(
+        tampered.verify(&root_hash),
+        Err(TrieMerkleProofError::HashMismatch(last))
+    );
+
+    // Dropping the leaf leaves the proof incomplete
+    steps.truncate(last);
+    let truncated = TrieMerkleProof::new(key, steps);
+    assert_eq!(
+        truncated.verify(&root_hash),
+        Err(TrieMerkleProofError::IncompleteProof)
+    );
+
+    let empty: TrieMerkleProof<TestKey, TestValue> = TrieMerkleProof::new(key, Vec::new());
+    assert_eq!(
+        empty.verify(&root_hash),
+        Err(TrieMerkleProofError::EmptyProof)
+    );
+}
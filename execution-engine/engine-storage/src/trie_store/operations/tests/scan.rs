@@ -58,23 +58,27 @@ where
 mod partial_tries {
     use super::*;
 
-    #[test]
-    fn lmdb_scans_from_n_leaf_partial_trie_had_expected_results() {
-        for generator in &TEST_TRIE_GENERATORS {
-            let correlation_id = CorrelationId::new();
-            let (root_hash, tries) = generator().unwrap();
-            let context = LmdbTestContext::new(&tries).unwrap();
+    persistent_backend_tests! {
+        TestContext;
 
-            for leaf in TEST_LEAVES.iter() {
-                let leaf_bytes = leaf.to_bytes().unwrap();
-                check_scan::<_, _, error::Error>(
-                    correlation_id,
-                    &context.environment,
-                    &context.store,
-                    &root_hash,
-                    &leaf_bytes,
-                )
-                .unwrap()
+        #[test]
+        fn scans_from_n_leaf_partial_trie_had_expected_results() {
+            for generator in &TEST_TRIE_GENERATORS {
+                let correlation_id = CorrelationId::new();
+                let (root_hash, tries) = generator().unwrap();
+                let context = TestContext::new(&tries).unwrap();
+
+                for leaf in TEST_LEAVES.iter() {
+                    let leaf_bytes = leaf.to_bytes().unwrap();
+                    check_scan::<_, _, error::Error>(
+                        correlation_id,
+                        &context.environment,
+                        &context.store,
+                        &root_hash,
+                        &leaf_bytes,
+                    )
+                    .unwrap()
+                }
             }
         }
     }
@@ -104,28 +108,32 @@ mod partial_tries {
 mod full_tries {
     use super::*;
 
-    #[test]
-    fn lmdb_scans_from_n_leaf_full_trie_had_expected_results() {
-        let correlation_id = CorrelationId::new();
-        let context = LmdbTestContext::new(EMPTY_HASHED_TEST_TRIES).unwrap();
-        let mut states: Vec<Blake2bHash> = Vec::new();
+    persistent_backend_tests! {
+        TestContext;
 
-        for (state_index, generator) in TEST_TRIE_GENERATORS.iter().enumerate() {
-            let (root_hash, tries) = generator().unwrap();
-            context.update(&tries).unwrap();
-            states.push(root_hash);
+        #[test]
+        fn scans_from_n_leaf_full_trie_had_expected_results() {
+            let correlation_id = CorrelationId::new();
+            let context = TestContext::new(EMPTY_HASHED_TEST_TRIES).unwrap();
+            let mut states: Vec<Blake2bHash> = Vec::new();
 
-            for state in &states[..state_index] {
-                for leaf in TEST_LEAVES.iter() {
-                    let leaf_bytes = leaf.to_bytes().unwrap();
-                    check_scan::<_, _, error::Error>(
-                        correlation_id,
-                        &context.environment,
-                        &context.store,
-                        state,
-                        &leaf_bytes,
-                    )
-                    .unwrap()
+            for (state_index, generator) in TEST_TRIE_GENERATORS.iter().enumerate() {
+                let (root_hash, tries) = generator().unwrap();
+                context.update(&tries).unwrap();
+                states.push(root_hash);
+
+                for state in &states[..state_index] {
+                    for leaf in TEST_LEAVES.iter() {
+                        let leaf_bytes = leaf.to_bytes().unwrap();
+                        check_scan::<_, _, error::Error>(
+                            correlation_id,
+                            &context.environment,
+                            &context.store,
+                            state,
+                            &leaf_bytes,
+                        )
+                        .unwrap()
+                    }
                 }
             }
         }
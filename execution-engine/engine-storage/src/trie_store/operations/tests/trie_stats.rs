@@ -0,0 +1,148 @@
+use super::*;
+use crate::{
+    error::{self, in_memory},
+    trie_store::operations::{trie_stats, TrieStats},
+};
+
+fn check_trie_stats<'a, K, V, R, S, E>(
+    correlation_id: CorrelationId,
+    environment: &'a R,
+    store: &S,
+    root: &Blake2bHash,
+    expected: Option<TrieStats>,
+) -> Result<(), E>
+where
+    K: ToBytes + FromBytes,
+    V: ToBytes + FromBytes,
+    R: TransactionSource<'a, Handle = S::Handle>,
+    S: TrieStore<K, V>,
+    S::Error: From<R::Error>,
+    E: From<R::Error> + From<S::Error> + From<contract_ffi::bytesrepr::Error>,
+{
+    let txn: R::ReadTransaction = environment.create_read_txn()?;
+    let actual = trie_stats::<K, V, _, _, E>(correlation_id, &txn, store, root)?;
+    assert_eq!(actual, expected);
+    txn.commit()?;
+    Ok(())
+}
+
+/// Returns the stats of `tries`, all of which are expected to be reachable from a root found at
+/// the given depth.
+fn stats_of(tries: &[HashedTestTrie], max_depth: usize) -> TrieStats {
+    let mut stats = TrieStats {
+        max_depth,
+        ..Default::default()
+    };
+    for HashedTrie { trie, .. } in tries {
+        let trie_bytes = trie.to_bytes().unwrap().len();
+        match trie {
+            Trie::Leaf { .. } => {
+                stats.leaf_count += 1;
+                stats.leaf_bytes += trie_bytes;
+            }
+            Trie::Node { .. } => {
+                stats.node_count += 1;
+                stats.node_bytes += trie_bytes;
+            }
+            Trie::Extension { .. } => {
+                stats.extension_count += 1;
+                stats.extension_bytes += trie_bytes;
+            }
+        }
+    }
+    stats
+}
+
+persistent_backend_tests! {
+    TestContext;
+
+    #[test]
+    fn trie_stats_of_2_leaf_trie_are_expected() {
+        let correlation_id = CorrelationId::new();
+        let (root_hash, tries) = create_2_leaf_trie().unwrap();
+        let context = TestContext::new(&tries).unwrap();
+
+        let expected = stats_of(&tries, 4);
+        assert_eq!(expected.element_count(), tries.len());
+        check_trie_stats::<TestKey, TestValue, _, _, error::Error>(
+            correlation_id,
+            &context.environment,
+            &context.store,
+            &root_hash,
+            Some(expected),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn trie_stats_exclude_tries_of_other_roots() {
+        let correlation_id = CorrelationId::new();
+        let (small_root_hash, small_tries) = create_2_leaf_trie().unwrap();
+        let (_, large_tries) = create_6_leaf_trie().unwrap();
+        let context = TestContext::new(&small_tries).unwrap();
+        context.update(&large_tries).unwrap();
+
+        check_trie_stats::<TestKey, TestValue, _, _, error::Error>(
+            correlation_id,
+            &context.environment,
+            &context.store,
+            &small_root_hash,
+            Some(stats_of(&small_tries, 4)),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn trie_stats_of_unknown_root_are_none() {
+        let correlation_id = CorrelationId::new();
+        let (_, tries) = create_6_leaf_trie().unwrap();
+        let context = TestContext::new(&tries).unwrap();
+
+        check_trie_stats::<TestKey, TestValue, _, _, error::Error>(
+            correlation_id,
+            &context.environment,
+            &context.store,
+            &Blake2bHash::new(b"unknown"),
+            None,
+        )
+        .unwrap();
+    }
+}
+
+#[test]
+fn in_memory_trie_stats_of_6_leaf_trie_are_expected() {
+    let correlation_id = CorrelationId::new();
+    let (root_hash, tries) = create_6_leaf_trie().unwrap();
+    let context = InMemoryTestContext::new(&tries).unwrap();
+
+    check_trie_stats::<TestKey, TestValue, _, _, in_memory::Error>(
+        correlation_id,
+        &context.environment,
+        &context.store,
+        &root_hash,
+        Some(stats_of(&tries, 8)),
+    )
+    .unwrap();
+}
+
+#[test]
+fn in_memory_trie_stats_count_missing_elements() {
+    let correlation_id = CorrelationId::new();
+    let (root_hash, tries) = create_2_leaf_trie().unwrap();
+    // The leaves come first
+    let (missing, present) = tries.split_at(1);
+    let context = InMemoryTestContext::new(present).unwrap();
+
+    let expected = TrieStats {
+        missing_count: missing.len(),
+        ..stats_of(present, 4)
+    };
+    check_trie_stats::<TestKey, TestValue, _, _, in_memory::Error>(
+        correlation_id,
+        &context.environment,
+        &context.store,
+        &root_hash,
+        Some(expected),
+    )
+    .unwrap();
+}
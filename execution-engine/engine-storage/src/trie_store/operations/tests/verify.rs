@@ -0,0 +1,238 @@
+use super::*;
+use crate::{
+    error::{self, in_memory},
+    store::Store,
+    transaction_source::Writable,
+    trie_store::operations::{verify, TrieCorruption, TrieVerification},
+};
+
+fn check_verify<'a, K, V, R, S, E>(
+    correlation_id: CorrelationId,
+    environment: &'a R,
+    store: &S,
+    roots: &[Blake2bHash],
+) -> Result<TrieVerification, E>
+where
+    K: ToBytes + FromBytes,
+    V: ToBytes + FromBytes,
+    R: TransactionSource<'a, Handle = S::Handle>,
+    S: TrieStore<K, V>,
+    S::Error: From<R::Error>,
+    E: From<R::Error> + From<S::Error> + From<contract_ffi::bytesrepr::Error>,
+{
+    let txn: R::ReadTransaction = environment.create_read_txn()?;
+    let verification = verify::<K, V, _, _, E>(correlation_id, &txn, store, roots)?;
+    txn.commit()?;
+    Ok(verification)
+}
+
+persistent_backend_tests! {
+    TestContext;
+
+    #[test]
+    fn verify_n_leaf_trie_is_intact() {
+        for generator in &TEST_TRIE_GENERATORS {
+            let correlation_id = CorrelationId::new();
+            let (root_hash, tries) = generator().unwrap();
+            let context = TestContext::new(&tries).unwrap();
+
+            let verification = check_verify::<TestKey, TestValue, _, _, error::Error>(
+                correlation_id,
+                &context.environment,
+                &context.store,
+                &[root_hash],
+            )
+            .unwrap();
+            assert!(verification.is_intact(), "{:?}", verification);
+            assert_eq!(verification.checked, tries.len());
+        }
+    }
+
+    #[test]
+    fn verify_reports_missing_elements() {
+        let correlation_id = CorrelationId::new();
+        let (root_hash, tries) = create_2_leaf_trie().unwrap();
+        // The leaves come first, followed by the root, the extension and the node
+        let context = TestContext::new(&tries[1..]).unwrap();
+        let unknown_root = Blake2bHash::new(b"unknown");
+
+        let verification = check_verify::<TestKey, TestValue, _, _, error::Error>(
+            correlation_id,
+            &context.environment,
+            &context.store,
+            &[root_hash, unknown_root],
+        )
+        .unwrap();
+        assert_eq!(verification.corruptions.len(), 2);
+        assert!(verification.corruptions.contains(&TrieCorruption::Missing {
+            hash: tries[0].hash,
+            parent: Some(tries[4].hash),
+        }));
+        assert!(verification.corruptions.contains(&TrieCorruption::Missing {
+            hash: unknown_root,
+            parent: None,
+        }));
+    }
+
+    #[test]
+    fn verify_reports_undecodable_element() {
+        let correlation_id = CorrelationId::new();
+        let garbage = [255u8; 3];
+        let garbage_hash = Blake2bHash::new(&garbage);
+        let root: HashedTestTrie =
+            HashedTrie::new(Trie::node(&[(0, Pointer::LeafPointer(garbage_hash))])).unwrap();
+        let context = TestContext::new(&[root.clone()]).unwrap();
+        {
+            let mut txn = context.environment.create_read_write_txn().unwrap();
+            let handle = Store::<Blake2bHash, TestTrie>::handle(&context.store);
+            txn.write(handle, &garbage_hash.to_bytes().unwrap(), &garbage)
+                .unwrap();
+            txn.commit().unwrap();
+        }
+
+        let verification = check_verify::<TestKey, TestValue, _, _, error::Error>(
+            correlation_id,
+            &context.environment,
+            &context.store,
+            &[root.hash],
+        )
+        .unwrap();
+        assert_eq!(
+            verification.corruptions,
+            vec![TrieCorruption::Undecodable { hash: garbage_hash }]
+        );
+    }
+
+    #[test]
+    fn verify_reports_invalid_extensions() {
+        let correlation_id = CorrelationId::new();
+        let leaves = hash_test_tries(&TEST_LEAVES[..2]).unwrap();
+        let node = HashedTrie::new(Trie::node(&[
+            (0, Pointer::LeafPointer(leaves[0].hash)),
+            (1, Pointer::LeafPointer(leaves[1].hash)),
+        ]))
+        .unwrap();
+        let empty_affix_ext =
+            HashedTrie::new(Trie::extension(vec![], Pointer::NodePointer(node.hash))).unwrap();
+        let ext_to_ext = HashedTrie::new(Trie::extension(
+            vec![0u8, 0, 0, 0, 0],
+            Pointer::NodePointer(empty_affix_ext.hash),
+        ))
+        .unwrap();
+        let root =
+            HashedTrie::new(Trie::node(&[(0, Pointer::NodePointer(ext_to_ext.hash))])).unwrap();
+        let mut tries = leaves;
+        tries.extend(vec![
+            node,
+            empty_affix_ext.clone(),
+            ext_to_ext.clone(),
+            root.clone(),
+        ]);
+        let context = TestContext::new(&tries).unwrap();
+
+        let verification = check_verify::<TestKey, TestValue, _, _, error::Error>(
+            correlation_id,
+            &context.environment,
+            &context.store,
+            &[root.hash],
+        )
+        .unwrap();
+        assert_eq!(verification.corruptions.len(), 2);
+        assert!(verification
+            .corruptions
+            .contains(&TrieCorruption::InvalidExtension {
+                hash: empty_affix_ext.hash
+            }));
+        assert!(verification
+            .corruptions
+            .contains(&TrieCorruption::InvalidExtension {
+                hash: ext_to_ext.hash
+            }));
+    }
+}
+
+#[test]
+fn in_memory_verify_n_leaf_trie_is_intact() {
+    for generator in &TEST_TRIE_GENERATORS {
+        let correlation_id = CorrelationId::new();
+        let (root_hash, tries) = generator().unwrap();
+        let context = InMemoryTestContext::new(&tries).unwrap();
+
+        let verification = check_verify::<TestKey, TestValue, _, _, in_memory::Error>(
+            correlation_id,
+            &context.environment,
+            &context.store,
+            &[root_hash],
+        )
+        .unwrap();
+        assert!(verification.is_intact(), "{:?}", verification);
+        assert_eq!(verification.checked, tries.len());
+    }
+}
+
+#[test]
+fn in_memory_verify_reports_hash_mismatch() {
+    let correlation_id = CorrelationId::new();
+    let (root_hash, tries) = create_2_leaf_trie().unwrap();
+    let context = InMemoryTestContext::new(&tries[1..]).unwrap();
+    {
+        // Store the second leaf in place of the first one
+        let mut txn = context.environment.create_read_write_txn().unwrap();
+        context
+            .store
+            .put(&mut txn, &tries[0].hash, &tries[1].trie)
+            .unwrap();
+        txn.commit().unwrap();
+    }
+
+    let verification = check_verify::<TestKey, TestValue, _, _, in_memory::Error>(
+        correlation_id,
+        &context.environment,
+        &context.store,
+        &[root_hash],
+    )
+    .unwrap();
+    assert_eq!(
+        verification.corruptions,
+        vec![TrieCorruption::HashMismatch {
+            hash: tries[0].hash,
+            actual: tries[1].hash,
+        }]
+    );
+    assert!(verification.corruptions[0].is_damaged());
+}
+
+#[test]
+fn in_memory_verify_reports_misplaced_leaf_and_pointer_mismatch() {
+    let correlation_id = CorrelationId::new();
+    let leaves = hash_test_tries(&TEST_LEAVES[..2]).unwrap();
+    // The keys of both leaves start with 0
+    let root = HashedTrie::new(Trie::node(&[
+        (1, Pointer::LeafPointer(leaves[0].hash)),
+        (0, Pointer::NodePointer(leaves[1].hash)),
+    ]))
+    .unwrap();
+    let mut tries = leaves.clone();
+    tries.push(root.clone());
+    let context = InMemoryTestContext::new(&tries).unwrap();
+
+    let verification = check_verify::<TestKey, TestValue, _, _, in_memory::Error>(
+        correlation_id,
+        &context.environment,
+        &context.store,
+        &[root.hash],
+    )
+    .unwrap();
+    assert_eq!(verification.corruptions.len(), 2);
+    assert!(verification
+        .corruptions
+        .contains(&TrieCorruption::MisplacedLeaf {
+            hash: leaves[0].hash
+        }));
+    assert!(verification
+        .corruptions
+        .contains(&TrieCorruption::PointerMismatch {
+            hash: leaves[1].hash,
+            parent: root.hash,
+        }));
+}
@@ -5,22 +5,45 @@ mod empty_tries {
 
     use super::*;
 
-    #[test]
-    fn lmdb_non_colliding_writes_to_n_leaf_empty_trie_had_expected_results() {
-        for num_leaves in 1..=TEST_LEAVES_LENGTH {
-            let correlation_id = CorrelationId::new();
-            let (root_hash, tries) = TEST_TRIE_GENERATORS[0]().unwrap();
-            let context = LmdbTestContext::new(&tries).unwrap();
-            let initial_states = vec![root_hash];
+    persistent_backend_tests! {
+        TestContext;
+
+        #[test]
+        fn non_colliding_writes_to_n_leaf_empty_trie_had_expected_results() {
+            for num_leaves in 1..=TEST_LEAVES_LENGTH {
+                let correlation_id = CorrelationId::new();
+                let (root_hash, tries) = TEST_TRIE_GENERATORS[0]().unwrap();
+                let context = TestContext::new(&tries).unwrap();
+                let initial_states = vec![root_hash];
+
+                super::writes_to_n_leaf_empty_trie_had_expected_results::<_, _, _, _, error::Error>(
+                    correlation_id,
+                    &context.environment,
+                    &context.store,
+                    &initial_states,
+                    &TEST_LEAVES_NON_COLLIDING[..num_leaves],
+                )
+                .unwrap();
+            }
+        }
 
-            writes_to_n_leaf_empty_trie_had_expected_results::<_, _, _, _, error::Error>(
-                correlation_id,
-                &context.environment,
-                &context.store,
-                &initial_states,
-                &TEST_LEAVES_NON_COLLIDING[..num_leaves],
-            )
-            .unwrap();
+        #[test]
+        fn writes_to_n_leaf_empty_trie_had_expected_results() {
+            for num_leaves in 1..=TEST_LEAVES_LENGTH {
+                let correlation_id = CorrelationId::new();
+                let (root_hash, tries) = TEST_TRIE_GENERATORS[0]().unwrap();
+                let context = TestContext::new(&tries).unwrap();
+                let initial_states = vec![root_hash];
+
+                super::writes_to_n_leaf_empty_trie_had_expected_results::<_, _, _, _, error::Error>(
+                    correlation_id,
+                    &context.environment,
+                    &context.store,
+                    &initial_states,
+                    &TEST_LEAVES[..num_leaves],
+                )
+                .unwrap();
+            }
         }
     }
 
@@ -43,25 +66,6 @@ mod empty_tries {
         }
     }
 
-    #[test]
-    fn lmdb_writes_to_n_leaf_empty_trie_had_expected_results() {
-        for num_leaves in 1..=TEST_LEAVES_LENGTH {
-            let correlation_id = CorrelationId::new();
-            let (root_hash, tries) = TEST_TRIE_GENERATORS[0]().unwrap();
-            let context = LmdbTestContext::new(&tries).unwrap();
-            let initial_states = vec![root_hash];
-
-            writes_to_n_leaf_empty_trie_had_expected_results::<_, _, _, _, error::Error>(
-                correlation_id,
-                &context.environment,
-                &context.store,
-                &initial_states,
-                &TEST_LEAVES[..num_leaves],
-            )
-            .unwrap();
-        }
-    }
-
     #[test]
     fn in_memory_writes_to_n_leaf_empty_trie_had_expected_results() {
         for num_leaves in 1..=TEST_LEAVES_LENGTH {
@@ -165,22 +169,53 @@ mod partial_tries {
         )
     }
 
-    #[test]
-    fn lmdb_noop_writes_to_n_leaf_partial_trie_had_expected_results() {
-        for (num_leaves, generator) in TEST_TRIE_GENERATORS.iter().enumerate() {
-            let correlation_id = CorrelationId::new();
-            let (root_hash, tries) = generator().unwrap();
-            let context = LmdbTestContext::new(&tries).unwrap();
-            let states = vec![root_hash];
+    persistent_backend_tests! {
+        TestContext;
+
+        #[test]
+        fn noop_writes_to_n_leaf_partial_trie_had_expected_results() {
+            for (num_leaves, generator) in TEST_TRIE_GENERATORS.iter().enumerate() {
+                let correlation_id = CorrelationId::new();
+                let (root_hash, tries) = generator().unwrap();
+                let context = TestContext::new(&tries).unwrap();
+                let states = vec![root_hash];
+
+                super::noop_writes_to_n_leaf_partial_trie_had_expected_results::<
+                    _,
+                    _,
+                    error::Error,
+                >(
+                    correlation_id,
+                    &context.environment,
+                    &context.store,
+                    &states,
+                    num_leaves,
+                )
+                .unwrap()
+            }
+        }
 
-            noop_writes_to_n_leaf_partial_trie_had_expected_results::<_, _, error::Error>(
-                correlation_id,
-                &context.environment,
-                &context.store,
-                &states,
-                num_leaves,
-            )
-            .unwrap()
+        #[test]
+        fn update_writes_to_n_leaf_partial_trie_had_expected_results() {
+            for (num_leaves, generator) in TEST_TRIE_GENERATORS.iter().enumerate() {
+                let correlation_id = CorrelationId::new();
+                let (root_hash, tries) = generator().unwrap();
+                let context = TestContext::new(&tries).unwrap();
+                let initial_states = vec![root_hash];
+
+                super::update_writes_to_n_leaf_partial_trie_had_expected_results::<
+                    _,
+                    _,
+                    error::Error,
+                >(
+                    correlation_id,
+                    &context.environment,
+                    &context.store,
+                    &initial_states,
+                    num_leaves,
+                )
+                .unwrap()
+            }
         }
     }
 
@@ -271,25 +306,6 @@ mod partial_tries {
         Ok(())
     }
 
-    #[test]
-    fn lmdb_update_writes_to_n_leaf_partial_trie_had_expected_results() {
-        for (num_leaves, generator) in TEST_TRIE_GENERATORS.iter().enumerate() {
-            let correlation_id = CorrelationId::new();
-            let (root_hash, tries) = generator().unwrap();
-            let context = LmdbTestContext::new(&tries).unwrap();
-            let initial_states = vec![root_hash];
-
-            update_writes_to_n_leaf_partial_trie_had_expected_results::<_, _, error::Error>(
-                correlation_id,
-                &context.environment,
-                &context.store,
-                &initial_states,
-                num_leaves,
-            )
-            .unwrap()
-        }
-    }
-
     #[test]
     fn in_memory_update_writes_to_n_leaf_partial_trie_had_expected_results() {
         for (num_leaves, generator) in TEST_TRIE_GENERATORS.iter().enumerate() {
@@ -366,25 +382,72 @@ mod full_tries {
         Ok(())
     }
 
-    #[test]
-    fn lmdb_noop_writes_to_n_leaf_full_trie_had_expected_results() {
-        let correlation_id = CorrelationId::new();
-        let context = LmdbTestContext::new(EMPTY_HASHED_TEST_TRIES).unwrap();
-        let mut states: Vec<Blake2bHash> = Vec::new();
+    persistent_backend_tests! {
+        TestContext;
 
-        for (index, generator) in TEST_TRIE_GENERATORS.iter().enumerate() {
-            let (root_hash, tries) = generator().unwrap();
-            context.update(&tries).unwrap();
-            states.push(root_hash);
+        #[test]
+        fn noop_writes_to_n_leaf_full_trie_had_expected_results() {
+            let correlation_id = CorrelationId::new();
+            let context = TestContext::new(EMPTY_HASHED_TEST_TRIES).unwrap();
+            let mut states: Vec<Blake2bHash> = Vec::new();
+
+            for (index, generator) in TEST_TRIE_GENERATORS.iter().enumerate() {
+                let (root_hash, tries) = generator().unwrap();
+                context.update(&tries).unwrap();
+                states.push(root_hash);
+
+                super::noop_writes_to_n_leaf_full_trie_had_expected_results::<_, _, error::Error>(
+                    correlation_id,
+                    &context.environment,
+                    &context.store,
+                    &states,
+                    index,
+                )
+                .unwrap();
+            }
+        }
+
+        #[test]
+        fn update_writes_to_n_leaf_full_trie_had_expected_results() {
+            let correlation_id = CorrelationId::new();
+            let context = TestContext::new(EMPTY_HASHED_TEST_TRIES).unwrap();
+            let mut states: Vec<Blake2bHash> = Vec::new();
+
+            for (num_leaves, generator) in TEST_TRIE_GENERATORS.iter().enumerate() {
+                let (root_hash, tries) = generator().unwrap();
+                context.update(&tries).unwrap();
+                states.push(root_hash);
 
-            noop_writes_to_n_leaf_full_trie_had_expected_results::<_, _, error::Error>(
+                super::update_writes_to_n_leaf_full_trie_had_expected_results::<_, _, error::Error>(
+                    correlation_id,
+                    &context.environment,
+                    &context.store,
+                    &states,
+                    num_leaves,
+                )
+                .unwrap()
+            }
+        }
+
+        #[test]
+        fn node_writes_to_5_leaf_full_trie_had_expected_results() {
+            let correlation_id = CorrelationId::new();
+            let context = TestContext::new(EMPTY_HASHED_TEST_TRIES).unwrap();
+            let mut states: Vec<Blake2bHash> = Vec::new();
+
+            for generator in &TEST_TRIE_GENERATORS {
+                let (root_hash, tries) = generator().unwrap();
+                context.update(&tries).unwrap();
+                states.push(root_hash);
+            }
+
+            super::node_writes_to_5_leaf_full_trie_had_expected_results::<_, _, error::Error>(
                 correlation_id,
                 &context.environment,
                 &context.store,
                 &states,
-                index,
             )
-            .unwrap();
+            .unwrap()
         }
     }
 
@@ -490,28 +553,6 @@ mod full_tries {
         Ok(())
     }
 
-    #[test]
-    fn lmdb_update_writes_to_n_leaf_full_trie_had_expected_results() {
-        let correlation_id = CorrelationId::new();
-        let context = LmdbTestContext::new(EMPTY_HASHED_TEST_TRIES).unwrap();
-        let mut states: Vec<Blake2bHash> = Vec::new();
-
-        for (num_leaves, generator) in TEST_TRIE_GENERATORS.iter().enumerate() {
-            let (root_hash, tries) = generator().unwrap();
-            context.update(&tries).unwrap();
-            states.push(root_hash);
-
-            update_writes_to_n_leaf_full_trie_had_expected_results::<_, _, error::Error>(
-                correlation_id,
-                &context.environment,
-                &context.store,
-                &states,
-                num_leaves,
-            )
-            .unwrap()
-        }
-    }
-
     #[test]
     fn in_memory_update_writes_to_n_leaf_full_trie_had_expected_results() {
         let correlation_id = CorrelationId::new();
@@ -613,27 +654,6 @@ mod full_tries {
         Ok(())
     }
 
-    #[test]
-    fn lmdb_node_writes_to_5_leaf_full_trie_had_expected_results() {
-        let correlation_id = CorrelationId::new();
-        let context = LmdbTestContext::new(EMPTY_HASHED_TEST_TRIES).unwrap();
-        let mut states: Vec<Blake2bHash> = Vec::new();
-
-        for generator in &TEST_TRIE_GENERATORS {
-            let (root_hash, tries) = generator().unwrap();
-            context.update(&tries).unwrap();
-            states.push(root_hash);
-        }
-
-        node_writes_to_5_leaf_full_trie_had_expected_results::<_, _, error::Error>(
-            correlation_id,
-            &context.environment,
-            &context.store,
-            &states,
-        )
-        .unwrap()
-    }
-
     #[test]
     fn in_memory_node_writes_to_5_leaf_full_trie_had_expected_results() {
         let correlation_id = CorrelationId::new();
@@ -0,0 +1,171 @@
+use super::*;
+use crate::error::{self, in_memory};
+
+fn pairs_of(leaves: &[TestTrie]) -> Vec<(TestKey, TestValue)> {
+    leaves
+        .iter()
+        .map(|leaf| match leaf {
+            Trie::Leaf { key, value } => (*key, *value),
+            _ => panic!("leaves should contain only leaves"),
+        })
+        .collect()
+}
+
+/// Checks that writing `pairs` to `root_hash` in a batch results in the same root as writing them
+/// one at a time.
+fn check_batch_matches_sequential_writes<'a, R, S, E>(
+    correlation_id: CorrelationId,
+    environment: &'a R,
+    store: &S,
+    root_hash: &Blake2bHash,
+    pairs: &[(TestKey, TestValue)],
+) -> Result<(), E>
+where
+    R: TransactionSource<'a, Handle = S::Handle>,
+    S: TrieStore<TestKey, TestValue>,
+    S::Error: From<R::Error>,
+    E: From<R::Error> + From<S::Error> + From<contract_ffi::bytesrepr::Error>,
+{
+    let sequential_root =
+        write_pairs::<_, _, _, _, E>(correlation_id, environment, store, root_hash, pairs)?
+            .last()
+            .cloned()
+            .unwrap_or(*root_hash);
+    let expected = if sequential_root == *root_hash {
+        WriteResult::AlreadyExists
+    } else {
+        WriteResult::Written(sequential_root)
+    };
+
+    let actual = write_pairs_in_batch::<_, _, _, _, E>(
+        correlation_id,
+        environment,
+        store,
+        root_hash,
+        pairs,
+    )?;
+    assert_eq!(actual, expected);
+    Ok(())
+}
+
+persistent_backend_tests! {
+    TestContext;
+
+    #[test]
+    fn batch_write_to_empty_trie_matches_sequential_writes() {
+        let test_leaves = TEST_LEAVES;
+        for num_leaves in 1..=TEST_LEAVES_LENGTH {
+            let correlation_id = CorrelationId::new();
+            let (root_hash, tries) = TEST_TRIE_GENERATORS[0]().unwrap();
+            let context = TestContext::new(&tries).unwrap();
+
+            check_batch_matches_sequential_writes::<_, _, error::Error>(
+                correlation_id,
+                &context.environment,
+                &context.store,
+                &root_hash,
+                &pairs_of(&test_leaves[..num_leaves]),
+            )
+            .unwrap();
+        }
+    }
+}
+
+#[test]
+fn in_memory_batch_write_to_empty_trie_matches_sequential_writes() {
+    let test_leaves = TEST_LEAVES;
+    for num_leaves in 1..=TEST_LEAVES_LENGTH {
+        let correlation_id = CorrelationId::new();
+        let (root_hash, tries) = TEST_TRIE_GENERATORS[0]().unwrap();
+        let context = InMemoryTestContext::new(&tries).unwrap();
+
+        check_batch_matches_sequential_writes::<_, _, in_memory::Error>(
+            correlation_id,
+            &context.environment,
+            &context.store,
+            &root_hash,
+            &pairs_of(&test_leaves[..num_leaves]),
+        )
+        .unwrap();
+    }
+}
+
+#[test]
+fn in_memory_batch_write_to_n_leaf_trie_matches_sequential_writes() {
+    let leaves: Vec<TestTrie> = TEST_LEAVES_UPDATED
+        .iter()
+        .chain(TEST_LEAVES_ADJACENTS.iter())
+        .cloned()
+        .collect();
+
+    for generator in &TEST_TRIE_GENERATORS {
+        let correlation_id = CorrelationId::new();
+        let (root_hash, tries) = generator().unwrap();
+        let context = InMemoryTestContext::new(&tries).unwrap();
+
+        // Every prefix of the leaves, so that the existing trie is split in as many ways as
+        // possible.
+        for num_leaves in 1..=leaves.len() {
+            check_batch_matches_sequential_writes::<_, _, in_memory::Error>(
+                correlation_id,
+                &context.environment,
+                &context.store,
+                &root_hash,
+                &pairs_of(&leaves[..num_leaves]),
+            )
+            .unwrap();
+        }
+    }
+}
+
+#[test]
+fn in_memory_batch_write_of_existing_leaves_already_exists() {
+    let correlation_id = CorrelationId::new();
+    let (root_hash, tries) = create_6_leaf_trie().unwrap();
+    let context = InMemoryTestContext::new(&tries).unwrap();
+
+    let write_result = write_pairs_in_batch::<_, _, _, _, in_memory::Error>(
+        correlation_id,
+        &context.environment,
+        &context.store,
+        &root_hash,
+        &pairs_of(&TEST_LEAVES),
+    )
+    .unwrap();
+    assert_eq!(write_result, WriteResult::AlreadyExists);
+}
+
+#[test]
+fn in_memory_batch_write_keeps_last_value_of_repeated_key() {
+    let correlation_id = CorrelationId::new();
+    let (root_hash, tries) = create_6_leaf_trie().unwrap();
+    let context = InMemoryTestContext::new(&tries).unwrap();
+
+    let pairs = pairs_of(&[TEST_LEAVES_UPDATED[0].clone(), TEST_LEAVES[0].clone()]);
+    let write_result = write_pairs_in_batch::<_, _, _, _, in_memory::Error>(
+        correlation_id,
+        &context.environment,
+        &context.store,
+        &root_hash,
+        &pairs,
+    )
+    .unwrap();
+    assert_eq!(write_result, WriteResult::AlreadyExists);
+}
+
+#[test]
+fn in_memory_batch_write_to_missing_root_is_root_not_found() {
+    let correlation_id = CorrelationId::new();
+    let (_, tries) = create_6_leaf_trie().unwrap();
+    let context = InMemoryTestContext::new(&tries).unwrap();
+
+    let write_result = write_pairs_in_batch::<_, _, _, _, in_memory::Error>(
+        correlation_id,
+        &context.environment,
+        &context.store,
+        &Blake2bHash::new(b"missing root"),
+        &pairs_of(&TEST_LEAVES_UPDATED),
+    )
+    .unwrap();
+    assert_eq!(write_result, WriteResult::RootNotFound);
+}
@@ -0,0 +1,218 @@
+//! A RocksDB-backed trie store.
+//!
+//! # Usage
+//!
+//! ```
+//! use casperlabs_engine_storage::store::Store;
+//! use casperlabs_engine_storage::transaction_source::{Transaction, TransactionSource};
+//! use casperlabs_engine_storage::transaction_source::rocksdb::RocksDbEnvironment;
+//! use casperlabs_engine_storage::trie::Trie;
+//! use casperlabs_engine_storage::trie_store::rocksdb::RocksDbTrieStore;
+//! use contract_ffi::bytesrepr::ToBytes;
+//! use engine_shared::newtypes::Blake2bHash;
+//! use tempfile::tempdir;
+//!
+//! let leaf = Trie::Leaf { key: vec![0u8, 0, 0], value: b"val_1".to_vec() };
+//! let leaf_hash = Blake2bHash::new(&leaf.to_bytes().unwrap());
+//!
+//! // Unlike LMDB, RocksDB does not need a map size.
+//! let tmp_dir = tempdir().unwrap();
+//! let env = RocksDbEnvironment::new(&tmp_dir.path().to_path_buf()).unwrap();
+//! let store = RocksDbTrieStore::new(&env, None);
+//!
+//! // Writes are only visible to other transactions once committed.
+//! {
+//!     let mut txn = env.create_read_write_txn().unwrap();
+//!     store.put(&mut txn, &leaf_hash, &leaf).unwrap();
+//!     txn.commit().unwrap();
+//! }
+//!
+//! {
+//!     let txn = env.create_read_txn().unwrap();
+//!     assert_eq!(Some(leaf), store.get(&txn, &leaf_hash).unwrap());
+//!     txn.commit().unwrap();
+//! }
+//!
+//! tmp_dir.close().unwrap();
+//! ```
+
+use std::{
+    collections::HashSet,
+    sync::{Arc, Mutex},
+};
+
+use rocksdb::{Direction, IteratorMode};
+
+use contract_ffi::bytesrepr::{self, FromBytes, ToBytes};
+use engine_shared::newtypes::Blake2bHash;
+
+use crate::{
+    error,
+    store::Store,
+    transaction_source::{
+        rocksdb::{
+            RocksDbEnvironment, RocksDbNamespace, RocksDbReadTransaction,
+            RocksDbReadWriteTransaction,
+        },
+        Readable, Writable,
+    },
+    trie::Trie,
+    trie_store::{self, TrieStore},
+};
+
+/// A RocksDB-backed trie store.
+///
+/// Keeps its entries under its own [`RocksDbNamespace`] of the environment's database.  Clones of
+/// the store share the hashes of the elements written while a prune is in progress.
+#[derive(Debug, Clone)]
+pub struct RocksDbTrieStore {
+    namespace: RocksDbNamespace,
+    written_while_pruning: Arc<Mutex<Option<HashSet<Blake2bHash>>>>,
+}
+
+impl RocksDbTrieStore {
+    pub fn new(_env: &RocksDbEnvironment, maybe_name: Option<&str>) -> Self {
+        let name = maybe_name
+            .map(|name| format!("{}-{}", trie_store::NAME, name))
+            .unwrap_or_else(|| String::from(trie_store::NAME));
+        RocksDbTrieStore {
+            namespace: RocksDbNamespace::new(&name),
+            written_while_pruning: Default::default(),
+        }
+    }
+
+    /// Starts recording the hashes of the elements written to the store, until
+    /// [`RocksDbTrieStore::stop_recording_writes`] is called, so that a prune does not sweep the
+    /// elements committed after it marked the reachable ones.
+    pub fn start_recording_writes(&self) -> Result<(), error::Error> {
+        *self.written_while_pruning.lock()? = Some(HashSet::new());
+        Ok(())
+    }
+
+    /// Stops recording the hashes of the elements written to the store, and forgets them.
+    pub fn stop_recording_writes(&self) -> Result<(), error::Error> {
+        *self.written_while_pruning.lock()? = None;
+        Ok(())
+    }
+
+    /// Scans up to `batch_size` elements, starting at the serialized hash `start` or at the first
+    /// element if it is `None`, and deletes those whose hash is neither in `retain` nor recorded
+    /// as written since [`RocksDbTrieStore::start_recording_writes`].
+    ///
+    /// Returns the number of elements deleted, along with the serialized hash to resume the scan
+    /// at, or `None` once the whole store has been scanned.
+    pub fn prune_batch(
+        &self,
+        txn: &mut RocksDbReadWriteTransaction,
+        retain: &HashSet<Blake2bHash>,
+        start: Option<&[u8]>,
+        batch_size: usize,
+    ) -> Result<(usize, Option<Vec<u8>>), error::Error> {
+        let written_while_pruning = self.written_while_pruning.lock()?;
+        let is_retained = |hash: &Blake2bHash| {
+            retain.contains(hash)
+                || written_while_pruning
+                    .as_ref()
+                    .map_or(false, |written| written.contains(hash))
+        };
+        let prefix = self.namespace.prefix();
+        let from = match start {
+            Some(start) => self.namespace.key(start),
+            None => prefix.to_vec(),
+        };
+        let mut to_delete: Vec<Vec<u8>> = Vec::new();
+        let mut next_start = None;
+        let mode = IteratorMode::From(&from, Direction::Forward);
+        for (scanned, (key_bytes, _)) in txn.db().iterator(mode).enumerate() {
+            if !key_bytes.starts_with(prefix) {
+                break;
+            }
+            let hash_bytes = &key_bytes[prefix.len()..];
+            if scanned == batch_size {
+                next_start = Some(hash_bytes.to_vec());
+                break;
+            }
+            let hash: Blake2bHash = bytesrepr::deserialize(hash_bytes.to_vec())?;
+            if !is_retained(&hash) {
+                to_delete.push(hash_bytes.to_vec());
+            }
+        }
+        for hash_bytes in &to_delete {
+            txn.delete(self.namespace.clone(), hash_bytes);
+        }
+        Ok((to_delete.len(), next_start))
+    }
+
+    /// Returns the hashes of the stored elements which no decodable stored element points to.
+    ///
+    /// These are the state roots, along with any elements orphaned by corruption of their parents,
+    /// so every stored element is reachable from them.
+    pub fn unreferenced_hashes<K, V>(
+        &self,
+        txn: &RocksDbReadTransaction,
+    ) -> Result<Vec<Blake2bHash>, error::Error>
+    where
+        K: FromBytes,
+        V: FromBytes,
+    {
+        let prefix = self.namespace.prefix();
+        let mode = IteratorMode::From(prefix, Direction::Forward);
+        let mut stored: Vec<Blake2bHash> = Vec::new();
+        let mut referenced: HashSet<Blake2bHash> = HashSet::new();
+        for (key_bytes, value_bytes) in txn.snapshot().iterator(mode) {
+            if !key_bytes.starts_with(prefix) {
+                break;
+            }
+            stored.push(bytesrepr::deserialize(key_bytes[prefix.len()..].to_vec())?);
+            if let Ok(trie) = bytesrepr::deserialize::<Trie<K, V>>(value_bytes.to_vec()) {
+                referenced.extend(trie.child_hashes());
+            }
+        }
+        stored.retain(|hash| !referenced.contains(hash));
+        Ok(stored)
+    }
+
+    /// Deletes the elements stored under `hashes`, returning the number of elements deleted.
+    pub fn delete(
+        &self,
+        txn: &mut RocksDbReadWriteTransaction,
+        hashes: &[Blake2bHash],
+    ) -> Result<usize, error::Error> {
+        let mut deleted = 0;
+        for hash in hashes {
+            let hash_bytes = hash.to_bytes()?;
+            if txn.read(self.namespace.clone(), &hash_bytes)?.is_some() {
+                txn.delete(self.namespace.clone(), &hash_bytes);
+                deleted += 1;
+            }
+        }
+        Ok(deleted)
+    }
+}
+
+impl<K, V> Store<Blake2bHash, Trie<K, V>> for RocksDbTrieStore {
+    type Error = error::Error;
+
+    type Handle = RocksDbNamespace;
+
+    fn handle(&self) -> Self::Handle {
+        self.namespace.clone()
+    }
+
+    /// Writes `value` at `key`, recording `key` if a prune is in progress.
+    fn put<T>(&self, txn: &mut T, key: &Blake2bHash, value: &Trie<K, V>) -> Result<(), Self::Error>
+    where
+        T: Writable<Handle = Self::Handle>,
+        Blake2bHash: ToBytes,
+        Trie<K, V>: ToBytes,
+        Self::Error: From<T::Error>,
+    {
+        if let Some(written) = self.written_while_pruning.lock()?.as_mut() {
+            written.insert(*key);
+        }
+        txn.write(self.namespace.clone(), &key.to_bytes()?, &value.to_bytes()?)
+            .map_err(Into::into)
+    }
+}
+
+impl<K, V> TrieStore<K, V> for RocksDbTrieStore {}
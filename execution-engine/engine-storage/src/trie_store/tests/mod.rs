@@ -2,10 +2,22 @@ mod concurrent;
 mod proptests;
 mod simple;
 
+use lmdb::DatabaseFlags;
+use tempfile::{tempdir, TempDir};
+
 use contract_ffi::bytesrepr::ToBytes;
 use engine_shared::newtypes::Blake2bHash;
 
-use crate::trie::{Pointer, PointerBlock, Trie};
+use crate::{
+    transaction_source::lmdb::LmdbEnvironment,
+    trie::{Pointer, PointerBlock, Trie},
+    trie_store::lmdb::LmdbTrieStore,
+    TEST_MAP_SIZE,
+};
+#[cfg(feature = "use_rocksdb")]
+use crate::{
+    transaction_source::rocksdb::RocksDbEnvironment, trie_store::rocksdb::RocksDbTrieStore,
+};
 
 #[derive(Clone)]
 struct TestData<K, V>(Blake2bHash, Trie<K, V>);
@@ -71,3 +83,48 @@ fn create_data() -> Vec<TestData<Vec<u8>, Vec<u8>>> {
         TestData(ext_node_hash, ext_node),
     ]
 }
+
+// A context for holding an lmdb environment and the trie stores opened in it
+struct LmdbTestContext {
+    _temp_dir: TempDir,
+    environment: LmdbEnvironment,
+}
+
+impl LmdbTestContext {
+    fn new() -> Self {
+        let _temp_dir = tempdir().unwrap();
+        let environment =
+            LmdbEnvironment::new(&_temp_dir.path().to_path_buf(), *TEST_MAP_SIZE).unwrap();
+        LmdbTestContext {
+            _temp_dir,
+            environment,
+        }
+    }
+
+    fn store(&self, name: Option<&str>) -> LmdbTrieStore {
+        LmdbTrieStore::new(&self.environment, name, DatabaseFlags::empty()).unwrap()
+    }
+}
+
+// A context for holding a rocksdb environment and the trie stores opened in it
+#[cfg(feature = "use_rocksdb")]
+struct RocksDbTestContext {
+    _temp_dir: TempDir,
+    environment: RocksDbEnvironment,
+}
+
+#[cfg(feature = "use_rocksdb")]
+impl RocksDbTestContext {
+    fn new() -> Self {
+        let _temp_dir = tempdir().unwrap();
+        let environment = RocksDbEnvironment::new(&_temp_dir.path().to_path_buf()).unwrap();
+        RocksDbTestContext {
+            _temp_dir,
+            environment,
+        }
+    }
+
+    fn store(&self, name: Option<&str>) -> RocksDbTrieStore {
+        RocksDbTrieStore::new(&self.environment, name)
+    }
+}
@@ -61,6 +61,24 @@ fn lmdb_roundtrip_succeeds(inputs: Vec<Trie<Key, StoredValue>>) -> bool {
     ret
 }
 
+#[cfg(feature = "use_rocksdb")]
+fn rocksdb_roundtrip_succeeds(inputs: Vec<Trie<Key, StoredValue>>) -> bool {
+    use crate::{
+        transaction_source::rocksdb::RocksDbEnvironment, trie_store::rocksdb::RocksDbTrieStore,
+    };
+
+    let tmp_dir = tempdir().unwrap();
+    let env = RocksDbEnvironment::new(&tmp_dir.path().to_path_buf()).unwrap();
+    let store = RocksDbTrieStore::new(&env, None);
+
+    let inputs: BTreeMap<Blake2bHash, Trie<Key, StoredValue>> = inputs
+        .into_iter()
+        .map(|trie| (Blake2bHash::new(&trie.to_bytes().unwrap()), trie))
+        .collect();
+
+    store_tests::roundtrip_succeeds(&env, &store, inputs).unwrap()
+}
+
 proptest! {
     #[test]
     fn prop_in_memory_roundtrip_succeeds(v in vec(trie_arb(), get_range())) {
@@ -71,4 +89,10 @@ proptest! {
     fn prop_lmdb_roundtrip_succeeds(v in vec(trie_arb(), get_range())) {
         assert!(lmdb_roundtrip_succeeds(v))
     }
+
+    #[cfg(feature = "use_rocksdb")]
+    #[test]
+    fn prop_rocksdb_roundtrip_succeeds(v in vec(trie_arb(), get_range())) {
+        assert!(rocksdb_roundtrip_succeeds(v))
+    }
 }
@@ -2,8 +2,11 @@ use lmdb::DatabaseFlags;
 use tempfile::tempdir;
 
 use contract_ffi::bytesrepr::{FromBytes, ToBytes};
+use engine_shared::newtypes::Blake2bHash;
 
-use super::TestData;
+#[cfg(feature = "use_rocksdb")]
+use super::RocksDbTestContext;
+use super::{create_data, LmdbTestContext, TestData};
 use crate::{
     error::{self, in_memory},
     store::StoreExt,
@@ -44,18 +47,6 @@ fn in_memory_put_succeeds() {
     assert!(put_succeeds::<_, _, _, _, in_memory::Error>(&store, &env, data).is_ok());
 }
 
-#[test]
-fn lmdb_put_succeeds() {
-    let tmp_dir = tempdir().unwrap();
-    let env = LmdbEnvironment::new(&tmp_dir.path().to_path_buf(), *TEST_MAP_SIZE).unwrap();
-    let store = LmdbTrieStore::new(&env, None, DatabaseFlags::empty()).unwrap();
-    let data = &super::create_data()[0..1];
-
-    assert!(put_succeeds::<_, _, _, _, error::Error>(&store, &env, data).is_ok());
-
-    tmp_dir.close().unwrap();
-}
-
 fn put_get_succeeds<'a, K, V, S, X, E>(
     store: &S,
     transaction_source: &'a X,
@@ -97,28 +88,6 @@ fn in_memory_put_get_succeeds() {
     )
 }
 
-#[test]
-fn lmdb_put_get_succeeds() {
-    let tmp_dir = tempdir().unwrap();
-    let env = LmdbEnvironment::new(&tmp_dir.path().to_path_buf(), *TEST_MAP_SIZE).unwrap();
-    let store = LmdbTrieStore::new(&env, None, DatabaseFlags::empty()).unwrap();
-    let data = &super::create_data()[0..1];
-
-    let expected: Vec<Trie<Vec<u8>, Vec<u8>>> =
-        data.to_vec().into_iter().map(|TestData(_, v)| v).collect();
-
-    assert_eq!(
-        expected,
-        put_get_succeeds::<_, _, _, _, error::Error>(&store, &env, data)
-            .expect("put_get_succeeds failed")
-            .into_iter()
-            .collect::<Option<Vec<Trie<Vec<u8>, Vec<u8>>>>>()
-            .expect("one of the outputs was empty")
-    );
-
-    tmp_dir.close().unwrap();
-}
-
 #[test]
 fn in_memory_put_get_many_succeeds() {
     let env = InMemoryEnvironment::new();
@@ -138,28 +107,6 @@ fn in_memory_put_get_many_succeeds() {
     )
 }
 
-#[test]
-fn lmdb_put_get_many_succeeds() {
-    let tmp_dir = tempdir().unwrap();
-    let env = LmdbEnvironment::new(&tmp_dir.path().to_path_buf(), *TEST_MAP_SIZE).unwrap();
-    let store = LmdbTrieStore::new(&env, None, DatabaseFlags::empty()).unwrap();
-    let data = super::create_data();
-
-    let expected: Vec<Trie<Vec<u8>, Vec<u8>>> =
-        data.to_vec().into_iter().map(|TestData(_, v)| v).collect();
-
-    assert_eq!(
-        expected,
-        put_get_succeeds::<_, _, _, _, error::Error>(&store, &env, &data)
-            .expect("put_get failed")
-            .into_iter()
-            .collect::<Option<Vec<Trie<Vec<u8>, Vec<u8>>>>>()
-            .expect("one of the outputs was empty")
-    );
-
-    tmp_dir.close().unwrap();
-}
-
 fn uncommitted_read_write_txn_does_not_persist<'a, K, V, S, X, E>(
     store: &S,
     transaction_source: &'a X,
@@ -205,12 +152,59 @@ fn in_memory_uncommitted_read_write_txn_does_not_persist() {
 }
 
 #[test]
-fn lmdb_uncommitted_read_write_txn_does_not_persist() {
+fn lmdb_with_cache_get_many_succeeds() {
+    let tmp_dir = tempdir().unwrap();
+    let env = LmdbEnvironment::new(&tmp_dir.path().to_path_buf(), *TEST_MAP_SIZE).unwrap();
+    let store = LmdbTrieStore::new(&env, None, DatabaseFlags::empty())
+        .unwrap()
+        .with_cache(1024 * 1024);
+    let data = super::create_data();
+
+    let expected: Vec<Trie<Vec<u8>, Vec<u8>>> =
+        data.to_vec().into_iter().map(|TestData(_, v)| v).collect();
+
+    put_get_succeeds::<_, _, _, _, error::Error>(&store, &env, &data).expect("put_get failed");
+
+    for _ in 0..2 {
+        let txn = env.create_read_txn().unwrap();
+        let keys = data.iter().map(|TestData(k, _)| k);
+        let tries: Vec<Option<Trie<Vec<u8>, Vec<u8>>>> = store.get_many(&txn, keys).unwrap();
+        txn.commit().unwrap();
+        assert_eq!(
+            expected,
+            tries
+                .into_iter()
+                .collect::<Option<Vec<Trie<Vec<u8>, Vec<u8>>>>>()
+                .expect("one of the outputs was empty")
+        );
+    }
+
+    let stats = store.cache_stats().unwrap().unwrap();
+    assert_eq!(stats.entries, data.len());
+    assert_eq!(stats.hits, data.len() as u64);
+
+    tmp_dir.close().unwrap();
+}
+
+#[test]
+fn lmdb_with_cache_does_not_cache_reads_of_uncommitted_writes() {
     let tmp_dir = tempdir().unwrap();
     let env = LmdbEnvironment::new(&tmp_dir.path().to_path_buf(), *TEST_MAP_SIZE).unwrap();
-    let store = LmdbTrieStore::new(&env, None, DatabaseFlags::empty()).unwrap();
+    let store = LmdbTrieStore::new(&env, None, DatabaseFlags::empty())
+        .unwrap()
+        .with_cache(1024 * 1024);
     let data = super::create_data();
 
+    {
+        let mut txn = env.create_read_write_txn().unwrap();
+        let items = data.iter().map(Into::into);
+        store.put_many(&mut txn, items).unwrap();
+        let keys = data.iter().map(|TestData(k, _)| k);
+        let tries: Vec<Option<Trie<Vec<u8>, Vec<u8>>>> = store.get_many(&txn, keys).unwrap();
+        assert!(tries.iter().all(Option::is_some));
+    }
+
+    assert_eq!(store.cache_stats().unwrap().unwrap().entries, 0);
     assert_eq!(
         None,
         uncommitted_read_write_txn_does_not_persist::<_, _, _, _, error::Error>(
@@ -224,6 +218,39 @@ fn lmdb_uncommitted_read_write_txn_does_not_persist() {
     tmp_dir.close().unwrap();
 }
 
+#[test]
+fn lmdb_with_cache_does_not_cache_deleted_elements_read_by_older_txn() {
+    let tmp_dir = tempdir().unwrap();
+    let env = LmdbEnvironment::new(&tmp_dir.path().to_path_buf(), *TEST_MAP_SIZE).unwrap();
+    let store = LmdbTrieStore::new(&env, None, DatabaseFlags::empty())
+        .unwrap()
+        .with_cache(1024 * 1024);
+    let data = super::create_data();
+    let hashes: Vec<Blake2bHash> = data.iter().map(|TestData(k, _)| *k).collect();
+
+    put_succeeds::<_, _, _, _, error::Error>(&store, &env, &data).expect("put failed");
+
+    // Begun before the deletion is committed, so it still sees the deleted elements
+    let old_txn = env.create_read_txn().unwrap();
+    {
+        let mut txn = env.create_read_write_txn().unwrap();
+        assert_eq!(store.delete(&mut txn, &hashes).unwrap(), data.len());
+        txn.commit().unwrap();
+    }
+    let tries: Vec<Option<Trie<Vec<u8>, Vec<u8>>>> =
+        store.get_many(&old_txn, hashes.iter()).unwrap();
+    old_txn.commit().unwrap();
+    assert!(tries.iter().all(Option::is_some));
+    assert_eq!(store.cache_stats().unwrap().unwrap().entries, 0);
+
+    let txn = env.create_read_txn().unwrap();
+    let tries: Vec<Option<Trie<Vec<u8>, Vec<u8>>>> = store.get_many(&txn, hashes.iter()).unwrap();
+    txn.commit().unwrap();
+    assert!(tries.iter().all(Option::is_none));
+
+    tmp_dir.close().unwrap();
+}
+
 fn read_write_transaction_does_not_block_read_transaction<'a, X, E>(
     transaction_source: &'a X,
 ) -> Result<(), E>
@@ -247,14 +274,6 @@ fn in_memory_read_write_transaction_does_not_block_read_transaction() {
     )
 }
 
-#[test]
-fn lmdb_read_write_transaction_does_not_block_read_transaction() {
-    let dir = tempdir().unwrap();
-    let env = LmdbEnvironment::new(&dir.path().to_path_buf(), *TEST_MAP_SIZE).unwrap();
-
-    assert!(read_write_transaction_does_not_block_read_transaction::<_, error::Error>(&env).is_ok())
-}
-
 fn reads_are_isolated<'a, S, X, E>(store: &S, env: &'a X) -> Result<(), E>
 where
     S: TrieStore<Vec<u8>, Vec<u8>>,
@@ -298,15 +317,6 @@ fn in_memory_reads_are_isolated() {
     assert!(reads_are_isolated::<_, _, in_memory::Error>(&store, &env).is_ok())
 }
 
-#[test]
-fn lmdb_reads_are_isolated() {
-    let dir = tempdir().unwrap();
-    let env = LmdbEnvironment::new(&dir.path().to_path_buf(), *TEST_MAP_SIZE).unwrap();
-    let store = LmdbTrieStore::new(&env, None, DatabaseFlags::empty()).unwrap();
-
-    assert!(reads_are_isolated::<_, _, error::Error>(&store, &env).is_ok())
-}
-
 fn reads_are_isolated_2<'a, S, X, E>(store: &S, env: &'a X) -> Result<(), E>
 where
     S: TrieStore<Vec<u8>, Vec<u8>>,
@@ -354,15 +364,6 @@ fn in_memory_reads_are_isolated_2() {
     assert!(reads_are_isolated_2::<_, _, in_memory::Error>(&store, &env).is_ok())
 }
 
-#[test]
-fn lmdb_reads_are_isolated_2() {
-    let dir = tempdir().unwrap();
-    let env = LmdbEnvironment::new(&dir.path().to_path_buf(), *TEST_MAP_SIZE).unwrap();
-    let store = LmdbTrieStore::new(&env, None, DatabaseFlags::empty()).unwrap();
-
-    assert!(reads_are_isolated_2::<_, _, error::Error>(&store, &env).is_ok())
-}
-
 fn dbs_are_isolated<'a, S, X, E>(env: &'a X, store_a: &S, store_b: &S) -> Result<(), E>
 where
     S: TrieStore<Vec<u8>, Vec<u8>>,
@@ -416,16 +417,6 @@ fn in_memory_dbs_are_isolated() {
     assert!(dbs_are_isolated::<_, _, in_memory::Error>(&env, &store_a, &store_b).is_ok())
 }
 
-#[test]
-fn lmdb_dbs_are_isolated() {
-    let dir = tempdir().unwrap();
-    let env = LmdbEnvironment::new(&dir.path().to_path_buf(), *TEST_MAP_SIZE).unwrap();
-    let store_a = LmdbTrieStore::new(&env, Some("a"), DatabaseFlags::empty()).unwrap();
-    let store_b = LmdbTrieStore::new(&env, Some("b"), DatabaseFlags::empty()).unwrap();
-
-    assert!(dbs_are_isolated::<_, _, error::Error>(&env, &store_a, &store_b).is_ok())
-}
-
 fn transactions_can_be_used_across_sub_databases<'a, S, X, E>(
     env: &'a X,
     store_a: &S,
@@ -474,21 +465,6 @@ fn in_memory_transactions_can_be_used_across_sub_databases() {
     );
 }
 
-#[test]
-fn lmdb_transactions_can_be_used_across_sub_databases() {
-    let dir = tempdir().unwrap();
-    let env = LmdbEnvironment::new(&dir.path().to_path_buf(), *TEST_MAP_SIZE).unwrap();
-    let store_a = LmdbTrieStore::new(&env, Some("a"), DatabaseFlags::empty()).unwrap();
-    let store_b = LmdbTrieStore::new(&env, Some("b"), DatabaseFlags::empty()).unwrap();
-
-    assert!(
-        transactions_can_be_used_across_sub_databases::<_, _, error::Error>(
-            &env, &store_a, &store_b
-        )
-        .is_ok()
-    )
-}
-
 fn uncommitted_transactions_across_sub_databases_do_not_persist<'a, S, X, E>(
     env: &'a X,
     store_a: &S,
@@ -536,17 +512,163 @@ fn in_memory_uncommitted_transactions_across_sub_databases_do_not_persist() {
     );
 }
 
-#[test]
-fn lmdb_uncommitted_transactions_across_sub_databases_do_not_persist() {
-    let dir = tempdir().unwrap();
-    let env = LmdbEnvironment::new(&dir.path().to_path_buf(), *TEST_MAP_SIZE).unwrap();
-    let store_a = LmdbTrieStore::new(&env, Some("a"), DatabaseFlags::empty()).unwrap();
-    let store_b = LmdbTrieStore::new(&env, Some("b"), DatabaseFlags::empty()).unwrap();
+persistent_backend_tests! {
+    TestContext;
 
-    assert!(
-        uncommitted_transactions_across_sub_databases_do_not_persist::<_, _, error::Error>(
-            &env, &store_a, &store_b
+    #[test]
+    fn put_succeeds() {
+        let context = TestContext::new();
+        let store = context.store(None);
+        let data = &create_data()[0..1];
+
+        assert!(super::put_succeeds::<_, _, _, _, error::Error>(
+            &store,
+            &context.environment,
+            data
         )
-        .is_ok()
-    )
+        .is_ok());
+    }
+
+    #[test]
+    fn put_get_succeeds() {
+        let context = TestContext::new();
+        let store = context.store(None);
+        let data = &create_data()[0..1];
+
+        let expected: Vec<Trie<Vec<u8>, Vec<u8>>> =
+            data.to_vec().into_iter().map(|TestData(_, v)| v).collect();
+
+        assert_eq!(
+            expected,
+            super::put_get_succeeds::<_, _, _, _, error::Error>(
+                &store,
+                &context.environment,
+                data
+            )
+            .expect("put_get_succeeds failed")
+            .into_iter()
+            .collect::<Option<Vec<Trie<Vec<u8>, Vec<u8>>>>>()
+            .expect("one of the outputs was empty")
+        );
+    }
+
+    #[test]
+    fn put_get_many_succeeds() {
+        let context = TestContext::new();
+        let store = context.store(None);
+        let data = create_data();
+
+        let expected: Vec<Trie<Vec<u8>, Vec<u8>>> =
+            data.to_vec().into_iter().map(|TestData(_, v)| v).collect();
+
+        assert_eq!(
+            expected,
+            super::put_get_succeeds::<_, _, _, _, error::Error>(
+                &store,
+                &context.environment,
+                &data
+            )
+            .expect("put_get failed")
+            .into_iter()
+            .collect::<Option<Vec<Trie<Vec<u8>, Vec<u8>>>>>()
+            .expect("one of the outputs was empty")
+        );
+    }
+
+    #[test]
+    fn uncommitted_read_write_txn_does_not_persist() {
+        let context = TestContext::new();
+        let store = context.store(None);
+        let data = create_data();
+
+        assert_eq!(
+            None,
+            super::uncommitted_read_write_txn_does_not_persist::<_, _, _, _, error::Error>(
+                &store,
+                &context.environment,
+                &data
+            )
+            .expect("uncommitted_read_write_txn_does_not_persist failed")
+            .into_iter()
+            .collect::<Option<Vec<Trie<Vec<u8>, Vec<u8>>>>>()
+        );
+    }
+
+    #[test]
+    fn read_write_transaction_does_not_block_read_transaction() {
+        let context = TestContext::new();
+
+        assert!(super::read_write_transaction_does_not_block_read_transaction::<
+            _,
+            error::Error,
+        >(&context.environment)
+        .is_ok())
+    }
+
+    #[test]
+    fn reads_are_isolated() {
+        let context = TestContext::new();
+        let store = context.store(None);
+
+        assert!(
+            super::reads_are_isolated::<_, _, error::Error>(&store, &context.environment).is_ok()
+        )
+    }
+
+    #[test]
+    fn reads_are_isolated_2() {
+        let context = TestContext::new();
+        let store = context.store(None);
+
+        assert!(
+            super::reads_are_isolated_2::<_, _, error::Error>(&store, &context.environment)
+                .is_ok()
+        )
+    }
+
+    #[test]
+    fn dbs_are_isolated() {
+        let context = TestContext::new();
+        let store_a = context.store(Some("a"));
+        let store_b = context.store(Some("b"));
+
+        assert!(super::dbs_are_isolated::<_, _, error::Error>(
+            &context.environment,
+            &store_a,
+            &store_b
+        )
+        .is_ok())
+    }
+
+    #[test]
+    fn transactions_can_be_used_across_sub_databases() {
+        let context = TestContext::new();
+        let store_a = context.store(Some("a"));
+        let store_b = context.store(Some("b"));
+
+        assert!(
+            super::transactions_can_be_used_across_sub_databases::<_, _, error::Error>(
+                &context.environment,
+                &store_a,
+                &store_b
+            )
+            .is_ok()
+        )
+    }
+
+    #[test]
+    fn uncommitted_transactions_across_sub_databases_do_not_persist() {
+        let context = TestContext::new();
+        let store_a = context.store(Some("a"));
+        let store_b = context.store(Some("b"));
+
+        assert!(
+            super::uncommitted_transactions_across_sub_databases_do_not_persist::<
+                _,
+                _,
+                error::Error,
+            >(&context.environment, &store_a, &store_b)
+            .is_ok()
+        )
+    }
 }
@@ -18,6 +18,7 @@ use contract_ffi::{
     args_parser::ArgsParser,
     bytesrepr::ToBytes,
     key::Key,
+    system_contracts::pos,
     uref::URef,
     value::{
         account::{PublicKey, PurseId},
@@ -26,18 +27,25 @@ use contract_ffi::{
 };
 use engine_core::{
     engine_state::{
-        genesis::{GenesisAccount, GenesisConfig},
+        contract_event::ContractEvent,
+        execution_trace::ExecutionTrace,
+        genesis::{GenesisAccount, GenesisConfig, POS_CONFIG},
         EngineConfig, EngineState, SYSTEM_ACCOUNT_ADDR,
     },
     execution,
 };
 use engine_grpc_server::engine_server::{
     ipc::{
-        ChainSpec_ActivationPoint, ChainSpec_CostTable_WasmCosts, ChainSpec_UpgradePoint,
+        ChainSpec_ActivationPoint, ChainSpec_CostTable_HostFunctionCosts,
+        ChainSpec_CostTable_WasmCosts, ChainSpec_ProofOfStakeConfig, ChainSpec_UpgradePoint,
         CommitRequest, CommitResponse, DeployCode, DeployItem, DeployPayload, DeployResult,
-        DeployResult_ExecutionResult, DeployResult_PreconditionFailure, ExecuteRequest,
-        ExecuteResponse, GenesisResponse, QueryRequest, StoredContractHash, StoredContractName,
-        StoredContractURef, UpgradeRequest, UpgradeResponse,
+        DeployResult_ExecutionResult, DeployResult_PreconditionFailure, DiffStateRequest,
+        EstimateGasRequest, EstimateGasResponse, ExecuteAndCommitRequest, ExecuteAndCommitResponse,
+        ExecuteRequest, ExecuteResponse, GenesisResponse, GetTrieRequest, ListKeysRequest,
+        ListStateRootsRequest, MissingTrieKeysRequest, PutTrieRequest, PutTrieRequest_Entry,
+        QueryRequest, RewardWeight, SlashRequest, SlashResponse, StateRoot, StepRequest,
+        StepResponse, StoredContractHash, StoredContractName, StoredContractURef, UpgradeRequest,
+        UpgradeResponse,
     },
     ipc_grpc::ExecutionEngineService,
     mappings::{MappingError, TransformMap},
@@ -51,14 +59,17 @@ use engine_shared::{
 use engine_storage::{
     global_state::{in_memory::InMemoryGlobalState, lmdb::LmdbGlobalState, StateProvider},
     protocol_data_store::lmdb::LmdbProtocolDataStore,
+    state_root_store::lmdb::LmdbStateRootStore,
     transaction_source::lmdb::LmdbEnvironment,
-    trie_store::lmdb::LmdbTrieStore,
+    trie::merkle_proof::TrieMerkleProof,
+    trie_store::{lmdb::LmdbTrieStore, operations::Difference},
 };
-use engine_wasm_prep::wasm_costs::WasmCosts;
+use engine_wasm_prep::{host_function_costs::HostFunctionCosts, wasm_costs::WasmCosts};
 
 use crate::test::{
     CONTRACT_MINT_INSTALL, CONTRACT_POS_INSTALL, CONTRACT_STANDARD_PAYMENT, DEFAULT_CHAIN_NAME,
-    DEFAULT_GENESIS_TIMESTAMP, DEFAULT_PAYMENT, DEFAULT_PROTOCOL_VERSION, DEFAULT_WASM_COSTS,
+    DEFAULT_GENESIS_TIMESTAMP, DEFAULT_HOST_FUNCTION_COSTS, DEFAULT_PAYMENT, DEFAULT_POS_CONFIG,
+    DEFAULT_PROTOCOL_VERSION, DEFAULT_WASM_COSTS,
 };
 
 pub const STANDARD_PAYMENT_CONTRACT: &str = "standard_payment.wasm";
@@ -202,6 +213,11 @@ impl DeployItemBuilder {
         self
     }
 
+    pub fn with_trace(mut self, trace: bool) -> Self {
+        self.deploy_item.set_trace(trace);
+        self
+    }
+
     pub fn build(self) -> DeployItem {
         self.deploy_item
     }
@@ -336,7 +352,10 @@ pub struct UpgradeRequestBuilder {
     new_protocol_version: ProtocolVersion,
     upgrade_installer: DeployCode,
     new_costs: Option<ChainSpec_CostTable_WasmCosts>,
+    new_host_function_costs: Option<ChainSpec_CostTable_HostFunctionCosts>,
+    new_pos_config: Option<ChainSpec_ProofOfStakeConfig>,
     activation_point: ChainSpec_ActivationPoint,
+    block_time: u64,
 }
 
 impl UpgradeRequestBuilder {
@@ -394,10 +413,25 @@ impl UpgradeRequestBuilder {
         new_costs.set_max_stack_height(wasm_costs.max_stack_height);
         new_costs.set_mem(wasm_costs.mem);
         new_costs.set_memcpy(wasm_costs.memcpy);
+        new_costs.set_verify_ed25519(wasm_costs.verify_ed25519);
+        new_costs.set_verify_secp256k1(wasm_costs.verify_secp256k1);
+        new_costs.set_hash_blake2b(wasm_costs.hash_blake2b);
+        new_costs.set_hash_sha256(wasm_costs.hash_sha256);
+        new_costs.set_hash_keccak256(wasm_costs.hash_keccak256);
         self.new_costs = Some(new_costs);
         self
     }
 
+    pub fn with_new_host_function_costs(mut self, host_function_costs: HostFunctionCosts) -> Self {
+        self.new_host_function_costs = Some(host_function_costs.into());
+        self
+    }
+
+    pub fn with_new_pos_config(mut self, pos_config: pos::Config) -> Self {
+        self.new_pos_config = Some(pos_config.into());
+        self
+    }
+
     pub fn with_activation_point(mut self, rank: u64) -> Self {
         self.activation_point = {
             let mut ret = ChainSpec_ActivationPoint::new();
@@ -407,17 +441,26 @@ impl UpgradeRequestBuilder {
         self
     }
 
+    pub fn with_block_time(mut self, block_time: u64) -> Self {
+        self.block_time = block_time;
+        self
+    }
+
     pub fn build(self) -> UpgradeRequest {
         let mut upgrade_point = ChainSpec_UpgradePoint::new();
         upgrade_point.set_activation_point(self.activation_point);
-        match self.new_costs {
-            None => {}
-            Some(new_costs) => {
-                let mut cost_table =
-                    engine_grpc_server::engine_server::ipc::ChainSpec_CostTable::new();
+        if self.new_costs.is_some() || self.new_host_function_costs.is_some() {
+            let mut cost_table = engine_grpc_server::engine_server::ipc::ChainSpec_CostTable::new();
+            if let Some(new_costs) = self.new_costs {
                 cost_table.set_wasm(new_costs);
-                upgrade_point.set_new_costs(cost_table);
             }
+            if let Some(new_host_function_costs) = self.new_host_function_costs {
+                cost_table.set_host(new_host_function_costs);
+            }
+            upgrade_point.set_new_costs(cost_table);
+        }
+        if let Some(new_pos_config) = self.new_pos_config {
+            upgrade_point.set_new_pos_config(new_pos_config);
         }
         upgrade_point.set_protocol_version(self.new_protocol_version);
         upgrade_point.set_upgrade_installer(self.upgrade_installer);
@@ -425,6 +468,7 @@ impl UpgradeRequestBuilder {
         let mut upgrade_request = UpgradeRequest::new();
         upgrade_request.set_protocol_version(self.current_protocol_version);
         upgrade_request.set_upgrade_point(upgrade_point);
+        upgrade_request.set_block_time(self.block_time);
         upgrade_request
     }
 }
@@ -437,7 +481,10 @@ impl Default for UpgradeRequestBuilder {
             new_protocol_version: Default::default(),
             upgrade_installer: Default::default(),
             new_costs: None,
+            new_host_function_costs: None,
+            new_pos_config: None,
             activation_point: Default::default(),
+            block_time: Default::default(),
         }
     }
 }
@@ -552,8 +599,17 @@ impl LmdbWasmTestBuilder {
             LmdbProtocolDataStore::new(&environment, None, DatabaseFlags::empty())
                 .expect("should create LmdbProtocolDataStore"),
         );
-        let global_state = LmdbGlobalState::empty(environment, trie_store, protocol_data_store)
-            .expect("should create LmdbGlobalState");
+        let state_root_store = Arc::new(
+            LmdbStateRootStore::new(&environment, None, DatabaseFlags::empty())
+                .expect("should create LmdbStateRootStore"),
+        );
+        let global_state = LmdbGlobalState::empty(
+            environment,
+            trie_store,
+            protocol_data_store,
+            state_root_store,
+        )
+        .expect("should create LmdbGlobalState");
         let engine_state = EngineState::new(global_state, engine_config);
         WasmTestBuilder {
             engine_state: Rc::new(engine_state),
@@ -611,8 +667,16 @@ impl LmdbWasmTestBuilder {
             LmdbProtocolDataStore::open(&environment, None)
                 .expect("should open LmdbProtocolDataStore"),
         );
-        let global_state = LmdbGlobalState::empty(environment, trie_store, protocol_data_store)
-            .expect("should create LmdbGlobalState");
+        let state_root_store = Arc::new(
+            LmdbStateRootStore::open(&environment, None).expect("should open LmdbStateRootStore"),
+        );
+        let global_state = LmdbGlobalState::empty(
+            environment,
+            trie_store,
+            protocol_data_store,
+            state_root_store,
+        )
+        .expect("should create LmdbGlobalState");
         let engine_state = EngineState::new(global_state, engine_config);
         WasmTestBuilder {
             engine_state: Rc::new(engine_state),
@@ -737,6 +801,252 @@ where
         }
     }
 
+    /// Like `query`, but also returns the Merkle proofs of the values read while resolving the
+    /// query.
+    pub fn query_with_proofs(
+        &self,
+        maybe_post_state: Option<Vec<u8>>,
+        base_key: Key,
+        path: &[&str],
+    ) -> (Option<StoredValue>, Vec<TrieMerkleProof<Key, StoredValue>>) {
+        let post_state = maybe_post_state
+            .or_else(|| self.post_state_hash.clone())
+            .expect("builder must have a post-state hash");
+
+        let path_vec: Vec<String> = path.iter().map(|s| String::from(*s)).collect();
+
+        let mut query_request = create_query_request(post_state, base_key, path_vec);
+        query_request.set_include_proofs(true);
+
+        let mut query_response = self
+            .engine_state
+            .query(RequestOptions::new(), query_request)
+            .wait_drop_metadata()
+            .expect("should get query response");
+
+        let proofs = query_response
+            .take_proofs()
+            .into_iter()
+            .map(|pb_proof| pb_proof.try_into().expect("should parse proof"))
+            .collect();
+
+        let maybe_value = if query_response.has_success() {
+            query_response.take_success().try_into().ok()
+        } else {
+            None
+        };
+
+        (maybe_value, proofs)
+    }
+
+    /// Lists up to `limit` of the key-value pairs whose serialized keys start with `prefix` in
+    /// the latest post-state, beginning at `start`.  Returns them along with the start of the
+    /// next page, if there is one.
+    pub fn list_keys(
+        &self,
+        prefix: &[u8],
+        start: &[u8],
+        limit: u32,
+    ) -> (Vec<(Key, StoredValue)>, Option<Vec<u8>>) {
+        let post_state = self
+            .post_state_hash
+            .clone()
+            .expect("builder must have a post-state hash");
+
+        let mut list_keys_request = ListKeysRequest::new();
+        list_keys_request.set_state_hash(post_state);
+        list_keys_request.set_prefix(prefix.to_vec());
+        list_keys_request.set_start(start.to_vec());
+        list_keys_request.set_limit(limit);
+
+        let mut list_keys_response = self
+            .engine_state
+            .list_keys(RequestOptions::new(), list_keys_request)
+            .wait_drop_metadata()
+            .expect("should get list keys response");
+        assert!(list_keys_response.has_success(), "{:?}", list_keys_response);
+
+        let mut result = list_keys_response.take_success();
+        let entries = result
+            .take_entries()
+            .into_iter()
+            .map(|mut pb_entry| {
+                let key = pb_entry.take_key().try_into().expect("should parse key");
+                let value = pb_entry
+                    .take_value()
+                    .try_into()
+                    .expect("should parse value");
+                (key, value)
+            })
+            .collect();
+        let next_start = Some(result.take_next_start()).filter(|start| !start.is_empty());
+
+        (entries, next_start)
+    }
+
+    /// Returns the keys whose values differ between the given post-states, with their old and
+    /// new values, fetching one page after another until all are found.
+    pub fn diff_state(
+        &self,
+        old_state_hash: Vec<u8>,
+        new_state_hash: Vec<u8>,
+    ) -> Vec<Difference<Key, StoredValue>> {
+        let mut differences = Vec::new();
+        let mut start = Vec::new();
+        loop {
+            let (mut page, next_start) =
+                self.diff_state_page(old_state_hash.clone(), new_state_hash.clone(), &start, 0);
+            differences.append(&mut page);
+            match next_start {
+                Some(next_start) => start = next_start,
+                None => return differences,
+            }
+        }
+    }
+
+    /// Returns up to `limit` of the keys whose values differ between the given post-states,
+    /// beginning at the serialized key `start`, along with the serialized key which starts the
+    /// next page, if there is one.
+    pub fn diff_state_page(
+        &self,
+        old_state_hash: Vec<u8>,
+        new_state_hash: Vec<u8>,
+        start: &[u8],
+        limit: u32,
+    ) -> (Vec<Difference<Key, StoredValue>>, Option<Vec<u8>>) {
+        let mut diff_state_request = DiffStateRequest::new();
+        diff_state_request.set_old_state_hash(old_state_hash);
+        diff_state_request.set_new_state_hash(new_state_hash);
+        diff_state_request.set_start(start.to_vec());
+        diff_state_request.set_limit(limit);
+
+        let mut diff_state_response = self
+            .engine_state
+            .diff_state(RequestOptions::new(), diff_state_request)
+            .wait_drop_metadata()
+            .expect("should get diff state response");
+        assert!(
+            diff_state_response.has_success(),
+            "{:?}",
+            diff_state_response
+        );
+
+        let mut result = diff_state_response.take_success();
+        let differences = result
+            .take_entries()
+            .into_iter()
+            .map(|pb_entry| pb_entry.try_into().expect("should parse state diff entry"))
+            .collect();
+        let next_start = Some(result.take_next_start()).filter(|start| !start.is_empty());
+
+        (differences, next_start)
+    }
+
+    /// Returns the serialized trie element stored under `trie_key`, if any.
+    pub fn get_trie(&self, trie_key: Vec<u8>) -> Option<Vec<u8>> {
+        let mut get_trie_request = GetTrieRequest::new();
+        get_trie_request.set_trie_key(trie_key);
+
+        let mut get_trie_response = self
+            .engine_state
+            .get_trie(RequestOptions::new(), get_trie_request)
+            .wait_drop_metadata()
+            .expect("should get get trie response");
+        assert!(!get_trie_response.has_failure(), "{:?}", get_trie_response);
+
+        if get_trie_response.has_trie() {
+            Some(get_trie_response.take_trie())
+        } else {
+            None
+        }
+    }
+
+    /// Stores the serialized trie elements in `entries`, each paired with the trie key it was
+    /// requested by.  Returns the hashes they were stored under, or the failure message if they
+    /// were rejected.
+    pub fn put_tries(&self, entries: Vec<(Vec<u8>, Vec<u8>)>) -> Result<Vec<Vec<u8>>, String> {
+        let pb_entries: Vec<PutTrieRequest_Entry> = entries
+            .into_iter()
+            .map(|(trie_key, trie)| {
+                let mut pb_entry = PutTrieRequest_Entry::new();
+                pb_entry.set_trie_key(trie_key);
+                pb_entry.set_trie(trie);
+                pb_entry
+            })
+            .collect();
+        let mut put_trie_request = PutTrieRequest::new();
+        put_trie_request.set_entries(pb_entries.into());
+
+        let mut put_trie_response = self
+            .engine_state
+            .put_trie(RequestOptions::new(), put_trie_request)
+            .wait_drop_metadata()
+            .expect("should get put trie response");
+
+        if put_trie_response.has_success() {
+            Ok(put_trie_response.take_success().take_trie_keys().into_vec())
+        } else {
+            Err(put_trie_response.take_failure())
+        }
+    }
+
+    /// Returns the hashes of up to `limit` trie elements reachable from `trie_keys` which are not
+    /// yet in global state, along with the trie keys left unexplored once the limit was reached.
+    pub fn missing_trie_keys(
+        &self,
+        trie_keys: Vec<Vec<u8>>,
+        limit: u32,
+    ) -> (Vec<Vec<u8>>, Vec<Vec<u8>>) {
+        let mut missing_trie_keys_request = MissingTrieKeysRequest::new();
+        missing_trie_keys_request.set_trie_keys(trie_keys.into());
+        missing_trie_keys_request.set_limit(limit);
+
+        let mut missing_trie_keys_response = self
+            .engine_state
+            .missing_trie_keys(RequestOptions::new(), missing_trie_keys_request)
+            .wait_drop_metadata()
+            .expect("should get missing trie keys response");
+        assert!(
+            missing_trie_keys_response.has_success(),
+            "{:?}",
+            missing_trie_keys_response
+        );
+
+        let mut result = missing_trie_keys_response.take_success();
+        (
+            result.take_trie_keys().into_vec(),
+            result.take_unexplored_trie_keys().into_vec(),
+        )
+    }
+
+    /// Returns the state roots produced by commits, genesis and upgrades, in ascending order of
+    /// state hash, requesting them one page at a time.
+    pub fn list_state_roots(&self) -> Vec<StateRoot> {
+        let mut state_roots = Vec::new();
+        let mut start = Vec::new();
+        loop {
+            let mut list_state_roots_request = ListStateRootsRequest::new();
+            list_state_roots_request.set_start(start);
+            let mut list_state_roots_response = self
+                .engine_state
+                .list_state_roots(RequestOptions::new(), list_state_roots_request)
+                .wait_drop_metadata()
+                .expect("should get list state roots response");
+            assert!(
+                list_state_roots_response.has_success(),
+                "{:?}",
+                list_state_roots_response
+            );
+
+            let mut result = list_state_roots_response.take_success();
+            state_roots.extend(result.take_state_roots().into_iter());
+            start = result.take_next_start();
+            if start.is_empty() {
+                return state_roots;
+            }
+        }
+    }
+
     pub fn exec(&mut self, mut exec_request: ExecuteRequest) -> &mut Self {
         let exec_request = {
             let hash = self
@@ -772,6 +1082,156 @@ where
         self
     }
 
+    /// Executes and commits the deploys of `exec_request` in a single call, each deploy running
+    /// against the post-state of the ones before it.  On success, overwrites the cached post
+    /// state hash with the new one.
+    pub fn exec_and_commit(
+        &mut self,
+        mut exec_request: ExecuteRequest,
+    ) -> ExecuteAndCommitResponse {
+        let parent_state_hash = self
+            .post_state_hash
+            .clone()
+            .expect("expected post_state_hash");
+
+        let mut request = ExecuteAndCommitRequest::new();
+        request.set_parent_state_hash(parent_state_hash);
+        request.set_block_time(exec_request.get_block_time());
+        request.set_deploys(exec_request.take_deploys());
+        request.set_protocol_version(exec_request.take_protocol_version());
+
+        let response = self
+            .engine_state
+            .execute_and_commit(RequestOptions::new(), request)
+            .wait_drop_metadata()
+            .expect("should execute and commit");
+
+        if response.has_success() {
+            self.post_state_hash = Some(response.get_success().get_poststate_hash().to_vec());
+        }
+
+        response
+    }
+
+    /// Estimates the gas needed by the first deploy of `exec_request` by running only its session
+    /// code against the latest post-state.  Nothing is cached or committed.
+    pub fn estimate_gas(
+        &self,
+        mut exec_request: ExecuteRequest,
+        gas_limit: Option<U512>,
+    ) -> EstimateGasResponse {
+        let parent_state_hash = self
+            .post_state_hash
+            .clone()
+            .expect("expected post_state_hash");
+        let deploy = exec_request
+            .take_deploys()
+            .into_iter()
+            .next()
+            .expect("should have a deploy");
+
+        let mut request = EstimateGasRequest::new();
+        request.set_parent_state_hash(parent_state_hash);
+        request.set_block_time(exec_request.get_block_time());
+        request.set_deploy(deploy);
+        request.set_protocol_version(exec_request.take_protocol_version());
+        if let Some(gas_limit) = gas_limit {
+            request.set_gas_limit(gas_limit.into());
+        }
+
+        self.engine_state
+            .estimate_gas(RequestOptions::new(), request)
+            .wait_drop_metadata()
+            .expect("should estimate gas")
+    }
+
+    /// Runs the proof of stake contract's `step` at `block_time` on the latest post-state, then
+    /// distributes its rewards purse according to `reward_weights` unless they are empty.  On
+    /// success, overwrites the cached post state hash with the new one and caches the bonded
+    /// validators.
+    pub fn step(&mut self, block_time: u64, reward_weights: &[(PublicKey, u64)]) -> StepResponse {
+        let parent_state_hash = self
+            .post_state_hash
+            .clone()
+            .expect("expected post_state_hash");
+
+        let mut request = StepRequest::new();
+        request.set_parent_state_hash(parent_state_hash);
+        request.set_block_time(block_time);
+        request.set_protocol_version(get_protocol_version());
+        request.set_reward_weights(
+            reward_weights
+                .iter()
+                .cloned()
+                .map(RewardWeight::from)
+                .collect(),
+        );
+
+        let response = self
+            .engine_state
+            .step(RequestOptions::new(), request)
+            .wait_drop_metadata()
+            .expect("should run step");
+
+        if response.has_success() {
+            let step_success = response.get_success();
+            self.post_state_hash = Some(step_success.get_poststate_hash().to_vec());
+            let bonded_validators = step_success
+                .get_bonded_validators()
+                .iter()
+                .cloned()
+                .map(TryInto::try_into)
+                .collect::<Result<HashMap<PublicKey, U512>, MappingError>>()
+                .unwrap();
+            self.bonded_validators.push(bonded_validators);
+        }
+
+        response
+    }
+
+    /// Slashes `validators` by `fraction` millionths of their stakes on the latest post-state.  On
+    /// success, overwrites the cached post state hash with the new one and caches the bonded
+    /// validators.
+    pub fn slash(
+        &mut self,
+        block_time: u64,
+        validators: &[PublicKey],
+        fraction: u64,
+    ) -> SlashResponse {
+        let parent_state_hash = self
+            .post_state_hash
+            .clone()
+            .expect("expected post_state_hash");
+
+        let mut request = SlashRequest::new();
+        request.set_parent_state_hash(parent_state_hash);
+        request.set_block_time(block_time);
+        request.set_protocol_version(get_protocol_version());
+        request.set_validator_public_keys(validators.iter().map(PublicKey::to_vec).collect());
+        request.set_fraction(fraction);
+
+        let response = self
+            .engine_state
+            .slash(RequestOptions::new(), request)
+            .wait_drop_metadata()
+            .expect("should run slash");
+
+        if response.has_success() {
+            let slash_success = response.get_success();
+            self.post_state_hash = Some(slash_success.get_poststate_hash().to_vec());
+            let bonded_validators = slash_success
+                .get_bonded_validators()
+                .iter()
+                .cloned()
+                .map(TryInto::try_into)
+                .collect::<Result<HashMap<PublicKey, U512>, MappingError>>()
+                .unwrap();
+            self.bonded_validators.push(bonded_validators);
+        }
+
+        response
+    }
+
     /// Commit effects of previous exec call on the latest post-state hash.
     pub fn commit(&mut self) -> &mut Self {
         let prestate_hash = self
@@ -966,6 +1426,17 @@ where
             .expect("should find PoS URef")
     }
 
+    pub fn get_pos_config(&self) -> pos::Config {
+        let pos_contract: Key = self
+            .pos_contract_uref
+            .expect("should have pos contract uref")
+            .into();
+        self.query(None, pos_contract, &[POS_CONFIG])
+            .and_then(|v| CLValue::try_from(v).ok())
+            .and_then(|cl_value| cl_value.into_t().ok())
+            .expect("should find PoS config")
+    }
+
     pub fn get_purse_balance(&self, purse_id: PurseId) -> U512 {
         let mint = self.get_mint_contract_uref();
         let purse_addr = purse_id.value().addr();
@@ -1015,12 +1486,27 @@ where
         get_exec_costs(exec_response)
     }
 
+    pub fn exec_events(&self, index: usize) -> Vec<ContractEvent> {
+        let exec_response = self
+            .get_exec_response(index)
+            .expect("should have exec response");
+        get_exec_events(exec_response)
+    }
+
     pub fn exec_error_message(&self, index: usize) -> Option<String> {
         let response = self.get_exec_response(index)?;
         let execution_result = get_success_result(&response);
         Some(get_error_message(execution_result))
     }
 
+    /// Returns the trace of the first deploy of the exec response at `index`, if it was requested.
+    pub fn exec_trace(&self, index: usize) -> Option<ExecutionTrace> {
+        let exec_response = self
+            .get_exec_response(index)
+            .expect("should have exec response");
+        get_exec_trace(exec_response)
+    }
+
     pub fn exec_commit_finish(&mut self, execute_request: ExecuteRequest) -> WasmTestResult<S> {
         self.exec(execute_request)
             .expect_success()
@@ -1082,6 +1568,8 @@ pub fn create_genesis_config(accounts: Vec<GenesisAccount>) -> GenesisConfig {
     let proof_of_stake_installer_bytes = read_wasm_file_bytes(CONTRACT_POS_INSTALL);
     let protocol_version = *DEFAULT_PROTOCOL_VERSION;
     let wasm_costs = *DEFAULT_WASM_COSTS;
+    let host_function_costs = *DEFAULT_HOST_FUNCTION_COSTS;
+    let proof_of_stake_config = *DEFAULT_POS_CONFIG;
     GenesisConfig::new(
         name,
         timestamp,
@@ -1090,6 +1578,8 @@ pub fn create_genesis_config(accounts: Vec<GenesisAccount>) -> GenesisConfig {
         proof_of_stake_installer_bytes,
         accounts,
         wasm_costs,
+        host_function_costs,
+        proof_of_stake_config,
     )
 }
 
@@ -1148,6 +1638,27 @@ pub fn get_exec_costs(exec_response: &ExecuteResponse) -> Vec<Gas> {
         .collect()
 }
 
+pub fn get_exec_events(exec_response: &ExecuteResponse) -> Vec<ContractEvent> {
+    get_success_result(exec_response)
+        .take_events()
+        .into_iter()
+        .map(TryInto::try_into)
+        .collect::<Result<Vec<ContractEvent>, _>>()
+        .expect("events should map to ContractEvent")
+}
+
+pub fn get_exec_trace(exec_response: &ExecuteResponse) -> Option<ExecutionTrace> {
+    let mut execution_result = get_success_result(exec_response);
+    if !execution_result.has_trace() {
+        return None;
+    }
+    let trace = execution_result
+        .take_trace()
+        .try_into()
+        .expect("trace should map to ExecutionTrace");
+    Some(trace)
+}
+
 #[allow(clippy::implicit_hasher)]
 pub fn get_account(transforms: &AdditiveMap<Key, Transform>, account: &Key) -> Option<Account> {
     transforms.get(account).and_then(|transform| {
@@ -0,0 +1,60 @@
+use contract_ffi::{key::Key, value::CLValue};
+
+use crate::{
+    support::test_support::{ExecuteRequestBuilder, InMemoryWasmTestBuilder},
+    test::{DEFAULT_ACCOUNT_ADDR, DEFAULT_GENESIS_CONFIG},
+};
+
+const CONTRACT_EMIT_EVENT: &str = "emit_event.wasm";
+const EVENT_TOPIC: &str = "counter";
+
+#[ignore]
+#[test]
+fn should_return_emitted_events_in_order() {
+    let event_count: u64 = 3;
+    let exec_request = ExecuteRequestBuilder::standard(
+        DEFAULT_ACCOUNT_ADDR,
+        CONTRACT_EMIT_EVENT,
+        (event_count, false),
+    )
+    .build();
+
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder
+        .run_genesis(&DEFAULT_GENESIS_CONFIG)
+        .exec(exec_request)
+        .expect_success()
+        .commit();
+
+    let events = builder.exec_events(0);
+    assert_eq!(events.len(), event_count as usize);
+
+    for (i, event) in events.iter().enumerate() {
+        assert_eq!(event.emitter(), Key::Account(DEFAULT_ACCOUNT_ADDR));
+        assert_eq!(event.topic(), EVENT_TOPIC);
+        assert_eq!(
+            event.value(),
+            &CLValue::from_t(i as u64).expect("should create CLValue")
+        );
+    }
+}
+
+#[ignore]
+#[test]
+fn should_drop_events_of_reverted_session() {
+    let exec_request =
+        ExecuteRequestBuilder::standard(DEFAULT_ACCOUNT_ADDR, CONTRACT_EMIT_EVENT, (3u64, true))
+            .build();
+
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder
+        .run_genesis(&DEFAULT_GENESIS_CONFIG)
+        .exec(exec_request)
+        .commit();
+
+    assert!(builder.is_error(), "session should revert");
+    assert!(
+        builder.exec_events(0).is_empty(),
+        "events of reverted session should be dropped"
+    );
+}
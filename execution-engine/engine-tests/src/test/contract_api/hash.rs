@@ -0,0 +1,67 @@
+use contract_ffi::contract_api::crypto::HashAlgorithm;
+
+use crate::{
+    support::test_support::{ExecuteRequestBuilder, InMemoryWasmTestBuilder},
+    test::{DEFAULT_ACCOUNT_ADDR, DEFAULT_GENESIS_CONFIG},
+};
+
+const CONTRACT_HASH: &str = "hash.wasm";
+const INPUT: &[u8] = b"abc";
+
+fn run_hash_contract(algorithm: HashAlgorithm, expected_digest: &str) -> InMemoryWasmTestBuilder {
+    let algorithm_index: u32 = algorithm.into();
+    let expected_digest = base16::decode(expected_digest).expect("should decode digest");
+    let exec_request = ExecuteRequestBuilder::standard(
+        DEFAULT_ACCOUNT_ADDR,
+        CONTRACT_HASH,
+        (algorithm_index, INPUT.to_vec(), expected_digest),
+    )
+    .build();
+
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder
+        .run_genesis(&DEFAULT_GENESIS_CONFIG)
+        .exec(exec_request)
+        .commit();
+    builder
+}
+
+#[ignore]
+#[test]
+fn should_hash_with_blake2b() {
+    run_hash_contract(
+        HashAlgorithm::Blake2b,
+        "bddd813c634239723171ef3fee98579b94964e3bb1cb3e427262c8c068d52319",
+    )
+    .expect_success();
+}
+
+#[ignore]
+#[test]
+fn should_hash_with_sha256() {
+    run_hash_contract(
+        HashAlgorithm::Sha256,
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+    )
+    .expect_success();
+}
+
+#[ignore]
+#[test]
+fn should_hash_with_keccak256() {
+    run_hash_contract(
+        HashAlgorithm::Keccak256,
+        "4e03657aea45a94fc7d47ba826c8d667c0d1e6e33a64a036ec44f58fa12d6c45",
+    )
+    .expect_success();
+}
+
+#[ignore]
+#[test]
+fn should_fail_on_digest_mismatch() {
+    let builder = run_hash_contract(
+        HashAlgorithm::Keccak256,
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+    );
+    assert!(builder.is_error(), "digest should not match");
+}
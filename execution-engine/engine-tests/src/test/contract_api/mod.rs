@@ -1,6 +1,10 @@
 #[cfg(test)]
 mod create_purse;
 #[cfg(test)]
+mod emit_event;
+#[cfg(test)]
+mod execution_trace;
+#[cfg(test)]
 mod get_arg;
 #[cfg(test)]
 mod get_blocktime;
@@ -9,6 +13,8 @@ mod get_caller;
 #[cfg(test)]
 mod get_phase;
 #[cfg(test)]
+mod hash;
+#[cfg(test)]
 mod list_named_keys;
 #[cfg(test)]
 mod local_state;
@@ -26,6 +32,8 @@ mod transfer;
 mod transfer_purse_to_account;
 #[cfg(test)]
 mod transfer_purse_to_purse;
+#[cfg(test)]
+mod verify_signature;
 
 #[cfg(test)]
 pub mod account;
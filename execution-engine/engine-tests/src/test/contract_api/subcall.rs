@@ -68,8 +68,6 @@ fn should_charge_gas_for_subcall() {
     );
 }
 
-// TODO: remove `#[should_panic]` once subcalls' gas costs are included in total costs.
-#[should_panic]
 #[ignore]
 #[test]
 fn should_add_all_gas_for_subcall() {
@@ -148,3 +146,89 @@ fn should_add_all_gas_for_subcall() {
         gas_to_add + add_zero_gas_via_subcall_cost.value()
     );
 }
+
+#[ignore]
+#[test]
+fn should_add_all_gas_for_reverted_subcall() {
+    const CONTRACT_NAME: &str = "add_gas_subcall.wasm";
+    const ADD_GAS_AND_REVERT_VIA_SUBCALL: &str = "add-gas-and-revert-via-subcall";
+
+    // Use 90% of the standard test contract's balance
+    let gas_to_add: U512 = *DEFAULT_PAYMENT / CONV_RATE * 9 / 10;
+
+    assert!(gas_to_add <= U512::from(i32::max_value()));
+    let gas_to_add_as_arg: i32 = gas_to_add.as_();
+
+    let add_zero_gas_and_revert_request = ExecuteRequestBuilder::standard(
+        DEFAULT_ACCOUNT_ADDR,
+        CONTRACT_NAME,
+        (0, ADD_GAS_AND_REVERT_VIA_SUBCALL),
+    )
+    .build();
+
+    let add_some_gas_and_revert_request = ExecuteRequestBuilder::standard(
+        DEFAULT_ACCOUNT_ADDR,
+        CONTRACT_NAME,
+        (gas_to_add_as_arg, ADD_GAS_AND_REVERT_VIA_SUBCALL),
+    )
+    .build();
+
+    let mut builder = InMemoryWasmTestBuilder::default();
+
+    builder.run_genesis(&DEFAULT_GENESIS_CONFIG);
+
+    let is_error = builder
+        .exec(add_zero_gas_and_revert_request)
+        .commit()
+        .is_error();
+    assert!(is_error, "subcall should revert");
+
+    let is_error = builder
+        .exec(add_some_gas_and_revert_request)
+        .commit()
+        .is_error();
+    assert!(is_error, "subcall should revert");
+
+    let add_zero_gas_and_revert_cost = builder.exec_costs(0)[0];
+    let add_some_gas_and_revert_cost = builder.exec_costs(1)[0];
+
+    assert!(add_zero_gas_and_revert_cost.value() < gas_to_add);
+    assert_eq!(
+        add_some_gas_and_revert_cost.value(),
+        gas_to_add + add_zero_gas_and_revert_cost.value()
+    );
+}
+
+#[ignore]
+#[test]
+fn should_not_exceed_gas_limit_via_subcall() {
+    const CONTRACT_NAME: &str = "add_gas_subcall.wasm";
+    const ADD_GAS_VIA_SUBCALL: &str = "add-gas-via-subcall";
+
+    // Use all of the standard test contract's balance, leaving nothing for the caller
+    let gas_to_add: U512 = *DEFAULT_PAYMENT / CONV_RATE;
+
+    assert!(gas_to_add <= U512::from(i32::max_value()));
+    let gas_to_add_as_arg: i32 = gas_to_add.as_();
+
+    let add_gas_via_subcall_request = ExecuteRequestBuilder::standard(
+        DEFAULT_ACCOUNT_ADDR,
+        CONTRACT_NAME,
+        (gas_to_add_as_arg, ADD_GAS_VIA_SUBCALL),
+    )
+    .build();
+
+    let mut builder = InMemoryWasmTestBuilder::default();
+
+    let is_error = builder
+        .run_genesis(&DEFAULT_GENESIS_CONFIG)
+        .exec(add_gas_via_subcall_request)
+        .commit()
+        .is_error();
+    assert!(is_error, "subcall should run out of gas");
+
+    let error_message = builder
+        .exec_error_message(0)
+        .expect("should have exec error");
+    assert_eq!(error_message, "GasLimit", "expected gas limit error");
+}
@@ -0,0 +1,121 @@
+use sha2::{Digest, Sha256};
+
+use contract_ffi::contract_api::{crypto::SignatureScheme, Error};
+
+use crate::{
+    support::test_support::{ExecuteRequestBuilder, InMemoryWasmTestBuilder},
+    test::{DEFAULT_ACCOUNT_ADDR, DEFAULT_GENESIS_CONFIG, DEFAULT_WASM_COSTS},
+};
+
+const CONTRACT_VERIFY_SIGNATURE: &str = "verify_signature.wasm";
+const MESSAGE: &[u8] = b"voucher #1";
+const SECRET_KEY_BYTES: [u8; 32] = [42u8; 32];
+
+fn ed25519_sign(message: &[u8]) -> (Vec<u8>, Vec<u8>) {
+    let secret_key =
+        ed25519_dalek::SecretKey::from_bytes(&SECRET_KEY_BYTES).expect("should create secret key");
+    let public_key: ed25519_dalek::PublicKey = (&secret_key).into();
+    let expanded_secret_key: ed25519_dalek::ExpandedSecretKey = (&secret_key).into();
+    let signature = expanded_secret_key.sign(message, &public_key);
+    (
+        public_key.to_bytes().to_vec(),
+        signature.to_bytes().to_vec(),
+    )
+}
+
+fn secp256k1_sign(message: &[u8]) -> (Vec<u8>, Vec<u8>) {
+    let secret_key =
+        secp256k1::SecretKey::parse(&SECRET_KEY_BYTES).expect("should create secret key");
+    let public_key = secp256k1::PublicKey::from_secret_key(&secret_key);
+    let digest = {
+        let mut ret = [0u8; 32];
+        ret.copy_from_slice(&Sha256::digest(message));
+        secp256k1::Message::parse(&ret)
+    };
+    let (signature, _recovery_id) = secp256k1::sign(&digest, &secret_key);
+    (
+        public_key.serialize_compressed().to_vec(),
+        signature.serialize().to_vec(),
+    )
+}
+
+fn run_verify_signature(
+    scheme: SignatureScheme,
+    public_key: Vec<u8>,
+    message: Vec<u8>,
+    signature: Vec<u8>,
+) -> InMemoryWasmTestBuilder {
+    let scheme_index: u32 = scheme.into();
+    let exec_request = ExecuteRequestBuilder::standard(
+        DEFAULT_ACCOUNT_ADDR,
+        CONTRACT_VERIFY_SIGNATURE,
+        (scheme_index, public_key, message, signature),
+    )
+    .build();
+
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder
+        .run_genesis(&DEFAULT_GENESIS_CONFIG)
+        .exec(exec_request)
+        .commit();
+    builder
+}
+
+#[ignore]
+#[test]
+fn should_verify_ed25519_signature() {
+    let (public_key, signature) = ed25519_sign(MESSAGE);
+    let builder = run_verify_signature(
+        SignatureScheme::Ed25519,
+        public_key,
+        MESSAGE.to_vec(),
+        signature,
+    );
+
+    assert!(!builder.is_error(), "signature should be valid");
+    let cost = builder.exec_costs(0)[0];
+    assert!(
+        cost.value() > DEFAULT_WASM_COSTS.verify_ed25519.into(),
+        "verification should be charged"
+    );
+}
+
+#[ignore]
+#[test]
+fn should_verify_secp256k1_signature() {
+    let (public_key, signature) = secp256k1_sign(MESSAGE);
+    let builder = run_verify_signature(
+        SignatureScheme::Secp256k1,
+        public_key,
+        MESSAGE.to_vec(),
+        signature,
+    );
+
+    assert!(!builder.is_error(), "signature should be valid");
+    let cost = builder.exec_costs(0)[0];
+    assert!(
+        cost.value() > DEFAULT_WASM_COSTS.verify_secp256k1.into(),
+        "verification should be charged"
+    );
+}
+
+#[ignore]
+#[test]
+fn should_reject_signature_of_other_message() {
+    let (public_key, signature) = ed25519_sign(b"voucher #2");
+    let builder = run_verify_signature(
+        SignatureScheme::Ed25519,
+        public_key,
+        MESSAGE.to_vec(),
+        signature,
+    );
+
+    let error_message = builder
+        .exec_error_message(0)
+        .expect("should have exec error");
+    assert_eq!(
+        error_message,
+        format!("Exit code: {}", u32::from(Error::User(0))),
+        "signature should be rejected"
+    );
+}
@@ -0,0 +1,165 @@
+use std::convert::TryInto;
+
+use num_traits::identities::Zero;
+
+use contract_ffi::value::{account::PublicKey, U512};
+use engine_core::engine_state::genesis::GenesisAccount;
+use engine_grpc_server::engine_server::ipc::{DeployResult, EstimateGasResponse};
+use engine_shared::motes::Motes;
+
+use crate::{
+    support::test_support::{self, ExecuteRequestBuilder, InMemoryWasmTestBuilder},
+    test::{DEFAULT_ACCOUNTS, DEFAULT_ACCOUNT_ADDR, DEFAULT_GENESIS_CONFIG},
+};
+
+const CONTRACT_ADD_GAS_SUBCALL: &str = "add_gas_subcall.wasm";
+const CONTRACT_DO_NOTHING: &str = "do_nothing.wasm";
+const ADD_GAS_FROM_SESSION: &str = "add-gas-from-session";
+const ACCOUNT_1_ADDR: [u8; 32] = [1u8; 32];
+
+fn get_deploy_result(response: &EstimateGasResponse) -> &DeployResult {
+    assert!(response.has_success(), "{:?}", response);
+    let deploy_result = response.get_success();
+    assert!(deploy_result.has_execution_result(), "{:?}", deploy_result);
+    deploy_result
+}
+
+fn get_cost(deploy_result: &DeployResult) -> U512 {
+    deploy_result
+        .get_execution_result()
+        .get_cost()
+        .clone()
+        .try_into()
+        .expect("cost should map to U512")
+}
+
+#[ignore]
+#[test]
+fn should_estimate_gas_for_account_without_balance() {
+    let accounts = {
+        let mut tmp: Vec<GenesisAccount> = DEFAULT_ACCOUNTS.clone();
+        let account =
+            GenesisAccount::new(PublicKey::new(ACCOUNT_1_ADDR), Motes::zero(), Motes::zero());
+        tmp.push(account);
+        tmp
+    };
+    let genesis_config = test_support::create_genesis_config(accounts);
+
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder.run_genesis(&genesis_config);
+
+    let exec_request =
+        ExecuteRequestBuilder::standard(ACCOUNT_1_ADDR, CONTRACT_DO_NOTHING, ()).build();
+
+    let response = builder.estimate_gas(exec_request, None);
+
+    let deploy_result = get_deploy_result(&response);
+    assert!(
+        !deploy_result.get_execution_result().has_error(),
+        "{:?}",
+        deploy_result
+    );
+    assert!(get_cost(deploy_result) > U512::zero());
+}
+
+#[ignore]
+#[test]
+fn should_estimate_gas_used_by_session_only() {
+    const GAS_TO_ADD: i32 = 1_000_000;
+
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder.run_genesis(&DEFAULT_GENESIS_CONFIG);
+
+    let add_zero_gas_request = ExecuteRequestBuilder::standard(
+        DEFAULT_ACCOUNT_ADDR,
+        CONTRACT_ADD_GAS_SUBCALL,
+        (0, ADD_GAS_FROM_SESSION),
+    )
+    .build();
+
+    let add_some_gas_request = ExecuteRequestBuilder::standard(
+        DEFAULT_ACCOUNT_ADDR,
+        CONTRACT_ADD_GAS_SUBCALL,
+        (GAS_TO_ADD, ADD_GAS_FROM_SESSION),
+    )
+    .build();
+
+    let add_zero_gas_response = builder.estimate_gas(add_zero_gas_request, None);
+    let add_some_gas_response = builder.estimate_gas(add_some_gas_request, None);
+
+    let add_zero_gas_cost = get_cost(get_deploy_result(&add_zero_gas_response));
+    let add_some_gas_cost = get_cost(get_deploy_result(&add_some_gas_response));
+
+    assert_eq!(
+        add_some_gas_cost - add_zero_gas_cost,
+        U512::from(GAS_TO_ADD),
+        "estimates should differ by exactly the gas added by the session"
+    );
+}
+
+#[ignore]
+#[test]
+fn should_report_gas_limit_error_when_estimate_exceeds_cap() {
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder.run_genesis(&DEFAULT_GENESIS_CONFIG);
+
+    let exec_request =
+        ExecuteRequestBuilder::standard(DEFAULT_ACCOUNT_ADDR, CONTRACT_DO_NOTHING, ()).build();
+
+    let response = builder.estimate_gas(exec_request, Some(U512::from(1)));
+
+    let deploy_result = get_deploy_result(&response);
+    let execution_result = deploy_result.get_execution_result();
+    assert!(execution_result.has_error(), "{:?}", deploy_result);
+    assert!(execution_result.get_error().has_gas_error());
+}
+
+#[ignore]
+#[test]
+fn should_cap_requested_gas_limit_at_max_payment() {
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder.run_genesis(&DEFAULT_GENESIS_CONFIG);
+
+    // More gas than the maximum payment buys, but less than the requested limit.
+    let exec_request = ExecuteRequestBuilder::standard(
+        DEFAULT_ACCOUNT_ADDR,
+        CONTRACT_ADD_GAS_SUBCALL,
+        (std::i32::MAX, ADD_GAS_FROM_SESSION),
+    )
+    .build();
+
+    let response = builder.estimate_gas(exec_request, Some(U512::max_value()));
+
+    let deploy_result = get_deploy_result(&response);
+    let execution_result = deploy_result.get_execution_result();
+    assert!(execution_result.has_error(), "{:?}", deploy_result);
+    assert!(execution_result.get_error().has_gas_error());
+}
+
+#[ignore]
+#[test]
+fn should_not_commit_estimate_effects() {
+    const CONTRACT_TRANSFER_TO_ACCOUNT_01: &str = "transfer_to_account_01.wasm";
+
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder.run_genesis(&DEFAULT_GENESIS_CONFIG);
+    let genesis_hash = builder.get_genesis_hash();
+
+    let exec_request = ExecuteRequestBuilder::standard(
+        DEFAULT_ACCOUNT_ADDR,
+        CONTRACT_TRANSFER_TO_ACCOUNT_01,
+        (ACCOUNT_1_ADDR,),
+    )
+    .build();
+
+    let response = builder.estimate_gas(exec_request, None);
+    let deploy_result = get_deploy_result(&response);
+    assert!(
+        !deploy_result.get_execution_result().has_error(),
+        "{:?}",
+        deploy_result
+    );
+
+    assert_eq!(builder.get_post_state_hash(), genesis_hash);
+    assert!(builder.get_account(ACCOUNT_1_ADDR).is_none());
+}
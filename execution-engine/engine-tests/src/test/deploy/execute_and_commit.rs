@@ -0,0 +1,89 @@
+use contract_ffi::value::U512;
+
+use crate::{
+    support::test_support::{ExecuteRequestBuilder, InMemoryWasmTestBuilder},
+    test::{DEFAULT_ACCOUNT_ADDR, DEFAULT_GENESIS_CONFIG},
+};
+
+const CONTRACT_TRANSFER_TO_ACCOUNT_01: &str = "transfer_to_account_01.wasm";
+const CONTRACT_TRANSFER_TO_ACCOUNT_02: &str = "transfer_to_account_02.wasm";
+const ACCOUNT_1_ADDR: [u8; 32] = [1u8; 32];
+const ACCOUNT_2_ADDR: [u8; 32] = [2u8; 32];
+const TRANSFER_2_AMOUNT: u64 = 750;
+
+#[ignore]
+#[test]
+fn should_run_dependent_deploys_in_one_call() {
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder.run_genesis(&DEFAULT_GENESIS_CONFIG);
+    let genesis_hash = builder.get_genesis_hash();
+
+    // The second deploy is sent from the account created by the first one.
+    let exec_request = ExecuteRequestBuilder::standard(
+        DEFAULT_ACCOUNT_ADDR,
+        CONTRACT_TRANSFER_TO_ACCOUNT_01,
+        (ACCOUNT_1_ADDR,),
+    )
+    .push_deploy(
+        ExecuteRequestBuilder::standard(
+            ACCOUNT_1_ADDR,
+            CONTRACT_TRANSFER_TO_ACCOUNT_02,
+            (U512::from(TRANSFER_2_AMOUNT),),
+        )
+        .build()
+        .take_deploys()
+        .pop()
+        .expect("should have deploy"),
+    )
+    .build();
+
+    let response = builder.exec_and_commit(exec_request);
+    assert!(response.has_success(), "{:?}", response);
+
+    let deploy_results = response.get_success().get_deploy_results();
+    assert_eq!(deploy_results.len(), 2);
+    for deploy_result in deploy_results {
+        assert!(deploy_result.has_execution_result());
+        assert!(
+            !deploy_result.get_execution_result().has_error(),
+            "{:?}",
+            deploy_result
+        );
+    }
+
+    assert_ne!(builder.get_post_state_hash(), genesis_hash);
+
+    let account_2 = builder
+        .get_account(ACCOUNT_2_ADDR)
+        .expect("should have account 2");
+    let account_2_balance = builder.get_purse_balance(account_2.purse_id());
+    assert_eq!(account_2_balance, U512::from(TRANSFER_2_AMOUNT));
+}
+
+#[ignore]
+#[test]
+fn should_match_sequential_execute_and_commit() {
+    let exec_request = ExecuteRequestBuilder::standard(
+        DEFAULT_ACCOUNT_ADDR,
+        CONTRACT_TRANSFER_TO_ACCOUNT_01,
+        (ACCOUNT_1_ADDR,),
+    )
+    .build();
+
+    let mut sequential_builder = InMemoryWasmTestBuilder::default();
+    sequential_builder
+        .run_genesis(&DEFAULT_GENESIS_CONFIG)
+        .exec(exec_request.clone())
+        .expect_success()
+        .commit();
+
+    let mut batch_builder = InMemoryWasmTestBuilder::default();
+    batch_builder.run_genesis(&DEFAULT_GENESIS_CONFIG);
+    let response = batch_builder.exec_and_commit(exec_request);
+    assert!(response.has_success(), "{:?}", response);
+
+    assert_eq!(
+        batch_builder.get_post_state_hash(),
+        sequential_builder.get_post_state_hash()
+    );
+}
@@ -1,4 +1,8 @@
 #[cfg(test)]
+mod estimate_gas;
+#[cfg(test)]
+mod execute_and_commit;
+#[cfg(test)]
 mod payment_code;
 #[cfg(test)]
 mod preconditions;
@@ -0,0 +1,136 @@
+use contract_ffi::{bytesrepr::ToBytes, key::Key};
+use engine_storage::trie_store::operations::Difference;
+
+use crate::{
+    support::test_support::{ExecuteRequestBuilder, InMemoryWasmTestBuilder},
+    test::{DEFAULT_ACCOUNT_ADDR, DEFAULT_GENESIS_CONFIG},
+};
+
+const CONTRACT_TRANSFER_TO_ACCOUNT_01: &str = "transfer_to_account_01.wasm";
+const ACCOUNT_1_ADDR: [u8; 32] = [1u8; 32];
+
+fn setup() -> InMemoryWasmTestBuilder {
+    let exec_request = ExecuteRequestBuilder::standard(
+        DEFAULT_ACCOUNT_ADDR,
+        CONTRACT_TRANSFER_TO_ACCOUNT_01,
+        (ACCOUNT_1_ADDR,),
+    )
+    .build();
+
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder
+        .run_genesis(&DEFAULT_GENESIS_CONFIG)
+        .exec(exec_request)
+        .expect_success()
+        .commit();
+    builder
+}
+
+#[ignore]
+#[test]
+fn should_diff_state_against_values_in_each_state() {
+    let builder = setup();
+    let genesis_hash = builder.get_genesis_hash();
+    let post_state_hash = builder.get_post_state_hash();
+
+    let differences = builder.diff_state(genesis_hash.clone(), post_state_hash.clone());
+
+    assert!(differences.contains(&Difference::Added {
+        key: Key::Account(ACCOUNT_1_ADDR),
+        value: builder
+            .query(None, Key::Account(ACCOUNT_1_ADDR), &[])
+            .expect("should have new account"),
+    }));
+
+    let keys: Vec<Key> = differences
+        .iter()
+        .map(|difference| *difference.key())
+        .collect();
+    let mut sorted_keys = keys.clone();
+    sorted_keys.sort_by_key(|key| key.to_bytes().expect("should serialize key"));
+    assert_eq!(keys, sorted_keys);
+
+    for difference in differences {
+        let (key, maybe_old_value, maybe_new_value) = match difference {
+            Difference::Added { key, value } => (key, None, Some(value)),
+            Difference::Removed { key, value } => (key, Some(value), None),
+            Difference::Modified {
+                key,
+                old_value,
+                new_value,
+            } => (key, Some(old_value), Some(new_value)),
+        };
+        assert_eq!(
+            builder.query(Some(genesis_hash.clone()), key, &[]),
+            maybe_old_value
+        );
+        assert_eq!(
+            builder.query(Some(post_state_hash.clone()), key, &[]),
+            maybe_new_value
+        );
+    }
+}
+
+#[ignore]
+#[test]
+fn should_diff_state_in_reverse() {
+    let builder = setup();
+    let genesis_hash = builder.get_genesis_hash();
+    let post_state_hash = builder.get_post_state_hash();
+
+    let forward = builder.diff_state(genesis_hash.clone(), post_state_hash.clone());
+    let reverse = builder.diff_state(post_state_hash, genesis_hash);
+
+    let inverted: Vec<Difference<Key, _>> = forward
+        .into_iter()
+        .map(|difference| match difference {
+            Difference::Added { key, value } => Difference::Removed { key, value },
+            Difference::Removed { key, value } => Difference::Added { key, value },
+            Difference::Modified {
+                key,
+                old_value,
+                new_value,
+            } => Difference::Modified {
+                key,
+                old_value: new_value,
+                new_value: old_value,
+            },
+        })
+        .collect();
+    assert_eq!(reverse, inverted);
+}
+
+#[ignore]
+#[test]
+fn should_have_no_differences_from_a_state_to_itself() {
+    let builder = setup();
+    let post_state_hash = builder.get_post_state_hash();
+
+    let differences = builder.diff_state(post_state_hash.clone(), post_state_hash);
+    assert!(differences.is_empty());
+}
+
+#[ignore]
+#[test]
+fn should_diff_state_in_pages() {
+    let builder = setup();
+    let genesis_hash = builder.get_genesis_hash();
+    let post_state_hash = builder.get_post_state_hash();
+
+    let all_differences = builder.diff_state(genesis_hash.clone(), post_state_hash.clone());
+    assert!(all_differences.len() > 1);
+
+    let mut paged_differences = Vec::new();
+    let mut start = Vec::new();
+    loop {
+        let (mut differences, next_start) =
+            builder.diff_state_page(genesis_hash.clone(), post_state_hash.clone(), &start, 1);
+        assert_eq!(differences.len(), 1);
+        paged_differences.append(&mut differences);
+        match next_start {
+            Some(next_start) => start = next_start,
+            None => break,
+        }
+    }
+    assert_eq!(paged_differences, all_differences);
+}
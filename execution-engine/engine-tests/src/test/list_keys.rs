@@ -0,0 +1,84 @@
+use contract_ffi::{bytesrepr::ToBytes, key::Key};
+
+use crate::{
+    support::test_support::{ExecuteRequestBuilder, InMemoryWasmTestBuilder},
+    test::{DEFAULT_ACCOUNT_ADDR, DEFAULT_GENESIS_CONFIG},
+};
+
+const CONTRACT_TRANSFER_TO_ACCOUNT_01: &str = "transfer_to_account_01.wasm";
+const ACCOUNT_1_ADDR: [u8; 32] = [1u8; 32];
+const ACCOUNT_PREFIX: [u8; 1] = [0u8];
+const UREF_PREFIX: [u8; 1] = [2u8];
+
+fn setup() -> InMemoryWasmTestBuilder {
+    let exec_request = ExecuteRequestBuilder::standard(
+        DEFAULT_ACCOUNT_ADDR,
+        CONTRACT_TRANSFER_TO_ACCOUNT_01,
+        (ACCOUNT_1_ADDR,),
+    )
+    .build();
+
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder
+        .run_genesis(&DEFAULT_GENESIS_CONFIG)
+        .exec(exec_request)
+        .expect_success()
+        .commit();
+    builder
+}
+
+#[ignore]
+#[test]
+fn should_list_accounts_in_order() {
+    let builder = setup();
+
+    let (entries, next_start) = builder.list_keys(&ACCOUNT_PREFIX, &[], 100);
+    assert!(next_start.is_none());
+
+    let keys: Vec<Key> = entries.iter().map(|(key, _value)| *key).collect();
+    assert!(keys.contains(&Key::Account(DEFAULT_ACCOUNT_ADDR)));
+    assert!(keys.contains(&Key::Account(ACCOUNT_1_ADDR)));
+    assert!(keys.iter().all(|key| key.as_account().is_some()));
+
+    let mut sorted_keys = keys.clone();
+    sorted_keys.sort_by_key(|key| key.to_bytes().expect("should serialize key"));
+    assert_eq!(keys, sorted_keys);
+
+    for (key, value) in entries {
+        assert_eq!(builder.query(None, key, &[]), Some(value));
+    }
+}
+
+#[ignore]
+#[test]
+fn should_list_keys_page_by_page() {
+    let builder = setup();
+
+    let (all_entries, _) = builder.list_keys(&UREF_PREFIX, &[], 100);
+    assert!(all_entries.len() > 1, "should have several urefs");
+
+    let mut paged_entries = Vec::new();
+    let mut start = Vec::new();
+    loop {
+        let (entries, next_start) = builder.list_keys(&UREF_PREFIX, &start, 1);
+        assert_eq!(entries.len(), 1);
+        paged_entries.extend(entries);
+        match next_start {
+            Some(next_start) => start = next_start,
+            None => break,
+        }
+    }
+
+    assert_eq!(paged_entries, all_entries);
+}
+
+#[ignore]
+#[test]
+fn should_list_nothing_for_unused_prefix() {
+    let builder = setup();
+
+    let unused_prefix = [255u8];
+    let (entries, next_start) = builder.list_keys(&unused_prefix, &[], 100);
+    assert!(entries.is_empty());
+    assert!(next_start.is_none());
+}
@@ -1,6 +1,16 @@
 #[cfg(test)]
+mod diff_state;
+#[cfg(test)]
+mod list_keys;
+#[cfg(test)]
 mod metrics;
 #[cfg(test)]
+mod query_proofs;
+#[cfg(test)]
+mod state_roots;
+#[cfg(test)]
+mod trie_sync;
+#[cfg(test)]
 mod upgrade;
 
 #[cfg(test)]
@@ -17,10 +27,13 @@ pub mod system_contracts;
 use lazy_static::lazy_static;
 use num_traits::identities::Zero;
 
-use contract_ffi::value::{account::PublicKey, ProtocolVersion, U512};
+use contract_ffi::{
+    system_contracts::pos,
+    value::{account::PublicKey, ProtocolVersion, U512},
+};
 use engine_core::engine_state::genesis::{GenesisAccount, GenesisConfig};
 use engine_shared::{motes::Motes, test_utils};
-use engine_wasm_prep::wasm_costs::WasmCosts;
+use engine_wasm_prep::{host_function_costs::HostFunctionCosts, wasm_costs::WasmCosts};
 
 use crate::support::test_support;
 
@@ -48,6 +61,9 @@ lazy_static! {
     pub static ref DEFAULT_PROTOCOL_VERSION: ProtocolVersion = ProtocolVersion::V1_0_0;
     pub static ref DEFAULT_PAYMENT: U512 = 100_000_000.into();
     pub static ref DEFAULT_WASM_COSTS: WasmCosts = test_utils::wasm_costs_mock();
+    pub static ref DEFAULT_HOST_FUNCTION_COSTS: HostFunctionCosts =
+        test_utils::host_function_costs_mock();
+    pub static ref DEFAULT_POS_CONFIG: pos::Config = pos::Config::default();
     pub static ref DEFAULT_GENESIS_CONFIG: GenesisConfig = {
         let mint_installer_bytes = test_support::read_wasm_file_bytes(CONTRACT_MINT_INSTALL);
         let pos_installer_bytes = test_support::read_wasm_file_bytes(CONTRACT_POS_INSTALL);
@@ -59,6 +75,8 @@ lazy_static! {
             pos_installer_bytes,
             DEFAULT_ACCOUNTS.clone(),
             *DEFAULT_WASM_COSTS,
+            *DEFAULT_HOST_FUNCTION_COSTS,
+            *DEFAULT_POS_CONFIG,
         )
     };
 }
@@ -0,0 +1,52 @@
+use std::convert::TryFrom;
+
+use contract_ffi::key::Key;
+use engine_shared::{newtypes::Blake2bHash, stored_value::StoredValue};
+
+use crate::{
+    support::test_support::InMemoryWasmTestBuilder,
+    test::{DEFAULT_ACCOUNT_ADDR, DEFAULT_GENESIS_CONFIG},
+};
+
+const UNKNOWN_ADDR: [u8; 32] = [42u8; 32];
+
+#[ignore]
+#[test]
+fn should_return_verifiable_proof_of_inclusion() {
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder.run_genesis(&DEFAULT_GENESIS_CONFIG);
+
+    let root_hash = Blake2bHash::try_from(builder.get_post_state_hash().as_slice())
+        .expect("should have valid post-state hash");
+    let account_key = Key::Account(DEFAULT_ACCOUNT_ADDR);
+
+    let (maybe_value, proofs) = builder.query_with_proofs(None, account_key, &[]);
+    let value = maybe_value.expect("should find account");
+
+    assert_eq!(proofs.len(), 1);
+    let proof = &proofs[0];
+    assert_eq!(*proof.key(), account_key);
+    let proven_value: &StoredValue = proof
+        .verify(&root_hash)
+        .expect("proof should verify")
+        .expect("proof should be a proof of inclusion");
+    assert_eq!(*proven_value, value);
+}
+
+#[ignore]
+#[test]
+fn should_return_verifiable_proof_of_exclusion() {
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder.run_genesis(&DEFAULT_GENESIS_CONFIG);
+
+    let root_hash = Blake2bHash::try_from(builder.get_post_state_hash().as_slice())
+        .expect("should have valid post-state hash");
+    let unknown_key = Key::Account(UNKNOWN_ADDR);
+
+    let (maybe_value, proofs) = builder.query_with_proofs(None, unknown_key, &[]);
+    assert!(maybe_value.is_none());
+
+    assert_eq!(proofs.len(), 1);
+    let maybe_proven_value = proofs[0].verify(&root_hash).expect("proof should verify");
+    assert!(maybe_proven_value.is_none());
+}
@@ -0,0 +1,103 @@
+use contract_ffi::value::ProtocolVersion;
+
+use crate::{
+    support::test_support::{
+        ExecuteRequestBuilder, InMemoryWasmTestBuilder, UpgradeRequestBuilder,
+    },
+    test::{
+        DEFAULT_ACCOUNT_ADDR, DEFAULT_GENESIS_CONFIG, DEFAULT_POS_CONFIG, DEFAULT_PROTOCOL_VERSION,
+    },
+};
+
+const CONTRACT_TRANSFER_TO_ACCOUNT_01: &str = "transfer_to_account_01.wasm";
+const ACCOUNT_1_ADDR: [u8; 32] = [1u8; 32];
+const UPGRADE_BLOCK_TIME: u64 = 42;
+
+#[ignore]
+#[test]
+fn should_list_state_roots_with_their_parents() {
+    let exec_request = ExecuteRequestBuilder::standard(
+        DEFAULT_ACCOUNT_ADDR,
+        CONTRACT_TRANSFER_TO_ACCOUNT_01,
+        (ACCOUNT_1_ADDR,),
+    )
+    .build();
+
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder.run_genesis(&DEFAULT_GENESIS_CONFIG);
+    let genesis_hash = builder.get_genesis_hash();
+
+    builder.exec(exec_request).expect_success().commit();
+    let post_state_hash = builder.get_post_state_hash();
+
+    let state_roots = builder.list_state_roots();
+    assert_eq!(state_roots.len(), 2);
+
+    let genesis_root = state_roots
+        .iter()
+        .find(|state_root| state_root.get_state_hash() == genesis_hash.as_slice())
+        .expect("should list genesis state root");
+    assert_eq!(
+        ProtocolVersion::from(genesis_root.get_protocol_version().clone()),
+        *DEFAULT_PROTOCOL_VERSION
+    );
+    assert!(genesis_root.get_effects_count() > 0);
+
+    let post_state_root = state_roots
+        .iter()
+        .find(|state_root| state_root.get_state_hash() == post_state_hash.as_slice())
+        .expect("should list post state root");
+    assert_eq!(
+        post_state_root.get_parent_state_hash(),
+        genesis_hash.as_slice()
+    );
+    assert!(post_state_root.get_effects_count() > 0);
+}
+
+#[ignore]
+#[test]
+fn should_list_upgrade_state_root_with_its_activation_block_time() {
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder.run_genesis(&DEFAULT_GENESIS_CONFIG);
+    let genesis_hash = builder.get_genesis_hash();
+
+    let new_protocol_version = ProtocolVersion::from_parts(2, 0, 0);
+    let mut new_pos_config = *DEFAULT_POS_CONFIG;
+    new_pos_config.unbond_delay += 1;
+
+    let mut upgrade_request = UpgradeRequestBuilder::new()
+        .with_current_protocol_version(*DEFAULT_PROTOCOL_VERSION)
+        .with_new_protocol_version(new_protocol_version)
+        .with_new_pos_config(new_pos_config)
+        .with_block_time(UPGRADE_BLOCK_TIME)
+        .build();
+    builder.upgrade_with_upgrade_request(&mut upgrade_request);
+
+    let mut upgrade_response = builder
+        .get_upgrade_response(0)
+        .expect("should have response")
+        .clone();
+    assert!(upgrade_response.has_success(), "expected success");
+    let post_state_hash = upgrade_response.take_success().take_post_state_hash();
+
+    let state_roots = builder.list_state_roots();
+    let upgrade_root = state_roots
+        .iter()
+        .find(|state_root| state_root.get_state_hash() == post_state_hash.as_slice())
+        .expect("should list upgrade state root");
+    assert_eq!(
+        upgrade_root.get_parent_state_hash(),
+        genesis_hash.as_slice()
+    );
+    assert_eq!(
+        ProtocolVersion::from(upgrade_root.get_protocol_version().clone()),
+        new_protocol_version
+    );
+    assert_eq!(upgrade_root.get_block_time(), UPGRADE_BLOCK_TIME);
+}
+
+#[test]
+fn should_not_list_state_roots_before_genesis() {
+    let builder = InMemoryWasmTestBuilder::default();
+    assert!(builder.list_state_roots().is_empty());
+}
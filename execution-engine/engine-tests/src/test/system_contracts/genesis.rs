@@ -1,5 +1,6 @@
 use contract_ffi::{
     key::Key,
+    system_contracts::pos,
     value::{account::PublicKey, ProtocolVersion, U512},
 };
 use engine_core::engine_state::{
@@ -7,10 +8,11 @@ use engine_core::engine_state::{
     SYSTEM_ACCOUNT_ADDR,
 };
 use engine_shared::{motes::Motes, stored_value::StoredValue};
+use engine_wasm_prep::wasm_costs::WasmCosts;
 
 use crate::{
     support::test_support::{self, InMemoryWasmTestBuilder},
-    test::DEFAULT_WASM_COSTS,
+    test::{DEFAULT_HOST_FUNCTION_COSTS, DEFAULT_POS_CONFIG, DEFAULT_WASM_COSTS},
 };
 
 const MINT_INSTALL: &str = "mint_install.wasm";
@@ -25,6 +27,8 @@ const ACCOUNT_1_BONDED_AMOUNT: u64 = 1_000_000;
 const ACCOUNT_2_BONDED_AMOUNT: u64 = 2_000_000;
 const ACCOUNT_1_BALANCE: u64 = 1_000_000_000;
 const ACCOUNT_2_BALANCE: u64 = 2_000_000_000;
+const POS_BOND_DELAY: u64 = 10;
+const POS_UNBOND_DELAY: u64 = 20;
 
 #[ignore]
 #[test]
@@ -57,6 +61,12 @@ fn should_run_genesis() {
     let accounts = vec![account_1, account_2];
     let protocol_version = ProtocolVersion::V1_0_0;
     let wasm_costs = *DEFAULT_WASM_COSTS;
+    let host_function_costs = *DEFAULT_HOST_FUNCTION_COSTS;
+    let pos_config = pos::Config {
+        bond_delay: POS_BOND_DELAY,
+        unbond_delay: POS_UNBOND_DELAY,
+        ..*DEFAULT_POS_CONFIG
+    };
 
     let genesis_config = GenesisConfig::new(
         name,
@@ -66,6 +76,8 @@ fn should_run_genesis() {
         pos_installer_bytes,
         accounts,
         wasm_costs,
+        host_function_costs,
+        pos_config,
     );
 
     let mut builder = InMemoryWasmTestBuilder::default();
@@ -107,6 +119,8 @@ fn should_run_genesis() {
     } else {
         panic!("contract not found at pos uref");
     }
+
+    assert_eq!(builder.get_pos_config(), pos_config);
 }
 
 #[ignore]
@@ -140,6 +154,8 @@ fn should_fail_if_bad_mint_install_contract_is_provided() {
         let accounts = vec![account_1, account_2];
         let protocol_version = ProtocolVersion::V1_0_0;
         let wasm_costs = *DEFAULT_WASM_COSTS;
+        let host_function_costs = *DEFAULT_HOST_FUNCTION_COSTS;
+        let pos_config = *DEFAULT_POS_CONFIG;
 
         GenesisConfig::new(
             name,
@@ -149,6 +165,8 @@ fn should_fail_if_bad_mint_install_contract_is_provided() {
             pos_installer_bytes,
             accounts,
             wasm_costs,
+            host_function_costs,
+            pos_config,
         )
     };
 
@@ -188,6 +206,98 @@ fn should_fail_if_bad_pos_install_contract_is_provided() {
         let accounts = vec![account_1, account_2];
         let protocol_version = ProtocolVersion::V1_0_0;
         let wasm_costs = *DEFAULT_WASM_COSTS;
+        let host_function_costs = *DEFAULT_HOST_FUNCTION_COSTS;
+        let pos_config = *DEFAULT_POS_CONFIG;
+
+        GenesisConfig::new(
+            name,
+            TIMESTAMP,
+            protocol_version,
+            mint_installer_bytes,
+            pos_installer_bytes,
+            accounts,
+            wasm_costs,
+            host_function_costs,
+            pos_config,
+        )
+    };
+
+    let mut builder = InMemoryWasmTestBuilder::default();
+
+    builder.run_genesis(&genesis_config);
+}
+
+#[ignore]
+#[should_panic]
+#[test]
+fn should_fail_if_crypto_costs_are_zero() {
+    let genesis_config = {
+        let account_1 = {
+            let account_1_public_key = PublicKey::new(ACCOUNT_1_ADDR);
+            let account_1_balance = Motes::new(ACCOUNT_1_BALANCE.into());
+            let account_1_bonded_amount = Motes::new(ACCOUNT_1_BONDED_AMOUNT.into());
+            GenesisAccount::new(
+                account_1_public_key,
+                account_1_balance,
+                account_1_bonded_amount,
+            )
+        };
+        let name = CHAIN_NAME.to_string();
+        let mint_installer_bytes = test_support::read_wasm_file_bytes(MINT_INSTALL);
+        let pos_installer_bytes = test_support::read_wasm_file_bytes(POS_INSTALL);
+        let accounts = vec![account_1];
+        let protocol_version = ProtocolVersion::V1_0_0;
+        let wasm_costs = WasmCosts {
+            verify_secp256k1: 0,
+            ..*DEFAULT_WASM_COSTS
+        };
+        let host_function_costs = *DEFAULT_HOST_FUNCTION_COSTS;
+        let pos_config = *DEFAULT_POS_CONFIG;
+
+        GenesisConfig::new(
+            name,
+            TIMESTAMP,
+            protocol_version,
+            mint_installer_bytes,
+            pos_installer_bytes,
+            accounts,
+            wasm_costs,
+            host_function_costs,
+            pos_config,
+        )
+    };
+
+    let mut builder = InMemoryWasmTestBuilder::default();
+
+    builder.run_genesis(&genesis_config);
+}
+
+#[ignore]
+#[should_panic]
+#[test]
+fn should_fail_if_pos_config_limits_are_zero() {
+    let genesis_config = {
+        let account_1 = {
+            let account_1_public_key = PublicKey::new(ACCOUNT_1_ADDR);
+            let account_1_balance = Motes::new(ACCOUNT_1_BALANCE.into());
+            let account_1_bonded_amount = Motes::new(ACCOUNT_1_BONDED_AMOUNT.into());
+            GenesisAccount::new(
+                account_1_public_key,
+                account_1_balance,
+                account_1_bonded_amount,
+            )
+        };
+        let name = CHAIN_NAME.to_string();
+        let mint_installer_bytes = test_support::read_wasm_file_bytes(MINT_INSTALL);
+        let pos_installer_bytes = test_support::read_wasm_file_bytes(POS_INSTALL);
+        let accounts = vec![account_1];
+        let protocol_version = ProtocolVersion::V1_0_0;
+        let wasm_costs = *DEFAULT_WASM_COSTS;
+        let host_function_costs = *DEFAULT_HOST_FUNCTION_COSTS;
+        let pos_config = pos::Config {
+            max_rel_decrease: 0,
+            ..*DEFAULT_POS_CONFIG
+        };
 
         GenesisConfig::new(
             name,
@@ -197,6 +307,8 @@ fn should_fail_if_bad_pos_install_contract_is_provided() {
             pos_installer_bytes,
             accounts,
             wasm_costs,
+            host_function_costs,
+            pos_config,
         )
     };
 
@@ -6,6 +6,7 @@ use crate::support::{
 };
 use contract_ffi::{
     key::Key,
+    system_contracts::pos,
     uref::{AccessRights, URef},
     value::{
         account::{PublicKey, PurseId},
@@ -21,12 +22,13 @@ const SYSTEM_ADDR: [u8; 32] = [0u8; 32];
 const DEPLOY_HASH_2: [u8; 32] = [2u8; 32];
 const N_VALIDATORS: u8 = 5;
 
-// one named_key for each validator and three for the purses
-const EXPECTED_KNOWN_KEYS_LEN: usize = (N_VALIDATORS as usize) + 3;
+// one named_key for each validator, three for the purses and one for the config
+const EXPECTED_KNOWN_KEYS_LEN: usize = (N_VALIDATORS as usize) + 4;
 
 const POS_BONDING_PURSE: &str = "pos_bonding_purse";
 const POS_PAYMENT_PURSE: &str = "pos_payment_purse";
 const POS_REWARDS_PURSE: &str = "pos_rewards_purse";
+const POS_CONFIG: &str = "pos_config";
 
 #[ignore]
 #[test]
@@ -51,6 +53,7 @@ fn should_run_pos_install_contract() {
         .collect();
 
     let total_bond = genesis_validators.values().fold(U512::zero(), |x, y| x + y);
+    let pos_config = pos::Config::default();
 
     let (ret_value, ret_urefs, effect): (URef, _, _) = exec_with_return::exec(
         &mut builder,
@@ -58,7 +61,7 @@ fn should_run_pos_install_contract() {
         "pos_install.wasm",
         DEFAULT_BLOCK_TIME,
         DEPLOY_HASH_2,
-        (mint_uref, genesis_validators),
+        (mint_uref, genesis_validators, pos_config),
         vec![mint_uref],
     )
     .expect("should run successfully");
@@ -101,6 +104,19 @@ fn should_run_pos_install_contract() {
 
     let rewards_purse_balance = builder.get_purse_balance(rewards_purse);
     assert_eq!(rewards_purse_balance, U512::zero());
+
+    // config is stored under its named key
+    let config_key = named_keys
+        .get(POS_CONFIG)
+        .expect("should find config in named_keys")
+        .normalize();
+    match effect.transforms.get(&config_key) {
+        Some(Transform::Write(StoredValue::CLValue(cl_value))) => {
+            let config: pos::Config = cl_value.clone().into_t().expect("should parse config");
+            assert_eq!(config, pos_config);
+        }
+        _ => panic!("Expected config to be written under the key"),
+    }
 }
 
 fn get_purse(named_keys: &BTreeMap<String, Key>, name: &str) -> Option<PurseId> {
@@ -0,0 +1,272 @@
+use std::collections::HashMap;
+
+use contract_ffi::{
+    key::Key,
+    system_contracts::pos,
+    value::{
+        account::{PublicKey, PurseId},
+        U512,
+    },
+};
+use engine_core::engine_state::genesis::{
+    GenesisAccount, GenesisConfig, POS_BONDING_PURSE, POS_REWARDS_PURSE,
+};
+use engine_shared::motes::Motes;
+
+use crate::{
+    support::test_support::{self, ExecuteRequestBuilder, InMemoryWasmTestBuilder},
+    test::{
+        CONTRACT_MINT_INSTALL, CONTRACT_POS_INSTALL, DEFAULT_ACCOUNTS, DEFAULT_ACCOUNT_ADDR,
+        DEFAULT_CHAIN_NAME, DEFAULT_GENESIS_TIMESTAMP, DEFAULT_HOST_FUNCTION_COSTS,
+        DEFAULT_POS_CONFIG, DEFAULT_PROTOCOL_VERSION, DEFAULT_WASM_COSTS,
+    },
+};
+
+const CONTRACT_POS_BONDING: &str = "pos_bonding.wasm";
+const TEST_DELEGATE: &str = "delegate";
+const TEST_UNDELEGATE: &str = "undelegate";
+const STEP_BLOCK_TIME: u64 = 1_000;
+const UNBOND_DELAY: u64 = 100;
+
+const VALIDATOR_1_ADDR: [u8; 32] = [1u8; 32];
+const VALIDATOR_1_BALANCE: u64 = 2_000;
+const VALIDATOR_1_STAKE: u64 = 1_000;
+const DELEGATION: u64 = 3_000;
+
+const UNBONDED_ACCOUNT_ADDR: [u8; 32] = [2u8; 32];
+
+fn get_pos_purse_balance(builder: &InMemoryWasmTestBuilder, name: &str) -> U512 {
+    let purse = builder
+        .get_pos_contract()
+        .named_keys()
+        .get(name)
+        .and_then(Key::as_uref)
+        .map(|uref| PurseId::new(*uref))
+        .expect("should find PoS purse");
+    builder.get_purse_balance(purse)
+}
+
+fn get_main_purse_balance(builder: &InMemoryWasmTestBuilder, addr: [u8; 32]) -> U512 {
+    let account = builder.get_account(addr).expect("should get account");
+    builder.get_purse_balance(account.purse_id())
+}
+
+fn setup() -> InMemoryWasmTestBuilder {
+    setup_with_pos_config(*DEFAULT_POS_CONFIG)
+}
+
+fn setup_with_pos_config(pos_config: pos::Config) -> InMemoryWasmTestBuilder {
+    let accounts = {
+        let mut tmp: Vec<GenesisAccount> = DEFAULT_ACCOUNTS.clone();
+        tmp.push(GenesisAccount::new(
+            PublicKey::new(VALIDATOR_1_ADDR),
+            Motes::new(VALIDATOR_1_BALANCE.into()),
+            Motes::new(VALIDATOR_1_STAKE.into()),
+        ));
+        tmp
+    };
+    let genesis_config = GenesisConfig::new(
+        DEFAULT_CHAIN_NAME.to_string(),
+        DEFAULT_GENESIS_TIMESTAMP,
+        *DEFAULT_PROTOCOL_VERSION,
+        test_support::read_wasm_file_bytes(CONTRACT_MINT_INSTALL),
+        test_support::read_wasm_file_bytes(CONTRACT_POS_INSTALL),
+        accounts,
+        *DEFAULT_WASM_COSTS,
+        *DEFAULT_HOST_FUNCTION_COSTS,
+        pos_config,
+    );
+
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder.run_genesis(&genesis_config);
+    builder
+}
+
+fn delegate(builder: &mut InMemoryWasmTestBuilder, validator: [u8; 32], amount: u64) {
+    let exec_request = ExecuteRequestBuilder::standard(
+        DEFAULT_ACCOUNT_ADDR,
+        CONTRACT_POS_BONDING,
+        (
+            String::from(TEST_DELEGATE),
+            PublicKey::new(validator),
+            U512::from(amount),
+        ),
+    )
+    .build();
+    builder.exec(exec_request).commit();
+}
+
+fn undelegate(builder: &mut InMemoryWasmTestBuilder, validator: [u8; 32]) {
+    let exec_request = ExecuteRequestBuilder::standard(
+        DEFAULT_ACCOUNT_ADDR,
+        CONTRACT_POS_BONDING,
+        (
+            String::from(TEST_UNDELEGATE),
+            PublicKey::new(validator),
+            None::<U512>,
+        ),
+    )
+    .build();
+    builder.exec(exec_request).expect_success().commit();
+}
+
+#[ignore]
+#[test]
+fn should_count_delegations_in_bonded_validators() {
+    let mut builder = setup();
+    let bonding_purse_balance = get_pos_purse_balance(&builder, POS_BONDING_PURSE);
+
+    delegate(&mut builder, VALIDATOR_1_ADDR, DELEGATION);
+    assert!(!builder.is_error());
+
+    let expected: HashMap<PublicKey, U512> = {
+        let mut tmp = HashMap::new();
+        tmp.insert(
+            PublicKey::new(VALIDATOR_1_ADDR),
+            U512::from(VALIDATOR_1_STAKE + DELEGATION),
+        );
+        tmp
+    };
+    assert_eq!(builder.get_bonded_validators()[0], expected);
+    assert_eq!(
+        get_pos_purse_balance(&builder, POS_BONDING_PURSE),
+        bonding_purse_balance + U512::from(DELEGATION)
+    );
+
+    undelegate(&mut builder, VALIDATOR_1_ADDR);
+
+    let expected: HashMap<PublicKey, U512> = {
+        let mut tmp = HashMap::new();
+        tmp.insert(
+            PublicKey::new(VALIDATOR_1_ADDR),
+            U512::from(VALIDATOR_1_STAKE),
+        );
+        tmp
+    };
+    assert_eq!(builder.get_bonded_validators()[1], expected);
+    assert_eq!(
+        get_pos_purse_balance(&builder, POS_BONDING_PURSE),
+        bonding_purse_balance
+    );
+}
+
+#[ignore]
+#[test]
+fn should_pay_out_undelegation_after_unbond_delay() {
+    let pos_config = pos::Config {
+        unbond_delay: UNBOND_DELAY,
+        ..*DEFAULT_POS_CONFIG
+    };
+    let mut builder = setup_with_pos_config(pos_config);
+    let bonding_purse_balance = get_pos_purse_balance(&builder, POS_BONDING_PURSE);
+
+    delegate(&mut builder, VALIDATOR_1_ADDR, DELEGATION);
+    assert!(!builder.is_error());
+    undelegate(&mut builder, VALIDATOR_1_ADDR);
+
+    // The delegation stops counting immediately, but is held until the delay has passed.
+    let expected: HashMap<PublicKey, U512> = {
+        let mut tmp = HashMap::new();
+        tmp.insert(
+            PublicKey::new(VALIDATOR_1_ADDR),
+            U512::from(VALIDATOR_1_STAKE),
+        );
+        tmp
+    };
+    assert_eq!(builder.get_bonded_validators()[1], expected);
+    assert_eq!(
+        get_pos_purse_balance(&builder, POS_BONDING_PURSE),
+        bonding_purse_balance + U512::from(DELEGATION)
+    );
+    let delegator_balance = get_main_purse_balance(&builder, DEFAULT_ACCOUNT_ADDR);
+
+    let step_response = builder.step(STEP_BLOCK_TIME, &[]);
+    assert!(step_response.has_success(), "{:?}", step_response);
+    assert_eq!(
+        get_pos_purse_balance(&builder, POS_BONDING_PURSE),
+        bonding_purse_balance
+    );
+    assert_eq!(
+        get_main_purse_balance(&builder, DEFAULT_ACCOUNT_ADDR),
+        delegator_balance + U512::from(DELEGATION)
+    );
+}
+
+#[ignore]
+#[test]
+fn should_slash_fraction_of_pending_undelegation() {
+    let pos_config = pos::Config {
+        unbond_delay: UNBOND_DELAY,
+        ..*DEFAULT_POS_CONFIG
+    };
+    let mut builder = setup_with_pos_config(pos_config);
+
+    delegate(&mut builder, VALIDATOR_1_ADDR, DELEGATION);
+    assert!(!builder.is_error());
+    undelegate(&mut builder, VALIDATOR_1_ADDR);
+    let rewards_purse_balance = get_pos_purse_balance(&builder, POS_REWARDS_PURSE);
+    let delegator_balance = get_main_purse_balance(&builder, DEFAULT_ACCOUNT_ADDR);
+
+    let slash_response = builder.slash(
+        STEP_BLOCK_TIME,
+        &[PublicKey::new(VALIDATOR_1_ADDR)],
+        500_000,
+    );
+    assert!(slash_response.has_success(), "{:?}", slash_response);
+    assert_eq!(
+        get_pos_purse_balance(&builder, POS_REWARDS_PURSE),
+        rewards_purse_balance + U512::from(VALIDATOR_1_STAKE / 2 + DELEGATION / 2)
+    );
+
+    let step_response = builder.step(STEP_BLOCK_TIME, &[]);
+    assert!(step_response.has_success(), "{:?}", step_response);
+    assert_eq!(
+        get_main_purse_balance(&builder, DEFAULT_ACCOUNT_ADDR),
+        delegator_balance + U512::from(DELEGATION / 2)
+    );
+}
+
+#[ignore]
+#[test]
+fn should_not_delegate_to_unbonded_account() {
+    let mut builder = setup();
+    let bonding_purse_balance = get_pos_purse_balance(&builder, POS_BONDING_PURSE);
+
+    delegate(&mut builder, UNBONDED_ACCOUNT_ADDR, DELEGATION);
+    assert!(builder.is_error());
+    assert_eq!(
+        get_pos_purse_balance(&builder, POS_BONDING_PURSE),
+        bonding_purse_balance
+    );
+}
+
+#[ignore]
+#[test]
+fn should_split_rewards_between_validator_and_delegator() {
+    let mut builder = setup();
+    delegate(&mut builder, VALIDATOR_1_ADDR, DELEGATION);
+    assert!(!builder.is_error());
+
+    let rewards = get_pos_purse_balance(&builder, POS_REWARDS_PURSE);
+    assert!(!rewards.is_zero());
+    let validator_balance = get_main_purse_balance(&builder, VALIDATOR_1_ADDR);
+    let delegator_balance = get_main_purse_balance(&builder, DEFAULT_ACCOUNT_ADDR);
+
+    let reward_weights = [(PublicKey::new(VALIDATOR_1_ADDR), 1)];
+    let step_response = builder.step(STEP_BLOCK_TIME, &reward_weights);
+    assert!(step_response.has_success(), "{:?}", step_response);
+
+    // The delegator owns 3/4 of the pooled stake and pays the commission on its share.
+    let commission = DEFAULT_POS_CONFIG.delegation_commission;
+    let delegator_reward = rewards * U512::from(DELEGATION) * U512::from(1_000_000 - commission)
+        / U512::from((VALIDATOR_1_STAKE + DELEGATION) * 1_000_000);
+    assert_eq!(
+        get_main_purse_balance(&builder, DEFAULT_ACCOUNT_ADDR),
+        delegator_balance + delegator_reward
+    );
+    assert_eq!(
+        get_main_purse_balance(&builder, VALIDATOR_1_ADDR),
+        validator_balance + rewards - delegator_reward
+    );
+    assert!(get_pos_purse_balance(&builder, POS_REWARDS_PURSE).is_zero());
+}
@@ -0,0 +1,179 @@
+use num_traits::Zero;
+
+use contract_ffi::{
+    key::Key,
+    value::{
+        account::{PublicKey, PurseId},
+        U512,
+    },
+};
+use engine_core::engine_state::genesis::{GenesisAccount, POS_REWARDS_PURSE};
+use engine_shared::motes::Motes;
+
+use crate::{
+    support::test_support::{self, ExecuteRequestBuilder, InMemoryWasmTestBuilder},
+    test::{DEFAULT_ACCOUNTS, DEFAULT_ACCOUNT_ADDR},
+};
+
+const CONTRACT_DO_NOTHING: &str = "do_nothing.wasm";
+const STEP_BLOCK_TIME: u64 = 1_000;
+
+const VALIDATOR_1_ADDR: [u8; 32] = [1u8; 32];
+const VALIDATOR_1_BALANCE: u64 = 2_000;
+const VALIDATOR_1_STAKE: u64 = 1_000;
+
+const VALIDATOR_2_ADDR: [u8; 32] = [2u8; 32];
+const VALIDATOR_2_BALANCE: u64 = 2_000;
+const VALIDATOR_2_STAKE: u64 = 2_000;
+
+const UNBONDED_ACCOUNT_ADDR: [u8; 32] = [3u8; 32];
+const UNBONDED_ACCOUNT_BALANCE: u64 = 2_000;
+
+fn get_rewards_purse_balance(builder: &InMemoryWasmTestBuilder) -> U512 {
+    let rewards_purse = builder
+        .get_pos_contract()
+        .named_keys()
+        .get(POS_REWARDS_PURSE)
+        .and_then(Key::as_uref)
+        .map(|uref| PurseId::new(*uref))
+        .expect("should find PoS rewards purse");
+    builder.get_purse_balance(rewards_purse)
+}
+
+fn get_main_purse_balance(builder: &InMemoryWasmTestBuilder, addr: [u8; 32]) -> U512 {
+    let account = builder.get_account(addr).expect("should get account");
+    builder.get_purse_balance(account.purse_id())
+}
+
+/// Runs genesis with two validators and an unbonded account, then runs a deploy so that its fee
+/// is paid into the rewards purse.
+fn setup() -> InMemoryWasmTestBuilder {
+    let accounts = {
+        let mut tmp: Vec<GenesisAccount> = DEFAULT_ACCOUNTS.clone();
+        tmp.push(GenesisAccount::new(
+            PublicKey::new(VALIDATOR_1_ADDR),
+            Motes::new(VALIDATOR_1_BALANCE.into()),
+            Motes::new(VALIDATOR_1_STAKE.into()),
+        ));
+        tmp.push(GenesisAccount::new(
+            PublicKey::new(VALIDATOR_2_ADDR),
+            Motes::new(VALIDATOR_2_BALANCE.into()),
+            Motes::new(VALIDATOR_2_STAKE.into()),
+        ));
+        tmp.push(GenesisAccount::new(
+            PublicKey::new(UNBONDED_ACCOUNT_ADDR),
+            Motes::new(UNBONDED_ACCOUNT_BALANCE.into()),
+            Motes::zero(),
+        ));
+        tmp
+    };
+    let genesis_config = test_support::create_genesis_config(accounts);
+
+    let exec_request =
+        ExecuteRequestBuilder::standard(DEFAULT_ACCOUNT_ADDR, CONTRACT_DO_NOTHING, ()).build();
+
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder
+        .run_genesis(&genesis_config)
+        .exec(exec_request)
+        .expect_success()
+        .commit();
+    builder
+}
+
+#[ignore]
+#[test]
+fn should_distribute_rewards_proportionally_to_weighted_stakes() {
+    let mut builder = setup();
+    let rewards = get_rewards_purse_balance(&builder);
+    assert!(!rewards.is_zero());
+    let validator_1_balance = get_main_purse_balance(&builder, VALIDATOR_1_ADDR);
+    let validator_2_balance = get_main_purse_balance(&builder, VALIDATOR_2_ADDR);
+
+    let reward_weights = [
+        (PublicKey::new(VALIDATOR_1_ADDR), 3),
+        (PublicKey::new(VALIDATOR_2_ADDR), 1),
+    ];
+    let step_response = builder.step(STEP_BLOCK_TIME, &reward_weights);
+    assert!(step_response.has_success(), "{:?}", step_response);
+
+    // The weighted stakes are 3 * 1_000 and 1 * 2_000
+    let validator_1_reward = rewards * 3 / 5;
+    let validator_2_reward = rewards * 2 / 5;
+    assert_eq!(
+        get_main_purse_balance(&builder, VALIDATOR_1_ADDR),
+        validator_1_balance + validator_1_reward
+    );
+    assert_eq!(
+        get_main_purse_balance(&builder, VALIDATOR_2_ADDR),
+        validator_2_balance + validator_2_reward
+    );
+
+    // Rounding down leaves less than a mote per validator in the rewards purse
+    let remainder = rewards - validator_1_reward - validator_2_reward;
+    assert_eq!(get_rewards_purse_balance(&builder), remainder);
+    assert!(remainder < U512::from(2));
+}
+
+#[ignore]
+#[test]
+fn should_not_distribute_rewards_to_unbonded_accounts() {
+    let mut builder = setup();
+    let rewards = get_rewards_purse_balance(&builder);
+    let validator_1_balance = get_main_purse_balance(&builder, VALIDATOR_1_ADDR);
+    let validator_2_balance = get_main_purse_balance(&builder, VALIDATOR_2_ADDR);
+    let unbonded_account_balance = get_main_purse_balance(&builder, UNBONDED_ACCOUNT_ADDR);
+
+    let reward_weights = [
+        (PublicKey::new(VALIDATOR_1_ADDR), 1),
+        (PublicKey::new(UNBONDED_ACCOUNT_ADDR), 1),
+    ];
+    let step_response = builder.step(STEP_BLOCK_TIME, &reward_weights);
+    assert!(step_response.has_success(), "{:?}", step_response);
+
+    // Validator 2 has no weight and the unbonded account has no stake, so validator 1 gets all
+    assert_eq!(
+        get_main_purse_balance(&builder, VALIDATOR_1_ADDR),
+        validator_1_balance + rewards
+    );
+    assert_eq!(
+        get_main_purse_balance(&builder, VALIDATOR_2_ADDR),
+        validator_2_balance
+    );
+    assert_eq!(
+        get_main_purse_balance(&builder, UNBONDED_ACCOUNT_ADDR),
+        unbonded_account_balance
+    );
+    assert!(get_rewards_purse_balance(&builder).is_zero());
+}
+
+#[ignore]
+#[test]
+fn should_keep_rewards_without_weighted_stakes() {
+    let mut builder = setup();
+    let rewards = get_rewards_purse_balance(&builder);
+    let validator_1_balance = get_main_purse_balance(&builder, VALIDATOR_1_ADDR);
+    let validator_2_balance = get_main_purse_balance(&builder, VALIDATOR_2_ADDR);
+
+    let reward_weights = [
+        (PublicKey::new(VALIDATOR_1_ADDR), 0),
+        (PublicKey::new(UNBONDED_ACCOUNT_ADDR), 1),
+    ];
+    let step_response = builder.step(STEP_BLOCK_TIME, &reward_weights);
+    assert!(step_response.has_success(), "{:?}", step_response);
+
+    assert_eq!(get_rewards_purse_balance(&builder), rewards);
+    assert_eq!(
+        get_main_purse_balance(&builder, VALIDATOR_1_ADDR),
+        validator_1_balance
+    );
+
+    // The rewards are kept for the next distribution
+    let reward_weights = [(PublicKey::new(VALIDATOR_2_ADDR), 1)];
+    let step_response = builder.step(STEP_BLOCK_TIME + 1, &reward_weights);
+    assert!(step_response.has_success(), "{:?}", step_response);
+    assert_eq!(
+        get_main_purse_balance(&builder, VALIDATOR_2_ADDR),
+        validator_2_balance + rewards
+    );
+}
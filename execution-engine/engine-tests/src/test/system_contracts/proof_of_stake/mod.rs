@@ -3,8 +3,16 @@ mod bonding;
 #[cfg(test)]
 mod commit_validators;
 #[cfg(test)]
+mod delegation;
+#[cfg(test)]
+mod distribute_rewards;
+#[cfg(test)]
 mod finalize_payment;
 #[cfg(test)]
 mod get_payment_purse;
 #[cfg(test)]
 mod refund_purse;
+#[cfg(test)]
+mod slash;
+#[cfg(test)]
+mod step;
@@ -0,0 +1,163 @@
+use std::collections::HashMap;
+
+use contract_ffi::{
+    key::Key,
+    value::{
+        account::{PublicKey, PurseId},
+        U512,
+    },
+};
+use engine_core::engine_state::genesis::{GenesisAccount, POS_BONDING_PURSE, POS_REWARDS_PURSE};
+use engine_shared::motes::Motes;
+
+use crate::{
+    support::test_support::{self, InMemoryWasmTestBuilder},
+    test::DEFAULT_ACCOUNTS,
+};
+
+const SLASH_BLOCK_TIME: u64 = 1_000;
+
+const VALIDATOR_1_ADDR: [u8; 32] = [1u8; 32];
+const VALIDATOR_1_BALANCE: u64 = 2_000;
+const VALIDATOR_1_STAKE: u64 = 1_000;
+
+const VALIDATOR_2_ADDR: [u8; 32] = [2u8; 32];
+const VALIDATOR_2_BALANCE: u64 = 2_000;
+const VALIDATOR_2_STAKE: u64 = 2_000;
+
+fn get_pos_purse_balance(builder: &InMemoryWasmTestBuilder, name: &str) -> U512 {
+    let purse = builder
+        .get_pos_contract()
+        .named_keys()
+        .get(name)
+        .and_then(Key::as_uref)
+        .map(|uref| PurseId::new(*uref))
+        .expect("should find PoS purse");
+    builder.get_purse_balance(purse)
+}
+
+fn setup() -> InMemoryWasmTestBuilder {
+    let accounts = {
+        let mut tmp: Vec<GenesisAccount> = DEFAULT_ACCOUNTS.clone();
+        tmp.push(GenesisAccount::new(
+            PublicKey::new(VALIDATOR_1_ADDR),
+            Motes::new(VALIDATOR_1_BALANCE.into()),
+            Motes::new(VALIDATOR_1_STAKE.into()),
+        ));
+        tmp.push(GenesisAccount::new(
+            PublicKey::new(VALIDATOR_2_ADDR),
+            Motes::new(VALIDATOR_2_BALANCE.into()),
+            Motes::new(VALIDATOR_2_STAKE.into()),
+        ));
+        tmp
+    };
+    let genesis_config = test_support::create_genesis_config(accounts);
+
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder.run_genesis(&genesis_config);
+    builder
+}
+
+#[ignore]
+#[test]
+fn should_slash_fraction_of_stake_into_rewards_purse() {
+    let mut builder = setup();
+    let bonding_purse_balance = get_pos_purse_balance(&builder, POS_BONDING_PURSE);
+    let rewards_purse_balance = get_pos_purse_balance(&builder, POS_REWARDS_PURSE);
+
+    // Half of validator 1's stake
+    let slash_response = builder.slash(
+        SLASH_BLOCK_TIME,
+        &[PublicKey::new(VALIDATOR_1_ADDR)],
+        500_000,
+    );
+    assert!(slash_response.has_success(), "{:?}", slash_response);
+
+    let slashed = U512::from(VALIDATOR_1_STAKE / 2);
+    let expected: HashMap<PublicKey, U512> = {
+        let mut tmp = HashMap::new();
+        tmp.insert(PublicKey::new(VALIDATOR_1_ADDR), slashed);
+        tmp.insert(
+            PublicKey::new(VALIDATOR_2_ADDR),
+            U512::from(VALIDATOR_2_STAKE),
+        );
+        tmp
+    };
+    assert_eq!(builder.get_bonded_validators()[0], expected);
+    assert_eq!(
+        get_pos_purse_balance(&builder, POS_BONDING_PURSE),
+        bonding_purse_balance - slashed
+    );
+    assert_eq!(
+        get_pos_purse_balance(&builder, POS_REWARDS_PURSE),
+        rewards_purse_balance + slashed
+    );
+}
+
+#[ignore]
+#[test]
+fn should_unbond_fully_slashed_validator() {
+    let mut builder = setup();
+
+    let slash_response = builder.slash(
+        SLASH_BLOCK_TIME,
+        &[PublicKey::new(VALIDATOR_2_ADDR)],
+        1_000_000,
+    );
+    assert!(slash_response.has_success(), "{:?}", slash_response);
+
+    let expected: HashMap<PublicKey, U512> = {
+        let mut tmp = HashMap::new();
+        tmp.insert(
+            PublicKey::new(VALIDATOR_1_ADDR),
+            U512::from(VALIDATOR_1_STAKE),
+        );
+        tmp
+    };
+    assert_eq!(builder.get_bonded_validators()[0], expected);
+}
+
+#[ignore]
+#[test]
+fn should_not_slash_more_than_whole_stake() {
+    let mut builder = setup();
+
+    let slash_response = builder.slash(
+        SLASH_BLOCK_TIME,
+        &[PublicKey::new(VALIDATOR_1_ADDR)],
+        1_000_001,
+    );
+    assert!(slash_response.has_failed_slash(), "{:?}", slash_response);
+    assert!(builder.get_bonded_validators().is_empty());
+}
+
+#[ignore]
+#[test]
+fn should_keep_largest_validator_bonded_when_slashing_all_validators() {
+    let mut builder = setup();
+    let rewards_purse_balance = get_pos_purse_balance(&builder, POS_REWARDS_PURSE);
+
+    let slash_response = builder.slash(
+        SLASH_BLOCK_TIME,
+        &[
+            PublicKey::new(VALIDATOR_1_ADDR),
+            PublicKey::new(VALIDATOR_2_ADDR),
+        ],
+        1_000_000,
+    );
+    assert!(slash_response.has_success(), "{:?}", slash_response);
+
+    let expected: HashMap<PublicKey, U512> = {
+        let mut tmp = HashMap::new();
+        tmp.insert(
+            PublicKey::new(VALIDATOR_2_ADDR),
+            U512::from(VALIDATOR_2_STAKE),
+        );
+        tmp
+    };
+    assert_eq!(builder.get_bonded_validators()[0], expected);
+    assert_eq!(
+        get_pos_purse_balance(&builder, POS_REWARDS_PURSE),
+        rewards_purse_balance + U512::from(VALIDATOR_1_STAKE)
+    );
+}
@@ -0,0 +1,45 @@
+use std::collections::HashMap;
+
+use contract_ffi::value::{account::PublicKey, U512};
+use engine_core::engine_state::genesis::GenesisAccount;
+use engine_shared::motes::Motes;
+
+use crate::{
+    support::test_support::{self, InMemoryWasmTestBuilder},
+    test::DEFAULT_ACCOUNTS,
+};
+
+const ACCOUNT_1_ADDR: [u8; 32] = [1u8; 32];
+const ACCOUNT_1_BALANCE: u64 = 2000;
+const ACCOUNT_1_BOND: u64 = 1000;
+const STEP_BLOCK_TIME: u64 = 1_000;
+
+#[ignore]
+#[test]
+fn should_run_step_and_return_bonded_validators() {
+    let accounts = {
+        let mut tmp: Vec<GenesisAccount> = DEFAULT_ACCOUNTS.clone();
+        let account_1 = GenesisAccount::new(
+            PublicKey::new(ACCOUNT_1_ADDR),
+            Motes::new(ACCOUNT_1_BALANCE.into()),
+            Motes::new(ACCOUNT_1_BOND.into()),
+        );
+        tmp.push(account_1);
+        tmp
+    };
+
+    let genesis_config = test_support::create_genesis_config(accounts);
+
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder.run_genesis(&genesis_config);
+
+    let step_response = builder.step(STEP_BLOCK_TIME, &[]);
+    assert!(step_response.has_success(), "{:?}", step_response);
+
+    let expected: HashMap<PublicKey, U512> = {
+        let mut tmp = HashMap::new();
+        tmp.insert(PublicKey::new(ACCOUNT_1_ADDR), U512::from(ACCOUNT_1_BOND));
+        tmp
+    };
+    assert_eq!(builder.get_bonded_validators()[0], expected);
+}
@@ -1,23 +1,31 @@
 use contract_ffi::{
     key::Key,
+    system_contracts::pos,
     value::{CLValue, ProtocolVersion, U512},
 };
-use engine_core::engine_state::{upgrade::ActivationPoint, Error};
+use engine_core::engine_state::{genesis::POS_CONFIG, upgrade::ActivationPoint, Error};
 use engine_grpc_server::engine_server::ipc::DeployCode;
-use engine_shared::{stored_value::StoredValue, transform::Transform};
-use engine_wasm_prep::wasm_costs::WasmCosts;
+use engine_shared::{additive_map::AdditiveMap, stored_value::StoredValue, transform::Transform};
+use engine_wasm_prep::{
+    host_function_costs::{HostFunctionCost, HostFunctionCosts},
+    wasm_costs::WasmCosts,
+};
 
 use crate::{
     support::test_support::{
         self, ExecuteRequestBuilder, InMemoryWasmTestBuilder, UpgradeRequestBuilder,
     },
-    test::{DEFAULT_ACCOUNT_ADDR, DEFAULT_GENESIS_CONFIG, DEFAULT_WASM_COSTS},
+    test::{DEFAULT_ACCOUNT_ADDR, DEFAULT_GENESIS_CONFIG, DEFAULT_POS_CONFIG, DEFAULT_WASM_COSTS},
 };
 
 const PROTOCOL_VERSION: ProtocolVersion = ProtocolVersion::V1_0_0;
 const DEFAULT_ACTIVATION_POINT: ActivationPoint = 1;
 const MODIFIED_SYSTEM_UPGRADER_CONTRACT_NAME: &str = "modified_system_upgrader.wasm";
 const MODIFIED_MINT_CALLER_CONTRACT_NAME: &str = "modified_mint_caller.wasm";
+const DO_NOTHING_CONTRACT_NAME: &str = "do_nothing.wasm";
+const POS_BONDING_CONTRACT_NAME: &str = "pos_bonding.wasm";
+const POS_BOND_COMMAND: &str = "bond";
+const POS_BOND_AMOUNT: u64 = 1_000;
 const PAYMENT_AMOUNT: u64 = 200_000_000;
 
 fn get_upgraded_wasm_costs() -> WasmCosts {
@@ -32,9 +40,18 @@ fn get_upgraded_wasm_costs() -> WasmCosts {
         max_stack_height: 64 * 1024,
         opcodes_mul: 3,
         opcodes_div: 8,
+        verify_ed25519: 1,
+        verify_secp256k1: 1,
+        hash_blake2b: 1,
+        hash_sha256: 1,
+        hash_keccak256: 1,
     }
 }
 
+fn get_upgraded_host_function_costs() -> HostFunctionCosts {
+    HostFunctionCosts::uniform(HostFunctionCost::new(10_000, 100))
+}
+
 #[ignore]
 #[test]
 fn should_upgrade_only_protocol_version() {
@@ -393,6 +410,319 @@ fn should_allow_only_wasm_costs_minor_version() {
     );
 }
 
+#[ignore]
+#[test]
+fn should_allow_only_host_function_costs_patch_version() {
+    let mut builder = InMemoryWasmTestBuilder::default();
+
+    builder.run_genesis(&*DEFAULT_GENESIS_CONFIG);
+
+    let sem_ver = PROTOCOL_VERSION.value();
+    let new_protocol_version =
+        ProtocolVersion::from_parts(sem_ver.major, sem_ver.minor, sem_ver.patch + 1);
+
+    let new_host_function_costs = get_upgraded_host_function_costs();
+
+    let mut upgrade_request = {
+        UpgradeRequestBuilder::new()
+            .with_current_protocol_version(PROTOCOL_VERSION)
+            .with_new_protocol_version(new_protocol_version)
+            .with_activation_point(DEFAULT_ACTIVATION_POINT)
+            .with_new_host_function_costs(new_host_function_costs)
+            .build()
+    };
+
+    builder.upgrade_with_upgrade_request(&mut upgrade_request);
+
+    let upgrade_response = builder
+        .get_upgrade_response(0)
+        .expect("should have response");
+
+    assert!(upgrade_response.has_success(), "expected success");
+
+    let engine_state = builder.get_engine_state();
+
+    let upgraded_host_function_costs = engine_state
+        .host_function_costs(new_protocol_version)
+        .expect("should have result")
+        .expect("should have upgraded costs");
+
+    assert_eq!(
+        new_host_function_costs, upgraded_host_function_costs,
+        "upgraded costs should equal new costs"
+    );
+
+    let upgraded_wasm_costs = engine_state
+        .wasm_costs(new_protocol_version)
+        .expect("should have result")
+        .expect("should have wasm costs");
+
+    assert_eq!(
+        *DEFAULT_WASM_COSTS, upgraded_wasm_costs,
+        "wasm costs should not change"
+    );
+}
+
+#[ignore]
+#[test]
+fn should_upgrade_pos_config_without_replacing_contract() {
+    let mut builder = InMemoryWasmTestBuilder::default();
+
+    builder.run_genesis(&*DEFAULT_GENESIS_CONFIG);
+
+    assert_eq!(builder.get_pos_config(), *DEFAULT_POS_CONFIG);
+    let pos_contract_uref = builder.get_pos_contract_uref();
+
+    let sem_ver = PROTOCOL_VERSION.value();
+    let new_protocol_version =
+        ProtocolVersion::from_parts(sem_ver.major, sem_ver.minor, sem_ver.patch + 1);
+
+    // Disallow bonding requests as large as the one below.
+    let new_pos_config = pos::Config {
+        max_increase: U512::from(POS_BOND_AMOUNT - 1),
+        ..*DEFAULT_POS_CONFIG
+    };
+
+    let mut upgrade_request = {
+        UpgradeRequestBuilder::new()
+            .with_current_protocol_version(PROTOCOL_VERSION)
+            .with_new_protocol_version(new_protocol_version)
+            .with_activation_point(DEFAULT_ACTIVATION_POINT)
+            .with_new_pos_config(new_pos_config)
+            .build()
+    };
+
+    builder.upgrade_with_upgrade_request(&mut upgrade_request);
+
+    let upgrade_response = builder
+        .get_upgrade_response(0)
+        .expect("should have response");
+
+    assert!(upgrade_response.has_success(), "expected success");
+    assert_eq!(builder.get_pos_config(), new_pos_config);
+    assert_eq!(
+        builder.get_pos_contract_uref(),
+        pos_contract_uref,
+        "pos contract should not be replaced"
+    );
+
+    let exec_request = ExecuteRequestBuilder::standard(
+        DEFAULT_ACCOUNT_ADDR,
+        POS_BONDING_CONTRACT_NAME,
+        (String::from(POS_BOND_COMMAND), U512::from(POS_BOND_AMOUNT)),
+    )
+    .with_protocol_version(new_protocol_version)
+    .build();
+
+    builder.exec(exec_request).commit();
+
+    assert!(
+        builder.is_error(),
+        "bonding should fail with the new config"
+    );
+}
+
+#[ignore]
+#[test]
+fn should_create_missing_pos_config_key_on_upgrade() {
+    let mut builder = InMemoryWasmTestBuilder::default();
+
+    builder.run_genesis(&*DEFAULT_GENESIS_CONFIG);
+
+    // Simulate a chain whose PoS was installed before it stored its config.
+    let pos_contract_uref = builder.get_pos_contract_uref();
+    let mut pos_contract = builder.get_pos_contract();
+    pos_contract.named_keys_mut().remove(POS_CONFIG);
+    let mut effects = AdditiveMap::new();
+    effects.insert(
+        Key::from(pos_contract_uref).normalize(),
+        Transform::Write(StoredValue::Contract(pos_contract)),
+    );
+    let post_state_hash = builder.get_post_state_hash();
+    builder.commit_effects(post_state_hash, effects);
+    assert!(!builder
+        .get_pos_contract()
+        .named_keys()
+        .contains_key(POS_CONFIG));
+
+    let sem_ver = PROTOCOL_VERSION.value();
+    let new_protocol_version =
+        ProtocolVersion::from_parts(sem_ver.major, sem_ver.minor, sem_ver.patch + 1);
+
+    let new_pos_config = pos::Config {
+        unbond_delay: DEFAULT_POS_CONFIG.unbond_delay + 1,
+        ..*DEFAULT_POS_CONFIG
+    };
+
+    let mut upgrade_request = {
+        UpgradeRequestBuilder::new()
+            .with_current_protocol_version(PROTOCOL_VERSION)
+            .with_new_protocol_version(new_protocol_version)
+            .with_activation_point(DEFAULT_ACTIVATION_POINT)
+            .with_new_pos_config(new_pos_config)
+            .build()
+    };
+
+    builder.upgrade_with_upgrade_request(&mut upgrade_request);
+
+    let upgrade_response = builder
+        .get_upgrade_response(0)
+        .expect("should have response");
+
+    assert!(upgrade_response.has_success(), "expected success");
+    assert_eq!(builder.get_pos_config(), new_pos_config);
+    assert_eq!(
+        builder.get_pos_contract_uref(),
+        pos_contract_uref,
+        "pos contract should not be replaced"
+    );
+}
+
+#[ignore]
+#[test]
+fn should_fail_upgrade_if_pos_config_key_is_not_a_uref() {
+    let mut builder = InMemoryWasmTestBuilder::default();
+
+    builder.run_genesis(&*DEFAULT_GENESIS_CONFIG);
+
+    let pos_contract_uref = builder.get_pos_contract_uref();
+    let mut pos_contract = builder.get_pos_contract();
+    pos_contract
+        .named_keys_mut()
+        .insert(POS_CONFIG.to_string(), Key::Hash([1u8; 32]));
+    let mut effects = AdditiveMap::new();
+    effects.insert(
+        Key::from(pos_contract_uref).normalize(),
+        Transform::Write(StoredValue::Contract(pos_contract)),
+    );
+    let post_state_hash = builder.get_post_state_hash();
+    builder.commit_effects(post_state_hash, effects);
+
+    let sem_ver = PROTOCOL_VERSION.value();
+    let new_protocol_version =
+        ProtocolVersion::from_parts(sem_ver.major, sem_ver.minor, sem_ver.patch + 1);
+
+    let mut upgrade_request = {
+        UpgradeRequestBuilder::new()
+            .with_current_protocol_version(PROTOCOL_VERSION)
+            .with_new_protocol_version(new_protocol_version)
+            .with_activation_point(DEFAULT_ACTIVATION_POINT)
+            .with_new_pos_config(*DEFAULT_POS_CONFIG)
+            .build()
+    };
+
+    builder.upgrade_with_upgrade_request(&mut upgrade_request);
+
+    let upgrade_response = builder
+        .get_upgrade_response(0)
+        .expect("should have response");
+
+    assert!(
+        upgrade_response.has_failed_deploy(),
+        "should have failed deploy"
+    );
+    assert_eq!(
+        upgrade_response.get_failed_deploy().message,
+        Error::UnexpectedSystemContractKeyType(POS_CONFIG.to_string()).to_string()
+    );
+}
+
+#[ignore]
+#[test]
+fn should_fail_upgrade_with_zero_pos_config_limit() {
+    let mut builder = InMemoryWasmTestBuilder::default();
+
+    builder.run_genesis(&*DEFAULT_GENESIS_CONFIG);
+
+    let sem_ver = PROTOCOL_VERSION.value();
+    let new_protocol_version =
+        ProtocolVersion::from_parts(sem_ver.major, sem_ver.minor, sem_ver.patch + 1);
+
+    let new_pos_config = pos::Config {
+        max_bond_len: 0,
+        ..*DEFAULT_POS_CONFIG
+    };
+
+    let mut upgrade_request = {
+        UpgradeRequestBuilder::new()
+            .with_current_protocol_version(PROTOCOL_VERSION)
+            .with_new_protocol_version(new_protocol_version)
+            .with_activation_point(DEFAULT_ACTIVATION_POINT)
+            .with_new_pos_config(new_pos_config)
+            .build()
+    };
+
+    builder.upgrade_with_upgrade_request(&mut upgrade_request);
+
+    let upgrade_response = builder
+        .get_upgrade_response(0)
+        .expect("should have response");
+
+    assert!(
+        upgrade_response.has_failed_deploy(),
+        "should have failed deploy"
+    );
+    assert_eq!(
+        upgrade_response.get_failed_deploy().message,
+        Error::InvalidProofOfStakeConfig("max_bond_len".to_string()).to_string()
+    );
+    assert!(
+        builder
+            .get_engine_state()
+            .wasm_costs(new_protocol_version)
+            .expect("should have result")
+            .is_none(),
+        "should not store costs for the new protocol version"
+    );
+    assert_eq!(builder.get_pos_config(), *DEFAULT_POS_CONFIG);
+}
+
+#[ignore]
+#[test]
+fn should_charge_upgraded_host_function_costs() {
+    let mut builder = InMemoryWasmTestBuilder::default();
+
+    builder.run_genesis(&*DEFAULT_GENESIS_CONFIG);
+
+    let exec_request =
+        ExecuteRequestBuilder::standard(DEFAULT_ACCOUNT_ADDR, DO_NOTHING_CONTRACT_NAME, ()).build();
+
+    builder.exec(exec_request).expect_success().commit();
+
+    let sem_ver = PROTOCOL_VERSION.value();
+    let new_protocol_version =
+        ProtocolVersion::from_parts(sem_ver.major, sem_ver.minor, sem_ver.patch + 1);
+
+    let mut upgrade_request = {
+        UpgradeRequestBuilder::new()
+            .with_current_protocol_version(PROTOCOL_VERSION)
+            .with_new_protocol_version(new_protocol_version)
+            .with_activation_point(DEFAULT_ACTIVATION_POINT)
+            .with_new_host_function_costs(get_upgraded_host_function_costs())
+            .build()
+    };
+
+    builder.upgrade_with_upgrade_request(&mut upgrade_request);
+
+    let exec_request =
+        ExecuteRequestBuilder::standard(DEFAULT_ACCOUNT_ADDR, DO_NOTHING_CONTRACT_NAME, ())
+            .with_protocol_version(new_protocol_version)
+            .build();
+
+    builder.exec(exec_request).expect_success().commit();
+
+    let cost_before = builder.exec_costs(0)[0];
+    let cost_after = builder.exec_costs(1)[0];
+
+    // Standard payment calls host functions, so raising their costs raises the cost of the deploy.
+    assert!(
+        cost_after > cost_before,
+        "expected {} to be greater than {}",
+        cost_after,
+        cost_before
+    );
+}
+
 #[ignore]
 #[test]
 fn should_upgrade_system_contract_and_wasm_costs_major() {
@@ -631,3 +961,93 @@ fn should_fail_major_upgrade_without_installer() {
         Error::InvalidUpgradeConfig.to_string()
     );
 }
+
+#[ignore]
+#[test]
+fn should_fail_upgrade_with_zero_crypto_costs() {
+    let mut builder = InMemoryWasmTestBuilder::default();
+
+    builder.run_genesis(&*DEFAULT_GENESIS_CONFIG);
+
+    let sem_ver = PROTOCOL_VERSION.value();
+    let new_protocol_version =
+        ProtocolVersion::from_parts(sem_ver.major, sem_ver.minor, sem_ver.patch + 1);
+
+    let new_costs = WasmCosts {
+        verify_ed25519: 0,
+        ..get_upgraded_wasm_costs()
+    };
+
+    let mut upgrade_request = {
+        UpgradeRequestBuilder::new()
+            .with_current_protocol_version(PROTOCOL_VERSION)
+            .with_new_protocol_version(new_protocol_version)
+            .with_activation_point(DEFAULT_ACTIVATION_POINT)
+            .with_new_costs(new_costs)
+            .build()
+    };
+
+    builder.upgrade_with_upgrade_request(&mut upgrade_request);
+
+    let upgrade_response = builder
+        .get_upgrade_response(0)
+        .expect("should have response");
+
+    assert!(
+        upgrade_response.has_failed_deploy(),
+        "should have failed deploy"
+    );
+    assert_eq!(
+        upgrade_response.get_failed_deploy().message,
+        Error::ZeroCost("verify_ed25519".to_string()).to_string()
+    );
+    assert!(
+        builder
+            .get_engine_state()
+            .wasm_costs(new_protocol_version)
+            .expect("should have result")
+            .is_none(),
+        "should not store costs for the new protocol version"
+    );
+}
+
+#[ignore]
+#[test]
+fn should_fail_upgrade_with_zero_host_function_costs() {
+    let mut builder = InMemoryWasmTestBuilder::default();
+
+    builder.run_genesis(&*DEFAULT_GENESIS_CONFIG);
+
+    let sem_ver = PROTOCOL_VERSION.value();
+    let new_protocol_version =
+        ProtocolVersion::from_parts(sem_ver.major, sem_ver.minor, sem_ver.patch + 1);
+
+    let new_host_function_costs = HostFunctionCosts {
+        verify_signature: HostFunctionCost::new(0, 100),
+        ..get_upgraded_host_function_costs()
+    };
+
+    let mut upgrade_request = {
+        UpgradeRequestBuilder::new()
+            .with_current_protocol_version(PROTOCOL_VERSION)
+            .with_new_protocol_version(new_protocol_version)
+            .with_activation_point(DEFAULT_ACTIVATION_POINT)
+            .with_new_host_function_costs(new_host_function_costs)
+            .build()
+    };
+
+    builder.upgrade_with_upgrade_request(&mut upgrade_request);
+
+    let upgrade_response = builder
+        .get_upgrade_response(0)
+        .expect("should have response");
+
+    assert!(
+        upgrade_response.has_failed_deploy(),
+        "should have failed deploy"
+    );
+    assert_eq!(
+        upgrade_response.get_failed_deploy().message,
+        Error::ZeroCost("verify_signature host function".to_string()).to_string()
+    );
+}
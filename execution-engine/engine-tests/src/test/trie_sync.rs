@@ -0,0 +1,129 @@
+use contract_ffi::key::Key;
+
+use crate::{
+    support::test_support::{ExecuteRequestBuilder, InMemoryWasmTestBuilder},
+    test::{DEFAULT_ACCOUNT_ADDR, DEFAULT_GENESIS_CONFIG},
+};
+
+const CONTRACT_TRANSFER_TO_ACCOUNT_01: &str = "transfer_to_account_01.wasm";
+const ACCOUNT_1_ADDR: [u8; 32] = [1u8; 32];
+const MISSING_TRIE_KEYS_LIMIT: u32 = 5;
+
+fn setup() -> InMemoryWasmTestBuilder {
+    let exec_request = ExecuteRequestBuilder::standard(
+        DEFAULT_ACCOUNT_ADDR,
+        CONTRACT_TRANSFER_TO_ACCOUNT_01,
+        (ACCOUNT_1_ADDR,),
+    )
+    .build();
+
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder
+        .run_genesis(&DEFAULT_GENESIS_CONFIG)
+        .exec(exec_request)
+        .expect_success()
+        .commit();
+    builder
+}
+
+#[ignore]
+#[test]
+fn should_sync_post_state_to_empty_engine() {
+    let source = setup();
+    let post_state_hash = source.get_post_state_hash();
+
+    // Each round only walks the elements stored in the previous round, plus whatever was left
+    // unexplored when the limit was reached.
+    let target = InMemoryWasmTestBuilder::default();
+    let mut frontier = vec![post_state_hash.clone()];
+    while !frontier.is_empty() {
+        let (missing, unexplored) = target.missing_trie_keys(frontier, MISSING_TRIE_KEYS_LIMIT);
+        assert!(missing.len() <= MISSING_TRIE_KEYS_LIMIT as usize);
+        let entries: Vec<(Vec<u8>, Vec<u8>)> = missing
+            .iter()
+            .map(|trie_key| {
+                let trie = source
+                    .get_trie(trie_key.clone())
+                    .expect("source should have trie element");
+                (trie_key.clone(), trie)
+            })
+            .collect();
+        let stored_keys = target.put_tries(entries).expect("should put trie elements");
+        assert_eq!(stored_keys, missing);
+        frontier = missing;
+        frontier.extend(unexplored);
+    }
+
+    let (missing, unexplored) = target.missing_trie_keys(vec![post_state_hash.clone()], 0);
+    assert!(missing.is_empty());
+    assert!(unexplored.is_empty());
+
+    for key in &[
+        Key::Account(DEFAULT_ACCOUNT_ADDR),
+        Key::Account(ACCOUNT_1_ADDR),
+    ] {
+        let expected = source.query(Some(post_state_hash.clone()), *key, &[]);
+        assert!(expected.is_some());
+        assert_eq!(
+            target.query(Some(post_state_hash.clone()), *key, &[]),
+            expected
+        );
+    }
+}
+
+#[ignore]
+#[test]
+fn should_only_list_trie_elements_not_yet_stored() {
+    let source = setup();
+    let post_state_hash = source.get_post_state_hash();
+
+    let target = InMemoryWasmTestBuilder::default();
+    let root = source
+        .get_trie(post_state_hash.clone())
+        .expect("source should have state root");
+    target
+        .put_tries(vec![(post_state_hash.clone(), root)])
+        .expect("should put state root");
+
+    // Only the children of the state root are missing, not the state root itself.
+    let (missing, _) = target.missing_trie_keys(vec![post_state_hash.clone()], 0);
+    assert!(!missing.is_empty());
+    assert!(!missing.contains(&post_state_hash));
+}
+
+#[ignore]
+#[test]
+fn should_reject_trie_element_with_wrong_hash() {
+    let source = setup();
+    let genesis_hash = source.get_genesis_hash();
+    let post_state_hash = source.get_post_state_hash();
+
+    let target = InMemoryWasmTestBuilder::default();
+    let root = source
+        .get_trie(post_state_hash.clone())
+        .expect("source should have state root");
+    assert!(target
+        .put_tries(vec![(genesis_hash.clone(), root.clone())])
+        .is_err());
+
+    // A batch is rejected as a whole, so the valid element is not stored either.
+    let genesis_root = source
+        .get_trie(genesis_hash.clone())
+        .expect("source should have genesis state root");
+    assert!(target
+        .put_tries(vec![
+            (genesis_hash.clone(), genesis_root),
+            (genesis_hash.clone(), root),
+        ])
+        .is_err());
+
+    let (missing, _) = target.missing_trie_keys(vec![genesis_hash.clone()], 0);
+    assert_eq!(missing, vec![genesis_hash]);
+}
+
+#[ignore]
+#[test]
+fn should_not_get_unknown_trie_element() {
+    let builder = setup();
+    assert_eq!(builder.get_trie(vec![0u8; 32]), None);
+}
@@ -0,0 +1,439 @@
+use contract_ffi::bytesrepr::{self, FromBytes, ToBytes, U32_SERIALIZED_LENGTH};
+
+const HOST_FUNCTION_COST_SERIALIZED_LENGTH: usize = 2 * U32_SERIALIZED_LENGTH;
+const NUM_HOST_FUNCTIONS: usize = 39;
+pub const HOST_FUNCTION_COSTS_SERIALIZED_LENGTH: usize =
+    NUM_HOST_FUNCTIONS * HOST_FUNCTION_COST_SERIALIZED_LENGTH;
+
+/// Cost of every host function the node leaves unset, and of every host function after upgrading
+/// from protocol data stored before host functions were charged.
+pub const DEFAULT_HOST_FUNCTION_COST: HostFunctionCost = HostFunctionCost {
+    base: 10,
+    per_byte: 1,
+};
+
+/// Cost of a single call to a host function.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct HostFunctionCost {
+    /// Cost charged for every call
+    pub base: u32,
+    /// Cost per byte of the buffers passed to the call, and of the value read into the host buffer
+    /// by `read_value` and `read_value_local`
+    pub per_byte: u32,
+}
+
+impl HostFunctionCost {
+    pub fn new(base: u32, per_byte: u32) -> Self {
+        HostFunctionCost { base, per_byte }
+    }
+}
+
+impl ToBytes for HostFunctionCost {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut ret: Vec<u8> = Vec::with_capacity(HOST_FUNCTION_COST_SERIALIZED_LENGTH);
+        ret.append(&mut self.base.to_bytes()?);
+        ret.append(&mut self.per_byte.to_bytes()?);
+        Ok(ret)
+    }
+}
+
+impl FromBytes for HostFunctionCost {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (base, rem): (u32, &[u8]) = FromBytes::from_bytes(bytes)?;
+        let (per_byte, rem): (u32, &[u8]) = FromBytes::from_bytes(rem)?;
+        Ok((HostFunctionCost { base, per_byte }, rem))
+    }
+}
+
+/// Costs of calling each of the host functions exposed to contracts.
+///
+/// Fields are named after the host functions they apply to.  The `gas` host function, which is
+/// injected into contracts to meter Wasm opcodes, is not charged.  Only the `base` cost applies to
+/// `emit_event`, `verify_signature` and `hash`, whose per-byte costs are set by
+/// [`WasmCosts`](crate::wasm_costs::WasmCosts).
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct HostFunctionCosts {
+    pub read_value: HostFunctionCost,
+    pub read_value_local: HostFunctionCost,
+    pub load_named_keys: HostFunctionCost,
+    pub write: HostFunctionCost,
+    pub write_local: HostFunctionCost,
+    pub add: HostFunctionCost,
+    pub add_local: HostFunctionCost,
+    pub new_uref: HostFunctionCost,
+    pub get_arg_size: HostFunctionCost,
+    pub get_arg: HostFunctionCost,
+    pub ret: HostFunctionCost,
+    pub call_contract: HostFunctionCost,
+    pub get_key: HostFunctionCost,
+    pub has_key: HostFunctionCost,
+    pub put_key: HostFunctionCost,
+    pub store_function: HostFunctionCost,
+    pub store_function_at_hash: HostFunctionCost,
+    pub is_valid_uref: HostFunctionCost,
+    pub revert: HostFunctionCost,
+    pub add_associated_key: HostFunctionCost,
+    pub remove_associated_key: HostFunctionCost,
+    pub update_associated_key: HostFunctionCost,
+    pub set_action_threshold: HostFunctionCost,
+    pub remove_key: HostFunctionCost,
+    pub get_caller: HostFunctionCost,
+    pub get_blocktime: HostFunctionCost,
+    pub create_purse: HostFunctionCost,
+    pub transfer_to_account: HostFunctionCost,
+    pub transfer_from_purse_to_account: HostFunctionCost,
+    pub transfer_from_purse_to_purse: HostFunctionCost,
+    pub get_balance: HostFunctionCost,
+    pub get_phase: HostFunctionCost,
+    pub upgrade_contract_at_uref: HostFunctionCost,
+    pub get_system_contract: HostFunctionCost,
+    pub get_main_purse: HostFunctionCost,
+    pub read_host_buffer: HostFunctionCost,
+    pub emit_event: HostFunctionCost,
+    pub verify_signature: HostFunctionCost,
+    pub hash: HostFunctionCost,
+}
+
+impl HostFunctionCosts {
+    /// Returns a table which charges `cost` for every host function.
+    pub fn uniform(cost: HostFunctionCost) -> Self {
+        HostFunctionCosts {
+            read_value: cost,
+            read_value_local: cost,
+            load_named_keys: cost,
+            write: cost,
+            write_local: cost,
+            add: cost,
+            add_local: cost,
+            new_uref: cost,
+            get_arg_size: cost,
+            get_arg: cost,
+            ret: cost,
+            call_contract: cost,
+            get_key: cost,
+            has_key: cost,
+            put_key: cost,
+            store_function: cost,
+            store_function_at_hash: cost,
+            is_valid_uref: cost,
+            revert: cost,
+            add_associated_key: cost,
+            remove_associated_key: cost,
+            update_associated_key: cost,
+            set_action_threshold: cost,
+            remove_key: cost,
+            get_caller: cost,
+            get_blocktime: cost,
+            create_purse: cost,
+            transfer_to_account: cost,
+            transfer_from_purse_to_account: cost,
+            transfer_from_purse_to_purse: cost,
+            get_balance: cost,
+            get_phase: cost,
+            upgrade_contract_at_uref: cost,
+            get_system_contract: cost,
+            get_main_purse: cost,
+            read_host_buffer: cost,
+            emit_event: cost,
+            verify_signature: cost,
+            hash: cost,
+        }
+    }
+
+    /// Returns the name of the first host function whose `base` cost is zero, if any.
+    ///
+    /// A cost table which omits host function costs decodes with every cost at zero, which would
+    /// make all host calls free, so such tables must be rejected.
+    pub fn zero_cost(&self) -> Option<&'static str> {
+        let costs = [
+            ("read_value", self.read_value),
+            ("read_value_local", self.read_value_local),
+            ("load_named_keys", self.load_named_keys),
+            ("write", self.write),
+            ("write_local", self.write_local),
+            ("add", self.add),
+            ("add_local", self.add_local),
+            ("new_uref", self.new_uref),
+            ("get_arg_size", self.get_arg_size),
+            ("get_arg", self.get_arg),
+            ("ret", self.ret),
+            ("call_contract", self.call_contract),
+            ("get_key", self.get_key),
+            ("has_key", self.has_key),
+            ("put_key", self.put_key),
+            ("store_function", self.store_function),
+            ("store_function_at_hash", self.store_function_at_hash),
+            ("is_valid_uref", self.is_valid_uref),
+            ("revert", self.revert),
+            ("add_associated_key", self.add_associated_key),
+            ("remove_associated_key", self.remove_associated_key),
+            ("update_associated_key", self.update_associated_key),
+            ("set_action_threshold", self.set_action_threshold),
+            ("remove_key", self.remove_key),
+            ("get_caller", self.get_caller),
+            ("get_blocktime", self.get_blocktime),
+            ("create_purse", self.create_purse),
+            ("transfer_to_account", self.transfer_to_account),
+            (
+                "transfer_from_purse_to_account",
+                self.transfer_from_purse_to_account,
+            ),
+            (
+                "transfer_from_purse_to_purse",
+                self.transfer_from_purse_to_purse,
+            ),
+            ("get_balance", self.get_balance),
+            ("get_phase", self.get_phase),
+            ("upgrade_contract_at_uref", self.upgrade_contract_at_uref),
+            ("get_system_contract", self.get_system_contract),
+            ("get_main_purse", self.get_main_purse),
+            ("read_host_buffer", self.read_host_buffer),
+            ("emit_event", self.emit_event),
+            ("verify_signature", self.verify_signature),
+            ("hash", self.hash),
+        ];
+        costs
+            .iter()
+            .find(|(_, cost)| cost.base == 0)
+            .map(|(name, _)| *name)
+    }
+}
+
+impl ToBytes for HostFunctionCosts {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut ret: Vec<u8> = Vec::with_capacity(HOST_FUNCTION_COSTS_SERIALIZED_LENGTH);
+        ret.append(&mut self.read_value.to_bytes()?);
+        ret.append(&mut self.read_value_local.to_bytes()?);
+        ret.append(&mut self.load_named_keys.to_bytes()?);
+        ret.append(&mut self.write.to_bytes()?);
+        ret.append(&mut self.write_local.to_bytes()?);
+        ret.append(&mut self.add.to_bytes()?);
+        ret.append(&mut self.add_local.to_bytes()?);
+        ret.append(&mut self.new_uref.to_bytes()?);
+        ret.append(&mut self.get_arg_size.to_bytes()?);
+        ret.append(&mut self.get_arg.to_bytes()?);
+        ret.append(&mut self.ret.to_bytes()?);
+        ret.append(&mut self.call_contract.to_bytes()?);
+        ret.append(&mut self.get_key.to_bytes()?);
+        ret.append(&mut self.has_key.to_bytes()?);
+        ret.append(&mut self.put_key.to_bytes()?);
+        ret.append(&mut self.store_function.to_bytes()?);
+        ret.append(&mut self.store_function_at_hash.to_bytes()?);
+        ret.append(&mut self.is_valid_uref.to_bytes()?);
+        ret.append(&mut self.revert.to_bytes()?);
+        ret.append(&mut self.add_associated_key.to_bytes()?);
+        ret.append(&mut self.remove_associated_key.to_bytes()?);
+        ret.append(&mut self.update_associated_key.to_bytes()?);
+        ret.append(&mut self.set_action_threshold.to_bytes()?);
+        ret.append(&mut self.remove_key.to_bytes()?);
+        ret.append(&mut self.get_caller.to_bytes()?);
+        ret.append(&mut self.get_blocktime.to_bytes()?);
+        ret.append(&mut self.create_purse.to_bytes()?);
+        ret.append(&mut self.transfer_to_account.to_bytes()?);
+        ret.append(&mut self.transfer_from_purse_to_account.to_bytes()?);
+        ret.append(&mut self.transfer_from_purse_to_purse.to_bytes()?);
+        ret.append(&mut self.get_balance.to_bytes()?);
+        ret.append(&mut self.get_phase.to_bytes()?);
+        ret.append(&mut self.upgrade_contract_at_uref.to_bytes()?);
+        ret.append(&mut self.get_system_contract.to_bytes()?);
+        ret.append(&mut self.get_main_purse.to_bytes()?);
+        ret.append(&mut self.read_host_buffer.to_bytes()?);
+        ret.append(&mut self.emit_event.to_bytes()?);
+        ret.append(&mut self.verify_signature.to_bytes()?);
+        ret.append(&mut self.hash.to_bytes()?);
+        Ok(ret)
+    }
+}
+
+impl FromBytes for HostFunctionCosts {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (read_value, rem): (HostFunctionCost, &[u8]) = FromBytes::from_bytes(bytes)?;
+        let (read_value_local, rem): (HostFunctionCost, &[u8]) = FromBytes::from_bytes(rem)?;
+        let (load_named_keys, rem): (HostFunctionCost, &[u8]) = FromBytes::from_bytes(rem)?;
+        let (write, rem): (HostFunctionCost, &[u8]) = FromBytes::from_bytes(rem)?;
+        let (write_local, rem): (HostFunctionCost, &[u8]) = FromBytes::from_bytes(rem)?;
+        let (add, rem): (HostFunctionCost, &[u8]) = FromBytes::from_bytes(rem)?;
+        let (add_local, rem): (HostFunctionCost, &[u8]) = FromBytes::from_bytes(rem)?;
+        let (new_uref, rem): (HostFunctionCost, &[u8]) = FromBytes::from_bytes(rem)?;
+        let (get_arg_size, rem): (HostFunctionCost, &[u8]) = FromBytes::from_bytes(rem)?;
+        let (get_arg, rem): (HostFunctionCost, &[u8]) = FromBytes::from_bytes(rem)?;
+        let (ret, rem): (HostFunctionCost, &[u8]) = FromBytes::from_bytes(rem)?;
+        let (call_contract, rem): (HostFunctionCost, &[u8]) = FromBytes::from_bytes(rem)?;
+        let (get_key, rem): (HostFunctionCost, &[u8]) = FromBytes::from_bytes(rem)?;
+        let (has_key, rem): (HostFunctionCost, &[u8]) = FromBytes::from_bytes(rem)?;
+        let (put_key, rem): (HostFunctionCost, &[u8]) = FromBytes::from_bytes(rem)?;
+        let (store_function, rem): (HostFunctionCost, &[u8]) = FromBytes::from_bytes(rem)?;
+        let (store_function_at_hash, rem): (HostFunctionCost, &[u8]) = FromBytes::from_bytes(rem)?;
+        let (is_valid_uref, rem): (HostFunctionCost, &[u8]) = FromBytes::from_bytes(rem)?;
+        let (revert, rem): (HostFunctionCost, &[u8]) = FromBytes::from_bytes(rem)?;
+        let (add_associated_key, rem): (HostFunctionCost, &[u8]) = FromBytes::from_bytes(rem)?;
+        let (remove_associated_key, rem): (HostFunctionCost, &[u8]) = FromBytes::from_bytes(rem)?;
+        let (update_associated_key, rem): (HostFunctionCost, &[u8]) = FromBytes::from_bytes(rem)?;
+        let (set_action_threshold, rem): (HostFunctionCost, &[u8]) = FromBytes::from_bytes(rem)?;
+        let (remove_key, rem): (HostFunctionCost, &[u8]) = FromBytes::from_bytes(rem)?;
+        let (get_caller, rem): (HostFunctionCost, &[u8]) = FromBytes::from_bytes(rem)?;
+        let (get_blocktime, rem): (HostFunctionCost, &[u8]) = FromBytes::from_bytes(rem)?;
+        let (create_purse, rem): (HostFunctionCost, &[u8]) = FromBytes::from_bytes(rem)?;
+        let (transfer_to_account, rem): (HostFunctionCost, &[u8]) = FromBytes::from_bytes(rem)?;
+        let (transfer_from_purse_to_account, rem): (HostFunctionCost, &[u8]) =
+            FromBytes::from_bytes(rem)?;
+        let (transfer_from_purse_to_purse, rem): (HostFunctionCost, &[u8]) =
+            FromBytes::from_bytes(rem)?;
+        let (get_balance, rem): (HostFunctionCost, &[u8]) = FromBytes::from_bytes(rem)?;
+        let (get_phase, rem): (HostFunctionCost, &[u8]) = FromBytes::from_bytes(rem)?;
+        let (upgrade_contract_at_uref, rem): (HostFunctionCost, &[u8]) =
+            FromBytes::from_bytes(rem)?;
+        let (get_system_contract, rem): (HostFunctionCost, &[u8]) = FromBytes::from_bytes(rem)?;
+        let (get_main_purse, rem): (HostFunctionCost, &[u8]) = FromBytes::from_bytes(rem)?;
+        let (read_host_buffer, rem): (HostFunctionCost, &[u8]) = FromBytes::from_bytes(rem)?;
+        let (emit_event, rem): (HostFunctionCost, &[u8]) = FromBytes::from_bytes(rem)?;
+        let (verify_signature, rem): (HostFunctionCost, &[u8]) = FromBytes::from_bytes(rem)?;
+        let (hash, rem): (HostFunctionCost, &[u8]) = FromBytes::from_bytes(rem)?;
+        let host_function_costs = HostFunctionCosts {
+            read_value,
+            read_value_local,
+            load_named_keys,
+            write,
+            write_local,
+            add,
+            add_local,
+            new_uref,
+            get_arg_size,
+            get_arg,
+            ret,
+            call_contract,
+            get_key,
+            has_key,
+            put_key,
+            store_function,
+            store_function_at_hash,
+            is_valid_uref,
+            revert,
+            add_associated_key,
+            remove_associated_key,
+            update_associated_key,
+            set_action_threshold,
+            remove_key,
+            get_caller,
+            get_blocktime,
+            create_purse,
+            transfer_to_account,
+            transfer_from_purse_to_account,
+            transfer_from_purse_to_purse,
+            get_balance,
+            get_phase,
+            upgrade_contract_at_uref,
+            get_system_contract,
+            get_main_purse,
+            read_host_buffer,
+            emit_event,
+            verify_signature,
+            hash,
+        };
+        Ok((host_function_costs, rem))
+    }
+}
+
+pub mod gens {
+    use proptest::{collection, num, prop_compose};
+
+    use crate::host_function_costs::{HostFunctionCost, HostFunctionCosts, NUM_HOST_FUNCTIONS};
+
+    prop_compose! {
+        pub fn host_function_cost_arb()(
+            base in num::u32::ANY,
+            per_byte in num::u32::ANY,
+        ) -> HostFunctionCost {
+            HostFunctionCost { base, per_byte }
+        }
+    }
+
+    prop_compose! {
+        pub fn host_function_costs_arb()(
+            costs in collection::vec(host_function_cost_arb(), NUM_HOST_FUNCTIONS),
+        ) -> HostFunctionCosts {
+            let mut costs = costs.into_iter();
+            let mut next = || costs.next().expect("should have a cost for each host function");
+            HostFunctionCosts {
+                read_value: next(),
+                read_value_local: next(),
+                load_named_keys: next(),
+                write: next(),
+                write_local: next(),
+                add: next(),
+                add_local: next(),
+                new_uref: next(),
+                get_arg_size: next(),
+                get_arg: next(),
+                ret: next(),
+                call_contract: next(),
+                get_key: next(),
+                has_key: next(),
+                put_key: next(),
+                store_function: next(),
+                store_function_at_hash: next(),
+                is_valid_uref: next(),
+                revert: next(),
+                add_associated_key: next(),
+                remove_associated_key: next(),
+                update_associated_key: next(),
+                set_action_threshold: next(),
+                remove_key: next(),
+                get_caller: next(),
+                get_blocktime: next(),
+                create_purse: next(),
+                transfer_to_account: next(),
+                transfer_from_purse_to_account: next(),
+                transfer_from_purse_to_purse: next(),
+                get_balance: next(),
+                get_phase: next(),
+                upgrade_contract_at_uref: next(),
+                get_system_contract: next(),
+                get_main_purse: next(),
+                read_host_buffer: next(),
+                emit_event: next(),
+                verify_signature: next(),
+                hash: next(),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::proptest;
+
+    use contract_ffi::bytesrepr;
+    use engine_shared::test_utils;
+
+    use super::{gens, HostFunctionCost, HostFunctionCosts, DEFAULT_HOST_FUNCTION_COST};
+
+    #[test]
+    fn should_serialize_and_deserialize() {
+        let mock = test_utils::host_function_costs_mock();
+        let free = test_utils::host_function_costs_free();
+        bytesrepr::test_serialization_roundtrip(&mock);
+        bytesrepr::test_serialization_roundtrip(&free);
+    }
+
+    #[test]
+    fn should_find_zero_cost() {
+        let mut host_function_costs = HostFunctionCosts::uniform(DEFAULT_HOST_FUNCTION_COST);
+        assert_eq!(host_function_costs.zero_cost(), None);
+        host_function_costs.hash = HostFunctionCost::new(0, 1);
+        assert_eq!(host_function_costs.zero_cost(), Some("hash"));
+        assert_eq!(
+            test_utils::host_function_costs_free().zero_cost(),
+            Some("read_value")
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn should_serialize_and_deserialize_with_arbitrary_values(
+            host_function_costs in gens::host_function_costs_arb()
+        ) {
+            bytesrepr::test_serialization_roundtrip(&host_function_costs);
+        }
+    }
+}
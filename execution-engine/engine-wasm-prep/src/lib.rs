@@ -1,3 +1,4 @@
+pub mod host_function_costs;
 pub mod wasm_costs;
 
 use std::{
@@ -4,9 +4,21 @@ use pwasm_utils::rules::{InstructionType, Metering, Set};
 
 use contract_ffi::bytesrepr::{self, FromBytes, ToBytes, U32_SERIALIZED_LENGTH};
 
-const NUM_FIELDS: usize = 10;
+const NUM_FIELDS: usize = 15;
 pub const WASM_COSTS_SERIALIZED_LENGTH: usize = NUM_FIELDS * U32_SERIALIZED_LENGTH;
 
+/// Number of fields serialized before the costs of the cryptographic host functions were added.
+const NUM_LEGACY_FIELDS: usize = 10;
+pub const LEGACY_WASM_COSTS_SERIALIZED_LENGTH: usize = NUM_LEGACY_FIELDS * U32_SERIALIZED_LENGTH;
+
+/// Costs of the cryptographic host functions given to costs deserialized from the legacy layout,
+/// which predates them, and to cost tables from the node which leave them unset.
+pub const DEFAULT_VERIFY_ED25519_COST: u32 = 10_000;
+pub const DEFAULT_VERIFY_SECP256K1_COST: u32 = 15_000;
+pub const DEFAULT_HASH_BLAKE2B_COST: u32 = 2;
+pub const DEFAULT_HASH_SHA256_COST: u32 = 2;
+pub const DEFAULT_HASH_KECCAK256_COST: u32 = 3;
+
 // Taken (partially) from parity-ethereum
 #[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
 pub struct WasmCosts {
@@ -33,6 +45,16 @@ pub struct WasmCosts {
     /// Cost of wasm opcode is calculated as TABLE_ENTRY_COST * `opcodes_mul` /
     /// `opcodes_div`
     pub opcodes_div: u32,
+    /// Cost of verifying an Ed25519 signature
+    pub verify_ed25519: u32,
+    /// Cost of verifying a secp256k1 signature
+    pub verify_secp256k1: u32,
+    /// Blake2b-256 hashing cost, per byte
+    pub hash_blake2b: u32,
+    /// SHA2-256 hashing cost, per byte
+    pub hash_sha256: u32,
+    /// Keccak-256 hashing cost, per byte
+    pub hash_keccak256: u32,
 }
 
 impl WasmCosts {
@@ -49,6 +71,57 @@ impl WasmCosts {
             .with_grow_cost(self.grow_mem)
             .with_forbidden_floats()
     }
+
+    /// Returns the name of the first cost of a cryptographic host function which is zero, if any.
+    ///
+    /// Cost tables written before these costs existed leave them unset, which decodes as zero and
+    /// would make the host functions free to call, so such tables must be rejected.
+    pub fn zero_crypto_cost(&self) -> Option<&'static str> {
+        let costs = [
+            ("verify_ed25519", self.verify_ed25519),
+            ("verify_secp256k1", self.verify_secp256k1),
+            ("hash_blake2b", self.hash_blake2b),
+            ("hash_sha256", self.hash_sha256),
+            ("hash_keccak256", self.hash_keccak256),
+        ];
+        costs
+            .iter()
+            .find(|(_, cost)| *cost == 0)
+            .map(|(name, _)| *name)
+    }
+
+    /// Deserializes costs in the legacy layout, which lacks the costs of the cryptographic host
+    /// functions.  Those are set to their defaults.
+    pub fn from_legacy_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (regular, rem): (u32, &[u8]) = FromBytes::from_bytes(bytes)?;
+        let (div, rem): (u32, &[u8]) = FromBytes::from_bytes(rem)?;
+        let (mul, rem): (u32, &[u8]) = FromBytes::from_bytes(rem)?;
+        let (mem, rem): (u32, &[u8]) = FromBytes::from_bytes(rem)?;
+        let (initial_mem, rem): (u32, &[u8]) = FromBytes::from_bytes(rem)?;
+        let (grow_mem, rem): (u32, &[u8]) = FromBytes::from_bytes(rem)?;
+        let (memcpy, rem): (u32, &[u8]) = FromBytes::from_bytes(rem)?;
+        let (max_stack_height, rem): (u32, &[u8]) = FromBytes::from_bytes(rem)?;
+        let (opcodes_mul, rem): (u32, &[u8]) = FromBytes::from_bytes(rem)?;
+        let (opcodes_div, rem): (u32, &[u8]) = FromBytes::from_bytes(rem)?;
+        let wasm_costs = WasmCosts {
+            regular,
+            div,
+            mul,
+            mem,
+            initial_mem,
+            grow_mem,
+            memcpy,
+            max_stack_height,
+            opcodes_mul,
+            opcodes_div,
+            verify_ed25519: DEFAULT_VERIFY_ED25519_COST,
+            verify_secp256k1: DEFAULT_VERIFY_SECP256K1_COST,
+            hash_blake2b: DEFAULT_HASH_BLAKE2B_COST,
+            hash_sha256: DEFAULT_HASH_SHA256_COST,
+            hash_keccak256: DEFAULT_HASH_KECCAK256_COST,
+        };
+        Ok((wasm_costs, rem))
+    }
 }
 
 impl ToBytes for WasmCosts {
@@ -64,6 +137,11 @@ impl ToBytes for WasmCosts {
         ret.append(&mut self.max_stack_height.to_bytes()?);
         ret.append(&mut self.opcodes_mul.to_bytes()?);
         ret.append(&mut self.opcodes_div.to_bytes()?);
+        ret.append(&mut self.verify_ed25519.to_bytes()?);
+        ret.append(&mut self.verify_secp256k1.to_bytes()?);
+        ret.append(&mut self.hash_blake2b.to_bytes()?);
+        ret.append(&mut self.hash_sha256.to_bytes()?);
+        ret.append(&mut self.hash_keccak256.to_bytes()?);
         Ok(ret)
     }
 }
@@ -80,6 +158,11 @@ impl FromBytes for WasmCosts {
         let (max_stack_height, rem): (u32, &[u8]) = FromBytes::from_bytes(rem)?;
         let (opcodes_mul, rem): (u32, &[u8]) = FromBytes::from_bytes(rem)?;
         let (opcodes_div, rem): (u32, &[u8]) = FromBytes::from_bytes(rem)?;
+        let (verify_ed25519, rem): (u32, &[u8]) = FromBytes::from_bytes(rem)?;
+        let (verify_secp256k1, rem): (u32, &[u8]) = FromBytes::from_bytes(rem)?;
+        let (hash_blake2b, rem): (u32, &[u8]) = FromBytes::from_bytes(rem)?;
+        let (hash_sha256, rem): (u32, &[u8]) = FromBytes::from_bytes(rem)?;
+        let (hash_keccak256, rem): (u32, &[u8]) = FromBytes::from_bytes(rem)?;
         let wasm_costs = WasmCosts {
             regular,
             div,
@@ -91,6 +174,11 @@ impl FromBytes for WasmCosts {
             max_stack_height,
             opcodes_mul,
             opcodes_div,
+            verify_ed25519,
+            verify_secp256k1,
+            hash_blake2b,
+            hash_sha256,
+            hash_keccak256,
         };
         Ok((wasm_costs, rem))
     }
@@ -113,6 +201,11 @@ pub mod gens {
             max_stack_height in num::u32::ANY,
             opcodes_mul in num::u32::ANY,
             opcodes_div in num::u32::ANY,
+            verify_ed25519 in 1..=u32::max_value(),
+            verify_secp256k1 in 1..=u32::max_value(),
+            hash_blake2b in 1..=u32::max_value(),
+            hash_sha256 in 1..=u32::max_value(),
+            hash_keccak256 in 1..=u32::max_value(),
         ) -> WasmCosts {
             WasmCosts {
                 regular,
@@ -125,6 +218,11 @@ pub mod gens {
                 max_stack_height,
                 opcodes_mul,
                 opcodes_div,
+                verify_ed25519,
+                verify_secp256k1,
+                hash_blake2b,
+                hash_sha256,
+                hash_keccak256,
             }
         }
     }
@@ -134,10 +232,10 @@ pub mod gens {
 mod tests {
     use proptest::proptest;
 
-    use contract_ffi::bytesrepr;
+    use contract_ffi::bytesrepr::{self, ToBytes};
     use engine_shared::test_utils;
 
-    use super::gens;
+    use super::{gens, WasmCosts, LEGACY_WASM_COSTS_SERIALIZED_LENGTH};
 
     #[test]
     fn should_serialize_and_deserialize() {
@@ -147,6 +245,30 @@ mod tests {
         bytesrepr::test_serialization_roundtrip(&free);
     }
 
+    #[test]
+    fn should_deserialize_legacy_layout_with_default_crypto_costs() {
+        let mock = test_utils::wasm_costs_mock();
+        let bytes = mock.to_bytes().unwrap();
+        let (legacy, rem) =
+            WasmCosts::from_legacy_bytes(&bytes[..LEGACY_WASM_COSTS_SERIALIZED_LENGTH]).unwrap();
+        assert!(rem.is_empty());
+        assert_eq!(legacy.regular, mock.regular);
+        assert_eq!(legacy.opcodes_div, mock.opcodes_div);
+        assert_eq!(legacy.zero_crypto_cost(), None);
+    }
+
+    #[test]
+    fn should_find_zero_crypto_cost() {
+        let mut wasm_costs = test_utils::wasm_costs_mock();
+        assert_eq!(wasm_costs.zero_crypto_cost(), None);
+        wasm_costs.hash_sha256 = 0;
+        assert_eq!(wasm_costs.zero_crypto_cost(), Some("hash_sha256"));
+        assert_eq!(
+            test_utils::wasm_costs_free().zero_crypto_cost(),
+            Some("verify_ed25519")
+        );
+    }
+
     proptest! {
         #[test]
         fn should_serialize_and_deserialize_with_arbitrary_values(